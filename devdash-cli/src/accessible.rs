@@ -0,0 +1,105 @@
+// devdash-cli/src/accessible.rs
+//! Plain-text output for screen readers (`--accessible`).
+//!
+//! Instead of drawing the dashboard, each widget is described in one line
+//! of text. Lines are printed when they change, at most once per
+//! [`ANNOUNCE_INTERVAL`], so a screen reader isn't flooded with updates.
+use std::time::Duration;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use devdash_core::DevdashError;
+
+use crate::app::App;
+
+/// How often changed summaries are printed
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Width used for widgets summarized from their rendered text when the
+/// terminal size is unknown
+const FALLBACK_WIDTH: u16 = 80;
+
+/// Remembers the last printed line of each widget
+#[derive(Debug, Default)]
+pub struct Announcer {
+    last: Vec<String>,
+}
+
+impl Announcer {
+    /// The lines that differ from the previous call
+    ///
+    /// Everything counts as changed when the number of widgets changes.
+    pub fn changes(&mut self, lines: Vec<String>) -> Vec<String> {
+        let changed = if lines.len() == self.last.len() {
+            lines
+                .iter()
+                .zip(&self.last)
+                .filter(|(new, old)| new != old)
+                .map(|(new, _)| new.clone())
+                .collect()
+        } else {
+            lines.clone()
+        };
+        self.last = lines;
+        changed
+    }
+}
+
+/// Keep widgets updated and print their summaries until Ctrl+C
+pub async fn run(app: &mut App) -> Result<(), DevdashError> {
+    let width = crossterm::terminal::size().map_or(FALLBACK_WIDTH, |(w, _)| w);
+    let mut announcer = Announcer::default();
+    let mut announce = tokio::time::interval(ANNOUNCE_INTERVAL);
+    announce.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        let deadline = app
+            .next_update()
+            .map_or_else(|| Instant::now() + ANNOUNCE_INTERVAL, Instant::from_std);
+
+        tokio::select! {
+            result = &mut ctrl_c => return result.map_err(DevdashError::from),
+            () = tokio::time::sleep_until(deadline) => {
+                app.update_due(std::time::Instant::now());
+            }
+            _ = announce.tick() => {
+                app.check_plugins();
+                for line in announcer.changes(app.summaries(width)) {
+                    println!("{}", line);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_only_changed_lines_are_announced() {
+        let mut announcer = Announcer::default();
+        assert_eq!(
+            announcer.changes(lines(&["CPU 5.0% used", "Git: clean"])),
+            lines(&["CPU 5.0% used", "Git: clean"])
+        );
+        assert!(
+            announcer
+                .changes(lines(&["CPU 5.0% used", "Git: clean"]))
+                .is_empty()
+        );
+        assert_eq!(
+            announcer.changes(lines(&["CPU 7.5% used", "Git: clean"])),
+            lines(&["CPU 7.5% used"])
+        );
+        assert_eq!(
+            announcer.changes(lines(&["CPU 7.5% used"])),
+            lines(&["CPU 7.5% used"])
+        );
+    }
+}
@@ -0,0 +1,981 @@
+// devdash-cli/src/alerts.rs
+//! Critical alerts raised on the event bus, their history, and the banner
+//! and toasts shown over the dashboard when one fires.
+use crossbeam::channel::Receiver;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget as _},
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime},
+};
+
+use devdash_core::{BusEvent, EventBus, event::Subscription};
+use devdash_widgets::{
+    BackupStatus, DevCrash, DiskProjection, DiskUsageMetrics, EndpointHealth, Freshness,
+    HealthIssue, JobResult, MemoryMetrics, ProcessInfo, QuotaUsage, ScheduledJob, Session,
+    SourceActivity, Temperature, common::format_timestamp,
+};
+
+use crate::{
+    notify::{self, NotificationConfig},
+    overlay::{Overlay, z},
+    rules::{AlertRule, AlertRules},
+};
+
+/// Topics that raise alerts
+const ALERT_TOPICS: [&str; 15] = [
+    "system.memory.pressure",
+    "system.memory.thrashing",
+    "system.disk.full",
+    "system.disk.projection",
+    "system.network.quota",
+    "process.zombie",
+    "process.fd.pressure",
+    "system.login.remote",
+    "system.firewall.burst",
+    "system.job.failed",
+    "system.backup.stale",
+    "system.health.issue",
+    "system.dev.crashed",
+    "system.temperature.critical",
+    "system.http.down",
+];
+
+/// How long a dismissed alert stays off the banner while it keeps firing
+const DISMISS_QUIET: Duration = Duration::from_secs(5 * 60);
+
+/// How long a toast stays up
+pub const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Toasts shown at once; a new one pushes out the oldest
+const MAX_TOASTS: usize = 3;
+
+/// An alert that keeps firing pops up again after this long
+const TOAST_REPEAT: Duration = Duration::from_secs(5 * 60);
+
+/// Widest a toast gets
+const TOAST_WIDTH: u16 = 48;
+
+/// Distinct alerts kept in the history
+const HISTORY_LEN: usize = 50;
+
+/// Memory usage at which pressure becomes critical
+const MEMORY_CRITICAL_PERCENT: f32 = 95.0;
+
+/// Open file usage at which descriptor pressure becomes critical
+const FD_CRITICAL_PERCENT: f32 = 95.0;
+
+/// Disks projected to fill up sooner than this raise a warning
+const PROJECTION_WARNING: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// ...and sooner than this, a critical alert
+const PROJECTION_CRITICAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Warning => "WARNING",
+            Self::Critical => "CRITICAL",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Warning => Color::Yellow,
+            Self::Critical => Color::Red,
+        }
+    }
+}
+
+/// One alert condition and how often it fired
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// Identifies the condition, e.g. `disk.full:/home`
+    pub key: String,
+    pub severity: Severity,
+    /// Latest description
+    pub message: String,
+    pub first_seen: SystemTime,
+    pub last_seen: SystemTime,
+    pub count: u32,
+}
+
+/// The alert a bus event raises, if any, as `(key, severity, message)`
+pub fn from_event(event: &BusEvent) -> Option<(String, Severity, String)> {
+    match event.topic.as_str() {
+        "system.memory.pressure" => {
+            let metrics = event.payload.downcast::<MemoryMetrics>()?;
+            let severity = if metrics.usage_percent >= MEMORY_CRITICAL_PERCENT {
+                Severity::Critical
+            } else {
+                Severity::Warning
+            };
+            Some((
+                "memory.pressure".to_string(),
+                severity,
+                format!("Memory pressure: {:.0}% in use", metrics.usage_percent),
+            ))
+        }
+        "system.memory.thrashing" => {
+            let metrics = event.payload.downcast::<MemoryMetrics>()?;
+            let paging = metrics.paging?;
+            Some((
+                "memory.thrashing".to_string(),
+                Severity::Critical,
+                format!(
+                    "Memory thrashing: swapping {:.0} pages/s in, {:.0} out",
+                    paging.pages_in, paging.pages_out
+                ),
+            ))
+        }
+        "system.disk.full" => {
+            let metrics = event.payload.downcast::<DiskUsageMetrics>()?;
+            Some((
+                format!("disk.full:{}", metrics.mount_point),
+                Severity::Critical,
+                format!(
+                    "Disk {} is {:.0}% full",
+                    metrics.mount_point, metrics.percentage
+                ),
+            ))
+        }
+        "system.disk.projection" => {
+            let projection = event.payload.downcast::<DiskProjection>()?;
+            let left = projection.time_until_full();
+            let severity = if left <= PROJECTION_CRITICAL {
+                Severity::Critical
+            } else if left <= PROJECTION_WARNING {
+                Severity::Warning
+            } else {
+                return None;
+            };
+            Some((
+                format!("disk.projection:{}", projection.mount_point),
+                severity,
+                format!("Disk {}", projection.message()),
+            ))
+        }
+        "system.network.quota" => {
+            let usage = event.payload.downcast::<QuotaUsage>()?;
+            let severity = if usage.is_exceeded() {
+                Severity::Critical
+            } else {
+                Severity::Warning
+            };
+            Some((
+                "network.quota".to_string(),
+                severity,
+                format!("Network quota: {}", usage.describe()),
+            ))
+        }
+        "process.zombie" => {
+            let process = event.payload.downcast::<ProcessInfo>()?;
+            Some((
+                format!("process.zombie:{}", process.pid),
+                Severity::Warning,
+                format!(
+                    "Process {} ({}) is a zombie, not reaped by its parent",
+                    process.name, process.pid
+                ),
+            ))
+        }
+        "process.fd.pressure" => {
+            let process = event.payload.downcast::<ProcessInfo>()?;
+            let percent = process.fd_percent()?;
+            let severity = if percent >= FD_CRITICAL_PERCENT {
+                Severity::Critical
+            } else {
+                Severity::Warning
+            };
+            Some((
+                format!("process.fd.pressure:{}", process.pid),
+                severity,
+                format!(
+                    "Process {} ({}) has {} of {} files open",
+                    process.name,
+                    process.pid,
+                    process.open_files.unwrap_or_default(),
+                    process.open_files_limit.unwrap_or_default()
+                ),
+            ))
+        }
+        "system.login.remote" => {
+            let session = event.payload.downcast::<Session>()?;
+            Some((
+                format!("login.remote:{}@{}", session.user, session.tty),
+                Severity::Warning,
+                format!(
+                    "Remote login: {} from {} on {}",
+                    session.user, session.origin, session.tty
+                ),
+            ))
+        }
+        "system.firewall.burst" => {
+            let source = event.payload.downcast::<SourceActivity>()?;
+            let ports: Vec<String> = source.ports.iter().take(3).map(u16::to_string).collect();
+            Some((
+                format!("firewall.burst:{}", source.source),
+                Severity::Warning,
+                format!(
+                    "Firewall blocked {} attempts from {} in the last minute (ports {})",
+                    source.recent,
+                    source.source,
+                    ports.join(", ")
+                ),
+            ))
+        }
+        "system.job.failed" => {
+            let job = event.payload.downcast::<ScheduledJob>()?;
+            let Some(JobResult::Failed(reason)) = &job.result else {
+                return None;
+            };
+            let when = job
+                .last_run
+                .map_or(String::new(), |at| format!(" at {}", format_timestamp(at)));
+            Some((
+                format!("job.failed:{}", job.name),
+                Severity::Warning,
+                format!("Scheduled job {} failed{} ({})", job.name, when, reason),
+            ))
+        }
+        "system.backup.stale" => {
+            let status = event.payload.downcast::<BackupStatus>()?;
+            let severity = match status.freshness {
+                Freshness::Overdue => Severity::Warning,
+                Freshness::Stale => Severity::Critical,
+                Freshness::Fresh | Freshness::Unknown => return None,
+            };
+            let since = status.last_success.map_or(String::new(), |at| {
+                format!(" since {}", format_timestamp(at))
+            });
+            Some((
+                format!("backup.stale:{}", status.name),
+                severity,
+                format!("No backup of {}{}", status.name, since),
+            ))
+        }
+        "system.health.issue" => {
+            let issue = event.payload.downcast::<HealthIssue>()?;
+            let subject = issue.subject.as_deref().unwrap_or_default();
+            Some((
+                format!("health:{:?}:{}", issue.kind, subject),
+                Severity::Critical,
+                format!("{}: {}", issue.describe(), issue.message),
+            ))
+        }
+        "system.dev.crashed" => {
+            let crash = event.payload.downcast::<DevCrash>()?;
+            Some((
+                format!("dev.crash:{}", crash.name),
+                Severity::Warning,
+                format!("Dev command {}", crash.describe()),
+            ))
+        }
+        "system.temperature.critical" => {
+            let reading = event.payload.downcast::<Temperature>()?;
+            Some((
+                format!("temperature:{}", reading.label),
+                Severity::Critical,
+                format!(
+                    "{} is at {:.0}°C (critical {:.0}°C)",
+                    reading.label, reading.celsius, reading.critical
+                ),
+            ))
+        }
+        "system.http.down" => {
+            let endpoint = event.payload.downcast::<EndpointHealth>()?;
+            Some((
+                format!("http.down:{}", endpoint.name),
+                Severity::Critical,
+                format!("Endpoint {}", endpoint.describe()),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Alerts seen so far and which one the banner shows
+#[derive(Debug, Default)]
+pub struct AlertHistory {
+    /// Oldest first
+    alerts: Vec<Alert>,
+    banner: Option<String>,
+    /// Dismissed alerts and when they may show again
+    quiet: HashMap<String, Instant>,
+    /// Alerts popped up as toasts and when, oldest first
+    toasts: Vec<(String, Instant)>,
+    /// When each alert last popped up
+    toasted: HashMap<String, Instant>,
+}
+
+impl AlertHistory {
+    /// Record that an alert fired
+    ///
+    /// Returns true if the banner or toasts changed. A more severe alert
+    /// replaces the one on the banner; a dismissed alert stays off it for a
+    /// while. An alert pops up as a toast when it first fires, when it gets
+    /// more severe, and again every few minutes while it keeps firing.
+    pub fn record(
+        &mut self,
+        key: String,
+        severity: Severity,
+        message: String,
+        now: Instant,
+    ) -> bool {
+        let wall = SystemTime::now();
+        let escalated = self
+            .alerts
+            .iter()
+            .find(|a| a.key == key)
+            .is_none_or(|alert| severity > alert.severity);
+        match self.alerts.iter_mut().find(|a| a.key == key) {
+            Some(alert) => {
+                alert.severity = severity;
+                alert.message = message;
+                alert.last_seen = wall;
+                alert.count += 1;
+            }
+            None => {
+                self.alerts.push(Alert {
+                    key: key.clone(),
+                    severity,
+                    message,
+                    first_seen: wall,
+                    last_seen: wall,
+                    count: 1,
+                });
+                if self.alerts.len() > HISTORY_LEN {
+                    self.alerts.remove(0);
+                }
+            }
+        }
+
+        if self.quiet.get(&key).is_some_and(|until| now < *until) {
+            return false;
+        }
+        let toast = escalated
+            || self
+                .toasted
+                .get(&key)
+                .is_none_or(|at| now.duration_since(*at) >= TOAST_REPEAT);
+        if toast {
+            self.toasts.retain(|(shown, _)| *shown != key);
+            self.toasts.push((key.clone(), now));
+            if self.toasts.len() > MAX_TOASTS {
+                self.toasts.remove(0);
+            }
+            self.toasted.insert(key.clone(), now);
+        }
+        let replace = self
+            .banner()
+            .is_none_or(|shown| shown.key == key || severity >= shown.severity);
+        if replace {
+            self.banner = Some(key);
+        }
+        replace || toast
+    }
+
+    /// Alerts popped up as toasts that haven't timed out, oldest first
+    pub fn toasts(&self) -> Vec<&Alert> {
+        self.toasts
+            .iter()
+            .filter_map(|(key, _)| self.alerts.iter().find(|a| &a.key == key))
+            .collect()
+    }
+
+    /// Take down toasts that have been up long enough; returns true if any
+    /// were
+    pub fn expire_toasts(&mut self, now: Instant) -> bool {
+        let before = self.toasts.len();
+        self.toasts
+            .retain(|(_, at)| now.duration_since(*at) < TOAST_DURATION);
+        self.toasts.len() != before
+    }
+
+    /// The alert on the banner, if any
+    pub fn banner(&self) -> Option<&Alert> {
+        let key = self.banner.as_ref()?;
+        self.alerts.iter().find(|a| &a.key == key)
+    }
+
+    /// Hide the banner and toasts; returns false if none were shown
+    pub fn dismiss(&mut self, now: Instant) -> bool {
+        let toasts = !self.toasts.is_empty();
+        self.toasts.clear();
+        match self.banner.take() {
+            Some(key) => {
+                self.quiet.insert(key, now + DISMISS_QUIET);
+                true
+            }
+            None => toasts,
+        }
+    }
+
+    /// The alert recorded under `key` if it popped up as a toast at `now`
+    pub fn popped_up(&self, key: &str, now: Instant) -> Option<&Alert> {
+        if self.toasted.get(key) != Some(&now) {
+            return None;
+        }
+        self.alerts.iter().find(|a| a.key == key)
+    }
+
+    /// Every alert seen, oldest first
+    pub fn alerts(&self) -> &[Alert] {
+        &self.alerts
+    }
+
+    /// The alert that fired last, dismissed or not
+    pub fn latest(&self) -> Option<&Alert> {
+        self.alerts.iter().max_by_key(|a| a.last_seen)
+    }
+}
+
+/// Subscriptions to the alert topics, the configured alert rules, and
+/// which alerts notify the desktop
+pub struct AlertWatcher {
+    _subscriptions: Vec<Subscription>,
+    receivers: Vec<Receiver<BusEvent>>,
+    rules: AlertRules,
+    notifications: NotificationConfig,
+}
+
+impl AlertWatcher {
+    pub fn new(event_bus: &EventBus) -> Self {
+        let (subscriptions, receivers) = ALERT_TOPICS
+            .iter()
+            .map(|topic| event_bus.subscribe(*topic))
+            .unzip();
+        Self {
+            _subscriptions: subscriptions,
+            receivers,
+            rules: AlertRules::default(),
+            notifications: NotificationConfig::default(),
+        }
+    }
+
+    /// Replace the `[[alerts]]` rules checked along with the built-in alerts
+    pub fn set_rules(&mut self, event_bus: &EventBus, rules: Vec<AlertRule>) {
+        self.rules = AlertRules::new(event_bus, rules);
+    }
+
+    pub fn set_notifications(&mut self, notifications: NotificationConfig) {
+        self.notifications = notifications;
+    }
+
+    /// Record alerts published since the last call; returns true if the
+    /// banner or toasts changed
+    ///
+    /// Alerts popping up as toasts are also sent to the desktop when
+    /// `[notifications]` or their rule ask for it.
+    pub fn drain(&mut self, history: &mut AlertHistory) -> bool {
+        let now = Instant::now();
+        let mut raised: Vec<_> = self
+            .receivers
+            .iter()
+            .flat_map(|rx| rx.try_iter())
+            .filter_map(|event| from_event(&event))
+            .collect();
+        raised.extend(self.rules.check(now));
+
+        let mut changed = false;
+        for (key, severity, message) in raised {
+            let rule = self.rules.notify(&key);
+            changed |= history.record(key.clone(), severity, message, now);
+            if let Some(alert) = history.popped_up(&key, now)
+                && self.notifications.wants(severity, rule)
+            {
+                notify::send(alert);
+            }
+        }
+        changed
+    }
+}
+
+/// Strip across the top of the screen showing the current alert
+pub struct Banner<'a>(pub &'a Alert);
+
+impl Overlay for Banner<'_> {
+    fn z_index(&self) -> u8 {
+        z::BANNER
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let alert = self.0;
+        let strip = Rect { height: 1, ..area };
+        let repeats = if alert.count > 1 {
+            format!(" (x{})", alert.count)
+        } else {
+            String::new()
+        };
+
+        Clear.render(strip, buf);
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!(" {} ", alert.severity.label()),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::from(format!("{}{}", alert.message, repeats)),
+            Span::from("  [! history, Esc dismiss]"),
+        ]))
+        .style(Style::default().bg(alert.severity.color()).fg(Color::Black))
+        .render(strip, buf);
+    }
+}
+
+/// Popups down the right side for alerts that just fired, newest on top
+pub struct Toasts<'a>(pub Vec<&'a Alert>);
+
+impl Overlay for Toasts<'_> {
+    fn z_index(&self) -> u8 {
+        z::TOAST
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = TOAST_WIDTH.min(area.width);
+        // Below the banner's line
+        let mut y = area.y + 1;
+        for alert in self.0.iter().rev() {
+            if y + 3 > area.bottom() {
+                break;
+            }
+            let toast = Rect::new(area.right() - width, y, width, 3);
+            let repeats = if alert.count > 1 {
+                format!(" (x{})", alert.count)
+            } else {
+                String::new()
+            };
+            Clear.render(toast, buf);
+            Paragraph::new(format!("{}{}", alert.message, repeats))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(alert.severity.color()))
+                        .title(format!(" {} ", alert.severity.label())),
+                )
+                .render(toast, buf);
+            y += 3;
+        }
+    }
+}
+
+/// Panel listing the alert history, newest first
+pub struct HistoryPanel<'a>(pub &'a AlertHistory);
+
+impl Overlay for HistoryPanel<'_> {
+    fn z_index(&self) -> u8 {
+        z::PANEL
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let alerts = self.0.alerts();
+        let height = (alerts.len().max(1) as u16 + 2).min(area.height);
+        let [panel] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [panel] = Layout::horizontal([Constraint::Percentage(80)])
+            .flex(Flex::Center)
+            .areas(panel);
+
+        let lines: Vec<Line> = if alerts.is_empty() {
+            vec![Line::from("No alerts")]
+        } else {
+            alerts
+                .iter()
+                .rev()
+                .map(|alert| {
+                    Line::from(vec![
+                        Span::from(format!("{}  ", format_timestamp(alert.last_seen))),
+                        Span::styled(
+                            format!("{:<8}", alert.severity.label()),
+                            Style::default().fg(alert.severity.color()),
+                        ),
+                        Span::from(format!("  {}  x{}", alert.message, alert.count)),
+                    ])
+                })
+                .collect()
+        };
+
+        Clear.render(panel, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Alert History [Esc to close] "),
+            )
+            .render(panel, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devdash_widgets::{HealthKind, JobSource};
+
+    fn record(history: &mut AlertHistory, key: &str, severity: Severity, now: Instant) -> bool {
+        history.record(key.to_string(), severity, key.to_string(), now)
+    }
+
+    #[test]
+    fn test_more_severe_alert_takes_the_banner() {
+        let mut history = AlertHistory::default();
+        let now = Instant::now();
+        assert!(record(
+            &mut history,
+            "memory.pressure",
+            Severity::Warning,
+            now
+        ));
+        assert!(record(&mut history, "disk.full:/", Severity::Critical, now));
+        assert!(!record(
+            &mut history,
+            "memory.pressure",
+            Severity::Warning,
+            now
+        ));
+        assert_eq!(history.banner().unwrap().key, "disk.full:/");
+
+        // Repeats update the existing entry
+        assert_eq!(history.alerts().len(), 2);
+        assert_eq!(history.alerts()[0].count, 2);
+    }
+
+    #[test]
+    fn test_dismissed_alert_stays_quiet() {
+        let mut history = AlertHistory::default();
+        let now = Instant::now();
+        record(&mut history, "disk.full:/", Severity::Critical, now);
+        assert_eq!(history.toasts().len(), 1);
+        assert!(history.dismiss(now));
+        assert!(!history.dismiss(now));
+        assert!(history.toasts().is_empty());
+        assert!(history.banner().is_none());
+
+        assert!(!record(
+            &mut history,
+            "disk.full:/",
+            Severity::Critical,
+            now
+        ));
+        assert!(record(
+            &mut history,
+            "disk.full:/",
+            Severity::Critical,
+            now + DISMISS_QUIET
+        ));
+    }
+
+    #[test]
+    fn test_toasts() {
+        let mut history = AlertHistory::default();
+        let now = Instant::now();
+        record(&mut history, "memory.pressure", Severity::Warning, now);
+        record(&mut history, "disk.full:/", Severity::Warning, now);
+        let keys = |history: &AlertHistory| -> Vec<String> {
+            history.toasts().iter().map(|a| a.key.clone()).collect()
+        };
+        assert_eq!(keys(&history), ["memory.pressure", "disk.full:/"]);
+        assert!(history.popped_up("disk.full:/", now).is_some());
+
+        // Repeats don't pop up again until they escalate
+        let later = now + Duration::from_secs(1);
+        record(&mut history, "disk.full:/", Severity::Warning, later);
+        assert_eq!(keys(&history), ["memory.pressure", "disk.full:/"]);
+        assert!(history.popped_up("disk.full:/", later).is_none());
+        record(&mut history, "memory.pressure", Severity::Critical, later);
+        assert_eq!(keys(&history), ["disk.full:/", "memory.pressure"]);
+        assert!(history.popped_up("memory.pressure", later).is_some());
+
+        assert!(history.expire_toasts(now + TOAST_DURATION));
+        assert_eq!(keys(&history), ["memory.pressure"]);
+        assert!(history.expire_toasts(later + TOAST_DURATION));
+        assert!(!history.expire_toasts(later + TOAST_DURATION));
+        assert!(history.toasts().is_empty());
+
+        for i in 0..4 {
+            record(
+                &mut history,
+                &format!("job:{}", i),
+                Severity::Warning,
+                later,
+            );
+        }
+        assert_eq!(keys(&history), ["job:1", "job:2", "job:3"]);
+    }
+
+    #[test]
+    fn test_from_event() {
+        let event = BusEvent::new(
+            "system.disk.full",
+            DiskUsageMetrics {
+                mount_point: "/home".to_string(),
+                total: 100,
+                used: 95,
+                available: 5,
+                percentage: 95.0,
+            },
+        );
+        assert_eq!(
+            from_event(&event),
+            Some((
+                "disk.full:/home".to_string(),
+                Severity::Critical,
+                "Disk /home is 95% full".to_string()
+            ))
+        );
+        assert_eq!(from_event(&BusEvent::new("system.disk.full", 1u8)), None);
+
+        let projection = |days: u64| {
+            BusEvent::new(
+                "system.disk.projection",
+                DiskProjection {
+                    mount_point: "/var".to_string(),
+                    bytes_per_day: 1024.0,
+                    seconds_until_full: days * 86_400,
+                },
+            )
+        };
+        assert_eq!(
+            from_event(&projection(3)),
+            Some((
+                "disk.projection:/var".to_string(),
+                Severity::Warning,
+                "Disk /var full in ~3 days (+1.0 KiB/day)".to_string()
+            ))
+        );
+        assert_eq!(from_event(&projection(30)), None);
+
+        let event = BusEvent::new(
+            "system.temperature.critical",
+            Temperature {
+                label: "amdgpu edge".to_string(),
+                celsius: 94.4,
+                max: 95.0,
+                critical: 90.0,
+            },
+        );
+        assert_eq!(
+            from_event(&event),
+            Some((
+                "temperature:amdgpu edge".to_string(),
+                Severity::Critical,
+                "amdgpu edge is at 94°C (critical 90°C)".to_string()
+            ))
+        );
+
+        let event = BusEvent::new(
+            "system.network.quota",
+            QuotaUsage {
+                used: 900,
+                limit: 1000,
+                percentage: 90.0,
+                period: devdash_widgets::quota::QuotaPeriod::Monthly,
+                warn_percent: 80.0,
+            },
+        );
+        assert_eq!(
+            from_event(&event),
+            Some((
+                "network.quota".to_string(),
+                Severity::Warning,
+                "Network quota: 900 B of 1000 B this month (90%)".to_string()
+            ))
+        );
+
+        let thrashing = BusEvent::new(
+            "system.memory.thrashing",
+            MemoryMetrics {
+                used: 15,
+                total: 16,
+                swap_used: 3,
+                swap_total: 4,
+                usage_percent: 93.75,
+                swap_percent: 75.0,
+                paging: Some(devdash_widgets::PagingRates {
+                    pages_in: 700.0,
+                    pages_out: 350.0,
+                }),
+            },
+        );
+        assert_eq!(
+            from_event(&thrashing),
+            Some((
+                "memory.thrashing".to_string(),
+                Severity::Critical,
+                "Memory thrashing: swapping 700 pages/s in, 350 out".to_string()
+            ))
+        );
+
+        let process = |open_files| ProcessInfo {
+            pid: 42,
+            name: "node".to_string(),
+            cpu_percent: 0.0,
+            memory_bytes: 0,
+            start_time: 0,
+            cwd: None,
+            zombie: false,
+            open_files: Some(open_files),
+            open_files_limit: Some(1000),
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("process.fd.pressure", process(990))),
+            Some((
+                "process.fd.pressure:42".to_string(),
+                Severity::Critical,
+                "Process node (42) has 990 of 1000 files open".to_string()
+            ))
+        );
+        let zombie = ProcessInfo {
+            zombie: true,
+            ..process(0)
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("process.zombie", zombie))
+                .map(|(key, severity, _)| (key, severity)),
+            Some(("process.zombie:42".to_string(), Severity::Warning))
+        );
+
+        let login = Session {
+            user: "deploy".to_string(),
+            tty: "pts/3".to_string(),
+            origin: "203.0.113.9".to_string(),
+            login_time: SystemTime::UNIX_EPOCH,
+            idle: None,
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.login.remote", login)),
+            Some((
+                "login.remote:deploy@pts/3".to_string(),
+                Severity::Warning,
+                "Remote login: deploy from 203.0.113.9 on pts/3".to_string()
+            ))
+        );
+
+        let burst = SourceActivity {
+            source: "198.51.100.4".to_string(),
+            attempts: 120,
+            recent: 45,
+            burst: true,
+            ports: vec![22, 2222],
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.firewall.burst", burst)),
+            Some((
+                "firewall.burst:198.51.100.4".to_string(),
+                Severity::Warning,
+                "Firewall blocked 45 attempts from 198.51.100.4 in the last minute (ports 22, 2222)"
+                    .to_string()
+            ))
+        );
+
+        let job = ScheduledJob {
+            name: "backup.timer".to_string(),
+            source: JobSource::Timer,
+            next_run: None,
+            last_run: None,
+            result: Some(JobResult::Failed("exit 2".to_string())),
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.job.failed", job.clone())),
+            Some((
+                "job.failed:backup.timer".to_string(),
+                Severity::Warning,
+                "Scheduled job backup.timer failed (exit 2)".to_string()
+            ))
+        );
+        let succeeded = ScheduledJob {
+            result: Some(JobResult::Success),
+            ..job
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.job.failed", succeeded)),
+            None
+        );
+
+        let backup = BackupStatus {
+            name: "offsite".to_string(),
+            last_success: None,
+            freshness: Freshness::Stale,
+            error: None,
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.backup.stale", backup.clone())),
+            Some((
+                "backup.stale:offsite".to_string(),
+                Severity::Critical,
+                "No backup of offsite".to_string()
+            ))
+        );
+        let overdue = BackupStatus {
+            freshness: Freshness::Overdue,
+            ..backup
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.backup.stale", overdue)).map(|(_, s, _)| s),
+            Some(Severity::Warning)
+        );
+
+        let issue = HealthIssue {
+            kind: HealthKind::ReadOnly,
+            subject: Some("sda1".to_string()),
+            message: "EXT4-fs (sda1): Remounting filesystem read-only".to_string(),
+            time: SystemTime::UNIX_EPOCH,
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.health.issue", issue)),
+            Some((
+                "health:ReadOnly:sda1".to_string(),
+                Severity::Critical,
+                "Filesystem on sda1 went read-only: EXT4-fs (sda1): Remounting filesystem read-only"
+                    .to_string()
+            ))
+        );
+
+        let crash = DevCrash {
+            name: "web".to_string(),
+            command: "npm run dev".to_string(),
+            code: Some(1),
+            log: vec!["Error: listen EADDRINUSE :::3000".to_string()],
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.dev.crashed", crash)),
+            Some((
+                "dev.crash:web".to_string(),
+                Severity::Warning,
+                "Dev command web crashed (exit code 1)".to_string()
+            ))
+        );
+
+        let endpoint = EndpointHealth {
+            name: "api".to_string(),
+            url: "https://api.example.com/health".to_string(),
+            up: Some(false),
+            status: Some(503),
+            latency: Some(Duration::from_millis(12)),
+            checks: 40,
+            failures: 2,
+            error: Some("HTTP 503".to_string()),
+        };
+        assert_eq!(
+            from_event(&BusEvent::new("system.http.down", endpoint)),
+            Some((
+                "http.down:api".to_string(),
+                Severity::Critical,
+                "Endpoint api is down (HTTP 503)".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,364 @@
+// devdash-cli/src/api.rs
+//! The JSON HTTP API (`--api ADDR`), for scraping what the dashboard shows.
+//!
+//! - `/api/metrics`: the latest payload of each `system.*` topic
+//! - `/api/widgets`: the dashboard's widgets with their text summaries
+//! - `/api/git`: the git widget's status and the latest `git.*` events
+//! - `/api/stream?topic=system.*`: a WebSocket sending each event published
+//!   on the given topics (every topic without one) as it happens, in the
+//!   `--event-log` format; browser pages only get it when served from this
+//!   host or localhost
+//!
+//! Payloads are read through their JSON form, so only the types registered
+//! with the [`devdash_core::PayloadRegistry`] show up; other topics are left
+//! out.
+use crossbeam::channel::{Receiver, RecvTimeoutError};
+use devdash_core::{
+    BusEvent, EventBus, HttpReply, HttpResponse, HttpServer, WebSocket, event::Subscription,
+};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::{
+    collections::BTreeMap,
+    io,
+    net::SocketAddr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{codec, event_log::record_line};
+
+/// Path of the WebSocket event stream
+const STREAM_PATH: &str = "/api/stream";
+
+/// How often a quiet stream checks whether the API has stopped
+const STREAM_POLL: Duration = Duration::from_secs(1);
+
+/// Topic the git widget publishes its status on
+const GIT_STATUS_TOPIC: &str = "system.git.status";
+
+/// The latest payload published on a topic
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Latest {
+    #[serde(rename = "type")]
+    payload_type: &'static str,
+    payload: Value,
+    timestamp_ms: u128,
+}
+
+/// One widget of the dashboard, as reported by the app
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WidgetReport {
+    pub name: String,
+    pub host: Option<String>,
+    pub summary: String,
+    /// Seconds since the widget's data last refreshed, once it's stale
+    pub stale_secs: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct ApiState {
+    topics: BTreeMap<String, Latest>,
+    dashboard: String,
+    widgets: Vec<WidgetReport>,
+}
+
+impl ApiState {
+    fn record(&mut self, event: &BusEvent) {
+        let Some((payload_type, payload)) = codec::encode(event) else {
+            return;
+        };
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        self.topics.insert(
+            event.topic.clone(),
+            Latest {
+                payload_type,
+                payload,
+                timestamp_ms,
+            },
+        );
+    }
+
+    /// The latest payloads of the topics matching `pattern`
+    fn matching(&self, pattern: &str) -> Map<String, Value> {
+        self.topics
+            .iter()
+            .filter(|(topic, _)| EventBus::topic_matches(topic, pattern))
+            .map(|(topic, latest)| (topic.clone(), json!(latest)))
+            .collect()
+    }
+
+    fn respond(&self, path: &str) -> Option<Value> {
+        match path.trim_end_matches('/') {
+            "/api" => Some(json!({
+                "endpoints": ["/api/metrics", "/api/widgets", "/api/git", STREAM_PATH],
+            })),
+            "/api/metrics" => {
+                let mut metrics = self.matching("system.*");
+                metrics.retain(|topic, _| !EventBus::topic_matches(topic, "system.git.*"));
+                Some(Value::Object(metrics))
+            }
+            "/api/widgets" => Some(json!({
+                "dashboard": self.dashboard,
+                "widgets": self.widgets,
+            })),
+            "/api/git" => {
+                let status = self
+                    .topics
+                    .get(GIT_STATUS_TOPIC)
+                    .map(|latest| latest.payload.clone());
+                let mut events = self.matching("system.git.*");
+                events.extend(self.matching("git.*"));
+                Some(json!({ "status": status, "events": events }))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Send the events matching `topics` to `socket` until the client goes
+/// away or the API stops
+fn stream(
+    _subscription: Subscription,
+    events: Receiver<BusEvent>,
+    topics: Vec<String>,
+    mut socket: WebSocket,
+    stopped: &AtomicBool,
+) {
+    while !stopped.load(Ordering::Relaxed) {
+        if !socket.poll() {
+            return;
+        }
+        let event = match events.recv_timeout(STREAM_POLL) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if topics
+            .iter()
+            .any(|pattern| EventBus::topic_matches(&event.topic, pattern))
+            && socket.send_text(&record_line(&event)).is_err()
+        {
+            return;
+        }
+    }
+    socket.close();
+}
+
+/// The running API server; stops when dropped
+pub struct Api {
+    state: Arc<Mutex<ApiState>>,
+    _server: HttpServer,
+    subscription: Option<Subscription>,
+    handle: Option<JoinHandle<()>>,
+    /// Tells streams to close
+    stopped: Arc<AtomicBool>,
+}
+
+impl Api {
+    /// Start recording bus events and serving them on `addr`
+    pub fn start(event_bus: &EventBus, addr: SocketAddr) -> io::Result<Self> {
+        let state = Arc::new(Mutex::new(ApiState::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let serving = state.clone();
+        let bus = event_bus.clone();
+        let stopping = stopped.clone();
+        let server = HttpServer::start(addr, move |request| {
+            if request.path == STREAM_PATH {
+                let mut topics = request.query("topic");
+                if topics.is_empty() {
+                    topics.push("*".to_string());
+                }
+                // Subscribe before the upgrade is answered, so the client
+                // gets everything published once it is connected
+                let (subscription, events) = bus.subscribe("*");
+                let stopped = stopping.clone();
+                return Some(HttpReply::Upgrade(Box::new(move |socket| {
+                    stream(subscription, events, topics, socket, &stopped)
+                })));
+            }
+            let body = serving.lock().ok()?.respond(request.path)?;
+            Some(HttpResponse::json(body.to_string()).into())
+        })?;
+
+        let (subscription, rx) = event_bus.subscribe("*");
+        let recording = state.clone();
+        let handle = std::thread::spawn(move || {
+            // Ends once the subscription is dropped and the channel closes
+            for event in rx {
+                if let Ok(mut state) = recording.lock() {
+                    state.record(&event);
+                }
+            }
+        });
+
+        Ok(Self {
+            state,
+            _server: server,
+            subscription: Some(subscription),
+            handle: Some(handle),
+            stopped,
+        })
+    }
+
+    /// Replace the dashboard's widgets served on `/api/widgets`
+    pub fn set_widgets(&self, dashboard: &str, widgets: Vec<WidgetReport>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.dashboard = dashboard.to_string();
+            state.widgets = widgets;
+        }
+    }
+}
+
+impl Drop for Api {
+    fn drop(&mut self) {
+        // Unsubscribing closes the channel, letting the recorder exit; the
+        // server stops when dropped after this
+        self.stopped.store(true, Ordering::Relaxed);
+        self.subscription.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devdash_core::SystemMetrics;
+    use devdash_widgets::GitStatus;
+
+    fn state() -> ApiState {
+        let mut state = ApiState::default();
+        state.record(&BusEvent::new(
+            "system.metrics",
+            SystemMetrics {
+                cpu_usage: 12.5,
+                memory_used: 1,
+                memory_total: 2,
+            },
+        ));
+        state.record(&BusEvent::new(
+            GIT_STATUS_TOPIC,
+            GitStatus {
+                workdir: "/src/devdash".into(),
+                branch: "main".to_string(),
+                remote_branch: Some("origin/main".to_string()),
+                ahead: 0,
+                behind: 3,
+                staged: 1,
+                unstaged: 0,
+                untracked: 2,
+                forge_url: None,
+                files: Vec::new(),
+                last_commits: Vec::new(),
+            },
+        ));
+        // No JSON form, so never served
+        state.record(&BusEvent::new("system.opaque", 42u8));
+        state
+    }
+
+    #[test]
+    fn test_metrics() {
+        let metrics = state().respond("/api/metrics").unwrap();
+        let topics: Vec<_> = metrics.as_object().unwrap().keys().collect();
+        assert_eq!(topics, ["system.metrics"]);
+        assert_eq!(metrics["system.metrics"]["type"], "SystemMetrics");
+        assert_eq!(metrics["system.metrics"]["payload"]["cpu_usage"], 12.5);
+    }
+
+    #[test]
+    fn test_git() {
+        let git = state().respond("/api/git/").unwrap();
+        assert_eq!(git["status"]["branch"], "main");
+        assert_eq!(git["status"]["staged"], 1);
+        assert_eq!(git["status"]["behind"], 3);
+        assert!(git["events"].get(GIT_STATUS_TOPIC).is_some());
+        assert_eq!(
+            ApiState::default().respond("/api/git").unwrap()["status"],
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_widgets_and_unknown_paths() {
+        let mut state = state();
+        state.dashboard = "dev".to_string();
+        state.widgets = vec![WidgetReport {
+            name: "cpu".to_string(),
+            host: None,
+            summary: "CPU 12%".to_string(),
+            stale_secs: Some(30),
+        }];
+        assert_eq!(
+            state.respond("/api/widgets").unwrap(),
+            json!({
+                "dashboard": "dev",
+                "widgets": [{ "name": "cpu", "host": null, "summary": "CPU 12%", "stale_secs": 30 }],
+            })
+        );
+        assert!(state.respond("/api/processes").is_none());
+        assert!(state.respond("/").is_none());
+    }
+
+    #[test]
+    fn test_serves_widgets() {
+        use std::io::{Read, Write};
+
+        let bus = EventBus::new();
+        let api = Api::start(&bus, "127.0.0.1:0".parse().unwrap()).unwrap();
+        api.set_widgets("default", Vec::new());
+        let mut stream = std::net::TcpStream::connect(api._server.local_addr()).unwrap();
+        stream
+            .write_all(b"GET /api/widgets HTTP/1.1\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with(r#"{"dashboard":"default","widgets":[]}"#));
+    }
+
+    #[test]
+    fn test_streams_matching_events() {
+        use std::io::{Read, Write};
+
+        let bus = EventBus::new();
+        let api = Api::start(&bus, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut stream = std::net::TcpStream::connect(api._server.local_addr()).unwrap();
+        stream
+            .write_all(
+                b"GET /api/stream?topic=system.%2A HTTP/1.1\r\nUpgrade: websocket\r\n\
+                  Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            )
+            .unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            let mut byte = [0];
+            stream.read_exact(&mut byte).unwrap();
+            head.push(byte[0]);
+        }
+        assert!(head.starts_with(b"HTTP/1.1 101 "));
+
+        bus.publish(BusEvent::new("link.disk", "/".to_string()));
+        bus.publish(BusEvent::new("system.uptime", "3 days".to_string()));
+
+        // One short text frame, for the only event matching the topic
+        let mut header = [0; 2];
+        stream.read_exact(&mut header).unwrap();
+        assert_eq!(header[0], 0x81);
+        let mut message = vec![0; usize::from(header[1])];
+        stream.read_exact(&mut message).unwrap();
+        let record: Value = serde_json::from_slice(&message).unwrap();
+        assert_eq!(record["topic"], "system.uptime");
+        assert_eq!(record["payload"], "3 days");
+    }
+}
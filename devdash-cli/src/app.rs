@@ -0,0 +1,1661 @@
+// devdash-cli/src/app.rs
+//! Dashboard state driven by the main loop.
+use crossbeam::channel::Receiver;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    buffer::Buffer,
+    layout::{Alignment, Position, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Paragraph, Widget as _},
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    alerts::{AlertHistory, AlertWatcher, Banner, HistoryPanel, Toasts},
+    api::{Api, WidgetReport},
+    args::CliArgs,
+    export,
+    keys::{HelpPanel, Section},
+    notify::NotificationConfig,
+    onboarding::{Onboarding, Outcome},
+    overlay::{self, BottomLine, Overlay},
+    remote::{HOST_WIDGETS, Hosts},
+    rules::AlertRule,
+    statusbar::{StatusBar, StatusBarConfig},
+};
+use devdash_core::{
+    ACTION_TOPIC, Action, ActionRequest, BusEvent, CommandPalette, ConfigError, ConfigFile,
+    DevdashError, Event, EventBus, EventResult, Keymap, Layout, PaletteEntry, PaletteResult,
+    PluginManager, Selection, SessionState, Theme, WidgetContainer, WidgetRegistry, WidgetSettings,
+    WidgetStyle,
+    config::{ConfigConstraint, ConfigLayout, ConfigLayoutItem, Dashboard, Direction, LabelStyle},
+    event::Subscription,
+    flatten_layout_items, no_color,
+    opener::{Openers, set_openers},
+    read_only, set_theme, strip_styles,
+};
+use devdash_widgets::{
+    ErrorWidget,
+    common::{UnitSystem, set_time_format, set_unit_system, with_unit_system},
+};
+
+/// What the main loop should do after handling input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// Actions of the dashboard itself rather than the focused widget
+pub const GLOBAL_ACTIONS: &[Action] = &[
+    Action::new("quit", &["q"], "Quit"),
+    Action::new("focus_next", &["tab"], "Focus next widget"),
+    Action::new("reload", &["ctrl+r"], "Reload configuration"),
+    Action::new("alert_history", &["!"], "Show alert history"),
+    Action::new("help", &["?"], "Show key bindings"),
+    Action::new("palette", &["ctrl+p"], "Open command palette"),
+    Action::new("dismiss", &["esc"], "Dismiss alert banner or close panel"),
+    Action::new("swap_prev", &["<"], "Swap focused widget with the previous").mutating(),
+    Action::new("swap_next", &[">"], "Swap focused widget with the next").mutating(),
+    Action::new("hide", &["H"], "Hide focused widget").mutating(),
+    Action::new("write_layout", &["w"], "Write layout to config").mutating(),
+    Action::new("zoom", &["z"], "Zoom focused widget to the full screen"),
+    Action::new("export_frame", &["ctrl+e"], "Save the screen to a file"),
+    Action::new("grow_width", &["ctrl+right"], "Widen focused widget").mutating(),
+    Action::new("shrink_width", &["ctrl+left"], "Narrow focused widget").mutating(),
+    Action::new("grow_height", &["ctrl+down"], "Make focused widget taller").mutating(),
+    Action::new("shrink_height", &["ctrl+up"], "Make focused widget shorter").mutating(),
+];
+
+/// Poll interval of built-in widgets without an `interval` setting, unless
+/// `--tick-rate` changes it
+pub const DEFAULT_TICK_RATE: Duration = Duration::from_secs(1);
+
+/// How long a notice stays on the bottom line
+const NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+/// Cells a widget grows or shrinks by per resize key
+const RESIZE_STEP: i32 = 2;
+
+/// Least time between widget summaries handed to the HTTP API
+const API_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Width widgets without their own summary are rendered at for the API
+const API_SUMMARY_WIDTH: u16 = 80;
+
+/// Dashboard action taking the dashboard to switch to as its argument; only
+/// offered in the command palette, so it has no key
+const SWITCH_DASHBOARD: &str = "switch_dashboard";
+
+/// Dashboard action taking the registry name of a widget to put beside the
+/// focused one; only offered in the command palette
+const ADD_WIDGET: &str = "add_widget";
+
+/// Dashboard action putting a copy of the focused widget beside it; only
+/// offered in the command palette
+const CLONE_WIDGET: &str = "clone_widget";
+
+/// Dashboard action taking the registry name of a widget to focus; only
+/// requested on the bus, e.g. by the ports widget
+const FOCUS_WIDGET: &str = "focus_widget";
+
+/// Widget actions left out of the command palette, since moving a selection
+/// one row is pointless there
+const NAVIGATION_ACTIONS: [&str; 2] = ["up", "down"];
+
+/// A question on the bottom line waiting for a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Prompt {
+    /// Write the layout to this config file?
+    WriteLayout(PathBuf),
+    /// Quit with layout changes not written?
+    Quit,
+}
+
+/// A widget border being dragged with the mouse
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    /// The widget whose right or bottom border it is
+    widget: usize,
+    direction: Direction,
+    /// Column or row the border was last moved to
+    position: u16,
+}
+
+/// A leaf of the layout, in layout order
+enum Leaf {
+    /// The next widget in `App::widgets`
+    Widget,
+    Spacer,
+    Label(Box<Paragraph<'static>>),
+}
+
+/// The running dashboard: its widgets, layout, and focus
+pub struct App {
+    dashboard_name: String,
+    /// Config file given with `--config`, read instead of searching
+    config: Option<PathBuf>,
+    tick_rate: Duration,
+    event_bus: EventBus,
+    registry: WidgetRegistry,
+    plugin_manager: PluginManager,
+    /// Connections to the `[[host]]`s the layout shows
+    hosts: Hosts,
+    /// Address of the `--connect` agent
+    connect: Option<String>,
+    widgets: Vec<WidgetContainer>,
+    leaves: Vec<Leaf>,
+    layout: Layout,
+    /// The layout as changed at runtime, and as last loaded or written
+    layout_config: ConfigLayout,
+    saved_layout: ConfigLayout,
+    prompt: Option<Prompt>,
+    /// Message on the bottom line, e.g. after writing the layout
+    notice: Option<(String, Instant)>,
+    focused: usize,
+    /// Slot and name of the widget focused on each dashboard left, restored
+    /// on coming back
+    dashboard_focus: HashMap<String, (usize, String)>,
+    /// Whether the focused widget fills the screen in place of the layout
+    zoomed: bool,
+    /// Where each widget was last drawn, for mouse hit-testing; empty for
+    /// widgets not on screen
+    widget_areas: Vec<Rect>,
+    /// The area the layout was last drawn in, which resizing divides up
+    layout_area: Rect,
+    drag: Option<Drag>,
+    /// Keys bound to [`GLOBAL_ACTIONS`]
+    keymap: Keymap,
+    /// Per-widget `units` settings overriding the global one
+    units: HashMap<String, UnitSystem>,
+    /// Link group of each widget with a `link` setting, by widget name
+    links: HashMap<String, String>,
+    /// Last selection shared in each link group
+    link_selections: HashMap<String, Selection>,
+    /// `link.*` events, which other processes publish on a shared bus
+    _link_subscription: Subscription,
+    link_events: Receiver<BusEvent>,
+    /// Widget state saved by the last run
+    session: SessionState,
+    alert_watcher: AlertWatcher,
+    alerts: AlertHistory,
+    show_alert_history: bool,
+    /// Whether the key bindings panel is open
+    show_help: bool,
+    /// Save the next frame drawn with [`export::save`]
+    export_frame: bool,
+    palette: Option<CommandPalette>,
+    /// First-run setup, shown when there is no config file
+    onboarding: Option<Onboarding>,
+    status_bar: StatusBarConfig,
+    /// The clock as last drawn on the status bar
+    clock: String,
+    /// Actions requested on the bus, by the palette or anything else
+    _action_subscription: Subscription,
+    action_events: Receiver<BusEvent>,
+    /// The JSON HTTP API, with when it was last given widget summaries
+    api: Option<(Api, Option<Instant>)>,
+}
+
+impl App {
+    pub fn new(
+        dashboard: &Dashboard,
+        args: &CliArgs,
+        event_bus: EventBus,
+        mut registry: WidgetRegistry,
+        plugin_manager: PluginManager,
+        hosts: Hosts,
+    ) -> Self {
+        let widgets = build_widgets(dashboard, &mut registry, &event_bus, &hosts, args.tick_rate);
+        let alert_watcher = AlertWatcher::new(&event_bus);
+        let (link_subscription, link_events) = event_bus.subscribe("link.*");
+        let (action_subscription, action_events) = event_bus.subscribe(ACTION_TOPIC);
+        Self {
+            dashboard_name: dashboard.name.clone(),
+            config: args.config.clone(),
+            tick_rate: args.tick_rate,
+            event_bus,
+            registry,
+            plugin_manager,
+            hosts,
+            connect: args.connect.clone(),
+            widgets,
+            leaves: build_leaves(&dashboard.layout),
+            layout: dashboard.layout.to_layout(),
+            layout_config: dashboard.layout.clone(),
+            saved_layout: dashboard.layout.clone(),
+            prompt: None,
+            notice: None,
+            focused: 0,
+            dashboard_focus: HashMap::new(),
+            zoomed: false,
+            widget_areas: Vec::new(),
+            layout_area: Rect::default(),
+            drag: None,
+            keymap: Keymap::new(GLOBAL_ACTIONS),
+            units: widget_units(dashboard),
+            links: widget_links(dashboard),
+            link_selections: HashMap::new(),
+            _link_subscription: link_subscription,
+            link_events,
+            session: SessionState::load(),
+            alert_watcher,
+            alerts: AlertHistory::default(),
+            show_alert_history: false,
+            show_help: false,
+            export_frame: false,
+            palette: None,
+            onboarding: None,
+            status_bar: StatusBarConfig::default(),
+            clock: String::new(),
+            _action_subscription: action_subscription,
+            action_events,
+            api: None,
+        }
+    }
+
+    pub fn mount(&mut self) {
+        for widget in self.widgets.iter_mut() {
+            widget.mount();
+            if let Some(state) = self.session.widget(&self.dashboard_name, widget.name()) {
+                widget.restore_state(state);
+            }
+        }
+    }
+
+    pub fn unmount(&mut self) {
+        for widget in self.widgets.iter_mut() {
+            if let Some(state) = widget.save_state() {
+                self.session
+                    .set_widget(&self.dashboard_name, widget.name(), state);
+            }
+            widget.unmount();
+        }
+    }
+
+    /// Write the state of the widgets unmounted so far to disk
+    pub fn save_session(&self) {
+        if let Err(e) = self.session.save() {
+            eprintln!("Warning: Failed to save session state: {}", e);
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let screen = frame.area();
+        let buf = frame.buffer_mut();
+        let (area, bar_area) = self.status_bar.split(screen);
+
+        self.widget_areas = vec![Rect::default(); self.widgets.len()];
+        self.layout_area = area;
+
+        // A zoomed widget takes the whole screen; the others stay mounted
+        // but are suspended, like widgets squeezed to nothing, until the
+        // layout is back
+        if self.zoomed && self.focused < self.widgets.len() {
+            for (i, widget) in self.widgets.iter_mut().enumerate() {
+                if i != self.focused {
+                    widget.set_visible(false);
+                }
+            }
+            let widget = &mut self.widgets[self.focused];
+            self.widget_areas[self.focused] = area;
+            widget.set_visible(area.area() > 0);
+            let units = self.units.get(widget.name()).copied();
+            with_unit_system(units, || widget.render_focused(area, buf, true));
+        } else {
+            self.render_layout(area, buf);
+        }
+
+        self.clock = self.status_bar.clock(SystemTime::now());
+        StatusBar {
+            config: &self.status_bar,
+            dashboard: &self.dashboard_name,
+            focused: self.widgets.get(self.focused).map(|w| match w.host() {
+                Some(host) => format!("{}@{}", w.name(), host),
+                None => w.name().to_string(),
+            }),
+            alert: self.alerts.latest(),
+            clock: &self.clock,
+        }
+        .render(bar_area, buf);
+
+        let banner = self.alerts.banner().map(Banner);
+        let toasts = Toasts(self.alerts.toasts());
+        let history = self
+            .show_alert_history
+            .then_some(HistoryPanel(&self.alerts));
+        let help_sections = self.show_help.then(|| self.help_sections());
+        let help = help_sections.as_deref().map(HelpPanel);
+        let bottom_line = self.bottom_line();
+        let bottom_line = bottom_line.as_ref().map(|(text, style)| BottomLine {
+            text,
+            style: *style,
+        });
+        let overlays: Vec<&dyn Overlay> = [
+            banner.as_ref().map(|o| o as &dyn Overlay),
+            Some(&toasts as &dyn Overlay),
+            history.as_ref().map(|o| o as &dyn Overlay),
+            help.as_ref().map(|o| o as &dyn Overlay),
+            self.palette.as_ref().map(|o| o as &dyn Overlay),
+            self.onboarding.as_ref().map(|o| o as &dyn Overlay),
+            bottom_line.as_ref().map(|o| o as &dyn Overlay),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        overlay::render_all(overlays, screen, buf);
+
+        if no_color() {
+            strip_styles(buf);
+        }
+
+        if std::mem::take(&mut self.export_frame) {
+            match export::save(buf, &self.dashboard_name) {
+                Ok(path) => self.notify(format!("Saved the screen to {} and .txt", path.display())),
+                Err(e) => self.notify(format!("Can't save the screen: {}", e)),
+            }
+        }
+    }
+
+    /// Draw the widgets, spacers and labels in their layout areas
+    fn render_layout(&mut self, area: Rect, buf: &mut Buffer) {
+        let areas = self.layout.calculate(area);
+
+        // Render each widget in its allocated area; widgets squeezed to
+        // nothing are suspended until they get space again
+        let mut widgets = self.widgets.iter_mut().enumerate();
+        for (leaf, leaf_area) in self.leaves.iter().zip(areas) {
+            match leaf {
+                Leaf::Widget => {
+                    let Some((i, widget)) = widgets.next() else {
+                        continue;
+                    };
+                    self.widget_areas[i] = leaf_area;
+                    widget.set_visible(leaf_area.area() > 0);
+                    let units = self.units.get(widget.name()).copied();
+                    with_unit_system(units, || {
+                        widget.render_focused(leaf_area, buf, i == self.focused)
+                    });
+                }
+                Leaf::Spacer => {}
+                Leaf::Label(label) => label.as_ref().render(leaf_area, buf),
+            }
+        }
+    }
+
+    /// One plain-text line per widget, for the accessible output mode
+    pub fn summaries(&mut self, width: u16) -> Vec<String> {
+        let units = &self.units;
+        self.widgets
+            .iter_mut()
+            .map(|widget| {
+                let system = units.get(widget.name()).copied();
+                with_unit_system(system, || widget.summary(width))
+            })
+            .collect()
+    }
+
+    /// Handle a key press
+    ///
+    /// Only unrecoverable errors are returned; a failed reload keeps the
+    /// current dashboard.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<Flow, DevdashError> {
+        if let Some(prompt) = self.prompt.take() {
+            return Ok(self.answer(prompt, key));
+        }
+        if let Some(onboarding) = &mut self.onboarding {
+            match onboarding.handle_key(&key) {
+                Outcome::Open => {}
+                Outcome::Skipped => {
+                    self.onboarding = None;
+                    self.notify(
+                        "Showing the default dashboard; `devdash init` writes a config file",
+                    );
+                }
+                Outcome::Written(path) => {
+                    self.onboarding = None;
+                    match self.reload() {
+                        Ok(()) => self.notify(format!(
+                            "Wrote {}; rearrange the widgets and press {} to save the layout",
+                            path.display(),
+                            self.keymap.keys("write_layout")
+                        )),
+                        Err(e) if e.is_recoverable() => self.notify(e.to_string()),
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            return Ok(Flow::Continue);
+        }
+        if let Some(palette) = &mut self.palette {
+            match palette.handle_key(&key) {
+                PaletteResult::Open => {}
+                PaletteResult::Closed => self.palette = None,
+                PaletteResult::Run(request) => {
+                    self.palette = None;
+                    self.event_bus.publish(BusEvent::new(ACTION_TOPIC, request));
+                    return Ok(self.check_actions()?.unwrap_or(Flow::Continue));
+                }
+            }
+            return Ok(Flow::Continue);
+        }
+
+        if let Some(action) = self.keymap.action(&key)
+            && let Some(flow) = self.run_action(action)?
+        {
+            return Ok(flow);
+        }
+        // Pass event only to focused widget
+        if let Some(focused) = self.widgets.get_mut(self.focused) {
+            focused.handle_event(Event::Key(key));
+        }
+        self.sync_links();
+        Ok(Flow::Continue)
+    }
+
+    /// Carry out one of [`GLOBAL_ACTIONS`]
+    ///
+    /// Returns `None` if the action doesn't apply now, e.g. `dismiss` with
+    /// nothing to dismiss, so its key goes to the focused widget instead.
+    /// Only unrecoverable errors are returned.
+    fn run_action(&mut self, action: &str) -> Result<Option<Flow>, DevdashError> {
+        if GLOBAL_ACTIONS
+            .iter()
+            .any(|a| a.name == action && !a.is_enabled())
+        {
+            return Ok(None);
+        }
+        match action {
+            "quit" if self.layout_changed() => self.prompt = Some(Prompt::Quit),
+            "quit" => return Ok(Some(Flow::Quit)),
+            "write_layout" if self.layout_changed() => match config_path(self.config.as_deref()) {
+                Some(path) => self.prompt = Some(Prompt::WriteLayout(path)),
+                None => self.notify("No config directory to write the layout to"),
+            },
+            "write_layout" => self.notify("The layout matches the config"),
+            "swap_prev" if self.focused > 0 => self.swap_focused(self.focused - 1),
+            "swap_next" if self.focused + 1 < self.widgets.len() => {
+                self.swap_focused(self.focused + 1)
+            }
+            "hide" => self.hide_focused(),
+            "zoom" => self.zoomed = !self.zoomed,
+            "export_frame" => self.export_frame = true,
+            "grow_width" => self.resize_focused(Direction::Horizontal, RESIZE_STEP),
+            "shrink_width" => self.resize_focused(Direction::Horizontal, -RESIZE_STEP),
+            "grow_height" => self.resize_focused(Direction::Vertical, RESIZE_STEP),
+            "shrink_height" => self.resize_focused(Direction::Vertical, -RESIZE_STEP),
+            "reload" => match self.reload() {
+                Ok(()) => {}
+                Err(e) if e.is_recoverable() => {
+                    eprintln!("Config reload failed: {}. Keeping old config.", e);
+                }
+                Err(e) => return Err(e),
+            },
+            "dismiss" if self.show_help => self.show_help = false,
+            "dismiss" if self.show_alert_history => self.show_alert_history = false,
+            "dismiss" if self.alerts.dismiss(Instant::now()) => {}
+            "alert_history" => self.show_alert_history = !self.show_alert_history,
+            "help" => self.show_help = !self.show_help,
+            "palette" => self.palette = Some(CommandPalette::new(self.palette_entries())),
+            "focus_next" => {
+                if !self.widgets.is_empty() {
+                    self.focused = (self.focused + 1) % self.widgets.len();
+                }
+            }
+            _ => return Ok(None),
+        }
+        Ok(Some(Flow::Continue))
+    }
+
+    /// Everything the command palette offers: the dashboard's actions,
+    /// switching to another dashboard, and the widgets' actions, the
+    /// focused widget's first
+    fn palette_entries(&self) -> Vec<PaletteEntry> {
+        let mut entries: Vec<PaletteEntry> = GLOBAL_ACTIONS
+            .iter()
+            .filter(|action| action.name != "palette" && action.is_enabled())
+            .map(|action| {
+                PaletteEntry::new(
+                    action.description,
+                    self.keymap.keys(action.name),
+                    ActionRequest::dashboard(action.name),
+                )
+            })
+            .collect();
+
+        // The config may have changed since it was loaded
+        if let Ok(config) = self.load_config() {
+            entries.extend(
+                config
+                    .dashboard
+                    .iter()
+                    .filter(|dashboard| dashboard.name != self.dashboard_name)
+                    .map(|dashboard| {
+                        PaletteEntry::new(
+                            format!("Switch to dashboard {}", dashboard.name),
+                            "",
+                            ActionRequest::dashboard(SWITCH_DASHBOARD)
+                                .with_argument(&dashboard.name),
+                        )
+                    }),
+            );
+        }
+
+        // Plugin widgets are loaded once, so only built-in ones can be added
+        if let Some(widget) = self.widgets.get(self.focused)
+            && !read_only()
+            && self.can_add(widget.name())
+        {
+            entries.push(PaletteEntry::new(
+                format!("Clone widget {}", widget.name()),
+                "",
+                ActionRequest::dashboard(CLONE_WIDGET),
+            ));
+        }
+        let mut names = if read_only() {
+            Vec::new()
+        } else {
+            self.registry.list_widgets()
+        };
+        names.sort();
+        entries.extend(names.into_iter().map(|name| {
+            PaletteEntry::new(
+                format!("Add widget {}", name),
+                "",
+                ActionRequest::dashboard(ADD_WIDGET).with_argument(name),
+            )
+        }));
+
+        let mut widgets: Vec<&WidgetContainer> = self.widgets.iter().collect();
+        if self.focused < widgets.len() {
+            let focused = widgets.remove(self.focused);
+            widgets.insert(0, focused);
+        }
+        for widget in widgets {
+            entries.extend(
+                widget
+                    .actions()
+                    .into_iter()
+                    .filter(|action| !NAVIGATION_ACTIONS.contains(&action.name))
+                    .map(|action| {
+                        PaletteEntry::new(
+                            format!("{}: {}", widget.name(), action.description),
+                            widget.keys(action.name),
+                            ActionRequest::widget(widget.name(), action.name),
+                        )
+                    }),
+            );
+        }
+        entries
+    }
+
+    /// Carry out the actions requested on the bus since the last check, by
+    /// the command palette or anything else publishing [`ActionRequest`]s
+    ///
+    /// Returns `None` if there were none. Only unrecoverable errors are
+    /// returned.
+    pub fn check_actions(&mut self) -> Result<Option<Flow>, DevdashError> {
+        let requests: Vec<_> = self
+            .action_events
+            .try_iter()
+            .filter_map(|event| event.payload.downcast::<ActionRequest>())
+            .collect();
+        let mut flow = None;
+        for request in requests {
+            match self.run_request(&request)? {
+                Some(Flow::Quit) => return Ok(Some(Flow::Quit)),
+                result => flow = flow.or(result),
+            }
+        }
+        Ok(flow)
+    }
+
+    fn run_request(&mut self, request: &ActionRequest) -> Result<Option<Flow>, DevdashError> {
+        let Some(name) = &request.widget else {
+            if request.action == SWITCH_DASHBOARD
+                && let Some(dashboard) = &request.argument
+            {
+                self.switch_dashboard(dashboard)?;
+                return Ok(Some(Flow::Continue));
+            }
+            if request.action == ADD_WIDGET
+                && let Some(name) = &request.argument
+            {
+                self.add_widget(ConfigLayoutItem::Widget {
+                    name: name.clone(),
+                    host: None,
+                    constraint: ConfigConstraint::default(),
+                    style: WidgetStyle::default(),
+                });
+                return Ok(Some(Flow::Continue));
+            }
+            if request.action == FOCUS_WIDGET
+                && let Some(name) = &request.argument
+            {
+                match self.widgets.iter().position(|w| w.name() == name) {
+                    Some(i) => self.focused = i,
+                    None => self.notify(format!("No {} widget on this dashboard", name)),
+                }
+                return Ok(Some(Flow::Continue));
+            }
+            if request.action == CLONE_WIDGET {
+                let focused = flatten_layout_items(&self.layout_config)
+                    .into_iter()
+                    .filter(|item| matches!(item, ConfigLayoutItem::Widget { .. }))
+                    .nth(self.focused)
+                    .cloned();
+                if let Some(item) = focused {
+                    self.add_widget(item);
+                }
+                return Ok(Some(Flow::Continue));
+            }
+            return self.run_action(&request.action);
+        };
+
+        // Prefer the focused widget when several share the name
+        let index = match self.widgets.get(self.focused) {
+            Some(widget) if widget.name() == name => Some(self.focused),
+            _ => self.widgets.iter().position(|w| w.name() == name),
+        };
+        let Some(i) = index else {
+            self.notify(format!("No {} widget on this dashboard", name));
+            return Ok(Some(Flow::Continue));
+        };
+        self.widgets[i].handle_event(Event::Action(request.action.clone()));
+        if i == self.focused {
+            self.sync_links();
+        }
+        Ok(Some(Flow::Continue))
+    }
+
+    /// Handle a mouse event: a left click focuses the widget under the
+    /// pointer, and clicks, drags and wheel scrolls go to that widget;
+    /// dragging the border between two widgets resizes them
+    ///
+    /// Returns true if anything changed. The mouse is ignored while a
+    /// prompt, the alert history, the key bindings, the command palette or
+    /// the first-run setup are open.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> bool {
+        if matches!(mouse.kind, MouseEventKind::Moved)
+            || self.prompt.is_some()
+            || self.show_alert_history
+            || self.show_help
+            || self.palette.is_some()
+            || self.onboarding.is_some()
+        {
+            return false;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.drag = self.border_at(position);
+                if let Some(drag) = self.drag {
+                    self.focused = drag.widget;
+                    return true;
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.drag.is_some() => {
+                return self.drag_border(position);
+            }
+            MouseEventKind::Up(MouseButton::Left) if self.drag.take().is_some() => return false,
+            _ => {}
+        }
+        let Some(i) = self.widget_areas.iter().position(|a| a.contains(position)) else {
+            return false;
+        };
+        let Some(widget) = self.widgets.get_mut(i) else {
+            return false;
+        };
+
+        let mut changed = false;
+        if matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left)) && i != self.focused {
+            self.focused = i;
+            changed = true;
+        }
+        if widget.handle_mouse(mouse, self.widget_areas[i]) == EventResult::Consumed {
+            changed = true;
+        }
+        if i == self.focused {
+            self.sync_links();
+        }
+        changed
+    }
+
+    /// The border shared by the widget under `position` and the one right
+    /// of or below it, if `position` is on it; none can be dragged in
+    /// kiosk mode
+    fn border_at(&self, position: Position) -> Option<Drag> {
+        if self.zoomed || read_only() {
+            return None;
+        }
+        let on_screen = |x, y| {
+            self.widget_areas
+                .iter()
+                .any(|area| area.contains(Position::new(x, y)))
+        };
+        let widget = self
+            .widget_areas
+            .iter()
+            .position(|area| area.contains(position))?;
+        let area = self.widget_areas[widget];
+        if position.x + 1 == area.right() && on_screen(area.right(), position.y) {
+            Some(Drag {
+                widget,
+                direction: Direction::Horizontal,
+                position: position.x,
+            })
+        } else if position.y + 1 == area.bottom() && on_screen(position.x, area.bottom()) {
+            Some(Drag {
+                widget,
+                direction: Direction::Vertical,
+                position: position.y,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Move the border being dragged to follow the pointer
+    fn drag_border(&mut self, position: Position) -> bool {
+        let Some(drag) = &mut self.drag else {
+            return false;
+        };
+        let to = match drag.direction {
+            Direction::Horizontal => position.x,
+            Direction::Vertical => position.y,
+        };
+        let delta = to as i32 - drag.position as i32;
+        drag.position = to;
+        if delta == 0
+            || !self
+                .layout_config
+                .move_border(drag.widget, drag.direction, delta, self.layout_area)
+        {
+            return false;
+        }
+        self.layout = self.layout_config.to_layout();
+        true
+    }
+
+    /// The dashboard's key bindings and the focused widget's, for the help
+    /// panel
+    fn help_sections(&self) -> Vec<Section> {
+        let mut sections = vec![(
+            "Dashboard".to_string(),
+            self.keymap.bindings(GLOBAL_ACTIONS),
+        )];
+        if let Some(widget) = self.widgets.get(self.focused) {
+            sections.push((widget.name().to_string(), widget.key_bindings()));
+        }
+        sections
+    }
+
+    /// Handle the key answering `prompt`; any key but the ones asked for
+    /// cancels
+    fn answer(&mut self, prompt: Prompt, key: KeyEvent) -> Flow {
+        match (prompt, key.code, self.keymap.action(&key)) {
+            (Prompt::WriteLayout(path), KeyCode::Char('y'), _) => {
+                self.write_layout(path);
+            }
+            (Prompt::Quit, _, Some("quit")) => return Flow::Quit,
+            (Prompt::Quit, _, Some("write_layout")) => {
+                if let Some(path) = config_path(self.config.as_deref())
+                    && self.write_layout(path)
+                {
+                    return Flow::Quit;
+                }
+            }
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    /// Rebind keys from the config's `[keys]` section: the dashboard's
+    /// actions from its top-level entries, each widget's from its table
+    ///
+    /// Invalid remaps are reported and leave the keys they would change as
+    /// they were.
+    pub fn remap_keys(&mut self, keys: &toml::Table) {
+        self.keymap = Keymap::new(GLOBAL_ACTIONS);
+        if let Err(e) = self.keymap.remap(keys) {
+            eprintln!("Warning: [keys]: {}", e);
+        }
+        for widget in self.widgets.iter_mut() {
+            if let Some(toml::Value::Table(widget_keys)) = keys.get(widget.name())
+                && let Err(e) = widget.remap_keys(widget_keys)
+            {
+                eprintln!("Warning: [keys.{}]: {}", widget.name(), e);
+            }
+        }
+    }
+
+    /// Open the first-run setup, which writes the config file at `path`
+    pub fn start_onboarding(&mut self, path: PathBuf) {
+        self.onboarding = Some(Onboarding::new(path, &self.dashboard_name));
+    }
+
+    /// Apply the `[statusbar]` section of `config`
+    ///
+    /// An invalid section is reported and the default bar shown.
+    pub fn configure_status_bar(&mut self, config: &ConfigFile) {
+        self.status_bar = StatusBarConfig::from_config(config).unwrap_or_else(|e| {
+            eprintln!("Warning: invalid [statusbar]: {}", e);
+            StatusBarConfig::default()
+        });
+    }
+
+    /// Apply the `[[alerts]]` rules and `[notifications]` settings of
+    /// `config`
+    ///
+    /// Invalid rules are reported and none checked; invalid notification
+    /// settings are reported and notifications left off.
+    pub fn configure_alert_rules(&mut self, config: &ConfigFile) {
+        let rules = AlertRule::from_config(config).unwrap_or_else(|e| {
+            eprintln!("Warning: invalid [[alerts]]: {}", e);
+            Vec::new()
+        });
+        self.alert_watcher.set_rules(&self.event_bus, rules);
+        let notifications = NotificationConfig::from_config(config).unwrap_or_else(|e| {
+            eprintln!("Warning: invalid [notifications]: {}", e);
+            NotificationConfig::default()
+        });
+        self.alert_watcher.set_notifications(notifications);
+    }
+
+    /// Whether the layout was changed since it was loaded or written
+    pub fn layout_changed(&self) -> bool {
+        self.layout_config != self.saved_layout
+    }
+
+    /// Write the current layout to the config file at `path`; returns true
+    /// if it was written
+    fn write_layout(&mut self, path: PathBuf) -> bool {
+        match ConfigFile::write_layout(&path, &self.dashboard_name, &self.layout_config) {
+            Ok(()) => {
+                self.saved_layout = self.layout_config.clone();
+                self.notify(format!("Layout written to {}", path.display()));
+                true
+            }
+            Err(e) => {
+                self.notify(e.to_string());
+                false
+            }
+        }
+    }
+
+    fn notify(&mut self, message: impl Into<String>) {
+        self.notice = Some((message.into(), Instant::now()));
+    }
+
+    /// Text and style of the bottom line: a prompt, a recent notice, or a
+    /// reminder that the layout changed
+    fn bottom_line(&self) -> Option<(String, Style)> {
+        let question = Style::default().fg(Color::Black).bg(Color::Yellow);
+        match &self.prompt {
+            Some(Prompt::WriteLayout(path)) => {
+                return Some((
+                    format!(" Write the layout to {}? (y/n)", path.display()),
+                    question,
+                ));
+            }
+            Some(Prompt::Quit) => {
+                return Some((
+                    format!(
+                        " The layout changed: {} to write it and quit, {} to quit anyway, any other key to stay",
+                        self.keymap.keys("write_layout"),
+                        self.keymap.keys("quit")
+                    ),
+                    question,
+                ));
+            }
+            None => {}
+        }
+        if let Some((notice, at)) = &self.notice
+            && at.elapsed() < NOTICE_DURATION
+        {
+            return Some((format!(" {}", notice), Style::default().fg(Color::Cyan)));
+        }
+        if self.layout_changed() {
+            return Some((
+                format!(
+                    " Layout changed ({} to write it to the config)",
+                    self.keymap.keys("write_layout")
+                ),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        self.zoomed.then(|| {
+            (
+                format!(
+                    " Zoomed ({} to restore the layout)",
+                    self.keymap.keys("zoom")
+                ),
+                Style::default().fg(Color::DarkGray),
+            )
+        })
+    }
+
+    /// Exchange the focused widget with the one in slot `other`, keeping
+    /// focus on it
+    fn swap_focused(&mut self, other: usize) {
+        self.layout_config.swap_widgets(self.focused, other);
+        self.widgets.swap(self.focused, other);
+        self.focused = other;
+    }
+
+    /// Grow the focused widget by `delta` cells, or shrink it if negative
+    fn resize_focused(&mut self, direction: Direction, delta: i32) {
+        if self
+            .layout_config
+            .resize_widget(self.focused, direction, delta, self.layout_area)
+        {
+            self.layout = self.layout_config.to_layout();
+        }
+    }
+
+    /// Whether a widget called `name` can be created at runtime
+    fn can_add(&self, name: &str) -> bool {
+        self.registry.list_widgets().iter().any(|n| *n == name)
+    }
+
+    /// Put a widget made from `item` beside the focused one and focus it
+    ///
+    /// The focused slot is split along its longer side. The widget gets the
+    /// dashboard's settings for its name, so a clone is configured like the
+    /// original; the layout change is kept until written or reloaded, like
+    /// the other layout edits.
+    fn add_widget(&mut self, item: ConfigLayoutItem) {
+        let ConfigLayoutItem::Widget { name, .. } = &item else {
+            return;
+        };
+        let name = name.clone();
+        if read_only() {
+            self.notify("The layout can't be changed in kiosk mode");
+            return;
+        }
+        if !self.can_add(&name) {
+            self.notify(format!("The {} widget can't be added at runtime", name));
+            return;
+        }
+
+        // The config may have changed since it was loaded
+        let widgets = self
+            .load_config()
+            .ok()
+            .and_then(|config| {
+                config
+                    .dashboard
+                    .into_iter()
+                    .find(|d| d.name == self.dashboard_name)
+            })
+            .map(|dashboard| dashboard.widgets)
+            .unwrap_or_default();
+        let single = Dashboard {
+            name: self.dashboard_name.clone(),
+            layout: ConfigLayout::Layout {
+                direction: Direction::Horizontal,
+                items: vec![item.clone()],
+            },
+            widgets,
+        };
+        let Some(mut widget) = build_widgets(
+            &single,
+            &mut self.registry,
+            &self.event_bus,
+            &self.hosts,
+            self.tick_rate,
+        )
+        .pop() else {
+            return;
+        };
+
+        let area = self
+            .widget_areas
+            .get(self.focused)
+            .copied()
+            .unwrap_or_default();
+        // Cells are about twice as tall as they are wide
+        let direction = if area.width >= area.height * 2 {
+            Direction::Horizontal
+        } else {
+            Direction::Vertical
+        };
+        let index = if self
+            .layout_config
+            .split_widget(self.focused, direction, item.clone())
+        {
+            self.focused + 1
+        } else {
+            // Nothing to split on an empty dashboard
+            let ConfigLayout::Layout { items, .. } = &mut self.layout_config;
+            items.push(item);
+            self.widgets.len()
+        };
+
+        widget.mount();
+        self.widgets.insert(index, widget);
+        self.leaves = build_leaves(&self.layout_config);
+        self.layout = self.layout_config.to_layout();
+        self.focused = index;
+        self.zoomed = false;
+        self.notify(format!(
+            "Added {} ({} writes it to the config)",
+            name,
+            self.keymap.keys("write_layout")
+        ));
+    }
+
+    /// Take the focused widget out of the layout until the config is
+    /// reloaded
+    fn hide_focused(&mut self) {
+        if self.widgets.len() < 2 {
+            return;
+        }
+        let mut widget = self.widgets.remove(self.focused);
+        if let Some(state) = widget.save_state() {
+            self.session
+                .set_widget(&self.dashboard_name, widget.name(), state);
+        }
+        widget.unmount();
+
+        self.layout_config.remove_widget(self.focused);
+        self.leaves = build_leaves(&self.layout_config);
+        self.layout = self.layout_config.to_layout();
+        self.focused = self.focused.min(self.widgets.len() - 1);
+    }
+
+    /// Earliest time any widget wants to be updated
+    pub fn next_update(&self) -> Option<Instant> {
+        self.widgets.iter().filter_map(|w| w.next_update()).min()
+    }
+
+    /// The start of the next second, if the status bar shows a clock
+    pub fn next_clock_tick(&self) -> Option<Instant> {
+        self.status_bar.shows_clock().then(|| {
+            let since_epoch = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            Instant::now() + Duration::from_nanos(1_000_000_000 - since_epoch.subsec_nanos() as u64)
+        })
+    }
+
+    /// Whether the status bar's clock changed since it was drawn
+    pub fn clock_changed(&self) -> bool {
+        self.status_bar.shows_clock() && self.status_bar.clock(SystemTime::now()) != self.clock
+    }
+
+    /// Update the widgets whose interval has elapsed
+    ///
+    /// Updates run one after another on the caller's task; see `run_loop`.
+    /// Returns true if any widget was updated.
+    pub fn update_due(&mut self, now: Instant) -> bool {
+        let mut updated = false;
+        for widget in self.widgets.iter_mut().filter(|w| w.is_due(now)) {
+            widget.update();
+            updated = true;
+        }
+        if updated {
+            self.sync_links();
+            self.report_to_api(now);
+        }
+        updated
+    }
+
+    /// Each widget with its summary, as if `width` columns wide
+    pub fn widget_reports(&mut self, width: u16, now: Instant) -> Vec<WidgetReport> {
+        let summaries = self.summaries(width);
+        self.widgets
+            .iter()
+            .zip(summaries)
+            .map(|(widget, summary)| WidgetReport {
+                name: widget.name().to_string(),
+                host: widget.host().map(str::to_string),
+                summary,
+                stale_secs: widget
+                    .data_status()
+                    .and_then(|status| status.stale_for(now))
+                    .map(|age| age.as_secs()),
+            })
+            .collect()
+    }
+
+    /// Whether any widget has a poll in flight
+    pub fn is_refreshing(&self) -> bool {
+        self.widgets
+            .iter()
+            .any(|w| w.data_status().is_some_and(|status| status.refreshing))
+    }
+
+    /// Serve the widgets' summaries on `api`, refreshed as widgets update
+    pub fn set_api(&mut self, api: Api) {
+        self.api = Some((api, None));
+        self.report_to_api(Instant::now());
+    }
+
+    /// Hand the API fresh widget summaries, at most every
+    /// [`API_REPORT_INTERVAL`] since summarizing may render the widgets
+    fn report_to_api(&mut self, now: Instant) {
+        let Some((_, reported)) = &self.api else {
+            return;
+        };
+        if reported.is_some_and(|at| now.duration_since(at) < API_REPORT_INTERVAL) {
+            return;
+        }
+        let widgets = self.widget_reports(API_SUMMARY_WIDTH, now);
+        if let Some((api, reported)) = &mut self.api {
+            api.set_widgets(&self.dashboard_name, widgets);
+            *reported = Some(now);
+        }
+    }
+
+    /// Share the focused widget's selection with the widgets linked to it
+    ///
+    /// Only the focused widget drives its group, so linked widgets that
+    /// can't follow a selection don't bounce it back. The selection is also
+    /// published on the bus as `link.<group>`.
+    fn sync_links(&mut self) {
+        let Some(focused) = self.widgets.get(self.focused) else {
+            return;
+        };
+        let (Some(group), Some(selection)) = (self.links.get(focused.name()), focused.selection())
+        else {
+            return;
+        };
+        if self.link_selections.get(group) == Some(&selection) {
+            return;
+        }
+
+        let group = group.clone();
+        self.event_bus
+            .publish(BusEvent::new(format!("link.{}", group), selection.clone()));
+        for (i, widget) in self.widgets.iter_mut().enumerate() {
+            if i != self.focused && self.links.get(widget.name()) == Some(&group) {
+                widget.handle_event(Event::Linked(selection.clone()));
+            }
+        }
+        self.link_selections.insert(group, selection);
+    }
+
+    /// Apply selections published in link groups by other processes
+    ///
+    /// Returns true if any were applied.
+    pub fn check_links(&mut self) -> bool {
+        let mut applied = false;
+        for event in self.link_events.try_iter() {
+            let (Some(group), Some(selection)) = (
+                event.topic.strip_prefix("link."),
+                event.payload.downcast::<Selection>(),
+            ) else {
+                continue;
+            };
+            // Our own selections come back from the bus too
+            if self.link_selections.get(group) == Some(&*selection) {
+                continue;
+            }
+            for widget in self.widgets.iter_mut() {
+                if self.links.get(widget.name()).map(String::as_str) == Some(group) {
+                    widget.handle_event(Event::Linked((*selection).clone()));
+                    applied = true;
+                }
+            }
+        }
+        applied
+    }
+
+    /// Record alerts raised since the last check, and take down toasts
+    /// that timed out
+    ///
+    /// Returns true if the alert banner or toasts changed.
+    pub fn check_alerts(&mut self) -> bool {
+        self.alert_watcher.drain(&mut self.alerts) | self.alerts.expire_toasts(Instant::now())
+    }
+
+    /// Reload plugins whose files changed, and say on the bottom line why
+    /// one couldn't be
+    ///
+    /// Returns true if any widget was replaced or a failure shown.
+    pub fn check_plugins(&mut self) -> bool {
+        let reloads = self.plugin_manager.check_for_changes(&mut self.widgets);
+        for (name, result) in &reloads {
+            if let Err(e) = result {
+                self.notify(format!("Kept the old build of plugin {}: {}", name, e));
+            }
+        }
+        !reloads.is_empty()
+    }
+
+    /// The config file in use, loaded again
+    fn load_config(&self) -> Result<ConfigFile, ConfigError> {
+        match &self.config {
+            Some(path) => ConfigFile::load_from(path),
+            None => ConfigFile::load(),
+        }
+    }
+
+    /// Slot of the widget last focused on dashboard `name`: the same slot
+    /// if it still holds that widget, otherwise the first widget of that
+    /// name, otherwise the first slot
+    fn remembered_focus(&self, name: &str) -> usize {
+        let Some((slot, widget)) = self.dashboard_focus.get(name) else {
+            return 0;
+        };
+        match self.widgets.get(*slot) {
+            Some(w) if w.name() == widget => *slot,
+            _ => self
+                .widgets
+                .iter()
+                .position(|w| w.name() == widget)
+                .unwrap_or(0),
+        }
+    }
+
+    fn reload(&mut self) -> Result<(), DevdashError> {
+        let name = self.dashboard_name.clone();
+        self.load_dashboard(&name)
+    }
+
+    /// Replace the dashboard with the one called `name`, reporting failure
+    /// on the bottom line
+    ///
+    /// Only unrecoverable errors are returned.
+    fn switch_dashboard(&mut self, name: &str) -> Result<(), DevdashError> {
+        match self.load_dashboard(name) {
+            Ok(()) => self.notify(format!("Switched to dashboard {}", name)),
+            Err(e) if e.is_recoverable() => self.notify(e.to_string()),
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+
+    /// Load the config again and show its dashboard called `name`
+    fn load_dashboard(&mut self, name: &str) -> Result<(), DevdashError> {
+        let config = self.load_config()?;
+        let dashboard = config.require_dashboard(name)?;
+        apply_global_settings(&config);
+
+        // Clear existing plugin widgets from registry
+        self.registry.clear_widgets();
+
+        // Reload plugins and re-register them in the registry
+        self.plugin_manager.set_settings(widget_settings(dashboard));
+        let plugin_widgets = self.plugin_manager.load_all().unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: Failed to reload plugins: {}. Continuing without plugins.",
+                e
+            );
+            Vec::new()
+        });
+        for (name, widget) in plugin_widgets {
+            self.registry.register_widget(&name, Box::new(widget));
+        }
+
+        self.hosts = Hosts::connect(&config, dashboard, self.connect.as_deref());
+        let widgets = build_widgets(
+            dashboard,
+            &mut self.registry,
+            &self.event_bus,
+            &self.hosts,
+            self.tick_rate,
+        );
+
+        // Swap in the new widgets and focus the one focused when the new
+        // dashboard was last shown; state, such as the rows selected and
+        // scrolled to, is saved under the old dashboard's name and restored
+        // under the new one's
+        if let Some(widget) = self.widgets.get(self.focused) {
+            self.dashboard_focus.insert(
+                self.dashboard_name.clone(),
+                (self.focused, widget.name().to_string()),
+            );
+        }
+        self.unmount();
+        self.dashboard_name = dashboard.name.clone();
+        self.widgets = widgets;
+        self.leaves = build_leaves(&dashboard.layout);
+        self.layout = dashboard.layout.to_layout();
+        self.layout_config = dashboard.layout.clone();
+        self.saved_layout = dashboard.layout.clone();
+        self.focused = self.remembered_focus(&dashboard.name);
+        self.zoomed = false;
+        self.units = widget_units(dashboard);
+        self.links = widget_links(dashboard);
+        self.link_selections.clear();
+        self.remap_keys(&config.keys);
+        self.configure_status_bar(&config);
+        self.configure_alert_rules(&config);
+        self.mount();
+        Ok(())
+    }
+}
+
+/// The config file the layout is written to: the one in use, or the
+/// user's if there is none
+fn config_path(config: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = config {
+        return Some(path.to_path_buf());
+    }
+    match ConfigFile::find() {
+        Ok(Some(path)) => Some(path),
+        _ => ConfigFile::search_paths().ok().map(|[_, user]| user),
+    }
+}
+
+/// The layout leaves of a dashboard, with labels ready to draw
+fn build_leaves(layout: &ConfigLayout) -> Vec<Leaf> {
+    flatten_layout_items(layout)
+        .into_iter()
+        .map(|item| match item {
+            ConfigLayoutItem::Widget { .. } | ConfigLayoutItem::Layout { .. } => Leaf::Widget,
+            ConfigLayoutItem::Spacer { .. } => Leaf::Spacer,
+            ConfigLayoutItem::Label { text, style, .. } => {
+                Leaf::Label(Box::new(label(text, style)))
+            }
+        })
+        .collect()
+}
+
+fn label(text: &str, style: &LabelStyle) -> Paragraph<'static> {
+    let mut text_style = Style::default();
+    if let Some(color) = &style.color {
+        match color.parse::<Color>() {
+            Ok(color) => text_style = text_style.fg(color),
+            Err(_) => eprintln!("Warning: label '{}': unknown color '{}'", text, color),
+        }
+    }
+    if style.bold {
+        text_style = text_style.add_modifier(Modifier::BOLD);
+    }
+    Paragraph::new(text.to_string())
+        .style(text_style)
+        .alignment(style.alignment.map_or(Alignment::Left, Alignment::from))
+}
+
+/// Create the widgets of a dashboard, substituting error widgets for failures
+fn build_widgets(
+    dashboard: &Dashboard,
+    registry: &mut WidgetRegistry,
+    event_bus: &EventBus,
+    hosts: &Hosts,
+    tick_rate: Duration,
+) -> Vec<WidgetContainer> {
+    flatten_layout_items(&dashboard.layout)
+        .into_iter()
+        .filter_map(|item| match item {
+            ConfigLayoutItem::Widget {
+                name, host, style, ..
+            } => Some((name, host, *style)),
+            _ => None,
+        })
+        .map(|(name, host, style)| {
+            let no_settings = WidgetSettings::new();
+            let settings = dashboard.widget_settings(name).unwrap_or(&no_settings);
+            // Widgets for another host are created against its bus, and
+            // those that can show one against the `--connect` agent's
+            let default = hosts
+                .default_bus()
+                .filter(|_| host.is_none() && HOST_WIDGETS.contains(&name.as_str()));
+            let host = host
+                .as_deref()
+                .or(default.as_ref().map(|(address, _)| *address));
+            let bus = match (host, default) {
+                (_, Some((_, bus))) => bus,
+                (None, None) => Ok(event_bus),
+                (Some(_), None) if !HOST_WIDGETS.contains(&name.as_str()) => Err(format!(
+                    "The {} widget can't show another host; use one of: {}",
+                    name,
+                    HOST_WIDGETS.join(", ")
+                )),
+                (Some(host), None) => hosts.bus(host),
+            };
+            let widget = match bus {
+                Ok(bus) => registry
+                    .create(name, bus, tick_rate, settings)
+                    .unwrap_or_else(|e| match e {
+                        DevdashError::UnknownWidget(_) => Box::new(ErrorWidget::plugin_error(name)),
+                        e => Box::new(ErrorWidget::new(e.to_string())),
+                    }),
+                Err(e) => Box::new(ErrorWidget::new(e)),
+            };
+            let container = WidgetContainer::new(name.clone(), widget).with_style(style);
+            match host {
+                Some(host) => container.with_host(host),
+                None => container,
+            }
+        })
+        .collect()
+}
+
+/// Apply the top-level settings of a config file
+///
+/// Invalid values are reported and leave the setting unchanged.
+pub fn apply_global_settings(config: &ConfigFile) {
+    match parse_units(config.settings.get("units")) {
+        Ok(Some(units)) => set_unit_system(units),
+        Ok(None) => set_unit_system(UnitSystem::default()),
+        Err(e) => eprintln!("Warning: {}", e),
+    }
+
+    let openers = config
+        .settings
+        .get("openers")
+        .map_or(Ok(Openers::default()), |value| value.clone().try_into());
+    match openers {
+        Ok(openers) => set_openers(openers),
+        Err(e) => eprintln!("Warning: invalid [openers]: {}", e.message()),
+    }
+
+    match config.settings.get("theme").map(Theme::from_config) {
+        None => set_theme(Theme::default()),
+        Some(Ok(theme)) => set_theme(theme),
+        Some(Err(e)) => eprintln!("Warning: invalid [theme]: {}", e),
+    }
+
+    let time_format = match config.settings.get("time_format") {
+        None => Ok(None),
+        Some(toml::Value::String(format)) => Ok(Some(format.as_str())),
+        Some(other) => Err(format!("time_format must be a string, got {}", other)),
+    };
+    if let Err(e) = time_format.and_then(set_time_format) {
+        eprintln!("Warning: {}", e);
+    }
+}
+
+/// Settings of the widgets in a dashboard, by widget name, for the plugins
+/// that accept them
+pub fn widget_settings(dashboard: &Dashboard) -> HashMap<String, WidgetSettings> {
+    dashboard
+        .widgets
+        .iter()
+        .filter_map(|widget| Some((widget.name.clone(), widget.settings.as_table()?.clone())))
+        .collect()
+}
+
+/// `units` settings of the widgets in a dashboard, by widget name
+fn widget_units(dashboard: &Dashboard) -> HashMap<String, UnitSystem> {
+    dashboard
+        .widgets
+        .iter()
+        .filter_map(|widget| match parse_units(widget.settings.get("units")) {
+            Ok(units) => units.map(|units| (widget.name.clone(), units)),
+            Err(e) => {
+                eprintln!("Warning: widget '{}': {}", widget.name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// `link` settings of the widgets in a dashboard, by widget name
+fn widget_links(dashboard: &Dashboard) -> HashMap<String, String> {
+    dashboard
+        .widgets
+        .iter()
+        .filter_map(|widget| match widget.settings.get("link")? {
+            toml::Value::String(group) => Some((widget.name.clone(), group.clone())),
+            other => {
+                eprintln!(
+                    "Warning: widget '{}': link must be a string, got {}",
+                    widget.name, other
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+fn parse_units(value: Option<&toml::Value>) -> Result<Option<UnitSystem>, String> {
+    match value {
+        None => Ok(None),
+        Some(toml::Value::String(s)) => s.parse().map(Some),
+        Some(other) => Err(format!("units must be a string, got {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widget_units() {
+        let config: ConfigFile = toml::from_str(
+            r#"
+            units = "decimal"
+
+            [[dashboard]]
+            name = "default"
+            layout = { type = "layout", direction = "horizontal", items = [] }
+
+            [[dashboard.widgets]]
+            name = "memory"
+            units = "binary"
+
+            [[dashboard.widgets]]
+            name = "disk"
+            units = 1000
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_units(config.settings.get("units")),
+            Ok(Some(UnitSystem::Decimal))
+        );
+        let units = widget_units(&config.dashboard[0]);
+        assert_eq!(units.get("memory"), Some(&UnitSystem::Binary));
+        assert_eq!(units.get("disk"), None);
+    }
+
+    #[test]
+    fn test_widget_links() {
+        let dashboard: Dashboard = toml::from_str(
+            r#"
+            name = "storage"
+            layout = { type = "layout", direction = "horizontal", items = [] }
+
+            [[widgets]]
+            name = "disk"
+            link = "storage"
+
+            [[widgets]]
+            name = "process"
+            link = "storage"
+
+            [[widgets]]
+            name = "cpu"
+            link = 1
+            "#,
+        )
+        .unwrap();
+
+        let links = widget_links(&dashboard);
+        assert_eq!(links.len(), 2);
+        assert_eq!(links.get("process").map(String::as_str), Some("storage"));
+    }
+
+    /// Widget drawing nothing, to put in a dashboard
+    struct Blank;
+
+    impl devdash_core::Widget for Blank {
+        fn render(&mut self, _area: Rect, _buf: &mut Buffer) {}
+    }
+
+    #[test]
+    fn test_zoom_suspends_covered_widgets() {
+        let dashboard: Dashboard = toml::from_str(
+            r#"
+            name = "zoom"
+            [layout]
+            type = "layout"
+            direction = "horizontal"
+            items = [
+                { type = "widget", name = "left" },
+                { type = "widget", name = "right" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let mut registry = WidgetRegistry::new();
+        registry.register_widget("left", Box::new(Blank));
+        registry.register_widget("right", Box::new(Blank));
+        let mut plugins = PluginManager::new();
+        plugins.set_plugin_dir(std::env::temp_dir().join("devdash-app-test-no-plugins"));
+        let hosts = Hosts::connect(&ConfigFile::default(), &dashboard, None);
+        let mut app = App::new(
+            &dashboard,
+            &CliArgs::default(),
+            EventBus::new(),
+            registry,
+            plugins,
+            hosts,
+        );
+        app.mount();
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(40, 10)).unwrap();
+        let suspended = |app: &App| -> Vec<bool> {
+            app.widgets
+                .iter()
+                .map(WidgetContainer::is_suspended)
+                .collect()
+        };
+
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert_eq!(suspended(&app), [false, false]);
+
+        app.run_action("zoom").unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert_eq!(suspended(&app), [false, true]);
+
+        app.run_action("zoom").unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert_eq!(suspended(&app), [false, false]);
+    }
+
+    #[test]
+    fn test_leaves_follow_layout_order() {
+        let dashboard: Dashboard = toml::from_str(
+            r#"
+            name = "labels"
+            [layout]
+            type = "layout"
+            direction = "vertical"
+            items = [
+                { type = "label", text = "CPU", fixed = 1, bold = true },
+                { type = "widget", name = "cpu" },
+                { type = "spacer", fixed = 1 },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let leaves = build_leaves(&dashboard.layout);
+        assert!(matches!(
+            leaves.as_slice(),
+            [Leaf::Label(_), Leaf::Widget, Leaf::Spacer]
+        ));
+    }
+}
@@ -0,0 +1,856 @@
+// devdash-cli/src/args.rs
+use crate::{
+    app::DEFAULT_TICK_RATE, frame::DEFAULT_MAX_FPS, presets::Preset, remote::DEFAULT_AGENT_ADDR,
+    shared_bus, snapshot::SnapshotFormat,
+};
+use clap::{Arg, ArgAction, ArgMatches, error::ErrorKind, value_parser};
+use devdash_core::{
+    ConfigError, ConfigFile, MIN_INTERVAL, PluginManager, parse_duration,
+    plugin_lock::TrustSettings,
+};
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+/// What to do after parsing the arguments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Show the dashboard (`devdash run`, or no subcommand)
+    Run,
+    /// Check the config file without starting (`devdash validate`)
+    Validate,
+    /// List the built-in and plugin widgets (`devdash list-widgets`)
+    ListWidgets,
+    /// Check the environment and print a report (`devdash doctor`)
+    Doctor,
+    /// Print every key binding (`devdash keys`)
+    Keys(KeysFormat),
+    /// List installed plugins and their capabilities (`devdash plugins list`)
+    Plugins,
+    /// Copy a plugin library into the plugin directory and record its
+    /// checksum (`devdash plugins install`); the library is `plugin_file`
+    InstallPlugin,
+    /// Collect in the background and serve clients (`devdash daemon`)
+    Daemon,
+    /// Show the dashboard with data from a running daemon (`devdash attach`)
+    Attach,
+    /// Write this machine's metrics events to stdout (`devdash stream`)
+    Stream,
+    /// Send this machine's metrics events to viewers connecting over TCP
+    /// (`devdash agent`)
+    Agent { listen: SocketAddr },
+    /// Write a ready-made dashboard to the config file (`devdash init`)
+    Init { preset: Preset, force: bool },
+    /// Show the dashboard, writing its events to a file (`devdash record`)
+    Record,
+    /// Show the dashboard with the events of a recording instead of live
+    /// data (`devdash replay`)
+    Replay,
+    /// Poll the dashboard's widgets once and print them (`devdash snapshot`)
+    Snapshot(SnapshotFormat),
+}
+
+/// Output format of `devdash keys`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeysFormat {
+    #[default]
+    Text,
+    Markdown,
+}
+
+/// Command line options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArgs {
+    pub command: Command,
+    /// Dashboard to display
+    pub dashboard: String,
+    /// Config file to use instead of searching for one
+    pub config: Option<PathBuf>,
+    /// Poll interval of built-in widgets without an `interval` setting
+    pub tick_rate: Duration,
+    /// Directory to load plugins from instead of `~/.devdash/plugins`
+    pub plugin_dir: Option<PathBuf>,
+    /// Append every bus event to this file as JSON lines
+    pub event_log: Option<PathBuf>,
+    /// Publish events read from this file (`-` for stdin)
+    pub inject: Option<PathBuf>,
+    /// Collect live system data; disabled with `--no-live`
+    pub live: bool,
+    /// Upper bound on redraws per second
+    pub max_fps: u32,
+    /// Print plain-text widget summaries instead of drawing the dashboard
+    pub accessible: bool,
+    /// Daemon socket; defaults to one per dashboard
+    pub socket: Option<PathBuf>,
+    /// Connect the event bus to other devdash processes through this socket
+    pub share_bus: Option<PathBuf>,
+    /// Serve the dashboard's data as JSON over HTTP on this address
+    pub api: Option<SocketAddr>,
+    /// Turn off everything that changes the system or the layout
+    pub kiosk: bool,
+    /// Draw without colors or text attributes
+    pub no_color: bool,
+    /// `host:port` of a `devdash agent` whose metrics the host widgets show
+    pub connect: Option<String>,
+    /// Write one frame of the dashboard to this file instead of showing it
+    pub export_frame: Option<PathBuf>,
+    /// Library to install with `devdash plugins install`
+    pub plugin_file: Option<PathBuf>,
+}
+
+impl Default for CliArgs {
+    fn default() -> Self {
+        Self {
+            command: Command::Run,
+            dashboard: "default".to_string(),
+            config: None,
+            tick_rate: DEFAULT_TICK_RATE,
+            plugin_dir: None,
+            event_log: None,
+            inject: None,
+            live: true,
+            max_fps: DEFAULT_MAX_FPS,
+            accessible: false,
+            socket: None,
+            share_bus: None,
+            api: None,
+            kiosk: false,
+            no_color: false,
+            connect: None,
+            export_frame: None,
+            plugin_file: None,
+        }
+    }
+}
+
+/// The command line, as `devdash --help` describes it
+///
+/// Options other than `--socket`, `--listen`, `--format`, `--preset` and
+/// `--force` are global, so they may come before or after the subcommand.
+fn cli() -> clap::Command {
+    let socket = Arg::new("socket")
+        .long("socket")
+        .value_name("PATH")
+        .value_parser(value_parser!(PathBuf))
+        .help("Daemon socket [default: one per dashboard]");
+    let recording = Arg::new("file")
+        .required(true)
+        .value_name("FILE")
+        .value_parser(value_parser!(PathBuf));
+
+    clap::Command::new("devdash")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("A terminal dashboard for developers")
+        .subcommand(clap::Command::new("run").about("Show the dashboard (the default)"))
+        .subcommand(
+            clap::Command::new("validate")
+                .about("Check the config file and the widgets it names, then exit"),
+        )
+        .subcommand(clap::Command::new("list-widgets").about("List built-in and plugin widgets"))
+        .subcommand(
+            clap::Command::new("doctor").about("Check the environment and print a report"),
+        )
+        .subcommand(
+            clap::Command::new("keys")
+                .about("Print every key binding")
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["txt", "md"])
+                        .default_value("txt")
+                        .help("Plain text or a Markdown table"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("plugins")
+                .about("List installed plugins and their capabilities")
+                // `list` is the default, so a subcommand is optional
+                .subcommand(clap::Command::new("list").about("List installed plugins"))
+                .subcommand(
+                    clap::Command::new("install")
+                        .about("Install a plugin library and record its checksum")
+                        .arg(
+                            Arg::new("file")
+                                .required(true)
+                                .value_parser(value_parser!(PathBuf))
+                                .help("The library, with its .minisig signature next to it if signed"),
+                        ),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("daemon")
+                .about("Collect in the background and serve `devdash attach`")
+                .arg(socket.clone()),
+        )
+        .subcommand(
+            clap::Command::new("attach")
+                .about("Show the dashboard with data from a running daemon")
+                .arg(socket),
+        )
+        .subcommand(
+            clap::Command::new("stream").about("Write this machine's metrics events to stdout"),
+        )
+        .subcommand(
+            clap::Command::new("agent")
+                .about("Send this machine's metrics events to `--connect` viewers")
+                .arg(
+                    Arg::new("listen")
+                        .long("listen")
+                        .value_name("ADDR")
+                        .value_parser(value_parser!(SocketAddr))
+                        .default_value(DEFAULT_AGENT_ADDR)
+                        .help("Address to accept viewers on"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("record")
+                .about("Show the dashboard, recording its events to FILE")
+                .arg(recording.clone().help("Recording to write, replacing the file")),
+        )
+        .subcommand(
+            clap::Command::new("replay")
+                .about("Show the dashboard with the events of a recording")
+                .arg(recording.help("Recording made with `devdash record` or --event-log")),
+        )
+        .subcommand(
+            clap::Command::new("snapshot")
+                .about("Poll the dashboard's widgets once and print them")
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["text", "json"])
+                        .default_value("text")
+                        .help("A summary line per widget, or JSON with their events"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("init")
+                .about("Write a ready-made dashboard to the config file")
+                .arg(
+                    Arg::new("preset")
+                        .long("preset")
+                        .required(true)
+                        .value_parser(Preset::ALL.map(Preset::name))
+                        .help("Which dashboard to write"),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help("Replace the config file if it exists"),
+                ),
+        )
+        .arg(
+            Arg::new("dashboard")
+                .long("dashboard")
+                .global(true)
+                .value_name("NAME")
+                .default_value("default")
+                .help("Dashboard to display"),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .global(true)
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .help("Config file to use instead of ./devdash.toml or the user config"),
+        )
+        .arg(
+            Arg::new("tick-rate")
+                .long("tick-rate")
+                .global(true)
+                .value_name("INTERVAL")
+                .value_parser(parse_tick_rate)
+                .help("Poll interval of widgets without an `interval` setting, e.g. 500ms [default: 1s]"),
+        )
+        .arg(
+            Arg::new("plugin-dir")
+                .long("plugin-dir")
+                .global(true)
+                .value_name("DIR")
+                .value_parser(value_parser!(PathBuf))
+                .help("Load plugins from DIR only, instead of the system, user and project directories"),
+        )
+        .arg(
+            Arg::new("event-log")
+                .long("event-log")
+                .global(true)
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .help("Append every bus event to PATH as JSON lines"),
+        )
+        .arg(
+            Arg::new("inject")
+                .long("inject")
+                .global(true)
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .help("Publish events read from PATH (- for stdin)"),
+        )
+        .arg(
+            Arg::new("no-live")
+                .long("no-live")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .requires("inject")
+                .help("Show only injected events instead of collecting live data"),
+        )
+        .arg(
+            Arg::new("max-fps")
+                .long("max-fps")
+                .global(true)
+                .value_name("FPS")
+                .value_parser(value_parser!(u32).range(1..))
+                .help(format!(
+                    "Upper bound on redraws per second [default: {}]",
+                    DEFAULT_MAX_FPS
+                )),
+        )
+        .arg(
+            Arg::new("accessible")
+                .long("accessible")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print plain-text widget summaries instead of drawing"),
+        )
+        // The path is optional, so it can only be given inline
+        .arg(
+            Arg::new("share-bus")
+                .long("share-bus")
+                .global(true)
+                .value_name("PATH")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("")
+                .help("Share links and alerts with other devdash processes"),
+        )
+        .arg(
+            Arg::new("api")
+                .long("api")
+                .global(true)
+                .value_name("ADDR")
+                .value_parser(value_parser!(SocketAddr))
+                .help("Serve the dashboard's data as JSON, e.g. on 127.0.0.1:9900"),
+        )
+        .arg(
+            Arg::new("kiosk")
+                .long("kiosk")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Read-only: no killing, restarting or layout editing"),
+        )
+        .arg(
+            Arg::new("no-color")
+                .long("no-color")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Draw without colors, as does setting NO_COLOR"),
+        )
+        .arg(
+            Arg::new("connect")
+                .long("connect")
+                .global(true)
+                .value_name("HOST:PORT")
+                .help("Show the metrics of the `devdash agent` at HOST:PORT"),
+        )
+        .arg(
+            Arg::new("export-frame")
+                .long("export-frame")
+                .global(true)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .help("Write one frame to FILE and exit; with colors if named .ans"),
+        )
+}
+
+/// `--tick-rate`: a duration as in `interval` settings, and no shorter
+fn parse_tick_rate(text: &str) -> Result<Duration, String> {
+    let rate = parse_duration(text)?;
+    if rate < MIN_INTERVAL {
+        return Err(format!("must be at least {}ms", MIN_INTERVAL.as_millis()));
+    }
+    Ok(rate)
+}
+
+impl CliArgs {
+    /// Parse the process arguments, printing help or an error and exiting
+    /// when they ask for it
+    pub fn from_env() -> Self {
+        Self::parse(std::env::args().skip(1)).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parse arguments (without the program name)
+    ///
+    /// Options accept both `--name=value` and `--name value`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, clap::Error> {
+        let matches =
+            cli().try_get_matches_from(std::iter::once("devdash".to_string()).chain(args))?;
+
+        let (command, leaf) = match matches.subcommand() {
+            None => (Command::Run, &matches),
+            Some(("run", m)) => (Command::Run, m),
+            Some(("validate", m)) => (Command::Validate, m),
+            Some(("list-widgets", m)) => (Command::ListWidgets, m),
+            Some(("doctor", m)) => (Command::Doctor, m),
+            Some(("keys", m)) => {
+                let format = match m.get_one::<String>("format").map(String::as_str) {
+                    Some("md") => KeysFormat::Markdown,
+                    _ => KeysFormat::Text,
+                };
+                (Command::Keys(format), m)
+            }
+            Some(("plugins", m)) => match m.subcommand() {
+                Some(("install", m)) => (Command::InstallPlugin, m),
+                Some((_, m)) => (Command::Plugins, m),
+                None => (Command::Plugins, m),
+            },
+            Some(("daemon", m)) => (Command::Daemon, m),
+            Some(("attach", m)) => (Command::Attach, m),
+            Some(("stream", m)) => (Command::Stream, m),
+            Some(("agent", m)) => {
+                let listen = *m
+                    .get_one::<SocketAddr>("listen")
+                    .expect("--listen has a default");
+                (Command::Agent { listen }, m)
+            }
+            Some(("init", m)) => {
+                let preset = m
+                    .get_one::<String>("preset")
+                    .and_then(|name| Preset::from_name(name))
+                    .expect("--preset is required and checked");
+                let force = m.get_flag("force");
+                (Command::Init { preset, force }, m)
+            }
+            Some(("snapshot", m)) => {
+                let format = match m.get_one::<String>("format").map(String::as_str) {
+                    Some("json") => SnapshotFormat::Json,
+                    _ => SnapshotFormat::Text,
+                };
+                (Command::Snapshot(format), m)
+            }
+            Some(("record", m)) => (Command::Record, m),
+            Some(("replay", m)) => (Command::Replay, m),
+            Some((name, _)) => unreachable!("subcommand {} is not defined", name),
+        };
+        let mut parsed = Self::from_matches(command, leaf);
+        let recording = || leaf.get_one::<PathBuf>("file").cloned();
+        match parsed.command {
+            Command::Record if parsed.event_log.is_some() => {
+                return Err(cli().error(
+                    ErrorKind::ArgumentConflict,
+                    "record already writes its events to FILE",
+                ));
+            }
+            Command::Record => parsed.event_log = recording(),
+            Command::Replay if parsed.inject.is_some() => {
+                return Err(cli().error(
+                    ErrorKind::ArgumentConflict,
+                    "replay already takes its events from FILE",
+                ));
+            }
+            Command::Replay => {
+                parsed.inject = recording();
+                parsed.live = false;
+            }
+            _ => {}
+        }
+
+        if leaf.contains_id("share-bus") && parsed.share_bus.is_none() {
+            return Err(cli().error(
+                ErrorKind::ValueValidation,
+                "no private directory for the shared bus socket; give --share-bus PATH",
+            ));
+        }
+
+        if matches!(parsed.command, Command::Snapshot(_)) && parsed.export_frame.is_some() {
+            return Err(cli().error(
+                ErrorKind::ArgumentConflict,
+                "snapshot already prints the widgets",
+            ));
+        }
+
+        if parsed.command == Command::Attach
+            && (parsed.inject.is_some() || !parsed.live || parsed.connect.is_some())
+        {
+            return Err(cli().error(
+                ErrorKind::ArgumentConflict,
+                "attach already takes its events from the daemon",
+            ));
+        }
+        Ok(parsed)
+    }
+
+    /// Read the options from the matches of the subcommand, where global
+    /// options end up
+    fn from_matches(command: Command, matches: &ArgMatches) -> Self {
+        let path = |id: &str| matches.get_one::<PathBuf>(id).cloned();
+        let defaults = Self::default();
+        Self {
+            command,
+            dashboard: matches
+                .get_one::<String>("dashboard")
+                .cloned()
+                .unwrap_or(defaults.dashboard),
+            config: path("config"),
+            tick_rate: matches
+                .get_one::<Duration>("tick-rate")
+                .copied()
+                .unwrap_or(defaults.tick_rate),
+            plugin_dir: path("plugin-dir"),
+            event_log: path("event-log"),
+            inject: path("inject"),
+            live: !matches.get_flag("no-live"),
+            max_fps: matches
+                .get_one::<u32>("max-fps")
+                .copied()
+                .unwrap_or(defaults.max_fps),
+            accessible: matches.get_flag("accessible"),
+            socket: matches
+                .try_get_one::<PathBuf>("socket")
+                .ok()
+                .flatten()
+                .cloned(),
+            share_bus: matches.get_one::<String>("share-bus").and_then(|path| {
+                match path.as_str() {
+                    "" => shared_bus::default_socket(),
+                    path => Some(PathBuf::from(path)),
+                }
+            }),
+            api: matches.get_one::<SocketAddr>("api").copied(),
+            kiosk: matches.get_flag("kiosk"),
+            no_color: matches.get_flag("no-color"),
+            connect: matches.get_one::<String>("connect").cloned(),
+            export_frame: path("export-frame"),
+            plugin_file: matches
+                .try_get_one::<PathBuf>("file")
+                .ok()
+                .flatten()
+                .cloned(),
+        }
+    }
+
+    /// The config file in use: `--config`, or the first one found
+    pub fn config_path(&self) -> Result<Option<PathBuf>, ConfigError> {
+        match &self.config {
+            Some(path) => Ok(Some(path.clone())),
+            None => ConfigFile::find(),
+        }
+    }
+
+    /// Load the config file in use, or the built-in dashboards if there is
+    /// none
+    pub fn load_config(&self) -> Result<ConfigFile, ConfigError> {
+        match self.config_path()? {
+            Some(path) => ConfigFile::load_from(&path),
+            None => Ok(ConfigFile::default()),
+        }
+    }
+
+    /// A plugin manager loading from `--plugin-dir`, if given, and checking
+    /// libraries as the config's `[plugins]` section says
+    pub fn plugin_manager(&self) -> PluginManager {
+        let mut manager = PluginManager::new();
+        if let Some(dir) = &self.plugin_dir {
+            manager.set_plugin_dir(dir.clone());
+        }
+        if let Ok(config) = self.load_config() {
+            manager.set_trust(TrustSettings::from_config(&config).unwrap_or_else(|e| {
+                // A mistyped section must not turn checks off
+                eprintln!(
+                    "Warning: invalid [plugins]: {}; only locked plugins load",
+                    e
+                );
+                TrustSettings {
+                    strict: true,
+                    public_key: None,
+                }
+            }));
+        }
+        manager
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, clap::Error> {
+        CliArgs::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+        assert_eq!(parse(&["run"]).unwrap(), CliArgs::default());
+    }
+
+    #[test]
+    fn test_command_definition() {
+        cli().debug_assert();
+    }
+
+    #[test]
+    fn test_inline_and_separate_values() {
+        let args = parse(&["--dashboard=dev", "--event-log", "events.jsonl"]).unwrap();
+        assert_eq!(args.dashboard, "dev");
+        assert_eq!(args.event_log, Some(PathBuf::from("events.jsonl")));
+
+        let args = parse(&["--dashboard", "minimal", "--event-log=out.jsonl"]).unwrap();
+        assert_eq!(args.dashboard, "minimal");
+        assert_eq!(args.event_log, Some(PathBuf::from("out.jsonl")));
+    }
+
+    #[test]
+    fn test_config_tick_rate_and_plugin_dir() {
+        let args = parse(&[
+            "run",
+            "--config",
+            "ci/devdash.toml",
+            "--tick-rate=500ms",
+            "--plugin-dir",
+            "target/plugins",
+        ])
+        .unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.config, Some(PathBuf::from("ci/devdash.toml")));
+        assert_eq!(args.tick_rate, Duration::from_millis(500));
+        assert_eq!(args.plugin_dir, Some(PathBuf::from("target/plugins")));
+
+        assert_eq!(
+            parse(&["--tick-rate", "2"]).unwrap().tick_rate,
+            Duration::from_secs(2)
+        );
+        assert!(parse(&["--tick-rate=1ms"]).is_err());
+        assert!(parse(&["--tick-rate=soon"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_and_list_widgets() {
+        let args = parse(&["validate", "--config=devdash.toml"]).unwrap();
+        assert_eq!(args.command, Command::Validate);
+        assert_eq!(args.config, Some(PathBuf::from("devdash.toml")));
+        assert_eq!(
+            parse(&["list-widgets"]).unwrap().command,
+            Command::ListWidgets
+        );
+    }
+
+    #[test]
+    fn test_inject() {
+        let args = parse(&["--inject", "-", "--no-live"]).unwrap();
+        assert_eq!(args.inject, Some(PathBuf::from("-")));
+        assert!(!args.live);
+
+        assert!(parse(&["--inject=demo.jsonl"]).unwrap().live);
+    }
+
+    #[test]
+    fn test_doctor() {
+        assert_eq!(parse(&["doctor"]).unwrap().command, Command::Doctor);
+        let args = parse(&["--dashboard=dev", "doctor"]).unwrap();
+        assert_eq!(args.command, Command::Doctor);
+        assert_eq!(args.dashboard, "dev");
+    }
+
+    #[test]
+    fn test_accessible() {
+        assert!(!parse(&[]).unwrap().accessible);
+        assert!(parse(&["--accessible"]).unwrap().accessible);
+        assert!(parse(&["--accessible=yes"]).is_err());
+    }
+
+    #[test]
+    fn test_api() {
+        assert_eq!(parse(&[]).unwrap().api, None);
+        assert_eq!(
+            parse(&["attach", "--api=127.0.0.1:9900"]).unwrap().api,
+            Some("127.0.0.1:9900".parse().unwrap())
+        );
+        assert!(parse(&["--api", "localhost"]).is_err());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        assert_eq!(
+            parse(&["snapshot"]).unwrap().command,
+            Command::Snapshot(SnapshotFormat::Text)
+        );
+        let args = parse(&["snapshot", "--format", "json", "--dashboard", "dev"]).unwrap();
+        assert_eq!(args.command, Command::Snapshot(SnapshotFormat::Json));
+        assert_eq!(args.dashboard, "dev");
+        assert!(parse(&["snapshot", "--format", "yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_export_frame() {
+        assert_eq!(parse(&[]).unwrap().export_frame, None);
+        assert_eq!(
+            parse(&["--export-frame", "incident.ans"])
+                .unwrap()
+                .export_frame,
+            Some(PathBuf::from("incident.ans"))
+        );
+        assert!(parse(&["snapshot", "--export-frame=frame.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_agent_and_connect() {
+        assert_eq!(
+            parse(&["agent"]).unwrap().command,
+            Command::Agent {
+                listen: DEFAULT_AGENT_ADDR.parse().unwrap()
+            }
+        );
+        assert_eq!(
+            parse(&["agent", "--listen", "127.0.0.1:7000"])
+                .unwrap()
+                .command,
+            Command::Agent {
+                listen: "127.0.0.1:7000".parse().unwrap()
+            }
+        );
+        assert!(parse(&["--listen", "127.0.0.1:7000"]).is_err());
+
+        assert_eq!(parse(&[]).unwrap().connect, None);
+        assert_eq!(
+            parse(&["--connect", "buildbox:9901"]).unwrap().connect,
+            Some("buildbox:9901".to_string())
+        );
+        assert!(parse(&["attach", "--connect=buildbox:9901"]).is_err());
+    }
+
+    #[test]
+    fn test_kiosk() {
+        assert!(!parse(&[]).unwrap().kiosk);
+        assert!(parse(&["--kiosk"]).unwrap().kiosk);
+        assert!(parse(&["keys", "--kiosk"]).unwrap().kiosk);
+    }
+
+    #[test]
+    fn test_no_color() {
+        assert!(!parse(&[]).unwrap().no_color);
+        assert!(parse(&["attach", "--no-color"]).unwrap().no_color);
+    }
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(
+            parse(&["keys"]).unwrap().command,
+            Command::Keys(KeysFormat::Text)
+        );
+        assert_eq!(
+            parse(&["keys", "--format", "md"]).unwrap().command,
+            Command::Keys(KeysFormat::Markdown)
+        );
+        assert!(parse(&["keys", "--format=html"]).is_err());
+        assert!(parse(&["--format=md"]).is_err());
+    }
+
+    #[test]
+    fn test_plugins() {
+        assert_eq!(parse(&["plugins"]).unwrap().command, Command::Plugins);
+        assert_eq!(
+            parse(&["plugins", "list"]).unwrap().command,
+            Command::Plugins
+        );
+        assert_eq!(
+            parse(&["plugins", "install", "libweather.so"])
+                .unwrap()
+                .command,
+            Command::InstallPlugin
+        );
+        assert_eq!(
+            parse(&["plugins", "install", "libweather.so"])
+                .unwrap()
+                .plugin_file,
+            Some(PathBuf::from("libweather.so"))
+        );
+        assert!(parse(&["plugins", "install"]).is_err());
+        assert!(parse(&["list"]).is_err());
+    }
+
+    #[test]
+    fn test_daemon_and_attach() {
+        let args = parse(&["daemon", "--dashboard=dev"]).unwrap();
+        assert_eq!(args.command, Command::Daemon);
+        assert_eq!(args.dashboard, "dev");
+        assert_eq!(args.socket, None);
+        let args = parse(&["attach", "--socket", "/tmp/d.sock"]).unwrap();
+        assert_eq!(args.command, Command::Attach);
+        assert_eq!(args.socket, Some(PathBuf::from("/tmp/d.sock")));
+
+        assert!(parse(&["--socket=/tmp/d.sock"]).is_err());
+        assert!(parse(&["attach", "--inject=-"]).is_err());
+        assert_eq!(parse(&["stream"]).unwrap().command, Command::Stream);
+    }
+
+    #[test]
+    fn test_init() {
+        let args = parse(&["init", "--preset", "sysadmin", "--config=ops.toml"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Init {
+                preset: Preset::Sysadmin,
+                force: false
+            }
+        );
+        assert_eq!(args.config, Some(PathBuf::from("ops.toml")));
+        assert_eq!(
+            parse(&["init", "--preset=homelab", "--force"])
+                .unwrap()
+                .command,
+            Command::Init {
+                preset: Preset::Homelab,
+                force: true
+            }
+        );
+        assert!(parse(&["init"]).is_err());
+        assert!(parse(&["init", "--preset", "gaming"]).is_err());
+        assert!(parse(&["--force"]).is_err());
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let args = parse(&["record", "bug.jsonl", "--dashboard=dev"]).unwrap();
+        assert_eq!(args.command, Command::Record);
+        assert_eq!(args.event_log, Some(PathBuf::from("bug.jsonl")));
+        assert_eq!(args.dashboard, "dev");
+        assert!(args.live);
+
+        let args = parse(&["replay", "bug.jsonl"]).unwrap();
+        assert_eq!(args.command, Command::Replay);
+        assert_eq!(args.inject, Some(PathBuf::from("bug.jsonl")));
+        assert!(!args.live);
+
+        assert!(parse(&["record"]).is_err());
+        assert!(parse(&["record", "a.jsonl", "--event-log=b.jsonl"]).is_err());
+        assert!(parse(&["replay", "a.jsonl", "--inject=-"]).is_err());
+    }
+
+    #[test]
+    fn test_share_bus() {
+        assert_eq!(parse(&[]).unwrap().share_bus, None);
+        assert_eq!(
+            parse(&["--share-bus"]).unwrap().share_bus,
+            shared_bus::default_socket()
+        );
+        let args = parse(&["--share-bus=/tmp/bus.sock", "--dashboard", "dev"]).unwrap();
+        assert_eq!(args.share_bus, Some(PathBuf::from("/tmp/bus.sock")));
+        assert_eq!(args.dashboard, "dev");
+    }
+
+    #[test]
+    fn test_max_fps() {
+        assert_eq!(parse(&[]).unwrap().max_fps, DEFAULT_MAX_FPS);
+        assert_eq!(parse(&["--max-fps", "60"]).unwrap().max_fps, 60);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse(&["--event-log"]).is_err());
+        assert!(parse(&["--no-live"]).is_err());
+        assert!(parse(&["--no-live=1", "--inject=x"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&["--max-fps=0"]).is_err());
+        assert!(parse(&["--max-fps", "fast"]).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+// devdash-cli/src/codec.rs
+//! JSON encoding of event bus payloads, through the process-wide
+//! [`PayloadRegistry`] with the built-in widgets' payload types added.
+use devdash_core::{BusEvent, EventPayload, PayloadRegistry};
+use serde_json::Value;
+use std::sync::Once;
+
+/// The global registry, once the widgets' payload types are in it
+pub fn registry() -> &'static PayloadRegistry {
+    static WIDGETS: Once = Once::new();
+    let registry = PayloadRegistry::global();
+    WIDGETS.call_once(|| devdash_widgets::register_payloads(registry));
+    registry
+}
+
+/// Serialize an event payload, returning its type name and JSON value
+///
+/// Returns `None` for payload types that have no JSON representation.
+pub fn encode(event: &BusEvent) -> Option<(&'static str, Value)> {
+    registry().encode(event)
+}
+
+/// Deserialize a payload previously produced by [`encode`]
+///
+/// Returns `None` for unknown type names or values that don't match the type.
+pub fn decode(type_name: &str, value: Value) -> Option<EventPayload> {
+    registry().decode(type_name, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_widget_payload() {
+        let event = BusEvent::new(
+            "system.disk.projection",
+            devdash_widgets::DiskProjection {
+                mount_point: "/".to_string(),
+                bytes_per_day: 1e9,
+                seconds_until_full: 3600,
+            },
+        );
+
+        let (name, value) = encode(&event).unwrap();
+        assert_eq!(name, "DiskProjection");
+        assert_eq!(value["seconds_until_full"], 3600);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let event = BusEvent::new("system.git.status", "branch=main".to_string());
+        let (name, value) = encode(&event).unwrap();
+
+        let payload = decode(name, value).unwrap();
+        assert_eq!(*payload.downcast::<String>().unwrap(), "branch=main");
+        assert!(decode("Nope", Value::Null).is_none());
+    }
+
+    #[test]
+    fn test_encode_unknown_payload() {
+        assert!(encode(&BusEvent::new("test", 42u8)).is_none());
+    }
+}
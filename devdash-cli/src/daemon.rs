@@ -0,0 +1,346 @@
+// devdash-cli/src/daemon.rs
+//! `devdash daemon` and `devdash attach`: collecting without a terminal.
+//!
+//! The daemon runs a dashboard's widgets headlessly and streams every bus
+//! event over a local socket, in the `--event-log` format. Clients started
+//! with `devdash attach` publish the stream on their own bus with live
+//! collection off, the same way `--inject --no-live` plays back a recording.
+//! New clients first receive the last [`BACKLOG_WINDOW`] of events, so
+//! charts and alerts pick up where the daemon is.
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::MissedTickBehavior;
+
+use devdash_core::{DevdashError, EventBus};
+
+use crate::{PLUGIN_CHECK_INTERVAL, app::App, event_log::record_line};
+
+/// How far back the events replayed to new clients go
+pub const BACKLOG_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Upper bound on replayed events, however busy the bus
+const BACKLOG_LEN: usize = 20_000;
+
+/// Directory of the default sockets, which only the user may enter:
+/// `devdash` in the runtime directory where there is one, otherwise
+/// `~/.devdash/run`
+///
+/// Never the shared temp directory, where another user could get to a
+/// socket first; `None` when there is neither.
+pub fn socket_dir() -> Option<PathBuf> {
+    dirs::runtime_dir()
+        .map(|dir| dir.join("devdash"))
+        .or_else(|| dirs::home_dir().map(|home| home.join(".devdash/run")))
+}
+
+/// Socket of the daemon serving `dashboard`, in [`socket_dir`]
+pub fn default_socket(dashboard: &str) -> Option<PathBuf> {
+    Some(socket_dir()?.join(format!("devdash-{}.sock", dashboard)))
+}
+
+/// Create the directory socket `path` goes in; [`socket_dir`] is made
+/// private to the user, even if it already exists
+pub fn create_socket_dir(path: &Path) -> io::Result<()> {
+    let Some(parent) = path.parent() else {
+        return Ok(());
+    };
+    #[cfg(unix)]
+    if socket_dir().as_deref() == Some(parent) {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)?;
+        return std::fs::set_permissions(parent, std::fs::Permissions::from_mode(0o700));
+    }
+    std::fs::create_dir_all(parent)
+}
+
+/// Become the one process serving on socket `path`, holding a lock next to
+/// it until the returned file is dropped; fails with `AddrInUse` while
+/// another process holds it
+///
+/// Whoever holds the lock may replace a socket file left behind without
+/// racing another process doing the same.
+pub fn lock_socket(path: &Path) -> io::Result<std::fs::File> {
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path)?;
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(std::fs::TryLockError::WouldBlock) => Err(io::ErrorKind::AddrInUse.into()),
+        Err(std::fs::TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// Recent events, encoded, oldest first
+#[derive(Debug, Default)]
+struct Backlog {
+    lines: VecDeque<(Instant, String)>,
+}
+
+impl Backlog {
+    fn push(&mut self, now: Instant, line: String) {
+        self.lines.push_back((now, line));
+        while self.lines.len() > BACKLOG_LEN
+            || self
+                .lines
+                .front()
+                .is_some_and(|(at, _)| now.duration_since(*at) > BACKLOG_WINDOW)
+        {
+            self.lines.pop_front();
+        }
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(|(_, line)| line.as_str())
+    }
+}
+
+/// Listens on the daemon socket and streams bus events to each client
+pub struct Server {
+    path: PathBuf,
+    /// Keeps other daemons off the socket
+    _lock: std::fs::File,
+}
+
+impl Server {
+    /// Bind `path` and start serving; a socket left behind by a daemon that
+    /// is no longer running is replaced
+    #[cfg(unix)]
+    pub fn start(event_bus: &EventBus, path: &Path) -> io::Result<Self> {
+        use std::{io::Write, os::unix::net::UnixListener};
+
+        create_socket_dir(path)?;
+        let lock = lock_socket(path).map_err(|e| match e.kind() {
+            io::ErrorKind::AddrInUse => io::Error::new(
+                io::ErrorKind::AddrInUse,
+                "a daemon is already running on this socket",
+            ),
+            _ => e,
+        })?;
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+
+        let backlog = Arc::new(Mutex::new(Backlog::default()));
+        let (subscription, rx) = event_bus.subscribe("*");
+        let recorded = backlog.clone();
+        // Detached, like the listener: both live as long as the daemon
+        std::thread::spawn(move || {
+            let _subscription = subscription;
+            for event in rx {
+                let line = record_line(&event);
+                recorded
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(Instant::now(), line);
+            }
+        });
+
+        let event_bus = event_bus.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                // Subscribe while holding the backlog so no event falls
+                // between the replay and the live stream
+                let (replay, (subscription, rx)) = {
+                    let backlog = backlog.lock().unwrap_or_else(|e| e.into_inner());
+                    let replay: Vec<String> = backlog.lines().map(str::to_string).collect();
+                    (replay, event_bus.subscribe("*"))
+                };
+                std::thread::spawn(move || {
+                    let _subscription = subscription;
+                    let live = rx.iter().map(|event| record_line(&event));
+                    // Ends once the client hangs up and a write fails
+                    for line in replay.into_iter().chain(live) {
+                        if writeln!(stream, "{}", line).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            _lock: lock,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_event_bus: &EventBus, _path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "daemon mode needs Unix domain sockets",
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Connect to the daemon listening on `path`
+pub fn connect(path: &Path) -> io::Result<Box<dyn BufRead + Send>> {
+    #[cfg(unix)]
+    {
+        let stream = std::os::unix::net::UnixStream::connect(path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "no daemon on {} ({}); start one with `devdash daemon`",
+                    path.display(),
+                    e
+                ),
+            )
+        })?;
+        Ok(Box::new(io::BufReader::new(stream)))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "daemon mode needs Unix domain sockets",
+        ))
+    }
+}
+
+/// Keep the widgets and alerts updated until Ctrl+C or SIGTERM
+pub async fn run(app: &mut App, server: &Server) -> Result<(), DevdashError> {
+    eprintln!("devdash daemon listening on {}", server.path().display());
+    let mut plugin_check = tokio::time::interval(PLUGIN_CHECK_INTERVAL);
+    plugin_check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let stopped = stop_signal();
+    tokio::pin!(stopped);
+
+    loop {
+        let deadline = app.next_update().map_or_else(
+            || tokio::time::Instant::now() + PLUGIN_CHECK_INTERVAL,
+            tokio::time::Instant::from_std,
+        );
+
+        tokio::select! {
+            result = &mut stopped => return result.map_err(DevdashError::from),
+            () = tokio::time::sleep_until(deadline) => {
+                app.update_due(Instant::now());
+                app.check_alerts();
+                app.check_links();
+            }
+            _ = plugin_check.tick() => {
+                app.check_plugins();
+                app.check_alerts();
+                app.check_links();
+            }
+        }
+    }
+}
+
+/// Resolves on Ctrl+C, or on SIGTERM where there is one
+async fn stop_signal() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backlog_drops_old_events() {
+        let start = Instant::now();
+        let mut backlog = Backlog::default();
+        backlog.push(start, "a".to_string());
+        backlog.push(start + Duration::from_secs(60), "b".to_string());
+        backlog.push(
+            start + BACKLOG_WINDOW + Duration::from_secs(1),
+            "c".to_string(),
+        );
+        assert_eq!(backlog.lines().collect::<Vec<_>>(), ["b", "c"]);
+    }
+
+    #[test]
+    fn test_default_socket_per_dashboard() {
+        let socket = default_socket("dev").unwrap();
+        assert_eq!(socket.file_name().unwrap(), "devdash-dev.sock");
+        assert_ne!(Some(socket.clone()), default_socket("default"));
+        assert!(!socket.starts_with(std::env::temp_dir()));
+    }
+
+    #[test]
+    fn test_socket_lock_is_exclusive() {
+        let dir = std::env::temp_dir().join(format!("devdash-lock-test-{}", std::process::id()));
+        let socket = dir.join("bus.sock");
+        create_socket_dir(&socket).unwrap();
+        let lock = lock_socket(&socket).unwrap();
+        assert_eq!(
+            lock_socket(&socket).unwrap_err().kind(),
+            io::ErrorKind::AddrInUse
+        );
+        drop(lock);
+        assert!(lock_socket(&socket).is_ok());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_client_receives_backlog_and_live_events() {
+        use devdash_core::BusEvent;
+
+        let dir = std::env::temp_dir().join(format!("devdash-daemon-test-{}", std::process::id()));
+        let socket = dir.join("test.sock");
+        let bus = EventBus::new();
+        let server = Server::start(&bus, &socket).unwrap();
+        assert!(Server::start(&bus, &socket).is_err());
+
+        bus.publish(BusEvent::new("system.git.status", "before".to_string()));
+        // Let the backlog thread record it
+        std::thread::sleep(Duration::from_millis(50));
+        let mut client = connect(&socket).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        bus.publish(BusEvent::new("system.git.status", "after".to_string()));
+
+        let mut lines = Vec::new();
+        for _ in 0..2 {
+            let mut line = String::new();
+            client.read_line(&mut line).unwrap();
+            lines.push(line);
+        }
+        assert!(lines[0].contains("\"before\""), "{}", lines[0]);
+        assert!(lines[1].contains("\"after\""), "{}", lines[1]);
+
+        drop(server);
+        assert!(!socket.exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
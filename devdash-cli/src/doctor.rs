@@ -0,0 +1,414 @@
+// devdash-cli/src/doctor.rs
+//! `devdash doctor`: environment checks with a pass/fail report.
+use std::{
+    fmt,
+    io::IsTerminal,
+    path::Path,
+    process::{Command, ExitCode},
+};
+
+use devdash_core::{ConfigFile, PluginError, PluginManager};
+
+use crate::{args::CliArgs, plugins};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// Works, but with reduced functionality
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        })
+    }
+}
+
+/// Result of a single check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub status: Status,
+    pub name: String,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(status: Status, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            name: name.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Checks grouped under headings, in the order they ran
+#[derive(Debug, Default)]
+pub struct Report {
+    sections: Vec<(&'static str, Vec<Check>)>,
+}
+
+impl Report {
+    pub fn section(&mut self, title: &'static str, checks: Vec<Check>) {
+        self.sections.push((title, checks));
+    }
+
+    /// Whether no check failed
+    pub fn passed(&self) -> bool {
+        self.checks().all(|check| check.status != Status::Fail)
+    }
+
+    fn checks(&self) -> impl Iterator<Item = &Check> {
+        self.sections.iter().flat_map(|(_, checks)| checks)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (title, checks) in &self.sections {
+            writeln!(f, "{}", title)?;
+            for check in checks {
+                writeln!(f, "  [{}] {}: {}", check.status, check.name, check.detail)?;
+            }
+            writeln!(f)?;
+        }
+
+        let count = |status| self.checks().filter(|c| c.status == status).count();
+        write!(
+            f,
+            "{} passed, {} warnings, {} failed",
+            count(Status::Pass),
+            count(Status::Warn),
+            count(Status::Fail)
+        )
+    }
+}
+
+/// Run every check for `dashboard`, print the report, and exit non-zero if
+/// anything failed
+pub fn run(args: &CliArgs) -> ExitCode {
+    let env = |name: &str| std::env::var(name).ok();
+
+    let mut report = Report::default();
+    report.section(
+        "Terminal",
+        terminal_checks(std::io::stdout().is_terminal(), env),
+    );
+    report.section("Configuration", config_checks(args));
+    report.section("Plugins", plugin_checks(args.plugin_manager()));
+    report.section("Git", git_checks());
+    report.section("Metrics", metric_checks());
+
+    println!("{}", report);
+    if report.passed() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn terminal_checks(is_tty: bool, env: impl Fn(&str) -> Option<String>) -> Vec<Check> {
+    let mut checks = vec![if is_tty {
+        Check::new(Status::Pass, "tty", "stdout is a terminal")
+    } else {
+        Check::new(Status::Fail, "tty", "stdout is not a terminal")
+    }];
+
+    let term = env("TERM").unwrap_or_default();
+    let colorterm = env("COLORTERM").unwrap_or_default();
+    checks.push(if env("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        Check::new(Status::Warn, "color", "disabled by NO_COLOR")
+    } else if matches!(colorterm.as_str(), "truecolor" | "24bit") {
+        Check::new(Status::Pass, "color", "24-bit color")
+    } else if term.contains("256color") {
+        Check::new(Status::Pass, "color", "256 colors")
+    } else if term.is_empty() || term == "dumb" {
+        Check::new(
+            Status::Warn,
+            "color",
+            "unknown terminal, colors may not render",
+        )
+    } else {
+        Check::new(
+            Status::Warn,
+            "color",
+            format!("TERM={} may only support 16 colors", term),
+        )
+    });
+
+    // The first of these that is set decides the locale
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|name| env(name).filter(|v| !v.is_empty()));
+    checks.push(match locale {
+        Some(l) if l.to_lowercase().replace('-', "").contains("utf8") => {
+            Check::new(Status::Pass, "unicode", format!("locale {}", l))
+        }
+        // Windows terminals handle Unicode without a locale variable
+        None if cfg!(windows) => Check::new(Status::Pass, "unicode", "Windows console"),
+        Some(l) => Check::new(
+            Status::Warn,
+            "unicode",
+            format!("locale {} is not UTF-8; charts may show garbage", l),
+        ),
+        None => Check::new(
+            Status::Warn,
+            "unicode",
+            "no locale set; charts may show garbage",
+        ),
+    });
+
+    checks
+}
+
+fn config_checks(args: &CliArgs) -> Vec<Check> {
+    let dashboard = &args.dashboard;
+    let path = match args.config_path() {
+        Ok(Some(path)) => path,
+        Ok(None) => {
+            return vec![Check::new(
+                Status::Pass,
+                "config file",
+                "none found, using built-in dashboards",
+            )];
+        }
+        Err(e) => return vec![Check::new(Status::Fail, "config file", e.to_string())],
+    };
+
+    let mut checks = vec![Check::new(
+        Status::Pass,
+        "config file",
+        path.display().to_string(),
+    )];
+    match ConfigFile::load_from(&path) {
+        Ok(config) => {
+            checks.push(Check::new(
+                Status::Pass,
+                "parse",
+                format!("{} dashboards", config.dashboard.len()),
+            ));
+            checks.push(match config.require_dashboard(dashboard) {
+                Ok(_) => Check::new(Status::Pass, "dashboard", format!("'{}' found", dashboard)),
+                Err(e) => Check::new(Status::Fail, "dashboard", e.to_string()),
+            });
+            if plugins::lists_commands(&config)
+                && let Some(project) = plugins::untrusted_config(args, &args.plugin_manager())
+            {
+                checks.push(Check::new(
+                    Status::Warn,
+                    "project commands",
+                    format!(
+                        "{} isn't trusted, so its commands don't start; start devdash there to trust it",
+                        project.display()
+                    ),
+                ));
+            }
+        }
+        Err(e) => checks.push(Check::new(Status::Fail, "parse", e.to_string())),
+    }
+    checks
+}
+
+fn plugin_checks(mut manager: PluginManager) -> Vec<Check> {
+    let mut checks: Vec<Check> = manager
+        .plugin_dirs()
+        .iter()
+        .map(|dir| {
+            let detail = if dir.exists() {
+                dir.display().to_string()
+            } else {
+                format!("{} (not created)", dir.display())
+            };
+            Check::new(Status::Pass, "plugin directory", detail)
+        })
+        .collect();
+    if let Some(dir) = manager.untrusted_project() {
+        checks.push(Check::new(
+            Status::Warn,
+            "project plugins",
+            format!(
+                "{} skipped; start devdash there to trust the project",
+                dir.display()
+            ),
+        ));
+    }
+    if !manager.plugin_dirs().iter().any(|dir| dir.exists()) {
+        return checks;
+    }
+
+    match manager.load_each() {
+        Ok(results) if results.is_empty() => {
+            checks.push(Check::new(Status::Pass, "plugins", "none installed"));
+        }
+        Ok(results) => {
+            for (path, result) in results {
+                // Shown with its directory, as any layer may have provided it
+                let file = path.display().to_string();
+                checks.push(match result {
+                    Ok((name, _widget)) => {
+                        Check::new(Status::Pass, file, format!("loads as '{}'", name))
+                    }
+                    // Granted when devdash is next started interactively
+                    Err(e @ PluginError::PermissionDenied { .. }) => {
+                        Check::new(Status::Warn, file, e.to_string())
+                    }
+                    Err(e) => Check::new(Status::Fail, file, e.to_string()),
+                });
+            }
+        }
+        Err(e) => checks.push(Check::new(Status::Fail, "plugins", e.to_string())),
+    }
+    checks
+}
+
+fn git_checks() -> Vec<Check> {
+    let mut checks = vec![match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => Check::new(
+            Status::Pass,
+            "git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => Check::new(
+            Status::Warn,
+            "git",
+            "not on PATH; the git widget still reads repositories but can't open them",
+        ),
+    }];
+
+    let in_repo =
+        std::env::current_dir().is_ok_and(|dir| dir.ancestors().any(|d| d.join(".git").exists()));
+    checks.push(if in_repo {
+        Check::new(
+            Status::Pass,
+            "repository",
+            "current directory is in a repository",
+        )
+    } else {
+        Check::new(
+            Status::Warn,
+            "repository",
+            "current directory is not in a repository; the git widget will be empty",
+        )
+    });
+    checks
+}
+
+fn metric_checks() -> Vec<Check> {
+    // Sources the built-in widgets read directly or through sysinfo
+    let sources: &[(&str, &str)] = if cfg!(target_os = "linux") {
+        &[
+            ("/proc/stat", "CPU usage"),
+            ("/proc/meminfo", "memory usage"),
+            ("/proc/diskstats", "disk I/O"),
+            ("/proc/net/dev", "network I/O"),
+            ("/sys/fs/cgroup", "cgroups widget"),
+        ]
+    } else {
+        &[]
+    };
+
+    let mut checks: Vec<_> = sources
+        .iter()
+        .map(|(path, what)| readable(Path::new(path), what))
+        .collect();
+
+    // Other users' processes are hidden without access to their /proc entries
+    #[cfg(target_os = "linux")]
+    checks.push(match std::fs::read_link("/proc/1/exe") {
+        Ok(_) => Check::new(Status::Pass, "process details", "all processes visible"),
+        Err(_) => Check::new(
+            Status::Warn,
+            "process details",
+            "can't inspect other users' processes; run as root for full details",
+        ),
+    });
+
+    if checks.is_empty() {
+        checks.push(Check::new(
+            Status::Pass,
+            "metrics",
+            "read through platform APIs",
+        ));
+    }
+    checks
+}
+
+fn readable(path: &Path, what: &str) -> Check {
+    let ok = if path.is_dir() {
+        std::fs::read_dir(path).is_ok()
+    } else {
+        std::fs::File::open(path).is_ok()
+    };
+    if ok {
+        Check::new(Status::Pass, path.display().to_string(), what.to_string())
+    } else {
+        Check::new(
+            Status::Fail,
+            path.display().to_string(),
+            format!("not readable; {} unavailable", what),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    fn status(checks: &[Check], name: &str) -> Status {
+        checks.iter().find(|c| c.name == name).unwrap().status
+    }
+
+    #[test]
+    fn test_terminal_capabilities() {
+        let checks = terminal_checks(
+            true,
+            env(&[("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")]),
+        );
+        assert!(checks.iter().all(|c| c.status == Status::Pass));
+
+        let checks = terminal_checks(false, env(&[("TERM", "vt100"), ("LC_ALL", "C")]));
+        assert_eq!(status(&checks, "tty"), Status::Fail);
+        assert_eq!(status(&checks, "color"), Status::Warn);
+        assert_eq!(status(&checks, "unicode"), Status::Warn);
+    }
+
+    #[test]
+    fn test_no_color() {
+        let checks = terminal_checks(true, env(&[("COLORTERM", "truecolor"), ("NO_COLOR", "1")]));
+        assert_eq!(checks[1].detail, "disabled by NO_COLOR");
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = Report::default();
+        report.section(
+            "Terminal",
+            vec![
+                Check::new(Status::Pass, "tty", "stdout is a terminal"),
+                Check::new(Status::Warn, "color", "16 colors"),
+            ],
+        );
+        assert!(report.passed());
+        assert_eq!(
+            report.to_string(),
+            "Terminal\n  [PASS] tty: stdout is a terminal\n  [WARN] color: 16 colors\n\n\
+             1 passed, 1 warnings, 0 failed"
+        );
+
+        report.section("Git", vec![Check::new(Status::Fail, "git", "missing")]);
+        assert!(!report.passed());
+    }
+}
@@ -0,0 +1,93 @@
+// devdash-cli/src/event_log.rs
+//! Streams every bus event to a JSON lines file.
+use devdash_core::{BusEvent, EventBus, event::Subscription};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
+    thread::JoinHandle,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::codec;
+
+/// One line of the event log
+#[derive(Debug, Serialize)]
+struct LogRecord<'a> {
+    timestamp_ms: u128,
+    topic: &'a str,
+    #[serde(rename = "type")]
+    payload_type: &'a str,
+    payload: Value,
+}
+
+/// An event as one line of the log, without the newline
+///
+/// Payloads without a JSON encoding are written with type `"unknown"` and a
+/// `null` payload so the topic still shows up in the log.
+pub fn record_line(event: &BusEvent) -> String {
+    let (payload_type, payload) = codec::encode(event).unwrap_or(("unknown", Value::Null));
+    let record = LogRecord {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default(),
+        topic: &event.topic,
+        payload_type,
+        payload,
+    };
+    serde_json::to_string(&record).expect("records always serialize")
+}
+
+/// Background writer appending bus events to a file
+pub struct EventLogger {
+    subscription: Option<Subscription>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EventLogger {
+    /// Subscribe to all topics and start writing to `path` (appending)
+    pub fn start(event_bus: &EventBus, path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::write_to(event_bus, file))
+    }
+
+    /// Like [`start`](Self::start), but replacing what `path` held, so
+    /// replaying it doesn't first wait out the gap since an older session
+    pub fn create(event_bus: &EventBus, path: &Path) -> io::Result<Self> {
+        Ok(Self::write_to(event_bus, File::create(path)?))
+    }
+
+    fn write_to(event_bus: &EventBus, file: File) -> Self {
+        let mut writer = BufWriter::new(file);
+        let (subscription, rx) = event_bus.subscribe("*");
+
+        let handle = std::thread::spawn(move || {
+            // Ends once the subscription is dropped and the channel closes
+            for event in rx {
+                let written =
+                    writeln!(writer, "{}", record_line(&event)).and_then(|_| writer.flush());
+                if written.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            subscription: Some(subscription),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for EventLogger {
+    fn drop(&mut self) {
+        // Unsubscribing closes the channel, letting the writer drain and exit
+        self.subscription.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,199 @@
+// devdash-cli/src/export.rs
+//! Dumping the dashboard's frame to a file, for incident reports.
+//!
+//! Frames are written as plain text, or with ANSI escape codes so `cat`
+//! shows them in color. The `export_frame` key saves what is on screen as
+//! both; `--export-frame FILE` polls the widgets once, draws a frame
+//! off-screen and exits.
+use devdash_core::{DevdashError, no_color};
+use devdash_widgets::common::format_time_with;
+use ratatui::{
+    Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{app::App, snapshot::poll_once};
+
+/// Size of frames drawn with `--export-frame` when the terminal size is
+/// unknown
+const FALLBACK_SIZE: (u16, u16) = (120, 40);
+
+/// Extensions of files written with ANSI escape codes
+const ANSI_EXTENSIONS: [&str; 2] = ["ans", "ansi"];
+
+/// SGR parameters of the text attributes
+const MODIFIER_CODES: [(Modifier, u8); 9] = [
+    (Modifier::BOLD, 1),
+    (Modifier::DIM, 2),
+    (Modifier::ITALIC, 3),
+    (Modifier::UNDERLINED, 4),
+    (Modifier::SLOW_BLINK, 5),
+    (Modifier::RAPID_BLINK, 6),
+    (Modifier::REVERSED, 7),
+    (Modifier::HIDDEN, 8),
+    (Modifier::CROSSED_OUT, 9),
+];
+
+/// The text of `buf`, one line per row with trailing spaces removed
+pub fn plain_text(buf: &Buffer) -> String {
+    let width = usize::from(buf.area.width).max(1);
+    buf.content
+        .chunks(width)
+        .map(|row| {
+            let line: String = row
+                .iter()
+                .filter(|cell| !cell.skip)
+                .map(|cell| cell.symbol())
+                .collect();
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+/// The text of `buf` with ANSI escape codes for its colors and attributes;
+/// each line ends with a reset
+pub fn ansi(buf: &Buffer) -> String {
+    let width = usize::from(buf.area.width).max(1);
+    let mut text = String::new();
+    for row in buf.content.chunks(width) {
+        let mut current = None;
+        for cell in row.iter().filter(|cell| !cell.skip) {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                text.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                current = Some(style);
+            }
+            text.push_str(cell.symbol());
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// Escape sequence switching from any style to this one
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    codes.extend(
+        MODIFIER_CODES
+            .iter()
+            .filter(|(m, _)| modifier.contains(*m))
+            .map(|(_, code)| code.to_string()),
+    );
+    codes.extend(color_code(fg, 30));
+    codes.extend(color_code(bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameter of `color`, with `base` 30 for the foreground and 40 for
+/// the background; `None` for the terminal's default
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Indexed(i) => return Some(format!("{};5;{}", base + 8, i)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    };
+    Some(code.to_string())
+}
+
+/// Write `buf` to `path`: with escape codes if the file is named `.ans` or
+/// `.ansi` and colors are on, as plain text otherwise
+pub fn write(buf: &Buffer, path: &Path) -> io::Result<()> {
+    let colors = !no_color()
+        && path
+            .extension()
+            .is_some_and(|ext| ANSI_EXTENSIONS.iter().any(|a| ext.eq_ignore_ascii_case(a)));
+    fs::write(path, if colors { ansi(buf) } else { plain_text(buf) })
+}
+
+/// Save `buf` in the current directory as `devdash-<dashboard>-<time>`,
+/// both `.ans` and `.txt`, and return the path of the former
+pub fn save(buf: &Buffer, dashboard: &str) -> io::Result<PathBuf> {
+    let stamp = format_time_with(SystemTime::now(), "%Y%m%d-%H%M%S");
+    let path = PathBuf::from(format!("devdash-{}-{}.ans", dashboard, stamp));
+    write(buf, &path)?;
+    write(buf, &path.with_extension("txt"))?;
+    Ok(path)
+}
+
+/// `--export-frame FILE`: poll the widgets of `app` once, draw a frame the
+/// size of the terminal and write it to `path`
+pub fn run(app: &mut App, path: &Path, tick_rate: Duration) -> Result<(), DevdashError> {
+    poll_once(app, tick_rate);
+    let (width, height) = crossterm::terminal::size().unwrap_or(FALLBACK_SIZE);
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("TestBackend never fails");
+    let frame = terminal
+        .draw(|frame| app.render(frame))
+        .expect("TestBackend never fails");
+    write(frame.buffer, path).map_err(|e| DevdashError::file(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{layout::Rect, style::Style};
+
+    fn frame() -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buf.set_string(0, 0, "ok", Style::default().fg(Color::Green));
+        buf.set_string(3, 0, "!", Style::default().bg(Color::Rgb(1, 2, 3)));
+        buf.set_string(
+            0,
+            1,
+            "cpu",
+            Style::default()
+                .fg(Color::Indexed(208))
+                .add_modifier(Modifier::BOLD),
+        );
+        buf
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(plain_text(&frame()), "ok !\ncpu\n");
+    }
+
+    #[test]
+    fn test_ansi() {
+        assert_eq!(
+            ansi(&frame()),
+            "\x1b[0;32mok\x1b[0m \x1b[0;48;2;1;2;3m!\x1b[0m  \x1b[0m\n\
+             \x1b[0;1;38;5;208mcpu\x1b[0m   \x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_write_picks_format_by_extension() {
+        let dir = std::env::temp_dir().join(format!("devdash-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (plain, colored) = (dir.join("frame.txt"), dir.join("frame.ANS"));
+        write(&frame(), &plain).unwrap();
+        write(&frame(), &colored).unwrap();
+        assert_eq!(fs::read_to_string(&plain).unwrap(), plain_text(&frame()));
+        assert_eq!(fs::read_to_string(&colored).unwrap(), ansi(&frame()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,98 @@
+// devdash-cli/src/frame.rs
+//! Render coalescing and frame-rate limiting.
+use std::time::{Duration, Instant};
+
+/// Frame rate used when `--max-fps` isn't given
+pub const DEFAULT_MAX_FPS: u32 = 30;
+
+/// Decides when the screen needs drawing
+///
+/// Changes are only recorded; the draw happens once per frame at most, so
+/// several changes between frames produce a single draw, and nothing is
+/// drawn while nothing changed.
+#[derive(Debug)]
+pub struct FrameLimiter {
+    frame_time: Duration,
+    last_draw: Option<Instant>,
+    dirty: bool,
+}
+
+impl FrameLimiter {
+    /// Limit drawing to `max_fps` frames per second (at least one)
+    pub fn new(max_fps: u32) -> Self {
+        Self {
+            frame_time: Duration::from_secs(1) / max_fps.max(1),
+            last_draw: None,
+            // Nothing is on screen yet
+            dirty: true,
+        }
+    }
+
+    /// Record that something on screen changed
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// When the next draw may happen, if one is pending
+    pub fn next_draw(&self) -> Option<Instant> {
+        if !self.dirty {
+            return None;
+        }
+        Some(match self.last_draw {
+            Some(last) => last + self.frame_time,
+            None => Instant::now(),
+        })
+    }
+
+    /// Whether a draw is pending and allowed at `now`
+    pub fn should_draw(&self, now: Instant) -> bool {
+        self.dirty
+            && self
+                .last_draw
+                .is_none_or(|last| last + self.frame_time <= now)
+    }
+
+    /// Record a draw at `now`
+    pub fn drawn(&mut self, now: Instant) {
+        self.last_draw = Some(now);
+        self.dirty = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_frame_draws_immediately() {
+        let limiter = FrameLimiter::new(30);
+        assert!(limiter.should_draw(Instant::now()));
+    }
+
+    #[test]
+    fn test_changes_within_a_frame_coalesce() {
+        let mut limiter = FrameLimiter::new(10);
+        let start = Instant::now();
+        limiter.drawn(start);
+        assert_eq!(limiter.next_draw(), None);
+
+        limiter.mark_dirty();
+        limiter.mark_dirty();
+        assert!(!limiter.should_draw(start + Duration::from_millis(50)));
+        assert_eq!(
+            limiter.next_draw(),
+            Some(start + Duration::from_millis(100))
+        );
+
+        let frame = start + Duration::from_millis(100);
+        assert!(limiter.should_draw(frame));
+        limiter.drawn(frame);
+        assert!(!limiter.should_draw(frame + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_zero_fps_is_clamped() {
+        let limiter = FrameLimiter::new(0);
+        assert_eq!(limiter.frame_time, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,124 @@
+// devdash-cli/src/inject.rs
+//! Publishes events read from a JSON lines stream onto the bus.
+//!
+//! The input uses the same format as `--event-log`, so a recorded session
+//! can be played back as-is.
+use devdash_core::{BusEvent, EventBus};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::{codec, daemon};
+
+/// One line of injected input
+#[derive(Debug, Deserialize)]
+struct InjectRecord {
+    /// Used to reproduce the spacing between events; optional
+    timestamp_ms: Option<u64>,
+    topic: String,
+    #[serde(rename = "type")]
+    payload_type: String,
+    #[serde(default)]
+    payload: Value,
+}
+
+impl InjectRecord {
+    fn into_event(self) -> Option<BusEvent> {
+        Some(BusEvent {
+            topic: self.topic,
+            payload: codec::decode(&self.payload_type, self.payload)?,
+        })
+    }
+}
+
+/// An event from one line in the `--event-log` format
+///
+/// `None` for invalid lines and unknown payload types.
+pub fn decode_line(line: &str) -> Option<BusEvent> {
+    serde_json::from_str::<InjectRecord>(line)
+        .ok()?
+        .into_event()
+}
+
+/// Background reader publishing injected events
+///
+/// Lines that aren't valid records or carry an unknown payload type are
+/// skipped. Events are paced by the gaps between their timestamps.
+pub struct Injector {
+    stop: Arc<AtomicBool>,
+}
+
+impl Injector {
+    /// Start reading from `path`, or from stdin if `path` is `-`
+    pub fn start(event_bus: &EventBus, path: &Path) -> io::Result<Self> {
+        let reader: Box<dyn BufRead + Send> = if path == Path::new("-") {
+            Box::new(BufReader::new(io::stdin()))
+        } else {
+            Box::new(BufReader::new(File::open(path)?))
+        };
+        Ok(Self::from_reader(event_bus, reader, true))
+    }
+
+    /// Start publishing the events streamed by a daemon on `socket`
+    ///
+    /// The daemon sends events as they happen, so they aren't paced.
+    pub fn attach(event_bus: &EventBus, socket: &Path) -> io::Result<Self> {
+        Ok(Self::from_reader(
+            event_bus,
+            daemon::connect(socket)?,
+            false,
+        ))
+    }
+
+    /// Start publishing the events read from `reader`, paced by their
+    /// timestamps if `pace` is set
+    pub fn from_reader(event_bus: &EventBus, reader: Box<dyn BufRead + Send>, pace: bool) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let event_bus = event_bus.clone();
+        let stopped = stop.clone();
+
+        // Detached: a blocking read on stdin must not hold up shutdown
+        std::thread::spawn(move || {
+            let mut last_timestamp = None;
+
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                if stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let Ok(record) = serde_json::from_str::<InjectRecord>(&line) else {
+                    continue;
+                };
+
+                if let (true, Some(prev), Some(current)) =
+                    (pace, last_timestamp, record.timestamp_ms)
+                {
+                    std::thread::sleep(Duration::from_millis(current.saturating_sub(prev)));
+                }
+                last_timestamp = record.timestamp_ms.or(last_timestamp);
+
+                if let Some(event) = record.into_event() {
+                    event_bus.publish(event);
+                }
+            }
+        });
+
+        Self { stop }
+    }
+}
+
+impl Drop for Injector {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,70 @@
+// devdash-cli/src/input.rs
+//! Terminal input read on a dedicated thread.
+//!
+//! Reading happens independently of rendering and widget updates, so key
+//! presses queue up immediately and the main loop can handle a whole burst
+//! before drawing the next frame.
+use crossterm::event::{self, Event as CEvent};
+use std::{io, thread::JoinHandle, time::Duration};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+/// How long a read blocks before checking whether the reader was dropped
+const POLL_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Background reader forwarding crossterm events to the main loop
+pub struct InputReader {
+    rx: UnboundedReceiver<io::Result<CEvent>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InputReader {
+    pub fn start() -> Self {
+        let (tx, rx) = unbounded_channel();
+        let handle = std::thread::Builder::new()
+            .name("devdash-input".to_string())
+            .spawn(move || read_events(tx))
+            .expect("failed to spawn input thread");
+
+        Self {
+            rx,
+            handle: Some(handle),
+        }
+    }
+
+    /// Wait for the next event
+    ///
+    /// Returns `None` once the input thread has stopped.
+    pub async fn next(&mut self) -> Option<io::Result<CEvent>> {
+        self.rx.recv().await
+    }
+
+    /// Take an already queued event without waiting
+    pub fn try_next(&mut self) -> Option<io::Result<CEvent>> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl Drop for InputReader {
+    fn drop(&mut self) {
+        // Closing the channel stops the thread after its current poll
+        self.rx.close();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn read_events(tx: UnboundedSender<io::Result<CEvent>>) {
+    while !tx.is_closed() {
+        let event = match event::poll(POLL_TIMEOUT) {
+            Ok(true) => event::read(),
+            Ok(false) => continue,
+            Err(e) => Err(e),
+        };
+
+        let failed = event.is_err();
+        if tx.send(event).is_err() || failed {
+            break;
+        }
+    }
+}
@@ -0,0 +1,275 @@
+// devdash-cli/src/keys.rs
+//! `devdash keys`: every key binding as a cheat sheet, and the `?` panel
+//! listing the ones that apply right now.
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget as _},
+};
+use std::{fmt::Write, process::ExitCode, time::Duration};
+
+use devdash_core::{
+    EventBus, KeyBinding, Keymap, PluginManager, Widget, WidgetContainer, WidgetSettings,
+};
+
+use crate::{
+    app::GLOBAL_ACTIONS,
+    args::{CliArgs, KeysFormat},
+    builtin_registry,
+    overlay::{Overlay, z},
+};
+
+/// Bindings grouped under a heading
+pub type Section = (String, Vec<KeyBinding>);
+
+/// Print the bindings of the dashboard and of every available widget, as
+/// remapped in the config's `[keys]` section
+pub fn run(args: &CliArgs, format: KeysFormat) -> ExitCode {
+    let keys = match args.load_config() {
+        Ok(config) => config.keys,
+        Err(e) => {
+            eprintln!("Warning: {}; showing the default keys", e);
+            toml::Table::new()
+        }
+    };
+    print!(
+        "{}",
+        render(&sections(args.plugin_manager(), &keys), format)
+    );
+    ExitCode::SUCCESS
+}
+
+/// Global bindings, then built-in widgets by name, then plugins
+fn sections(mut plugins: PluginManager, keys: &toml::Table) -> Vec<Section> {
+    let mut global = Keymap::new(GLOBAL_ACTIONS);
+    if let Err(e) = global.remap(keys) {
+        eprintln!("Warning: [keys]: {}", e);
+    }
+    let mut sections = vec![("Global".to_string(), global.bindings(GLOBAL_ACTIONS))];
+
+    let mut widgets = builtin_widgets();
+    match plugins.load_all() {
+        Ok(plugins) => widgets.extend(
+            plugins
+                .into_iter()
+                .map(|(name, widget)| (name, Box::new(widget) as Box<dyn Widget>)),
+        ),
+        Err(e) => eprintln!("Warning: Failed to load plugins: {}", e),
+    }
+    for (name, widget) in widgets {
+        let mut container = WidgetContainer::new(name.clone(), widget);
+        if let Some(toml::Value::Table(widget_keys)) = keys.get(&name)
+            && let Err(e) = container.remap_keys(widget_keys)
+        {
+            eprintln!("Warning: [keys.{}]: {}", name, e);
+        }
+        sections.push((name, container.key_bindings()));
+    }
+
+    sections
+}
+
+fn builtin_widgets() -> Vec<(String, Box<dyn Widget>)> {
+    // Widgets are only constructed to ask for their bindings, so keep
+    // them from polling the system
+    let bus = EventBus::new();
+    bus.set_live(false);
+    let mut registry = builtin_registry();
+    let mut names: Vec<String> = registry.list_widgets().into_iter().cloned().collect();
+    names.sort();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let widget = registry
+                .create(&name, &bus, Duration::from_secs(1), &WidgetSettings::new())
+                .ok()?;
+            Some((name, widget))
+        })
+        .collect()
+}
+
+fn render(sections: &[Section], format: KeysFormat) -> String {
+    let mut out = String::new();
+    for (title, bindings) in sections.iter().filter(|(_, b)| !b.is_empty()) {
+        match format {
+            KeysFormat::Text => {
+                let width = bindings.iter().map(|b| b.keys.chars().count()).max();
+                let _ = writeln!(out, "{}", title);
+                for binding in bindings {
+                    let _ = writeln!(
+                        out,
+                        "  {:width$}  {}",
+                        binding.keys,
+                        binding.description,
+                        width = width.unwrap_or(0)
+                    );
+                }
+            }
+            KeysFormat::Markdown => {
+                let _ = writeln!(out, "## {}\n\n| Key | Action |\n| --- | --- |", title);
+                for binding in bindings {
+                    let keys = binding.keys.replace('|', "\\|");
+                    let _ = writeln!(out, "| `{}` | {} |", keys, binding.description);
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Title of the [`HelpPanel`]
+const TITLE: &str = " Keys [Esc to close] ";
+
+/// The dashboard's bindings and the focused widget's, over the dashboard
+pub struct HelpPanel<'a>(pub &'a [Section]);
+
+impl Overlay for HelpPanel<'_> {
+    fn z_index(&self) -> u8 {
+        z::PANEL
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = self
+            .0
+            .iter()
+            .flat_map(|(_, bindings)| bindings)
+            .map(|b| b.keys.chars().count())
+            .max()
+            .unwrap_or(0);
+        let heading = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let mut lines = Vec::new();
+        for (title, bindings) in self.0 {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::styled(title.clone(), heading));
+            if bindings.is_empty() {
+                lines.push(Line::from("  No keys of its own"));
+            }
+            for binding in bindings {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {:width$}  ", binding.keys, width = width),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::from(binding.description),
+                ]));
+            }
+        }
+
+        // Sized to fit, as far as the screen allows
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let content = lines.iter().map(Line::width).max().unwrap_or(0);
+        let panel_width = (content.max(TITLE.len()) as u16 + 2).min(area.width);
+        let [panel] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [panel] = Layout::horizontal([Constraint::Length(panel_width)])
+            .flex(Flex::Center)
+            .areas(panel);
+
+        Clear.render(panel, buf);
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(TITLE))
+            .render(panel, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Section> {
+        vec![
+            (
+                "Global".to_string(),
+                vec![
+                    KeyBinding::new("q", "Quit"),
+                    KeyBinding::new("Ctrl+R", "Reload configuration"),
+                ],
+            ),
+            ("clock".to_string(), vec![]),
+        ]
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            render(&sample(), KeysFormat::Text),
+            "Global\n  q       Quit\n  Ctrl+R  Reload configuration\n\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            render(&sample(), KeysFormat::Markdown),
+            "## Global\n\n| Key | Action |\n| --- | --- |\n\
+             | `q` | Quit |\n| `Ctrl+R` | Reload configuration |\n\n"
+        );
+    }
+
+    #[test]
+    fn test_help_panel() {
+        let area = Rect::new(0, 0, 40, 9);
+        let mut buf = Buffer::empty(area);
+        HelpPanel(&sample()).render(area, &mut buf);
+        let text: Vec<String> = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "",
+                "    ┌ Keys [Esc to close] ─────────┐",
+                "    │Global                        │",
+                "    │  q       Quit                │",
+                "    │  Ctrl+R  Reload configuration│",
+                "    │                              │",
+                "    │clock                         │",
+                "    │  No keys of its own          │",
+                "    └──────────────────────────────┘",
+            ]
+        );
+    }
+
+    fn plugins() -> PluginManager {
+        let mut plugins = PluginManager::new();
+        plugins.set_plugin_dir(std::env::temp_dir().join("devdash-keys-test-no-plugins"));
+        plugins
+    }
+
+    #[test]
+    fn test_builtin_widgets_list_bindings() {
+        let sections = sections(plugins(), &toml::Table::new());
+        let process = sections.iter().find(|(name, _)| name == "process").unwrap();
+        assert!(process.1.contains(&KeyBinding::new("m", "Sort by memory")));
+    }
+
+    #[test]
+    fn test_remapped_bindings() {
+        let keys: toml::Table = toml::from_str(
+            r#"
+            quit = ["ctrl+q", "q"]
+            [process]
+            sort_memory = "M"
+            "#,
+        )
+        .unwrap();
+        let sections = sections(plugins(), &keys);
+        assert!(sections[0].1.contains(&KeyBinding::new("Ctrl+Q/q", "Quit")));
+        let process = sections.iter().find(|(name, _)| name == "process").unwrap();
+        assert!(process.1.contains(&KeyBinding::new("M", "Sort by memory")));
+    }
+}
@@ -1,128 +1,199 @@
 // devdash-cli/src/main.rs
+mod accessible;
+mod alerts;
+mod api;
+mod app;
+mod args;
+mod codec;
+mod daemon;
+mod doctor;
+mod event_log;
+mod export;
+mod frame;
+mod inject;
+mod input;
+mod keys;
+mod notify;
+mod onboarding;
+mod overlay;
+mod plugins;
+mod presets;
+mod remote;
+mod rules;
+mod shared_bus;
+mod snapshot;
+mod statusbar;
+mod validate;
+
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyCode},
+    event::{DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyEventKind},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
-use std::{
-    io,
-    time::{Duration, Instant},
-};
+use std::{io, process::ExitCode, time::Duration};
+use tokio::time::{Instant, MissedTickBehavior};
 
 use devdash_core::{
-    ConfigFile, EventBus, PluginManager, WidgetContainer, WidgetRegistry, flatten_layout_items,
-    register_widget, register_widget_no_bus, widget::CpuWidget,
+    ConfigError, ConfigFile, DevdashError, EventBus, WidgetRegistry, register_widget,
+    register_widget_no_bus, register_widget_with_settings, set_autostart, set_no_color,
+    set_read_only,
 };
 use devdash_widgets::{
-    DiskWidget, ErrorWidget, GitWidget, MemoryWidget, NetworkWidget, ProcessWidget,
+    BackupWidget, CgroupWidget, ClockWidget, CpuWidget, DiagnosticsWidget, DiskWidget,
+    FirewallWidget, GitWidget, HealthWidget, HttpMonitorWidget, LatencyWidget, MemoryWidget,
+    NetworkWidget, PortsWidget, ProcessWidget, ScheduleWidget, SensorsWidget, SessionWidget,
+    SupervisorWidget, TaskWidget, TunnelWidget, UptimeWidget,
 };
 
-fn reload_dashboard(
-    dashboard_name: &str,
-    registry: &mut WidgetRegistry,
-    event_bus: &EventBus,
-    plugin_manager: &mut PluginManager,
-) -> Result<(Vec<WidgetContainer>, devdash_core::Layout), Box<dyn std::error::Error>> {
-    // Re-load config
-    let config = ConfigFile::load()?;
-
-    // Clear existing plugin widgets from registry
-    registry.clear_widgets();
-
-    // Reload plugins and re-register them in the registry
-    let plugin_widgets = plugin_manager.load_all().unwrap_or_else(|e| {
-        eprintln!(
-            "Warning: Failed to reload plugins: {}. Continuing without plugins.",
-            e
-        );
-        Vec::new()
-    });
-
-    // Register plugin widgets in the registry
-    for (name, widget) in plugin_widgets {
-        registry.register_widget(&name, Box::new(widget));
-    }
-
-    // Get specified dashboard by name
-    let dashboard = config
-        .get_dashboard(dashboard_name)
-        .ok_or_else(|| format!("Dashboard '{}' not found", dashboard_name))?;
-
-    // Flatten layout items to get widget list
-    let layout_items = flatten_layout_items(&dashboard.layout);
-
-    // Create new widgets from config
-    let mut new_widgets = Vec::new();
-    for item in layout_items {
-        if let devdash_core::config::ConfigLayoutItem::Widget { name, .. } = item {
-            if let Some(widget) = registry.create(name, event_bus, Duration::from_secs(1)) {
-                new_widgets.push(WidgetContainer::new(name.clone(), widget));
-            } else {
-                // Create error widget for missing/unknown widgets
-                let error_widget = ErrorWidget::plugin_error(name);
-                new_widgets.push(WidgetContainer::new(name.clone(), Box::new(error_widget)));
-            }
+use api::Api;
+use app::{App, Flow};
+use args::{CliArgs, Command};
+use daemon::Server;
+use event_log::EventLogger;
+use frame::FrameLimiter;
+use inject::Injector;
+use input::InputReader;
+use remote::Hosts;
+use shared_bus::SharedBus;
+
+/// How often to look for rebuilt plugins
+pub(crate) const PLUGIN_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to sleep when no widget wants updates
+const IDLE_WAKEUP: Duration = Duration::from_secs(1);
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let args = CliArgs::from_env();
+    set_read_only(args.kiosk);
+    // https://no-color.org: set and not empty
+    set_no_color(args.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()));
+    let result = match args.command {
+        Command::Doctor => return doctor::run(&args),
+        Command::Keys(format) => return keys::run(&args, format),
+        Command::Plugins => return plugins::run(&args),
+        Command::InstallPlugin => return plugins::install(&args),
+        Command::Stream => return remote::stream(args.tick_rate),
+        Command::Agent { listen } => return remote::agent(listen, args.tick_rate),
+        Command::Validate => return validate::run(&args),
+        Command::ListWidgets => return validate::list_widgets(&args),
+        Command::Init { preset, force } => return presets::init(&args, preset, force),
+        _ => run(args).await,
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
         }
     }
-
-    // Convert config layout to runtime layout
-    let new_layout = dashboard.layout.to_layout();
-
-    Ok((new_widgets, new_layout))
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Load config
-    let config = ConfigFile::load().unwrap_or_else(|e| {
-        eprintln!("Warning: Failed to load config: {}. Using default.", e);
-        ConfigFile::default()
-    });
+async fn run(args: CliArgs) -> Result<(), DevdashError> {
+    // Load config; one given with --config has to load
+    let config = match args.load_config() {
+        Ok(config) => config,
+        Err(e) if args.config.is_none() => {
+            eprintln!("Warning: Failed to load config: {}. Using default.", e);
+            ConfigFile::default()
+        }
+        Err(ConfigError::Io(e)) => {
+            return Err(DevdashError::file(
+                args.config.clone().unwrap_or_default(),
+                e,
+            ));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    app::apply_global_settings(&config);
+
+    let dashboard_name = args.dashboard.clone();
+
+    let dashboard = config.require_dashboard(&dashboard_name)?;
+
+    // Only `daemon` and `attach` use it
+    let socket = || {
+        args.socket
+            .clone()
+            .or_else(|| daemon::default_socket(&dashboard_name))
+            .ok_or_else(|| {
+                DevdashError::InvalidArgs(
+                    "no private directory for the daemon socket; give --socket PATH".to_string(),
+                )
+            })
+    };
+
+    // Create event bus; attached clients display the daemon's data
+    let event_bus = EventBus::new();
+    event_bus.set_live(args.live && args.command != Command::Attach);
 
-    // Parse CLI args for dashboard selection
-    let dashboard_name = std::env::args()
-        .nth(1)
-        .filter(|arg| arg.starts_with("--dashboard="))
-        .and_then(|arg| arg.strip_prefix("--dashboard=").map(String::from))
-        .unwrap_or_else(|| "default".to_string());
-
-    let dashboard = config.get_dashboard(&dashboard_name).ok_or_else(|| {
-        format!(
-            "Dashboard '{}' not found. Available: {}",
-            dashboard_name,
-            config
-                .dashboard
-                .iter()
-                .map(|d| d.name.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        )
-    })?;
-
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Optionally record the event stream
+    let event_logger = match &args.event_log {
+        Some(path) if args.command == Command::Record => {
+            Some(EventLogger::create(&event_bus, path).map_err(|e| DevdashError::file(path, e))?)
+        }
+        Some(path) => {
+            Some(EventLogger::start(&event_bus, path).map_err(|e| DevdashError::file(path, e))?)
+        }
+        None => None,
+    };
+
+    // Optionally publish events from a file or stdin, or from the daemon
+    let injector = match &args.inject {
+        _ if args.command == Command::Attach => {
+            let socket = socket()?;
+            Some(
+                Injector::attach(&event_bus, &socket)
+                    .map_err(|e| DevdashError::file(&socket, e))?,
+            )
+        }
+        Some(path) => {
+            Some(Injector::start(&event_bus, path).map_err(|e| DevdashError::file(path, e))?)
+        }
+        None => None,
+    };
 
-    // Create event bus
-    let event_bus = EventBus::new();
+    let shared_bus = match &args.share_bus {
+        Some(path) => {
+            Some(SharedBus::start(&event_bus, path).map_err(|e| DevdashError::file(path, e))?)
+        }
+        None => None,
+    };
+
+    let api = match args.api {
+        Some(addr) => Some(
+            Api::start(&event_bus, addr)
+                .map_err(|e| DevdashError::InvalidArgs(format!("--api {}: {}", addr, e)))?,
+        ),
+        None => None,
+    };
+
+    let server = match args.command {
+        Command::Daemon => {
+            let socket = socket()?;
+            Some(Server::start(&event_bus, &socket).map_err(|e| DevdashError::file(&socket, e))?)
+        }
+        _ => None,
+    };
 
     // Build widget registry
-    let mut registry = WidgetRegistry::new();
-    register_widget!(registry, "process", ProcessWidget);
-    register_widget_no_bus!(registry, "cpu", CpuWidget);
-    register_widget!(registry, "memory", MemoryWidget);
-    register_widget!(registry, "disk", DiskWidget);
-    register_widget!(registry, "network", NetworkWidget);
-    register_widget!(registry, "git", GitWidget);
+    let mut registry = builtin_registry();
 
     // Register plugin widgets (they'll be loaded dynamically)
     // The plugin system will handle creating these widgets
 
     // Load plugins and register them
-    let mut plugin_manager = PluginManager::new();
+    let mut plugin_manager = args.plugin_manager();
+    // A project's own devdash.toml starts nothing until the project is
+    // trusted, which is also asked before the dashboard takes the screen
+    set_autostart(plugins::trust_config(&args, &config, &plugin_manager));
+    plugin_manager.set_settings(app::widget_settings(dashboard));
+    plugin_manager.set_offline(!args.live);
+    // Permissions can only be asked for before the dashboard takes the screen
+    plugin_manager.set_permission_prompt(Some(Box::new(plugins::ask_permission)));
+    plugin_manager.set_project_prompt(Some(Box::new(plugins::ask_trust_project)));
     let plugin_widgets = plugin_manager.load_all().unwrap_or_else(|e| {
         eprintln!(
             "Warning: Failed to load plugins: {}. Continuing without plugins.",
@@ -130,6 +201,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
         Vec::new()
     });
+    plugin_manager.set_permission_prompt(None);
+    plugin_manager.set_project_prompt(None);
 
     // Start watching for plugin changes
     if let Err(e) = plugin_manager.watch() {
@@ -144,147 +217,196 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         registry.register_widget(&name, Box::new(widget));
     }
 
-    // Create widgets from config
-    let mut widgets = Vec::new();
-
-    for item in flatten_layout_items(&dashboard.layout) {
-        if let devdash_core::config::ConfigLayoutItem::Widget { name, .. } = item {
-            if let Some(widget) = registry.create(name, &event_bus, Duration::from_secs(1)) {
-                widgets.push(WidgetContainer::new(name.clone(), widget));
-            } else {
-                // Create error widget for missing/unknown widgets
-                let error_widget = ErrorWidget::plugin_error(name);
-                widgets.push(WidgetContainer::new(name.clone(), Box::new(error_widget)));
-            }
-        }
+    // A snapshot prints what the widgets publish when mounted, too
+    let snapshot_events = match args.command {
+        Command::Snapshot(_) => Some(event_bus.subscribe("*")),
+        _ => None,
+    };
+
+    let hosts = Hosts::connect(&config, dashboard, args.connect.as_deref());
+    let mut app = App::new(dashboard, &args, event_bus, registry, plugin_manager, hosts);
+    app.remap_keys(&config.keys);
+    app.configure_status_bar(&config);
+    app.configure_alert_rules(&config);
+    app.mount();
+    if let Some(api) = api {
+        app.set_api(api);
     }
 
-    // Convert config layout to runtime layout
-    let mut layout = dashboard.layout.to_layout();
+    // Without a config file, offer to write one rather than silently
+    // showing the defaults
+    if args.command == Command::Run
+        && !args.accessible
+        && !args.kiosk
+        && args.export_frame.is_none()
+        && args.config.is_none()
+        && let Ok(None) = ConfigFile::find()
+        && let Ok([_, path]) = ConfigFile::search_paths()
+    {
+        app.start_onboarding(path);
+    }
 
-    // Focus management
-    let mut focused_widget = 0;
+    let result = if let Command::Snapshot(format) = args.command
+        && let Some((_, events)) = &snapshot_events
+    {
+        snapshot::run(&mut app, &dashboard_name, format, args.tick_rate, events)
+    } else if let Some(path) = &args.export_frame {
+        export::run(&mut app, path, args.tick_rate)
+    } else if let Some(server) = &server {
+        daemon::run(&mut app, server).await
+    } else if args.accessible {
+        accessible::run(&mut app).await
+    } else {
+        run_tui(&mut app, args.max_fps).await
+    };
+
+    // Cleanup; dropping the app drops the plugin manager, which must happen
+    // after its widgets are unmounted
+    app.unmount();
+    app.save_session();
+    drop(app);
+
+    // Stop serving and injecting, then flush remaining events to the log
+    drop(server);
+    drop(shared_bus);
+    drop(injector);
+    drop(event_logger);
+
+    result
+}
 
-    // Mount all widgets
-    for widget in widgets.iter_mut() {
-        widget.mount();
-    }
+/// Registry of the built-in widgets
+fn builtin_registry() -> WidgetRegistry {
+    let mut registry = WidgetRegistry::new();
+    register_widget_with_settings!(registry, "process", ProcessWidget);
+    register_widget!(registry, "cpu", CpuWidget);
+    register_widget!(registry, "memory", MemoryWidget);
+    register_widget_with_settings!(registry, "disk", DiskWidget);
+    register_widget_with_settings!(registry, "network", NetworkWidget);
+    register_widget_with_settings!(registry, "git", GitWidget);
+    register_widget_no_bus!(registry, "cgroups", CgroupWidget);
+    register_widget_no_bus!(registry, "uptime", UptimeWidget);
+    register_widget!(registry, "sessions", SessionWidget);
+    register_widget_with_settings!(registry, "firewall", FirewallWidget);
+    register_widget_with_settings!(registry, "schedule", ScheduleWidget);
+    register_widget_with_settings!(registry, "backup", BackupWidget);
+    register_widget_with_settings!(registry, "health", HealthWidget);
+    register_widget_with_settings!(registry, "diagnostics", DiagnosticsWidget);
+    register_widget_with_settings!(registry, "tasks", TaskWidget);
+    register_widget_with_settings!(registry, "supervisor", SupervisorWidget);
+    register_widget_with_settings!(registry, "latency", LatencyWidget);
+    register_widget_with_settings!(registry, "http", HttpMonitorWidget);
+    register_widget_with_settings!(registry, "tunnels", TunnelWidget);
+    register_widget_with_settings!(registry, "sensors", SensorsWidget);
+    register_widget_with_settings!(registry, "ports", PortsWidget);
+    register_widget_with_settings!(registry, "clock", ClockWidget);
+    registry
+}
 
-    // Main loop
-    let tick_rate = Duration::from_millis(100);
-    let mut last_tick = Instant::now();
+/// Draw the dashboard in the alternate screen until the user quits
+async fn run_tui(app: &mut App, max_fps: u32) -> Result<(), DevdashError> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
 
-    loop {
-        // Render
-        terminal.draw(|f| {
-            let area = f.area();
-            let buf = f.buffer_mut();
-
-            // Calculate layout areas
-            let areas = layout.calculate(area);
-
-            // Render each widget in its allocated area
-            for (i, (widget, widget_area)) in widgets.iter_mut().zip(areas).enumerate() {
-                let is_focused = i == focused_widget;
-                widget.render_focused(widget_area, buf, is_focused);
-            }
-        })?;
-
-        // Handle input with timeout
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-
-        if event::poll(timeout)?
-            && let CEvent::Key(key) = event::read()?
-        {
-            // Only handle key press events, not key release
-            if key.kind == crossterm::event::KeyEventKind::Press {
-                // Quit on 'q'
-                if key.code == KeyCode::Char('q') {
-                    break;
-                }
+    let result = run_loop(&mut terminal, app, max_fps).await;
 
-                // Reload config on Ctrl+r
-                if key.code == KeyCode::Char('r')
-                    && key
-                        .modifiers
-                        .contains(crossterm::event::KeyModifiers::CONTROL)
-                {
-                    match reload_dashboard(
-                        &dashboard_name,
-                        &mut registry,
-                        &event_bus,
-                        &mut plugin_manager,
-                    ) {
-                        Ok((new_widgets, new_layout)) => {
-                            // Unmount old widgets
-                            for w in widgets.iter_mut() {
-                                w.unmount();
-                            }
-
-                            // Replace with new
-                            widgets = new_widgets;
-                            layout = new_layout;
-
-                            // Mount new widgets
-                            for w in widgets.iter_mut() {
-                                w.mount();
-                            }
-
-                            // Reset focus
-                            focused_widget = 0;
-                        }
-                        Err(e) => {
-                            eprintln!("Config reload failed: {}. Keeping old config.", e);
-                        }
-                    }
-                    continue;
-                }
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
 
-                // Handle focus management
-                if key.code == KeyCode::Tab {
-                    focused_widget = (focused_widget + 1) % widgets.len();
-                    continue;
-                }
+    result
+}
 
-                // Pass event only to focused widget
-                let widget_event = devdash_core::Event::Key(key);
-                if let Some(focused) = widgets.get_mut(focused_widget) {
-                    focused.handle_event(widget_event);
-                }
-            }
-        }
+/// Wait for input, widget updates, and plugin changes, redrawing at most
+/// `max_fps` times per second and only when something changed
+///
+/// Widgets' `on_update` still runs here, on the UI task, so a widget that
+/// blocks in it delays input and drawing until it returns. The built-in
+/// widgets only read cheap sources (sysinfo, /proc) inline and hand anything
+/// slower to a `Collector`; plugins are expected to do the same.
+async fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    max_fps: u32,
+) -> Result<(), DevdashError> {
+    let mut input = InputReader::start();
+    let mut plugin_check = tokio::time::interval(PLUGIN_CHECK_INTERVAL);
+    plugin_check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut frames = FrameLimiter::new(max_fps);
 
-        // Check for plugin changes (hot-reload)
-        if let Err(e) = plugin_manager.check_for_changes(&mut widgets) {
-            eprintln!("Plugin reload error: {}", e);
+    loop {
+        let now = std::time::Instant::now();
+        if frames.should_draw(now) {
+            terminal.draw(|f| app.render(f))?;
+            frames.drawn(now);
         }
 
-        // Update widgets on tick
-        if last_tick.elapsed() >= tick_rate {
-            for widget in widgets.iter_mut() {
-                widget.update();
+        // Sleep until the next widget is due or a pending frame may be
+        // drawn; suspending a widget during render can push the former
+        // out, so compute it after drawing
+        let deadline = [app.next_update(), app.next_clock_tick(), frames.next_draw()]
+            .into_iter()
+            .flatten()
+            .min()
+            .map_or_else(|| Instant::now() + IDLE_WAKEUP, Instant::from_std);
+
+        tokio::select! {
+            // Input goes first so keys are never starved by busy widgets
+            biased;
+
+            event = input.next() => {
+                let Some(event) = event else {
+                    return Ok(());
+                };
+
+                // Handle everything queued since the last frame before
+                // drawing again, so bursts of typing don't lag behind
+                let mut next = Some(event);
+                while let Some(event) = next {
+                    match handle_input(app, event)? {
+                        Some(Flow::Quit) => return Ok(()),
+                        Some(Flow::Continue) => frames.mark_dirty(),
+                        None => {}
+                    }
+                    next = input.try_next();
+                }
+            }
+            () = tokio::time::sleep_until(deadline) => {
+                let now = std::time::Instant::now();
+                if app.update_due(now) | app.clock_changed() | app.check_alerts() | app.check_links() {
+                    frames.mark_dirty();
+                }
+            }
+            _ = plugin_check.tick() => {
+                if app.check_plugins() | app.check_alerts() | app.check_links() {
+                    frames.mark_dirty();
+                }
+                match app.check_actions()? {
+                    Some(Flow::Quit) => return Ok(()),
+                    Some(Flow::Continue) => frames.mark_dirty(),
+                    None => {}
+                }
             }
-            last_tick = Instant::now();
         }
     }
+}
 
-    // Cleanup
-    for widget in widgets.iter_mut() {
-        widget.unmount();
+/// Apply one input event
+///
+/// Returns `None` if the event was ignored and nothing needs redrawing.
+fn handle_input(app: &mut App, event: io::Result<CEvent>) -> Result<Option<Flow>, DevdashError> {
+    match event? {
+        // Only handle key press events, not key release
+        CEvent::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key).map(Some),
+        CEvent::Mouse(mouse) => Ok(app.handle_mouse(mouse).then_some(Flow::Continue)),
+        CEvent::Resize(..) => Ok(Some(Flow::Continue)),
+        _ => Ok(None),
     }
-
-    // Explicitly drop plugin manager to ensure proper cleanup
-    drop(plugin_manager);
-
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-
-    Ok(())
 }
@@ -0,0 +1,168 @@
+// devdash-cli/src/notify.rs
+//! Desktop notifications for alerts, so they're seen while devdash runs in
+//! a background terminal.
+//!
+//! Notifications go through the desktop's own tool: `notify-send` on Linux
+//! and the BSDs, `osascript` on macOS, and a PowerShell toast on Windows.
+use devdash_core::ConfigFile;
+use serde::Deserialize;
+use std::process::{Command, Stdio};
+
+use crate::alerts::{Alert, Severity};
+
+/// App ID PowerShell's own toasts show under; toasts from an unregistered
+/// ID are dropped silently
+#[cfg(windows)]
+const POWERSHELL_APP_ID: &str =
+    r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+/// Shows a toast with the title and text in `DEVDASH_TITLE` and
+/// `DEVDASH_BODY`, which spares quoting them
+#[cfg(windows)]
+const TOAST_SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null
+$template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02)
+$text = $template.GetElementsByTagName('text')
+$text.Item(0).AppendChild($template.CreateTextNode($env:DEVDASH_TITLE)) > $null
+$text.Item(1).AppendChild($template.CreateTextNode($env:DEVDASH_BODY)) > $null
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:DEVDASH_APP_ID).Show([Windows.UI.Notifications.ToastNotification]::new($template))
+"#;
+
+/// `[notifications]` settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    /// Least severe alert that notifies; `[[alerts]]` rules may override
+    /// it with `notify`
+    pub severity: Severity,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            severity: Severity::Critical,
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// The `[notifications]` section of a config file, or the defaults if
+    /// it has none
+    pub fn from_config(config: &ConfigFile) -> Result<Self, String> {
+        let Some(value) = config.settings.get("notifications") else {
+            return Ok(Self::default());
+        };
+        value
+            .clone()
+            .try_into()
+            .map_err(|e| e.message().to_string())
+    }
+
+    /// Whether an alert of `severity` notifies; `rule` is the `notify`
+    /// setting of the rule that raised it, if any
+    pub fn wants(&self, severity: Severity, rule: Option<bool>) -> bool {
+        self.enabled && rule.unwrap_or(severity >= self.severity)
+    }
+}
+
+/// The command showing a notification on this platform
+fn command(title: &str, body: &str, severity: Severity) -> Command {
+    if cfg!(target_os = "macos") {
+        // Passed as arguments, so they need no AppleScript quoting
+        let mut osascript = Command::new("osascript");
+        osascript.args([
+            "-e",
+            "on run argv",
+            "-e",
+            "display notification (item 2 of argv) with title (item 1 of argv)",
+            "-e",
+            "end run",
+            title,
+            body,
+        ]);
+        osascript
+    } else if cfg!(windows) {
+        let mut powershell = Command::new("powershell");
+        powershell.args(["-NoProfile", "-NonInteractive", "-Command"]);
+        #[cfg(windows)]
+        powershell
+            .arg(TOAST_SCRIPT)
+            .env("DEVDASH_APP_ID", POWERSHELL_APP_ID);
+        powershell
+            .env("DEVDASH_TITLE", title)
+            .env("DEVDASH_BODY", body);
+        powershell
+    } else {
+        let urgency = match severity {
+            Severity::Warning => "normal",
+            Severity::Critical => "critical",
+        };
+        let mut notify_send = Command::new("notify-send");
+        notify_send.args(["--app-name=devdash", "--urgency", urgency, title, body]);
+        notify_send
+    }
+}
+
+/// Show `alert` as a desktop notification
+///
+/// Runs in the background; a missing or failing notifier is ignored, as
+/// there is nowhere to report it without disturbing the dashboard.
+pub fn send(alert: &Alert) {
+    let title = format!("devdash: {}", alert.severity.label());
+    let mut command = command(&title, &alert.message, alert.severity);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Ok(mut child) = command.spawn() {
+        std::thread::spawn(move || child.wait());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<NotificationConfig, String> {
+        NotificationConfig::from_config(&toml::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = parse("").unwrap();
+        assert!(!config.enabled);
+        assert!(!config.wants(Severity::Critical, Some(true)));
+
+        let config = parse("[notifications]\nenabled = true").unwrap();
+        assert!(config.wants(Severity::Critical, None));
+        assert!(!config.wants(Severity::Warning, None));
+        assert!(config.wants(Severity::Warning, Some(true)));
+        assert!(!config.wants(Severity::Critical, Some(false)));
+
+        let config = parse("[notifications]\nenabled = true\nseverity = \"warning\"").unwrap();
+        assert!(config.wants(Severity::Warning, None));
+
+        assert!(parse("[notifications]\nseverity = \"info\"").is_err());
+        assert!(parse("[notifications]\nsound = true").is_err());
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_command() {
+        let command = command("devdash: CRITICAL", "/home is 97% full", Severity::Critical);
+        assert_eq!(command.get_program(), "notify-send");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "--app-name=devdash",
+                "--urgency",
+                "critical",
+                "devdash: CRITICAL",
+                "/home is 97% full"
+            ]
+        );
+    }
+}
@@ -0,0 +1,380 @@
+// devdash-cli/src/onboarding.rs
+//! First-run setup: when no config file exists, a panel for picking widgets
+//! and a layout style that writes the config file from them.
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+    },
+};
+use std::path::PathBuf;
+
+use devdash_core::{ConfigFile, config::ConfigLayoutItem};
+
+use crate::{
+    overlay::{Overlay, z},
+    presets::{column, row, single_dashboard, widget, write_config},
+};
+
+const TITLE: &str = " Welcome to devdash ";
+
+/// Built-in widgets offered, with what they show; the ones needing
+/// settings to show anything are left out
+pub const WIDGETS: [(&str, &str); 17] = [
+    ("cpu", "CPU usage per core"),
+    ("memory", "Memory and swap usage"),
+    ("process", "Processes by CPU and memory"),
+    ("disk", "Disk usage and I/O"),
+    ("network", "Network traffic per interface"),
+    ("ports", "Listening ports and their processes"),
+    ("uptime", "Uptime and load averages"),
+    ("clock", "Time, date and other time zones"),
+    ("sensors", "CPU, GPU and drive temperatures"),
+    ("git", "Status of the current repository"),
+    ("diagnostics", "Compiler errors and warnings"),
+    ("tasks", "TODO and FIXME comments"),
+    ("sessions", "Logged-in users"),
+    ("cgroups", "Resource usage per cgroup"),
+    ("schedule", "Cron jobs and systemd timers"),
+    ("firewall", "Firewall rules"),
+    ("health", "Kernel log errors and failed units"),
+];
+
+/// Widgets picked to begin with: the ones on the default dashboard
+const PICKED: [&str; 3] = ["cpu", "memory", "process"];
+
+/// How the picked widgets are arranged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutStyle {
+    /// Two widgets to a row
+    Grid,
+    /// Side by side
+    Columns,
+    /// Stacked top to bottom
+    Rows,
+}
+
+impl LayoutStyle {
+    pub const ALL: [LayoutStyle; 3] = [LayoutStyle::Grid, LayoutStyle::Columns, LayoutStyle::Rows];
+
+    fn description(self) -> &'static str {
+        match self {
+            LayoutStyle::Grid => "Grid      two widgets to a row",
+            LayoutStyle::Columns => "Columns   side by side",
+            LayoutStyle::Rows => "Rows      stacked top to bottom",
+        }
+    }
+
+    /// `names` arranged in this style
+    fn layout(self, names: &[&str]) -> ConfigLayoutItem {
+        let widgets = names.iter().map(|name| widget(name, 1));
+        match self {
+            LayoutStyle::Grid => column(
+                names
+                    .chunks(2)
+                    .map(|pair| row(pair.iter().map(|name| widget(name, 1)).collect()))
+                    .collect(),
+            ),
+            LayoutStyle::Columns => row(widgets.collect()),
+            LayoutStyle::Rows => column(widgets.collect()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Widgets,
+    Layout,
+}
+
+/// What became of the setup after a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Still being filled in
+    Open,
+    /// Closed without writing a config file
+    Skipped,
+    /// The config file was written to this path
+    Written(PathBuf),
+}
+
+/// The first-run setup panel
+pub struct Onboarding {
+    /// Config file to write
+    path: PathBuf,
+    /// Dashboard to write, the one being shown
+    dashboard: String,
+    step: Step,
+    /// Whether each of [`WIDGETS`] is picked
+    picked: [bool; WIDGETS.len()],
+    /// Highlighted row of the current step
+    cursor: usize,
+    style: LayoutStyle,
+    error: Option<String>,
+}
+
+impl Onboarding {
+    pub fn new(path: PathBuf, dashboard: &str) -> Self {
+        Self {
+            path,
+            dashboard: dashboard.to_string(),
+            step: Step::Widgets,
+            picked: WIDGETS.map(|(name, _)| PICKED.contains(&name)),
+            cursor: 0,
+            style: LayoutStyle::Grid,
+            error: None,
+        }
+    }
+
+    /// Names of the picked widgets, in the order offered
+    fn picked(&self) -> Vec<&'static str> {
+        WIDGETS
+            .iter()
+            .zip(self.picked)
+            .filter(|(_, picked)| *picked)
+            .map(|((name, _), _)| *name)
+            .collect()
+    }
+
+    /// The config file the choices so far make
+    pub fn config(&self) -> ConfigFile {
+        single_dashboard(&self.dashboard, self.style.layout(&self.picked()))
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> Outcome {
+        let rows = match self.step {
+            Step::Widgets => WIDGETS.len(),
+            Step::Layout => LayoutStyle::ALL.len(),
+        };
+        match (self.step, key.code) {
+            (_, KeyCode::Up | KeyCode::Char('k')) => {
+                self.cursor = (self.cursor + rows - 1) % rows;
+            }
+            (_, KeyCode::Down | KeyCode::Char('j')) => self.cursor = (self.cursor + 1) % rows,
+            (Step::Widgets, KeyCode::Char(' ')) => {
+                self.picked[self.cursor] = !self.picked[self.cursor];
+                self.error = None;
+            }
+            (Step::Widgets, KeyCode::Enter) if self.picked().is_empty() => {
+                self.error = Some("Pick at least one widget".to_string());
+            }
+            (Step::Widgets, KeyCode::Enter) => {
+                self.step = Step::Layout;
+                self.cursor = LayoutStyle::ALL
+                    .iter()
+                    .position(|style| *style == self.style)
+                    .unwrap_or(0);
+            }
+            (Step::Widgets, KeyCode::Esc) => return Outcome::Skipped,
+            (Step::Layout, KeyCode::Enter) => {
+                self.style = LayoutStyle::ALL[self.cursor];
+                return match self.write() {
+                    Ok(()) => Outcome::Written(self.path.clone()),
+                    Err(e) => {
+                        self.error = Some(e);
+                        Outcome::Open
+                    }
+                };
+            }
+            (Step::Layout, KeyCode::Esc | KeyCode::Backspace) => {
+                self.style = LayoutStyle::ALL[self.cursor];
+                self.step = Step::Widgets;
+                self.cursor = 0;
+            }
+            _ => {}
+        }
+        Outcome::Open
+    }
+
+    fn write(&self) -> Result<(), String> {
+        let text = toml::to_string(&self.config()).map_err(|e| e.to_string())?;
+        write_config(
+            &self.path,
+            &format!(
+                "# Written by devdash's first-run setup; see the README for the\n\
+                 # widgets' settings and the other sections this file may hold.\n\n{}",
+                text
+            ),
+        )
+    }
+
+    /// The heading and rows of the current step
+    fn lines(&self) -> (&'static str, Vec<ListItem<'static>>) {
+        match self.step {
+            Step::Widgets => (
+                "Pick widgets: space picks, enter goes on, esc keeps the defaults",
+                WIDGETS
+                    .iter()
+                    .zip(self.picked)
+                    .map(|((name, description), picked)| {
+                        ListItem::new(Line::from(vec![
+                            Span::from(if picked { "[x] " } else { "[ ] " }),
+                            Span::styled(format!("{:<12}", name), Style::default().fg(Color::Cyan)),
+                            Span::from(*description),
+                        ]))
+                    })
+                    .collect(),
+            ),
+            Step::Layout => (
+                "Pick a layout: enter writes the config, esc goes back",
+                LayoutStyle::ALL
+                    .iter()
+                    .map(|style| ListItem::new(style.description()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Overlay for Onboarding {
+    fn z_index(&self) -> u8 {
+        z::PANEL
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let (heading, items) = self.lines();
+        let footer = match &self.error {
+            Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+            None => Line::styled(
+                format!("Writes {}", self.path.display()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        };
+
+        // Heading, a blank line, the rows, a blank line and the footer
+        let height = (items.len() as u16 + 6).min(area.height);
+        let width = (heading.len().max(footer.width()) as u16 + 4).min(area.width);
+        let [panel] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [panel] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(panel);
+
+        Clear.render(panel, buf);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(TITLE)
+            .border_style(Style::default().fg(Color::Yellow));
+        let inner = block.inner(panel);
+        block.render(panel, buf);
+        let [heading_area, list_area, footer_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
+        .areas(inner.inner(ratatui::layout::Margin::new(1, 0)));
+
+        Paragraph::new(heading)
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .render(heading_area, buf);
+        let mut state = ListState::default().with_selected(Some(self.cursor));
+        StatefulWidget::render(
+            List::new(items).highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            list_area,
+            buf,
+            &mut state,
+        );
+        let [_, footer_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(footer_area);
+        Paragraph::new(footer).render(footer_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{args::CliArgs, builtin_registry, validate::problems};
+    use crossterm::event::KeyModifiers;
+
+    fn press(onboarding: &mut Onboarding, code: KeyCode) -> Outcome {
+        onboarding.handle_key(&KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_every_widget_works_unconfigured() {
+        let mut onboarding = Onboarding::new(PathBuf::new(), "default");
+        onboarding.picked = [true; WIDGETS.len()];
+        for style in LayoutStyle::ALL {
+            onboarding.style = style;
+            let problems = problems(
+                &onboarding.config(),
+                &mut builtin_registry(),
+                &[],
+                &CliArgs::default(),
+            );
+            assert_eq!(problems, Vec::<String>::new(), "{:?}", style);
+        }
+    }
+
+    #[test]
+    fn test_layout_styles() {
+        let names = |item: &ConfigLayoutItem| -> Vec<String> {
+            match item {
+                ConfigLayoutItem::Widget { name, .. } => vec![name.clone()],
+                ConfigLayoutItem::Layout { items, .. } => items
+                    .iter()
+                    .map(|item| match item {
+                        ConfigLayoutItem::Layout { items, .. } => format!("{} items", items.len()),
+                        ConfigLayoutItem::Widget { name, .. } => name.clone(),
+                        _ => String::new(),
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            }
+        };
+        let picked = ["cpu", "memory", "disk"];
+        assert_eq!(
+            names(&LayoutStyle::Grid.layout(&picked)),
+            ["2 items", "1 items"]
+        );
+        assert_eq!(
+            names(&LayoutStyle::Rows.layout(&picked)),
+            ["cpu", "memory", "disk"]
+        );
+    }
+
+    #[test]
+    fn test_writes_config() {
+        let dir = std::env::temp_dir().join(format!("devdash-onboarding-{}", std::process::id()));
+        let path = dir.join("devdash.toml");
+        let mut onboarding = Onboarding::new(path.clone(), "default");
+
+        // Unpick everything, which can't go on
+        for (name, _) in WIDGETS {
+            if PICKED.contains(&name) {
+                press(&mut onboarding, KeyCode::Char(' '));
+            }
+            press(&mut onboarding, KeyCode::Down);
+        }
+        assert_eq!(press(&mut onboarding, KeyCode::Enter), Outcome::Open);
+        assert!(onboarding.error.is_some());
+
+        // Pick the first widget, and lay it out in rows
+        press(&mut onboarding, KeyCode::Char(' '));
+        press(&mut onboarding, KeyCode::Enter);
+        press(&mut onboarding, KeyCode::Up);
+        assert_eq!(
+            press(&mut onboarding, KeyCode::Enter),
+            Outcome::Written(path.clone())
+        );
+        let config = ConfigFile::load_from(&path).unwrap();
+        assert_eq!(config.dashboard[0].name, "default");
+        assert_eq!(
+            config.dashboard[0].layout,
+            single_dashboard("default", LayoutStyle::Rows.layout(&["cpu"])).dashboard[0].layout
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+
+        let mut onboarding = Onboarding::new(path, "default");
+        assert_eq!(press(&mut onboarding, KeyCode::Esc), Outcome::Skipped);
+    }
+}
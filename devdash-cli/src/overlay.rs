@@ -0,0 +1,100 @@
+// devdash-cli/src/overlay.rs
+//! Layers drawn over the dashboard after the widgets.
+use devdash_core::CommandPalette;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    widgets::{Clear, Paragraph, Widget as _},
+};
+
+/// Stacking order of the built-in overlays; higher is drawn on top
+pub mod z {
+    /// Alert banner across the top of the screen
+    pub const BANNER: u8 = 10;
+    /// Alert popups down the right side
+    pub const TOAST: u8 = 15;
+    /// Panels opened with a key, such as the alert history
+    pub const PANEL: u8 = 20;
+    /// Questions and notices on the bottom line
+    pub const PROMPT: u8 = 30;
+}
+
+/// Something drawn over the dashboard
+pub trait Overlay {
+    /// Stacking order; overlays with a higher index cover lower ones
+    fn z_index(&self) -> u8;
+
+    /// Draw over the whole screen area, covering only what it needs
+    fn render(&self, area: Rect, buf: &mut Buffer);
+}
+
+/// One line of text across the bottom of the screen
+pub struct BottomLine<'a> {
+    pub text: &'a str,
+    pub style: Style,
+}
+
+impl Overlay for BottomLine<'_> {
+    fn z_index(&self) -> u8 {
+        z::PROMPT
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        let line = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        Clear.render(line, buf);
+        Paragraph::new(self.text)
+            .style(self.style)
+            .render(line, buf);
+    }
+}
+
+impl Overlay for CommandPalette {
+    fn z_index(&self) -> u8 {
+        z::PANEL
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        ratatui::widgets::Widget::render(self, area, buf);
+    }
+}
+
+/// Draw `overlays` bottom to top
+pub fn render_all(mut overlays: Vec<&dyn Overlay>, area: Rect, buf: &mut Buffer) {
+    overlays.sort_by_key(|overlay| overlay.z_index());
+    for overlay in overlays {
+        overlay.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fill(u8, &'static str);
+
+    impl Overlay for Fill {
+        fn z_index(&self) -> u8 {
+            self.0
+        }
+
+        fn render(&self, area: Rect, buf: &mut Buffer) {
+            buf[(area.x, area.y)].set_symbol(self.1);
+        }
+    }
+
+    #[test]
+    fn test_higher_z_draws_on_top() {
+        let area = Rect::new(0, 0, 1, 1);
+        let mut buf = Buffer::empty(area);
+        render_all(
+            vec![&Fill(z::PANEL, "p"), &Fill(z::BANNER, "b")],
+            area,
+            &mut buf,
+        );
+        assert_eq!(buf[(0, 0)].symbol(), "p");
+    }
+}
@@ -0,0 +1,233 @@
+// devdash-cli/src/plugins.rs
+//! `devdash plugins list`: installed plugins and what they support,
+//! `devdash plugins install`, and asking the user to grant plugins their
+//! permissions and to trust a project's plugins and commands.
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use devdash_core::{
+    ConfigFile, PluginManager,
+    plugin::{PLUGIN_API_VERSION, PermissionRequest},
+    plugin_lock::LOCK_FILE,
+};
+
+use crate::args::CliArgs;
+
+/// Print each plugin library with its widget name and capabilities, or why
+/// it failed to load
+pub fn run(args: &CliArgs) -> ExitCode {
+    let mut manager = args.plugin_manager();
+    let results = match manager.load_each() {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let dirs: Vec<String> = manager
+        .plugin_dirs()
+        .iter()
+        .map(|dir| dir.display().to_string())
+        .collect();
+    println!(
+        "Plugin directories: {} (API version {})",
+        dirs.join(", "),
+        PLUGIN_API_VERSION
+    );
+    if let Some(dir) = manager.untrusted_project() {
+        println!(
+            "Skipped {}: the project isn't trusted; start devdash there to be asked",
+            dir.display()
+        );
+    }
+    if results.is_empty() {
+        println!("No plugins installed");
+        return ExitCode::SUCCESS;
+    }
+
+    let mut failed = false;
+    for (path, result) in results {
+        let file = path.display();
+        match result {
+            Ok((name, widget)) => {
+                println!(
+                    "{}  {}  capabilities: {}  permissions: {}",
+                    file,
+                    name,
+                    widget.capabilities(),
+                    widget.permissions()
+                )
+            }
+            Err(e) => {
+                failed = true;
+                println!("{}  failed to load: {}", file, e);
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Install the plugin library given on the command line, checking its
+/// signature if a key is configured, and record its checksum
+pub fn install(args: &CliArgs) -> ExitCode {
+    let file = args.plugin_file.as_ref().expect("FILE is required");
+    let mut manager = args.plugin_manager();
+    match manager.install(file) {
+        Ok(target) => {
+            println!(
+                "Installed {} and recorded its checksum in {}",
+                target.display(),
+                manager.plugin_dir().join(LOCK_FILE).display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Ask on the terminal whether to grant a plugin what it asks for; refused
+/// without asking when stdin isn't a terminal
+pub fn ask_permission(request: &PermissionRequest) -> bool {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return false;
+    }
+    eprint!(
+        "Plugin '{}' ({}) asks for: {}\nAllow? [y/N] ",
+        request.name,
+        request.library.display(),
+        request.missing
+    );
+    read_yes(&stdin)
+}
+
+/// Ask on the terminal whether to load the plugins shipped with the project
+/// at `project`; refused without asking when stdin isn't a terminal
+pub fn ask_trust_project(project: &Path) -> bool {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return false;
+    }
+    eprint!(
+        "{} ships its own plugins, which run as native code inside devdash\nTrust this project and load them? [y/N] ",
+        project.display()
+    );
+    read_yes(&stdin)
+}
+
+/// Ask on the terminal whether to let the widgets of the project at
+/// `project` start the commands its config lists; refused without asking
+/// when stdin isn't a terminal
+pub fn ask_trust_commands(project: &Path) -> bool {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return false;
+    }
+    eprint!(
+        "{} has a devdash.toml that runs commands, such as dev servers and tunnels\nTrust this project and run them? [y/N] ",
+        project.display()
+    );
+    read_yes(&stdin)
+}
+
+/// The project whose own `./devdash.toml` is the config in use, unless the
+/// user trusts it; one given with `--config` or in the user's config
+/// directory is theirs
+pub fn untrusted_config(args: &CliArgs, manager: &PluginManager) -> Option<PathBuf> {
+    if args.config.is_some() {
+        return None;
+    }
+    let [project_config, user_config] = ConfigFile::search_paths().ok()?;
+    if project_config == user_config || !project_config.exists() {
+        return None;
+    }
+    let project = project_config.parent()?;
+    (!manager.is_trusted(project)).then(|| project.to_path_buf())
+}
+
+/// Whether any widget settings in `config` list commands to start
+pub fn lists_commands(config: &ConfigFile) -> bool {
+    fn lists(value: &toml::Value) -> bool {
+        match value {
+            toml::Value::Table(table) => table.iter().any(|(key, value)| {
+                matches!(key.as_str(), "command" | "commands" | "tunnels") || lists(value)
+            }),
+            toml::Value::Array(values) => values.iter().any(lists),
+            _ => false,
+        }
+    }
+    config
+        .dashboard
+        .iter()
+        .flat_map(|dashboard| &dashboard.widgets)
+        .any(|widget| lists(&widget.settings))
+}
+
+/// Whether the widgets may start the commands `config` lists on their own:
+/// always for the user's own config, and for a project's once the user
+/// trusts it, which they are asked on the terminal
+pub fn trust_config(args: &CliArgs, config: &ConfigFile, manager: &PluginManager) -> bool {
+    let Some(project) = untrusted_config(args, manager) else {
+        return true;
+    };
+    if !lists_commands(config) || !ask_trust_commands(&project) {
+        return false;
+    }
+    if let Err(e) = manager.add_trusted(&project) {
+        eprintln!(
+            "Warning: can't remember trusting {}: {}",
+            project.display(),
+            e
+        );
+    }
+    true
+}
+
+fn read_yes(stdin: &io::Stdin) -> bool {
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    if stdin.lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_commands() {
+        let config = |widgets: &str| -> ConfigFile {
+            toml::from_str(&format!(
+                "[[dashboard]]\nname = \"default\"\nlayout = {{ type = \"layout\", direction = \"vertical\", items = [] }}\n{}",
+                widgets
+            ))
+            .unwrap()
+        };
+        assert!(!lists_commands(&config("")));
+        assert!(!lists_commands(&config(
+            "[[dashboard.widgets]]\nname = \"git\"\nsettings = { interval = \"5s\" }"
+        )));
+        assert!(lists_commands(&config(
+            "[[dashboard.widgets]]\nname = \"supervisor\"\n\
+             settings = { commands = [{ name = \"web\", command = \"npm run dev\" }] }"
+        )));
+        assert!(lists_commands(&config(
+            "[[dashboard.widgets]]\nname = \"backup\"\n\
+             settings = { targets = [{ name = \"db\", command = \"restic snapshots\" }] }"
+        )));
+    }
+}
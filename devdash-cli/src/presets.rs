@@ -0,0 +1,224 @@
+// devdash-cli/src/presets.rs
+//! `devdash init --preset`: writing a ready-made dashboard to the config
+//! file.
+//!
+//! Presets are built from the config types rather than kept as TOML text,
+//! so they can't drift out of step with what the config file accepts.
+use std::{path::Path, process::ExitCode};
+
+use devdash_core::{
+    ConfigFile, WidgetStyle,
+    config::{ConfigConstraint, ConfigLayout, ConfigLayoutItem, Dashboard, Direction},
+};
+
+use crate::args::CliArgs;
+
+/// A ready-made dashboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// Git, compiler diagnostics, tasks, processes and CPU
+    Developer,
+    /// Disks, network, systemd timers and the kernel log
+    Sysadmin,
+    /// Resource usage and uptime of a small server
+    Homelab,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::Developer, Preset::Sysadmin, Preset::Homelab];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Developer => "developer",
+            Preset::Sysadmin => "sysadmin",
+            Preset::Homelab => "homelab",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    /// The preset as a config file holding one dashboard, named `default`
+    /// so it shows without `--dashboard`
+    pub fn config(self) -> ConfigFile {
+        let layout = match self {
+            Preset::Developer => row(vec![
+                column(vec![widget("git", 1), widget("diagnostics", 1)]),
+                column(vec![
+                    widget("cpu", 1),
+                    widget("process", 2),
+                    widget("tasks", 1),
+                ]),
+            ]),
+            Preset::Sysadmin => column(vec![
+                row(vec![widget("cpu", 1), widget("memory", 1)]),
+                row(vec![widget("disk", 1), widget("network", 1)]),
+                row(vec![widget("schedule", 1), widget("health", 1)]),
+            ]),
+            Preset::Homelab => row(vec![
+                column(vec![
+                    widget("cpu", 1),
+                    widget("memory", 1),
+                    widget("network", 1),
+                ]),
+                column(vec![
+                    widget("uptime", 1),
+                    widget("disk", 2),
+                    widget("process", 2),
+                ]),
+            ]),
+        };
+        single_dashboard("default", layout)
+    }
+
+    /// The preset as the text of a config file
+    pub fn to_toml(self) -> Result<String, toml::ser::Error> {
+        Ok(format!(
+            "# Written by `devdash init --preset {}`; see the README for the\n\
+             # widgets' settings and the other sections this file may hold.\n\n{}",
+            self.name(),
+            toml::to_string(&self.config())?
+        ))
+    }
+}
+
+/// A config file holding just the dashboard `name`, laid out as `layout`
+pub(crate) fn single_dashboard(name: &str, layout: ConfigLayoutItem) -> ConfigFile {
+    let ConfigLayoutItem::Layout { direction, items } = layout else {
+        unreachable!("dashboards are built from layouts");
+    };
+    ConfigFile {
+        dashboard: vec![Dashboard {
+            name: name.to_string(),
+            layout: ConfigLayout::Layout { direction, items },
+            widgets: Vec::new(),
+        }],
+        ..ConfigFile::default()
+    }
+}
+
+/// A widget taking `flex` shares of its layout
+pub(crate) fn widget(name: &str, flex: u16) -> ConfigLayoutItem {
+    ConfigLayoutItem::Widget {
+        name: name.to_string(),
+        host: None,
+        constraint: ConfigConstraint {
+            flex: Some(flex),
+            ..ConfigConstraint::default()
+        },
+        style: WidgetStyle::default(),
+    }
+}
+
+/// Items side by side; nested layouts share their parent's space equally
+pub(crate) fn row(items: Vec<ConfigLayoutItem>) -> ConfigLayoutItem {
+    ConfigLayoutItem::Layout {
+        direction: Direction::Horizontal,
+        items,
+    }
+}
+
+/// Items stacked top to bottom
+pub(crate) fn column(items: Vec<ConfigLayoutItem>) -> ConfigLayoutItem {
+    ConfigLayoutItem::Layout {
+        direction: Direction::Vertical,
+        items,
+    }
+}
+
+/// Write `preset` to the config file named with `--config`, or the user's,
+/// refusing to replace an existing file unless `force` is set
+pub fn init(args: &CliArgs, preset: Preset, force: bool) -> ExitCode {
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => match ConfigFile::search_paths() {
+            Ok([_, user]) => user,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    match write(&path, preset, force) {
+        Ok(()) => {
+            println!(
+                "Wrote the {} dashboard to {}; run `devdash{}` to open it",
+                preset.name(),
+                path.display(),
+                match &args.config {
+                    Some(path) => format!(" --config {}", path.display()),
+                    None => String::new(),
+                }
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn write(path: &Path, preset: Preset, force: bool) -> Result<(), String> {
+    if path.exists() && !force {
+        return Err(format!(
+            "{} already exists; pass --force to replace it",
+            path.display()
+        ));
+    }
+    let text = preset.to_toml().map_err(|e| e.to_string())?;
+    write_config(path, &text)
+}
+
+/// Write `text` to the config file at `path`, creating its directory
+pub(crate) fn write_config(path: &Path, text: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    std::fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builtin_registry, validate::problems};
+
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in Preset::ALL {
+            assert_eq!(Preset::from_name(preset.name()), Some(preset));
+            // Check what gets written, as it is read back
+            let config: ConfigFile = toml::from_str(&preset.to_toml().unwrap()).unwrap();
+            assert_eq!(
+                config.dashboard[0].layout,
+                preset.config().dashboard[0].layout
+            );
+            let problems = problems(&config, &mut builtin_registry(), &[], &CliArgs::default());
+            assert_eq!(problems, Vec::<String>::new(), "{} preset", preset.name());
+        }
+        assert_eq!(Preset::from_name("gaming"), None);
+    }
+
+    #[test]
+    fn test_write() {
+        let dir = std::env::temp_dir().join(format!("devdash-init-{}", std::process::id()));
+        let path = dir.join("nested/devdash.toml");
+        write(&path, Preset::Developer, false).unwrap();
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("name = \"git\"")
+        );
+
+        let error = write(&path, Preset::Homelab, false).unwrap_err();
+        assert!(error.contains("--force"));
+        write(&path, Preset::Homelab, true).unwrap();
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("--preset homelab")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,371 @@
+// devdash-cli/src/remote.rs
+//! Widgets showing other machines, from `[[host]]` config blocks and
+//! `--connect`.
+//!
+//! Each host referenced by a layout item gets its own event bus with live
+//! collection off, fed by the host's event stream: a `devdash agent` over
+//! TCP, or `devdash stream` run over SSH. Widgets for that item are created
+//! against the host's bus, the same way `--inject --no-live` plays back a
+//! recording.
+use std::{
+    collections::HashMap,
+    io::{self, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    process::{Child, ExitCode, Stdio},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use devdash_core::{
+    ConfigFile, EventBus, WidgetSettings, config::ConfigLayoutItem, config::Dashboard,
+    config::HostConfig, flatten_layout_items,
+};
+
+use crate::{builtin_registry, event_log::record_line, inject::Injector};
+
+/// Widgets that display the events of their bus instead of polling the
+/// local system, and so can show another host
+pub const HOST_WIDGETS: [&str; 4] = ["cpu", "memory", "disk", "process"];
+
+/// Command run over SSH when a host doesn't set one
+const DEFAULT_COMMAND: &str = "devdash stream";
+
+/// How long to wait for an agent to accept the connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long an agent waits on a viewer that stopped reading before
+/// dropping it
+const WRITE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Address `devdash agent` listens on when not given one; other machines
+/// can only connect when another is chosen with `--listen`
+pub const DEFAULT_AGENT_ADDR: &str = "127.0.0.1:9901";
+
+/// A host's event stream; dropping it disconnects
+struct Connection {
+    bus: EventBus,
+    _injector: Injector,
+    ssh: Option<Child>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.ssh {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Connections to the hosts used by a dashboard, by host name
+pub struct Hosts {
+    connections: HashMap<String, Result<Connection, String>>,
+    /// The agent given with `--connect`, by its address
+    default: Option<(String, Result<Connection, String>)>,
+}
+
+impl Hosts {
+    /// Connect to every host the layout of `dashboard` refers to, and to the
+    /// agent at `default` if given
+    ///
+    /// Failures are kept and shown in place of the host's widgets.
+    pub fn connect(config: &ConfigFile, dashboard: &Dashboard, default: Option<&str>) -> Self {
+        let mut connections = HashMap::new();
+        for item in flatten_layout_items(&dashboard.layout) {
+            let ConfigLayoutItem::Widget {
+                host: Some(name), ..
+            } = item
+            else {
+                continue;
+            };
+            if connections.contains_key(name) {
+                continue;
+            }
+            let connection = match config.get_host(name) {
+                Some(host) => connect(host).map_err(|e| format!("Host '{}': {}", name, e)),
+                None => Err(format!("Unknown host '{}'; add a [[host]] block", name)),
+            };
+            connections.insert(name.clone(), connection);
+        }
+        let default = default.map(|address| {
+            let agent = HostConfig {
+                name: address.to_string(),
+                agent: Some(address.to_string()),
+                ssh: None,
+                command: None,
+            };
+            let connection = connect(&agent).map_err(|e| format!("Agent {}: {}", address, e));
+            (address.to_string(), connection)
+        });
+        Self {
+            connections,
+            default,
+        }
+    }
+
+    /// Address and bus of the `--connect` agent, which [`HOST_WIDGETS`]
+    /// without a host of their own show
+    pub fn default_bus(&self) -> Option<(&str, Result<&EventBus, String>)> {
+        self.default.as_ref().map(|(address, connection)| {
+            let bus = match connection {
+                Ok(connection) => Ok(&connection.bus),
+                Err(e) => Err(e.clone()),
+            };
+            (address.as_str(), bus)
+        })
+    }
+
+    /// Bus carrying the events of `host`
+    pub fn bus(&self, host: &str) -> Result<&EventBus, String> {
+        match self.connections.get(host) {
+            Some(Ok(connection)) => Ok(&connection.bus),
+            Some(Err(e)) => Err(e.clone()),
+            None => Err(format!("Host '{}' is not connected", host)),
+        }
+    }
+}
+
+fn connect(host: &HostConfig) -> io::Result<Connection> {
+    let bus = EventBus::new();
+    bus.set_live(false);
+
+    match (&host.agent, &host.ssh) {
+        (Some(address), None) => {
+            let addr = address.to_socket_addrs()?.next().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("can't resolve {}", address),
+                )
+            })?;
+            let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+            let injector = Injector::from_reader(&bus, Box::new(BufReader::new(stream)), false);
+            Ok(Connection {
+                bus,
+                _injector: injector,
+                ssh: None,
+            })
+        }
+        (None, Some(destination)) => {
+            let command = host.command.as_deref().unwrap_or(DEFAULT_COMMAND);
+            // Batch mode: a password prompt would end up in the dashboard
+            let mut child = std::process::Command::new("ssh")
+                .args(["-T", "-o", "BatchMode=yes", destination])
+                .args(command.split_whitespace())
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()?;
+            let stdout = child.stdout.take().expect("stdout is piped");
+            let injector = Injector::from_reader(&bus, Box::new(BufReader::new(stdout)), false);
+            Ok(Connection {
+                bus,
+                _injector: injector,
+                ssh: Some(child),
+            })
+        }
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "set exactly one of agent and ssh",
+        )),
+    }
+}
+
+/// `devdash stream`: collect the metrics of [`HOST_WIDGETS`] every
+/// `tick_rate` without a terminal and write their events to stdout until it
+/// closes
+///
+/// This is what hosts reached over SSH run.
+pub fn stream(tick_rate: Duration) -> ExitCode {
+    let mut stdout = io::stdout().lock();
+    collect(tick_rate, |line| {
+        writeln!(stdout, "{}", line).and_then(|_| stdout.flush())
+    });
+    // The viewer went away
+    ExitCode::SUCCESS
+}
+
+/// `devdash agent`: collect like [`stream`] and send the events to every
+/// viewer connected to `listen`, until killed
+///
+/// This is what hosts with `agent` set and `--connect` talk to. Viewers can
+/// come and go; each gets the events published after it connected.
+pub fn agent(listen: SocketAddr, tick_rate: Duration) -> ExitCode {
+    let listener = match TcpListener::bind(listen) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error: can't listen on {}: {}", listen, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!(
+        "devdash agent listening on {}",
+        listener.local_addr().unwrap_or(listen)
+    );
+    if !listen.ip().is_loopback() {
+        eprintln!(
+            "Warning: metrics are sent unencrypted to anyone who can reach {}",
+            listen
+        );
+    }
+
+    let viewers = Arc::new(Mutex::new(Vec::new()));
+    let accepted = viewers.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A viewer that stops reading must not hold up the others
+            if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_ok()
+                && let Ok(mut viewers) = accepted.lock()
+            {
+                viewers.push(stream);
+            }
+        }
+    });
+
+    collect(tick_rate, |line| {
+        if let Ok(mut viewers) = viewers.lock() {
+            viewers.retain_mut(|viewer: &mut TcpStream| writeln!(viewer, "{}", line).is_ok());
+        }
+        Ok(())
+    });
+    ExitCode::SUCCESS
+}
+
+/// Run the widgets of [`HOST_WIDGETS`] every `tick_rate` and pass each event
+/// they publish to `send` as an `--event-log` line, until it fails
+fn collect(tick_rate: Duration, mut send: impl FnMut(&str) -> io::Result<()>) {
+    let bus = EventBus::new();
+    let (_subscription, events) = bus.subscribe("*");
+    let mut registry = builtin_registry();
+    let mut widgets: Vec<_> = HOST_WIDGETS
+        .iter()
+        .filter_map(|name| {
+            let widget = registry
+                .create(name, &bus, tick_rate, &WidgetSettings::new())
+                .ok()?;
+            Some(devdash_core::WidgetContainer::new(name.to_string(), widget))
+        })
+        .collect();
+    widgets.iter_mut().for_each(|w| w.mount());
+
+    loop {
+        let now = Instant::now();
+        for widget in widgets.iter_mut().filter(|w| w.is_due(now)) {
+            widget.update();
+        }
+
+        if events
+            .try_iter()
+            .try_for_each(|event| send(&record_line(&event)))
+            .is_err()
+        {
+            return;
+        }
+
+        let next = widgets.iter().filter_map(|w| w.next_update()).min();
+        std::thread::sleep(next.map_or(Duration::from_secs(1), |due| {
+            due.saturating_duration_since(Instant::now())
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(text: &str) -> ConfigFile {
+        toml::from_str(text).unwrap()
+    }
+
+    const LAYOUT: &str = r#"
+        [[dashboard]]
+        name = "default"
+        [dashboard.layout]
+        type = "layout"
+        direction = "horizontal"
+        [[dashboard.layout.items]]
+        type = "widget"
+        name = "memory"
+        host = "buildbox"
+    "#;
+
+    #[test]
+    fn test_unknown_host() {
+        let config = config(LAYOUT);
+        let hosts = Hosts::connect(&config, config.get_dashboard("default").unwrap(), None);
+        assert!(
+            hosts
+                .bus("buildbox")
+                .is_err_and(|e| e.contains("Unknown host"))
+        );
+    }
+
+    #[test]
+    fn test_host_needs_one_source() {
+        let config = config(&format!("[[host]]\nname = \"buildbox\"\n{}", LAYOUT));
+        let hosts = Hosts::connect(&config, config.get_dashboard("default").unwrap(), None);
+        assert!(
+            hosts
+                .bus("buildbox")
+                .is_err_and(|e| e.contains("exactly one"))
+        );
+    }
+
+    #[test]
+    fn test_agent_stream() {
+        use devdash_core::BusEvent;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let config = config(&format!(
+            "[[host]]\nname = \"buildbox\"\nagent = \"{}\"\n{}",
+            address, LAYOUT
+        ));
+        let hosts = Hosts::connect(&config, config.get_dashboard("default").unwrap(), None);
+        let bus = hosts.bus("buildbox").unwrap();
+        assert!(!bus.is_live());
+        let (_subscription, rx) = bus.subscribe("system.git.status");
+
+        let (mut agent, _) = listener.accept().unwrap();
+        let event = BusEvent::new("system.git.status", "branch=main".to_string());
+        writeln!(agent, "{}", record_line(&event)).unwrap();
+
+        let received = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(
+            *received.payload.downcast::<String>().unwrap(),
+            "branch=main"
+        );
+    }
+
+    #[test]
+    fn test_connect_agent() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let config = config(LAYOUT);
+        let hosts = Hosts::connect(
+            &config,
+            config.get_dashboard("default").unwrap(),
+            Some(&address),
+        );
+        let (name, bus) = hosts.default_bus().unwrap();
+        assert_eq!(name, address);
+        assert!(!bus.unwrap().is_live());
+
+        let dashboard = config.get_dashboard("default").unwrap();
+        let unreachable = Hosts::connect(&config, dashboard, Some("nowhere.invalid:1"));
+        assert!(
+            unreachable
+                .default_bus()
+                .unwrap()
+                .1
+                .is_err_and(|e| e.starts_with("Agent nowhere.invalid:1"))
+        );
+        assert!(
+            Hosts::connect(&config, dashboard, None)
+                .default_bus()
+                .is_none()
+        );
+    }
+}
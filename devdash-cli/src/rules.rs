@@ -0,0 +1,351 @@
+// devdash-cli/src/rules.rs
+//! Alert rules defined in the config file's `[[alerts]]` tables.
+//!
+//! A rule compares a numeric field of the payloads published on a topic
+//! with a threshold, and raises an alert once the comparison has held for
+//! long enough. Payloads are read through their JSON form, so only the types
+//! registered with the [`devdash_core::PayloadRegistry`] can be checked.
+use crossbeam::channel::Receiver;
+use devdash_core::{BusEvent, ConfigFile, EventBus, event::Subscription, parse_duration};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+use crate::{alerts::Severity, codec};
+
+/// How a rule compares the field with its threshold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum Comparison {
+    #[default]
+    #[serde(rename = ">")]
+    Above,
+    #[serde(rename = ">=")]
+    AtLeast,
+    #[serde(rename = "<")]
+    Below,
+    #[serde(rename = "<=")]
+    AtMost,
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+}
+
+impl Comparison {
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::AtLeast => value >= threshold,
+            Self::Below => value < threshold,
+            Self::AtMost => value <= threshold,
+            Self::Equal => value == threshold,
+            Self::NotEqual => value != threshold,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Above => ">",
+            Self::AtLeast => ">=",
+            Self::Below => "<",
+            Self::AtMost => "<=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+        }
+    }
+}
+
+/// One `[[alerts]]` table
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRule {
+    /// Names the alert in the history; `<topic> <field>` when unset
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Topic the payloads are published on, e.g. `system.memory`
+    pub topic: String,
+    /// Dotted path to a number in the payload, e.g. `paging.pages_out`;
+    /// array elements are picked by index
+    pub field: String,
+    #[serde(default)]
+    pub comparison: Comparison,
+    pub threshold: f64,
+    /// How long the comparison must hold before the alert fires
+    #[serde(default, rename = "for", deserialize_with = "duration")]
+    pub duration: Duration,
+    /// Alert text; `{value}`, `{threshold}`, `{field}` and `{topic}` are
+    /// filled in
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Whether the alert shows as a desktop notification, overriding
+    /// `[notifications]`
+    #[serde(default)]
+    pub notify: Option<bool>,
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse_duration(&text).map_err(serde::de::Error::custom)
+}
+
+impl AlertRule {
+    /// The `[[alerts]]` tables of a config file
+    pub fn from_config(config: &ConfigFile) -> Result<Vec<Self>, String> {
+        let Some(value) = config.settings.get("alerts") else {
+            return Ok(Vec::new());
+        };
+        let rules: Vec<Self> = value
+            .clone()
+            .try_into()
+            .map_err(|e| e.message().to_string())?;
+        for rule in &rules {
+            if rule.field.is_empty() || rule.field.split('.').any(str::is_empty) {
+                return Err(format!("invalid field '{}'", rule.field));
+            }
+        }
+        Ok(rules)
+    }
+
+    fn name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", self.topic, self.field))
+    }
+
+    /// The number at the rule's field in a payload's JSON form
+    fn value(&self, payload: &Value) -> Option<f64> {
+        self.field
+            .split('.')
+            .try_fold(payload, |value, part| match value {
+                Value::Array(items) => items.get(part.parse::<usize>().ok()?),
+                _ => value.get(part),
+            })?
+            .as_f64()
+    }
+
+    fn message(&self, value: f64) -> String {
+        let value = format_number(value);
+        let threshold = format_number(self.threshold);
+        match &self.message {
+            Some(message) => message
+                .replace("{value}", &value)
+                .replace("{threshold}", &threshold)
+                .replace("{field}", &self.field)
+                .replace("{topic}", &self.topic),
+            None => format!(
+                "{}: {} {} {}",
+                self.name(),
+                value,
+                self.comparison.symbol(),
+                threshold
+            ),
+        }
+    }
+}
+
+/// `12`, or `12.5` with at most two decimals
+fn format_number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// A rule and since when its comparison has held
+struct RuleState {
+    rule: AlertRule,
+    since: Option<Instant>,
+    _subscription: Subscription,
+    events: Receiver<BusEvent>,
+}
+
+/// The configured rules, watching their topics
+#[derive(Default)]
+pub struct AlertRules {
+    rules: Vec<RuleState>,
+}
+
+impl AlertRules {
+    pub fn new(event_bus: &EventBus, rules: Vec<AlertRule>) -> Self {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let (subscription, events) = event_bus.subscribe(rule.topic.clone());
+                RuleState {
+                    rule,
+                    since: None,
+                    _subscription: subscription,
+                    events,
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// The `notify` setting of the rule raising alerts under `key`
+    pub fn notify(&self, key: &str) -> Option<bool> {
+        let name = key.strip_prefix("rule:")?;
+        self.rules
+            .iter()
+            .find(|state| state.rule.name() == name)
+            .and_then(|state| state.rule.notify)
+    }
+
+    /// Check the events published since the last call, returning the alerts
+    /// raised as `(key, severity, message)`
+    pub fn check(&mut self, now: Instant) -> Vec<(String, Severity, String)> {
+        let mut raised = Vec::new();
+        for state in &mut self.rules {
+            for event in state.events.try_iter() {
+                let Some(value) =
+                    codec::encode(&event).and_then(|(_, payload)| state.rule.value(&payload))
+                else {
+                    continue;
+                };
+                if !state.rule.comparison.holds(value, state.rule.threshold) {
+                    state.since = None;
+                    continue;
+                }
+                let since = *state.since.get_or_insert(now);
+                if now.duration_since(since) >= state.rule.duration {
+                    raised.push((
+                        format!("rule:{}", state.rule.name()),
+                        state.rule.severity,
+                        state.rule.message(value),
+                    ));
+                }
+            }
+        }
+        raised
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devdash_core::SystemMetrics;
+
+    fn parse(text: &str) -> Result<Vec<AlertRule>, String> {
+        AlertRule::from_config(&toml::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert_eq!(parse(""), Ok(Vec::new()));
+        let rules = parse(
+            r#"
+            [[alerts]]
+            topic = "system.metrics"
+            field = "cpu_usage"
+            threshold = 90
+            for = "30s"
+
+            [[alerts]]
+            name = "swap"
+            topic = "system.memory"
+            field = "paging.pages_out"
+            comparison = "<="
+            threshold = 0.5
+            severity = "critical"
+            message = "Swapping {value} pages/s"
+            notify = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rules[0].comparison, Comparison::Above);
+        assert_eq!(rules[0].duration, Duration::from_secs(30));
+        assert_eq!(rules[0].severity, Severity::Warning);
+        assert_eq!(rules[1].comparison, Comparison::AtMost);
+        assert_eq!(rules[1].severity, Severity::Critical);
+        assert_eq!((rules[0].notify, rules[1].notify), (None, Some(true)));
+
+        let rule = |extra: &str| {
+            parse(&format!(
+                "[[alerts]]\ntopic = \"t\"\nfield = \"f\"\nthreshold = 1\n{}",
+                extra
+            ))
+        };
+        assert!(rule("").is_ok());
+        assert!(rule("comparison = \"=>\"").is_err());
+        assert!(rule("for = \"soon\"").is_err());
+        assert!(rule("severity = \"info\"").is_err());
+        assert!(rule("colour = \"red\"").is_err());
+        assert!(parse("[[alerts]]\ntopic = \"t\"\nfield = \"a..b\"\nthreshold = 1").is_err());
+    }
+
+    #[test]
+    fn test_value() {
+        let rule = |field: &str| AlertRule {
+            name: None,
+            topic: "t".to_string(),
+            field: field.to_string(),
+            comparison: Comparison::Above,
+            threshold: 0.0,
+            duration: Duration::ZERO,
+            message: None,
+            severity: Severity::Warning,
+            notify: None,
+        };
+        let payload = serde_json::json!({
+            "usage": 42.5,
+            "paging": { "swap_out": 3 },
+            "disks": [{ "used": 1 }, { "used": 2 }],
+            "name": "x",
+        });
+        assert_eq!(rule("usage").value(&payload), Some(42.5));
+        assert_eq!(rule("paging.swap_out").value(&payload), Some(3.0));
+        assert_eq!(rule("disks.1.used").value(&payload), Some(2.0));
+        assert_eq!(rule("disks.2.used").value(&payload), None);
+        assert_eq!(rule("name").value(&payload), None);
+        assert_eq!(rule("missing").value(&payload), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let bus = EventBus::new();
+        let rules = parse(
+            r#"
+            [[alerts]]
+            name = "cpu"
+            topic = "system.metrics"
+            field = "cpu_usage"
+            threshold = 90
+            for = "30s"
+            message = "CPU at {value}% (over {threshold}%)"
+            notify = false
+            "#,
+        )
+        .unwrap();
+        let mut rules = AlertRules::new(&bus, rules);
+        assert_eq!(rules.notify("rule:cpu"), Some(false));
+        assert_eq!(rules.notify("disk.full:/"), None);
+        let publish = |cpu_usage| {
+            bus.publish(BusEvent::new(
+                "system.metrics",
+                SystemMetrics {
+                    cpu_usage,
+                    memory_used: 1,
+                    memory_total: 2,
+                },
+            ))
+        };
+        let start = Instant::now();
+
+        publish(95.0);
+        assert!(rules.check(start).is_empty());
+        publish(97.5);
+        assert_eq!(
+            rules.check(start + Duration::from_secs(30)),
+            [(
+                "rule:cpu".to_string(),
+                Severity::Warning,
+                "CPU at 97.5% (over 90%)".to_string()
+            )]
+        );
+
+        // Dropping below the threshold starts the wait over
+        publish(50.0);
+        publish(95.0);
+        assert!(rules.check(start + Duration::from_secs(40)).is_empty());
+    }
+}
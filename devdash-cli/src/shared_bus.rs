@@ -0,0 +1,336 @@
+// devdash-cli/src/shared_bus.rs
+//! Connects the event buses of several devdash processes (`--share-bus`).
+//!
+//! The first process to start binds a local socket and relays between the
+//! others, which connect to it; if it exits, the next one to notice takes
+//! over. Only [`SHARED_TOPICS`] cross over, so each pane keeps collecting its
+//! own data while link selections and alerts reach every pane. Events use
+//! the `--event-log` format.
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use devdash_core::{BusEvent, EventBus, EventPayload};
+
+use crate::{daemon, event_log::record_line, inject::decode_line};
+
+/// Topics propagated between processes: link selections, alerts, and
+/// `shared.*` for widgets and plugins announcing drill-downs and the like
+pub const SHARED_TOPICS: [&str; 15] = [
+    "link.*",
+    "system.memory.pressure",
+    "system.memory.thrashing",
+    "system.disk.full",
+    "system.disk.projection",
+    "system.network.quota",
+    "process.zombie",
+    "process.fd.pressure",
+    "system.login.remote",
+    "system.firewall.burst",
+    "system.job.failed",
+    "system.backup.stale",
+    "system.health.issue",
+    "system.dev.crashed",
+    "shared.*",
+];
+
+/// How long to wait before trying to reconnect after losing the hub
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Socket shared by every devdash of the current user, in the directory
+/// only they may enter; `None` without one
+pub fn default_socket() -> Option<PathBuf> {
+    Some(daemon::socket_dir()?.join("devdash-bus.sock"))
+}
+
+fn is_shared(topic: &str) -> bool {
+    SHARED_TOPICS
+        .iter()
+        .any(|pattern| EventBus::topic_matches(topic, pattern))
+}
+
+/// The topic of an `--event-log` line, read without decoding its payload
+fn line_topic(line: &str) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct Topic {
+        topic: String,
+    }
+    serde_json::from_str::<Topic>(line).ok().map(|t| t.topic)
+}
+
+/// State shared by the threads of one process
+struct Link {
+    event_bus: EventBus,
+    /// Connections to the other processes (the hub's clients, or the hub)
+    #[cfg(unix)]
+    peers: Mutex<Vec<(usize, std::os::unix::net::UnixStream)>>,
+    /// Whether this process is the hub, and owns the socket file
+    hub: AtomicBool,
+    /// Payloads received from peers and not yet seen by the forwarder, so
+    /// they aren't sent back
+    received: Mutex<Vec<EventPayload>>,
+}
+
+impl Link {
+    /// Publish an event from a peer on the local bus
+    fn publish_remote(&self, event: BusEvent) {
+        lock(&self.received).push(event.payload.clone());
+        self.event_bus.publish(event);
+    }
+
+    /// Whether a local event came from a peer, forgetting it if so
+    fn take_received(&self, event: &BusEvent) -> bool {
+        let mut received = lock(&self.received);
+        match received.iter().position(|p| p.ptr_eq(&event.payload)) {
+            Some(i) => {
+                received.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Send a line to every peer except `from`, dropping peers that hung up
+    #[cfg(unix)]
+    fn send(&self, line: &str, from: Option<usize>) {
+        use std::io::Write;
+        lock(&self.peers)
+            .retain_mut(|(id, stream)| Some(*id) == from || writeln!(stream, "{}", line).is_ok());
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Handle on the shared bus; its threads are detached and end with the
+/// process
+pub struct SharedBus {
+    path: PathBuf,
+    #[cfg(unix)]
+    link: Arc<Link>,
+}
+
+impl SharedBus {
+    /// Share `event_bus` with the other processes using `path`
+    #[cfg(unix)]
+    pub fn start(event_bus: &EventBus, path: &Path) -> io::Result<Self> {
+        daemon::create_socket_dir(path)?;
+        let link = Arc::new(Link {
+            event_bus: event_bus.clone(),
+            peers: Mutex::new(Vec::new()),
+            hub: AtomicBool::new(false),
+            received: Mutex::new(Vec::new()),
+        });
+
+        let (subscription, rx) = event_bus.subscribe("*");
+        let forwarder = link.clone();
+        std::thread::spawn(move || {
+            let _subscription = subscription;
+            for event in rx {
+                if !forwarder.take_received(&event) && is_shared(&event.topic) {
+                    forwarder.send(&record_line(&event), None);
+                }
+            }
+        });
+
+        let connector = link.clone();
+        let socket = path.to_path_buf();
+        std::thread::spawn(move || {
+            loop {
+                // Both return only once the role is lost
+                if let Err(e) = unix::serve(&connector, &socket)
+                    && e.kind() == io::ErrorKind::AddrInUse
+                {
+                    let _ = unix::follow(&connector, &socket);
+                }
+                std::thread::sleep(RECONNECT_DELAY);
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            link,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_event_bus: &EventBus, _path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the shared bus needs Unix domain sockets",
+        ))
+    }
+}
+
+impl Drop for SharedBus {
+    fn drop(&mut self) {
+        // Clients reconnect, and one of them takes over
+        #[cfg(unix)]
+        if self.link.hub.load(Ordering::SeqCst) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        io::{self, BufRead, BufReader},
+        os::unix::net::{UnixListener, UnixStream},
+        path::Path,
+        sync::{Arc, atomic::Ordering},
+    };
+
+    use super::{Link, lock};
+
+    /// Relay between the processes connecting to `path`
+    ///
+    /// Fails with `AddrInUse` while another process is the hub.
+    pub fn serve(link: &Arc<Link>, path: &Path) -> io::Result<()> {
+        // Held for as long as this process is the hub
+        let _lock = crate::daemon::lock_socket(path)?;
+        if path.exists() {
+            // Left behind by a hub that exited
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        link.hub.store(true, Ordering::SeqCst);
+
+        for (id, stream) in listener.incoming().enumerate() {
+            let Ok(stream) = stream else { continue };
+            let Ok(reader) = stream.try_clone() else {
+                continue;
+            };
+            lock(&link.peers).push((id, stream));
+            let link = link.clone();
+            std::thread::spawn(move || {
+                relay(&link, reader, Some(id));
+                lock(&link.peers).retain(|(peer, _)| *peer != id);
+            });
+        }
+        Ok(())
+    }
+
+    /// Connect to the hub on `path` and exchange events until it goes away
+    pub fn follow(link: &Link, path: &Path) -> io::Result<()> {
+        let stream = UnixStream::connect(path)?;
+        lock(&link.peers).push((0, stream.try_clone()?));
+        relay(link, stream, None);
+        lock(&link.peers).clear();
+        Ok(())
+    }
+
+    /// Publish what a peer sends; the hub (`from` set) also passes it on
+    ///
+    /// Only [`SHARED_TOPICS`](super::SHARED_TOPICS) are let in, whatever a
+    /// peer sends.
+    fn relay(link: &Link, stream: UnixStream, from: Option<usize>) {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            if !super::line_topic(&line).is_some_and(|topic| super::is_shared(&topic)) {
+                continue;
+            }
+            if from.is_some() {
+                link.send(&line, from);
+            }
+            if let Some(event) = super::decode_line(&line) {
+                link.publish_remote(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_topics() {
+        assert!(is_shared("link.storage"));
+        assert!(is_shared("system.disk.full"));
+        assert!(is_shared("shared.drilldown.process"));
+        assert!(!is_shared("system.memory"));
+        assert!(!is_shared("system.process.top"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_events_cross_between_buses() {
+        use devdash_core::Selection;
+
+        let dir = std::env::temp_dir().join(format!("devdash-bus-test-{}", std::process::id()));
+        let socket = dir.join("bus.sock");
+        let (first, second) = (EventBus::new(), EventBus::new());
+        let _hub = SharedBus::start(&first, &socket).unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        let client = SharedBus::start(&second, &socket).unwrap();
+        for _ in 0..50 {
+            if lock(&client.link.peers).len() == 1 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let (_sub, rx) = second.subscribe("*");
+        let (_own, first_rx) = first.subscribe("link.*");
+        first.publish(BusEvent::new("system.memory", "local only".to_string()));
+        first.publish(BusEvent::new(
+            "link.storage",
+            Selection::new("disk", "/home"),
+        ));
+
+        let event = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(event.topic, "link.storage");
+        assert_eq!(
+            *event.payload.downcast::<Selection>().unwrap(),
+            Selection::new("disk", "/home")
+        );
+
+        // Nothing echoes back to the publisher
+        assert!(first_rx.recv_timeout(Duration::from_millis(50)).is_ok());
+        assert!(first_rx.recv_timeout(Duration::from_millis(200)).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_peers_only_send_shared_topics() {
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        let dir = std::env::temp_dir().join(format!("devdash-bus-filter-{}", std::process::id()));
+        let socket = dir.join("bus.sock");
+        let bus = EventBus::new();
+        let hub = SharedBus::start(&bus, &socket).unwrap();
+        for _ in 0..50 {
+            if hub.link.hub.load(Ordering::SeqCst) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let (_sub, rx) = bus.subscribe("*");
+
+        let mut peer = UnixStream::connect(&socket).unwrap();
+        for event in [
+            BusEvent::new("system.memory", "not shared".to_string()),
+            BusEvent::new("link.storage", "/home".to_string()),
+        ] {
+            writeln!(peer, "{}", record_line(&event)).unwrap();
+        }
+        let event = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(event.topic, "link.storage");
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+        assert_eq!(
+            line_topic("{\"topic\":\"a.b\",\"x\":1}").as_deref(),
+            Some("a.b")
+        );
+        assert_eq!(line_topic("not json"), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
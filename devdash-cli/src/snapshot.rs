@@ -0,0 +1,128 @@
+// devdash-cli/src/snapshot.rs
+//! One-shot output for scripts and CI (`devdash snapshot`).
+//!
+//! The dashboard's widgets are mounted and polled once, then described on
+//! stdout without touching the terminal: one summary line per widget, or a
+//! JSON document with the summaries and the events the poll published.
+use crossbeam::channel::Receiver;
+use devdash_core::{BusEvent, DevdashError};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+use crate::{app::App, codec};
+
+/// Output format of `devdash snapshot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Width widgets without their own summary are rendered at
+const SUMMARY_WIDTH: u16 = 80;
+
+/// Longest wait for slow polls, such as a `cargo check`, before printing
+/// what there is
+const POLL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest sleep between checks on polls in flight
+const POLL_STEP: Duration = Duration::from_millis(50);
+
+/// Update the widgets until `tick_rate` has passed, so those comparing
+/// samples have two, and no poll is in flight, or until [`POLL_TIMEOUT`]
+pub fn poll_once(app: &mut App, tick_rate: Duration) {
+    let start = Instant::now();
+    loop {
+        let now = Instant::now();
+        app.update_due(now);
+        let elapsed = now.duration_since(start);
+        if (elapsed >= tick_rate && !app.is_refreshing()) || elapsed >= POLL_TIMEOUT {
+            return;
+        }
+        let next = app
+            .next_update()
+            .map_or(POLL_STEP, |due| due.saturating_duration_since(now));
+        std::thread::sleep(next.min(POLL_STEP));
+    }
+}
+
+/// The events in `events`, as `{topic, type, payload}` objects; payloads
+/// without a JSON form are left out
+fn encode_events(events: &Receiver<BusEvent>) -> Vec<Value> {
+    events
+        .try_iter()
+        .filter_map(|event| {
+            let (payload_type, payload) = codec::encode(&event)?;
+            Some(json!({
+                "topic": event.topic,
+                "type": payload_type,
+                "payload": payload,
+            }))
+        })
+        .collect()
+}
+
+/// Poll the widgets of `app` once and print them in `format`
+///
+/// `events` should be subscribed to every topic before the widgets were
+/// mounted, so the events of their first poll are in the JSON output.
+pub fn run(
+    app: &mut App,
+    dashboard: &str,
+    format: SnapshotFormat,
+    tick_rate: Duration,
+    events: &Receiver<BusEvent>,
+) -> Result<(), DevdashError> {
+    poll_once(app, tick_rate);
+    match format {
+        SnapshotFormat::Text => {
+            for line in app.summaries(SUMMARY_WIDTH) {
+                println!("{}", line);
+            }
+        }
+        SnapshotFormat::Json => {
+            let snapshot = json!({
+                "dashboard": dashboard,
+                "widgets": app.widget_reports(SUMMARY_WIDTH, Instant::now()),
+                "events": encode_events(events),
+            });
+            println!("{:#}", snapshot);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devdash_core::EventBus;
+    use devdash_widgets::DiskIOMetrics;
+
+    #[test]
+    fn test_encode_events() {
+        let bus = EventBus::new();
+        let (_subscription, events) = bus.subscribe("*");
+        bus.publish(BusEvent::new(
+            "system.disk.io",
+            DiskIOMetrics {
+                read_rate: 1,
+                write_rate: 2,
+                total_read: 3,
+                total_write: 4,
+            },
+        ));
+        // No JSON form
+        bus.publish(BusEvent::new("system.opaque", 42u8));
+
+        assert_eq!(
+            encode_events(&events),
+            [json!({
+                "topic": "system.disk.io",
+                "type": "DiskIOMetrics",
+                "payload": { "read_rate": 1, "write_rate": 2, "total_read": 3, "total_write": 4 },
+            })]
+        );
+        assert!(encode_events(&events).is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+// devdash-cli/src/statusbar.rs
+//! The one-line status bar kept below (or above) the dashboard.
+use devdash_core::ConfigFile;
+use devdash_widgets::common::{format_time_with, validate_time_format};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use serde::Deserialize;
+use std::time::SystemTime;
+
+use crate::alerts::Alert;
+
+/// Between two items on the bar
+const SEPARATOR: &str = " │ ";
+
+/// Which edge of the screen the bar takes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Position {
+    Top,
+    #[default]
+    Bottom,
+}
+
+/// Something the bar shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Item {
+    /// The dashboard's name
+    Dashboard,
+    /// The focused widget's name
+    Focus,
+    /// The most recent alert
+    Alert,
+    /// The time, always at the right end
+    Clock,
+}
+
+/// `[statusbar]` settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatusBarConfig {
+    pub enabled: bool,
+    pub position: Position,
+    /// What the bar shows, left to right
+    pub items: Vec<Item>,
+    /// strftime format of the clock
+    pub clock_format: String,
+}
+
+impl Default for StatusBarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            position: Position::Bottom,
+            items: vec![Item::Dashboard, Item::Focus, Item::Alert, Item::Clock],
+            clock_format: "%H:%M:%S".to_string(),
+        }
+    }
+}
+
+impl StatusBarConfig {
+    /// The `[statusbar]` section of a config file, or the defaults if it has
+    /// none
+    pub fn from_config(config: &ConfigFile) -> Result<Self, String> {
+        let Some(value) = config.settings.get("statusbar") else {
+            return Ok(Self::default());
+        };
+        let status_bar: Self = value
+            .clone()
+            .try_into()
+            .map_err(|e| e.message().to_string())?;
+        validate_time_format(&status_bar.clock_format)
+            .map_err(|_| format!("invalid clock_format '{}'", status_bar.clock_format))?;
+        Ok(status_bar)
+    }
+
+    /// Whether the bar shows anything that changes with time alone
+    pub fn shows_clock(&self) -> bool {
+        self.enabled && self.items.contains(&Item::Clock)
+    }
+
+    /// The clock's text at `now`
+    pub fn clock(&self, now: SystemTime) -> String {
+        format_time_with(now, &self.clock_format)
+    }
+
+    /// Split `area` into the dashboard's part and the bar's line; the bar
+    /// gets nothing when disabled or when it would leave no room
+    pub fn split(&self, area: Rect) -> (Rect, Rect) {
+        if !self.enabled || area.height < 2 {
+            return (area, Rect { height: 0, ..area });
+        }
+        let rest = Rect {
+            height: area.height - 1,
+            ..area
+        };
+        match self.position {
+            Position::Top => (
+                Rect {
+                    y: area.y + 1,
+                    ..rest
+                },
+                Rect { height: 1, ..area },
+            ),
+            Position::Bottom => (
+                rest,
+                Rect {
+                    y: area.bottom() - 1,
+                    height: 1,
+                    ..area
+                },
+            ),
+        }
+    }
+}
+
+/// What the bar shows right now
+pub struct StatusBar<'a> {
+    pub config: &'a StatusBarConfig,
+    pub dashboard: &'a str,
+    /// Name of the focused widget, with its host if it shows another one
+    pub focused: Option<String>,
+    pub alert: Option<&'a Alert>,
+    pub clock: &'a str,
+}
+
+impl StatusBar<'_> {
+    fn item(&self, item: Item) -> Option<Span<'static>> {
+        Some(match item {
+            Item::Dashboard => Span::styled(
+                self.dashboard.to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Item::Focus => Span::from(format!("Focus: {}", self.focused.as_ref()?)),
+            Item::Alert => match self.alert {
+                Some(alert) => Span::styled(
+                    format!("{} {}", alert.severity.label(), alert.message),
+                    Style::default().fg(alert.severity.color()),
+                ),
+                None => Span::styled("No alerts", Style::default().fg(Color::Gray)),
+            },
+            Item::Clock => return None,
+        })
+    }
+}
+
+impl Widget for StatusBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        let style = Style::default().bg(Color::DarkGray).fg(Color::White);
+        buf.set_style(area, style);
+
+        let mut spans = vec![Span::from(" ")];
+        for item in self.config.items.iter().filter_map(|&item| self.item(item)) {
+            if spans.len() > 1 {
+                spans.push(Span::from(SEPARATOR));
+            }
+            spans.push(item);
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
+
+        if self.config.items.contains(&Item::Clock) {
+            let clock = format!("{} ", self.clock);
+            Paragraph::new(clock).right_aligned().render(area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::Severity;
+
+    fn parse(text: &str) -> Result<StatusBarConfig, String> {
+        StatusBarConfig::from_config(&toml::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert_eq!(parse(""), Ok(StatusBarConfig::default()));
+        let config = parse(
+            r#"
+            [statusbar]
+            position = "top"
+            items = ["focus", "clock"]
+            clock_format = "%H:%M"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.position, Position::Top);
+        assert_eq!(config.items, [Item::Focus, Item::Clock]);
+        assert!(config.shows_clock());
+
+        let area = Rect::new(0, 0, 20, 10);
+        assert_eq!(
+            config.split(area),
+            (Rect::new(0, 1, 20, 9), Rect::new(0, 0, 20, 1))
+        );
+        let hidden = parse("[statusbar]\nenabled = false").unwrap();
+        assert_eq!(hidden.split(area).0, area);
+
+        assert!(parse("[statusbar]\nclock_format = \"%Q\"").is_err());
+        assert!(parse("[statusbar]\nitems = [\"weather\"]").is_err());
+        assert!(parse("[statusbar]\ncolour = \"red\"").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let config = StatusBarConfig::default();
+        let now = SystemTime::now();
+        let alert = Alert {
+            key: "disk.full:/home".to_string(),
+            severity: Severity::Warning,
+            message: "/home is 91% full".to_string(),
+            first_seen: now,
+            last_seen: now,
+            count: 1,
+        };
+        let area = Rect::new(0, 0, 64, 1);
+        let mut buf = Buffer::empty(area);
+        StatusBar {
+            config: &config,
+            dashboard: "default",
+            focused: Some("disk".to_string()),
+            alert: Some(&alert),
+            clock: "12:34:56",
+        }
+        .render(area, &mut buf);
+        let line: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(
+            line,
+            " default │ Focus: disk │ WARNING /home is 91% full     12:34:56 "
+        );
+    }
+}
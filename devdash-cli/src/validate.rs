@@ -0,0 +1,253 @@
+// devdash-cli/src/validate.rs
+//! `devdash validate` and `devdash list-widgets`: checking a config file
+//! against the widgets that exist, without starting the dashboard.
+use std::process::ExitCode;
+
+use devdash_core::{
+    ConfigFile, EventBus, Keymap, WidgetContainer, WidgetRegistry, WidgetSettings,
+    config::ConfigLayoutItem, flatten_layout_items,
+};
+
+use crate::{
+    app::GLOBAL_ACTIONS, args::CliArgs, builtin_registry, notify::NotificationConfig,
+    remote::HOST_WIDGETS, rules::AlertRule, statusbar::StatusBarConfig,
+};
+
+/// Check the config file in use and print what is wrong with it, exiting
+/// non-zero if anything is
+pub fn run(args: &CliArgs) -> ExitCode {
+    match args.config_path() {
+        Ok(Some(path)) => println!("Config file: {}", path.display()),
+        Ok(None) => println!("No config file found; checking the built-in dashboards"),
+        Err(e) => {
+            println!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    let config = match args.load_config() {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut manager = args.plugin_manager();
+    let plugins: Vec<String> = match manager.load_all() {
+        Ok(plugins) => plugins.into_iter().map(|(name, _)| name).collect(),
+        Err(e) => {
+            eprintln!("Warning: Failed to load plugins: {}", e);
+            Vec::new()
+        }
+    };
+
+    let problems = problems(&config, &mut builtin_registry(), &plugins, args);
+    for problem in &problems {
+        println!("  {}", problem);
+    }
+    if problems.is_empty() {
+        println!("OK: {} dashboards", config.dashboard.len());
+        ExitCode::SUCCESS
+    } else {
+        println!("{} problems found", problems.len());
+        ExitCode::FAILURE
+    }
+}
+
+/// What would keep the dashboards of `config` from showing as configured
+///
+/// Built-in widgets are created with their settings, without collecting
+/// anything, so invalid settings show up here. `plugins` are the names of
+/// the plugin widgets that loaded.
+pub(crate) fn problems(
+    config: &ConfigFile,
+    registry: &mut WidgetRegistry,
+    plugins: &[String],
+    args: &CliArgs,
+) -> Vec<String> {
+    let bus = EventBus::new();
+    bus.set_live(false);
+    let no_settings = WidgetSettings::new();
+    let mut problems = Vec::new();
+
+    for dashboard in &config.dashboard {
+        let mut report =
+            |problem: String| problems.push(format!("dashboard '{}': {}", dashboard.name, problem));
+        for item in flatten_layout_items(&dashboard.layout) {
+            let ConfigLayoutItem::Widget { name, host, .. } = item else {
+                continue;
+            };
+            if plugins.contains(name) {
+                continue;
+            }
+            let settings = dashboard.widget_settings(name).unwrap_or(&no_settings);
+            match registry.create(name, &bus, args.tick_rate, settings) {
+                Ok(_) => {}
+                Err(devdash_core::DevdashError::UnknownWidget(_)) => {
+                    report(format!("unknown widget '{}'", name))
+                }
+                Err(e) => report(e.to_string()),
+            }
+            match host {
+                Some(host) if config.get_host(host).is_none() => {
+                    report(format!("widget '{}' shows unknown host '{}'", name, host))
+                }
+                Some(_) if !HOST_WIDGETS.contains(&name.as_str()) => report(format!(
+                    "widget '{}' can't show another host; use one of: {}",
+                    name,
+                    HOST_WIDGETS.join(", ")
+                )),
+                _ => {}
+            }
+        }
+    }
+    if config.get_dashboard(&args.dashboard).is_none() {
+        problems.push(format!("no dashboard named '{}'", args.dashboard));
+    }
+
+    if let Err(e) = Keymap::new(GLOBAL_ACTIONS).remap(&config.keys) {
+        problems.push(format!("[keys]: {}", e));
+    }
+    if let Err(e) = StatusBarConfig::from_config(config) {
+        problems.push(format!("[statusbar]: {}", e));
+    }
+    if let Err(e) = AlertRule::from_config(config) {
+        problems.push(format!("[[alerts]]: {}", e));
+    }
+    if let Err(e) = NotificationConfig::from_config(config) {
+        problems.push(format!("[notifications]: {}", e));
+    }
+    for (name, keys) in &config.keys {
+        let toml::Value::Table(keys) = keys else {
+            continue;
+        };
+        if plugins.contains(name) {
+            continue;
+        }
+        match registry.create(name, &bus, args.tick_rate, &no_settings) {
+            Ok(widget) => {
+                if let Err(e) = WidgetContainer::new(name.clone(), widget).remap_keys(keys) {
+                    problems.push(format!("[keys.{}]: {}", name, e));
+                }
+            }
+            Err(_) => problems.push(format!("[keys.{}]: unknown widget '{}'", name, name)),
+        }
+    }
+    problems
+}
+
+/// Print the names of the built-in widgets and of the plugin widgets that
+/// load
+pub fn list_widgets(args: &CliArgs) -> ExitCode {
+    let registry = builtin_registry();
+    let mut builtin = registry.list_widgets();
+    builtin.sort();
+    println!("Built-in widgets:");
+    for name in builtin {
+        println!("  {}", name);
+    }
+
+    let mut manager = args.plugin_manager();
+    let dirs: Vec<String> = manager
+        .plugin_dirs()
+        .iter()
+        .map(|dir| dir.display().to_string())
+        .collect();
+    println!("Plugin widgets ({}):", dirs.join(", "));
+    match manager.load_all() {
+        Ok(plugins) if plugins.is_empty() => println!("  none"),
+        Ok(plugins) => {
+            for (name, _) in plugins {
+                println!("  {}", name);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to load plugins: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(text: &str) -> Vec<String> {
+        let config: ConfigFile = toml::from_str(text).unwrap();
+        problems(
+            &config,
+            &mut builtin_registry(),
+            &["weather".to_string()],
+            &CliArgs::default(),
+        )
+    }
+
+    #[test]
+    fn test_builtin_config_is_valid() {
+        let config = ConfigFile::default();
+        let problems = problems(&config, &mut builtin_registry(), &[], &CliArgs::default());
+        assert_eq!(problems, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_problems() {
+        let problems = check(
+            r#"
+            [[dashboard]]
+            name = "default"
+            [dashboard.layout]
+            type = "layout"
+            direction = "horizontal"
+            [[dashboard.layout.items]]
+            type = "widget"
+            name = "weather"
+            [[dashboard.layout.items]]
+            type = "widget"
+            name = "proces"
+            [[dashboard.layout.items]]
+            type = "widget"
+            name = "disk"
+            host = "buildbox"
+            [[dashboard.layout.items]]
+            type = "widget"
+            name = "process"
+            [[dashboard.widgets]]
+            name = "process"
+            interval = "soon"
+            "#,
+        );
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].contains("unknown widget 'proces'"));
+        assert!(problems[1].contains("unknown host 'buildbox'"));
+        assert!(problems[2].contains("'process'") && problems[2].contains("soon"));
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let problems = check(
+            r#"
+            [keys]
+            quit = "ctrl+q"
+            reload = "hyper+r"
+            [keys.process]
+            sort_memory = "M"
+            sort_size = "s"
+            [keys.weather]
+            refresh = "r"
+            [keys.proces]
+            sort_cpu = "C"
+            "#,
+        );
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems[1].starts_with("[keys]:") && problems[1].contains("hyper+r"));
+        assert!(problems[2].starts_with("[keys.proces]:"));
+        assert!(problems[3].starts_with("[keys.process]:") && problems[3].contains("sort_size"));
+    }
+
+    #[test]
+    fn test_missing_dashboard() {
+        let problems = check("");
+        assert_eq!(problems, ["no dashboard named 'default'"]);
+    }
+}
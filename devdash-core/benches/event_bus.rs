@@ -0,0 +1,59 @@
+// devdash-core/benches/event_bus.rs
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use devdash_core::{BusEvent, EventBus, SystemMetrics};
+use std::hint::black_box;
+
+fn metrics() -> SystemMetrics {
+    SystemMetrics {
+        cpu_usage: 42.0,
+        memory_used: 1024,
+        memory_total: 2048,
+    }
+}
+
+fn bench_publish_fan_out(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_bus_publish");
+
+    for subscribers in [1u64, 10, 100, 1000] {
+        let bus = EventBus::new();
+        // Half match the topic exactly, half through a wildcard
+        let subs: Vec<_> = (0..subscribers)
+            .map(|i: u64| {
+                bus.subscribe(if i.is_multiple_of(2) {
+                    "system.metrics"
+                } else {
+                    "system.*"
+                })
+            })
+            .collect();
+
+        group.throughput(Throughput::Elements(subscribers));
+        group.bench_with_input(
+            BenchmarkId::new("subscribers", subscribers),
+            &bus,
+            |b, bus| {
+                b.iter(|| {
+                    bus.publish(black_box(BusEvent::new("system.metrics", metrics())));
+                    // Drain so queues don't grow across iterations
+                    for (_, rx) in &subs {
+                        while rx.try_recv().is_ok() {}
+                    }
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_publish_unmatched(c: &mut Criterion) {
+    let bus = EventBus::new();
+    let _subs: Vec<_> = (0..100).map(|_| bus.subscribe("git.*")).collect();
+
+    c.bench_function("event_bus_publish_unmatched_100", |b| {
+        b.iter(|| bus.publish(black_box(BusEvent::new("system.metrics", metrics()))))
+    });
+}
+
+criterion_group!(benches, bench_publish_fan_out, bench_publish_unmatched);
+criterion_main!(benches);
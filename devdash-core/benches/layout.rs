@@ -0,0 +1,45 @@
+// devdash-core/benches/layout.rs
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use devdash_core::{Constraint, Layout, LayoutItem};
+use ratatui::layout::Rect;
+use std::hint::black_box;
+
+/// Alternate horizontal/vertical splits `depth` levels deep, `width` items each
+fn nested_layout(depth: usize, width: usize) -> Layout {
+    let items = (0..width)
+        .map(|i| {
+            if depth > 0 && i == 0 {
+                LayoutItem::nested(nested_layout(depth - 1, width))
+            } else {
+                LayoutItem::widget(match i % 3 {
+                    0 => Constraint::Flex(1),
+                    1 => Constraint::Percentage(20),
+                    _ => Constraint::Fixed(10),
+                })
+            }
+        })
+        .collect();
+
+    if depth.is_multiple_of(2) {
+        Layout::horizontal(items)
+    } else {
+        Layout::vertical(items)
+    }
+}
+
+fn bench_calculate(c: &mut Criterion) {
+    let area = Rect::new(0, 0, 320, 100);
+    let mut group = c.benchmark_group("layout_calculate");
+
+    for depth in [1, 4, 8, 16] {
+        let layout = nested_layout(depth, 4);
+        group.bench_with_input(BenchmarkId::new("depth", depth), &layout, |b, layout| {
+            b.iter(|| layout.calculate(black_box(area)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_calculate);
+criterion_main!(benches);
@@ -0,0 +1,353 @@
+// devdash-core/src/collector.rs
+//! Background metric collection.
+//!
+//! Heavy sources (process tables, git status, disk scans) run on a shared
+//! [`WorkerPool`] so a slow collector never stalls a frame. Widgets own a
+//! [`Collector`], request a run when their poll interval elapses, and pick
+//! up the result on the UI thread in `on_update`.
+//!
+//! A collector told which results are failures backs off while its source
+//! keeps failing, such as a missing socket or an unreadable log, instead of
+//! retrying on every poll.
+use crossbeam::channel::{Receiver, Sender, TryRecvError, unbounded};
+use std::sync::{Arc, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::widget::DataStatus;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Tells whether a collector's result is a failure
+type FailureCheck<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Failures in a row before runs are spaced out
+const BACKOFF_AFTER: u32 = 2;
+
+/// Delay after [`BACKOFF_AFTER`] failures, doubled with each further one
+const BACKOFF_START: Duration = Duration::from_secs(5);
+
+/// Longest delay between runs of a failing collector
+const BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+/// How long to hold off after `failures` failures in a row
+fn backoff_delay(failures: u32) -> Option<Duration> {
+    let doublings = failures.checked_sub(BACKOFF_AFTER)?;
+    Some(
+        BACKOFF_START
+            .saturating_mul(1 << doublings.min(16))
+            .min(BACKOFF_MAX),
+    )
+}
+
+/// Fixed-size pool of threads running collection jobs
+pub struct WorkerPool {
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// Start a pool with `threads` workers (at least one)
+    pub fn new(threads: usize) -> Self {
+        let (tx, rx) = unbounded::<Job>();
+        let workers = (0..threads.max(1))
+            .map(|i| {
+                let rx = rx.clone();
+                std::thread::Builder::new()
+                    .name(format!("devdash-worker-{i}"))
+                    .spawn(move || {
+                        for job in rx {
+                            // A panicking collector must not take the worker down
+                            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                        }
+                    })
+                    .expect("failed to spawn worker thread")
+            })
+            .collect();
+
+        Self {
+            jobs: Some(tx),
+            workers,
+        }
+    }
+
+    /// Process-wide pool shared by the built-in widgets
+    ///
+    /// Sized to the available parallelism, capped at 4 threads.
+    pub fn global() -> &'static WorkerPool {
+        static POOL: OnceLock<WorkerPool> = OnceLock::new();
+        POOL.get_or_init(|| {
+            let threads = std::thread::available_parallelism().map_or(2, |n| n.get().min(4));
+            WorkerPool::new(threads)
+        })
+    }
+
+    /// Number of worker threads
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Run `job` on the next free worker
+    pub fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(Box::new(job));
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // Closing the queue lets workers finish pending jobs and exit
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// A repeatable collection job with state kept between runs
+///
+/// The state (e.g. a `sysinfo::System`, which needs previous samples to
+/// compute CPU usage) moves to the worker for each run and comes back with
+/// the result. At most one run is in flight at a time.
+pub struct Collector<S, T> {
+    state: Option<S>,
+    collect: Arc<dyn Fn(&mut S) -> T + Send + Sync>,
+    tx: Sender<(S, T)>,
+    rx: Receiver<(S, T)>,
+    /// When the last result was taken, or the collector was created
+    last_result: Instant,
+    /// Tells failed results apart, for back-off
+    failed: Option<FailureCheck<T>>,
+    /// Failed results in a row
+    failures: u32,
+    /// Requests are skipped until then while backing off
+    retry_at: Option<Instant>,
+}
+
+impl<S: Send + 'static, T: Send + 'static> Collector<S, T> {
+    /// Create a collector that runs `collect` against `state`
+    ///
+    /// If `collect` panics the state is lost and the collector stays busy.
+    pub fn new(state: S, collect: impl Fn(&mut S) -> T + Send + Sync + 'static) -> Self {
+        let (tx, rx) = unbounded();
+        Self {
+            state: Some(state),
+            collect: Arc::new(collect),
+            tx,
+            rx,
+            last_result: Instant::now(),
+            failed: None,
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    /// Back off while `failed` says the results are failures
+    ///
+    /// After two failures in a row, requests are skipped for 5 seconds,
+    /// twice as long after each further failure, up to 5 minutes. The first
+    /// result that isn't a failure ends the back-off.
+    pub fn with_backoff(mut self, failed: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.failed = Some(Box::new(failed));
+        self
+    }
+
+    /// When the next run may start, while backing off
+    pub fn retry_at(&self) -> Option<Instant> {
+        self.retry_at
+    }
+
+    /// Whether a run is in flight
+    pub fn is_busy(&self) -> bool {
+        self.state.is_none()
+    }
+
+    /// Start a run on `pool` unless one is already in flight or the
+    /// collector is backing off
+    ///
+    /// Returns false if the request was skipped. A skipped request isn't
+    /// queued, so widgets can call this every poll interval without runs of
+    /// a slow source piling up; the next one starts at the first interval
+    /// after the current run finishes.
+    pub fn request(&mut self, pool: &WorkerPool) -> bool {
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return false;
+        }
+        let Some(mut state) = self.state.take() else {
+            return false;
+        };
+
+        let collect = self.collect.clone();
+        let tx = self.tx.clone();
+        pool.execute(move || {
+            let result = collect(&mut state);
+            let _ = tx.send((state, result));
+        });
+        true
+    }
+
+    /// Take the result of a finished run, if any
+    pub fn try_take(&mut self) -> Option<T> {
+        match self.rx.try_recv() {
+            Ok((state, result)) => Some(self.finish(state, result)),
+            Err(TryRecvError::Empty | TryRecvError::Disconnected) => None,
+        }
+    }
+
+    /// Take back the state of a finished run and count a failed result
+    fn finish(&mut self, state: S, result: T) -> T {
+        self.state = Some(state);
+        self.last_result = Instant::now();
+        if let Some(failed) = &self.failed {
+            self.failures = match failed(&result) {
+                true => self.failures.saturating_add(1),
+                false => 0,
+            };
+            self.retry_at = backoff_delay(self.failures).map(|delay| self.last_result + delay);
+        }
+        result
+    }
+
+    /// What [`Widget::data_status`](crate::Widget::data_status) should
+    /// report for a widget running this collector every `interval`
+    pub fn status(&self, interval: Duration) -> DataStatus {
+        DataStatus {
+            refreshing: self.is_busy(),
+            last_data: self.last_result,
+            interval,
+            retry_at: self.retry_at,
+        }
+    }
+
+    /// Block until the in-flight run finishes and return its result
+    ///
+    /// Returns `None` if nothing is running.
+    pub fn wait(&mut self) -> Option<T> {
+        if !self.is_busy() {
+            return None;
+        }
+        let (state, result) = self.rx.recv().ok()?;
+        Some(self.finish(state, result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+
+    #[test]
+    fn test_collector_keeps_state_between_runs() {
+        let pool = WorkerPool::new(1);
+        let mut counter = Collector::new(0u32, |n: &mut u32| {
+            *n += 1;
+            *n
+        });
+
+        assert!(counter.request(&pool));
+        assert!(counter.is_busy());
+        assert!(!counter.request(&pool));
+        assert_eq!(counter.wait(), Some(1));
+
+        counter.request(&pool);
+        assert_eq!(counter.wait(), Some(2));
+        assert_eq!(counter.wait(), None);
+    }
+
+    #[test]
+    fn test_jobs_run_concurrently() {
+        let pool = WorkerPool::new(2);
+        let barrier = Arc::new(Barrier::new(2));
+
+        // Both jobs must be running at once to get past the barrier
+        let mut collectors: Vec<_> = (0..2)
+            .map(|_| {
+                let barrier = barrier.clone();
+                Collector::new((), move |_: &mut ()| {
+                    barrier.wait();
+                })
+            })
+            .collect();
+        for collector in collectors.iter_mut() {
+            collector.request(&pool);
+        }
+
+        for collector in collectors.iter_mut() {
+            assert_eq!(collector.wait(), Some(()));
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let pool = WorkerPool::new(1);
+        let mut collector = Collector::new((), |_: &mut ()| ());
+        let created = collector.status(Duration::from_secs(1));
+        assert!(!created.refreshing);
+
+        collector.request(&pool);
+        assert!(collector.status(Duration::from_secs(1)).refreshing);
+        collector.wait();
+        let status = collector.status(Duration::from_secs(1));
+        assert!(!status.refreshing);
+        assert!(status.last_data >= created.last_data);
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(0), None);
+        assert_eq!(backoff_delay(1), None);
+        assert_eq!(backoff_delay(2), Some(Duration::from_secs(5)));
+        assert_eq!(backoff_delay(3), Some(Duration::from_secs(10)));
+        assert_eq!(backoff_delay(7), Some(Duration::from_secs(160)));
+        assert_eq!(backoff_delay(8), Some(BACKOFF_MAX));
+        assert_eq!(backoff_delay(u32::MAX), Some(BACKOFF_MAX));
+    }
+
+    #[test]
+    fn test_backs_off_while_failing() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let pool = WorkerPool::new(1);
+        let up = Arc::new(AtomicBool::new(false));
+        let source = up.clone();
+        let mut collector = Collector::new((), move |_: &mut ()| {
+            source.load(Ordering::Relaxed).then_some(()).ok_or("down")
+        })
+        .with_backoff(Result::is_err);
+
+        // One failure is retried on the next poll
+        collector.request(&pool);
+        assert_eq!(collector.wait(), Some(Err("down")));
+        assert_eq!(collector.retry_at(), None);
+
+        collector.request(&pool);
+        assert_eq!(collector.wait(), Some(Err("down")));
+        let retry_at = collector.retry_at().unwrap();
+        assert_eq!(
+            collector.status(Duration::from_secs(1)).retry_at,
+            Some(retry_at)
+        );
+        assert!(retry_at > Instant::now() + Duration::from_secs(4));
+        assert!(!collector.request(&pool));
+        assert!(!collector.is_busy());
+
+        // Pretend the delay is over; a success ends the back-off
+        collector.retry_at = Some(Instant::now());
+        up.store(true, Ordering::Relaxed);
+        assert!(collector.request(&pool));
+        assert_eq!(collector.wait(), Some(Ok(())));
+        assert_eq!(collector.retry_at(), None);
+        assert_eq!(collector.failures, 0);
+    }
+
+    #[test]
+    fn test_panicking_job_does_not_kill_worker() {
+        let pool = WorkerPool::new(1);
+        pool.execute(|| panic!("collector failed"));
+
+        let mut collector = Collector::new((), |_: &mut ()| 7);
+        collector.request(&pool);
+        assert_eq!(collector.wait(), Some(7));
+    }
+}
@@ -1,5 +1,26 @@
-use crate::{Constraint, Layout, LayoutItem};
+use crate::{
+    Constraint, DevdashError, Layout, LayoutItem, TitleAlignment, WidgetSettings, WidgetStyle,
+};
+use ratatui::layout::Rect;
 use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static AUTOSTART: AtomicBool = AtomicBool::new(true);
+
+/// Let widgets start the commands their settings list, such as dev servers
+/// and tunnels, on their own (or not), process-wide; a config shipped with
+/// a project the user hasn't trusted mustn't run anything unasked
+pub fn set_autostart(autostart: bool) {
+    AUTOSTART.store(autostart, Ordering::Relaxed);
+}
+
+/// Whether widgets may start the commands their settings list on their own
+pub fn autostart() -> bool {
+    AUTOSTART.load(Ordering::Relaxed)
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -9,23 +30,39 @@ pub enum ConfigError {
     Parse(#[from] toml::de::Error),
     #[error("Config directory not found")]
     NoConfigDir,
+    #[error("Failed to write config: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Failed to edit config: {0}")]
+    Edit(#[from] toml_edit::TomlError),
+    #[error("Dashboard '{0}' is not in the config file")]
+    MissingDashboard(String),
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ConfigFile {
     #[serde(default)]
     pub dashboard: Vec<Dashboard>,
+    /// Machines that layout items can show with `host = "<name>"`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub host: Vec<HostConfig>,
+    /// Key remaps: `action = "key"` for the dashboard's actions, and
+    /// `[keys.<widget>]` tables for a widget's
+    #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+    pub keys: toml::Table,
+    /// Top-level settings that apply to every dashboard, e.g. `units`
+    #[serde(flatten)]
+    pub settings: toml::Table,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Dashboard {
     pub name: String,
     pub layout: ConfigLayout,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub widgets: Vec<WidgetConfig>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum ConfigLayout {
     Layout {
@@ -34,13 +71,31 @@ pub enum ConfigLayout {
     },
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum ConfigLayoutItem {
     Widget {
         name: String,
+        /// Show this `[[host]]` instead of the local machine
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        host: Option<String>,
+        #[serde(flatten)]
+        constraint: ConfigConstraint,
+        #[serde(flatten)]
+        style: WidgetStyle,
+    },
+    /// Empty gap
+    Spacer {
+        #[serde(flatten)]
+        constraint: ConfigConstraint,
+    },
+    /// Static text such as a heading
+    Label {
+        text: String,
         #[serde(flatten)]
         constraint: ConfigConstraint,
+        #[serde(flatten)]
+        style: LabelStyle,
     },
     Layout {
         direction: Direction,
@@ -48,19 +103,67 @@ pub enum ConfigLayoutItem {
     },
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
     Horizontal,
     Vertical,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ConfigConstraint {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub flex: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fixed: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub percentage: Option<u16>,
+    /// At least this many cells, growing like `flex = 1` when there is room
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<u16>,
+    /// Like `flex = 1`, but never more than this many cells
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<u16>,
+}
+
+/// Appearance of a `label` layout item
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LabelStyle {
+    /// Text color: a name such as `"cyan"` or a hex value such as `"#ff8800"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<TitleAlignment>,
+}
+
+impl Dashboard {
+    /// Settings of the named widget from `[[dashboard.widgets]]`, if any
+    pub fn widget_settings(&self, name: &str) -> Option<&WidgetSettings> {
+        self.widgets
+            .iter()
+            .find(|widget| widget.name == name)
+            .and_then(|widget| widget.settings.as_table())
+    }
+}
+
+/// A `[[host]]` block: a machine whose metrics are streamed to devdash
+///
+/// Events come from an agent over TCP, or from `devdash stream` run over
+/// SSH; exactly one of `agent` and `ssh` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HostConfig {
+    pub name: String,
+    /// `host:port` of an agent
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// SSH destination, e.g. `me@buildbox`
+    #[serde(default)]
+    pub ssh: Option<String>,
+    /// Command run over SSH; `devdash stream` by default
+    #[serde(default)]
+    pub command: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -80,38 +183,54 @@ impl Default for ConfigFile {
                     items: vec![
                         ConfigLayoutItem::Widget {
                             name: "process".to_string(),
+                            host: None,
                             constraint: ConfigConstraint {
                                 flex: Some(1),
                                 fixed: None,
                                 percentage: None,
+                                min: None,
+                                max: None,
                             },
+                            style: WidgetStyle::default(),
                         },
                         ConfigLayoutItem::Layout {
                             direction: Direction::Vertical,
                             items: vec![
                                 ConfigLayoutItem::Widget {
                                     name: "cpu".to_string(),
+                                    host: None,
                                     constraint: ConfigConstraint {
                                         flex: Some(1),
                                         fixed: None,
                                         percentage: None,
+                                        min: None,
+                                        max: None,
                                     },
+                                    style: WidgetStyle::default(),
                                 },
                                 ConfigLayoutItem::Widget {
                                     name: "memory".to_string(),
+                                    host: None,
                                     constraint: ConfigConstraint {
                                         flex: Some(1),
                                         fixed: None,
                                         percentage: None,
+                                        min: None,
+                                        max: None,
                                     },
+                                    style: WidgetStyle::default(),
                                 },
                                 ConfigLayoutItem::Widget {
                                     name: "disk".to_string(),
+                                    host: None,
                                     constraint: ConfigConstraint {
                                         flex: Some(1),
                                         fixed: None,
                                         percentage: None,
+                                        min: None,
+                                        max: None,
                                     },
+                                    style: WidgetStyle::default(),
                                 },
                             ],
                         },
@@ -119,33 +238,94 @@ impl Default for ConfigFile {
                 },
                 widgets: vec![],
             }],
+            host: Vec::new(),
+            keys: toml::Table::new(),
+            settings: toml::Table::new(),
         }
     }
 }
 
 impl ConfigFile {
     pub fn load() -> Result<Self, ConfigError> {
+        match Self::find()? {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Locations searched for a config file, in priority order
+    pub fn search_paths() -> Result<[PathBuf; 2], ConfigError> {
         // Priority: ./devdash.toml -> ~/.config/devdash/devdash.toml -> default
-        let paths = [
+        Ok([
             std::env::current_dir()?.join("devdash.toml"),
             dirs::config_dir()
                 .ok_or(ConfigError::NoConfigDir)?
                 .join("devdash/devdash.toml"),
-        ];
+        ])
+    }
 
-        for path in paths {
-            if path.exists() {
-                let content = std::fs::read_to_string(path)?;
-                return toml::from_str(&content).map_err(ConfigError::Parse);
-            }
-        }
+    /// The config file [`load`](Self::load) would read, if any exists
+    pub fn find() -> Result<Option<PathBuf>, ConfigError> {
+        Ok(Self::search_paths()?.into_iter().find(|path| path.exists()))
+    }
 
-        Ok(Self::default())
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(ConfigError::Parse)
     }
 
     pub fn get_dashboard(&self, name: &str) -> Option<&Dashboard> {
         self.dashboard.iter().find(|d| d.name == name)
     }
+
+    pub fn get_host(&self, name: &str) -> Option<&HostConfig> {
+        self.host.iter().find(|h| h.name == name)
+    }
+
+    /// Replace the layout of dashboard `name` in the config file at `path`
+    ///
+    /// The rest of the file, comments included, is kept; comments inside
+    /// the old layout are lost. A missing file is created holding just this
+    /// dashboard.
+    pub fn write_layout(path: &Path, name: &str, layout: &ConfigLayout) -> Result<(), ConfigError> {
+        let layout: toml_edit::DocumentMut = toml::to_string(layout)?.parse()?;
+        let mut layout = layout.as_table().clone();
+        layout.set_implicit(false);
+
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                format!("[[dashboard]]\nname = {:?}\n", name)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut document: toml_edit::DocumentMut = content.parse()?;
+        let dashboard = document
+            .get_mut("dashboard")
+            .and_then(|d| d.as_array_of_tables_mut())
+            .and_then(|dashboards| {
+                dashboards
+                    .iter_mut()
+                    .find(|d| d.get("name").and_then(|n| n.as_str()) == Some(name))
+            })
+            .ok_or_else(|| ConfigError::MissingDashboard(name.to_string()))?;
+        dashboard.insert("layout", toml_edit::Item::Table(layout));
+
+        std::fs::write(path, document.to_string())?;
+        Ok(())
+    }
+
+    /// Like [`get_dashboard`](Self::get_dashboard), but reports the available names on failure
+    pub fn require_dashboard(&self, name: &str) -> Result<&Dashboard, DevdashError> {
+        self.get_dashboard(name)
+            .ok_or_else(|| DevdashError::DashboardNotFound {
+                name: name.to_string(),
+                available: self.dashboard.iter().map(|d| d.name.clone()).collect(),
+            })
+    }
 }
 
 impl ConfigLayout {
@@ -163,10 +343,340 @@ impl ConfigLayout {
     }
 }
 
+impl ConfigLayout {
+    /// Exchange the widgets in the `a`th and `b`th widget slots, counting
+    /// in layout order; the slots keep their sizes
+    pub fn swap_widgets(&mut self, a: usize, b: usize) {
+        let mut slots = self.widget_items_mut();
+        if a == b || a.max(b) >= slots.len() {
+            return;
+        }
+        let (low, high) = slots.split_at_mut(a.max(b));
+        let (
+            ConfigLayoutItem::Widget {
+                name, host, style, ..
+            },
+            ConfigLayoutItem::Widget {
+                name: other_name,
+                host: other_host,
+                style: other_style,
+                ..
+            },
+        ) = (&mut *low[a.min(b)], &mut *high[0])
+        else {
+            return;
+        };
+        std::mem::swap(name, other_name);
+        std::mem::swap(host, other_host);
+        std::mem::swap(style, other_style);
+    }
+
+    /// Take the `index`th widget out of the layout, dropping nested layouts
+    /// left empty
+    pub fn remove_widget(&mut self, index: usize) {
+        let ConfigLayout::Layout { items, .. } = self;
+        remove_widget_recursive(items, &mut { index });
+    }
+
+    /// Split the `index`th widget's slot along `direction`, putting `item`
+    /// after it; returns false if there is no such widget
+    ///
+    /// In a layout running along `direction` the new item becomes the
+    /// widget's sibling, sized like it. Otherwise the two share the slot in
+    /// a nested layout, which takes an equal share of its parent, as
+    /// nested layouts have no size of their own.
+    pub fn split_widget(
+        &mut self,
+        index: usize,
+        direction: Direction,
+        item: ConfigLayoutItem,
+    ) -> bool {
+        let ConfigLayout::Layout {
+            direction: outer,
+            items,
+        } = self;
+        split_widget_recursive(items, *outer, &mut { index }, direction, &mut Some(item))
+    }
+
+    /// Grow the `index`th widget by `delta` cells along `direction` when the
+    /// layout fills `area`, or shrink it if `delta` is negative
+    ///
+    /// The widget's right or bottom edge moves, or its left or top edge when
+    /// nothing follows it that way. Returns false if nothing changed.
+    pub fn resize_widget(
+        &mut self,
+        index: usize,
+        direction: Direction,
+        delta: i32,
+        area: Rect,
+    ) -> bool {
+        self.move_edge(index, direction, delta, area, true)
+            || self.move_edge(index, direction, delta, area, false)
+    }
+
+    /// Move the right or bottom edge of the `index`th widget by `delta`
+    /// cells, as when dragging the border it shares with what follows it
+    pub fn move_border(
+        &mut self,
+        index: usize,
+        direction: Direction,
+        delta: i32,
+        area: Rect,
+    ) -> bool {
+        self.move_edge(index, direction, delta, area, true)
+    }
+
+    fn move_edge(
+        &mut self,
+        index: usize,
+        direction: Direction,
+        delta: i32,
+        area: Rect,
+        trailing: bool,
+    ) -> bool {
+        let ConfigLayout::Layout {
+            direction: outer,
+            items,
+        } = self;
+        let edge = Edge {
+            direction,
+            delta,
+            trailing,
+        };
+        matches!(
+            resize_recursive(items, *outer, area, &mut { index }, &edge),
+            Resize::Done(true)
+        )
+    }
+
+    fn widget_items_mut(&mut self) -> Vec<&mut ConfigLayoutItem> {
+        let ConfigLayout::Layout { items, .. } = self;
+        let mut result = Vec::new();
+        widget_items_recursive(items, &mut result);
+        result
+    }
+}
+
+fn widget_items_recursive<'a>(
+    items: &'a mut [ConfigLayoutItem],
+    result: &mut Vec<&'a mut ConfigLayoutItem>,
+) {
+    for item in items {
+        match item {
+            ConfigLayoutItem::Layout { items: nested, .. } => {
+                widget_items_recursive(nested, result)
+            }
+            ConfigLayoutItem::Widget { .. } => result.push(item),
+            _ => {}
+        }
+    }
+}
+
+/// The edge of a widget to move, and how far
+struct Edge {
+    direction: Direction,
+    /// Cells the widget grows by
+    delta: i32,
+    /// Whether to move the right or bottom edge rather than the left or top
+    trailing: bool,
+}
+
+/// How far [`resize_recursive`] got
+enum Resize {
+    /// The widget is not among the items
+    NotFound,
+    /// The widget is among the items, but no layout around it has the edge
+    Pending,
+    /// The edge was found; whether it moved
+    Done(bool),
+}
+
+/// Move the edge of the widget `remaining` widgets further on, in the
+/// innermost layout running the edge's way that has an item beyond it
+fn resize_recursive(
+    items: &mut [ConfigLayoutItem],
+    direction: Direction,
+    area: Rect,
+    remaining: &mut usize,
+    edge: &Edge,
+) -> Resize {
+    let layout_items: Vec<_> = items.iter().map(|item| item.to_layout_item()).collect();
+    let total = match direction {
+        Direction::Horizontal => area.width,
+        Direction::Vertical => area.height,
+    };
+    let sizes = Layout::allocate(&layout_items, total);
+
+    for i in 0..items.len() {
+        let found = match &mut items[i] {
+            ConfigLayoutItem::Widget { .. } if *remaining == 0 => Resize::Pending,
+            ConfigLayoutItem::Widget { .. } => {
+                *remaining -= 1;
+                continue;
+            }
+            ConfigLayoutItem::Layout {
+                direction: nested_direction,
+                items: nested,
+            } => {
+                let nested_area = match direction {
+                    Direction::Horizontal => Rect {
+                        width: sizes[i],
+                        ..area
+                    },
+                    Direction::Vertical => Rect {
+                        height: sizes[i],
+                        ..area
+                    },
+                };
+                resize_recursive(nested, *nested_direction, nested_area, remaining, edge)
+            }
+            _ => continue,
+        };
+        let neighbor = if edge.trailing {
+            Some(i + 1).filter(|&j| j < items.len())
+        } else {
+            i.checked_sub(1)
+        };
+        return match (found, neighbor) {
+            (Resize::NotFound, _) => continue,
+            (Resize::Pending, Some(neighbor)) if direction == edge.direction => {
+                Resize::Done(resize_pair(items, &sizes, total, i, neighbor, edge.delta))
+            }
+            (found, _) => found,
+        };
+    }
+    Resize::NotFound
+}
+
+/// Move `delta` cells from `items[shrink]` to `items[grow]`, whose current
+/// sizes are `sizes` out of `total`
+///
+/// Fixed items stay fixed and the other widgets, spacers and labels become
+/// percentages; nested layouts have no size of their own and keep sharing
+/// what is left. Each side keeps at least one cell.
+fn resize_pair(
+    items: &mut [ConfigLayoutItem],
+    sizes: &[u16],
+    total: u16,
+    grow: usize,
+    shrink: usize,
+    delta: i32,
+) -> bool {
+    let lowest = (1 - sizes[grow] as i32).min(0);
+    let highest = (sizes[shrink] as i32 - 1).max(0);
+    let delta = delta.clamp(lowest, highest);
+    if delta == 0 || (items[grow].constraint().is_none() && items[shrink].constraint().is_none()) {
+        return false;
+    }
+
+    for (i, size) in [
+        (grow, sizes[grow] as i32 + delta),
+        (shrink, sizes[shrink] as i32 - delta),
+    ] {
+        let Some(constraint) = items[i].constraint_mut() else {
+            continue;
+        };
+        let size = size as u16;
+        *constraint = match constraint.to_constraint() {
+            Constraint::Fixed(_) => ConfigConstraint {
+                fixed: Some(size),
+                ..Default::default()
+            },
+            // The smallest percentage that gives at least `size` cells
+            _ => ConfigConstraint {
+                percentage: Some((size as u32 * 100).div_ceil(total as u32) as u16),
+                ..Default::default()
+            },
+        };
+    }
+    true
+}
+
+/// Remove the widget `remaining` widgets further on; returns true once done
+fn remove_widget_recursive(items: &mut Vec<ConfigLayoutItem>, remaining: &mut usize) -> bool {
+    for i in 0..items.len() {
+        let emptied = match &mut items[i] {
+            ConfigLayoutItem::Widget { .. } if *remaining == 0 => true,
+            ConfigLayoutItem::Widget { .. } => {
+                *remaining -= 1;
+                continue;
+            }
+            ConfigLayoutItem::Layout { items: nested, .. } => {
+                if !remove_widget_recursive(nested, remaining) {
+                    continue;
+                }
+                nested.is_empty()
+            }
+            _ => continue,
+        };
+        if emptied {
+            items.remove(i);
+        }
+        return true;
+    }
+    false
+}
+
+fn split_widget_recursive(
+    items: &mut Vec<ConfigLayoutItem>,
+    outer: Direction,
+    remaining: &mut usize,
+    direction: Direction,
+    item: &mut Option<ConfigLayoutItem>,
+) -> bool {
+    for i in 0..items.len() {
+        match &mut items[i] {
+            ConfigLayoutItem::Widget { .. } if *remaining > 0 => *remaining -= 1,
+            ConfigLayoutItem::Widget { constraint, .. } => {
+                let Some(mut item) = item.take() else {
+                    return false;
+                };
+                if outer == direction {
+                    if let Some(size) = item.constraint_mut() {
+                        *size = *constraint;
+                    }
+                    items.insert(i + 1, item);
+                } else {
+                    let mut widget = items.remove(i);
+                    for split in [&mut widget, &mut item] {
+                        if let Some(size) = split.constraint_mut() {
+                            *size = ConfigConstraint {
+                                flex: Some(1),
+                                ..ConfigConstraint::default()
+                            };
+                        }
+                    }
+                    items.insert(
+                        i,
+                        ConfigLayoutItem::Layout {
+                            direction,
+                            items: vec![widget, item],
+                        },
+                    );
+                }
+                return true;
+            }
+            ConfigLayoutItem::Layout {
+                direction: nested,
+                items: nested_items,
+            } => {
+                let nested = *nested;
+                if split_widget_recursive(nested_items, nested, remaining, direction, item) {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
 impl ConfigLayoutItem {
     pub fn to_layout_item(&self) -> LayoutItem {
         match self {
-            ConfigLayoutItem::Widget { constraint, .. } => {
+            ConfigLayoutItem::Widget { constraint, .. }
+            | ConfigLayoutItem::Spacer { constraint }
+            | ConfigLayoutItem::Label { constraint, .. } => {
                 LayoutItem::Constraint(constraint.to_constraint())
             }
             ConfigLayoutItem::Layout { direction, items } => {
@@ -183,6 +693,27 @@ impl ConfigLayoutItem {
     }
 }
 
+impl ConfigLayoutItem {
+    /// Size of the item; nested layouts have none
+    fn constraint(&self) -> Option<&ConfigConstraint> {
+        match self {
+            ConfigLayoutItem::Widget { constraint, .. }
+            | ConfigLayoutItem::Spacer { constraint }
+            | ConfigLayoutItem::Label { constraint, .. } => Some(constraint),
+            ConfigLayoutItem::Layout { .. } => None,
+        }
+    }
+
+    fn constraint_mut(&mut self) -> Option<&mut ConfigConstraint> {
+        match self {
+            ConfigLayoutItem::Widget { constraint, .. }
+            | ConfigLayoutItem::Spacer { constraint }
+            | ConfigLayoutItem::Label { constraint, .. } => Some(constraint),
+            ConfigLayoutItem::Layout { .. } => None,
+        }
+    }
+}
+
 impl ConfigConstraint {
     pub fn to_constraint(&self) -> Constraint {
         if let Some(flex) = self.flex {
@@ -191,13 +722,17 @@ impl ConfigConstraint {
             Constraint::Fixed(fixed)
         } else if let Some(pct) = self.percentage {
             Constraint::Percentage(pct)
+        } else if let Some(min) = self.min {
+            Constraint::Min(min)
+        } else if let Some(max) = self.max {
+            Constraint::Max(max)
         } else {
             Constraint::Flex(1) // default
         }
     }
 }
 
-/// Flatten a config layout to extract all widget names in order
+/// Flatten a config layout to its leaves (widgets, spacers, labels) in order
 pub fn flatten_layout_items(layout: &ConfigLayout) -> Vec<&ConfigLayoutItem> {
     let mut result = Vec::new();
     match layout {
@@ -214,7 +749,9 @@ fn flatten_items_recursive<'a>(
 ) {
     for item in items {
         match item {
-            ConfigLayoutItem::Widget { .. } => {
+            ConfigLayoutItem::Widget { .. }
+            | ConfigLayoutItem::Spacer { .. }
+            | ConfigLayoutItem::Label { .. } => {
                 result.push(item);
             }
             ConfigLayoutItem::Layout {
@@ -226,3 +763,308 @@ fn flatten_items_recursive<'a>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BorderKind;
+
+    #[test]
+    fn test_widget_style() {
+        let item: ConfigLayoutItem = toml::from_str(
+            r#"
+            type = "widget"
+            name = "cpu"
+            flex = 2
+            border = "rounded"
+            title_alignment = "center"
+            padding = 1
+            "#,
+        )
+        .unwrap();
+
+        let ConfigLayoutItem::Widget {
+            constraint, style, ..
+        } = item
+        else {
+            panic!("expected a widget");
+        };
+        assert_eq!(constraint.flex, Some(2));
+        assert_eq!(
+            style,
+            WidgetStyle {
+                border: BorderKind::Rounded,
+                title_alignment: Some(TitleAlignment::Center),
+                padding: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_hosts() {
+        let config: ConfigFile = toml::from_str(
+            r#"
+            [[host]]
+            name = "buildbox"
+            ssh = "me@buildbox"
+
+            [[dashboard]]
+            name = "default"
+            [dashboard.layout]
+            type = "layout"
+            direction = "horizontal"
+            [[dashboard.layout.items]]
+            type = "widget"
+            name = "memory"
+            host = "buildbox"
+            "#,
+        )
+        .unwrap();
+
+        let host = config.get_host("buildbox").unwrap();
+        assert_eq!(host.ssh.as_deref(), Some("me@buildbox"));
+        assert_eq!(host.agent, None);
+        let dashboard = config.get_dashboard("default").unwrap();
+        let ConfigLayoutItem::Widget { host, .. } = flatten_layout_items(&dashboard.layout)[0]
+        else {
+            panic!("expected a widget");
+        };
+        assert_eq!(host.as_deref(), Some("buildbox"));
+        assert!(!config.settings.contains_key("host"));
+    }
+
+    fn names(layout: &ConfigLayout) -> Vec<&str> {
+        flatten_layout_items(layout)
+            .into_iter()
+            .filter_map(|item| match item {
+                ConfigLayoutItem::Widget { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_swap_and_remove_widgets() {
+        let mut layout = ConfigFile::default().dashboard.remove(0).layout;
+        assert_eq!(names(&layout), ["process", "cpu", "memory", "disk"]);
+
+        layout.swap_widgets(0, 2);
+        assert_eq!(names(&layout), ["memory", "cpu", "process", "disk"]);
+        // Sizes stay with the slots
+        let ConfigLayoutItem::Widget { constraint, .. } = flatten_layout_items(&layout)[0] else {
+            panic!("expected a widget");
+        };
+        assert_eq!(constraint.flex, Some(1));
+
+        layout.remove_widget(1);
+        assert_eq!(names(&layout), ["memory", "process", "disk"]);
+        layout.remove_widget(2);
+        layout.remove_widget(1);
+        assert_eq!(names(&layout), ["memory"]);
+        let ConfigLayout::Layout { items, .. } = &layout;
+        assert_eq!(items.len(), 1, "the emptied column is dropped");
+    }
+
+    #[test]
+    fn test_split_widget() {
+        let mut layout = ConfigFile::default().dashboard.remove(0).layout;
+        let widget = |name: &str| ConfigLayoutItem::Widget {
+            name: name.to_string(),
+            host: None,
+            constraint: ConfigConstraint::default(),
+            style: WidgetStyle::default(),
+        };
+
+        // Beside process, in the row it is in: a sibling of the same size
+        assert!(layout.split_widget(0, Direction::Horizontal, widget("git")));
+        assert_eq!(names(&layout), ["process", "git", "cpu", "memory", "disk"]);
+        let ConfigLayoutItem::Widget { constraint, .. } = flatten_layout_items(&layout)[1] else {
+            panic!("expected a widget");
+        };
+        assert_eq!(constraint.flex, Some(1));
+
+        // Beside cpu, in a column: cpu's slot becomes a row of the two
+        assert!(layout.split_widget(2, Direction::Horizontal, widget("network")));
+        assert_eq!(
+            names(&layout),
+            ["process", "git", "cpu", "network", "memory", "disk"]
+        );
+        let ConfigLayout::Layout { items, .. } = &layout;
+        let ConfigLayoutItem::Layout { items: column, .. } = &items[2] else {
+            panic!("expected the column");
+        };
+        assert!(matches!(
+            &column[0],
+            ConfigLayoutItem::Layout { direction: Direction::Horizontal, items } if items.len() == 2
+        ));
+
+        assert!(!layout.split_widget(6, Direction::Vertical, widget("git")));
+    }
+
+    #[test]
+    fn test_resize_widgets() {
+        let mut layout = ConfigFile::default().dashboard.remove(0).layout;
+        let area = Rect::new(0, 0, 100, 30);
+        let sizes = |layout: &ConfigLayout| -> Vec<(u16, u16)> {
+            layout
+                .to_layout()
+                .calculate(area)
+                .iter()
+                .map(|rect| (rect.width, rect.height))
+                .collect()
+        };
+
+        // Process and the column beside it
+        assert!(layout.resize_widget(0, Direction::Horizontal, 10, area));
+        assert_eq!(sizes(&layout)[..2], [(60, 30), (40, 10)]);
+        // cpu takes from memory below it; disk from memory above it
+        assert!(layout.resize_widget(1, Direction::Vertical, 2, area));
+        assert!(layout.resize_widget(3, Direction::Vertical, 2, area));
+        assert_eq!(sizes(&layout)[1..], [(40, 12), (40, 6), (40, 12)]);
+        let ConfigLayoutItem::Widget { constraint, .. } = flatten_layout_items(&layout)[1] else {
+            panic!("expected a widget");
+        };
+        assert_eq!(constraint.to_constraint(), Constraint::Percentage(40));
+
+        // The column grows leftwards by shrinking process
+        assert!(layout.resize_widget(1, Direction::Horizontal, 5, area));
+        assert_eq!(sizes(&layout)[..2], [(55, 30), (45, 12)]);
+        // Nothing is below process, and nothing shrinks away entirely
+        assert!(!layout.move_border(0, Direction::Vertical, 1, area));
+        assert!(layout.resize_widget(0, Direction::Horizontal, -100, area));
+        assert_eq!(sizes(&layout)[0], (1, 30));
+        assert!(!layout.resize_widget(0, Direction::Horizontal, -1, area));
+
+        // Fixed items stay fixed
+        let mut layout: ConfigLayout = toml::from_str(
+            r#"
+            type = "layout"
+            direction = "vertical"
+            items = [
+                { type = "label", text = "System", fixed = 1 },
+                { type = "widget", name = "cpu" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert!(!layout.move_border(0, Direction::Vertical, -2, area));
+        assert!(layout.resize_widget(0, Direction::Vertical, -2, area));
+        assert_eq!(sizes(&layout), [(100, 3), (100, 27)]);
+        let ConfigLayoutItem::Label { constraint, .. } = flatten_layout_items(&layout)[0] else {
+            panic!("expected a label");
+        };
+        assert_eq!(constraint.to_constraint(), Constraint::Fixed(3));
+    }
+
+    #[test]
+    fn test_write_layout_keeps_comments() {
+        let dir = std::env::temp_dir().join(format!("devdash-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("devdash.toml");
+        std::fs::write(
+            &path,
+            r#"# My dashboards
+units = "si" # decimal units
+
+[[dashboard]]
+name = "default"
+[dashboard.layout]
+type = "layout"
+direction = "horizontal"
+[[dashboard.layout.items]]
+type = "widget"
+name = "cpu"
+[[dashboard.layout.items]]
+type = "widget"
+name = "memory"
+border = "rounded"
+
+# Per-widget settings
+[[dashboard.widgets]]
+name = "cpu"
+"#,
+        )
+        .unwrap();
+
+        let mut config = ConfigFile::load_from(&path).unwrap();
+        let dashboard = config.dashboard.remove(0);
+        let mut layout = dashboard.layout;
+        layout.swap_widgets(0, 1);
+        ConfigFile::write_layout(&path, "default", &layout).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("# My dashboards"), "{}", written);
+        assert!(written.contains("# decimal units"), "{}", written);
+        assert!(written.contains("# Per-widget settings"), "{}", written);
+        let config = ConfigFile::load_from(&path).unwrap();
+        let dashboard = config.get_dashboard("default").unwrap();
+        assert_eq!(dashboard.layout, layout);
+        assert_eq!(dashboard.widgets.len(), 1);
+        assert!(matches!(
+            ConfigFile::write_layout(&path, "other", &layout),
+            Err(ConfigError::MissingDashboard(_))
+        ));
+
+        let fresh = dir.join("new/devdash.toml");
+        ConfigFile::write_layout(&fresh, "default", &layout).unwrap();
+        let config = ConfigFile::load_from(&fresh).unwrap();
+        assert_eq!(config.get_dashboard("default").unwrap().layout, layout);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_spacer_and_label_are_leaves() {
+        let layout: ConfigLayout = toml::from_str(
+            r#"
+            type = "layout"
+            direction = "vertical"
+            items = [
+                { type = "label", text = "System", fixed = 1, color = "cyan", bold = true },
+                { type = "widget", name = "cpu" },
+                { type = "spacer", fixed = 1 },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let leaves = flatten_layout_items(&layout);
+        assert_eq!(leaves.len(), 3);
+        let ConfigLayoutItem::Label { text, style, .. } = leaves[0] else {
+            panic!("expected a label");
+        };
+        assert_eq!(text, "System");
+        assert_eq!(style.color.as_deref(), Some("cyan"));
+        assert!(style.bold);
+        assert!(matches!(leaves[2], ConfigLayoutItem::Spacer { .. }));
+        assert_eq!(
+            layout.to_layout().calculate(Rect::new(0, 0, 10, 10)).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_min_and_max_constraints() {
+        let layout: ConfigLayout = toml::from_str(
+            r#"
+            type = "layout"
+            direction = "horizontal"
+            items = [
+                { type = "widget", name = "cpu", min = 30 },
+                { type = "widget", name = "memory", max = 20 },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let constraints: Vec<Constraint> = flatten_layout_items(&layout)
+            .iter()
+            .map(|item| match item {
+                ConfigLayoutItem::Widget { constraint, .. } => constraint.to_constraint(),
+                _ => panic!("expected a widget"),
+            })
+            .collect();
+        assert_eq!(constraints, [Constraint::Min(30), Constraint::Max(20)]);
+        let areas = layout.to_layout().calculate(Rect::new(0, 0, 100, 10));
+        assert_eq!((areas[0].width, areas[1].width), (80, 20));
+    }
+}
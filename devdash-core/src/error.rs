@@ -0,0 +1,94 @@
+// devdash-core/src/error.rs
+use crate::{ConfigError, PluginError};
+use std::path::PathBuf;
+
+/// Result alias using [`DevdashError`]
+pub type Result<T, E = DevdashError> = std::result::Result<T, E>;
+
+/// Top-level error for building and running a dashboard
+///
+/// Wraps the per-module errors and adds the context needed to report them.
+/// Use [`DevdashError::is_recoverable`] to decide whether the UI can keep
+/// running with its previous state.
+#[derive(Debug, thiserror::Error)]
+pub enum DevdashError {
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+    #[error("Plugin error: {0}")]
+    Plugin(#[from] PluginError),
+    #[error("Dashboard '{name}' not found. Available: {}", available.join(", "))]
+    DashboardNotFound {
+        name: String,
+        available: Vec<String>,
+    },
+    #[error("Unknown widget '{0}'")]
+    UnknownWidget(String),
+    #[error("Failed to create widget '{name}': {reason}")]
+    WidgetInit { name: String, reason: String },
+    #[error("Invalid arguments: {0}")]
+    InvalidArgs(String),
+    #[error("Failed to open '{}': {source}", path.display())]
+    File {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Terminal error: {0}")]
+    Terminal(#[from] std::io::Error),
+}
+
+impl DevdashError {
+    /// Whether the dashboard can keep running after this error
+    ///
+    /// Configuration, plugin, and widget failures only affect what is shown,
+    /// so a reload can fall back to the current state. Argument, file, and
+    /// terminal failures are fatal.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Self::Config(_)
+            | Self::Plugin(_)
+            | Self::DashboardNotFound { .. }
+            | Self::UnknownWidget(_)
+            | Self::WidgetInit { .. } => true,
+            Self::InvalidArgs(_) | Self::File { .. } | Self::Terminal(_) => false,
+        }
+    }
+
+    /// Attach the offending path to an I/O error
+    pub fn file(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::File {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_include_context() {
+        let err = DevdashError::DashboardNotFound {
+            name: "work".to_string(),
+            available: vec!["default".to_string(), "dev".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Dashboard 'work' not found. Available: default, dev"
+        );
+
+        let err = DevdashError::file(
+            "events.jsonl",
+            std::io::Error::new(std::io::ErrorKind::NotFound, "missing"),
+        );
+        assert_eq!(err.to_string(), "Failed to open 'events.jsonl': missing");
+    }
+
+    #[test]
+    fn test_recoverable() {
+        assert!(DevdashError::UnknownWidget("clock".to_string()).is_recoverable());
+        assert!(DevdashError::Config(ConfigError::NoConfigDir).is_recoverable());
+        assert!(!DevdashError::InvalidArgs("--bogus".to_string()).is_recoverable());
+    }
+}
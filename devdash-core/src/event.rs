@@ -1,6 +1,7 @@
 // devdash-core/src/event.rs
 use crossbeam::channel::{Receiver, Sender, unbounded};
-use std::any::Any;
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
@@ -16,6 +17,16 @@ impl EventPayload {
     pub fn downcast<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
         Arc::downcast(self.0.clone()).ok()
     }
+
+    /// Type of the value inside
+    pub fn type_id(&self) -> TypeId {
+        (*self.0).type_id()
+    }
+
+    /// Whether both are clones of the same payload
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 /// Event with topic and payload
@@ -55,6 +66,7 @@ impl Drop for Subscription {
 struct EventBusInner {
     subscriptions: RwLock<HashMap<usize, (String, Sender<Event>)>>,
     next_id: std::sync::atomic::AtomicUsize,
+    live: std::sync::atomic::AtomicBool,
 }
 
 /// Lockfree event bus with topic-based pub/sub
@@ -75,10 +87,27 @@ impl EventBus {
             inner: Arc::new(EventBusInner {
                 subscriptions: RwLock::new(HashMap::new()),
                 next_id: std::sync::atomic::AtomicUsize::new(0),
+                live: std::sync::atomic::AtomicBool::new(true),
             }),
         }
     }
 
+    /// Whether widgets should collect live data
+    ///
+    /// When false, events are being injected from elsewhere (e.g. a recorded
+    /// stream) and widgets render the payloads published on their own topics
+    /// instead of polling the system.
+    pub fn is_live(&self) -> bool {
+        self.inner.live.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Enable or disable live data collection for all clones of this bus
+    pub fn set_live(&self, live: bool) {
+        self.inner
+            .live
+            .store(live, std::sync::atomic::Ordering::SeqCst);
+    }
+
     /// Publish an event to all matching subscribers
     pub fn publish(&self, event: Event) {
         let subs = self.inner.subscriptions.read().unwrap();
@@ -115,8 +144,9 @@ impl EventBus {
         (sub, rx)
     }
 
-    /// Check if topic matches pattern (supports * wildcard)
-    fn topic_matches(topic: &str, pattern: &str) -> bool {
+    /// Whether `topic` matches a subscription `pattern`; `*` matches one
+    /// segment, or everything after it at the end
+    pub fn topic_matches(topic: &str, pattern: &str) -> bool {
         // Exact match
         if topic == pattern {
             return true;
@@ -149,21 +179,21 @@ impl EventBus {
 }
 
 // Common event types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMetrics {
     pub cpu_usage: f32,
     pub memory_used: u64,
     pub memory_total: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitBranchChange {
     pub from: String,
     pub to: String,
     pub repo_path: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessUpdate {
     pub pid: u32,
     pub name: String,
@@ -204,6 +234,16 @@ mod tests {
         assert_eq!(received.cpu_usage, 50.0);
     }
 
+    #[test]
+    fn test_live_flag_shared_between_clones() {
+        let bus = EventBus::new();
+        let clone = bus.clone();
+        assert!(clone.is_live());
+
+        bus.set_live(false);
+        assert!(!clone.is_live());
+    }
+
     #[test]
     fn test_unsubscribe() {
         let bus = EventBus::new();
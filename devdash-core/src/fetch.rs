@@ -0,0 +1,713 @@
+// devdash-core/src/fetch.rs
+//! A shared HTTP client for widgets that read from the network.
+//!
+//! Requests go through one [`HttpClient`] so that widgets polling the same
+//! API share its quota and its answers: each host has a rate limit,
+//! responses are cached for as long as the caller allows, and stale entries
+//! are revalidated with `If-None-Match` so an unchanged resource costs a
+//! `304`. When a host can't be reached the client stops trying it for a
+//! while and hands out what it has cached instead; [`HttpClient::is_offline`]
+//! tells whether the last attempt anywhere failed that way. Widgets that
+//! measure an endpoint rather than read it, such as the latency and HTTP
+//! monitor widgets, [`send`](HttpClient::send) their requests uncached but
+//! within the same limits.
+//!
+//! Requests are made with `curl`.
+use std::{
+    collections::{HashMap, VecDeque},
+    process::{Command, Stdio},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// Requests per host per [`RateLimit::per`] unless set otherwise
+const DEFAULT_RATE_LIMIT: RateLimit = RateLimit {
+    requests: 60,
+    per: Duration::from_secs(60),
+};
+
+/// How long a host that couldn't be reached is left alone
+const UNREACHABLE_RETRY: Duration = Duration::from_secs(30);
+
+/// How long a request may take unless set otherwise
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `curl` exit codes for a host that couldn't be resolved or connected to
+const CURL_UNREACHABLE: [i32; 3] = [5, 6, 7];
+
+/// What `curl` writes out after the response: the seconds it took
+const CURL_TIME: &str = "\n%{time_total}";
+
+/// At most `requests` requests to a host within any `per`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+/// A request handed to the transport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub timeout: Duration,
+}
+
+/// A response as the transport received it
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RawResponse {
+    pub status: u16,
+    /// Header names are lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// How long the response took to arrive, if the transport timed it
+    pub elapsed: Option<Duration>,
+}
+
+impl RawResponse {
+    /// The first header called `name` (lowercase)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Why the transport got no response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportError {
+    /// The host couldn't be resolved or connected to
+    Unreachable(String),
+    /// Anything else, such as a timeout or a TLS failure
+    Failed(String),
+}
+
+/// Sends a request and waits for the response
+pub type Transport = Box<dyn Fn(&Request) -> Result<RawResponse, TransportError> + Send + Sync>;
+
+/// A response handed to the caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+    /// When the server last sent (or confirmed) this body
+    pub fetched_at: Instant,
+    /// Whether the body is older than the caller asked for, because the
+    /// host was rate limited or couldn't be reached
+    pub stale: bool,
+    /// How long the server took to answer; `None` if the answer came from
+    /// the cache
+    pub elapsed: Option<Duration>,
+}
+
+/// Why a request got no response and nothing was cached
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FetchError {
+    #[error("{0} is unreachable")]
+    Offline(String),
+    #[error("rate limited by {host}, retrying in {}s", retry_in.as_secs().max(1))]
+    RateLimited { host: String, retry_in: Duration },
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// A successful response kept for later requests
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    status: u16,
+    body: String,
+    etag: Option<String>,
+    fetched_at: Instant,
+}
+
+impl CacheEntry {
+    fn response(&self, stale: bool) -> Response {
+        Response {
+            status: self.status,
+            body: self.body.clone(),
+            fetched_at: self.fetched_at,
+            stale,
+            elapsed: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Host {
+    limit: Option<RateLimit>,
+    /// When recent requests were sent, oldest first
+    sent: VecDeque<Instant>,
+    /// Set by a `429`'s `Retry-After`
+    blocked_until: Option<Instant>,
+    /// When the host last couldn't be reached, until it answers again
+    unreachable_since: Option<Instant>,
+}
+
+impl Host {
+    /// Why a request to this host, called `name`, may not be sent now
+    fn refuse(&mut self, name: &str, now: Instant) -> Option<FetchError> {
+        if let Some(since) = self.unreachable_since
+            && now.saturating_duration_since(since) < UNREACHABLE_RETRY
+        {
+            return Some(FetchError::Offline(name.to_string()));
+        }
+        self.wait(now).map(|retry_in| FetchError::RateLimited {
+            host: name.to_string(),
+            retry_in,
+        })
+    }
+
+    /// How long until another request may be sent, if it may not now
+    fn wait(&mut self, now: Instant) -> Option<Duration> {
+        let limit = self.limit.unwrap_or(DEFAULT_RATE_LIMIT);
+        while self
+            .sent
+            .front()
+            .is_some_and(|&at| now.saturating_duration_since(at) >= limit.per)
+        {
+            self.sent.pop_front();
+        }
+        let blocked = self
+            .blocked_until
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero());
+        let limited = (self.sent.len() >= limit.requests as usize)
+            .then(|| {
+                self.sent
+                    .front()
+                    .map(|&at| limit.per - now.duration_since(at))
+            })
+            .flatten();
+        blocked.max(limited)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    hosts: HashMap<String, Host>,
+    cache: HashMap<String, CacheEntry>,
+    /// Whether the last request sent couldn't reach its host
+    offline: bool,
+}
+
+impl State {
+    /// Record how a request to `host` sent at `now` went, turning a
+    /// transport failure or a `429` into an error
+    fn settle(
+        &mut self,
+        host: &str,
+        result: Result<RawResponse, TransportError>,
+        now: Instant,
+    ) -> Result<RawResponse, FetchError> {
+        self.offline = matches!(result, Err(TransportError::Unreachable(_)));
+        let entry = self.hosts.entry(host.to_string()).or_default();
+        let response = match result {
+            Err(TransportError::Unreachable(_)) => {
+                entry.unreachable_since = Some(now);
+                return Err(FetchError::Offline(host.to_string()));
+            }
+            Err(TransportError::Failed(e)) => {
+                entry.unreachable_since = None;
+                return Err(FetchError::Failed(e));
+            }
+            Ok(response) => response,
+        };
+        entry.unreachable_since = None;
+        if response.status == 429 {
+            let retry_in = response
+                .header("retry-after")
+                .and_then(|s| s.trim().parse().ok())
+                .map_or(UNREACHABLE_RETRY, Duration::from_secs);
+            entry.blocked_until = Some(now + retry_in);
+            return Err(FetchError::RateLimited {
+                host: host.to_string(),
+                retry_in,
+            });
+        }
+        Ok(response)
+    }
+}
+
+/// Rate-limited, caching HTTP client shared by network widgets
+///
+/// Cheap to share between threads; requests block the calling thread, so
+/// widgets make them from their [`Collector`](crate::Collector).
+pub struct HttpClient {
+    state: Mutex<State>,
+    transport: Transport,
+    timeout: Duration,
+}
+
+impl HttpClient {
+    /// A client making requests with `curl`
+    pub fn new() -> Self {
+        Self::with_transport(Box::new(curl))
+    }
+
+    /// A client sending requests through `transport`, e.g. a canned one in
+    /// tests
+    pub fn with_transport(transport: Transport) -> Self {
+        Self {
+            state: Mutex::new(State::default()),
+            transport,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Give up on requests after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Process-wide client shared by the built-in widgets
+    pub fn global() -> &'static HttpClient {
+        static CLIENT: OnceLock<HttpClient> = OnceLock::new();
+        CLIENT.get_or_init(HttpClient::new)
+    }
+
+    /// Limit requests to `host`, e.g. to stay within an API's quota
+    pub fn set_rate_limit(&self, host: &str, limit: RateLimit) {
+        self.lock()
+            .hosts
+            .entry(host.to_ascii_lowercase())
+            .or_default()
+            .limit = Some(limit);
+    }
+
+    /// Whether the last request sent couldn't reach its host
+    pub fn is_offline(&self) -> bool {
+        self.lock().offline
+    }
+
+    /// `GET` `url`, answering from the cache if the last response is younger
+    /// than `max_age`
+    ///
+    /// An older cached response is revalidated with its `ETag`. It is
+    /// returned marked stale when the host is rate limited or unreachable,
+    /// or the request fails; without one those are errors. Only `2xx`
+    /// responses are cached.
+    pub fn get(&self, url: &str, max_age: Duration) -> Result<Response, FetchError> {
+        self.get_at(url, max_age, Instant::now())
+    }
+
+    fn get_at(&self, url: &str, max_age: Duration, now: Instant) -> Result<Response, FetchError> {
+        let host = host_of(url);
+        let request = {
+            let mut state = self.lock();
+            let cached = state.cache.get(url).cloned();
+            if let Some(entry) = &cached
+                && now.saturating_duration_since(entry.fetched_at) < max_age
+            {
+                return Ok(entry.response(false));
+            }
+            let fallback = |error| cached.as_ref().map(|e| e.response(true)).ok_or(error);
+            let entry = state.hosts.entry(host.clone()).or_default();
+            if let Some(error) = entry.refuse(&host, now) {
+                return fallback(error);
+            }
+            entry.sent.push_back(now);
+            let mut headers = Vec::new();
+            if let Some(etag) = cached.as_ref().and_then(|e| e.etag.clone()) {
+                headers.push(("If-None-Match".to_string(), etag));
+            }
+            Request {
+                method: "GET".to_string(),
+                url: url.to_string(),
+                headers,
+                timeout: self.timeout,
+            }
+        };
+
+        // The lock isn't held while waiting, so other hosts aren't held up
+        let result = (self.transport)(&request);
+
+        let mut state = self.lock();
+        let cached = state.cache.get(url).cloned();
+        let fallback = |error| cached.as_ref().map(|e| e.response(true)).ok_or(error);
+        let response = match state.settle(&host, result, now) {
+            Ok(response) => response,
+            Err(error) => return fallback(error),
+        };
+        let elapsed = response.elapsed;
+        let entry = match cached {
+            Some(mut entry) if response.status == 304 => {
+                entry.fetched_at = now;
+                state.cache.insert(url.to_string(), entry.clone());
+                entry
+            }
+            _ => {
+                let entry = CacheEntry {
+                    status: response.status,
+                    etag: response.header("etag").map(str::to_string),
+                    body: response.body,
+                    fetched_at: now,
+                };
+                if (200..300).contains(&entry.status) {
+                    state.cache.insert(url.to_string(), entry.clone());
+                }
+                entry
+            }
+        };
+        Ok(Response {
+            elapsed,
+            ..entry.response(false)
+        })
+    }
+
+    /// Send a `method` request to `url`, giving up after `timeout`, without
+    /// the cache, e.g. to check that an endpoint answers or time it
+    ///
+    /// The host's rate limit and the back-off from unreachable hosts still
+    /// apply, and a `429` is a [`FetchError::RateLimited`] like any other.
+    pub fn send(&self, method: &str, url: &str, timeout: Duration) -> Result<Response, FetchError> {
+        self.send_at(method, url, timeout, Instant::now())
+    }
+
+    fn send_at(
+        &self,
+        method: &str,
+        url: &str,
+        timeout: Duration,
+        now: Instant,
+    ) -> Result<Response, FetchError> {
+        let host = host_of(url);
+        {
+            let mut state = self.lock();
+            let entry = state.hosts.entry(host.clone()).or_default();
+            if let Some(error) = entry.refuse(&host, now) {
+                return Err(error);
+            }
+            entry.sent.push_back(now);
+        }
+        let request = Request {
+            method: method.to_ascii_uppercase(),
+            url: url.to_string(),
+            headers: Vec::new(),
+            timeout,
+        };
+        let result = (self.transport)(&request);
+        let response = self.lock().settle(&host, result, now)?;
+        Ok(Response {
+            status: response.status,
+            body: response.body,
+            fetched_at: now,
+            stale: false,
+            elapsed: response.elapsed,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The lowercase host (and port) of `url`
+pub fn host_of(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    host.to_ascii_lowercase()
+}
+
+/// Send `request` with `curl`
+fn curl(request: &Request) -> Result<RawResponse, TransportError> {
+    let mut curl = Command::new("curl");
+    curl.args(["-sS", "-i", "-w", CURL_TIME, "--max-time"])
+        .arg(format!("{:.3}", request.timeout.as_secs_f64()));
+    // A HEAD sent with -X waits for a body that never comes
+    if request.method == "HEAD" {
+        curl.arg("-I");
+    } else if request.method != "GET" {
+        curl.args(["-X", &request.method]);
+    }
+    for (name, value) in &request.headers {
+        curl.arg("-H").arg(format!("{}: {}", name, value));
+    }
+    let output = curl
+        .arg(&request.url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| TransportError::Failed(format!("can't run curl: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| line.trim_start_matches("curl: ").to_string())
+            .unwrap_or_else(|| "no response".to_string());
+        return Err(match output.status.code() {
+            Some(code) if CURL_UNREACHABLE.contains(&code) => TransportError::Unreachable(error),
+            _ => TransportError::Failed(error),
+        });
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // The time is written out on a line of its own after the body
+    let (text, seconds) = stdout.rsplit_once('\n').unwrap_or((&stdout, ""));
+    let mut response = parse_response(text)
+        .ok_or_else(|| TransportError::Failed("malformed response".to_string()))?;
+    response.elapsed = seconds
+        .trim()
+        .parse()
+        .ok()
+        .and_then(|s| Duration::try_from_secs_f64(s).ok());
+    Ok(response)
+}
+
+/// Parse what `curl -i` prints: the head of each response (interim ones
+/// and proxy `CONNECT`s first), then the body of the last
+fn parse_response(text: &str) -> Option<RawResponse> {
+    let mut rest = text;
+    loop {
+        let (head, body) = rest
+            .split_once("\r\n\r\n")
+            .or_else(|| rest.split_once("\n\n"))
+            .unwrap_or((rest, ""));
+        let mut lines = head.lines();
+        let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+        if body.starts_with("HTTP/") {
+            rest = body;
+            continue;
+        }
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+        return Some(RawResponse {
+            status,
+            headers,
+            body: body.to_string(),
+            elapsed: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    const URL: &str = "https://api.example.com/repos?page=1";
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    /// A client whose transport answers with `reply` and records requests
+    fn client(
+        reply: impl Fn(&Request) -> Result<RawResponse, TransportError> + Send + Sync + 'static,
+    ) -> (HttpClient, Arc<Mutex<Vec<Request>>>) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let log = sent.clone();
+        let client = HttpClient::with_transport(Box::new(move |request| {
+            log.lock().unwrap().push(request.clone());
+            reply(request)
+        }));
+        (client, sent)
+    }
+
+    fn ok(body: &str, etag: Option<&str>) -> Result<RawResponse, TransportError> {
+        Ok(RawResponse {
+            status: 200,
+            headers: etag
+                .map(|etag| ("etag".to_string(), etag.to_string()))
+                .into_iter()
+                .collect(),
+            body: body.to_string(),
+            elapsed: Some(Duration::from_millis(45)),
+        })
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of(URL), "api.example.com");
+        assert_eq!(
+            host_of("http://user:pw@Example.com:8080#x"),
+            "example.com:8080"
+        );
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let response = parse_response(
+            "HTTP/1.1 100 Continue\r\n\r\nHTTP/2 200\r\nETag: \"v1\"\r\nX-A: b: c\r\n\r\n{}\n",
+        )
+        .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("etag"), Some("\"v1\""));
+        assert_eq!(response.header("x-a"), Some("b: c"));
+        assert_eq!(response.body, "{}\n");
+        assert_eq!(parse_response("garbage"), None);
+    }
+
+    #[test]
+    fn test_caches_and_revalidates() {
+        let (client, sent) = client(|request| {
+            if request.headers.is_empty() {
+                ok("[1]", Some("\"v1\""))
+            } else {
+                Ok(RawResponse {
+                    status: 304,
+                    ..Default::default()
+                })
+            }
+        });
+        let start = Instant::now();
+
+        let first = client.get_at(URL, HOUR, start).unwrap();
+        assert_eq!((first.body.as_str(), first.stale), ("[1]", false));
+        assert_eq!(first.elapsed, Some(Duration::from_millis(45)));
+        // Fresh enough: no request
+        let cached = client.get_at(URL, HOUR, start + HOUR / 2).unwrap();
+        assert_eq!(cached.elapsed, None);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+
+        let later = start + HOUR * 2;
+        let revalidated = client.get_at(URL, HOUR, later).unwrap();
+        assert_eq!(revalidated.body, "[1]");
+        assert_eq!(revalidated.fetched_at, later);
+        let sent = sent.lock().unwrap();
+        assert_eq!(
+            sent[1].headers,
+            [("If-None-Match".to_string(), "\"v1\"".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_per_host() {
+        let (client, sent) = client(|_| ok("ok", None));
+        client.set_rate_limit(
+            "api.example.com",
+            RateLimit {
+                requests: 2,
+                per: Duration::from_secs(60),
+            },
+        );
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        client.get_at(URL, Duration::ZERO, at(0)).unwrap();
+        client.get_at(URL, Duration::ZERO, at(10)).unwrap();
+        // Over the limit: the cached body, marked stale
+        assert!(client.get_at(URL, Duration::ZERO, at(20)).unwrap().stale);
+        assert_eq!(
+            client.get_at("https://api.example.com/other", Duration::ZERO, at(20)),
+            Err(FetchError::RateLimited {
+                host: "api.example.com".to_string(),
+                retry_in: Duration::from_secs(40),
+            })
+        );
+        // Other hosts have their own limit
+        client
+            .get_at("https://example.org/", Duration::ZERO, at(20))
+            .unwrap();
+        assert!(!client.get_at(URL, Duration::ZERO, at(60)).unwrap().stale);
+        assert_eq!(sent.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_too_many_requests_blocks_host() {
+        let (client, sent) = client(|_| {
+            Ok(RawResponse {
+                status: 429,
+                headers: vec![("retry-after".to_string(), "120".to_string())],
+                ..Default::default()
+            })
+        });
+        let start = Instant::now();
+        let limited = Err(FetchError::RateLimited {
+            host: "api.example.com".to_string(),
+            retry_in: Duration::from_secs(120),
+        });
+        assert_eq!(client.get_at(URL, HOUR, start), limited);
+        assert!(matches!(
+            client.get_at(URL, HOUR, start + Duration::from_secs(60)),
+            Err(FetchError::RateLimited { .. })
+        ));
+        assert_eq!(sent.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_offline_serves_cache() {
+        let online = Arc::new(Mutex::new(true));
+        let up = online.clone();
+        let (client, sent) = client(move |_| {
+            if *up.lock().unwrap() {
+                ok("cached", None)
+            } else {
+                Err(TransportError::Unreachable(
+                    "Could not resolve host".to_string(),
+                ))
+            }
+        });
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        client.get_at(URL, Duration::ZERO, at(0)).unwrap();
+
+        *online.lock().unwrap() = false;
+        let response = client.get_at(URL, Duration::ZERO, at(1)).unwrap();
+        assert_eq!((response.body.as_str(), response.stale), ("cached", true));
+        assert!(client.is_offline());
+        // The host is left alone for a while
+        assert_eq!(
+            client.get_at("https://api.example.com/new", Duration::ZERO, at(2)),
+            Err(FetchError::Offline("api.example.com".to_string()))
+        );
+        assert_eq!(sent.lock().unwrap().len(), 2);
+
+        *online.lock().unwrap() = true;
+        assert!(!client.get_at(URL, Duration::ZERO, at(40)).unwrap().stale);
+        assert!(!client.is_offline());
+    }
+
+    #[test]
+    fn test_send_skips_cache() {
+        let (client, sent) = client(|request| match request.method.as_str() {
+            "HEAD" => ok("", None),
+            _ => Ok(RawResponse {
+                status: 503,
+                ..Default::default()
+            }),
+        });
+        client.set_rate_limit(
+            "api.example.com",
+            RateLimit {
+                requests: 2,
+                per: Duration::from_secs(60),
+            },
+        );
+        let start = Instant::now();
+        let timeout = Duration::from_secs(5);
+
+        let up = client.send_at("head", URL, timeout, start).unwrap();
+        assert_eq!(
+            (up.status, up.elapsed),
+            (200, Some(Duration::from_millis(45)))
+        );
+        // Error statuses are answers too, and nothing is cached
+        let down = client.send_at("GET", URL, timeout, start).unwrap();
+        assert_eq!((down.status, down.stale), (503, false));
+        assert!(matches!(
+            client.send_at("GET", URL, timeout, start),
+            Err(FetchError::RateLimited { .. })
+        ));
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(
+            (sent[0].method.as_str(), sent[0].timeout),
+            ("HEAD", timeout)
+        );
+        assert!(sent[1].headers.is_empty());
+    }
+}
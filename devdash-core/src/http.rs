@@ -0,0 +1,644 @@
+// devdash-core/src/http.rs
+//! A small HTTP/1.1 server answering `GET` requests, so other tools can
+//! read what a dashboard shows without a web framework.
+//!
+//! Each connection is served on its own thread and closed after one
+//! response, or handed over as a [`WebSocket`] when the handler upgrades it.
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use std::{
+    io::{self, Read, Write},
+    net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// Longest request head read; anything longer is refused
+const MAX_REQUEST: usize = 8 * 1024;
+
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long stopping waits to get through to the accept loop
+const WAKE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Appended to the client's key to prove the server speaks WebSocket
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// A `GET` request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Request<'a> {
+    /// The path, without the query string
+    pub path: &'a str,
+    query: &'a str,
+    head: &'a str,
+}
+
+impl<'a> Request<'a> {
+    fn parse(head: &'a str) -> Option<(&'a str, Self)> {
+        let mut request_line = head.lines().next()?.split(' ');
+        let (method, target) = (request_line.next()?, request_line.next()?);
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Some((method, Self { path, query, head }))
+    }
+
+    /// The values given for `key` in the query string, decoded
+    pub fn query(&self, key: &str) -> Vec<String> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+            .filter(|(name, _)| percent_decode(name) == key)
+            .map(|(_, value)| percent_decode(value))
+            .collect()
+    }
+
+    /// The value of header `name`, which is case-insensitive
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then_some(value.trim())
+        })
+    }
+}
+
+/// `%2A` and `+` in a query string as `*` and a space
+fn percent_decode(text: &str) -> String {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' if rest.len() >= 2 => {
+                match std::str::from_utf8(&rest[..2])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(decoded) => {
+                        bytes.push(decoded);
+                        rest = &rest[2..];
+                    }
+                    None => bytes.push(byte),
+                }
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// What to send back for a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: body.into(),
+        }
+    }
+
+    fn error(status: u16) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", reason(status)),
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    }
+}
+
+/// How to answer a request
+pub enum Reply {
+    Response(Response),
+    /// Switch the connection to WebSocket and hand it over; runs on the
+    /// connection's thread for as long as it likes
+    Upgrade(Box<dyn FnOnce(WebSocket) + Send>),
+}
+
+impl From<Response> for Reply {
+    fn from(response: Response) -> Self {
+        Self::Response(response)
+    }
+}
+
+/// Answers a `GET` request, or `None` for paths it doesn't serve
+pub type Handler = dyn Fn(&Request) -> Option<Reply> + Send + Sync;
+
+/// A listening server; stops when dropped
+pub struct HttpServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HttpServer {
+    /// Listen on `addr` and answer requests with `handler`
+    pub fn start(
+        addr: impl ToSocketAddrs,
+        handler: impl Fn(&Request) -> Option<Reply> + Send + Sync + 'static,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let stopping = stop.clone();
+        let handle = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopping.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let handler = handler.clone();
+                std::thread::spawn(move || serve(stream, &*handler));
+            }
+        });
+
+        Ok(Self {
+            addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// The address listened on, with the port chosen when bound to port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake the accept loop so it sees the flag; if that fails, the
+        // thread is left to end with the process rather than waited on
+        let woken = TcpStream::connect_timeout(&wake_addr(self.addr), WAKE_TIMEOUT).is_ok();
+        if let Some(handle) = self.handle.take()
+            && woken
+        {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Where to connect to reach a listener on `addr`: loopback of the same
+/// family when it listens on every address, which can't be connected to
+fn wake_addr(mut addr: SocketAddr) -> SocketAddr {
+    if addr.ip().is_unspecified() {
+        addr.set_ip(match addr {
+            SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+            SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+        });
+    }
+    addr
+}
+
+/// Answer the one request on `stream`
+fn serve(mut stream: TcpStream, handler: &Handler) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let Some(head) = read_head(&mut stream) else {
+        return respond(stream, Response::error(400));
+    };
+    let Some((method, request)) = Request::parse(&head) else {
+        return respond(stream, Response::error(400));
+    };
+    if method != "GET" {
+        return respond(stream, Response::error(405));
+    }
+    match handler(&request) {
+        Some(Reply::Response(response)) => respond(stream, response),
+        Some(Reply::Upgrade(_)) if !same_origin(&request) => respond(stream, Response::error(403)),
+        Some(Reply::Upgrade(run)) => match accept_key(&request) {
+            Some(accept) => {
+                let switched = write!(
+                    stream,
+                    "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                    accept
+                );
+                if switched.is_ok() {
+                    run(WebSocket {
+                        stream,
+                        incoming: Vec::new(),
+                    });
+                }
+            }
+            None => respond(stream, Response::error(400)),
+        },
+        None => respond(stream, Response::error(404)),
+    }
+}
+
+fn respond(mut stream: TcpStream, response: Response) {
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+        response.body
+    );
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// The request line and headers, up to the blank line ending them
+fn read_head(stream: &mut TcpStream) -> Option<String> {
+    let mut head = Vec::new();
+    let mut chunk = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 || head.len() + n > MAX_REQUEST {
+            return None;
+        }
+        head.extend_from_slice(&chunk[..n]);
+    }
+    String::from_utf8(head).ok()
+}
+
+/// The `Sec-WebSocket-Accept` answering a WebSocket upgrade request, or
+/// `None` if `request` isn't one
+fn accept_key(request: &Request) -> Option<String> {
+    let upgrade = request.header("Upgrade")?;
+    if !upgrade.eq_ignore_ascii_case("websocket") {
+        return None;
+    }
+    let key = request.header("Sec-WebSocket-Key")?;
+    let digest = sha1_smol::Sha1::from(format!("{}{}", key, WEBSOCKET_GUID)).digest();
+    Some(BASE64.encode(digest.bytes()))
+}
+
+/// Whether a browser page may open a WebSocket here: requests without an
+/// `Origin` come from other tools, and pages are let in only when served
+/// by this host or from localhost
+///
+/// Browsers don't apply the same-origin policy to WebSockets, so without
+/// this any site the user visits could read the stream.
+fn same_origin(request: &Request) -> bool {
+    let Some(origin) = request.header("Origin") else {
+        return true;
+    };
+    let authority = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    if request.header("Host") == Some(authority) {
+        return true;
+    }
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => host,
+        _ => authority,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// The server's end of a WebSocket connection, which sends text messages
+///
+/// Data messages from the client are ignored; [`WebSocket::poll`] answers
+/// its pings and closing handshake. A client that goes away without one
+/// shows up as a failed send.
+pub struct WebSocket {
+    stream: TcpStream,
+    /// Bytes read from the client that don't make up a whole frame yet
+    incoming: Vec<u8>,
+}
+
+impl WebSocket {
+    /// Send `text` as one message
+    pub fn send_text(&mut self, text: &str) -> io::Result<()> {
+        self.send_frame(0x1, text.as_bytes())
+    }
+
+    /// Answer the frames the client sent since the last call: a ping gets a
+    /// pong and a close is echoed. Returns `false` once the connection is
+    /// over, after which nothing more should be sent.
+    pub fn poll(&mut self) -> bool {
+        if !self.read_available() {
+            return false;
+        }
+        while let Some((opcode, payload, len)) = parse_frame(&self.incoming) {
+            self.incoming.drain(..len);
+            let answered = match opcode {
+                0x8 => {
+                    // Echo the status code, if there is one
+                    let _ = self.send_frame(0x8, &payload[..payload.len().min(2)]);
+                    let _ = self.stream.shutdown(Shutdown::Both);
+                    return false;
+                }
+                0x9 => self.send_frame(0xA, &payload),
+                _ => Ok(()),
+            };
+            if answered.is_err() {
+                return false;
+            }
+        }
+        // A frame this big is nothing this server expects
+        self.incoming.len() <= MAX_REQUEST
+    }
+
+    /// Say goodbye and close the connection
+    pub fn close(mut self) {
+        let _ = self.send_frame(0x8, &[]);
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+
+    /// Write an unfragmented, unmasked frame
+    fn send_frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = vec![0x80 | opcode];
+        match payload.len() {
+            len @ 0..=125 => frame.push(len as u8),
+            len @ 126..=0xFFFF => {
+                frame.push(126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(payload);
+        self.stream.write_all(&frame)
+    }
+
+    /// Read what the client has sent without waiting for more; `false` if
+    /// it hung up
+    fn read_available(&mut self) -> bool {
+        if self.stream.set_nonblocking(true).is_err() {
+            return false;
+        }
+        let mut chunk = [0; 1024];
+        let open = loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => break false,
+                Ok(n) => self.incoming.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break true,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break false,
+            }
+        };
+        self.stream.set_nonblocking(false).is_ok() && open
+    }
+}
+
+/// The opcode and unmasked payload of the frame at the start of `bytes`,
+/// with how many bytes it takes up, or `None` if it isn't all there yet
+fn parse_frame(bytes: &[u8]) -> Option<(u8, Vec<u8>, usize)> {
+    let (&first, rest) = bytes.split_first()?;
+    let (&second, rest) = rest.split_first()?;
+    let (len, rest) = match second & 0x7F {
+        126 => (
+            u64::from(u16::from_be_bytes(rest.get(..2)?.try_into().ok()?)),
+            &rest[2..],
+        ),
+        127 => (
+            u64::from_be_bytes(rest.get(..8)?.try_into().ok()?),
+            &rest[8..],
+        ),
+        len => (u64::from(len), rest),
+    };
+    let (mask, rest) = if second & 0x80 != 0 {
+        (rest.get(..4)?, &rest[4..])
+    } else {
+        (&[0; 4][..], rest)
+    };
+    let payload: Vec<u8> = rest
+        .get(..usize::try_from(len).ok()?)?
+        .iter()
+        .zip(mask.iter().cycle())
+        .map(|(byte, mask)| byte ^ mask)
+        .collect();
+    let used = bytes.len() - rest.len() + payload.len();
+    Some((first & 0x0F, payload, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_bytes(server: &HttpServer, request: &str) -> Vec<u8> {
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        response
+    }
+
+    fn request(server: &HttpServer, request: &str) -> String {
+        String::from_utf8(request_bytes(server, request)).unwrap()
+    }
+
+    #[test]
+    fn test_serves_handler_responses() {
+        let server = HttpServer::start("127.0.0.1:0", |request| {
+            (request.path == "/api/ping").then(|| Response::json("{\"ok\":true}").into())
+        })
+        .unwrap();
+
+        let response = request(
+            &server,
+            "GET /api/ping?verbose=1 HTTP/1.1\r\nHost: x\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        assert!(response.contains("Content-Length: 11\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"ok\":true}"));
+
+        let response = request(&server, "GET /missing HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        let response = request(&server, "POST /api/ping HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 405 "));
+        let response = request(&server, "\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 400 "));
+    }
+
+    #[test]
+    fn test_stops_when_bound_to_every_address() {
+        for addr in ["0.0.0.0:0", "[::]:0"] {
+            // Not every machine has IPv6
+            let Ok(server) = HttpServer::start(addr, |_| None) else {
+                continue;
+            };
+            assert!(server.local_addr().ip().is_unspecified());
+            let (done, stopped) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                drop(server);
+                let _ = done.send(());
+            });
+            assert!(
+                stopped.recv_timeout(Duration::from_secs(5)).is_ok(),
+                "{} didn't stop",
+                addr
+            );
+        }
+        assert_eq!(
+            wake_addr("0.0.0.0:8080".parse().unwrap()),
+            "127.0.0.1:8080".parse().unwrap()
+        );
+        assert_eq!(
+            wake_addr("[::]:8080".parse().unwrap()),
+            "[::1]:8080".parse().unwrap()
+        );
+        assert_eq!(
+            wake_addr("192.168.1.2:80".parse().unwrap()),
+            "192.168.1.2:80".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_request() {
+        let head = "GET /s?topic=system.%2A&topic=link.*&q=a+b&flag HTTP/1.1\r\n\
+                    Host: x\r\nsec-websocket-key: abc \r\n\r\n";
+        let (method, request) = Request::parse(head).unwrap();
+        assert_eq!((method, request.path), ("GET", "/s"));
+        assert_eq!(request.query("topic"), ["system.*", "link.*"]);
+        assert_eq!(request.query("q"), ["a b"]);
+        assert_eq!(request.query("flag"), [""]);
+        assert!(request.query("missing").is_empty());
+        assert_eq!(request.header("Sec-WebSocket-Key"), Some("abc"));
+        assert_eq!(request.header("Upgrade"), None);
+    }
+
+    #[test]
+    fn test_websocket_upgrade() {
+        let server = HttpServer::start("127.0.0.1:0", |request| {
+            let greeting = format!("hello {}", request.query("name").join(","));
+            Some(Reply::Upgrade(Box::new(move |mut socket| {
+                socket.send_text(&greeting).unwrap();
+                socket.close();
+            })))
+        })
+        .unwrap();
+
+        // The key and accept value from RFC 6455
+        let response = request_bytes(
+            &server,
+            "GET /stream?name=ws HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        );
+        let end = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        let head = String::from_utf8_lossy(&response[..end]);
+        assert!(head.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+        assert!(head.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+        assert_eq!(&response[end..], b"\x81\x08hello ws\x88\x00");
+
+        // Plain requests for it are refused
+        let response = request(&server, "GET /stream HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 400 "));
+    }
+
+    #[test]
+    fn test_websocket_origin() {
+        let server = HttpServer::start("127.0.0.1:0", |_| {
+            Some(Reply::Upgrade(Box::new(|socket| socket.close())))
+        })
+        .unwrap();
+        let upgrade = |headers: &str| {
+            let response = request_bytes(
+                &server,
+                &format!(
+                    "GET / HTTP/1.1\r\nHost: dash.lan:9900\r\nUpgrade: websocket\r\n\
+                     Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n{}\r\n",
+                    headers
+                ),
+            );
+            String::from_utf8_lossy(&response).into_owned()
+        };
+
+        for allowed in [
+            "",
+            "Origin: http://dash.lan:9900\r\n",
+            "Origin: http://localhost:3000\r\n",
+            "Origin: https://127.0.0.1\r\n",
+            "Origin: http://[::1]:8080\r\n",
+        ] {
+            assert!(upgrade(allowed).starts_with("HTTP/1.1 101 "), "{}", allowed);
+        }
+        for refused in [
+            "Origin: https://evil.example\r\n",
+            "Origin: http://dash.lan:9901\r\n",
+            "Origin: http://localhost.evil.example\r\n",
+            "Origin: null\r\n",
+        ] {
+            assert!(upgrade(refused).starts_with("HTTP/1.1 403 "), "{}", refused);
+        }
+    }
+
+    #[test]
+    fn test_websocket_answers_ping_and_close() {
+        let server = HttpServer::start("127.0.0.1:0", |_| {
+            Some(Reply::Upgrade(Box::new(|mut socket| {
+                while socket.poll() {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            })))
+        })
+        .unwrap();
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream
+            .write_all(
+                b"GET / HTTP/1.1\r\nUpgrade: websocket\r\n\
+                  Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            )
+            .unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            let mut byte = [0];
+            stream.read_exact(&mut byte).unwrap();
+            head.push(byte[0]);
+        }
+
+        // Client frames are masked; a text message is ignored
+        let mask = [1, 2, 3, 4];
+        let frame = |opcode: u8, payload: &[u8]| {
+            let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+            frame.extend_from_slice(&mask);
+            frame.extend(payload.iter().zip(mask.iter().cycle()).map(|(b, m)| b ^ m));
+            frame
+        };
+        stream.write_all(&frame(0x1, b"ignored")).unwrap();
+        stream.write_all(&frame(0x9, b"hi")).unwrap();
+        let mut pong = [0; 4];
+        stream.read_exact(&mut pong).unwrap();
+        assert_eq!(&pong, b"\x8A\x02hi");
+
+        stream
+            .write_all(&frame(0x8, &1000u16.to_be_bytes()))
+            .unwrap();
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"\x88\x02\x03\xE8");
+    }
+
+    #[test]
+    fn test_parse_frame() {
+        assert_eq!(parse_frame(b"\x89"), None);
+        assert_eq!(parse_frame(b"\x89\x02h"), None);
+        assert_eq!(
+            parse_frame(b"\x89\x02hi\x81"),
+            Some((0x9, b"hi".to_vec(), 4))
+        );
+        let mut long = vec![0x82, 126, 0x01, 0x00];
+        long.extend_from_slice(&[7; 256]);
+        assert_eq!(parse_frame(&long), Some((0x2, vec![7; 256], 260)));
+    }
+}
@@ -0,0 +1,483 @@
+// devdash-core/src/keymap.rs
+//! Named actions and the keys bound to them.
+//!
+//! Widgets and the dashboard declare [`Action`]s with their default keys. A
+//! [`Keymap`] built from those turns key presses into [`Event::Action`]s,
+//! after applying the remaps of the config's `[keys]` section, so nothing
+//! but the keymap looks at raw key codes.
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::{Event, KeyBinding};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum KeymapError {
+    #[error("Invalid key '{key}': {reason}")]
+    InvalidKey { key: String, reason: String },
+    #[error("Unknown action '{0}'")]
+    UnknownAction(String),
+    #[error("Keys for '{0}' must be a string or a list of strings")]
+    InvalidValue(String),
+}
+
+/// A key with its modifiers, e.g. `ctrl+r` or `shift+tab`
+///
+/// Shift is folded into the character for printable keys, so `H` and
+/// `shift+h` are the same chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                // Terminals report ctrl+r the same whether shift is held
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        *self == Self::from(key)
+    }
+}
+
+impl From<&KeyEvent> for KeyChord {
+    fn from(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = KeymapError;
+
+    /// Parse e.g. `"q"`, `"H"`, `"ctrl+r"`, `"shift+tab"`, `"space"`,
+    /// `"down"` or `"f5"`; names are case-insensitive
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| KeymapError::InvalidKey {
+            key: text.to_string(),
+            reason: reason.to_string(),
+        };
+        // `+` is a key of its own, as in `ctrl++`
+        let (prefix, key) = match text.strip_suffix('+') {
+            Some(prefix) if prefix.is_empty() || prefix.ends_with('+') => (prefix, "+"),
+            _ => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid(&format!("unknown modifier '{}'", modifier))),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => return Err(invalid("no key given")),
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => match c {
+                '↑' => KeyCode::Up,
+                '↓' => KeyCode::Down,
+                '←' => KeyCode::Left,
+                '→' => KeyCode::Right,
+                c => KeyCode::Char(c),
+            },
+            _ => match key.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(invalid("unknown key name")),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    /// As shown in help, e.g. `Ctrl+R`, `Shift+Tab`, `↓` or `q`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            code => write!(f, "{}", code),
+        }
+    }
+}
+
+/// A named command, with the keys bound to it unless the config remaps it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Action {
+    /// Name used in `[keys]`, e.g. `"sort_cpu"`
+    pub name: &'static str,
+    /// Default keys, in the format [`KeyChord`] parses
+    pub keys: &'static [&'static str],
+    pub description: &'static str,
+    /// Whether the action changes the system or the layout, rather than
+    /// only what is shown
+    pub mutating: bool,
+}
+
+impl Action {
+    pub const fn new(
+        name: &'static str,
+        keys: &'static [&'static str],
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            keys,
+            description,
+            mutating: false,
+        }
+    }
+
+    /// Mark the action as changing something, such as killing a process,
+    /// so read-only mode turns it off
+    pub const fn mutating(mut self) -> Self {
+        self.mutating = true;
+        self
+    }
+
+    /// Whether the action may run; read-only mode turns off mutating ones
+    pub fn is_enabled(&self) -> bool {
+        !(self.mutating && read_only())
+    }
+}
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Turn mutating actions off (or back on) process-wide, for displays
+/// nobody should change anything from
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+}
+
+/// Whether mutating actions are turned off
+pub fn read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// The keys bound to each action of a widget or of the dashboard
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(&'static str, Vec<KeyChord>)>,
+}
+
+impl Keymap {
+    /// Bind each of `actions` to its default keys
+    ///
+    /// # Panics
+    /// If a default key doesn't parse.
+    pub fn new(actions: &[Action]) -> Self {
+        let bindings = actions
+            .iter()
+            .map(|action| {
+                let chords = action
+                    .keys
+                    .iter()
+                    .map(|key| {
+                        key.parse()
+                            .unwrap_or_else(|e| panic!("default key of '{}': {}", action.name, e))
+                    })
+                    .collect();
+                (action.name, chords)
+            })
+            .collect();
+        Self { bindings }
+    }
+
+    /// Rebind the actions named in `keys`, a `[keys]` table mapping action
+    /// names to a key or a list of keys
+    ///
+    /// A key taken over from another action no longer triggers it. Tables
+    /// (the per-widget sections) are skipped. Nothing changes on error.
+    pub fn remap(&mut self, keys: &toml::Table) -> Result<(), KeymapError> {
+        let mut remapped = self.clone();
+        for (name, value) in keys {
+            let chords: Vec<KeyChord> = match value {
+                toml::Value::Table(_) => continue,
+                toml::Value::String(key) => vec![key.parse()?],
+                toml::Value::Array(keys) => keys
+                    .iter()
+                    .map(|key| {
+                        key.as_str()
+                            .ok_or_else(|| KeymapError::InvalidValue(name.clone()))?
+                            .parse()
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(KeymapError::InvalidValue(name.clone())),
+            };
+            if !remapped.bindings.iter().any(|(action, _)| action == name) {
+                return Err(KeymapError::UnknownAction(name.clone()));
+            }
+            for (action, bound) in remapped.bindings.iter_mut() {
+                if action == name {
+                    *bound = chords.clone();
+                } else {
+                    bound.retain(|chord| !chords.contains(chord));
+                }
+            }
+        }
+        *self = remapped;
+        Ok(())
+    }
+
+    /// The action bound to `key`, if any
+    pub fn action(&self, key: &KeyEvent) -> Option<&'static str> {
+        let chord = KeyChord::from(key);
+        self.bindings
+            .iter()
+            .find(|(_, chords)| chords.contains(&chord))
+            .map(|(action, _)| *action)
+    }
+
+    /// Turn a key press bound to an action into that action; other events
+    /// pass through
+    pub fn translate(&self, event: Event) -> Event {
+        match &event {
+            Event::Key(key) => match self.action(key) {
+                Some(action) => Event::Action(action.to_string()),
+                None => event,
+            },
+            _ => event,
+        }
+    }
+
+    /// The keys bound to `action` as shown in help, e.g. `"j/↓"`
+    pub fn keys(&self, action: &str) -> String {
+        self.bindings
+            .iter()
+            .find(|(name, _)| *name == action)
+            .map(|(_, chords)| {
+                chords
+                    .iter()
+                    .map(KeyChord::to_string)
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .unwrap_or_default()
+    }
+
+    /// Help entries for `actions` with their current keys, leaving out
+    /// actions with no key left and those read-only mode turns off
+    pub fn bindings(&self, actions: &[Action]) -> Vec<KeyBinding> {
+        actions
+            .iter()
+            .filter(|action| action.is_enabled())
+            .map(|action| KeyBinding::new(self.keys(action.name), action.description))
+            .filter(|binding| !binding.keys.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTIONS: &[Action] = &[
+        Action::new("down", &["j", "down"], "Move down"),
+        Action::new("sort_cpu", &["c"], "Sort by CPU"),
+        Action::new("reload", &["ctrl+r"], "Reload"),
+    ];
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn chord(text: &str) -> KeyChord {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        assert_eq!(
+            chord("q"),
+            KeyChord::new(KeyCode::Char('q'), KeyModifiers::NONE)
+        );
+        assert_eq!(chord("H"), chord("shift+h"));
+        assert_eq!(
+            chord("Ctrl+R"),
+            KeyChord::new(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            chord("shift+tab"),
+            KeyChord::new(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            chord("+"),
+            KeyChord::new(KeyCode::Char('+'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            chord("ctrl++"),
+            KeyChord::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            chord("space"),
+            KeyChord::new(KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+        assert_eq!(chord("↓"), chord("Down"));
+        assert_eq!(
+            chord("f5"),
+            KeyChord::new(KeyCode::F(5), KeyModifiers::NONE)
+        );
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("f13".parse::<KeyChord>().is_err());
+        assert!("".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        for (text, shown) in [
+            ("ctrl+r", "Ctrl+R"),
+            ("shift+tab", "Shift+Tab"),
+            ("down", "↓"),
+            ("space", "Space"),
+            ("esc", "Esc"),
+            ("!", "!"),
+        ] {
+            assert_eq!(chord(text).to_string(), shown);
+        }
+    }
+
+    #[test]
+    fn test_matches_key_events() {
+        // Terminals report uppercase letters with shift held
+        assert!(chord("H").matches(&key(KeyCode::Char('H'), KeyModifiers::SHIFT)));
+        assert!(chord("!").matches(&key(KeyCode::Char('!'), KeyModifiers::SHIFT)));
+        assert!(chord("shift+tab").matches(&key(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert!(!chord("r").matches(&key(KeyCode::Char('r'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn test_defaults() {
+        let keymap = Keymap::new(ACTIONS);
+        assert_eq!(
+            keymap.action(&key(KeyCode::Down, KeyModifiers::NONE)),
+            Some("down")
+        );
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+            Some("reload")
+        );
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('x'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(keymap.keys("down"), "j/↓");
+        assert!(matches!(
+            keymap.translate(Event::Key(key(KeyCode::Char('c'), KeyModifiers::NONE))),
+            Event::Action(action) if action == "sort_cpu"
+        ));
+        assert!(matches!(
+            keymap.translate(Event::Key(key(KeyCode::Char('x'), KeyModifiers::NONE))),
+            Event::Key(_)
+        ));
+    }
+
+    #[test]
+    fn test_remap() {
+        let mut keymap = Keymap::new(ACTIONS);
+        let keys: toml::Table = toml::from_str(
+            r#"
+            sort_cpu = ["s", "j"]
+            reload = "f5"
+            [process]
+            down = "x"
+            "#,
+        )
+        .unwrap();
+        keymap.remap(&keys).unwrap();
+        assert_eq!(keymap.keys("sort_cpu"), "s/j");
+        // `j` moved to sort_cpu
+        assert_eq!(keymap.keys("down"), "↓");
+        assert_eq!(keymap.keys("reload"), "F5");
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('c'), KeyModifiers::NONE)),
+            None
+        );
+
+        let bindings = keymap.bindings(ACTIONS);
+        assert_eq!(bindings[1], KeyBinding::new("s/j", "Sort by CPU"));
+    }
+
+    #[test]
+    fn test_remap_errors() {
+        let mut keymap = Keymap::new(ACTIONS);
+        let remap = |keymap: &mut Keymap, text: &str| keymap.remap(&toml::from_str(text).unwrap());
+        assert_eq!(
+            remap(&mut keymap, "sort_memory = \"m\""),
+            Err(KeymapError::UnknownAction("sort_memory".to_string()))
+        );
+        assert!(matches!(
+            remap(&mut keymap, "down = \"ctrl+\""),
+            Err(KeymapError::InvalidKey { .. })
+        ));
+        assert_eq!(
+            remap(&mut keymap, "down = 5"),
+            Err(KeymapError::InvalidValue("down".to_string()))
+        );
+        // Nothing was applied
+        assert_eq!(keymap, Keymap::new(ACTIONS));
+    }
+}
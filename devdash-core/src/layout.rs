@@ -89,256 +89,120 @@ impl Layout {
     }
 
     fn split_horizontal(area: Rect, items: &[LayoutItem]) -> Vec<Rect> {
-        if items.is_empty() {
-            return vec![];
-        }
-
-        let total_width = area.width;
-        let mut areas = Vec::with_capacity(items.len());
-        let mut remaining_width = total_width;
-        let mut flex_total = 0u16;
-
-        // First pass: allocate fixed and percentage constraints
-        for item in items {
-            match item {
-                LayoutItem::Constraint(constraint) => {
-                    match constraint {
-                        Constraint::Fixed(size) => {
-                            let allocated = (*size).min(remaining_width);
-                            areas.push(Rect {
-                                x: area.x + (total_width - remaining_width),
-                                y: area.y,
-                                width: allocated,
-                                height: area.height,
-                            });
-                            remaining_width = remaining_width.saturating_sub(allocated);
-                        }
-                        Constraint::Percentage(pct) => {
-                            let size = (total_width * pct.min(&100) / 100).min(remaining_width);
-                            areas.push(Rect {
-                                x: area.x + (total_width - remaining_width),
-                                y: area.y,
-                                width: size,
-                                height: area.height,
-                            });
-                            remaining_width = remaining_width.saturating_sub(size);
-                        }
-                        Constraint::Flex(_) => {
-                            // Placeholder for flex calculation
-                            areas.push(Rect {
-                                x: area.x + (total_width - remaining_width),
-                                y: area.y,
-                                width: 0,
-                                height: area.height,
-                            });
-                            flex_total += 1; // Count flex items
-                        }
-                        Constraint::Min(_) | Constraint::Max(_) => {
-                            // For now, treat Min/Max as Fixed(0) - will implement in Phase 2
-                            areas.push(Rect {
-                                x: area.x + (total_width - remaining_width),
-                                y: area.y,
-                                width: 0,
-                                height: area.height,
-                            });
-                        }
-                    }
-                }
-                LayoutItem::Nested(_) => {
-                    // For nested layouts, we need to allocate space for the entire nested area
-                    // We'll use a placeholder and let the recursive calculation handle it
-                    areas.push(Rect {
-                        x: area.x + (total_width - remaining_width),
-                        y: area.y,
-                        width: 0, // Will be calculated in second pass
-                        height: area.height,
-                    });
-                    flex_total += 1; // Count nested items as flex for now
-                }
-            }
-        }
-
-        // Second pass: distribute remaining space among flex constraints and nested layouts
-        if flex_total > 0 && remaining_width > 0 {
-            let total_flex_weight: u16 = items
-                .iter()
-                .filter_map(|item| {
-                    match item {
-                        LayoutItem::Constraint(Constraint::Flex(w)) => Some(*w),
-                        LayoutItem::Nested(_) => Some(1), // Default weight for nested layouts
-                        _ => None,
-                    }
-                })
-                .sum();
-
-            if total_flex_weight > 0 {
-                let mut distributed_width = 0u16;
-                for (i, item) in items.iter().enumerate() {
-                    match item {
-                        LayoutItem::Constraint(Constraint::Flex(weight)) => {
-                            let flex_width = (remaining_width * weight / total_flex_weight).max(1);
-                            areas[i].width = flex_width;
-                            distributed_width += flex_width;
-                        }
-                        LayoutItem::Nested(_) => {
-                            let flex_width = (remaining_width / total_flex_weight).max(1);
-                            areas[i].width = flex_width;
-                            distributed_width += flex_width;
-                        }
-                        _ => {} // Already handled in first pass
-                    }
-                }
-
-                // Handle rounding errors by giving any remaining space to the last flex item
-                if distributed_width < remaining_width {
-                    for (i, item) in items.iter().enumerate().rev() {
-                        match item {
-                            LayoutItem::Constraint(Constraint::Flex(_)) | LayoutItem::Nested(_) => {
-                                areas[i].width += remaining_width - distributed_width;
-                                break;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
-
-        // Adjust x positions for proper layout
-        let mut current_x = area.x;
-        for rect in &mut areas {
-            rect.x = current_x;
-            current_x += rect.width;
-        }
-
-        areas
+        let mut x = area.x;
+        Self::allocate(items, area.width)
+            .into_iter()
+            .map(|width| {
+                let rect = Rect { x, width, ..area };
+                x += width;
+                rect
+            })
+            .collect()
     }
 
     fn split_vertical(area: Rect, items: &[LayoutItem]) -> Vec<Rect> {
-        if items.is_empty() {
-            return vec![];
-        }
+        let mut y = area.y;
+        Self::allocate(items, area.height)
+            .into_iter()
+            .map(|height| {
+                let rect = Rect { y, height, ..area };
+                y += height;
+                rect
+            })
+            .collect()
+    }
 
-        let total_height = area.height;
-        let mut areas = Vec::with_capacity(items.len());
-        let mut remaining_height = total_height;
-        let mut flex_total = 0u16;
-
-        // First pass: allocate fixed and percentage constraints
-        for item in items {
-            match item {
-                LayoutItem::Constraint(constraint) => {
-                    match constraint {
-                        Constraint::Fixed(size) => {
-                            let allocated = (*size).min(remaining_height);
-                            areas.push(Rect {
-                                x: area.x,
-                                y: area.y + (total_height - remaining_height),
-                                width: area.width,
-                                height: allocated,
-                            });
-                            remaining_height = remaining_height.saturating_sub(allocated);
-                        }
-                        Constraint::Percentage(pct) => {
-                            let size = (total_height * pct.min(&100) / 100).min(remaining_height);
-                            areas.push(Rect {
-                                x: area.x,
-                                y: area.y + (total_height - remaining_height),
-                                width: area.width,
-                                height: size,
-                            });
-                            remaining_height = remaining_height.saturating_sub(size);
-                        }
-                        Constraint::Flex(_) => {
-                            // Placeholder for flex calculation
-                            areas.push(Rect {
-                                x: area.x,
-                                y: area.y + (total_height - remaining_height),
-                                width: area.width,
-                                height: 0,
-                            });
-                            flex_total += 1; // Count flex items
-                        }
-                        Constraint::Min(_) | Constraint::Max(_) => {
-                            // For now, treat Min/Max as Fixed(0) - will implement in Phase 2
-                            areas.push(Rect {
-                                x: area.x,
-                                y: area.y + (total_height - remaining_height),
-                                width: area.width,
-                                height: 0,
-                            });
-                        }
-                    }
-                }
-                LayoutItem::Nested(_) => {
-                    // For nested layouts, we need to allocate space for the entire nested area
-                    // We'll use a placeholder and let the recursive calculation handle it
-                    areas.push(Rect {
-                        x: area.x,
-                        y: area.y + (total_height - remaining_height),
-                        width: area.width,
-                        height: 0, // Will be calculated in second pass
-                    });
-                    flex_total += 1; // Count nested items as flex for now
+    /// Sizes of `items` along an axis `total` cells long
+    ///
+    /// Fixed and percentage items are sized first, in order, each cut to what
+    /// is left. The rest is shared by weight between flex items, nested
+    /// layouts, and min and max items (weight 1 each). An item whose share
+    /// would fall below its minimum or exceed its maximum is pinned to that
+    /// bound and the others share what remains.
+    pub(crate) fn allocate(items: &[LayoutItem], total: u16) -> Vec<u16> {
+        let mut sizes = vec![0u16; items.len()];
+        let mut remaining = total;
+        for (i, item) in items.iter().enumerate() {
+            let size = match item {
+                LayoutItem::Constraint(Constraint::Fixed(size)) => *size,
+                LayoutItem::Constraint(Constraint::Percentage(pct)) => {
+                    (total as u32 * (*pct).min(100) as u32 / 100) as u16
                 }
-            }
+                _ => continue,
+            };
+            sizes[i] = size.min(remaining);
+            remaining -= sizes[i];
         }
 
-        // Second pass: distribute remaining space among flex constraints and nested layouts
-        if flex_total > 0 && remaining_height > 0 {
-            let total_flex_weight: u16 = items
+        // (index, weight, minimum, maximum) of the items sharing the rest
+        let mut growing: Vec<(usize, u32, u16, u16)> = items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| match item {
+                LayoutItem::Constraint(Constraint::Flex(w)) => Some((i, *w as u32, 0, u16::MAX)),
+                LayoutItem::Constraint(Constraint::Min(min)) => Some((i, 1, *min, u16::MAX)),
+                LayoutItem::Constraint(Constraint::Max(max)) => Some((i, 1, 0, *max)),
+                LayoutItem::Nested(_) => Some((i, 1, 0, u16::MAX)), // Default weight for nested layouts
+                _ => None,
+            })
+            .collect();
+
+        loop {
+            let weights: Vec<(usize, u32)> = growing.iter().map(|&(i, w, _, _)| (i, w)).collect();
+            let shares = Self::distribute(&weights, remaining);
+            // Minimums first, so the space they take is shared out again
+            let violation = shares
                 .iter()
-                .filter_map(|item| {
-                    match item {
-                        LayoutItem::Constraint(Constraint::Flex(w)) => Some(*w),
-                        LayoutItem::Nested(_) => Some(1), // Default weight for nested layouts
-                        _ => None,
-                    }
-                })
-                .sum();
-
-            if total_flex_weight > 0 {
-                let mut distributed_height = 0u16;
-                for (i, item) in items.iter().enumerate() {
-                    match item {
-                        LayoutItem::Constraint(Constraint::Flex(weight)) => {
-                            let flex_height =
-                                (remaining_height * weight / total_flex_weight).max(1);
-                            areas[i].height = flex_height;
-                            distributed_height += flex_height;
-                        }
-                        LayoutItem::Nested(_) => {
-                            let flex_height = (remaining_height / total_flex_weight).max(1);
-                            areas[i].height = flex_height;
-                            distributed_height += flex_height;
-                        }
-                        _ => {} // Already handled in first pass
-                    }
-                }
-
-                // Handle rounding errors by giving any remaining space to the last flex item
-                if distributed_height < remaining_height {
-                    for (i, item) in items.iter().enumerate().rev() {
-                        match item {
-                            LayoutItem::Constraint(Constraint::Flex(_)) | LayoutItem::Nested(_) => {
-                                areas[i].height += remaining_height - distributed_height;
-                                break;
-                            }
-                            _ => {}
-                        }
-                    }
+                .zip(&growing)
+                .position(|(&(_, share), &(_, _, min, _))| share < min)
+                .or_else(|| {
+                    shares
+                        .iter()
+                        .zip(&growing)
+                        .position(|(&(_, share), &(_, _, _, max))| share > max)
+                });
+            let Some(j) = violation else {
+                for (i, share) in shares {
+                    sizes[i] = share;
                 }
-            }
+                return sizes;
+            };
+            let (i, _, min, max) = growing.remove(j);
+            sizes[i] = if shares[j].1 < min {
+                min.min(remaining)
+            } else {
+                max
+            };
+            remaining -= sizes[i];
         }
+    }
 
-        // Adjust y positions for proper layout
-        let mut current_y = area.y;
-        for rect in &mut areas {
-            rect.y = current_y;
-            current_y += rect.height;
+    /// Split `remaining` between `(item index, weight)` pairs by weight
+    ///
+    /// Returns `(item index, size)` pairs. Rounding leftovers are handed out one
+    /// cell at a time starting from the last item, so sizes never differ by
+    /// more than one cell from their exact share.
+    fn distribute(weights: &[(usize, u32)], remaining: u16) -> Vec<(usize, u16)> {
+        let total_weight: u32 = weights.iter().map(|(_, w)| w).sum();
+        let mut sizes: Vec<(usize, u16)> = weights
+            .iter()
+            .map(|(i, w)| {
+                let share = (remaining as u32 * w)
+                    .checked_div(total_weight)
+                    .unwrap_or(0);
+                (*i, share as u16)
+            })
+            .collect();
+
+        // Each share loses less than one cell to rounding, so the leftover is
+        // always smaller than the number of items
+        let distributed: u16 = sizes.iter().map(|(_, size)| size).sum();
+        let leftover = remaining.saturating_sub(distributed) as usize;
+        for (_, size) in sizes.iter_mut().rev().take(leftover) {
+            *size += 1;
         }
 
-        areas
+        sizes
     }
 }
 
@@ -426,6 +290,38 @@ mod tests {
         assert_eq!(areas[3].height, 7); // Disk gets 1/3 of 20 height
     }
 
+    #[test]
+    fn test_flex_leftover_is_spread() {
+        // 11 cells over three items leave two over, one each for the last two
+        let area = Rect::new(0, 0, 12, 5);
+        let layout = Layout::horizontal(vec![
+            LayoutItem::widget(Constraint::Flex(1)),
+            LayoutItem::widget(Constraint::Flex(1)),
+            LayoutItem::widget(Constraint::Flex(1)),
+            LayoutItem::widget(Constraint::Fixed(1)),
+        ]);
+        let widths: Vec<u16> = layout.calculate(area).iter().map(|a| a.width).collect();
+
+        assert_eq!(widths, vec![3, 4, 4, 1]);
+    }
+
+    #[test]
+    fn test_flex_weights_round_down() {
+        let weights = [(0, 3), (2, 1), (3, 1)];
+
+        // 3/5, 1/5 and 1/5 of 12 are 7.2, 2.4 and 2.4
+        assert_eq!(
+            Layout::distribute(&weights, 12),
+            vec![(0, 7), (2, 2), (3, 3)]
+        );
+        // Items too small for a cell get none instead of overflowing
+        assert_eq!(
+            Layout::distribute(&weights, 1),
+            vec![(0, 0), (2, 0), (3, 1)]
+        );
+        assert_eq!(Layout::distribute(&[], 12), vec![]);
+    }
+
     #[test]
     fn test_deeply_nested_layout() {
         let area = Rect::new(0, 0, 100, 20);
@@ -447,4 +343,50 @@ mod tests {
         assert_eq!(areas[2].width, 25); // Third widget gets 25% width (50% of 50%)
         assert_eq!(areas[3].width, 25); // Fourth widget gets 25% width (50% of 50%)
     }
+
+    #[test]
+    fn test_min_and_max() {
+        let area = Rect::new(0, 0, 100, 20);
+        let layout = Layout::horizontal(vec![
+            LayoutItem::widget(Constraint::Max(20)),
+            LayoutItem::widget(Constraint::Flex(1)),
+            LayoutItem::widget(Constraint::Min(50)),
+        ]);
+        let widths: Vec<u16> = layout.calculate(area).iter().map(|r| r.width).collect();
+        // The max item is capped, the min item gets its minimum, the flex
+        // item the rest
+        assert_eq!(widths, [20, 30, 50]);
+
+        // With room to spare, min and max items share it like flex items
+        let layout = Layout::vertical(vec![
+            LayoutItem::widget(Constraint::Min(5)),
+            LayoutItem::widget(Constraint::Max(40)),
+        ]);
+        let areas = layout.calculate(Rect::new(0, 0, 10, 60));
+        assert_eq!((areas[0].height, areas[1].height), (30, 30));
+        assert_eq!(areas[1].y, 30);
+    }
+
+    #[test]
+    fn test_min_cut_to_available_space() {
+        let area = Rect::new(0, 0, 30, 10);
+        let layout = Layout::horizontal(vec![
+            LayoutItem::widget(Constraint::Fixed(10)),
+            LayoutItem::widget(Constraint::Min(40)),
+            LayoutItem::widget(Constraint::Flex(1)),
+        ]);
+        let widths: Vec<u16> = layout.calculate(area).iter().map(|r| r.width).collect();
+        assert_eq!(widths, [10, 20, 0]);
+    }
+
+    #[test]
+    fn test_max_leaves_space_to_others() {
+        let area = Rect::new(0, 0, 100, 10);
+        let layout = Layout::horizontal(vec![
+            LayoutItem::widget(Constraint::Max(10)),
+            LayoutItem::widget(Constraint::Max(10)),
+        ]);
+        let widths: Vec<u16> = layout.calculate(area).iter().map(|r| r.width).collect();
+        assert_eq!(widths, [10, 10]);
+    }
 }
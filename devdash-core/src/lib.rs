@@ -1,15 +1,44 @@
+pub mod collector;
 pub mod config;
+pub mod error;
 pub mod event;
+pub mod fetch;
+pub mod http;
+pub mod keymap;
 pub mod layout;
+pub mod opener;
+pub mod palette;
+pub mod payload;
 pub mod plugin;
+pub mod plugin_lock;
 pub mod registry;
+pub mod state;
+pub mod style;
+pub mod theme;
 pub mod widget;
 
-pub use config::{ConfigError, ConfigFile, flatten_layout_items};
+pub use collector::{Collector, WorkerPool};
+pub use config::{ConfigError, ConfigFile, autostart, flatten_layout_items, set_autostart};
+pub use error::{DevdashError, Result};
 pub use event::{
     Event as BusEvent, EventBus, EventPayload, GitBranchChange, ProcessUpdate, SystemMetrics,
 };
+pub use fetch::{FetchError, HttpClient, RateLimit, Response as FetchResponse};
+pub use http::{
+    HttpServer, Reply as HttpReply, Request as HttpRequest, Response as HttpResponse, WebSocket,
+};
+pub use keymap::{Action, KeyChord, Keymap, KeymapError, read_only, set_read_only};
 pub use layout::{Constraint, Layout, LayoutItem};
+pub use palette::{ACTION_TOPIC, ActionRequest, CommandPalette, PaletteEntry, PaletteResult};
+pub use payload::PayloadRegistry;
 pub use plugin::{PluginError, PluginManager, PluginWidget};
-pub use registry::{WidgetFactory, WidgetRegistry};
-pub use widget::{Event, EventResult, Size, Widget, WidgetContainer};
+pub use registry::{
+    MIN_INTERVAL, WidgetFactory, WidgetRegistry, WidgetSettings, parse_duration, parse_interval,
+    parse_settings,
+};
+pub use state::{SessionState, WidgetState};
+pub use style::{BorderKind, TitleAlignment, WidgetStyle};
+pub use theme::{Theme, no_color, set_no_color, set_theme, strip_styles, theme};
+pub use widget::{
+    DataStatus, Event, EventResult, KeyBinding, Selection, Size, Widget, WidgetContainer,
+};
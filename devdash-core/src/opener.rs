@@ -0,0 +1,206 @@
+// devdash-core/src/opener.rs
+//! Opening directories, URLs and files in programs outside the terminal.
+//!
+//! Widgets call [`open_dir`], [`open_url`], [`edit_file`] and [`run`]; the commands
+//! come from the `[openers]` config section, falling back to the platform's
+//! own opener (`xdg-open`, `open`, or the shell's URL handler on Windows).
+use serde::Deserialize;
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+    sync::RwLock,
+};
+
+/// Commands from the `[openers]` config section
+///
+/// Each is a command line split on whitespace. `{}` is replaced by the
+/// directory, URL or file; without it, that argument is appended. The
+/// editor also understands `{line}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Openers {
+    pub file_manager: Option<String>,
+    pub browser: Option<String>,
+    pub editor: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OpenError {
+    #[error("The {0} command is empty")]
+    EmptyCommand(&'static str),
+    #[error("Failed to run '{command}': {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+static OPENERS: RwLock<Openers> = RwLock::new(Openers {
+    file_manager: None,
+    browser: None,
+    editor: None,
+});
+
+/// Set the commands used process-wide
+pub fn set_openers(openers: Openers) {
+    *OPENERS.write().unwrap_or_else(|e| e.into_inner()) = openers;
+}
+
+fn configured(pick: fn(&Openers) -> &Option<String>) -> Option<String> {
+    pick(&OPENERS.read().unwrap_or_else(|e| e.into_inner())).clone()
+}
+
+/// The platform's opener for files, directories, and URLs
+fn system_opener() -> Vec<String> {
+    let argv: &[&str] = if cfg!(windows) {
+        // Not `cmd /c start`, where `&`, `|` and `^` in a URL are commands
+        &["rundll32", "url.dll,FileProtocolHandler"]
+    } else if cfg!(target_os = "macos") {
+        &["open"]
+    } else {
+        &["xdg-open"]
+    };
+    argv.iter().map(|s| s.to_string()).collect()
+}
+
+/// Fill a command template with `target` and, for the editor, `line`
+///
+/// Both are filled in one pass, so placeholders in `target` stay as they
+/// are.
+fn expand(template: &str, target: &str, line: Option<u32>) -> Vec<String> {
+    let line = line.unwrap_or(1).to_string();
+    let mut has_target = false;
+    let mut argv: Vec<String> = template
+        .split_whitespace()
+        .map(|word| {
+            let mut filled = String::new();
+            let mut rest = word;
+            while let Some(start) = rest.find('{') {
+                filled.push_str(&rest[..start]);
+                rest = &rest[start..];
+                if let Some(after) = rest.strip_prefix("{}") {
+                    has_target = true;
+                    filled.push_str(target);
+                    rest = after;
+                } else if let Some(after) = rest.strip_prefix("{line}") {
+                    filled.push_str(&line);
+                    rest = after;
+                } else {
+                    filled.push('{');
+                    rest = &rest[1..];
+                }
+            }
+            filled.push_str(rest);
+            filled
+        })
+        .collect();
+    if !has_target {
+        argv.push(target.to_string());
+    }
+    argv
+}
+
+/// The command line opening `target`, from a configured template or the
+/// platform's opener
+fn command(
+    template: Option<&str>,
+    kind: &'static str,
+    target: &str,
+    line: Option<u32>,
+) -> Result<Vec<String>, OpenError> {
+    match template {
+        Some(template) if template.trim().is_empty() => Err(OpenError::EmptyCommand(kind)),
+        Some(template) => Ok(expand(template, target, line)),
+        None => {
+            let mut argv = system_opener();
+            argv.push(target.to_string());
+            Ok(argv)
+        }
+    }
+}
+
+/// Start `argv` detached from the terminal
+fn spawn(argv: Vec<String>) -> Result<(), OpenError> {
+    let (program, args) = argv.split_first().expect("commands are never empty");
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|source| OpenError::Spawn {
+            command: argv.join(" "),
+            source,
+        })?;
+    // Reap it so it doesn't linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+fn open_with(
+    pick: fn(&Openers) -> &Option<String>,
+    kind: &'static str,
+    target: &str,
+    line: Option<u32>,
+) -> Result<(), OpenError> {
+    spawn(command(configured(pick).as_deref(), kind, target, line)?)
+}
+
+/// Open a directory in the file manager
+pub fn open_dir(path: &Path) -> Result<(), OpenError> {
+    open_with(
+        |o| &o.file_manager,
+        "file_manager",
+        &path.to_string_lossy(),
+        None,
+    )
+}
+
+/// Open a URL in the browser
+pub fn open_url(url: &str) -> Result<(), OpenError> {
+    open_with(|o| &o.browser, "browser", url, None)
+}
+
+/// Open a file in the editor, at `line` where the editor command uses it
+pub fn edit_file(path: &Path, line: Option<u32>) -> Result<(), OpenError> {
+    open_with(|o| &o.editor, "editor", &path.to_string_lossy(), line)
+}
+
+/// Run a command template from widget settings, with `{}` replaced by
+/// `target`
+pub fn run(template: &str, target: &str) -> Result<(), OpenError> {
+    spawn(command(Some(template), "command", target, None)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand("firefox --new-tab", "https://x", None),
+            ["firefox", "--new-tab", "https://x"]
+        );
+        assert_eq!(
+            expand("code -g {}:{line}", "src/main.rs", Some(12)),
+            ["code", "-g", "src/main.rs:12"]
+        );
+        // A target naming a placeholder is left as it is
+        assert_eq!(
+            expand("vim +{line} {}", "notes/{line}.md", Some(3)),
+            ["vim", "+3", "notes/{line}.md"]
+        );
+        assert_eq!(expand("x {a} {}", "t", None), ["x", "{a}", "t"]);
+    }
+
+    #[test]
+    fn test_command() {
+        assert!(matches!(
+            command(Some(" "), "editor", "a.txt", None),
+            Err(OpenError::EmptyCommand("editor"))
+        ));
+        let argv = command(None, "browser", "https://x", None).unwrap();
+        assert_eq!(argv.last().map(String::as_str), Some("https://x"));
+    }
+}
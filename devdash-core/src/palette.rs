@@ -0,0 +1,387 @@
+// devdash-core/src/palette.rs
+//! Command palette: a fuzzy-searchable list of actions drawn over the
+//! dashboard.
+//!
+//! The palette only picks an action. The dashboard publishes the picked
+//! [`ActionRequest`] on the event bus under [`ACTION_TOPIC`] and carries out
+//! the requests it receives there, so anything else on the bus can request
+//! actions by name the same way.
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use serde::{Deserialize, Serialize};
+
+/// Topic action requests are published under
+pub const ACTION_TOPIC: &str = "action";
+
+/// Most entries listed at once; the list scrolls to follow the selection
+const MAX_ROWS: usize = 12;
+
+const WIDTH: u16 = 64;
+
+/// A named action to carry out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionRequest {
+    /// Widget the action belongs to, by name; `None` for the dashboard's
+    #[serde(default)]
+    pub widget: Option<String>,
+    /// Action name as used in `[keys]`, e.g. `"toggle_view"`
+    pub action: String,
+    /// What the action applies to, e.g. the dashboard to switch to
+    #[serde(default)]
+    pub argument: Option<String>,
+}
+
+impl ActionRequest {
+    /// One of the dashboard's own actions
+    pub fn dashboard(action: impl Into<String>) -> Self {
+        Self {
+            widget: None,
+            action: action.into(),
+            argument: None,
+        }
+    }
+
+    /// An action of the widget named `widget`
+    pub fn widget(widget: impl Into<String>, action: impl Into<String>) -> Self {
+        Self {
+            widget: Some(widget.into()),
+            ..Self::dashboard(action)
+        }
+    }
+
+    pub fn with_argument(mut self, argument: impl Into<String>) -> Self {
+        self.argument = Some(argument.into());
+        self
+    }
+}
+
+/// One line of the palette
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteEntry {
+    /// What is searched and shown, e.g. `"process: Sort by CPU"`
+    pub title: String,
+    /// Keys bound to the action, shown beside it; may be empty
+    pub keys: String,
+    pub request: ActionRequest,
+}
+
+impl PaletteEntry {
+    pub fn new(title: impl Into<String>, keys: impl Into<String>, request: ActionRequest) -> Self {
+        Self {
+            title: title.into(),
+            keys: keys.into(),
+            request,
+        }
+    }
+}
+
+/// What a key press did to the palette
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteResult {
+    /// The palette stays open
+    Open,
+    /// The palette was dismissed
+    Closed,
+    /// An entry was picked; the palette is done
+    Run(ActionRequest),
+}
+
+/// How well `query` matches `text`, or `None` if it doesn't
+///
+/// Every character of the query has to appear in `text` in order, ignoring
+/// case and spaces in the query. Runs of adjacent characters and characters
+/// starting a word score higher, so `"cpu"` prefers "Sort by CPU" to
+/// "Compute usage".
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous = None;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let found =
+            (next..text.len()).find(|&i| text[i].to_lowercase().eq(wanted.to_lowercase()))?;
+        score += 1;
+        if found > 0 && previous == Some(found - 1) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// Fuzzy-searchable list of actions, typed into and drawn over the dashboard
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    entries: Vec<PaletteEntry>,
+    query: String,
+    /// Indexes into `entries` matching the query, best first
+    matches: Vec<usize>,
+    /// Index into `matches`
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new(entries: Vec<PaletteEntry>) -> Self {
+        let mut palette = Self {
+            entries,
+            ..Self::default()
+        };
+        palette.search();
+        palette
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Entries matching the query, best first
+    pub fn matches(&self) -> impl Iterator<Item = &PaletteEntry> {
+        self.matches.iter().map(|&i| &self.entries[i])
+    }
+
+    pub fn selected(&self) -> Option<&PaletteEntry> {
+        self.matches.get(self.selected).map(|&i| &self.entries[i])
+    }
+
+    /// Type into the search, move the selection, pick or dismiss
+    ///
+    /// `Enter` runs the selected entry, `Esc` dismisses, `Up`/`Down` (or
+    /// `Ctrl+P`/`Ctrl+N`) move the selection, and other keys edit the query.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> PaletteResult {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return PaletteResult::Closed,
+            KeyCode::Enter => {
+                if let Some(entry) = self.selected() {
+                    return PaletteResult::Run(entry.request.clone());
+                }
+            }
+            KeyCode::Up => self.move_selection(false),
+            KeyCode::Down => self.move_selection(true),
+            KeyCode::Char('p') if control => self.move_selection(false),
+            KeyCode::Char('n') if control => self.move_selection(true),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.search();
+            }
+            KeyCode::Char(c) if !control && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.query.push(c);
+                self.search();
+            }
+            _ => {}
+        }
+        PaletteResult::Open
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        self.selected = if down {
+            (self.selected + 1).min(self.matches.len().saturating_sub(1))
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+
+    /// Rank the entries against the query, keeping their order among equal
+    /// scores
+    fn search(&mut self) {
+        let mut scored: Vec<(usize, u32)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| Some((i, fuzzy_score(&self.query, &entry.title)?)))
+            .collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        self.matches = scored.into_iter().map(|(i, _)| i).collect();
+        self.selected = 0;
+    }
+}
+
+/// Draws the palette centered near the top of `area`, covering only its
+/// own box
+impl Widget for &CommandPalette {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let rows = self.matches.len().clamp(1, MAX_ROWS) as u16;
+        let width = WIDTH.min(area.width);
+        let height = (rows + 3).min(area.height);
+        let panel = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 4,
+            width,
+            height,
+        );
+        Clear.render(panel, buf);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Commands [Esc to close] ")
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(panel);
+        block.render(panel, buf);
+        if inner.height == 0 {
+            return;
+        }
+
+        let prompt = Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(self.query.as_str()),
+            Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)),
+        ]);
+        Paragraph::new(prompt).render(Rect { height: 1, ..inner }, buf);
+        let list = Rect {
+            y: inner.y + 1,
+            height: inner.height - 1,
+            ..inner
+        };
+
+        if self.matches.is_empty() {
+            Paragraph::new("  No matching commands")
+                .style(Style::default().fg(Color::DarkGray))
+                .render(list, buf);
+            return;
+        }
+        // Scroll just enough to keep the selection in view
+        let visible = list.height as usize;
+        let offset = (self.selected + 1).saturating_sub(visible);
+        let lines: Vec<Line> = self
+            .matches()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(i, entry)| {
+                let keys_width = entry.keys.chars().count();
+                let title_width = (list.width as usize).saturating_sub(keys_width + 3);
+                let title: String = entry.title.chars().take(title_width).collect();
+                let padding =
+                    (list.width as usize).saturating_sub(title.chars().count() + keys_width + 2);
+                let line = Line::from(vec![
+                    Span::raw(format!(" {}{}", title, " ".repeat(padding))),
+                    Span::styled(entry.keys.clone(), Style::default().fg(Color::DarkGray)),
+                    Span::raw(" "),
+                ]);
+                if i == self.selected {
+                    line.style(
+                        Style::default()
+                            .bg(Color::DarkGray)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    line
+                }
+            })
+            .collect();
+        Paragraph::new(lines).render(list, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> CommandPalette {
+        CommandPalette::new(vec![
+            PaletteEntry::new("Show cores", "a", ActionRequest::widget("process", "cores")),
+            PaletteEntry::new(
+                "Reload configuration",
+                "Ctrl+R",
+                ActionRequest::dashboard("reload"),
+            ),
+            PaletteEntry::new(
+                "Sort by CPU",
+                "c",
+                ActionRequest::widget("process", "sort_cpu"),
+            ),
+            PaletteEntry::new(
+                "Switch to dashboard storage",
+                "",
+                ActionRequest::dashboard("switch_dashboard").with_argument("storage"),
+            ),
+        ])
+    }
+
+    fn type_text(palette: &mut CommandPalette, text: &str) {
+        for c in text.chars() {
+            palette.handle_key(&KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    fn titles(palette: &CommandPalette) -> Vec<&str> {
+        palette
+            .matches()
+            .map(|entry| entry.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "Sort by CPU"), None);
+        assert_eq!(fuzzy_score("cpus", "Sort by CPU"), None);
+        assert!(fuzzy_score("cpu", "Sort by CPU") > fuzzy_score("cpu", "Compute usage"));
+        assert!(fuzzy_score("rel", "Reload") > fuzzy_score("rel", "Rotate logs"));
+    }
+
+    #[test]
+    fn test_search_and_pick() {
+        let mut palette = palette();
+        assert_eq!(titles(&palette).len(), 4);
+
+        type_text(&mut palette, "cpu");
+        assert_eq!(titles(&palette)[0], "Sort by CPU");
+        assert_eq!(
+            palette.handle_key(&KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+            PaletteResult::Run(ActionRequest::widget("process", "sort_cpu"))
+        );
+
+        for _ in 0..3 {
+            palette.handle_key(&KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        }
+        type_text(&mut palette, "dash stor");
+        assert_eq!(titles(&palette), ["Switch to dashboard storage"]);
+        let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+        palette.handle_key(&down);
+        assert_eq!(
+            palette.selected().unwrap().request.argument.as_deref(),
+            Some("storage")
+        );
+
+        type_text(&mut palette, "zz");
+        assert_eq!(palette.selected(), None);
+        assert_eq!(
+            palette.handle_key(&KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+            PaletteResult::Open
+        );
+        assert_eq!(
+            palette.handle_key(&KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
+            PaletteResult::Closed
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let mut palette = palette();
+        type_text(&mut palette, "re");
+        let area = Rect::new(0, 0, 40, 10);
+        let mut buf = Buffer::empty(area);
+        (&palette).render(area, &mut buf);
+
+        let lines: Vec<String> = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect();
+        assert_eq!(lines[1], "┌ Commands [Esc to close] ─────────────┐");
+        assert_eq!(lines[2], "│> re                                  │");
+        assert_eq!(lines[3], "│ Reload configuration          Ctrl+R │");
+    }
+}
@@ -0,0 +1,184 @@
+// devdash-core/src/payload.rs
+//! JSON encoding of event bus payloads.
+//!
+//! Bus payloads are type-erased, so integrations that write them out — the
+//! event log, the HTTP API and its WebSocket stream, snapshots, alert rules
+//! — can only serialize types someone registered. Each crate registers its
+//! own payload types with a short type name, under which they can be
+//! decoded again later. devdash-core's types are registered from the start.
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::{
+    any::{Any, TypeId},
+    sync::{OnceLock, RwLock},
+};
+
+use crate::{
+    ActionRequest, BusEvent, EventPayload, GitBranchChange, ProcessUpdate, Selection, SystemMetrics,
+};
+
+/// Conversion functions for one payload type
+#[derive(Clone, Copy)]
+struct PayloadCodec {
+    type_id: TypeId,
+    name: &'static str,
+    encode: fn(&EventPayload) -> Option<Value>,
+    decode: fn(Value) -> Option<EventPayload>,
+}
+
+/// Payload types with a JSON form, by type name
+pub struct PayloadRegistry {
+    codecs: RwLock<Vec<PayloadCodec>>,
+}
+
+impl Default for PayloadRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PayloadRegistry {
+    /// A registry with devdash-core's payload types
+    pub fn new() -> Self {
+        let registry = Self::empty();
+        registry.register::<SystemMetrics>("SystemMetrics");
+        registry.register::<GitBranchChange>("GitBranchChange");
+        registry.register::<ProcessUpdate>("ProcessUpdate");
+        registry.register::<Selection>("Selection");
+        registry.register::<ActionRequest>("ActionRequest");
+        registry.register::<String>("String");
+        registry
+    }
+
+    /// A registry without any payload types
+    pub fn empty() -> Self {
+        Self {
+            codecs: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// The registry shared by the whole process
+    pub fn global() -> &'static Self {
+        static REGISTRY: OnceLock<PayloadRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(Self::new)
+    }
+
+    /// Give payloads of type `T` a JSON form, tagged `name`
+    ///
+    /// Registering a type again renames it, and a name taken by another
+    /// type moves over to `T`.
+    pub fn register<T>(&self, name: &'static str)
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        let codec = PayloadCodec {
+            type_id: TypeId::of::<T>(),
+            name,
+            encode: encode_as::<T>,
+            decode: decode_as::<T>,
+        };
+        let mut codecs = self.codecs.write().unwrap_or_else(|e| e.into_inner());
+        codecs.retain(|c| c.type_id != codec.type_id && c.name != name);
+        codecs.push(codec);
+    }
+
+    /// Whether payloads of type `T` have a JSON form
+    pub fn is_registered<T: Any>(&self) -> bool {
+        self.find(|c| c.type_id == TypeId::of::<T>()).is_some()
+    }
+
+    /// Serialize an event payload, returning its type name and JSON value
+    ///
+    /// Returns `None` for payload types that have no JSON form.
+    pub fn encode(&self, event: &BusEvent) -> Option<(&'static str, Value)> {
+        let codec = self.find(|c| c.type_id == event.payload.type_id())?;
+        (codec.encode)(&event.payload).map(|value| (codec.name, value))
+    }
+
+    /// Deserialize a payload previously produced by [`encode`](Self::encode)
+    ///
+    /// Returns `None` for unknown type names or values that don't match the
+    /// type.
+    pub fn decode(&self, type_name: &str, value: Value) -> Option<EventPayload> {
+        let codec = self.find(|c| c.name == type_name)?;
+        (codec.decode)(value)
+    }
+
+    fn find(&self, matches: impl Fn(&PayloadCodec) -> bool) -> Option<PayloadCodec> {
+        let codecs = self.codecs.read().unwrap_or_else(|e| e.into_inner());
+        codecs.iter().find(|c| matches(c)).copied()
+    }
+}
+
+/// Downcast the payload to `T` and serialize it
+fn encode_as<T: Serialize + Send + Sync + 'static>(payload: &EventPayload) -> Option<Value> {
+    let payload = payload.downcast::<T>()?;
+    serde_json::to_value(&*payload).ok()
+}
+
+fn decode_as<T: DeserializeOwned + Send + Sync + 'static>(value: Value) -> Option<EventPayload> {
+    serde_json::from_value::<T>(value)
+        .ok()
+        .map(EventPayload::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Weather {
+        celsius: f32,
+    }
+
+    #[test]
+    fn test_core_payloads() {
+        let event = BusEvent::new(
+            "system.metrics",
+            SystemMetrics {
+                cpu_usage: 12.5,
+                memory_used: 1,
+                memory_total: 2,
+            },
+        );
+        let (name, value) = PayloadRegistry::new().encode(&event).unwrap();
+        assert_eq!(name, "SystemMetrics");
+        assert_eq!(value["cpu_usage"], 12.5);
+    }
+
+    #[test]
+    fn test_register() {
+        let registry = PayloadRegistry::empty();
+        let event = BusEvent::new("weather", Weather { celsius: 21.5 });
+        assert!(registry.encode(&event).is_none());
+
+        registry.register::<Weather>("Weather");
+        assert!(registry.is_registered::<Weather>());
+        let (name, value) = registry.encode(&event).unwrap();
+        assert_eq!(name, "Weather");
+
+        let payload = registry.decode(name, value).unwrap();
+        assert_eq!(
+            *payload.downcast::<Weather>().unwrap(),
+            Weather { celsius: 21.5 }
+        );
+        assert!(registry.decode("Weather", Value::Null).is_none());
+        assert!(registry.decode("Nope", Value::Null).is_none());
+    }
+
+    #[test]
+    fn test_register_replaces() {
+        let registry = PayloadRegistry::empty();
+        registry.register::<Weather>("Weather");
+        registry.register::<Weather>("Forecast");
+        let event = BusEvent::new("weather", Weather { celsius: 3.0 });
+        assert_eq!(registry.encode(&event).unwrap().0, "Forecast");
+
+        // The name now decodes to the other type
+        registry.register::<String>("Forecast");
+        assert!(!registry.is_registered::<Weather>());
+        let payload = registry.decode("Forecast", Value::from("sunny")).unwrap();
+        assert_eq!(*payload.downcast::<String>().unwrap(), "sunny");
+    }
+}
@@ -1,14 +1,34 @@
-use crate::Widget;
+use crate::{
+    Event, EventResult, Widget, WidgetSettings,
+    plugin_lock::{self, PluginLock, TrustSettings, VerifyError},
+};
 use libloading::{Library, Symbol};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::BitOr;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::time::Duration;
 
+/// Version of the plugin ABI; bump whenever the `Widget` trait's layout changes
+pub const PLUGIN_API_VERSION: u32 = 10;
+
 /// Result type for plugin loading operations
 pub type PluginLoadResult = Result<Vec<(String, PluginWidget)>, PluginError>;
 
+/// Outcome of loading a single plugin library
+pub type PluginProbe = (PathBuf, Result<(String, PluginWidget), PluginError>);
+
+/// Plugins installed for every user of the machine
+#[cfg(unix)]
+pub const SYSTEM_PLUGIN_DIR: &str = "/usr/lib/devdash/plugins";
+
+/// Plugins shipped with a project, relative to where devdash is started;
+/// only loaded once the user trusts the project
+pub const PROJECT_PLUGIN_DIR: &str = ".devdash/plugins";
+
 #[derive(Debug, thiserror::Error)]
 pub enum PluginError {
     #[error("IO error: {0}")]
@@ -23,6 +43,27 @@ pub enum PluginError {
     VersionMismatch { expected: u32, got: u32 },
     #[error("Plugin not found: {0}")]
     PluginNotFound(String),
+    #[error(
+        "Plugin '{name}' was not granted {missing}; allow it when asked, or add it to {}",
+        grants.display()
+    )]
+    PermissionDenied {
+        name: String,
+        missing: String,
+        grants: PathBuf,
+    },
+    #[error("Failed to save plugin permissions: {0}")]
+    Grants(String),
+    #[error("Invalid plugin permissions file {0}")]
+    InvalidGrants(String),
+    #[error("Refusing to load {file}: {source}")]
+    Untrusted {
+        file: String,
+        #[source]
+        source: VerifyError,
+    },
+    #[error("Invalid plugin lockfile {0}")]
+    Lock(String),
 }
 
 #[repr(C)]
@@ -30,6 +71,243 @@ pub struct PluginMetadata {
     pub api_version: u32,
     pub name: *const u8,
     pub name_len: usize,
+    pub capabilities: Capabilities,
+    pub permissions: Permissions,
+    /// Paths the plugin reads or writes, one per line
+    pub paths: *const u8,
+    pub paths_len: usize,
+}
+
+/// What a plugin handles or needs, so the host can adapt to it
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities(pub u32);
+
+impl Capabilities {
+    pub const NONE: Self = Self(0);
+    /// Handles mouse events; other plugins are not sent any
+    pub const MOUSE: Self = Self(1);
+    /// Accepts its `[[dashboard.widgets]]` settings, sent before mounting
+    /// as a [`SETTINGS_EVENT`]
+    pub const CONFIG: Self = Self(1 << 1);
+    /// Implements `save_state`/`restore_state`; other plugins' are not called
+    pub const STATE: Self = Self(1 << 2);
+
+    const NAMES: [(Self, &'static str); 3] = [
+        (Self::MOUSE, "mouse"),
+        (Self::CONFIG, "config"),
+        (Self::STATE, "state"),
+    ];
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = Self::NAMES
+            .iter()
+            .filter(|(capability, _)| self.contains(*capability))
+            .map(|(_, name)| *name)
+            .collect();
+        if names.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", names.join(", "))
+        }
+    }
+}
+
+/// What a plugin may do beyond drawing itself, declared in its metadata
+///
+/// Plugins run inside devdash, so these are a promise rather than a
+/// sandbox: a plugin's widget is only created once the user has granted
+/// everything it declares, along with the paths it reads or writes.
+///
+/// They are advisory only. Plugins make requests, run programs and open
+/// files with whatever code they link, not through devdash, so nothing
+/// checks what a loaded plugin does against what it was granted.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Permissions(pub u32);
+
+impl Permissions {
+    pub const NONE: Self = Self(0);
+    /// Makes network requests
+    pub const NETWORK: Self = Self(1);
+    /// Runs other programs
+    pub const EXEC: Self = Self(1 << 1);
+
+    const NAMES: [(Self, &'static str); 2] = [(Self::NETWORK, "network"), (Self::EXEC, "exec")];
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The permissions called `names`; unknown names are ignored
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Self {
+        Self::NAMES
+            .iter()
+            .filter(|(_, name)| names.iter().any(|n| n.as_ref() == *name))
+            .fold(Self::NONE, |all, (permission, _)| all | *permission)
+    }
+
+    /// Names of the permissions in `self`
+    pub fn names(self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .filter(|(permission, _)| self.contains(*permission))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl BitOr for Permissions {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// What a plugin declares it needs: [`Permissions`] and the paths it reads
+/// or writes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Grant {
+    pub permissions: Vec<String>,
+    pub paths: Vec<String>,
+}
+
+impl Grant {
+    pub fn new(permissions: Permissions, paths: &[String]) -> Self {
+        Self {
+            permissions: permissions.names().into_iter().map(String::from).collect(),
+            paths: paths.to_vec(),
+        }
+    }
+
+    /// Whether nothing beyond drawing is asked for
+    pub fn is_empty(&self) -> bool {
+        self.permissions.is_empty() && self.paths.is_empty()
+    }
+
+    /// What `self` asks for that `granted` doesn't allow
+    pub fn missing(&self, granted: &Grant) -> Grant {
+        let allowed = Permissions::from_names(&granted.permissions);
+        Grant {
+            permissions: self
+                .permissions
+                .iter()
+                .filter(|p| !allowed.contains(Permissions::from_names(&[p])))
+                .cloned()
+                .collect(),
+            paths: self
+                .paths
+                .iter()
+                .filter(|p| !granted.paths.contains(p))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Everything `self` or `other` allows
+    fn merge(mut self, other: Grant) -> Grant {
+        for permission in other.permissions {
+            if !self.permissions.contains(&permission) {
+                self.permissions.push(permission);
+            }
+        }
+        for path in other.paths {
+            if !self.paths.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for Grant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items: Vec<String> = self
+            .permissions
+            .iter()
+            .cloned()
+            .chain(self.paths.iter().map(|path| format!("files in {}", path)))
+            .collect();
+        if items.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", items.join(", "))
+        }
+    }
+}
+
+/// Name of the file in the plugin directory recording what each library was
+/// granted, as `["<file name>"]` tables
+pub const GRANTS_FILE: &str = "permissions.toml";
+
+/// What one library was granted, as recorded in [`GRANTS_FILE`]
+///
+/// Grants are keyed on the library's file name rather than the name the
+/// plugin gives itself, and hold only for the build they were given to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct GrantRecord {
+    /// Checksum of the library when it was granted
+    sha256: String,
+    #[serde(flatten)]
+    grant: Grant,
+}
+
+/// A plugin asking for permissions on its first load
+#[derive(Debug, Clone, Copy)]
+pub struct PermissionRequest<'a> {
+    pub name: &'a str,
+    pub library: &'a Path,
+    /// What it asks for beyond what was granted before
+    pub missing: &'a Grant,
+}
+
+/// Asks the user whether to grant a plugin's permissions
+pub type PermissionPrompt = Box<dyn FnMut(&PermissionRequest) -> bool + Send>;
+
+/// Name of the file in the user's plugin directory listing the projects
+/// whose plugins may load, as a `trusted` list of directories
+pub const TRUSTED_PROJECTS_FILE: &str = "trusted-projects.toml";
+
+/// Contents of [`TRUSTED_PROJECTS_FILE`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TrustedProjects {
+    trusted: Vec<PathBuf>,
+}
+
+/// Asks the user whether to load the plugins in a project's plugin
+/// directory
+pub type ProjectPrompt = Box<dyn FnMut(&Path) -> bool + Send>;
+
+/// Topic of the [`Event::Custom`] carrying settings to plugins with
+/// [`Capabilities::CONFIG`]; the payload is the settings as TOML
+pub const SETTINGS_EVENT: &str = "devdash.settings";
+
+/// The settings carried by a [`SETTINGS_EVENT`]
+pub fn settings_from_event(event: &Event) -> Option<WidgetSettings> {
+    match event {
+        Event::Custom(topic, payload) if topic == SETTINGS_EVENT => {
+            toml::from_str(std::str::from_utf8(payload).ok()?).ok()
+        }
+        _ => None,
+    }
 }
 
 /// FFI-safe representation of a fat pointer (trait object)
@@ -47,6 +325,8 @@ pub struct PluginWidget {
     // Store components for destroy call
     fat_ptr: FatPointer,
     destroy: extern "C" fn(FatPointer),
+    capabilities: Capabilities,
+    permissions: Grant,
     // Keep library alive for as long as the widget exists
     _lib: Library,
 }
@@ -58,7 +338,13 @@ unsafe impl Send for PluginWidget {}
 unsafe impl Sync for PluginWidget {}
 
 impl PluginWidget {
-    unsafe fn new(fat_ptr: FatPointer, destroy: extern "C" fn(FatPointer), lib: Library) -> Self {
+    unsafe fn new(
+        fat_ptr: FatPointer,
+        destroy: extern "C" fn(FatPointer),
+        capabilities: Capabilities,
+        permissions: Grant,
+        lib: Library,
+    ) -> Self {
         // Reconstruct the fat pointer from components
         let ptr: *mut dyn Widget = unsafe { std::mem::transmute([fat_ptr.data, fat_ptr.vtable]) };
 
@@ -66,10 +352,31 @@ impl PluginWidget {
             ptr,
             fat_ptr,
             destroy,
+            capabilities,
+            permissions,
             _lib: lib,
         }
     }
 
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// What the plugin declared it needs, all of it granted
+    pub fn permissions(&self) -> &Grant {
+        &self.permissions
+    }
+
+    /// Send `settings` to a plugin that accepts them
+    pub fn configure(&mut self, settings: &WidgetSettings) {
+        if !self.capabilities.contains(Capabilities::CONFIG) {
+            return;
+        }
+        let payload = toml::to_string(settings).unwrap_or_default().into_bytes();
+        self.as_widget()
+            .on_event(Event::Custom(SETTINGS_EVENT.to_string(), payload));
+    }
+
     fn as_widget(&mut self) -> &mut dyn Widget {
         // Safety: The pointer is valid and the library keeps the code alive
         unsafe { &mut *self.ptr }
@@ -101,7 +408,10 @@ impl Widget for PluginWidget {
         self.as_widget().on_update(delta)
     }
 
-    fn on_event(&mut self, event: crate::Event) -> crate::EventResult {
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if matches!(event, Event::Mouse(_)) && !self.capabilities.contains(Capabilities::MOUSE) {
+            return EventResult::Ignored;
+        }
         self.as_widget().on_event(event)
     }
 
@@ -126,6 +436,47 @@ impl Widget for PluginWidget {
         self.as_widget_const().needs_update()
     }
 
+    fn update_interval(&self) -> Duration {
+        self.as_widget_const().update_interval()
+    }
+
+    fn actions(&self) -> Vec<crate::Action> {
+        self.as_widget_const().actions()
+    }
+
+    fn key_bindings(&self) -> Vec<crate::KeyBinding> {
+        self.as_widget_const().key_bindings()
+    }
+
+    fn summary(&self) -> Option<String> {
+        self.as_widget_const().summary()
+    }
+
+    fn selection(&self) -> Option<crate::Selection> {
+        self.as_widget_const().selection()
+    }
+
+    fn save_state(&self) -> Option<crate::WidgetState> {
+        if !self.capabilities.contains(Capabilities::STATE) {
+            return None;
+        }
+        self.as_widget_const().save_state()
+    }
+
+    fn restore_state(&mut self, state: &crate::WidgetState) {
+        if self.capabilities.contains(Capabilities::STATE) {
+            self.as_widget().restore_state(state)
+        }
+    }
+
+    fn on_suspend(&mut self) {
+        self.as_widget().on_suspend()
+    }
+
+    fn on_resume(&mut self) {
+        self.as_widget().on_resume()
+    }
+
     fn on_unmount(&mut self) {
         self.as_widget().on_unmount()
     }
@@ -133,7 +484,25 @@ impl Widget for PluginWidget {
 
 pub struct PluginManager {
     plugins: HashMap<String, LoadedPlugin>,
+    /// Settings for plugins with [`Capabilities::CONFIG`], by widget name
+    settings: HashMap<String, WidgetSettings>,
+    /// Whether live data collection is off (`--no-live`)
+    offline: bool,
+    /// Asks about permissions not granted yet; without one they are denied
+    prompt: Option<PermissionPrompt>,
+    /// Asks whether to trust a project's plugins; without one they are
+    /// skipped
+    project_prompt: Option<ProjectPrompt>,
+    /// How libraries are checked against the lockfile and signatures
+    trust: TrustSettings,
+    /// The user's plugin directory, where plugins are installed and their
+    /// permissions recorded
     plugin_dir: PathBuf,
+    /// Directories libraries are loaded from, in order; a library in a
+    /// later one replaces one of the same file name in an earlier one
+    layers: Vec<PathBuf>,
+    /// The project's plugin directory, added to `layers` once trusted
+    project_dir: Option<PathBuf>,
     temp_dir: PathBuf,
     watcher: RecommendedWatcher,
     rx: mpsc::Receiver<notify::Result<notify::Event>>,
@@ -154,6 +523,20 @@ impl PluginManager {
         let plugin_dir = dirs::home_dir()
             .map(|h| h.join(".devdash/plugins"))
             .unwrap_or_else(|| PathBuf::from("./plugins"));
+        let project_dir = std::env::current_dir()
+            .map(|dir| dir.join(PROJECT_PLUGIN_DIR))
+            .unwrap_or_else(|_| PathBuf::from(PROJECT_PLUGIN_DIR));
+        #[cfg(unix)]
+        let system_dirs = vec![PathBuf::from(SYSTEM_PLUGIN_DIR)];
+        #[cfg(not(unix))]
+        let system_dirs = Vec::new();
+        let mut layers = system_dirs;
+        if !layers.contains(&plugin_dir) {
+            layers.push(plugin_dir.clone());
+        }
+        // Started from the home directory, the project and user directory
+        // are the same
+        let project_dir = (!layers.contains(&project_dir)).then_some(project_dir);
 
         // Create temp directory for plugin copies (Windows file locking workaround)
         let temp_dir = std::env::temp_dir().join("devdash_plugins");
@@ -174,7 +557,14 @@ impl PluginManager {
 
         Self {
             plugins: HashMap::new(),
+            settings: HashMap::new(),
+            offline: false,
+            prompt: None,
+            project_prompt: None,
+            trust: TrustSettings::default(),
             plugin_dir,
+            layers,
+            project_dir,
             temp_dir,
             watcher,
             rx,
@@ -184,36 +574,325 @@ impl PluginManager {
     pub fn load_all(&mut self) -> PluginLoadResult {
         let mut widgets = Vec::new();
 
-        if !self.plugin_dir.exists() {
-            return Ok(widgets);
+        for (path, result) in self.load_each()? {
+            match result {
+                Ok((name, widget)) => widgets.push((name, widget)),
+                Err(e) => eprintln!("Warning: Failed to load plugin {:?}: {}", path, e),
+            }
+        }
+        if let Some(dir) = self.untrusted_project() {
+            eprintln!(
+                "Warning: skipping plugins in {}; the project isn't trusted",
+                dir.display()
+            );
         }
 
-        for entry in std::fs::read_dir(&self.plugin_dir)? {
-            let path = entry?.path();
+        Ok(widgets)
+    }
+
+    /// Load every library in the plugin directories, reporting each result
+    pub fn load_each(&mut self) -> Result<Vec<PluginProbe>, PluginError> {
+        self.trust_project()?;
+        let mut results = Vec::new();
+        for path in self.libraries()? {
+            let result = unsafe { self.load_plugin(&path) };
+            results.push((path, result));
+        }
+        Ok(results)
+    }
 
-            if path.extension().and_then(|s| s.to_str()) == Some(dll_extension()) {
-                match unsafe { self.load_plugin(&path) } {
-                    Ok((name, widget)) => widgets.push((name, widget)),
-                    Err(e) => eprintln!("Warning: Failed to load plugin {:?}: {}", path, e),
+    /// The libraries to load, by file name; those in later directories
+    /// replace those in earlier ones
+    pub fn libraries(&self) -> Result<Vec<PathBuf>, PluginError> {
+        let mut libraries = BTreeMap::new();
+        for dir in &self.layers {
+            for path in libraries_in(dir)? {
+                if let Some(name) = path.file_name() {
+                    libraries.insert(name.to_owned(), path);
                 }
             }
         }
+        Ok(libraries.into_values().collect())
+    }
 
-        Ok(widgets)
+    /// Ask with `prompt` before loading plugins from a project directory
+    /// that isn't trusted yet, and remember the answer if it is yes; `None`
+    /// skips such directories
+    pub fn set_project_prompt(&mut self, prompt: Option<ProjectPrompt>) {
+        self.project_prompt = prompt;
+    }
+
+    /// The project's plugin directory, if it has libraries that are skipped
+    /// because the project isn't trusted
+    pub fn untrusted_project(&self) -> Option<&Path> {
+        self.project_dir
+            .as_deref()
+            .filter(|dir| !self.layers.iter().any(|layer| layer == dir))
+            .filter(|dir| libraries_in(dir).is_ok_and(|libraries| !libraries.is_empty()))
+    }
+
+    /// File listing the projects whose plugins may load
+    pub fn trusted_projects_path(&self) -> PathBuf {
+        self.plugin_dir.join(TRUSTED_PROJECTS_FILE)
+    }
+
+    fn read_trusted_projects(&self) -> TrustedProjects {
+        std::fs::read_to_string(self.trusted_projects_path())
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether the user trusts the project at `project`, which lets its
+    /// plugins load and its config start commands
+    pub fn is_trusted(&self, project: &Path) -> bool {
+        project
+            .canonicalize()
+            .is_ok_and(|project| self.read_trusted_projects().trusted.contains(&project))
+    }
+
+    /// Remember that the user trusts the project at `project`
+    pub fn add_trusted(&self, project: &Path) -> Result<(), PluginError> {
+        let project = project.canonicalize()?;
+        let mut trusted = self.read_trusted_projects();
+        if !trusted.trusted.contains(&project) {
+            trusted.trusted.push(project);
+            let content = toml::to_string(&trusted).map_err(std::io::Error::other)?;
+            std::fs::create_dir_all(&self.plugin_dir)?;
+            std::fs::write(self.trusted_projects_path(), content)?;
+        }
+        Ok(())
+    }
+
+    /// Add the project's plugin directory to those loaded from if the user
+    /// trusted it before, or does when asked
+    fn trust_project(&mut self) -> Result<(), PluginError> {
+        let Some(dir) = self.untrusted_project().map(Path::to_path_buf) else {
+            return Ok(());
+        };
+        // Recorded as the project itself, wherever devdash is started in it
+        let project = dir
+            .canonicalize()?
+            .ancestors()
+            .nth(2)
+            .map_or_else(|| dir.clone(), Path::to_path_buf);
+        if !self.is_trusted(&project) {
+            if !self
+                .project_prompt
+                .as_mut()
+                .is_some_and(|prompt| prompt(&project))
+            {
+                return Ok(());
+            }
+            self.add_trusted(&project)?;
+        }
+        self.layers.push(dir);
+        Ok(())
+    }
+
+    /// Whether a library of the same file name in a later directory
+    /// replaces the one at `path`
+    fn is_shadowed(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name() else {
+            return false;
+        };
+        let dir = path.parent().and_then(|dir| dir.canonicalize().ok());
+        self.layers
+            .iter()
+            .rposition(|layer| layer.canonicalize().ok() == dir)
+            .is_some_and(|i| {
+                self.layers[i + 1..]
+                    .iter()
+                    .any(|layer| layer.join(name).exists())
+            })
+    }
+
+    /// Settings to send to plugins that accept them as they load
+    pub fn set_settings(&mut self, settings: HashMap<String, WidgetSettings>) {
+        self.settings = settings;
+    }
+
+    /// Warn when plugins needing the network load while running offline
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Ask with `prompt` before loading a plugin that declares permissions
+    /// it wasn't granted yet, and remember the answer if it is yes; `None`
+    /// refuses such plugins, e.g. while the dashboard is on screen
+    pub fn set_permission_prompt(&mut self, prompt: Option<PermissionPrompt>) {
+        self.prompt = prompt;
+    }
+
+    /// Check libraries as the config's `[plugins]` section says
+    pub fn set_trust(&mut self, trust: TrustSettings) {
+        self.trust = trust;
+    }
+
+    /// Check the copy of `library` about to be loaded, with contents
+    /// `bytes`, against the lockfile and the library's signature
+    fn verify(&self, library: &Path, bytes: &[u8]) -> Result<(), PluginError> {
+        let file = library
+            .file_name()
+            .map_or_else(String::new, |f| f.to_string_lossy().into_owned());
+        // Each directory has its own lockfile, so a project can ship one
+        let dir = library.parent().unwrap_or(&self.plugin_dir);
+        let lock = PluginLock::load(dir).map_err(PluginError::Lock)?;
+        let signature = std::fs::read_to_string(plugin_lock::signature_path(library)).ok();
+        lock.verify(&file, bytes, signature.as_deref(), &self.trust)
+            .map_err(|source| PluginError::Untrusted { file, source })
+    }
+
+    /// Copy the library at `source` into the plugin directory, with its
+    /// signature if it has one, and record its checksum in the lockfile
+    ///
+    /// The signature is checked first if a public key is configured, and
+    /// required in strict mode. Returns where the library was installed.
+    pub fn install(&mut self, source: &Path) -> Result<PathBuf, PluginError> {
+        let file_name = source.file_name().ok_or_else(|| {
+            PluginError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid plugin path",
+            ))
+        })?;
+        let file = file_name.to_string_lossy().into_owned();
+        if source.extension().and_then(|s| s.to_str()) != Some(dll_extension()) {
+            return Err(PluginError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is not a .{} library", file, dll_extension()),
+            )));
+        }
+        let bytes = std::fs::read(source)?;
+        let signature_source = plugin_lock::signature_path(source);
+        let signature = std::fs::read_to_string(&signature_source).ok();
+        plugin_lock::check_signature(&bytes, signature.as_deref(), &self.trust).map_err(
+            |source| PluginError::Untrusted {
+                file: file.clone(),
+                source,
+            },
+        )?;
+        let mut lock = PluginLock::load(&self.plugin_dir).map_err(PluginError::Lock)?;
+
+        std::fs::create_dir_all(&self.plugin_dir)?;
+        let target = self.plugin_dir.join(file_name);
+        // Installing a library already in the directory only records it
+        if !target.exists() || !same_file(source, &target)? {
+            std::fs::write(&target, &bytes)?;
+            if let Some(signature) = &signature {
+                std::fs::write(plugin_lock::signature_path(&target), signature)?;
+            }
+        }
+        lock.record(&file, &bytes);
+        lock.save(&self.plugin_dir)?;
+        Ok(target)
+    }
+
+    /// File recording the permissions granted to plugins
+    pub fn grants_path(&self) -> PathBuf {
+        self.plugin_dir.join(GRANTS_FILE)
+    }
+
+    /// The grants recorded so far; none before the file is first written
+    fn read_grants(&self) -> Result<BTreeMap<String, GrantRecord>, PluginError> {
+        let path = self.grants_path();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        toml::from_str(&content)
+            .map_err(|e| PluginError::InvalidGrants(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Make sure the plugin `name`, loaded from `library` with checksum
+    /// `sha256`, was granted `wanted`, asking for what is missing
+    ///
+    /// What another file name or another build of the library was granted
+    /// doesn't count, so replacing a library asks again.
+    fn check_permissions(
+        &mut self,
+        name: &str,
+        library: &Path,
+        sha256: &str,
+        wanted: &Grant,
+    ) -> Result<(), PluginError> {
+        if wanted.is_empty() {
+            return Ok(());
+        }
+        let file = library
+            .file_name()
+            .map_or_else(String::new, |f| f.to_string_lossy().into_owned());
+        let mut grants = self.read_grants()?;
+        let granted = grants
+            .get(&file)
+            .filter(|record| record.sha256.eq_ignore_ascii_case(sha256))
+            .map(|record| record.grant.clone())
+            .unwrap_or_default();
+        let missing = wanted.missing(&granted);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let request = PermissionRequest {
+            name,
+            library,
+            missing: &missing,
+        };
+        if !self.prompt.as_mut().is_some_and(|prompt| prompt(&request)) {
+            return Err(PluginError::PermissionDenied {
+                name: name.to_string(),
+                missing: missing.to_string(),
+                grants: self.grants_path(),
+            });
+        }
+        grants.insert(
+            file,
+            GrantRecord {
+                sha256: sha256.to_string(),
+                grant: granted.merge(missing),
+            },
+        );
+        let content = toml::to_string(&grants).map_err(|e| PluginError::Grants(e.to_string()))?;
+        std::fs::create_dir_all(&self.plugin_dir)
+            .and_then(|()| std::fs::write(self.grants_path(), content))
+            .map_err(|e| PluginError::Grants(e.to_string()))
+    }
+
+    /// Load plugins from `dir` only, and install them there, instead of the
+    /// system, user and project directories
+    pub fn set_plugin_dir(&mut self, dir: PathBuf) {
+        self.layers = vec![dir.clone()];
+        self.project_dir = None;
+        self.plugin_dir = dir;
+    }
+
+    /// Directory plugins are installed in and their permissions recorded
+    pub fn plugin_dir(&self) -> &Path {
+        &self.plugin_dir
+    }
+
+    /// Directories plugins are loaded from, later ones taking precedence;
+    /// the project's only once it is trusted
+    pub fn plugin_dirs(&self) -> &[PathBuf] {
+        &self.layers
     }
 
     pub fn watch(&mut self) -> Result<(), PluginError> {
-        if self.plugin_dir.exists() {
-            self.watcher
-                .watch(&self.plugin_dir, RecursiveMode::NonRecursive)?;
+        for dir in &self.layers {
+            if dir.exists() {
+                self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            }
         }
         Ok(())
     }
 
+    /// Reload plugins whose library changed, returning each one tried by
+    /// name with how it went; a plugin that fails to reload keeps running
+    /// its old build
     pub fn check_for_changes(
         &mut self,
         widgets: &mut Vec<crate::WidgetContainer>,
-    ) -> Result<(), PluginError> {
+    ) -> Vec<(String, Result<(), PluginError>)> {
+        let mut reloads = Vec::new();
         while let Ok(event) = self.rx.try_recv() {
             if let Ok(event) = event
                 && (event.kind.is_modify() || event.kind.is_create())
@@ -223,21 +902,24 @@ impl PluginManager {
                         .extension()
                         .map(|s| s == dll_extension())
                         .unwrap_or(false)
+                        && !self.is_shadowed(&path)
                     {
                         let plugin_name = extract_plugin_name(&path);
-                        if let Err(e) = self.reload_plugin(&path, &plugin_name, widgets) {
-                            eprintln!("Failed to reload plugin {}: {}", plugin_name, e);
-                        }
+                        let result = self.reload_plugin(&path, &plugin_name, widgets);
+                        reloads.push((plugin_name, result));
                     }
                 }
             }
         }
-        Ok(())
+        reloads
     }
 
     unsafe fn load_plugin(&mut self, path: &Path) -> Result<(String, PluginWidget), PluginError> {
         // FIX: Use temp copy to avoid Windows file locking
         let temp_path = self.copy_to_temp(path)?;
+        // The copy is checked, so the library can't change after the check
+        let bytes = std::fs::read(&temp_path)?;
+        self.verify(path, &bytes)?;
         let lib = unsafe { Library::new(&temp_path)? };
 
         // Check API version
@@ -245,9 +927,9 @@ impl PluginManager {
             unsafe { lib.get(b"devdash_plugin_metadata")? };
         let metadata = metadata_fn();
 
-        if metadata.api_version != 1 {
+        if metadata.api_version != PLUGIN_API_VERSION {
             return Err(PluginError::VersionMismatch {
-                expected: 1,
+                expected: PLUGIN_API_VERSION,
                 got: metadata.api_version,
             });
         }
@@ -258,13 +940,27 @@ impl PluginManager {
         let destroy_fn: Symbol<extern "C" fn(FatPointer)> =
             unsafe { lib.get(b"devdash_plugin_destroy")? };
 
-        let fat_ptr = create_fn();
-        let destroy = *destroy_fn;
-
         let name = std::str::from_utf8(unsafe {
             std::slice::from_raw_parts(metadata.name, metadata.name_len)
         })?
         .to_string();
+        let paths: Vec<String> = std::str::from_utf8(unsafe {
+            std::slice::from_raw_parts(metadata.paths, metadata.paths_len)
+        })?
+        .lines()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(String::from)
+        .collect();
+        let permissions = Grant::new(metadata.permissions, &paths);
+        // Opening the library has already run its initializers and the
+        // metadata function, so this only keeps an ungranted plugin's widget
+        // from being created; keeping its code from running at all is up to
+        // the lockfile and signature checks above
+        self.check_permissions(&name, path, &plugin_lock::sha256(&bytes), &permissions)?;
+
+        let fat_ptr = create_fn();
+        let destroy = *destroy_fn;
 
         self.plugins.insert(
             name.clone(),
@@ -273,7 +969,18 @@ impl PluginManager {
             },
         );
 
-        let plugin_widget = unsafe { PluginWidget::new(fat_ptr, destroy, lib) };
+        let capabilities = metadata.capabilities;
+        let mut plugin_widget =
+            unsafe { PluginWidget::new(fat_ptr, destroy, capabilities, permissions, lib) };
+        if let Some(settings) = self.settings.get(&name) {
+            plugin_widget.configure(settings);
+        }
+        if self.offline && metadata.permissions.contains(Permissions::NETWORK) {
+            eprintln!(
+                "Warning: plugin '{}' needs network access, but devdash is running offline",
+                name
+            );
+        }
 
         Ok((name, plugin_widget))
     }
@@ -308,43 +1015,29 @@ impl PluginManager {
         plugin_name: &str,
         widgets: &mut Vec<crate::WidgetContainer>,
     ) -> Result<(), PluginError> {
-        // Find widget in container list by name
-        let widget_idx = widgets.iter().position(|w| w.name() == plugin_name);
-
-        // FIX: Unmount and drop old widget BEFORE unloading library
-        if let Some(idx) = widget_idx {
-            let mut old_widget = std::mem::replace(
-                &mut widgets[idx],
-                crate::WidgetContainer::new(
-                    "placeholder".to_string(),
-                    Box::new(crate::widget::CpuWidget::new(Duration::from_secs(1))),
-                ),
-            );
-            old_widget.unmount();
-            // old_widget is dropped here, which calls PluginWidget::drop
-        }
-
-        // NOW remove plugin from HashMap (this drops Library)
-        self.plugins.remove(plugin_name);
-
-        // Small delay to ensure library is fully unloaded (especially on Windows)
-        std::thread::sleep(Duration::from_millis(100));
-
-        // Load new plugin
+        // The new build is loaded from its own temp copy next to the old
+        // one, so the old widget is only replaced once it has loaded
         let (name, widget) = unsafe { self.load_plugin(path) }?;
-
         let new_container = crate::WidgetContainer::new(name.clone(), Box::new(widget));
 
-        // Replace or add widget
-        if let Some(idx) = widget_idx {
-            widgets[idx] = new_container;
-        } else {
-            widgets.push(new_container);
+        // Unmount and drop the old widget, unloading its library, before
+        // the new one is mounted
+        match widgets.iter().position(|w| w.name() == plugin_name) {
+            Some(idx) => {
+                let mut old_widget = std::mem::replace(&mut widgets[idx], new_container);
+                old_widget.unmount();
+                drop(old_widget);
+                widgets[idx].mount();
+            }
+            None => {
+                widgets.push(new_container);
+                if let Some(widget) = widgets.last_mut() {
+                    widget.mount();
+                }
+            }
         }
-
-        // Mount the new widget
-        if let Some(idx) = widgets.iter().position(|w| w.name() == name) {
-            widgets[idx].mount();
+        if name != plugin_name {
+            self.plugins.remove(plugin_name);
         }
 
         Ok(())
@@ -354,7 +1047,9 @@ impl PluginManager {
 impl Drop for PluginManager {
     fn drop(&mut self) {
         // Explicitly stop watching before dropping
-        let _ = self.watcher.unwatch(&self.plugin_dir);
+        for dir in &self.layers {
+            let _ = self.watcher.unwatch(dir);
+        }
 
         // Clear plugins to ensure libraries are unloaded
         self.plugins.clear();
@@ -364,6 +1059,25 @@ impl Drop for PluginManager {
     }
 }
 
+fn same_file(a: &Path, b: &Path) -> std::io::Result<bool> {
+    Ok(a.canonicalize()? == b.canonicalize()?)
+}
+
+/// The plugin libraries in `dir`; none if it doesn't exist
+fn libraries_in(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut libraries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) == Some(dll_extension()) {
+            libraries.push(path);
+        }
+    }
+    Ok(libraries)
+}
+
 fn extract_plugin_name(path: &Path) -> String {
     path.file_stem()
         .and_then(|s| s.to_str())
@@ -381,3 +1095,291 @@ fn dll_extension() -> &'static str {
     #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     return "so";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = Capabilities::MOUSE | Capabilities::STATE;
+        assert!(capabilities.contains(Capabilities::MOUSE));
+        assert!(!capabilities.contains(Capabilities::CONFIG));
+        assert_eq!(capabilities.to_string(), "mouse, state");
+        assert_eq!(Capabilities::NONE.to_string(), "none");
+    }
+
+    fn grant(permissions: Permissions, paths: &[&str]) -> Grant {
+        let paths: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+        Grant::new(permissions, &paths)
+    }
+
+    #[test]
+    fn test_grant_missing() {
+        let wanted = grant(Permissions::NETWORK | Permissions::EXEC, &["/var/log"]);
+        assert_eq!(wanted.to_string(), "network, exec, files in /var/log");
+        let granted = grant(Permissions::NETWORK, &["/tmp"]);
+        assert_eq!(
+            wanted.missing(&granted),
+            grant(Permissions::EXEC, &["/var/log"])
+        );
+        assert!(wanted.missing(&wanted).is_empty());
+        assert_eq!(Grant::default().to_string(), "none");
+    }
+
+    #[test]
+    fn test_permissions_are_asked_once() {
+        let dir = std::env::temp_dir().join(format!("devdash-grants-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut manager = PluginManager::new();
+        manager.set_plugin_dir(dir.clone());
+        let library = dir.join("libweather.so");
+        let build = plugin_lock::sha256(b"weather");
+        let wanted = grant(Permissions::NETWORK, &[]);
+
+        // Nothing asked for, nothing to grant
+        assert!(
+            manager
+                .check_permissions("clock", &library, &build, &Grant::default())
+                .is_ok()
+        );
+        assert!(matches!(
+            manager.check_permissions("weather", &library, &build, &wanted),
+            Err(PluginError::PermissionDenied { .. })
+        ));
+
+        let asked = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = asked.clone();
+        manager.set_permission_prompt(Some(Box::new(move |request| {
+            log.lock().unwrap().push(request.missing.to_string());
+            true
+        })));
+        manager
+            .check_permissions("weather", &library, &build, &wanted)
+            .unwrap();
+        manager
+            .check_permissions("weather", &library, &build, &wanted)
+            .unwrap();
+        let more = grant(Permissions::NETWORK | Permissions::EXEC, &[]);
+        manager
+            .check_permissions("weather", &library, &build, &more)
+            .unwrap();
+        assert_eq!(*asked.lock().unwrap(), ["network", "exec"]);
+
+        // Remembered without asking
+        manager.set_permission_prompt(None);
+        manager
+            .check_permissions("weather", &library, &build, &more)
+            .unwrap();
+
+        // A damaged file is reported rather than read as granting nothing
+        std::fs::write(manager.grants_path(), "[\"libweather.so\"\nsha256 =").unwrap();
+        assert!(matches!(
+            manager.check_permissions("weather", &library, &build, &more),
+            Err(PluginError::InvalidGrants(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // The plugin directory is created for the first grant
+        manager.set_permission_prompt(Some(Box::new(|_| true)));
+        manager
+            .check_permissions("weather", &library, &build, &more)
+            .unwrap();
+        assert!(manager.grants_path().exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_grants_follow_the_library() {
+        let dir = std::env::temp_dir().join(format!("devdash-grants-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut manager = PluginManager::new();
+        manager.set_plugin_dir(dir.clone());
+        let library = dir.join("libweather.so");
+        let build = plugin_lock::sha256(b"weather");
+        let wanted = grant(Permissions::NETWORK, &["/tmp"]);
+        manager.set_permission_prompt(Some(Box::new(|_| true)));
+        manager
+            .check_permissions("weather", &library, &build, &wanted)
+            .unwrap();
+        let grants = std::fs::read_to_string(manager.grants_path()).unwrap();
+        assert!(grants.contains("[\"libweather.so\"]"), "{}", grants);
+        assert!(grants.contains(&build), "{}", grants);
+
+        manager.set_permission_prompt(None);
+        manager
+            .check_permissions("weather", &library, &build, &wanted)
+            .unwrap();
+        // Another library calling itself weather gets nothing
+        assert!(matches!(
+            manager.check_permissions("weather", &dir.join("libevil.so"), &build, &wanted),
+            Err(PluginError::PermissionDenied { .. })
+        ));
+        // Nor does a new build of the same one
+        let rebuilt = plugin_lock::sha256(b"weather 2");
+        assert!(matches!(
+            manager.check_permissions("weather", &library, &rebuilt, &wanted),
+            Err(PluginError::PermissionDenied { .. })
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_records_checksum() {
+        let dir = std::env::temp_dir().join(format!("devdash-install-{}", std::process::id()));
+        let source = dir.join(format!("libweather.{}", dll_extension()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&source, b"plugin library").unwrap();
+        let mut manager = PluginManager::new();
+        manager.set_plugin_dir(dir.join("plugins"));
+
+        let target = manager.install(&source).unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"plugin library");
+        let lock = PluginLock::load(manager.plugin_dir()).unwrap();
+        let file = target.file_name().unwrap().to_string_lossy();
+        assert_eq!(
+            lock.plugins[file.as_ref()].sha256,
+            plugin_lock::sha256(b"plugin library")
+        );
+        // Reinstalling from the plugin directory keeps the file
+        manager.install(&target).unwrap();
+        assert!(target.exists());
+
+        // With a key configured, strict mode wants a signature
+        manager.set_trust(TrustSettings {
+            strict: true,
+            public_key: Some("RWQBAgMEBQYHCOpKbGPinFIKvvVQexMuxfmVR3auvr57kkIe6mkURtIs".into()),
+        });
+        assert!(matches!(
+            manager.install(&source),
+            Err(PluginError::Untrusted {
+                source: VerifyError::Unsigned,
+                ..
+            })
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_later_directories_replace_libraries() {
+        let dir = std::env::temp_dir().join(format!("devdash-layers-{}", std::process::id()));
+        let layers = ["system", "user", "project"].map(|layer| dir.join(layer));
+        let library = |layer: usize, name: &str| {
+            let path = layers[layer].join(format!("{}.{}", name, dll_extension()));
+            std::fs::create_dir_all(&layers[layer]).unwrap();
+            std::fs::write(&path, name).unwrap();
+            path
+        };
+        let clock = library(0, "libclock");
+        library(0, "libweather");
+        library(1, "libweather");
+        let weather = library(2, "libweather");
+        let deploys = library(1, "libdeploys");
+        let mut manager = PluginManager::new();
+        manager.layers = layers.to_vec();
+
+        assert_eq!(
+            manager.libraries().unwrap(),
+            [clock.clone(), deploys, weather.clone()]
+        );
+        assert!(manager.is_shadowed(&layers[1].join(weather.file_name().unwrap())));
+        assert!(!manager.is_shadowed(&weather));
+        assert!(!manager.is_shadowed(&clock));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_widget() {
+        struct Old;
+        impl Widget for Old {
+            fn render(&mut self, _area: ratatui::layout::Rect, _buf: &mut ratatui::buffer::Buffer) {
+            }
+        }
+
+        let dir = std::env::temp_dir().join(format!("devdash-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let library = dir.join(format!("libweather.{}", dll_extension()));
+        std::fs::write(&library, b"not a library").unwrap();
+        let mut manager = PluginManager::new();
+        manager.set_plugin_dir(dir.clone());
+        let mut widgets = vec![crate::WidgetContainer::new(
+            "libweather".to_string(),
+            Box::new(Old),
+        )];
+
+        assert!(
+            manager
+                .reload_plugin(&library, "libweather", &mut widgets)
+                .is_err()
+        );
+        assert_eq!(widgets.len(), 1);
+        assert_eq!(widgets[0].name(), "libweather");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_untrusted_project_is_skipped() {
+        let dir = std::env::temp_dir().join(format!("devdash-project-{}", std::process::id()));
+        let user = dir.join("user");
+        let project = dir.join("app");
+        let project_plugins = project.join(PROJECT_PLUGIN_DIR);
+        std::fs::create_dir_all(&project_plugins).unwrap();
+        let library = project_plugins.join(format!("libdeploys.{}", dll_extension()));
+        std::fs::write(&library, b"deploys").unwrap();
+        let manager = || {
+            let mut manager = PluginManager::new();
+            manager.set_plugin_dir(user.clone());
+            manager.project_dir = Some(project_plugins.clone());
+            manager
+        };
+
+        // Without anyone to ask, the project's plugins are skipped
+        let mut untrusted = manager();
+        untrusted.trust_project().unwrap();
+        assert!(untrusted.libraries().unwrap().is_empty());
+        assert_eq!(
+            untrusted.untrusted_project(),
+            Some(project_plugins.as_path())
+        );
+
+        // Nor are they loaded when the answer is no
+        untrusted.set_project_prompt(Some(Box::new(|_| false)));
+        untrusted.trust_project().unwrap();
+        assert!(untrusted.libraries().unwrap().is_empty());
+        assert!(!untrusted.trusted_projects_path().exists());
+
+        let asked = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = asked.clone();
+        let mut trusted = manager();
+        trusted.set_project_prompt(Some(Box::new(move |project| {
+            log.lock().unwrap().push(project.to_path_buf());
+            true
+        })));
+        trusted.trust_project().unwrap();
+        assert_eq!(trusted.libraries().unwrap(), std::slice::from_ref(&library));
+        assert_eq!(trusted.untrusted_project(), None);
+        assert_eq!(*asked.lock().unwrap(), [project.canonicalize().unwrap()]);
+
+        // Remembered without asking, however the project is named
+        let mut remembered = manager();
+        assert!(remembered.is_trusted(&project_plugins.join("../..")));
+        assert!(!remembered.is_trusted(&user));
+        remembered.trust_project().unwrap();
+        assert_eq!(remembered.libraries().unwrap(), [library]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_settings_event() {
+        let settings: WidgetSettings = toml::from_str("url = \"http://localhost\"").unwrap();
+        let event = Event::Custom(
+            SETTINGS_EVENT.to_string(),
+            toml::to_string(&settings).unwrap().into_bytes(),
+        );
+        assert_eq!(settings_from_event(&event), Some(settings));
+        assert_eq!(
+            settings_from_event(&Event::Custom("other".to_string(), Vec::new())),
+            None
+        );
+    }
+}
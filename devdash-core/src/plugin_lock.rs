@@ -0,0 +1,272 @@
+// devdash-core/src/plugin_lock.rs
+//! Checksums and signatures of plugin libraries.
+//!
+//! `devdash plugins install` records each library's SHA-256 checksum in
+//! `plugins.lock` in the plugin directory, and the loader compares the
+//! library with it before any of its code runs. Libraries can also be
+//! signed with minisign, as `<library>.minisig` next to them, and are then
+//! checked against the `public_key` in the config's `[plugins]` section.
+//! In strict mode, libraries missing from the lockfile, or unsigned while
+//! a key is configured, are refused.
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::ConfigFile;
+
+/// Name of the lockfile in the plugin directory
+pub const LOCK_FILE: &str = "plugins.lock";
+
+/// Extension added to a library's file name for its minisign signature
+pub const SIGNATURE_EXTENSION: &str = "minisig";
+
+/// The `[plugins]` section of the config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrustSettings {
+    /// Refuse libraries missing from the lockfile, or unsigned while
+    /// `public_key` is set
+    pub strict: bool,
+    /// minisign public key signatures are checked with: the key itself, or
+    /// the contents of a `minisign.pub`
+    pub public_key: Option<String>,
+}
+
+impl TrustSettings {
+    /// The `[plugins]` section of a config file, or the defaults if it has
+    /// none
+    pub fn from_config(config: &ConfigFile) -> Result<Self, String> {
+        let Some(value) = config.settings.get("plugins") else {
+            return Ok(Self::default());
+        };
+        value
+            .clone()
+            .try_into()
+            .map_err(|e: toml::de::Error| e.message().to_string())
+    }
+}
+
+/// Why a library was refused
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum VerifyError {
+    #[error("its checksum {actual} doesn't match {expected} in {LOCK_FILE}")]
+    Checksum { expected: String, actual: String },
+    #[error("it is not in {LOCK_FILE} (strict mode)")]
+    Unlisted,
+    #[error("it has no .{SIGNATURE_EXTENSION} signature (strict mode)")]
+    Unsigned,
+    #[error("its signature doesn't verify: {0}")]
+    Signature(String),
+    #[error("the public_key in [plugins] is invalid: {0}")]
+    PublicKey(String),
+}
+
+/// Checksum of a library as recorded in the lockfile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    pub sha256: String,
+}
+
+/// Contents of [`LOCK_FILE`]: a `[plugins."<file name>"]` table per library
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginLock {
+    #[serde(default)]
+    pub plugins: BTreeMap<String, LockEntry>,
+}
+
+impl PluginLock {
+    /// The lockfile in `dir`; empty if there is none
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(dir.join(LOCK_FILE)) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| format!("{}: {}", dir.join(LOCK_FILE).display(), e.message())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", dir.join(LOCK_FILE).display(), e)),
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        let content = toml::to_string(self).map_err(io::Error::other)?;
+        std::fs::write(dir.join(LOCK_FILE), content)
+    }
+
+    /// Record the checksum of the library `file` with contents `bytes`
+    pub fn record(&mut self, file: &str, bytes: &[u8]) {
+        self.plugins.insert(
+            file.to_string(),
+            LockEntry {
+                sha256: sha256(bytes),
+            },
+        );
+    }
+
+    /// Check the library `file`, with contents `bytes` and the contents of
+    /// its signature file if it has one, before it is loaded
+    pub fn verify(
+        &self,
+        file: &str,
+        bytes: &[u8],
+        signature: Option<&str>,
+        settings: &TrustSettings,
+    ) -> Result<(), VerifyError> {
+        match self.plugins.get(file) {
+            Some(entry) => {
+                let actual = sha256(bytes);
+                if !actual.eq_ignore_ascii_case(&entry.sha256) {
+                    return Err(VerifyError::Checksum {
+                        expected: entry.sha256.clone(),
+                        actual,
+                    });
+                }
+            }
+            None if settings.strict => return Err(VerifyError::Unlisted),
+            None => {}
+        }
+        check_signature(bytes, signature, settings)
+    }
+}
+
+/// Check a library's signature with the configured key, if there is one;
+/// strict mode requires one then
+pub fn check_signature(
+    bytes: &[u8],
+    signature: Option<&str>,
+    settings: &TrustSettings,
+) -> Result<(), VerifyError> {
+    let Some(public_key) = &settings.public_key else {
+        return Ok(());
+    };
+    match signature {
+        Some(signature) => verify_signature(public_key, bytes, signature),
+        None if settings.strict => Err(VerifyError::Unsigned),
+        None => Ok(()),
+    }
+}
+
+/// Verify the minisign `signature` of `bytes` with `public_key`
+pub fn verify_signature(
+    public_key: &str,
+    bytes: &[u8],
+    signature: &str,
+) -> Result<(), VerifyError> {
+    // A minisign.pub has an untrusted comment line before the key
+    let key = public_key
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("");
+    let key = minisign_verify::PublicKey::from_base64(key)
+        .map_err(|e| VerifyError::PublicKey(e.to_string()))?;
+    let signature = minisign_verify::Signature::decode(signature)
+        .map_err(|e| VerifyError::Signature(e.to_string()))?;
+    key.verify(bytes, &signature, false)
+        .map_err(|e| VerifyError::Signature(e.to_string()))
+}
+
+/// Where the signature of `library` is looked for
+pub fn signature_path(library: &Path) -> PathBuf {
+    let mut name = library.as_os_str().to_owned();
+    name.push(".");
+    name.push(SIGNATURE_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// SHA-256 of `bytes` in lowercase hex
+pub fn sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY: &[u8] = b"plugin library";
+    const PUBLIC_KEY: &str = "untrusted comment: minisign public key 0807060504030201
+RWQBAgMEBQYHCOpKbGPinFIKvvVQexMuxfmVR3auvr57kkIe6mkURtIs";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBAgMEBQYHCEp/A//tMNC9qIk83YR18HXhfJjNpYs7NJkdLdL7KX7AVE4cal0BgbMoYAcEhXSy/Y19Ek+iRLzGzd21X8+r+Aw=
+trusted comment: timestamp:1760000000\tfile:libweather.so
+fjI0MFd3bsK7IqRMvYEUGAwJe5Daok6OeZ7FCtiw5WzGIsL79ZuxGOLElpxeRPQMLAssJakpx3bNdFy0Hiy9Cw==";
+
+    fn settings(strict: bool, public_key: Option<&str>) -> TrustSettings {
+        TrustSettings {
+            strict,
+            public_key: public_key.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            sha256(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_checksums() {
+        let mut lock = PluginLock::default();
+        lock.record("libweather.so", LIBRARY);
+        let relaxed = settings(false, None);
+
+        assert_eq!(
+            lock.verify("libweather.so", LIBRARY, None, &relaxed),
+            Ok(())
+        );
+        assert!(matches!(
+            lock.verify("libweather.so", b"tampered", None, &relaxed),
+            Err(VerifyError::Checksum { .. })
+        ));
+        // Unlisted libraries only load outside strict mode
+        assert_eq!(lock.verify("libclock.so", LIBRARY, None, &relaxed), Ok(()));
+        assert_eq!(
+            lock.verify("libclock.so", LIBRARY, None, &settings(true, None)),
+            Err(VerifyError::Unlisted)
+        );
+
+        let lock: PluginLock = toml::from_str(&toml::to_string(&lock).unwrap()).unwrap();
+        assert_eq!(lock.plugins["libweather.so"].sha256, sha256(LIBRARY));
+    }
+
+    #[test]
+    fn test_signatures() {
+        let signed = settings(false, Some(PUBLIC_KEY));
+        assert_eq!(check_signature(LIBRARY, Some(SIGNATURE), &signed), Ok(()));
+        assert!(matches!(
+            check_signature(b"tampered", Some(SIGNATURE), &signed),
+            Err(VerifyError::Signature(_))
+        ));
+        assert_eq!(check_signature(LIBRARY, None, &signed), Ok(()));
+        assert_eq!(
+            check_signature(LIBRARY, None, &settings(true, Some(PUBLIC_KEY))),
+            Err(VerifyError::Unsigned)
+        );
+        // Without a key there is nothing to check against
+        assert_eq!(
+            check_signature(b"tampered", Some(SIGNATURE), &settings(true, None)),
+            Ok(())
+        );
+        assert!(matches!(
+            check_signature(LIBRARY, Some(SIGNATURE), &settings(false, Some("RWQnope"))),
+            Err(VerifyError::PublicKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_signature_path() {
+        assert_eq!(
+            signature_path(Path::new("plugins/libweather.so")),
+            Path::new("plugins/libweather.so.minisig")
+        );
+    }
+}
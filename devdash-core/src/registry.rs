@@ -1,8 +1,15 @@
-use crate::{EventBus, Widget};
+use crate::{DevdashError, EventBus, Widget};
 use std::collections::HashMap;
 use std::time::Duration;
 
-pub type WidgetFactory = Box<dyn Fn(&EventBus, Duration) -> Box<dyn Widget>>;
+/// A widget's entry in `[[dashboard.widgets]]`, without its name
+pub type WidgetSettings = toml::Table;
+
+/// Shortest `interval` a widget may be given
+pub const MIN_INTERVAL: Duration = Duration::from_millis(50);
+
+pub type WidgetFactory =
+    Box<dyn Fn(&EventBus, Duration, &WidgetSettings) -> Result<Box<dyn Widget>, DevdashError>>;
 
 pub struct WidgetRegistry {
     factories: HashMap<String, WidgetFactory>,
@@ -31,19 +38,32 @@ impl WidgetRegistry {
         self.widgets.insert(name.to_string(), widget);
     }
 
+    /// Build the widget `name`, polling every `interval` unless its
+    /// settings set their own
     pub fn create(
         &mut self,
         name: &str,
         bus: &EventBus,
         interval: Duration,
-    ) -> Option<Box<dyn Widget>> {
+        settings: &WidgetSettings,
+    ) -> Result<Box<dyn Widget>, DevdashError> {
         // First check if it's a pre-registered widget
         if let Some(widget) = self.widgets.remove(name) {
-            return Some(widget);
+            return Ok(widget);
         }
 
         // Otherwise use factory
-        self.factories.get(name).map(|f| f(bus, interval))
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| DevdashError::UnknownWidget(name.to_string()))?;
+        let interval = parse_interval(settings)
+            .map_err(|reason| DevdashError::WidgetInit {
+                name: name.to_string(),
+                reason,
+            })?
+            .unwrap_or(interval);
+        factory(bus, interval, settings)
     }
 
     pub fn list_widgets(&self) -> Vec<&String> {
@@ -60,7 +80,9 @@ macro_rules! register_widget {
     ($registry:expr, $name:expr, $widget_type:ty) => {
         $registry.register(
             $name,
-            Box::new(|bus, interval| Box::new(<$widget_type>::new(bus.clone(), interval))),
+            Box::new(|bus, interval, _settings| {
+                Ok(Box::new(<$widget_type>::new(bus.clone(), interval)))
+            }),
         );
     };
 }
@@ -70,7 +92,159 @@ macro_rules! register_widget_no_bus {
     ($registry:expr, $name:expr, $widget_type:ty) => {
         $registry.register(
             $name,
-            Box::new(|_bus, interval| Box::new(<$widget_type>::new(interval))),
+            Box::new(|_bus, interval, _settings| Ok(Box::new(<$widget_type>::new(interval)))),
+        );
+    };
+}
+
+/// Register a widget built by `from_settings(bus, interval, settings)`
+///
+/// Invalid settings are reported as [`DevdashError::WidgetInit`].
+#[macro_export]
+macro_rules! register_widget_with_settings {
+    ($registry:expr, $name:expr, $widget_type:ty) => {
+        $registry.register(
+            $name,
+            Box::new(|bus, interval, settings| {
+                <$widget_type>::from_settings(bus.clone(), interval, settings)
+                    .map(|widget| Box::new(widget) as Box<dyn $crate::Widget>)
+                    .map_err(|reason| $crate::DevdashError::WidgetInit {
+                        name: $name.to_string(),
+                        reason,
+                    })
+            }),
         );
     };
 }
+
+/// Deserialize a widget's settings into its options type
+///
+/// Keys the type doesn't know, such as `units`, are ignored unless it
+/// denies unknown fields.
+pub fn parse_settings<T: serde::de::DeserializeOwned>(
+    settings: &WidgetSettings,
+) -> Result<T, String> {
+    toml::Value::Table(settings.clone())
+        .try_into()
+        .map_err(|e: toml::de::Error| e.message().to_string())
+}
+
+/// Read the `interval` setting: seconds as a number, or a string such as
+/// `"500ms"`, `"2s"` or `"1m"`
+pub fn parse_interval(settings: &WidgetSettings) -> Result<Option<Duration>, String> {
+    let interval = match settings.get("interval") {
+        None => return Ok(None),
+        Some(toml::Value::Integer(secs)) => u64::try_from(*secs)
+            .map(Duration::from_secs)
+            .map_err(|_| format!("interval must be positive, got {}", secs))?,
+        Some(toml::Value::Float(secs)) => {
+            Duration::try_from_secs_f64(*secs).map_err(|_| format!("invalid interval {}", secs))?
+        }
+        Some(toml::Value::String(text)) => parse_duration(text)?,
+        Some(other) => return Err(format!("invalid interval {}", other)),
+    };
+    if interval < MIN_INTERVAL {
+        return Err(format!(
+            "interval must be at least {}ms",
+            MIN_INTERVAL.as_millis()
+        ));
+    }
+    Ok(Some(interval))
+}
+
+/// Parse a duration such as `"500ms"`, `"2s"`, `"1m"`, or plain seconds
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid interval '{}'", text))?;
+    let secs = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        _ => {
+            return Err(format!(
+                "unknown unit in interval '{}' (use ms, s or m)",
+                text
+            ));
+        }
+    };
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("invalid interval '{}'", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Options {
+        #[serde(default)]
+        max_rows: u32,
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: WidgetSettings = toml::from_str("max_rows = 5\nunits = \"decimal\"").unwrap();
+        assert_eq!(parse_settings(&settings), Ok(Options { max_rows: 5 }));
+
+        let settings: WidgetSettings = toml::from_str("max_rows = \"many\"").unwrap();
+        assert!(parse_settings::<Options>(&settings).is_err());
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        let interval = |text: &str| {
+            let settings: WidgetSettings = toml::from_str(&format!("interval = {}", text)).unwrap();
+            parse_interval(&settings)
+        };
+        assert_eq!(parse_interval(&WidgetSettings::new()), Ok(None));
+        assert_eq!(interval("2"), Ok(Some(Duration::from_secs(2))));
+        assert_eq!(interval("0.5"), Ok(Some(Duration::from_millis(500))));
+        assert_eq!(interval("\"250ms\""), Ok(Some(Duration::from_millis(250))));
+        assert_eq!(interval("\"1m\""), Ok(Some(Duration::from_secs(60))));
+        assert!(interval("\"10ms\"").is_err());
+        assert!(interval("\"2h\"").is_err());
+        assert!(interval("-1").is_err());
+    }
+
+    #[test]
+    fn test_create_uses_interval_setting() {
+        struct Ticking(Duration);
+        impl Widget for Ticking {
+            fn render(&mut self, _area: ratatui::layout::Rect, _buf: &mut ratatui::buffer::Buffer) {
+            }
+            fn update_interval(&self) -> Duration {
+                self.0
+            }
+        }
+
+        let mut registry = WidgetRegistry::new();
+        registry.register(
+            "ticking",
+            Box::new(|_bus, interval, _settings| Ok(Box::new(Ticking(interval)))),
+        );
+        let bus = EventBus::new();
+        let default = Duration::from_secs(1);
+        let widget = registry
+            .create("ticking", &bus, default, &WidgetSettings::new())
+            .unwrap();
+        assert_eq!(widget.update_interval(), default);
+
+        let settings: WidgetSettings = toml::from_str("interval = \"5s\"").unwrap();
+        let widget = registry
+            .create("ticking", &bus, default, &settings)
+            .unwrap();
+        assert_eq!(widget.update_interval(), Duration::from_secs(5));
+
+        let settings: WidgetSettings = toml::from_str("interval = \"fast\"").unwrap();
+        assert!(matches!(
+            registry.create("ticking", &bus, default, &settings),
+            Err(DevdashError::WidgetInit { .. })
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+// devdash-core/src/state.rs
+//! Widget state kept between runs, such as the selected item.
+//!
+//! Stored as `[<dashboard>.<widget>]` tables in `devdash/state.toml` under
+//! the user's state directory (the local data directory where there is
+//! none).
+use std::path::{Path, PathBuf};
+
+/// What a widget saves with [`Widget::save_state`](crate::Widget::save_state)
+pub type WidgetState = toml::Table;
+
+#[derive(Debug, Default)]
+pub struct SessionState {
+    path: Option<PathBuf>,
+    dashboards: toml::Table,
+}
+
+impl SessionState {
+    /// Default location of the state file
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("devdash/state.toml"))
+    }
+
+    /// Load the state saved by the last run
+    ///
+    /// A missing or unreadable file starts an empty session; the state is
+    /// only a convenience.
+    pub fn load() -> Self {
+        match Self::default_path() {
+            Some(path) => Self::load_from(&path),
+            None => Self::default(),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let dashboards = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path.to_path_buf()),
+            dashboards,
+        }
+    }
+
+    /// Write the state back to the file it was loaded from
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string(&self.dashboards).map_err(std::io::Error::other)?;
+        std::fs::write(path, content)
+    }
+
+    pub fn widget(&self, dashboard: &str, widget: &str) -> Option<&WidgetState> {
+        self.dashboards.get(dashboard)?.get(widget)?.as_table()
+    }
+
+    pub fn set_widget(&mut self, dashboard: &str, widget: &str, state: WidgetState) {
+        let widgets = self
+            .dashboards
+            .entry(dashboard)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if let toml::Value::Table(widgets) = widgets {
+            widgets.insert(widget.to_string(), toml::Value::Table(state));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join(format!("devdash-state-{}.toml", std::process::id()));
+        let mut state = SessionState::load_from(&path);
+        assert!(state.widget("default", "network").is_none());
+
+        let mut network = WidgetState::new();
+        network.insert("interface".to_string(), "eth0".into());
+        state.set_widget("default", "network", network.clone());
+        state.save().unwrap();
+
+        let loaded = SessionState::load_from(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.widget("default", "network"), Some(&network));
+        assert!(loaded.widget("work", "network").is_none());
+    }
+}
@@ -0,0 +1,335 @@
+// devdash-core/src/style.rs
+//! Per-widget border and padding applied by [`WidgetContainer`](crate::WidgetContainer).
+//!
+//! Widgets draw their own bordered block. When a style is set, the container
+//! renders the widget off-screen so its content fills the padded area, then
+//! redraws the border in the configured style around it, keeping the
+//! widget's title and border color.
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Widget as _},
+};
+use serde::{Deserialize, Serialize};
+
+/// How a widget's border is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderKind {
+    /// Whatever the widget draws itself
+    #[default]
+    Plain,
+    Rounded,
+    Double,
+    /// No border; the content takes the whole area
+    None,
+}
+
+/// Where the title sits on the top border
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl From<TitleAlignment> for Alignment {
+    fn from(alignment: TitleAlignment) -> Self {
+        match alignment {
+            TitleAlignment::Left => Alignment::Left,
+            TitleAlignment::Center => Alignment::Center,
+            TitleAlignment::Right => Alignment::Right,
+        }
+    }
+}
+
+/// Border and padding of one widget, set per layout item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct WidgetStyle {
+    #[serde(default, skip_serializing_if = "BorderKind::is_plain")]
+    pub border: BorderKind,
+    /// `None` keeps the widget's own alignment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_alignment: Option<TitleAlignment>,
+    /// Blank cells between the border and the content
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub padding: u16,
+}
+
+impl BorderKind {
+    fn is_plain(&self) -> bool {
+        *self == Self::Plain
+    }
+}
+
+fn is_zero(n: &u16) -> bool {
+    *n == 0
+}
+
+impl WidgetStyle {
+    /// Whether the widget is drawn exactly as it draws itself
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Render `draw` into `area` with this style
+    ///
+    /// Widgets that don't draw a border, or areas too small to restyle, are
+    /// drawn unchanged.
+    pub fn render(&self, area: Rect, buf: &mut Buffer, mut draw: impl FnMut(Rect, &mut Buffer)) {
+        let border = u16::from(self.border != BorderKind::None);
+        let inset = border + self.padding;
+        if self.is_default() || area.width <= inset * 2 || area.height <= inset * 2 {
+            draw(area, buf);
+            return;
+        }
+
+        // Size the off-screen area so the widget's interior matches ours
+        let content = Rect::new(
+            area.x + inset,
+            area.y + inset,
+            area.width - inset * 2,
+            area.height - inset * 2,
+        );
+        let mut scratch = Buffer::empty(Rect::new(0, 0, content.width + 2, content.height + 2));
+        draw(scratch.area, &mut scratch);
+
+        if !has_border(&scratch) {
+            draw(area, buf);
+            return;
+        }
+
+        for y in 0..content.height {
+            for x in 0..content.width {
+                buf[(content.x + x, content.y + y)] = scratch[(x + 1, y + 1)].clone();
+            }
+        }
+
+        if border == 1 {
+            let (title, alignment) = extract_title(&scratch);
+            let mut block = Block::bordered()
+                .border_type(match self.border {
+                    BorderKind::Rounded => BorderType::Rounded,
+                    BorderKind::Double => BorderType::Double,
+                    _ => BorderType::Plain,
+                })
+                .border_style(scratch[(0, 0)].style());
+            if !title.spans.is_empty() {
+                let alignment = self.title_alignment.map_or(alignment, Alignment::from);
+                block = block.title(title.alignment(alignment));
+            }
+            // Only the border; the padding between it and the content
+            // stays blank
+            clear_ring(area, content, buf);
+            block.render(area, buf);
+        }
+    }
+}
+
+/// Write `text` right after the title on the top border of `area`, in the
+/// title's style patched with `patch`; nothing happens without a border or
+/// room
+pub(crate) fn append_title(area: Rect, buf: &mut Buffer, text: &str, patch: Style) {
+    if area.width < 3 || area.height < 2 {
+        return;
+    }
+    let (left, top, right) = (area.left(), area.top(), area.right() - 1);
+    if !["┌", "╭", "╔", "┏"].contains(&buf[(left, top)].symbol()) {
+        return;
+    }
+    let is_border = |x: u16| ["─", "═", "━"].contains(&buf[(x, top)].symbol());
+    let (start, style, text) = match (left + 1..right).rev().find(|&x| !is_border(x)) {
+        Some(end) => (end + 1, buf[(end, top)].style(), format!("{} ", text)),
+        None => (left + 1, buf[(left, top)].style(), format!(" {} ", text)),
+    };
+    if start < right {
+        buf.set_stringn(
+            start,
+            top,
+            text,
+            usize::from(right - start),
+            style.patch(patch),
+        );
+    }
+}
+
+/// Redraw the box around `area` with heavy lines, marking the focused
+/// widget where its border color can't; the title stays
+pub(crate) fn thicken_border(area: Rect, buf: &mut Buffer) {
+    if area.width < 2 || area.height < 2 {
+        return;
+    }
+    let (left, top) = (area.left(), area.top());
+    let (right, bottom) = (area.right() - 1, area.bottom() - 1);
+    if !["┌", "╭", "╔", "┏"].contains(&buf[(left, top)].symbol())
+        || !["┘", "╯", "╝", "┛"].contains(&buf[(right, bottom)].symbol())
+    {
+        return;
+    }
+    let heavy = |symbol: &str| match symbol {
+        "─" | "═" => Some("━"),
+        "│" | "║" => Some("┃"),
+        "┌" | "╭" | "╔" => Some("┏"),
+        "┐" | "╮" | "╗" => Some("┓"),
+        "└" | "╰" | "╚" => Some("┗"),
+        "┘" | "╯" | "╝" => Some("┛"),
+        _ => None,
+    };
+    let ring = (left..=right)
+        .flat_map(|x| [(x, top), (x, bottom)])
+        .chain((top + 1..bottom).flat_map(|y| [(left, y), (right, y)]));
+    for position in ring {
+        if let Some(symbol) = heavy(buf[position].symbol()) {
+            buf[position].set_symbol(symbol);
+        }
+    }
+}
+
+/// Whether the widget drew a box around the whole buffer
+fn has_border(buf: &Buffer) -> bool {
+    let area = buf.area;
+    let corner = buf[(0, 0)].symbol();
+    let bottom_right = buf[(area.width - 1, area.height - 1)].symbol();
+    ["┌", "╭", "╔", "┏"].contains(&corner) && ["┘", "╯", "╝", "┛"].contains(&bottom_right)
+}
+
+/// The title on the widget's top border and where it was placed
+fn extract_title(buf: &Buffer) -> (Line<'static>, Alignment) {
+    let width = buf.area.width;
+    let cells: Vec<&Cell> = (1..width - 1).map(|x| &buf[(x, 0)]).collect();
+    let is_border = |cell: &&Cell| ["─", "═", "━"].contains(&cell.symbol());
+
+    let Some(start) = cells.iter().position(|c| !is_border(c)) else {
+        return (Line::default(), Alignment::Left);
+    };
+    let end = cells.len() - cells.iter().rev().position(|c| !is_border(c)).unwrap_or(0);
+
+    let spans: Vec<Span<'static>> = cells[start..end]
+        .iter()
+        .map(|cell| Span::styled(cell.symbol().to_string(), cell.style()))
+        .collect();
+
+    let (left, right) = (start, cells.len() - end);
+    let alignment = if left.abs_diff(right) <= 1 && left > 0 {
+        Alignment::Center
+    } else if right < left {
+        Alignment::Right
+    } else {
+        Alignment::Left
+    };
+    (Line::from(spans), alignment)
+}
+
+/// Blank the cells of `outer` that aren't part of `inner`
+fn clear_ring(outer: Rect, inner: Rect, buf: &mut Buffer) {
+    for y in outer.top()..outer.bottom() {
+        for x in outer.left()..outer.right() {
+            let inside = (inner.left()..inner.right()).contains(&x)
+                && (inner.top()..inner.bottom()).contains(&y);
+            if !inside {
+                buf[(x, y)].reset();
+                buf[(x, y)].set_style(Style::default());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::widgets::{Borders, Paragraph};
+
+    fn boxed(area: Rect, buf: &mut Buffer) {
+        Paragraph::new("abc")
+            .block(Block::default().borders(Borders::ALL).title(" CPU "))
+            .render(area, buf);
+    }
+
+    fn rows(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    fn render(style: WidgetStyle, width: u16, height: u16) -> Vec<String> {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+        style.render(buf.area, &mut buf, boxed);
+        rows(&buf)
+    }
+
+    #[test]
+    fn test_append_title() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 16, 3));
+        boxed(buf.area, &mut buf);
+        append_title(buf.area, &mut buf, "@buildbox", Style::default());
+        assert_eq!(rows(&buf)[0], "┌ CPU @buildbox┐");
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 3));
+        boxed(buf.area, &mut buf);
+        append_title(buf.area, &mut buf, "@buildbox", Style::default());
+        assert_eq!(rows(&buf)[0], "┌ CPU @buil┐");
+    }
+
+    #[test]
+    fn test_thicken_border() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 9, 3));
+        boxed(buf.area, &mut buf);
+        thicken_border(buf.area, &mut buf);
+        assert_eq!(rows(&buf), ["┏ CPU ━━┓", "┃abc    ┃", "┗━━━━━━━┛"]);
+
+        // Nothing to do without a box
+        let mut buf = Buffer::empty(Rect::new(0, 0, 5, 2));
+        buf.set_string(0, 0, "─────", Style::default());
+        thicken_border(buf.area, &mut buf);
+        assert_eq!(rows(&buf)[0], "─────");
+    }
+
+    #[test]
+    fn test_default_style_is_untouched() {
+        assert_eq!(
+            render(WidgetStyle::default(), 9, 3),
+            ["┌ CPU ──┐", "│abc    │", "└───────┘"]
+        );
+    }
+
+    #[test]
+    fn test_rounded_centered() {
+        let style = WidgetStyle {
+            border: BorderKind::Rounded,
+            title_alignment: Some(TitleAlignment::Center),
+            padding: 0,
+        };
+        assert_eq!(render(style, 9, 3), ["╭─ CPU ─╮", "│abc    │", "╰───────╯"]);
+    }
+
+    #[test]
+    fn test_no_border_uses_whole_area() {
+        let style = WidgetStyle {
+            border: BorderKind::None,
+            ..Default::default()
+        };
+        assert_eq!(render(style, 5, 2), ["abc  ", "     "]);
+    }
+
+    #[test]
+    fn test_padding() {
+        let style = WidgetStyle {
+            border: BorderKind::Double,
+            padding: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(style, 9, 5),
+            [
+                "╔ CPU ══╗",
+                "║       ║",
+                "║ abc   ║",
+                "║       ║",
+                "╚═══════╝"
+            ]
+        );
+    }
+}
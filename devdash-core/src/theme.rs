@@ -0,0 +1,215 @@
+// devdash-core/src/theme.rs
+//! Colors every widget draws with, set from the config's `[theme]` section.
+//!
+//! With colors turned off (`--no-color` or `NO_COLOR`), widgets draw as
+//! usual and [`strip_styles`] takes the colors and text attributes out of
+//! the finished frame.
+use std::sync::{
+    RwLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
+use serde::{Deserialize, Deserializer, de::Error as _};
+
+/// Border, usage and chart colors shared by all widgets
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Border of the focused widget
+    #[serde(deserialize_with = "color")]
+    pub focus: Color,
+    /// Border of the other widgets
+    #[serde(deserialize_with = "color")]
+    pub border: Color,
+    /// Usage below `usage_warning`
+    #[serde(deserialize_with = "color")]
+    pub good: Color,
+    /// Usage from `usage_warning` up to `usage_critical`
+    #[serde(deserialize_with = "color")]
+    pub warning: Color,
+    /// Usage from `usage_critical` up
+    #[serde(deserialize_with = "color")]
+    pub critical: Color,
+    /// Single-series charts, and the first of two
+    #[serde(deserialize_with = "color")]
+    pub sparkline: Color,
+    /// The second series of two-series charts, e.g. upload or writes
+    #[serde(deserialize_with = "color")]
+    pub sparkline_alt: Color,
+    /// Usage percentages at which `warning` and `critical` start
+    pub usage_warning: f64,
+    pub usage_critical: f64,
+}
+
+impl Theme {
+    pub const DEFAULT: Self = Self {
+        focus: Color::Yellow,
+        border: Color::DarkGray,
+        good: Color::Green,
+        warning: Color::Yellow,
+        critical: Color::Red,
+        sparkline: Color::Cyan,
+        sparkline_alt: Color::Magenta,
+        usage_warning: 60.0,
+        usage_critical: 80.0,
+    };
+
+    /// Read a `[theme]` table; keys it leaves out keep their default
+    pub fn from_config(value: &toml::Value) -> Result<Self, String> {
+        let theme: Self = value
+            .clone()
+            .try_into()
+            .map_err(|e: toml::de::Error| e.message().to_string())?;
+        if !(0.0..=100.0).contains(&theme.usage_warning)
+            || !(0.0..=100.0).contains(&theme.usage_critical)
+            || theme.usage_warning > theme.usage_critical
+        {
+            return Err(format!(
+                "usage_warning and usage_critical must be percentages in order, got {} and {}",
+                theme.usage_warning, theme.usage_critical
+            ));
+        }
+        Ok(theme)
+    }
+
+    /// Border color for a widget
+    pub fn focus_color(&self, focused: bool) -> Color {
+        if focused { self.focus } else { self.border }
+    }
+
+    /// Color for a usage percentage (0.0 - 100.0)
+    pub fn usage_color(&self, percentage: f64) -> Color {
+        if percentage < self.usage_warning {
+            self.good
+        } else if percentage < self.usage_critical {
+            self.warning
+        } else {
+            self.critical
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// A color name (`"red"`, `"lightblue"`), `"#rrggbb"`, or a 256-color index
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    text.parse()
+        .map_err(|_| D::Error::custom(format!("unknown color '{}'", text)))
+}
+
+static THEME: RwLock<Theme> = RwLock::new(Theme::DEFAULT);
+
+/// Set the theme used process-wide
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// The theme in use
+pub fn theme() -> Theme {
+    *THEME.read().unwrap_or_else(|e| e.into_inner())
+}
+
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Draw without colors or text attributes, process-wide
+pub fn set_no_color(no_color: bool) {
+    NO_COLOR.store(no_color, Ordering::Relaxed);
+}
+
+/// Whether colors are turned off
+pub fn no_color() -> bool {
+    NO_COLOR.load(Ordering::Relaxed)
+}
+
+/// Take every color and text attribute out of `buf`
+///
+/// Highlights would vanish with them, so a highlighted run of cells right
+/// of a vertical border, such as a table's selected row, gets a `>` on
+/// that border instead.
+pub fn strip_styles(buf: &mut Buffer) {
+    let area = buf.area;
+    let highlighted = |buf: &Buffer, x: u16, y: u16| {
+        let cell = &buf[(x, y)];
+        cell.bg != Color::Reset || cell.modifier.contains(Modifier::REVERSED)
+    };
+    for y in area.top()..area.bottom() {
+        for x in area.left() + 1..area.right() {
+            if highlighted(buf, x, y)
+                && !highlighted(buf, x - 1, y)
+                && ["│", "║", "┃"].contains(&buf[(x - 1, y)].symbol())
+            {
+                buf[(x - 1, y)].set_symbol(">");
+            }
+        }
+    }
+    buf.set_style(area, Style::reset());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Theme, String> {
+        Theme::from_config(&toml::Value::Table(toml::from_str(text).unwrap()))
+    }
+
+    #[test]
+    fn test_partial_theme_keeps_defaults() {
+        let theme = parse(
+            r##"
+            focus = "lightblue"
+            critical = "#ff0000"
+            usage_warning = 50
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme.focus, Color::LightBlue);
+        assert_eq!(theme.critical, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.border, Color::DarkGray);
+        assert_eq!(theme.usage_color(55.0), Color::Yellow);
+        assert_eq!(theme.usage_color(85.0), Color::Rgb(255, 0, 0));
+        assert_eq!(theme.focus_color(false), Color::DarkGray);
+    }
+
+    #[test]
+    fn test_invalid_theme() {
+        assert!(
+            parse(r#"focus = "blurple""#)
+                .unwrap_err()
+                .contains("blurple")
+        );
+        assert!(parse(r#"focsu = "red""#).unwrap_err().contains("focsu"));
+        assert!(parse("usage_warning = 90").is_err());
+        assert!(parse("usage_critical = 120").is_err());
+    }
+
+    #[test]
+    fn test_strip_styles() {
+        use ratatui::layout::Rect;
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 3));
+        buf.set_string(0, 0, "│ab  │", Style::default().fg(Color::Red));
+        buf.set_string(0, 1, "│cd  │", Style::default());
+        buf.set_string(1, 1, "cd  ", Style::default().bg(Color::DarkGray));
+        buf.set_string(
+            0,
+            2,
+            "hi",
+            Style::default().add_modifier(Modifier::REVERSED),
+        );
+        strip_styles(&mut buf);
+
+        let mut expected = Buffer::with_lines(["│ab  │", ">cd  │", "hi    "]);
+        expected.set_style(expected.area, Style::reset());
+        assert_eq!(buf, expected);
+    }
+}
@@ -1,7 +1,24 @@
 // devdash-core/src/widget.rs
-use ratatui::{buffer::Buffer, layout::Rect};
-use std::time::Duration;
-use sysinfo::System;
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Action, Keymap, KeymapError, WidgetState, WidgetStyle,
+    style::{append_title, thicken_border},
+    theme::no_color,
+};
+
+/// Update interval used by widgets that don't override `update_interval`
+pub const DEFAULT_UPDATE_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Core widget trait with lifecycle hooks
 pub trait Widget: Send + Sync {
@@ -34,10 +51,156 @@ pub trait Widget: Send + Sync {
         false
     }
 
+    /// How often `on_update` should be called while `needs_update` is true
+    ///
+    /// Queried again after every update, so it may change at runtime.
+    fn update_interval(&self) -> Duration {
+        DEFAULT_UPDATE_INTERVAL
+    }
+
+    /// Named actions the widget handles while focused, with their default
+    /// keys
+    ///
+    /// Presses of their keys, as remapped in the config's `[keys]` section,
+    /// arrive as [`Event::Action`]; other keys still arrive as
+    /// [`Event::Key`].
+    fn actions(&self) -> Vec<Action> {
+        Vec::new()
+    }
+
+    /// Keys the widget handles while focused, for help and cheat sheets
+    ///
+    /// Defaults to the [`actions`](Self::actions) with their default keys.
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        let actions = self.actions();
+        Keymap::new(&actions).bindings(&actions)
+    }
+
+    /// One-line plain-text description of what the widget shows
+    ///
+    /// Used by the accessible output mode, so state should be spelled out
+    /// rather than conveyed by color. `None` falls back to the rendered text.
+    fn summary(&self) -> Option<String> {
+        None
+    }
+
+    /// The item currently picked in the widget, if any
+    ///
+    /// When it changes on the focused widget, widgets in the same link group
+    /// receive it as [`Event::Linked`].
+    fn selection(&self) -> Option<Selection> {
+        None
+    }
+
+    /// Where the widget's data source stands, for the refresh spinner and
+    /// stale marker in its title; `None` if it doesn't poll
+    ///
+    /// Widgets that poll synchronously in `on_update` can't fall behind, so
+    /// they only report a status while showing another instance's events.
+    fn data_status(&self) -> Option<DataStatus> {
+        None
+    }
+
+    /// State to carry over to the next run, such as the selected item
+    fn save_state(&self) -> Option<WidgetState> {
+        None
+    }
+
+    /// Restore what [`save_state`](Self::save_state) returned in an earlier
+    /// run; called after `on_mount`
+    fn restore_state(&mut self, _state: &WidgetState) {}
+
+    /// Called when the widget leaves the visible layout
+    ///
+    /// While suspended the host stops calling `on_update`, so polling pauses
+    /// without any extra work. Override to release expensive resources.
+    fn on_suspend(&mut self) {}
+
+    /// Called when a suspended widget becomes visible again
+    fn on_resume(&mut self) {}
+
     /// Cleanup when widget is removed
     fn on_unmount(&mut self) {}
 }
 
+/// A key binding as listed in help and cheat sheets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    /// Keys as shown to the user, e.g. `"j/↓"`
+    pub keys: Cow<'static, str>,
+    pub description: &'static str,
+}
+
+impl KeyBinding {
+    pub fn new(keys: impl Into<Cow<'static, str>>, description: &'static str) -> Self {
+        Self {
+            keys: keys.into(),
+            description,
+        }
+    }
+}
+
+/// An item picked in one widget and shared with the widgets linked to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Selection {
+    /// What was picked, e.g. `"disk"`
+    pub kind: String,
+    /// Which one, e.g. a mount point
+    pub value: String,
+}
+
+impl Selection {
+    pub fn new(kind: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// How current the data shown by a widget is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataStatus {
+    /// A poll is in flight
+    pub refreshing: bool,
+    /// When data last arrived
+    pub last_data: Instant,
+    /// How often new data is expected
+    pub interval: Duration,
+    /// The source keeps failing and won't be polled again before this
+    pub retry_at: Option<Instant>,
+}
+
+impl DataStatus {
+    /// How long the data has gone without refreshing, once that is well
+    /// past the expected interval
+    pub fn stale_for(&self, now: Instant) -> Option<Duration> {
+        let age = now.saturating_duration_since(self.last_data);
+        let limit = (self.interval * STALE_INTERVALS).max(MIN_STALE_AGE);
+        (age > limit).then_some(age)
+    }
+
+    /// How long until the failing source is polled again, rounded up to
+    /// whole seconds
+    pub fn retry_in(&self, now: Instant) -> Option<u64> {
+        let wait = self.retry_at?.checked_duration_since(now)?;
+        (!wait.is_zero()).then(|| wait.as_secs() + u64::from(wait.subsec_nanos() > 0))
+    }
+}
+
+/// Missed intervals before a widget's data counts as stale
+const STALE_INTERVALS: u32 = 3;
+
+/// Data is never stale sooner than this, however short the interval
+const MIN_STALE_AGE: Duration = Duration::from_secs(5);
+
+/// Polls quicker than this finish without showing the spinner
+const SPINNER_DELAY: Duration = Duration::from_millis(300);
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+const SPINNER_FRAME_TIME: Duration = Duration::from_millis(100);
+
 #[derive(Debug, Clone)]
 pub struct Size {
     pub width: u16,
@@ -50,6 +213,8 @@ pub enum Event {
     Mouse(crossterm::event::MouseEvent),
     Resize(u16, u16),
     Custom(String, Vec<u8>), // Plugin-defined events
+    Linked(Selection),       // Selection made in a linked widget
+    Action(String),          // Key press bound to one of the widget's actions
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -58,28 +223,89 @@ pub enum EventResult {
     Ignored,  // Continue to next widget
 }
 
+/// Rows rendered when falling back to a widget's screen text for its summary
+const SUMMARY_HEIGHT: u16 = 12;
+
+/// Join the text rows of a buffer into one line
+///
+/// Box-drawing, block, and braille characters are dropped, runs of spaces
+/// collapse, and rows are separated by `" | "`.
+fn linearize(buf: &Buffer) -> String {
+    let width = buf.area.width as usize;
+    let rows = buf.content.chunks(width.max(1)).map(|row| {
+        let text: String = row
+            .iter()
+            .map(|cell| cell.symbol())
+            .filter(|symbol| !symbol.chars().any(is_graphic))
+            .collect();
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    });
+    rows.filter(|row| !row.is_empty())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Characters that only make sense visually
+fn is_graphic(c: char) -> bool {
+    matches!(c, '\u{2500}'..='\u{259F}' | '\u{2800}'..='\u{28FF}')
+}
+
 /// Container for managing widget lifecycle
 pub struct WidgetContainer {
     widget: Box<dyn Widget>,
-    last_update: std::time::Instant,
+    last_update: Instant,
     mounted: bool,
+    suspended: bool,
     name: String,
+    style: WidgetStyle,
+    /// Host shown next to the title, for widgets showing another machine
+    host: Option<String>,
+    /// Since when the current poll has been in flight
+    refreshing_since: Option<Instant>,
+    /// Keys bound to the widget's actions
+    keymap: Keymap,
 }
 
 impl WidgetContainer {
     pub fn new(name: String, widget: Box<dyn Widget>) -> Self {
         Self {
+            keymap: Keymap::new(&widget.actions()),
             widget,
-            last_update: std::time::Instant::now(),
+            last_update: Instant::now(),
             mounted: false,
+            suspended: false,
             name,
+            style: WidgetStyle::default(),
+            host: None,
+            refreshing_since: None,
         }
     }
 
+    /// Draw the widget with a configured border and padding
+    pub fn with_style(mut self, style: WidgetStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Show `host` next to the widget's title
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// How fresh the widget's data is, for widgets that track it
+    pub fn data_status(&self) -> Option<DataStatus> {
+        self.widget.data_status()
+    }
+
     pub fn mount(&mut self) {
         if !self.mounted {
             self.widget.on_mount();
@@ -88,7 +314,12 @@ impl WidgetContainer {
     }
 
     pub fn update(&mut self) {
-        let now = std::time::Instant::now();
+        // Keep last_update so the first delta after resuming covers the pause
+        if self.suspended {
+            return;
+        }
+
+        let now = Instant::now();
         let delta = now.duration_since(self.last_update);
 
         if self.widget.needs_update() {
@@ -98,29 +329,232 @@ impl WidgetContainer {
         self.last_update = now;
     }
 
+    /// When the widget next wants `update` called, if at all
+    pub fn next_update(&self) -> Option<Instant> {
+        (self.mounted && !self.suspended && self.widget.needs_update())
+            .then(|| self.last_update + self.widget.update_interval())
+    }
+
+    /// Whether the widget's update interval has elapsed at `now`
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.next_update().is_some_and(|due| due <= now)
+    }
+
+    /// Rebind the widget's actions from its `[keys.<name>]` table
+    pub fn remap_keys(&mut self, keys: &toml::Table) -> Result<(), KeymapError> {
+        self.keymap.remap(keys)
+    }
+
+    /// Pass an event on, as an [`Event::Action`] if it is a key bound to one
+    ///
+    /// Actions turned off by read-only mode are dropped, keys included.
     pub fn handle_event(&mut self, event: Event) -> EventResult {
+        let event = self.keymap.translate(event);
+        if let Event::Action(name) = &event
+            && self
+                .widget
+                .actions()
+                .iter()
+                .any(|action| action.name == name && !action.is_enabled())
+        {
+            return EventResult::Ignored;
+        }
         self.widget.on_event(event)
     }
 
+    /// Pass a mouse event on with its position relative to `area`, where
+    /// the widget was drawn
+    ///
+    /// A wheel scroll the widget ignores becomes its `up` or `down` action,
+    /// so lists scroll without widgets handling the mouse themselves.
+    pub fn handle_mouse(&mut self, mut mouse: MouseEvent, area: Rect) -> EventResult {
+        mouse.column = mouse.column.saturating_sub(area.x);
+        mouse.row = mouse.row.saturating_sub(area.y);
+        let result = self.widget.on_event(Event::Mouse(mouse));
+        let action = match (result, mouse.kind) {
+            (EventResult::Ignored, MouseEventKind::ScrollUp) => "up",
+            (EventResult::Ignored, MouseEventKind::ScrollDown) => "down",
+            _ => return result,
+        };
+        if self.widget.actions().iter().any(|a| a.name == action) {
+            self.widget.on_event(Event::Action(action.to_string()))
+        } else {
+            result
+        }
+    }
+
+    /// The widget's key bindings, with the keys its actions are bound to
+    pub fn key_bindings(&self) -> Vec<KeyBinding> {
+        let actions = self.widget.actions();
+        if actions.is_empty() {
+            self.widget.key_bindings()
+        } else {
+            self.keymap.bindings(&actions)
+        }
+    }
+
+    /// The widget's actions, leaving out those read-only mode turns off
+    pub fn actions(&self) -> Vec<Action> {
+        let mut actions = self.widget.actions();
+        actions.retain(Action::is_enabled);
+        actions
+    }
+
+    /// The keys bound to the widget's `action` as shown in help, e.g. `"j/↓"`
+    pub fn keys(&self, action: &str) -> String {
+        self.keymap.keys(action)
+    }
+
+    pub fn selection(&self) -> Option<Selection> {
+        self.widget.selection()
+    }
+
+    pub fn save_state(&self) -> Option<WidgetState> {
+        self.widget.save_state()
+    }
+
+    pub fn restore_state(&mut self, state: &WidgetState) {
+        self.widget.restore_state(state);
+    }
+
     pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
-        self.widget.render(area, buf);
+        let widget = &mut self.widget;
+        self.style
+            .render(area, buf, |area, buf| widget.render(area, buf));
+        self.tag_title(area, buf);
     }
 
     pub fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
-        self.widget.render_focused(area, buf, focused);
+        let widget = &mut self.widget;
+        self.style.render(area, buf, |area, buf| {
+            widget.render_focused(area, buf, focused)
+        });
+        if focused && no_color() {
+            thicken_border(area, buf);
+        }
+        self.tag_title(area, buf);
+    }
+
+    /// Add the host, refresh spinner, and stale marker after the title
+    fn tag_title(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(host) = &self.host {
+            append_title(area, buf, &format!("@{}", host), Style::default());
+        }
+
+        let Some(status) = self.widget.data_status() else {
+            return;
+        };
+        let now = Instant::now();
+        let since = match status.refreshing {
+            true => *self.refreshing_since.get_or_insert(now),
+            false => {
+                self.refreshing_since = None;
+                now
+            }
+        };
+        let busy = now.duration_since(since);
+        if busy >= SPINNER_DELAY {
+            let frame = (busy.as_millis() / SPINNER_FRAME_TIME.as_millis()) as usize;
+            append_title(
+                area,
+                buf,
+                SPINNER_FRAMES[frame % SPINNER_FRAMES.len()],
+                Style::default(),
+            );
+        }
+        // Backing off explains why the data is stale
+        if let Some(secs) = status.retry_in(now) {
+            append_title(
+                area,
+                buf,
+                &format!("retrying in {}s", secs),
+                Style::default().fg(Color::Red),
+            );
+        } else if let Some(age) = status.stale_for(now) {
+            append_title(
+                area,
+                buf,
+                &format!("stale ({}s)", age.as_secs()),
+                Style::default().fg(Color::Yellow),
+            );
+        }
+    }
+
+    /// Plain-text summary of the widget for the accessible output mode
+    ///
+    /// Widgets without their own [`Widget::summary`] are rendered off-screen
+    /// at `width` columns and their text is linearized, with borders and
+    /// chart glyphs removed.
+    pub fn summary(&mut self, width: u16) -> String {
+        let summary = self.widget.summary().unwrap_or_else(|| {
+            let area = Rect::new(0, 0, width.max(1), SUMMARY_HEIGHT);
+            let mut buf = Buffer::empty(area);
+            self.widget.render_focused(area, &mut buf, false);
+            linearize(&buf)
+        });
+        let status = self.widget.data_status();
+        let now = Instant::now();
+        let summary = match (
+            status.and_then(|s| s.retry_in(now)),
+            status.and_then(|s| s.stale_for(now)),
+        ) {
+            (Some(secs), _) => format!("{} (failing, retrying in {}s)", summary, secs),
+            (None, Some(age)) => {
+                format!("{} (stale, last updated {}s ago)", summary, age.as_secs())
+            }
+            (None, None) => summary,
+        };
+        match &self.host {
+            Some(host) => format!("{}: {}", host, summary),
+            None => summary,
+        }
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Stop updating the widget until [`resume`](Self::resume) is called
+    pub fn suspend(&mut self) {
+        if self.mounted && !self.suspended {
+            self.widget.on_suspend();
+            self.suspended = true;
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.suspended {
+            self.widget.on_resume();
+            self.suspended = false;
+        }
+    }
+
+    /// Suspend or resume depending on whether the widget got any screen space
+    pub fn set_visible(&mut self, visible: bool) {
+        if visible {
+            self.resume();
+        } else {
+            self.suspend();
+        }
     }
 
     pub fn unmount(&mut self) {
         if self.mounted {
             self.widget.on_unmount();
             self.mounted = false;
+            self.suspended = false;
         }
     }
 }
 
-// Example widget implementation
+/// Original example CPU sparkline, kept so existing `widget::CpuWidget`
+/// imports still build
+///
+/// The built-in CPU widget now lives in `devdash-widgets`, which can't be
+/// re-exported from here since it depends on this crate.
+#[deprecated(note = "use devdash_widgets::CpuWidget, which publishes system.cpu events")]
 pub struct CpuWidget {
-    system: System,
+    system: sysinfo::System,
     usage: f32,
     history: Vec<u64>,
     poll_interval: Duration,
@@ -129,9 +563,10 @@ pub struct CpuWidget {
     show_percentage: bool,
 }
 
+#[allow(deprecated)]
 impl CpuWidget {
     pub fn new(poll_interval: Duration) -> Self {
-        let mut system = System::new_all();
+        let mut system = sysinfo::System::new_all();
         system.refresh_cpu_all();
 
         Self {
@@ -157,6 +592,7 @@ impl CpuWidget {
     }
 }
 
+#[allow(deprecated)]
 impl Widget for CpuWidget {
     fn on_mount(&mut self) {
         self.poll_cpu(); // Initial poll
@@ -286,3 +722,289 @@ impl Widget for CpuWidget {
         true // Always poll for updates
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton};
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    /// Counts lifecycle calls through shared counters
+    #[derive(Default, Clone)]
+    struct Probe {
+        updates: Arc<AtomicUsize>,
+        suspends: Arc<AtomicUsize>,
+        resumes: Arc<AtomicUsize>,
+    }
+
+    impl Widget for Probe {
+        fn on_update(&mut self, _delta: Duration) {
+            self.updates.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_suspend(&mut self) {
+            self.suspends.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_resume(&mut self) {
+            self.resumes.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn render(&mut self, _area: Rect, _buf: &mut Buffer) {}
+
+        fn needs_update(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_suspended_widget_skips_updates() {
+        let probe = Probe::default();
+        let mut container = WidgetContainer::new("probe".to_string(), Box::new(probe.clone()));
+        container.mount();
+
+        container.set_visible(false);
+        container.set_visible(false);
+        container.update();
+        assert!(container.is_suspended());
+        assert_eq!(probe.updates.load(Ordering::SeqCst), 0);
+        assert_eq!(probe.suspends.load(Ordering::SeqCst), 1);
+
+        container.set_visible(true);
+        container.update();
+        assert_eq!(probe.resumes.load(Ordering::SeqCst), 1);
+        assert_eq!(probe.updates.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_next_update() {
+        let probe = Probe::default();
+        let mut container = WidgetContainer::new("probe".to_string(), Box::new(probe));
+        assert_eq!(container.next_update(), None);
+
+        container.mount();
+        let due = container.next_update().unwrap();
+        assert!(!container.is_due(due - Duration::from_millis(1)));
+        assert!(container.is_due(due));
+
+        container.suspend();
+        assert_eq!(container.next_update(), None);
+    }
+
+    #[test]
+    fn test_unmounted_widget_is_not_suspended() {
+        let probe = Probe::default();
+        let mut container = WidgetContainer::new("probe".to_string(), Box::new(probe.clone()));
+
+        container.suspend();
+        assert!(!container.is_suspended());
+        assert_eq!(probe.suspends.load(Ordering::SeqCst), 0);
+    }
+
+    struct Boxed;
+
+    impl Widget for Boxed {
+        fn render(&mut self, area: Rect, buf: &mut Buffer) {
+            use ratatui::widgets::{Block, Borders, Paragraph, Widget as _};
+            Paragraph::new("up  3 ▁▂▃\nidle")
+                .block(Block::default().borders(Borders::ALL).title(" Load "))
+                .render(area, buf);
+        }
+    }
+
+    #[test]
+    fn test_stale_for() {
+        let now = Instant::now();
+        let status = |age: u64, interval: u64| DataStatus {
+            refreshing: false,
+            last_data: now - Duration::from_secs(age),
+            interval: Duration::from_secs(interval),
+            retry_at: None,
+        };
+        assert_eq!(status(4, 1).stale_for(now), None);
+        assert_eq!(status(12, 1).stale_for(now), Some(Duration::from_secs(12)));
+        assert_eq!(status(12, 5).stale_for(now), None);
+        assert!(status(16, 5).stale_for(now).is_some());
+
+        let retry = |millis: u64| DataStatus {
+            retry_at: Some(now + Duration::from_millis(millis)),
+            ..status(0, 1)
+        };
+        assert_eq!(status(0, 1).retry_in(now), None);
+        assert_eq!(retry(0).retry_in(now), None);
+        assert_eq!(retry(1).retry_in(now), Some(1));
+        assert_eq!(retry(5_000).retry_in(now), Some(5));
+        assert_eq!(retry(5_001).retry_in(now), Some(6));
+        assert_eq!(retry(5_000).retry_in(now + Duration::from_secs(6)), None);
+    }
+
+    /// Boxed, with data that stopped arriving 12 seconds ago
+    struct Stalled;
+
+    impl Widget for Stalled {
+        fn render(&mut self, area: Rect, buf: &mut Buffer) {
+            Boxed.render(area, buf);
+        }
+
+        fn data_status(&self) -> Option<DataStatus> {
+            Some(DataStatus {
+                refreshing: false,
+                last_data: Instant::now() - Duration::from_secs(12),
+                interval: Duration::from_secs(1),
+                retry_at: None,
+            })
+        }
+    }
+
+    #[test]
+    fn test_stale_marker_in_title() {
+        let mut container = WidgetContainer::new("stalled".to_string(), Box::new(Stalled));
+        let area = Rect::new(0, 0, 24, 3);
+        let mut buf = Buffer::empty(area);
+        container.render(area, &mut buf);
+        let title: String = (0..area.width).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(title, "┌ Load stale (12s) ────┐");
+        assert_eq!(buf[(7, 0)].fg, Color::Yellow);
+        assert_eq!(
+            container.summary(20),
+            "Load | up 3 | idle (stale, last updated 12s ago)"
+        );
+    }
+
+    /// Stalled, with a source that failed and is retried in 30 seconds
+    struct Failing;
+
+    impl Widget for Failing {
+        fn render(&mut self, area: Rect, buf: &mut Buffer) {
+            Boxed.render(area, buf);
+        }
+
+        fn data_status(&self) -> Option<DataStatus> {
+            Some(DataStatus {
+                retry_at: Some(Instant::now() + Duration::from_millis(29_500)),
+                ..Stalled.data_status().unwrap()
+            })
+        }
+    }
+
+    #[test]
+    fn test_retry_marker_replaces_stale_marker() {
+        let mut container = WidgetContainer::new("failing".to_string(), Box::new(Failing));
+        let area = Rect::new(0, 0, 26, 3);
+        let mut buf = Buffer::empty(area);
+        container.render(area, &mut buf);
+        let title: String = (0..area.width).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(title, "┌ Load retrying in 30s ──┐");
+        assert_eq!(buf[(7, 0)].fg, Color::Red);
+        assert_eq!(
+            container.summary(20),
+            "Load | up 3 | idle (failing, retrying in 30s)"
+        );
+    }
+
+    #[test]
+    fn test_summary_falls_back_to_rendered_text() {
+        let mut container = WidgetContainer::new("boxed".to_string(), Box::new(Boxed));
+        assert_eq!(container.summary(20), "Load | up 3 | idle");
+    }
+
+    /// Records the events it gets; handles clicks but not the wheel
+    #[derive(Default, Clone)]
+    struct Clickable {
+        events: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl Widget for Clickable {
+        fn on_event(&mut self, event: Event) -> EventResult {
+            let (text, result) = match event {
+                Event::Mouse(mouse)
+                    if matches!(
+                        mouse.kind,
+                        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                    ) =>
+                {
+                    (
+                        format!("scroll {},{}", mouse.column, mouse.row),
+                        EventResult::Ignored,
+                    )
+                }
+                Event::Mouse(mouse) => (
+                    format!("click {},{}", mouse.column, mouse.row),
+                    EventResult::Consumed,
+                ),
+                Event::Action(action) => (action, EventResult::Consumed),
+                _ => return EventResult::Ignored,
+            };
+            self.events.lock().unwrap().push(text);
+            result
+        }
+
+        fn render(&mut self, _area: Rect, _buf: &mut Buffer) {}
+
+        fn actions(&self) -> Vec<Action> {
+            vec![
+                Action::new("down", &["j"], "Move down"),
+                Action::new("kill", &["K"], "Kill").mutating(),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_handle_mouse() {
+        let widget = Clickable::default();
+        let mut container = WidgetContainer::new("list".to_string(), Box::new(widget.clone()));
+        let area = Rect::new(10, 5, 20, 10);
+        let mouse = |kind, column, row| MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        };
+
+        let click = mouse(MouseEventKind::Down(MouseButton::Left), 12, 8);
+        assert_eq!(container.handle_mouse(click, area), EventResult::Consumed);
+        let scroll = mouse(MouseEventKind::ScrollDown, 11, 6);
+        assert_eq!(container.handle_mouse(scroll, area), EventResult::Consumed);
+        // No `up` action to fall back to
+        let scroll = mouse(MouseEventKind::ScrollUp, 11, 6);
+        assert_eq!(container.handle_mouse(scroll, area), EventResult::Ignored);
+
+        assert_eq!(
+            *widget.events.lock().unwrap(),
+            ["click 2,3", "scroll 1,1", "down", "scroll 1,1"]
+        );
+    }
+
+    #[test]
+    fn test_read_only_drops_mutating_actions() {
+        let widget = Clickable::default();
+        let mut container = WidgetContainer::new("list".to_string(), Box::new(widget.clone()));
+        let press = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+
+        crate::set_read_only(true);
+        let names: Vec<_> = container.actions().iter().map(|a| a.name).collect();
+        let keys: Vec<_> = container
+            .key_bindings()
+            .into_iter()
+            .map(|b| b.keys)
+            .collect();
+        let pressed = container.handle_event(press('K'));
+        let requested = container.handle_event(Event::Action("kill".to_string()));
+        container.handle_event(press('j'));
+        crate::set_read_only(false);
+
+        assert_eq!(names, ["down"]);
+        assert_eq!(keys, ["j"]);
+        assert_eq!(
+            (pressed, requested),
+            (EventResult::Ignored, EventResult::Ignored)
+        );
+        assert_eq!(*widget.events.lock().unwrap(), ["down"]);
+
+        container.handle_event(press('K'));
+        assert_eq!(*widget.events.lock().unwrap(), ["down", "kill"]);
+    }
+}
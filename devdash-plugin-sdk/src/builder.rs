@@ -0,0 +1,279 @@
+// devdash-plugin-sdk/src/builder.rs
+//! Widgets assembled from a state value and a few callbacks.
+use crossterm::event::KeyEvent;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Widget as _},
+};
+use std::time::Duration;
+
+use devdash_core::{
+    Event, EventResult, KeyBinding, Widget, WidgetSettings, plugin::settings_from_event,
+    widget::DEFAULT_UPDATE_INTERVAL,
+};
+
+/// Border color for a widget's focus state, matching the built-in widgets
+///
+/// Comes from the [`Theme`](devdash_core::Theme) of the process the widget
+/// runs in; a plugin library has its own copy, which keeps the default.
+pub fn focus_color(focused: bool) -> Color {
+    devdash_core::theme().focus_color(focused)
+}
+
+type TitleFn<S> = Box<dyn Fn(&S) -> String + Send + Sync>;
+type UpdateFn<S> = Box<dyn FnMut(&mut S, Duration) + Send + Sync>;
+type KeyFn<S> = Box<dyn FnMut(&mut S, KeyEvent) -> EventResult + Send + Sync>;
+type SettingsFn<S> = Box<dyn FnMut(&mut S, &WidgetSettings) + Send + Sync>;
+type DrawFn<S> = Box<dyn Fn(&S, Rect, &mut Buffer) + Send + Sync>;
+type SummaryFn<S> = Box<dyn Fn(&S) -> String + Send + Sync>;
+
+/// Builds a [`Widget`] from a state value and callbacks
+///
+/// The widget draws a bordered block, highlighted while focused like the
+/// built-in widgets, and calls `draw` for the area inside it. Only the
+/// callbacks given are wired up; a widget without `on_update` is never
+/// polled.
+///
+/// # Example
+/// ```
+/// use devdash_plugin_sdk::{EventResult, WidgetBuilder};
+/// use crossterm::event::KeyCode;
+/// use ratatui::widgets::{Paragraph, Widget as _};
+///
+/// let widget = WidgetBuilder::new("Counter", 0u64)
+///     .on_update(|count, _delta| *count += 1)
+///     .on_key(|count, key| match key.code {
+///         KeyCode::Char('r') => {
+///             *count = 0;
+///             EventResult::Consumed
+///         }
+///         _ => EventResult::Ignored,
+///     })
+///     .draw(|count, area, buf| Paragraph::new(count.to_string()).render(area, buf))
+///     .build();
+/// ```
+pub struct WidgetBuilder<S> {
+    widget: BuiltWidget<S>,
+}
+
+impl<S: Send + Sync + 'static> WidgetBuilder<S> {
+    pub fn new(title: impl Into<String>, state: S) -> Self {
+        let title = title.into();
+        Self {
+            widget: BuiltWidget {
+                state,
+                title: Box::new(move |_| title.clone()),
+                update: None,
+                key: None,
+                settings: None,
+                draw: Box::new(|_, _, _| {}),
+                summary: None,
+                key_bindings: Vec::new(),
+                interval: DEFAULT_UPDATE_INTERVAL,
+            },
+        }
+    }
+
+    /// Title computed from the state, replacing the fixed one
+    pub fn title(mut self, title: impl Fn(&S) -> String + Send + Sync + 'static) -> Self {
+        self.widget.title = Box::new(title);
+        self
+    }
+
+    /// Called every `interval` with the time since the last call
+    pub fn on_update(
+        mut self,
+        update: impl FnMut(&mut S, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.widget.update = Some(Box::new(update));
+        self
+    }
+
+    /// How often `on_update` runs; 100ms by default
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.widget.interval = interval;
+        self
+    }
+
+    /// Handle key presses while focused
+    pub fn on_key(
+        mut self,
+        key: impl FnMut(&mut S, KeyEvent) -> EventResult + Send + Sync + 'static,
+    ) -> Self {
+        self.widget.key = Some(Box::new(key));
+        self
+    }
+
+    /// Apply the widget's settings from the config; export the plugin with
+    /// [`Capabilities::CONFIG`](crate::Capabilities::CONFIG) to receive them
+    pub fn on_settings(
+        mut self,
+        settings: impl FnMut(&mut S, &WidgetSettings) + Send + Sync + 'static,
+    ) -> Self {
+        self.widget.settings = Some(Box::new(settings));
+        self
+    }
+
+    /// Draw the state inside the border
+    pub fn draw(mut self, draw: impl Fn(&S, Rect, &mut Buffer) + Send + Sync + 'static) -> Self {
+        self.widget.draw = Box::new(draw);
+        self
+    }
+
+    /// One-line description for accessible mode
+    pub fn summary(mut self, summary: impl Fn(&S) -> String + Send + Sync + 'static) -> Self {
+        self.widget.summary = Some(Box::new(summary));
+        self
+    }
+
+    /// Keys listed in help and `devdash keys`
+    pub fn key_bindings(mut self, bindings: Vec<KeyBinding>) -> Self {
+        self.widget.key_bindings = bindings;
+        self
+    }
+
+    pub fn build(self) -> BuiltWidget<S> {
+        self.widget
+    }
+}
+
+/// A widget made by [`WidgetBuilder`]
+pub struct BuiltWidget<S> {
+    state: S,
+    title: TitleFn<S>,
+    update: Option<UpdateFn<S>>,
+    key: Option<KeyFn<S>>,
+    settings: Option<SettingsFn<S>>,
+    draw: DrawFn<S>,
+    summary: Option<SummaryFn<S>>,
+    key_bindings: Vec<KeyBinding>,
+    interval: Duration,
+}
+
+impl<S> BuiltWidget<S> {
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S: Send + Sync> Widget for BuiltWidget<S> {
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(update) = &mut self.update {
+            update(&mut self.state, delta);
+        }
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if let Some(apply) = &mut self.settings
+            && let Some(settings) = settings_from_event(&event)
+        {
+            apply(&mut self.state, &settings);
+            return EventResult::Consumed;
+        }
+
+        match (event, &mut self.key) {
+            (Event::Key(key), Some(handle)) => handle(&mut self.state, key),
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", (self.title)(&self.state)))
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+        (self.draw)(&self.state, inner, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        self.update.is_some()
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn key_bindings(&self) -> Vec<KeyBinding> {
+        self.key_bindings.clone()
+    }
+
+    fn summary(&self) -> Option<String> {
+        self.summary.as_ref().map(|summary| summary(&self.state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn counter() -> BuiltWidget<u32> {
+        WidgetBuilder::new("Counter", 0)
+            .title(|count| format!("Counter {}", count))
+            .on_update(|count, _| *count += 1)
+            .on_key(|count, key| match key.code {
+                KeyCode::Char('r') => {
+                    *count = 0;
+                    EventResult::Consumed
+                }
+                _ => EventResult::Ignored,
+            })
+            .build()
+    }
+
+    #[test]
+    fn test_callbacks() {
+        let mut widget = counter();
+        assert!(widget.needs_update());
+        widget.on_update(Duration::ZERO);
+        widget.on_update(Duration::ZERO);
+        assert_eq!(*widget.state(), 2);
+
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        assert_eq!(widget.on_event(key('x')), EventResult::Ignored);
+        assert_eq!(widget.on_event(key('r')), EventResult::Consumed);
+        assert_eq!(*widget.state(), 0);
+    }
+
+    #[test]
+    fn test_settings() {
+        let mut widget = WidgetBuilder::new("Counter", 0)
+            .on_settings(|count, settings| {
+                *count = settings["start"].as_integer().unwrap_or(0) as u32;
+            })
+            .build();
+        let payload = b"start = 7".to_vec();
+        assert_eq!(
+            widget.on_event(Event::Custom(
+                devdash_core::plugin::SETTINGS_EVENT.to_string(),
+                payload
+            )),
+            EventResult::Consumed
+        );
+        assert_eq!(*widget.state(), 7);
+    }
+
+    #[test]
+    fn test_focus_aware_border() {
+        let mut widget = counter();
+        widget.on_update(Duration::ZERO);
+        let area = Rect::new(0, 0, 20, 3);
+        let mut buf = Buffer::empty(area);
+
+        widget.render_focused(area, &mut buf, false);
+        assert_eq!(buf[(0, 0)].fg, Color::DarkGray);
+        let top: String = (0..20).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(top.contains(" Counter 1 "), "{}", top);
+
+        widget.render_focused(area, &mut buf, true);
+        assert_eq!(buf[(0, 0)].fg, Color::Yellow);
+    }
+}
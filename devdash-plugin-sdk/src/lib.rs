@@ -1,12 +1,24 @@
-pub use devdash_core::{Event, EventBus, EventResult, Size, Widget};
+pub mod builder;
 
-pub const PLUGIN_API_VERSION: u32 = 1;
+pub use builder::{BuiltWidget, WidgetBuilder, focus_color};
+pub use devdash_core::{
+    Event, EventBus, EventResult, KeyBinding, Size, Widget, WidgetSettings, parse_settings,
+};
+
+pub use devdash_core::plugin::{
+    Capabilities, PLUGIN_API_VERSION, Permissions, SETTINGS_EVENT, settings_from_event,
+};
 
 #[repr(C)]
 pub struct PluginMetadata {
     pub api_version: u32,
     pub name: *const u8,
     pub name_len: usize,
+    pub capabilities: Capabilities,
+    pub permissions: Permissions,
+    /// Paths the plugin reads or writes, one per line
+    pub paths: *const u8,
+    pub paths_len: usize,
 }
 
 /// FFI-safe representation of a fat pointer (trait object)
@@ -28,23 +40,37 @@ pub struct PluginApi {
     pub metadata: PluginMetadata,
 }
 
+/// Export a widget type implementing `Default` as the plugin `$name`
+///
+/// An optional third argument declares the plugin's [`Capabilities`], e.g.
+/// `export_plugin!(MyWidget, "my_widget", Capabilities::MOUSE | Capabilities::STATE)`.
+/// A fourth declares the [`Permissions`] it needs, optionally followed by
+/// the paths it reads or writes; the user is asked to grant them before the
+/// plugin first loads. Nothing stops a loaded plugin from doing more, so
+/// declare everything it does:
+/// `export_plugin!(MyWidget, "my_widget", Capabilities::NONE, Permissions::EXEC, ["/var/log/app"])`.
 #[macro_export]
 macro_rules! export_plugin {
-    ($widget_type:ty, $name:expr) => {
+    (@create $name:expr, $capabilities:expr, $permissions:expr, $paths:expr, $create:expr) => {
         // Metadata function
         #[unsafe(no_mangle)]
         pub extern "C" fn devdash_plugin_metadata() -> $crate::PluginMetadata {
+            let paths: &str = $paths;
             $crate::PluginMetadata {
                 api_version: $crate::PLUGIN_API_VERSION,
                 name: $name.as_ptr(),
                 name_len: $name.len(),
+                capabilities: $capabilities,
+                permissions: $permissions,
+                paths: paths.as_ptr(),
+                paths_len: paths.len(),
             }
         }
 
         // Create function - allocates with plugin's allocator
         #[unsafe(no_mangle)]
         pub extern "C" fn devdash_plugin_create() -> $crate::FatPointer {
-            let widget: Box<dyn $crate::Widget> = Box::new(<$widget_type>::default());
+            let widget: Box<dyn $crate::Widget> = Box::new($create);
             let ptr = Box::into_raw(widget);
             // Split fat pointer into data and vtable components
             unsafe {
@@ -69,4 +95,54 @@ macro_rules! export_plugin {
             }
         }
     };
+    ($widget_type:ty, $name:expr $(,)?) => {
+        $crate::export_plugin!($widget_type, $name, $crate::Capabilities::NONE);
+    };
+    ($widget_type:ty, $name:expr, $capabilities:expr $(,)?) => {
+        $crate::export_plugin!(
+            $widget_type,
+            $name,
+            $capabilities,
+            $crate::Permissions::NONE
+        );
+    };
+    ($widget_type:ty, $name:expr, $capabilities:expr, $permissions:expr $(, [$($path:literal),* $(,)?])? $(,)?) => {
+        $crate::export_plugin!(
+            @create $name,
+            $capabilities,
+            $permissions,
+            concat!($($($path, "\n"),*)?),
+            <$widget_type>::default()
+        );
+    };
+}
+
+/// Export the widget `$create` evaluates to as the plugin `$name`, with
+/// optional [`Capabilities`], [`Permissions`] and paths as for
+/// [`export_plugin!`]
+///
+/// For widgets made with [`WidgetBuilder`]:
+///
+/// ```
+/// use devdash_plugin_sdk::*;
+///
+/// export_widget!("counter", WidgetBuilder::new("Counter", 0u64).build());
+/// ```
+#[macro_export]
+macro_rules! export_widget {
+    ($name:expr, $create:expr $(,)?) => {
+        $crate::export_widget!($name, $create, $crate::Capabilities::NONE);
+    };
+    ($name:expr, $create:expr, $capabilities:expr $(,)?) => {
+        $crate::export_widget!($name, $create, $capabilities, $crate::Permissions::NONE);
+    };
+    ($name:expr, $create:expr, $capabilities:expr, $permissions:expr $(, [$($path:literal),* $(,)?])? $(,)?) => {
+        $crate::export_plugin!(
+            @create $name,
+            $capabilities,
+            $permissions,
+            concat!($($($path, "\n"),*)?),
+            $create
+        );
+    };
 }
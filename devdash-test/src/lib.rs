@@ -0,0 +1,270 @@
+// devdash-test/src/lib.rs
+//! Render-testing helpers for devdash widgets.
+//!
+//! [`WidgetHarness`] drives a widget through its lifecycle without a real
+//! terminal: mount it, feed it key presses and fake time, and render it into
+//! ratatui's `TestBackend`. Rendered frames can be compared against text
+//! snapshots stored next to the tests with [`assert_snapshot!`].
+//!
+//! # Example
+//! ```rust
+//! use devdash_test::WidgetHarness;
+//! # use devdash_core::Widget;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! # struct Hello;
+//! # impl Widget for Hello {
+//! #     fn render(&mut self, area: Rect, buf: &mut Buffer) {
+//! #         buf.set_string(area.x, area.y, "hello", ratatui::style::Style::default());
+//! #     }
+//! # }
+//!
+//! let mut harness = WidgetHarness::new(Hello, 10, 1);
+//! harness.mount();
+//! assert_eq!(harness.render_text(), "hello");
+//! ```
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use devdash_core::{Event, EventBus, EventResult, Keymap, Widget};
+use ratatui::{Terminal, backend::TestBackend, buffer::Buffer};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Set to `1` to rewrite snapshots instead of comparing against them
+pub const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// Event bus with live collection disabled
+///
+/// Widgets built with it render the events published on the bus instead of
+/// polling the system, which keeps their output deterministic.
+pub fn offline_bus() -> EventBus {
+    let bus = EventBus::new();
+    bus.set_live(false);
+    bus
+}
+
+/// Drives a single widget and renders it off-screen
+pub struct WidgetHarness<W: Widget> {
+    widget: W,
+    terminal: Terminal<TestBackend>,
+    focused: bool,
+}
+
+impl<W: Widget> WidgetHarness<W> {
+    /// Wrap `widget`, rendering into a `width` x `height` buffer
+    pub fn new(widget: W, width: u16, height: u16) -> Self {
+        let terminal =
+            Terminal::new(TestBackend::new(width, height)).expect("TestBackend never fails");
+        Self {
+            widget,
+            terminal,
+            focused: true,
+        }
+    }
+
+    /// Render as focused (the default) or unfocused
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Call the widget's `on_mount` hook
+    pub fn mount(&mut self) -> &mut Self {
+        self.widget.on_mount();
+        self
+    }
+
+    /// Pretend `delta` has elapsed since the last update
+    pub fn advance(&mut self, delta: Duration) -> &mut Self {
+        if self.widget.needs_update() {
+            self.widget.on_update(delta);
+        }
+        self
+    }
+
+    /// Send an input event to the widget
+    pub fn send(&mut self, event: Event) -> EventResult {
+        self.widget.on_event(event)
+    }
+
+    /// Send a key press without modifiers, as the action it is bound to by
+    /// default if any
+    pub fn key(&mut self, code: KeyCode) -> EventResult {
+        let keymap = Keymap::new(&self.widget.actions());
+        self.send(keymap.translate(Event::Key(KeyEvent::new(code, KeyModifiers::NONE))))
+    }
+
+    /// Change the size of the render area
+    pub fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+        self.terminal.backend_mut().resize(width, height);
+        // Force the next draw to pick up the new size
+        let _ = self.terminal.autoresize();
+        self
+    }
+
+    /// Render a frame and return the resulting buffer
+    pub fn render(&mut self) -> Buffer {
+        let (widget, focused) = (&mut self.widget, self.focused);
+        self.terminal
+            .draw(|frame| {
+                let area = frame.area();
+                widget.render_focused(area, frame.buffer_mut(), focused);
+            })
+            .expect("TestBackend never fails")
+            .buffer
+            .clone()
+    }
+
+    /// Render a frame as text, one line per row with trailing spaces removed
+    pub fn render_text(&mut self) -> String {
+        buffer_text(&self.render())
+    }
+
+    /// The wrapped widget
+    pub fn widget(&self) -> &W {
+        &self.widget
+    }
+
+    /// The wrapped widget, mutably
+    pub fn widget_mut(&mut self) -> &mut W {
+        &mut self.widget
+    }
+}
+
+/// Text content of a buffer, one line per row with trailing spaces removed
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let line: String = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compare `actual` against the snapshot `<dir>/<name>.txt`
+///
+/// A missing snapshot is written and the check passes. Set
+/// `UPDATE_SNAPSHOTS=1` to overwrite snapshots that no longer match.
+///
+/// # Panics
+/// Panics if the snapshot differs or cannot be read or written.
+pub fn check_snapshot(dir: &Path, name: &str, actual: &str) {
+    let path = snapshot_path(dir, name);
+    let update = std::env::var(UPDATE_ENV).is_ok_and(|v| v == "1");
+
+    if update || !path.exists() {
+        fs::create_dir_all(dir).expect("failed to create snapshot directory");
+        fs::write(&path, format!("{actual}\n")).expect("failed to write snapshot");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).expect("failed to read snapshot");
+    let expected = expected.strip_suffix('\n').unwrap_or(&expected);
+    if expected != actual {
+        panic!(
+            "snapshot '{}' does not match ({}=1 to update)\n--- expected\n{}\n--- actual\n{}\n",
+            path.display(),
+            UPDATE_ENV,
+            expected,
+            actual
+        );
+    }
+}
+
+fn snapshot_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.txt"))
+}
+
+/// Render a harness and compare it with `tests/snapshots/<name>.txt`
+///
+/// The path is relative to the manifest directory of the crate running the
+/// test.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($harness:expr, $name:expr) => {{
+        let text = $harness.render_text();
+        $crate::check_snapshot(
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots"),
+            $name,
+            &text,
+        );
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{layout::Rect, style::Style};
+
+    /// Counts updates and key presses, and prints them
+    #[derive(Default)]
+    struct Counter {
+        mounted: bool,
+        elapsed: Duration,
+        keys: usize,
+    }
+
+    impl Widget for Counter {
+        fn on_mount(&mut self) {
+            self.mounted = true;
+        }
+
+        fn on_update(&mut self, delta: Duration) {
+            self.elapsed += delta;
+        }
+
+        fn on_event(&mut self, event: Event) -> EventResult {
+            match event {
+                Event::Key(_) => {
+                    self.keys += 1;
+                    EventResult::Consumed
+                }
+                _ => EventResult::Ignored,
+            }
+        }
+
+        fn render(&mut self, area: Rect, buf: &mut Buffer) {
+            let text = format!("{}ms {}k", self.elapsed.as_millis(), self.keys);
+            buf.set_string(area.x, area.y, text, Style::default());
+        }
+
+        fn needs_update(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_harness_lifecycle() {
+        let mut harness = WidgetHarness::new(Counter::default(), 12, 2);
+        harness.mount().advance(Duration::from_millis(250));
+        assert_eq!(harness.key(KeyCode::Char('x')), EventResult::Consumed);
+
+        assert!(harness.widget().mounted);
+        assert_eq!(harness.render_text(), "250ms 1k\n");
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut harness = WidgetHarness::new(Counter::default(), 4, 1);
+        assert_eq!(harness.render_text(), "0ms");
+
+        harness.resize(10, 1);
+        assert_eq!(harness.render_text(), "0ms 0k");
+    }
+
+    #[test]
+    fn test_check_snapshot() {
+        let dir = std::env::temp_dir().join(format!("devdash-test-{}", std::process::id()));
+        check_snapshot(&dir, "frame", "a\nb");
+        check_snapshot(&dir, "frame", "a\nb");
+
+        let mismatch = std::panic::catch_unwind(|| check_snapshot(&dir, "frame", "c"));
+        let _ = fs::remove_dir_all(&dir);
+        assert!(mismatch.is_err());
+    }
+}
@@ -0,0 +1,45 @@
+// devdash-widgets/benches/process.rs
+//! Sorting the process table of a busy server into the visible rows.
+use criterion::{Criterion, criterion_group, criterion_main};
+use devdash_core::{BusEvent, Event, Widget};
+use devdash_test::offline_bus;
+use devdash_widgets::{ProcessInfo, ProcessWidget};
+use std::{hint::black_box, time::Duration};
+
+const PROCESSES: u32 = 10_000;
+
+fn bench_sort(c: &mut Criterion) {
+    let bus = offline_bus();
+    let mut widget = ProcessWidget::new(bus.clone(), Duration::from_secs(1));
+    widget.on_mount();
+    for pid in 1..=PROCESSES {
+        bus.publish(BusEvent::new(
+            "system.process.top",
+            ProcessInfo {
+                pid,
+                name: format!("worker-{}", pid % 64),
+                cpu_percent: (pid * 7919 % 1000) as f32 / 10.0,
+                memory_bytes: u64::from(pid * 104_729 % 65_536) * 4096,
+                start_time: 0,
+                cwd: Some(format!("/srv/app/{}", pid % 16).into()),
+                zombie: false,
+                open_files: Some(16),
+                open_files_limit: Some(1024),
+            },
+        ));
+    }
+    widget.on_update(Duration::ZERO);
+
+    let mut sorts = ["sort_cpu", "sort_memory", "sort_name"].into_iter().cycle();
+    c.bench_function("sort_10k_processes", |b| {
+        b.iter(|| widget.on_event(Event::Action(black_box(sorts.next().unwrap()).to_string())))
+    });
+
+    widget.on_event(Event::Action("group".to_string()));
+    c.bench_function("group_10k_processes", |b| {
+        b.iter(|| widget.on_event(Event::Action(black_box(sorts.next().unwrap()).to_string())))
+    });
+}
+
+criterion_group!(benches, bench_sort);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+// devdash-widgets/benches/render.rs
+//! Full-frame render of the six built-in widgets.
+use criterion::{Criterion, criterion_group, criterion_main};
+use devdash_core::{Constraint, EventBus, Layout, LayoutItem, Widget};
+use devdash_widgets::{
+    CpuWidget, DiskWidget, GitWidget, MemoryWidget, NetworkWidget, ProcessWidget,
+};
+use ratatui::{buffer::Buffer, layout::Rect};
+use std::time::Duration;
+
+/// Two rows of three widgets, like the `balanced` dashboard
+fn dashboard_layout() -> Layout {
+    let row = || {
+        LayoutItem::nested(Layout::horizontal(vec![
+            LayoutItem::widget(Constraint::Flex(1)),
+            LayoutItem::widget(Constraint::Flex(1)),
+            LayoutItem::widget(Constraint::Flex(1)),
+        ]))
+    };
+    Layout::vertical(vec![row(), row()])
+}
+
+fn bench_full_frame(c: &mut Criterion) {
+    let bus = EventBus::new();
+    let interval = Duration::from_secs(1);
+    let mut widgets: Vec<Box<dyn Widget>> = vec![
+        Box::new(CpuWidget::new(bus.clone(), interval)),
+        Box::new(MemoryWidget::new(bus.clone(), interval)),
+        Box::new(DiskWidget::new(bus.clone(), interval)),
+        Box::new(NetworkWidget::new(bus.clone(), interval)),
+        Box::new(ProcessWidget::new(bus.clone(), interval)),
+        Box::new(GitWidget::new(bus.clone(), interval)),
+    ];
+    for widget in widgets.iter_mut() {
+        widget.on_mount();
+    }
+
+    let layout = dashboard_layout();
+    let area = Rect::new(0, 0, 200, 60);
+    let mut buf = Buffer::empty(area);
+
+    c.bench_function("render_dashboard_6_widgets", |b| {
+        b.iter(|| {
+            buf.reset();
+            let areas = layout.calculate(area);
+            for (i, (widget, widget_area)) in widgets.iter_mut().zip(areas).enumerate() {
+                widget.render_focused(widget_area, &mut buf, i == 0);
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_full_frame);
+criterion_main!(benches);
@@ -0,0 +1,634 @@
+// devdash-widgets/src/backup.rs
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WorkerPool, autostart,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    common::{focus_color, format_relative_time, parse_age},
+    platform::shell,
+};
+
+/// Targets are checked at most this often, however short the widget's
+/// interval; backups run hours apart and checking one may be slow
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How recent a target's last successful backup is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Freshness {
+    /// The backup time couldn't be found
+    Unknown,
+    Fresh,
+    /// Older than the target's `warn` age
+    Overdue,
+    /// Older than the target's `critical` age
+    Stale,
+}
+
+/// The last successful backup of one target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupStatus {
+    pub name: String,
+    pub last_success: Option<SystemTime>,
+    pub freshness: Freshness,
+    /// Why the backup time couldn't be found
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// One thing that gets backed up, as written in the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BackupTarget {
+    pub name: String,
+    /// File or directory written by each successful backup, e.g. an rsync
+    /// log; its modification time is the backup time
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Shell command printing the time of the latest backup, e.g.
+    /// `restic snapshots --json --latest 1`; the newest timestamp in its
+    /// output is the backup time
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Overrides the widget's `warn`
+    #[serde(default, deserialize_with = "optional_age")]
+    pub warn: Option<Duration>,
+    /// Overrides the widget's `critical`
+    #[serde(default, deserialize_with = "optional_age")]
+    pub critical: Option<Duration>,
+}
+
+/// Backup widget settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct BackupOptions {
+    pub targets: Vec<BackupTarget>,
+    /// Age at which a backup is overdue, e.g. `"26h"`
+    #[serde(deserialize_with = "age")]
+    pub warn: Duration,
+    /// Age at which a backup is stale
+    #[serde(deserialize_with = "age")]
+    pub critical: Duration,
+}
+
+impl Default for BackupOptions {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            warn: Duration::from_secs(26 * 3600),
+            critical: Duration::from_secs(3 * 86_400),
+        }
+    }
+}
+
+impl BackupOptions {
+    /// Check each target has exactly one of `path` and `command`
+    fn validate(self) -> Result<Self, String> {
+        for target in &self.targets {
+            if target.path.is_some() == target.command.is_some() {
+                return Err(format!(
+                    "backup target '{}' needs either a path or a command",
+                    target.name
+                ));
+            }
+        }
+        Ok(self)
+    }
+}
+
+fn age<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    parse_age(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+fn optional_age<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    age(deserializer).map(Some)
+}
+
+/// The newest timestamp in `text`: `2024-05-01T02:00:03+02:00` or
+/// `2024-05-01 02:00:03` (local time), with optional fractional seconds,
+/// or the whole of `text` as Unix seconds
+///
+/// Covers the JSON of `restic snapshots` and `borg list --json` as well as
+/// plain `date` output such as `date -Is` or `date +%s`.
+pub fn latest_timestamp(text: &str) -> Option<SystemTime> {
+    let trimmed = text.trim();
+    if !trimmed.is_empty() && trimmed.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(UNIX_EPOCH + Duration::from_secs(trimmed.parse().ok()?));
+    }
+
+    const SHAPE: &[u8] = b"0000-00-00 00:00:00";
+    let bytes = text.as_bytes();
+    let starts_timestamp = |at: usize| {
+        bytes.len() >= at + SHAPE.len()
+            && SHAPE
+                .iter()
+                .zip(&bytes[at..])
+                .all(|(shape, b)| match shape {
+                    b'0' => b.is_ascii_digit(),
+                    b' ' => *b == b' ' || *b == b'T',
+                    other => b == other,
+                })
+    };
+
+    (0..bytes.len())
+        .filter(|&at| starts_timestamp(at))
+        .filter_map(|at| {
+            // Fractional seconds and the offset, if any
+            let end = text[at + SHAPE.len()..]
+                .find(|c: char| !(c.is_ascii_digit() || ".:+-Z".contains(c)))
+                .map_or(text.len(), |len| at + SHAPE.len() + len);
+            let candidate = text[at..end].replacen(' ', "T", 1);
+            match DateTime::parse_from_rfc3339(&candidate) {
+                Ok(time) => Some(SystemTime::from(time)),
+                Err(_) => {
+                    let naive =
+                        NaiveDateTime::parse_from_str(&candidate, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+                    Local
+                        .from_local_datetime(&naive)
+                        .earliest()
+                        .map(SystemTime::from)
+                }
+            }
+        })
+        .max()
+}
+
+/// Modification time of `path`, or for a directory the newest of it and
+/// its entries
+fn modified(path: &Path) -> Result<SystemTime, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut newest = metadata
+        .modified()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    if metadata.is_dir()
+        && let Ok(entries) = fs::read_dir(path)
+    {
+        for modified in entries
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        {
+            newest = newest.max(modified);
+        }
+    }
+    Ok(newest)
+}
+
+/// Run `command` and find the newest timestamp it printed
+fn command_time(command: &str) -> Result<SystemTime, String> {
+    if !autostart() {
+        return Err("not run until the project is trusted".to_string());
+    }
+    let output = shell(command)
+        .output()
+        .map_err(|e| format!("can't run command: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.lines().find(|line| !line.trim().is_empty()) {
+            Some(line) => line.trim().to_string(),
+            None => format!("command failed ({})", output.status),
+        });
+    }
+    latest_timestamp(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "no timestamp in command output".to_string())
+}
+
+/// How fresh a backup taken at `last_success` is as of `now`
+pub fn freshness(
+    last_success: Option<SystemTime>,
+    warn: Duration,
+    critical: Duration,
+    now: SystemTime,
+) -> Freshness {
+    let Some(last_success) = last_success else {
+        return Freshness::Unknown;
+    };
+    let age = now.duration_since(last_success).unwrap_or_default();
+    if age >= critical {
+        Freshness::Stale
+    } else if age >= warn {
+        Freshness::Overdue
+    } else {
+        Freshness::Fresh
+    }
+}
+
+/// Check every target (runs on a worker thread)
+fn check_targets(options: &mut BackupOptions) -> Vec<BackupStatus> {
+    let now = SystemTime::now();
+    options
+        .targets
+        .iter()
+        .map(|target| {
+            let time = match (&target.path, &target.command) {
+                (Some(path), _) => modified(path),
+                (None, Some(command)) => command_time(command),
+                (None, None) => Err("no path or command".to_string()),
+            };
+            let (last_success, error) = match time {
+                Ok(time) => (Some(time), None),
+                Err(e) => (None, Some(e)),
+            };
+            BackupStatus {
+                name: target.name.clone(),
+                last_success,
+                freshness: freshness(
+                    last_success,
+                    target.warn.unwrap_or(options.warn),
+                    target.critical.unwrap_or(options.critical),
+                    now,
+                ),
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Targets that became overdue or stale since `previous`
+pub fn newly_stale<'a>(
+    statuses: &'a [BackupStatus],
+    previous: &'a [BackupStatus],
+) -> impl Iterator<Item = &'a BackupStatus> {
+    let before: HashMap<&str, Freshness> = previous
+        .iter()
+        .map(|status| (status.name.as_str(), status.freshness))
+        .collect();
+    statuses.iter().filter(move |status| {
+        status.freshness >= Freshness::Overdue
+            && before
+                .get(status.name.as_str())
+                .is_none_or(|old| *old < status.freshness)
+    })
+}
+
+/// Backup freshness widget
+///
+/// Shows how long ago each configured target was last backed up, from the
+/// modification time of a file the backup writes or the output of a
+/// command such as `restic snapshots`. Ages past a target's `warn` and
+/// `critical` thresholds are shown in the warning and critical colors.
+/// Targets are checked every [`CHECK_INTERVAL`] at most.
+///
+/// # Events
+/// - `system.backup` - The [`BackupStatus`] of every target, every check
+/// - `system.backup.stale` - The [`BackupStatus`] of a target that became
+///   overdue or stale since the previous check (including the first)
+///
+/// When the event bus is not live, `system.backup` events are shown
+/// instead of checking the targets.
+pub struct BackupWidget {
+    collector: Collector<BackupOptions, Vec<BackupStatus>>,
+    statuses: Vec<BackupStatus>,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl BackupWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, BackupOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<BackupOptions>(settings)?.validate()?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: BackupOptions,
+    ) -> Self {
+        Self {
+            collector: Collector::new(options, check_targets),
+            statuses: Vec::new(),
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval: poll_interval.max(CHECK_INTERVAL),
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_statuses(&mut self, statuses: Vec<BackupStatus>) {
+        for status in newly_stale(&statuses, &self.statuses) {
+            self.event_bus
+                .publish(Event::new("system.backup.stale", status.clone()));
+        }
+        for status in &statuses {
+            self.event_bus
+                .publish(Event::new("system.backup", status.clone()));
+        }
+        self.statuses = statuses;
+    }
+
+    fn count(&self, freshness: Freshness) -> usize {
+        self.statuses
+            .iter()
+            .filter(|s| s.freshness == freshness)
+            .count()
+    }
+}
+
+impl Widget for BackupWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected statuses instead of checking the targets
+            let (sub, rx) = self.event_bus.subscribe("system.backup");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        self.collector.request(WorkerPool::global());
+        if let Some(statuses) = self.collector.wait() {
+            self.apply_statuses(statuses);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let mut received = false;
+            for status in rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<BackupStatus>())
+            {
+                match self.statuses.iter_mut().find(|s| s.name == status.name) {
+                    Some(existing) => *existing = BackupStatus::clone(&status),
+                    None => self.statuses.push(BackupStatus::clone(&status)),
+                }
+                received = true;
+            }
+            if received {
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(statuses) = self.collector.try_take() {
+            self.apply_statuses(statuses);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let stale = self.count(Freshness::Stale);
+        let title = match stale {
+            0 => format!(" Backups [{} targets] ", self.statuses.len()),
+            _ => format!(
+                " Backups [{} targets, {} stale] ",
+                self.statuses.len(),
+                stale
+            ),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+
+        let rows = self.statuses.iter().map(|status| {
+            let (label, color) = match status.freshness {
+                Freshness::Fresh => ("ok", theme().good),
+                Freshness::Overdue => ("overdue", theme().warning),
+                Freshness::Stale => ("stale", theme().critical),
+                Freshness::Unknown => ("unknown", theme().warning),
+            };
+            let detail = status.error.clone().unwrap_or_else(|| label.to_string());
+            Row::new(vec![
+                Cell::from(status.name.clone()),
+                Cell::from(
+                    status
+                        .last_success
+                        .map_or("never".to_string(), format_relative_time),
+                )
+                .style(Style::default().fg(color)),
+                Cell::from(detail).style(Style::default().fg(color)),
+            ])
+        });
+        let header = Row::new(
+            ["Target", "Last backup", "Status"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(15),
+                Constraint::Min(8),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .render(area, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mut summary = format!("Backups: {} targets", self.statuses.len());
+        for (freshness, label) in [
+            (Freshness::Stale, "stale"),
+            (Freshness::Overdue, "overdue"),
+            (Freshness::Unknown, "unknown"),
+        ] {
+            let names: Vec<&str> = self
+                .statuses
+                .iter()
+                .filter(|s| s.freshness == freshness)
+                .map(|s| s.name.as_str())
+                .collect();
+            if !names.is_empty() {
+                summary.push_str(&format!("; {}: {}", label, names.join(", ")));
+            }
+        }
+        if !self.statuses.is_empty() && self.count(Freshness::Fresh) == self.statuses.len() {
+            summary.push_str(", all fresh");
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(name: &str, freshness: Freshness) -> BackupStatus {
+        BackupStatus {
+            name: name.to_string(),
+            last_success: None,
+            freshness,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_latest_timestamp() {
+        let restic = r#"[{"time":"2024-05-01T02:00:03.512+02:00","hostname":"box"},
+                         {"time":"2024-05-02T02:00:04.1+02:00","hostname":"box"}]"#;
+        assert_eq!(
+            latest_timestamp(restic),
+            Some(UNIX_EPOCH + Duration::from_secs(1_714_608_004) + Duration::from_millis(100))
+        );
+        assert_eq!(
+            latest_timestamp("2024-05-01T00:00:00Z\n"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_714_521_600))
+        );
+        assert_eq!(
+            latest_timestamp("1714521600\n"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_714_521_600))
+        );
+
+        // borg prints local times without an offset
+        let borg =
+            r#"{"archives": [{"name": "box-2024-05-01", "time": "2024-05-01T02:00:03.000000"}]}"#;
+        let local =
+            NaiveDateTime::parse_from_str("2024-05-01 02:00:03", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            latest_timestamp(borg),
+            Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(SystemTime::from)
+        );
+
+        assert_eq!(latest_timestamp("no snapshots found"), None);
+        assert_eq!(latest_timestamp(""), None);
+    }
+
+    #[test]
+    fn test_freshness() {
+        let now = UNIX_EPOCH + Duration::from_secs(10 * 86_400);
+        let hours_ago = |hours: u64| Some(now - Duration::from_secs(hours * 3600));
+        let warn = Duration::from_secs(26 * 3600);
+        let critical = Duration::from_secs(72 * 3600);
+        assert_eq!(
+            freshness(hours_ago(2), warn, critical, now),
+            Freshness::Fresh
+        );
+        assert_eq!(
+            freshness(hours_ago(30), warn, critical, now),
+            Freshness::Overdue
+        );
+        assert_eq!(
+            freshness(hours_ago(72), warn, critical, now),
+            Freshness::Stale
+        );
+        assert_eq!(freshness(None, warn, critical, now), Freshness::Unknown);
+    }
+
+    #[test]
+    fn test_newly_stale() {
+        let before = [
+            status("home", Freshness::Fresh),
+            status("photos", Freshness::Overdue),
+            status("offsite", Freshness::Stale),
+        ];
+        let after = [
+            status("home", Freshness::Overdue),
+            status("photos", Freshness::Stale),
+            status("offsite", Freshness::Stale),
+            status("mail", Freshness::Overdue),
+        ];
+        let names: Vec<&str> = newly_stale(&after, &before)
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, ["home", "photos", "mail"]);
+        assert_eq!(newly_stale(&before, &[]).count(), 2);
+    }
+
+    #[test]
+    fn test_options() {
+        let settings: WidgetSettings = toml::from_str(
+            r#"
+            warn = "2d"
+            targets = [
+              { name = "home", path = "/var/log/rsync-home.log" },
+              { name = "offsite", command = "restic snapshots --json --latest 1", critical = "1w" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let options = parse_settings::<BackupOptions>(&settings)
+            .unwrap()
+            .validate()
+            .unwrap();
+        assert_eq!(options.warn, Duration::from_secs(2 * 86_400));
+        assert_eq!(options.critical, Duration::from_secs(3 * 86_400));
+        assert_eq!(
+            options.targets[0].path,
+            Some("/var/log/rsync-home.log".into())
+        );
+        assert_eq!(
+            options.targets[1].critical,
+            Some(Duration::from_secs(7 * 86_400))
+        );
+
+        let settings: WidgetSettings = toml::from_str(r#"targets = [{ name = "home" }]"#).unwrap();
+        let options = parse_settings::<BackupOptions>(&settings).unwrap();
+        assert!(options.validate().is_err());
+
+        let settings: WidgetSettings = toml::from_str(r#"warn = "tomorrow""#).unwrap();
+        assert!(parse_settings::<BackupOptions>(&settings).is_err());
+    }
+}
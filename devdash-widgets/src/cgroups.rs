@@ -0,0 +1,360 @@
+// devdash-widgets/src/cgroups.rs
+use devdash_core::{Action, Collector, DataStatus, EventResult, Widget, WidgetState, WorkerPool};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
+};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::common::{focus_color, format_bytes, restore_table, save_table};
+use crate::platform::cgroup::{self, CGROUP_ROOT};
+
+/// How many levels below the root to look for groups
+///
+/// Deep enough for `kubepods/<qos>/<pod>/<container>`.
+const MAX_DEPTH: usize = 4;
+
+/// Resource consumption of one cgroup
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupUsage {
+    /// Path within the hierarchy
+    pub path: String,
+    /// CPU usage in percent of one core; `None` until two samples exist
+    pub cpu_percent: Option<f32>,
+    /// CPU limit in cores
+    pub cpu_limit: Option<f64>,
+    pub memory_used: u64,
+    pub memory_limit: Option<u64>,
+}
+
+impl CgroupUsage {
+    /// Short name shown in the table: the last path component
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Scanner state carried between runs to compute CPU rates
+pub struct CgroupScan {
+    root: PathBuf,
+    last_scan: Option<Instant>,
+    cpu_time: HashMap<String, Duration>,
+}
+
+impl CgroupScan {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            last_scan: None,
+            cpu_time: HashMap::new(),
+        }
+    }
+
+    /// Read every group's usage (runs on a worker thread)
+    pub fn scan(&mut self) -> Vec<CgroupUsage> {
+        let now = Instant::now();
+        let elapsed = self.last_scan.replace(now).map(|last| now - last);
+        let mut cpu_time = HashMap::new();
+
+        let usage = cgroup::list(&self.root, MAX_DEPTH)
+            .into_iter()
+            .map(|group| {
+                let cpu = group.cpu_usage();
+                let cpu_percent = match (cpu, self.cpu_time.get(&group.path), elapsed) {
+                    (Some(now), Some(&then), Some(elapsed)) if !elapsed.is_zero() => Some(
+                        (now.saturating_sub(then).as_secs_f64() / elapsed.as_secs_f64() * 100.0)
+                            as f32,
+                    ),
+                    _ => None,
+                };
+                if let Some(cpu) = cpu {
+                    cpu_time.insert(group.path.clone(), cpu);
+                }
+
+                CgroupUsage {
+                    cpu_percent,
+                    cpu_limit: group.cpu_limit(),
+                    memory_used: group.memory_usage().unwrap_or(0),
+                    memory_limit: group.memory_limit(),
+                    path: group.path,
+                }
+            })
+            .collect();
+
+        // Forget groups that went away
+        self.cpu_time = cpu_time;
+        usage
+    }
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Move selection down"),
+    Action::new("up", &["k", "up"], "Move selection up"),
+    Action::new("sort_cpu", &["c"], "Sort by CPU"),
+    Action::new("sort_memory", &["m"], "Sort by memory"),
+];
+
+/// Cgroup and container resource widget
+///
+/// Lists the control groups on the host (systemd services, Docker
+/// containers, Kubernetes pods) with their CPU and memory consumption
+/// against their limits, busiest first.
+///
+/// # Keyboard Shortcuts
+/// - `j`/`k` or `↓`/`↑` - Move the selection
+/// - `c`/`m` - Sort by CPU or memory
+///
+/// The cgroup filesystem is read on the shared worker pool.
+pub struct CgroupWidget {
+    collector: Collector<CgroupScan, Vec<CgroupUsage>>,
+    groups: Vec<CgroupUsage>,
+    table_state: TableState,
+    sort_by_memory: bool,
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl CgroupWidget {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self::with_root(CGROUP_ROOT, poll_interval)
+    }
+
+    /// Read cgroups from a hierarchy mounted somewhere other than
+    /// `/sys/fs/cgroup`
+    pub fn with_root(root: impl Into<PathBuf>, poll_interval: Duration) -> Self {
+        Self {
+            collector: Collector::new(CgroupScan::new(root), CgroupScan::scan),
+            groups: Vec::new(),
+            table_state: TableState::default(),
+            sort_by_memory: false,
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_snapshot(&mut self, groups: Vec<CgroupUsage>) {
+        self.groups = groups;
+        self.sort_groups();
+        if self.table_state.selected().is_none() && !self.groups.is_empty() {
+            self.table_state.select(Some(0));
+        }
+    }
+
+    fn sort_groups(&mut self) {
+        if self.sort_by_memory {
+            self.groups
+                .sort_by_key(|g| std::cmp::Reverse(g.memory_used));
+        } else {
+            self.groups.sort_by(|a, b| {
+                b.cpu_percent
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.cpu_percent.unwrap_or(0.0))
+            });
+        }
+    }
+}
+
+impl Widget for CgroupWidget {
+    fn on_mount(&mut self) {
+        // CPU rates need two samples; the first scan just primes them
+        self.collector.request(WorkerPool::global());
+        if let Some(groups) = self.collector.wait() {
+            self.apply_snapshot(groups);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(groups) = self.collector.try_take() {
+            self.apply_snapshot(groups);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(self.collector.status(self.poll_interval))
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        if let devdash_core::Event::Action(action) = event {
+            match action.as_str() {
+                "down" => {
+                    let i = self.table_state.selected().unwrap_or(0);
+                    if i < self.groups.len().saturating_sub(1) {
+                        self.table_state.select(Some(i + 1));
+                    }
+                    return EventResult::Consumed;
+                }
+                "up" => {
+                    let i = self.table_state.selected().unwrap_or(0);
+                    self.table_state.select(Some(i.saturating_sub(1)));
+                    return EventResult::Consumed;
+                }
+                "sort_cpu" => {
+                    self.sort_by_memory = false;
+                    self.sort_groups();
+                    return EventResult::Consumed;
+                }
+                "sort_memory" => {
+                    self.sort_by_memory = true;
+                    self.sort_groups();
+                    return EventResult::Consumed;
+                }
+                _ => {}
+            }
+        }
+
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let sort_indicator = if self.sort_by_memory {
+            "↓MEM"
+        } else {
+            "↓CPU"
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " Cgroups [{} groups, {}] ",
+                self.groups.len(),
+                sort_indicator
+            ))
+            .border_style(Style::default().fg(focus_color(focused)));
+
+        let header = Row::new(
+            ["Group", "CPU%", "Limit", "Memory", "Limit"]
+                .iter()
+                .map(|h| Cell::from(*h).style(Style::default().fg(Color::Yellow))),
+        )
+        .bottom_margin(1);
+
+        let rows = self.groups.iter().map(|group| {
+            Row::new(vec![
+                Cell::from(group.name().to_string()),
+                Cell::from(
+                    group
+                        .cpu_percent
+                        .map_or("-".to_string(), |cpu| format!("{:.1}", cpu)),
+                ),
+                Cell::from(
+                    group
+                        .cpu_limit
+                        .map_or("-".to_string(), |cores| format!("{:.2}c", cores)),
+                ),
+                Cell::from(format_bytes(group.memory_used)),
+                Cell::from(group.memory_limit.map_or("-".to_string(), format_bytes)),
+            ])
+        });
+
+        let widths = [
+            Constraint::Min(16),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+        ratatui::widgets::StatefulWidget::render(table, inner, buf, &mut self.table_state);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        let top: Vec<String> = self
+            .groups
+            .iter()
+            .take(3)
+            .map(|g| {
+                format!(
+                    "{} {} CPU {}",
+                    g.name(),
+                    g.cpu_percent
+                        .map_or("-".to_string(), |cpu| format!("{:.1}%", cpu)),
+                    format_bytes(g.memory_used)
+                )
+            })
+            .collect();
+        let mut summary = format!("Cgroups: {} groups", self.groups.len());
+        if !top.is_empty() {
+            summary.push_str(&format!("; {}", top.join(", ")));
+        }
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_scan_reads_usage_against_limits() {
+        let root = std::env::temp_dir().join(format!("devdash-cgroups-{}", std::process::id()));
+        let group = root.join("system.slice/web.service");
+        fs::create_dir_all(&group).unwrap();
+        fs::write(root.join("cgroup.controllers"), "cpu memory").unwrap();
+        fs::write(group.join("memory.current"), "4096\n").unwrap();
+        fs::write(group.join("memory.max"), "8192\n").unwrap();
+        fs::write(group.join("cpu.max"), "50000 100000\n").unwrap();
+        fs::write(group.join("cpu.stat"), "usage_usec 1000\n").unwrap();
+
+        let mut scan = CgroupScan::new(&root);
+        let usage = scan.scan();
+        let web = usage.iter().find(|g| g.name() == "web.service").unwrap();
+        assert_eq!(web.memory_used, 4096);
+        assert_eq!(web.memory_limit, Some(8192));
+        assert_eq!(web.cpu_limit, Some(0.5));
+        assert_eq!(web.cpu_percent, None);
+
+        std::thread::sleep(Duration::from_millis(5));
+        let usage = scan.scan();
+        let web = usage.iter().find(|g| g.name() == "web.service").unwrap();
+        assert_eq!(web.cpu_percent, Some(0.0));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,519 @@
+// devdash-widgets/src/clock.rs
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    EventBus, EventResult, Widget, WidgetSettings,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use serde::{Deserialize, Deserializer, de::Error as _};
+use std::time::{Duration, SystemTime};
+
+use crate::common::{focus_color, time_format};
+
+/// Rows of the large digits
+const DIGIT_HEIGHT: usize = 5;
+
+/// The large digits `0` to `9`, three cells wide
+const DIGITS: [[&str; DIGIT_HEIGHT]; 10] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    ["  █", "  █", "  █", "  █", "  █"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+];
+
+/// The large `:`, one cell wide
+const COLON: [&str; DIGIT_HEIGHT] = [" ", "█", " ", "█", " "];
+
+/// `text` in large digits, one string per row; characters other than
+/// digits and `:` are left out
+pub fn big_digits(text: &str) -> Vec<String> {
+    let glyphs: Vec<&[&str; DIGIT_HEIGHT]> = text
+        .chars()
+        .filter_map(|c| match c {
+            ':' => Some(&COLON),
+            _ => c.to_digit(10).map(|d| &DIGITS[d as usize]),
+        })
+        .collect();
+    (0..DIGIT_HEIGHT)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// The month of `today` as a calendar starting on Monday, with `today`
+/// highlighted and, if `weeks`, each row's ISO week number in front
+pub fn month_lines(today: NaiveDate, weeks: bool) -> Vec<Line<'static>> {
+    let first = today.with_day(1).unwrap_or(today);
+    let last = first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(first);
+    let dim = Style::default().fg(Color::DarkGray);
+    let week_column = if weeks { 3 } else { 0 };
+    let width = 20 + week_column;
+
+    let title = first.format("%B %Y").to_string();
+    let mut lines = vec![
+        Line::from(format!("{:^width$}", title)).style(Style::default().fg(Color::Yellow)),
+        Line::from(Span::styled(
+            format!("{}Mo Tu We Th Fr Sa Su", if weeks { "Wk " } else { "" }),
+            dim,
+        )),
+    ];
+
+    // Monday of the week the month starts in
+    let mut monday = first - Days::new(u64::from(first.weekday().num_days_from_monday()));
+    while monday <= last {
+        let mut spans = Vec::new();
+        if weeks {
+            spans.push(Span::styled(
+                format!("{:>2} ", monday.iso_week().week()),
+                dim,
+            ));
+        }
+        for offset in 0..7 {
+            let day = monday + Days::new(offset);
+            let text = if day.month() == first.month() {
+                format!("{:>2}", day.day())
+            } else {
+                "  ".to_string()
+            };
+            let style = if day == today {
+                Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            spans.push(Span::styled(text, style));
+            if offset < 6 {
+                spans.push(Span::from(" "));
+            }
+        }
+        lines.push(Line::from(spans));
+        monday = monday + Days::new(7);
+    }
+    lines
+}
+
+/// A time zone as configured: an IANA name such as `Asia/Tokyo`, with an
+/// optional label
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ZoneConfig {
+    #[serde(deserialize_with = "tz")]
+    pub zone: Tz,
+    /// Shown instead of the zone's city, e.g. `Tokyo office`
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Clock widget settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ClockOptions {
+    /// Zone of the large clock; local time when unset
+    #[serde(deserialize_with = "optional_tz")]
+    pub zone: Option<Tz>,
+    /// More zones, listed below the date
+    pub zones: Vec<ZoneConfig>,
+    pub seconds: bool,
+    /// 12-hour clock; by default whatever `time_format` uses
+    pub hour12: Option<bool>,
+    /// Show the ISO week number with the date and in the calendar
+    pub week: bool,
+    /// Show the current month below
+    pub calendar: bool,
+}
+
+impl Default for ClockOptions {
+    fn default() -> Self {
+        Self {
+            zone: None,
+            zones: Vec::new(),
+            seconds: true,
+            hour12: None,
+            week: false,
+            calendar: false,
+        }
+    }
+}
+
+fn tz<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Tz, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse()
+        .map_err(|_| D::Error::custom(format!("unknown time zone '{}'", name)))
+}
+
+fn optional_tz<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Tz>, D::Error> {
+    tz(deserializer).map(Some)
+}
+
+/// Where the time is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Zone {
+    Local,
+    Named(Tz),
+}
+
+impl Zone {
+    /// The wall clock time in the zone at `time`
+    fn wall_time(self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Local => time.with_timezone(&Local).naive_local(),
+            Zone::Named(tz) => time.with_timezone(&tz).naive_local(),
+        }
+    }
+}
+
+/// `New York` for `America/New_York`
+fn city(zone: &str) -> String {
+    zone.rsplit('/').next().unwrap_or(zone).replace('_', " ")
+}
+
+/// How far `wall` is ahead of `reference`, e.g. `+9h` or `-3h30m`
+fn format_offset(wall: NaiveDateTime, reference: NaiveDateTime) -> String {
+    let minutes = wall.signed_duration_since(reference).num_minutes();
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let (hours, minutes) = (minutes.abs() / 60, minutes.abs() % 60);
+    match (hours, minutes) {
+        (0, 0) => "±0h".to_string(),
+        (_, 0) => format!("{}{}h", sign, hours),
+        _ => format!("{}{}h{:02}m", sign, hours, minutes),
+    }
+}
+
+/// Clock widget
+///
+/// Shows the time in large digits with the date below, the time in other
+/// zones, and optionally the current month with ISO week numbers. Zones are
+/// IANA names such as `Europe/Berlin`.
+///
+/// When the event bus is not live, the clock stands at the time of the
+/// latest `system.time` event ([`SystemTime`]) instead of following the
+/// system clock.
+pub struct ClockWidget {
+    now: DateTime<Utc>,
+    zone: Zone,
+    /// Label and zone of each extra row
+    zones: Vec<(String, Zone)>,
+    seconds: bool,
+    hour12: bool,
+    week: bool,
+    calendar: bool,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+}
+
+impl ClockWidget {
+    pub fn new(event_bus: EventBus) -> Self {
+        Self::with_options(event_bus, ClockOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        _poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<ClockOptions>(settings)?;
+        Ok(Self::with_options(event_bus, options))
+    }
+
+    pub fn with_options(event_bus: EventBus, options: ClockOptions) -> Self {
+        let zone = options.zone.map_or(Zone::Local, Zone::Named);
+        let zones = options
+            .zones
+            .into_iter()
+            .map(|config| {
+                let label = config.label.unwrap_or_else(|| city(config.zone.name()));
+                (label, Zone::Named(config.zone))
+            })
+            .collect();
+        let hour12 = options.hour12.unwrap_or_else(|| {
+            let format = time_format();
+            ["%I", "%l", "%p", "%r"].iter().any(|s| format.contains(s))
+        });
+        Self {
+            now: Utc::now(),
+            zone,
+            zones,
+            seconds: options.seconds,
+            hour12,
+            week: options.week,
+            calendar: options.calendar,
+            event_bus,
+            _subscription: None,
+            injected: None,
+        }
+    }
+
+    /// `14:05:09`, or `02:05:09` on a 12-hour clock
+    fn clock_text(&self, wall: NaiveDateTime) -> String {
+        let format = match (self.hour12, self.seconds) {
+            (false, true) => "%H:%M:%S",
+            (false, false) => "%H:%M",
+            (true, true) => "%I:%M:%S",
+            (true, false) => "%I:%M",
+        };
+        wall.format(format).to_string()
+    }
+
+    /// `Saturday, 17 October 2026`, with the week number and AM/PM as set
+    fn date_text(&self, wall: NaiveDateTime) -> String {
+        let mut parts = Vec::new();
+        if self.hour12 {
+            parts.push(wall.format("%p").to_string());
+        }
+        parts.push(wall.format("%A, %-d %B %Y").to_string());
+        if self.week {
+            parts.push(format!("Week {}", wall.iso_week().week()));
+        }
+        parts.join(" · ")
+    }
+
+    /// One row per extra zone: label, time, and offset from the large
+    /// clock, with the day if it is another one there
+    fn zone_lines(&self, reference: NaiveDateTime) -> Vec<Line<'static>> {
+        let label_width = self
+            .zones
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let format = if self.hour12 { "%I:%M %p" } else { "%H:%M" };
+        self.zones
+            .iter()
+            .map(|(label, zone)| {
+                let wall = zone.wall_time(self.now);
+                let day = if wall.date() == reference.date() {
+                    "   ".to_string()
+                } else {
+                    wall.format("%a").to_string()
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<label_width$}  ", label),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::from(format!("{} {}  ", wall.format(format), day)),
+                    Span::styled(
+                        format!("{:>7}", format_offset(wall, reference)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ])
+            })
+            .collect()
+    }
+}
+
+impl Widget for ClockWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Stand at injected times instead of following the system clock
+            let (sub, rx) = self.event_bus.subscribe("system.time");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            return;
+        }
+        self.now = Utc::now();
+    }
+
+    fn on_update(&mut self, _delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<SystemTime>())
+                .last();
+            if let Some(time) = latest {
+                self.now = DateTime::<Utc>::from(*time);
+            }
+            return;
+        }
+        self.now = Utc::now();
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Clock ")
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let wall = self.zone.wall_time(self.now);
+        let clock = self.clock_text(wall);
+        let digits = big_digits(&clock);
+        let big_width = digits[0].chars().count() as u16;
+
+        let mut lines: Vec<Line> = Vec::new();
+        // Large digits if they fit with the date below, the plain time
+        // otherwise
+        if inner.width >= big_width && inner.height as usize > DIGIT_HEIGHT {
+            let style = Style::default().fg(theme().focus);
+            lines.extend(digits.into_iter().map(|row| Line::styled(row, style)));
+        } else {
+            lines.push(Line::styled(
+                clock,
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        }
+        lines.push(Line::from(self.date_text(wall)));
+
+        let zones = self.zone_lines(wall);
+        if !zones.is_empty() {
+            lines.push(Line::default());
+            lines.extend(zones);
+        }
+        if self.calendar {
+            lines.push(Line::default());
+            lines.extend(month_lines(wall.date(), self.week));
+        }
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .render(inner, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn update_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn summary(&self) -> Option<String> {
+        let wall = self.zone.wall_time(self.now);
+        let format = if self.hour12 { "%I:%M %p" } else { "%H:%M" };
+        let mut summary = format!("{}, {}", wall.format(format), wall.format("%A %-d %B %Y"));
+        let zones: Vec<String> = self
+            .zones
+            .iter()
+            .map(|(label, zone)| format!("{} {}", label, zone.wall_time(self.now).format(format)))
+            .collect();
+        if !zones.is_empty() {
+            summary.push_str(&format!("; {}", zones.join(", ")));
+        }
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_big_digits() {
+        assert_eq!(
+            big_digits("10:42"),
+            [
+                "  █ ███   █ █ ███",
+                "  █ █ █ █ █ █   █",
+                "  █ █ █   ███ ███",
+                "  █ █ █ █   █ █  ",
+                "  █ ███     █ ███",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_month_lines() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 17).unwrap();
+        assert_eq!(
+            text(&month_lines(today, true)),
+            [
+                "     February 2026     ",
+                "Wk Mo Tu We Th Fr Sa Su",
+                " 5                    1",
+                " 6  2  3  4  5  6  7  8",
+                " 7  9 10 11 12 13 14 15",
+                " 8 16 17 18 19 20 21 22",
+                " 9 23 24 25 26 27 28   ",
+            ]
+        );
+        // A month starting on Monday has no leading blanks
+        let june = text(&month_lines(
+            NaiveDate::from_ymd_opt(2026, 6, 1).unwrap(),
+            false,
+        ));
+        assert_eq!(june[2], " 1  2  3  4  5  6  7");
+        assert_eq!(june.last().unwrap(), "29 30               ");
+    }
+
+    #[test]
+    fn test_zones() {
+        assert_eq!(city("America/New_York"), "New York");
+        assert_eq!(city("UTC"), "UTC");
+
+        let at = |h, m| {
+            NaiveDate::from_ymd_opt(2026, 10, 17)
+                .unwrap()
+                .and_hms_opt(h, m, 0)
+                .unwrap()
+        };
+        assert_eq!(format_offset(at(21, 0), at(12, 0)), "+9h");
+        assert_eq!(format_offset(at(8, 30), at(12, 0)), "-3h30m");
+        assert_eq!(format_offset(at(12, 0), at(12, 0)), "±0h");
+    }
+
+    #[test]
+    fn test_options() {
+        let parse = |text: &str| {
+            let settings: WidgetSettings = toml::from_str(text).unwrap();
+            parse_settings::<ClockOptions>(&settings)
+        };
+        let options = parse(
+            r#"
+            zone = "UTC"
+            zones = [{ zone = "Asia/Tokyo", label = "Tokyo office" }]
+            calendar = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(options.zone, Some(Tz::UTC));
+        assert_eq!(options.zones[0].zone, Tz::Asia__Tokyo);
+        assert!(options.seconds && options.calendar && !options.week);
+
+        assert!(
+            parse(r#"zones = [{ zone = "Mars/Olympus" }]"#)
+                .unwrap_err()
+                .contains("unknown time zone 'Mars/Olympus'")
+        );
+    }
+}
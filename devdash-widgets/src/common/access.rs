@@ -0,0 +1,169 @@
+// devdash-widgets/src/common/access.rs
+//! Data sources the user may not be allowed to read.
+//!
+//! On hardened systems the kernel log, the journal and other users'
+//! processes need more rights than a login shell has. Widgets reading them
+//! report an [`AccessDenied`] with how to get access, rather than an empty
+//! list that reads as "nothing wrong".
+use std::{fmt, io, path::Path};
+
+/// A source that can't be read without more rights
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessDenied {
+    /// What can't be read, e.g. `the kernel log`
+    pub source: String,
+    /// How to get access, e.g. `run devdash with sudo`
+    pub hint: String,
+}
+
+impl AccessDenied {
+    pub fn new(source: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            hint: hint.into(),
+        }
+    }
+
+    /// For an error reading `path`, if it is a permission failure
+    pub fn from_io(error: &io::Error, path: &Path, hint: impl Into<String>) -> Option<Self> {
+        (error.kind() == io::ErrorKind::PermissionDenied)
+            .then(|| Self::new(path.display().to_string(), hint))
+    }
+}
+
+impl fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Insufficient permissions to read {} — {}",
+            self.source, self.hint
+        )
+    }
+}
+
+/// How to get access to system logs, which on most Linux distributions are
+/// readable by the `adm` group
+pub const LOG_ACCESS_HINT: &str = if cfg!(windows) {
+    "run devdash as administrator"
+} else {
+    "add your user to the adm group, or run devdash with sudo"
+};
+
+/// Whether devdash runs as root, which reads everything
+pub fn is_root() -> bool {
+    #[cfg(unix)]
+    {
+        // Safety: geteuid has no preconditions and can't fail
+        unsafe { libc::geteuid() == 0 }
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+/// The `hidepid` and `gid` options of the `/proc` mount in `mounts` (the
+/// contents of `/proc/mounts`), if other users' processes are hidden
+pub fn proc_hidepid(mounts: &str) -> Option<(String, Option<u32>)> {
+    let options = mounts.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let (_, mount_point, file_system) = (fields.next(), fields.next()?, fields.next()?);
+        (mount_point == "/proc" && file_system == "proc").then(|| fields.next())?
+    })?;
+    let option = |name: &str| {
+        options
+            .split(',')
+            .find_map(|option| option.strip_prefix(name)?.strip_prefix('='))
+    };
+    let hidepid = option("hidepid")?;
+    // 0 and `off` show every process
+    if matches!(hidepid, "0" | "off") {
+        return None;
+    }
+    Some((
+        hidepid.to_string(),
+        option("gid").and_then(|gid| gid.parse().ok()),
+    ))
+}
+
+/// Why other users' processes are missing from `/proc`, if they are
+pub fn hidden_processes() -> Option<AccessDenied> {
+    if !cfg!(target_os = "linux") || is_root() {
+        return None;
+    }
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    let (hidepid, gid) = proc_hidepid(&mounts)?;
+    let hint = match gid {
+        Some(gid) => format!(
+            "/proc is mounted with hidepid={}; run devdash with sudo or join group {}",
+            hidepid, gid
+        ),
+        None => format!(
+            "/proc is mounted with hidepid={}; run devdash with sudo",
+            hidepid
+        ),
+    };
+    Some(AccessDenied::new("other users' processes", hint))
+}
+
+/// Whether the system journal exists but can't be read, in which case
+/// `journalctl` only shows the user's own messages without saying so
+pub fn journal_restricted() -> bool {
+    if is_root() {
+        return false;
+    }
+    let Ok(machine_id) = std::fs::read_to_string("/etc/machine-id") else {
+        return false;
+    };
+    ["/var/log/journal", "/run/log/journal"]
+        .iter()
+        .map(|dir| {
+            Path::new(dir)
+                .join(machine_id.trim())
+                .join("system.journal")
+        })
+        .find(|path| path.exists())
+        .is_some_and(|path| {
+            std::fs::File::open(path).is_err_and(|e| e.kind() == io::ErrorKind::PermissionDenied)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proc_hidepid() {
+        let mounts = "sysfs /sys sysfs rw,nosuid 0 0\n\
+                      proc /proc proc rw,nosuid,nodev,noexec,relatime,hidepid=invisible,gid=27 0 0\n";
+        assert_eq!(
+            proc_hidepid(mounts),
+            Some(("invisible".to_string(), Some(27)))
+        );
+        assert_eq!(
+            proc_hidepid("proc /proc proc rw,hidepid=2 0 0"),
+            Some(("2".to_string(), None))
+        );
+        assert_eq!(proc_hidepid("proc /proc proc rw,hidepid=0 0 0"), None);
+        assert_eq!(proc_hidepid("proc /proc proc rw,relatime 0 0"), None);
+        // Another proc mount, e.g. in a container's root
+        assert_eq!(
+            proc_hidepid("proc /srv/root/proc proc rw,hidepid=2 0 0"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_io() {
+        let path = Path::new("/var/log/kern.log");
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert_eq!(
+            AccessDenied::from_io(&denied, path, "join the adm group")
+                .unwrap()
+                .to_string(),
+            "Insufficient permissions to read /var/log/kern.log — join the adm group"
+        );
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(AccessDenied::from_io(&missing, path, "-"), None);
+    }
+}
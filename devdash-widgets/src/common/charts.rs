@@ -0,0 +1,508 @@
+// devdash-widgets/src/common/charts.rs
+//! Reusable chart components shared by the built-in widgets and plugins.
+//!
+//! Every component implements ratatui's `Widget` trait, so it can be rendered
+//! into any sub-area of a widget with `RatatuiWidget::render(chart, area, buf)`.
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Style},
+    widgets::{Block, Gauge, Sparkline},
+};
+
+use super::colors::usage_color;
+
+/// Shading characters used for activity bars, from lightest to heaviest
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+/// Bar characters in eighths of a row, from empty to full
+const EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Stretch or trim a history buffer so it exactly fills `width` columns
+///
+/// Histories longer than the width keep their most recent points, shorter
+/// histories are stretched by repeating samples.
+///
+/// # Example
+/// ```rust
+/// use devdash_widgets::common::charts::fit_to_width;
+///
+/// assert_eq!(fit_to_width(&[1, 2], 4), vec![1, 1, 2, 2]);
+/// assert_eq!(fit_to_width(&[], 3), vec![0, 0, 0]);
+/// ```
+pub fn fit_to_width(history: &[u64], width: usize) -> Vec<u64> {
+    if history.is_empty() {
+        vec![0; width]
+    } else if history.len() >= width {
+        history[history.len() - width..].to_vec()
+    } else {
+        let scale = history.len() as f32 / width as f32;
+        (0..width)
+            .map(|i| {
+                let idx = (i as f32 * scale) as usize;
+                history
+                    .get(idx)
+                    .copied()
+                    .unwrap_or_else(|| *history.last().unwrap_or(&0))
+            })
+            .collect()
+    }
+}
+
+/// Gauge with a label line above the bar, colored by usage
+///
+/// Occupies two rows: the label on the first, the bar on the second.
+///
+/// # Example
+/// ```rust
+/// use devdash_widgets::common::charts::LabelledGauge;
+///
+/// let gauge = LabelledGauge::new("RAM", 42.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LabelledGauge<'a> {
+    label: &'a str,
+    percent: f64,
+    color: Option<Color>,
+}
+
+impl<'a> LabelledGauge<'a> {
+    /// Create a gauge for a usage percentage (0.0 - 100.0)
+    pub fn new(label: &'a str, percent: f64) -> Self {
+        Self {
+            label,
+            percent,
+            color: None,
+        }
+    }
+
+    /// Override the usage-based color
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl RatatuiWidget for LabelledGauge<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let percent = self.percent.clamp(0.0, 100.0);
+        let color = self.color.unwrap_or_else(|| usage_color(percent));
+
+        Gauge::default()
+            .block(Block::default().title(self.label))
+            .gauge_style(Style::default().fg(color))
+            .ratio(percent / 100.0)
+            .render(area, buf);
+    }
+}
+
+/// Single-row activity bar: a label followed by a shaded bar
+///
+/// The bar gets heavier shading towards its filled end, which reads well as
+/// a throughput indicator.
+#[derive(Debug, Clone)]
+pub struct ActivityBar<'a> {
+    label: &'a str,
+    percent: f64,
+    color: Color,
+}
+
+impl<'a> ActivityBar<'a> {
+    /// Create an activity bar for an activity level (0.0 - 100.0)
+    pub fn new(label: &'a str, percent: f64, color: Color) -> Self {
+        Self {
+            label,
+            percent,
+            color,
+        }
+    }
+}
+
+impl RatatuiWidget for ActivityBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let style = Style::default().fg(self.color);
+        let label_width = self.label.chars().count() as u16;
+        buf.set_stringn(area.x, area.y, self.label, area.width as usize, style);
+
+        let bar_width = area.width.saturating_sub(label_width + 3).max(1);
+        let filled_width = ((self.percent.clamp(0.0, 100.0) / 100.0) * bar_width as f64) as u16;
+
+        let bar: String = (0..bar_width)
+            .map(|i| {
+                if i < filled_width {
+                    let shade = (i as f64 / bar_width as f64 * SHADES.len() as f64) as usize;
+                    SHADES[shade.min(SHADES.len() - 1)]
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+
+        let bar_x = area.x + label_width + 1;
+        if bar_x < area.right() {
+            buf.set_stringn(bar_x, area.y, bar, (area.right() - bar_x) as usize, style);
+        }
+    }
+}
+
+/// Two titled sparklines stacked vertically, e.g. download over upload
+///
+/// The area is split evenly between the two series; each history is fitted
+/// to the available width with [`fit_to_width`].
+#[derive(Debug, Clone)]
+pub struct DualSparkline<'a> {
+    top: (&'a str, &'a [u64], Color),
+    bottom: (&'a str, &'a [u64], Color),
+}
+
+impl<'a> DualSparkline<'a> {
+    /// Create a dual sparkline from two (title, history, color) series
+    pub fn new(top: (&'a str, &'a [u64], Color), bottom: (&'a str, &'a [u64], Color)) -> Self {
+        Self { top, bottom }
+    }
+}
+
+impl RatatuiWidget for DualSparkline<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let top_height = area.height / 2;
+        let top_area = Rect {
+            height: top_height,
+            ..area
+        };
+        let bottom_area = Rect {
+            y: area.y + top_height,
+            height: area.height - top_height,
+            ..area
+        };
+
+        for ((title, history, color), area) in [(self.top, top_area), (self.bottom, bottom_area)] {
+            let data = fit_to_width(history, area.width.max(1) as usize);
+            Sparkline::default()
+                .block(Block::default().title(title))
+                .data(&data)
+                .style(Style::default().fg(color))
+                .render(area, buf);
+        }
+    }
+}
+
+/// Grid of colored cells whose intensity tracks a value (0.0 - 100.0)
+///
+/// Cells are laid out row-major and sized to fit the area, so the same
+/// component works for 4 values or 128. Values that still don't fit are
+/// counted in a trailing "+N" marker.
+#[derive(Debug, Clone)]
+pub struct Heatmap<'a> {
+    values: &'a [f32],
+    cell_width: u16,
+}
+
+impl<'a> Heatmap<'a> {
+    /// Create a heatmap from a slice of percentages
+    pub fn new(values: &'a [f32]) -> Self {
+        Self {
+            values,
+            cell_width: 2,
+        }
+    }
+
+    /// Set the width of each cell in columns (minimum 1)
+    pub fn cell_width(mut self, width: u16) -> Self {
+        self.cell_width = width.max(1);
+        self
+    }
+
+    /// Number of (columns, rows) of cells used for `area`
+    pub fn grid_size(&self, area: Rect) -> (u16, u16) {
+        let (columns, rows, _) = self.grid(area);
+        (columns, rows)
+    }
+
+    /// Grid dimensions plus the spacing between cells
+    ///
+    /// Cells are separated by one column when the grid still fits vertically,
+    /// otherwise they are packed edge to edge.
+    fn grid(&self, area: Rect) -> (u16, u16, u16) {
+        let count = self.values.len() as u16;
+        if count == 0 || area.width == 0 || area.height == 0 {
+            return (0, 0, 0);
+        }
+
+        let mut grid = (0, 0, 0);
+        for spacing in [1, 0] {
+            let max_columns = (area.width / (self.cell_width + spacing)).max(1);
+            let columns = count.min(max_columns);
+            let rows = count.div_ceil(columns);
+            grid = (columns, rows, spacing);
+            if rows <= area.height {
+                break;
+            }
+        }
+        grid
+    }
+}
+
+/// Map a percentage to a heat color and shading character
+///
+/// # Example
+/// ```rust
+/// use devdash_widgets::common::charts::heat_cell;
+/// use ratatui::style::Color;
+///
+/// assert_eq!(heat_cell(5.0), ('░', Color::DarkGray));
+/// assert_eq!(heat_cell(95.0), ('█', Color::Red));
+/// ```
+pub fn heat_cell(percent: f32) -> (char, Color) {
+    match percent {
+        p if p < 10.0 => ('░', Color::DarkGray),
+        p if p < 30.0 => ('▒', Color::Blue),
+        p if p < 50.0 => ('▒', Color::Cyan),
+        p if p < 70.0 => ('▓', Color::Green),
+        p if p < 85.0 => ('▓', Color::Yellow),
+        _ => ('█', Color::Red),
+    }
+}
+
+impl RatatuiWidget for Heatmap<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (columns, rows, spacing) = self.grid(area);
+        if columns == 0 {
+            return;
+        }
+
+        // When even packed cells don't fit, the last cells give way to a
+        // "+N" marker counting the values left out
+        let pitch = self.cell_width + spacing;
+        let capacity = (columns * rows.min(area.height)) as usize;
+        let shown = if self.values.len() > capacity {
+            let marker_cells = format!("+{}", self.values.len())
+                .len()
+                .div_ceil(pitch as usize);
+            capacity.saturating_sub(marker_cells)
+        } else {
+            self.values.len()
+        };
+        let position = |i: usize| {
+            let (col, row) = (i as u16 % columns, i as u16 / columns);
+            (area.x + col * pitch, area.y + row)
+        };
+
+        for (i, value) in self.values[..shown].iter().enumerate() {
+            let (x, y) = position(i);
+            let (symbol, color) = heat_cell(*value);
+            let cell: String = std::iter::repeat_n(symbol, self.cell_width as usize).collect();
+            let width = area.right().saturating_sub(x) as usize;
+            buf.set_stringn(x, y, cell, width, Style::default().fg(color));
+        }
+
+        if shown < self.values.len() {
+            let (x, y) = position(shown);
+            let marker = format!("+{}", self.values.len() - shown);
+            let width = area.right().saturating_sub(x) as usize;
+            buf.set_stringn(x, y, marker, width, Style::default());
+        }
+    }
+}
+
+/// One character per count, as high as the count relative to the largest
+///
+/// Nonzero counts always show at least the lowest bar, so a rare value is
+/// never mistaken for none.
+///
+/// # Example
+/// ```rust
+/// use devdash_widgets::common::charts::bar_line;
+///
+/// assert_eq!(bar_line(&[0, 1, 4, 8]), " ▁▄█");
+/// ```
+pub fn bar_line(counts: &[u64]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    counts
+        .iter()
+        .map(|&count| match count {
+            0 => EIGHTHS[0],
+            _ => EIGHTHS[((count * 8).div_ceil(max) as usize).clamp(1, 8)],
+        })
+        .collect()
+}
+
+/// Vertical bars over labelled buckets, e.g. a latency distribution
+///
+/// The bars share the width evenly, one column apart, and are scaled to the
+/// largest count; each bucket's label goes on the bottom row, cut to fit
+/// under its bar.
+#[derive(Debug, Clone)]
+pub struct Histogram<'a> {
+    counts: &'a [u64],
+    labels: &'a [String],
+    color: Color,
+}
+
+impl<'a> Histogram<'a> {
+    /// Create a histogram from bucket counts and their labels
+    pub fn new(counts: &'a [u64], labels: &'a [String]) -> Self {
+        Self {
+            counts,
+            labels,
+            color: Color::Cyan,
+        }
+    }
+
+    /// Set the color of the bars
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl RatatuiWidget for Histogram<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let count = self.counts.len() as u16;
+        if count == 0 || area.height < 2 || area.width < count {
+            return;
+        }
+        let slot = area.width / count;
+        let bar_width = if slot > 1 { slot - 1 } else { slot };
+        let height = (area.height - 1) as u64;
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        let style = Style::default().fg(self.color);
+
+        for (i, &value) in self.counts.iter().enumerate() {
+            let x = area.x + i as u16 * slot;
+            // Height in eighths of a row, at least one for nonzero counts
+            let mut eighths = (value * height * 8).div_ceil(max);
+            if value > 0 {
+                eighths = eighths.max(1);
+            }
+            for row in 0..height {
+                let fill = eighths.saturating_sub(row * 8).min(8) as usize;
+                if fill == 0 {
+                    break;
+                }
+                let y = area.y + (height - 1 - row) as u16;
+                let bar: String = std::iter::repeat_n(EIGHTHS[fill], bar_width as usize).collect();
+                buf.set_string(x, y, bar, style);
+            }
+            if let Some(label) = self.labels.get(i) {
+                buf.set_stringn(
+                    x,
+                    area.bottom() - 1,
+                    label,
+                    slot as usize,
+                    Style::default().fg(Color::Gray),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_text(buf: &Buffer, y: u16) -> String {
+        (buf.area.x..buf.area.right())
+            .map(|x| buf[(x, y)].symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_to_width() {
+        assert_eq!(fit_to_width(&[], 3), vec![0, 0, 0]);
+        assert_eq!(fit_to_width(&[1, 2, 3, 4, 5], 3), vec![3, 4, 5]);
+        assert_eq!(fit_to_width(&[1, 2], 4), vec![1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_activity_bar_render() {
+        let area = Rect::new(0, 0, 12, 1);
+        let mut buf = Buffer::empty(area);
+        ActivityBar::new("Rd", 100.0, Color::Cyan).render(area, &mut buf);
+
+        assert_eq!(row_text(&buf, 0), "Rd ░░▒▒▓▓█  ");
+    }
+
+    #[test]
+    fn test_activity_bar_empty() {
+        let area = Rect::new(0, 0, 12, 1);
+        let mut buf = Buffer::empty(area);
+        ActivityBar::new("Rd", 0.0, Color::Cyan).render(area, &mut buf);
+
+        assert_eq!(row_text(&buf, 0).trim_end(), "Rd");
+    }
+
+    #[test]
+    fn test_labelled_gauge_render() {
+        let area = Rect::new(0, 0, 10, 2);
+        let mut buf = Buffer::empty(area);
+        LabelledGauge::new("RAM", 50.0).render(area, &mut buf);
+
+        assert!(row_text(&buf, 0).starts_with("RAM"));
+        assert_eq!(buf[(0, 1)].fg, Color::Green);
+    }
+
+    #[test]
+    fn test_heatmap_grid_size() {
+        let values = vec![0.0; 64];
+        let heatmap = Heatmap::new(&values);
+        assert_eq!(heatmap.grid_size(Rect::new(0, 0, 48, 10)), (16, 4));
+        assert_eq!(heatmap.grid_size(Rect::new(0, 0, 0, 10)), (0, 0));
+
+        let few = vec![0.0; 4];
+        assert_eq!(
+            Heatmap::new(&few).grid_size(Rect::new(0, 0, 48, 10)),
+            (4, 1)
+        );
+    }
+
+    #[test]
+    fn test_heatmap_render() {
+        let values = [5.0, 95.0];
+        let area = Rect::new(0, 0, 6, 1);
+        let mut buf = Buffer::empty(area);
+        Heatmap::new(&values).render(area, &mut buf);
+
+        assert_eq!(row_text(&buf, 0), "░░ ██ ");
+        assert_eq!(buf[(3, 0)].fg, Color::Red);
+    }
+
+    #[test]
+    fn test_heatmap_overflow_marker() {
+        let values = vec![95.0; 20];
+        let area = Rect::new(0, 0, 5, 2);
+        let mut buf = Buffer::empty(area);
+        Heatmap::new(&values).cell_width(1).render(area, &mut buf);
+
+        assert_eq!(row_text(&buf, 0), "█████");
+        assert_eq!(row_text(&buf, 1), "██+13");
+    }
+
+    #[test]
+    fn test_histogram_render() {
+        let counts = [1, 4, 0];
+        let labels = ["10ms", "25ms", "50ms"].map(String::from);
+        let area = Rect::new(0, 0, 15, 3);
+        let mut buf = Buffer::empty(area);
+        Histogram::new(&counts, &labels).render(area, &mut buf);
+
+        assert_eq!(row_text(&buf, 0), "     ████      ");
+        assert_eq!(row_text(&buf, 1), "▄▄▄▄ ████      ");
+        assert_eq!(row_text(&buf, 2), "10ms 25ms 50ms ");
+    }
+
+    #[test]
+    fn test_dual_sparkline_render() {
+        let area = Rect::new(0, 0, 8, 4);
+        let mut buf = Buffer::empty(area);
+        DualSparkline::new(("Down", &[1, 2], Color::Green), ("Up", &[3], Color::Blue))
+            .render(area, &mut buf);
+
+        assert!(row_text(&buf, 0).starts_with("Down"));
+        assert!(row_text(&buf, 2).starts_with("Up"));
+    }
+}
@@ -1,59 +1,64 @@
 // devdash-widgets/src/common/colors.rs
+use devdash_core::{Theme, theme};
 use ratatui::style::Color;
 
 /// Threshold constants for usage-based coloring
-pub const LOW_THRESHOLD: f64 = 60.0;
-pub const HIGH_THRESHOLD: f64 = 80.0;
+#[deprecated(note = "thresholds come from the theme; use `theme().usage_warning`")]
+pub const LOW_THRESHOLD: f64 = Theme::DEFAULT.usage_warning;
+#[deprecated(note = "thresholds come from the theme; use `theme().usage_critical`")]
+pub const HIGH_THRESHOLD: f64 = Theme::DEFAULT.usage_critical;
 
-/// Get color based on usage percentage
+/// Get color based on usage percentage, in the current [`Theme`]
 ///
 /// # Arguments
 /// * `percentage` - Usage percentage (0.0 - 100.0)
 ///
 /// # Returns
-/// Color based on thresholds:
+/// Color based on the theme's thresholds, by default:
 /// - Green: < 60%
 /// - Yellow: 60% - 80%
 /// - Red: >= 80%
 ///
 /// # Example
 /// ```rust
+/// # use devdash_widgets::common::usage_color;
+/// # use ratatui::style::Color;
 /// assert_eq!(usage_color(45.0), Color::Green);
 /// assert_eq!(usage_color(70.0), Color::Yellow);
 /// assert_eq!(usage_color(85.0), Color::Red);
 /// ```
 pub fn usage_color(percentage: f64) -> Color {
-    if percentage < LOW_THRESHOLD {
-        Color::Green
-    } else if percentage < HIGH_THRESHOLD {
-        Color::Yellow
-    } else {
-        Color::Red
-    }
+    theme().usage_color(percentage)
 }
 
-/// Get color for focus state
+/// Get color for focus state, in the current [`Theme`]
 ///
 /// # Arguments
 /// * `focused` - Whether the widget is currently focused
 ///
 /// # Returns
-/// Yellow if focused, DarkGray if not focused
+/// By default yellow if focused, DarkGray if not focused
 ///
 /// # Example
 /// ```rust
+/// # use devdash_widgets::common::focus_color;
+/// # use ratatui::style::Color;
 /// assert_eq!(focus_color(true), Color::Yellow);
 /// assert_eq!(focus_color(false), Color::DarkGray);
 /// ```
 pub fn focus_color(focused: bool) -> Color {
-    if focused {
-        Color::Yellow
-    } else {
-        Color::DarkGray
-    }
+    theme().focus_color(focused)
+}
+
+/// Colors of the first and second series of a chart, in the current
+/// [`Theme`]
+pub fn series_colors() -> (Color, Color) {
+    let theme = theme();
+    (theme.sparkline, theme.sparkline_alt)
 }
 
 /// Common color palette for consistent theming across widgets
+#[deprecated(note = "colors come from the `[theme]` section; use `devdash_core::theme()`")]
 #[derive(Debug, Clone, Copy)]
 pub struct ColorPalette {
     /// Color for focused widgets
@@ -70,14 +75,16 @@ pub struct ColorPalette {
     pub info: Color,
 }
 
-/// Default color palette used across all widgets
+/// Default color palette, the colors of the default [`Theme`]
+#[deprecated(note = "colors come from the `[theme]` section; use `devdash_core::theme()`")]
+#[allow(deprecated)]
 pub const DEFAULT_PALETTE: ColorPalette = ColorPalette {
-    focus: Color::Yellow,
-    unfocus: Color::DarkGray,
-    good: Color::Green,
-    warning: Color::Yellow,
-    critical: Color::Red,
-    info: Color::Cyan,
+    focus: Theme::DEFAULT.focus,
+    unfocus: Theme::DEFAULT.border,
+    good: Theme::DEFAULT.good,
+    warning: Theme::DEFAULT.warning,
+    critical: Theme::DEFAULT.critical,
+    info: Theme::DEFAULT.sparkline,
 };
 
 /// Get color from palette based on usage percentage
@@ -87,11 +94,14 @@ pub const DEFAULT_PALETTE: ColorPalette = ColorPalette {
 /// * `palette` - Color palette to use
 ///
 /// # Returns
-/// Color from palette based on usage thresholds
+/// Color from palette based on the current [`Theme`]'s usage thresholds
+#[deprecated(note = "use `usage_color`, which follows the `[theme]` section")]
+#[allow(deprecated)]
 pub fn usage_color_palette(percentage: f64, palette: ColorPalette) -> Color {
-    if percentage < LOW_THRESHOLD {
+    let theme = theme();
+    if percentage < theme.usage_warning {
         palette.good
-    } else if percentage < HIGH_THRESHOLD {
+    } else if percentage < theme.usage_critical {
         palette.warning
     } else {
         palette.critical
@@ -106,6 +116,8 @@ pub fn usage_color_palette(percentage: f64, palette: ColorPalette) -> Color {
 ///
 /// # Returns
 /// Focus or unfocus color from palette
+#[deprecated(note = "use `focus_color`, which follows the `[theme]` section")]
+#[allow(deprecated)]
 pub fn focus_color_palette(focused: bool, palette: ColorPalette) -> Color {
     if focused {
         palette.focus
@@ -135,7 +147,9 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_usage_color_palette() {
+        assert_eq!((LOW_THRESHOLD, HIGH_THRESHOLD), (60.0, 80.0));
         let palette = DEFAULT_PALETTE;
         assert_eq!(usage_color_palette(50.0, palette), Color::Green);
         assert_eq!(usage_color_palette(70.0, palette), Color::Yellow);
@@ -143,6 +157,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_focus_color_palette() {
         let palette = DEFAULT_PALETTE;
         assert_eq!(focus_color_palette(true, palette), Color::Yellow);
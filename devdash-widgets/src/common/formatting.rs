@@ -1,8 +1,109 @@
 // devdash-widgets/src/common/formatting.rs
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Whether byte sizes count in powers of 1024 or 1000
+///
+/// Binary units are labelled `KiB`, `MiB`, ...; decimal units `KB`, `MB`, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Binary,
+    Decimal,
+}
+
+impl UnitSystem {
+    /// Size of one kilobyte/kibibyte
+    pub fn base(self) -> f64 {
+        match self {
+            Self::Binary => 1024.0,
+            Self::Decimal => 1000.0,
+        }
+    }
+
+    /// Suffix for `base^power` bytes, up to terabytes
+    pub fn suffix(self, power: u32) -> &'static str {
+        const BINARY: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+        const DECIMAL: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+        let suffixes = match self {
+            Self::Binary => &BINARY,
+            Self::Decimal => &DECIMAL,
+        };
+        suffixes[(power as usize).min(suffixes.len() - 1)]
+    }
+}
+
+impl std::str::FromStr for UnitSystem {
+    type Err = String;
+
+    /// Parse a `units` setting: `binary`/`iec` or `decimal`/`si`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "binary" | "iec" => Ok(Self::Binary),
+            "decimal" | "si" => Ok(Self::Decimal),
+            _ => Err(format!(
+                "unknown units '{}', expected 'binary' or 'decimal'",
+                s
+            )),
+        }
+    }
+}
+
+static DECIMAL_DEFAULT: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static SCOPED_SYSTEM: Cell<Option<UnitSystem>> = const { Cell::new(None) };
+}
+
+/// Set the unit system used by the formatting functions process-wide
+pub fn set_unit_system(system: UnitSystem) {
+    DECIMAL_DEFAULT.store(system == UnitSystem::Decimal, Ordering::Relaxed);
+}
+
+/// The unit system the formatting functions currently use
+pub fn unit_system() -> UnitSystem {
+    SCOPED_SYSTEM.get().unwrap_or_else(|| {
+        if DECIMAL_DEFAULT.load(Ordering::Relaxed) {
+            UnitSystem::Decimal
+        } else {
+            UnitSystem::Binary
+        }
+    })
+}
+
+/// Run `f` with `system` overriding the process-wide unit system on this
+/// thread
+///
+/// Used to apply per-widget unit settings around rendering. `None` keeps
+/// the current system.
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::{UnitSystem, format_bytes, with_unit_system};
+/// let text = with_unit_system(Some(UnitSystem::Decimal), || format_bytes(1500));
+/// assert_eq!(text, "1.5 KB");
+/// ```
+pub fn with_unit_system<R>(system: Option<UnitSystem>, f: impl FnOnce() -> R) -> R {
+    let Some(system) = system else {
+        return f();
+    };
+    let previous = SCOPED_SYSTEM.replace(Some(system));
+    let result = f();
+    SCOPED_SYSTEM.set(previous);
+    result
+}
+
 /// Unit options for byte formatting
 ///
 /// Controls how byte values are displayed in widgets.
 /// Auto automatically selects the most appropriate unit based on the value size.
+/// Whether `KB` means 1000 or 1024 bytes follows the current [`UnitSystem`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Unit {
     /// Automatically select unit (Bytes/KB/MB/GB/TB) based on value size
@@ -31,6 +132,27 @@ impl Unit {
             Self::TB => Self::Auto,
         }
     }
+
+    /// Power of the base this unit stands for; `None` for `Auto`
+    fn power(self) -> Option<u32> {
+        match self {
+            Self::Auto => None,
+            Self::Bytes => Some(0),
+            Self::KB => Some(1),
+            Self::MB => Some(2),
+            Self::GB => Some(3),
+            Self::TB => Some(4),
+        }
+    }
+}
+
+/// Largest power of `base` not exceeding `value`, capped at terabytes
+fn auto_power(value: f64, base: f64) -> u32 {
+    let mut power = 0;
+    while power < 4 && value >= base.powi(power as i32 + 1) {
+        power += 1;
+    }
+    power
 }
 
 /// Format bytes to human-readable string with automatic unit selection
@@ -39,12 +161,13 @@ impl Unit {
 /// * `bytes` - Number of bytes to format
 ///
 /// # Returns
-/// Formatted string with appropriate unit (e.g., "1.5 GB", "512 MB")
+/// Formatted string with appropriate unit (e.g., "1.5 GiB", "512 MiB")
 ///
 /// # Example
 /// ```rust
-/// assert_eq!(format_bytes(1024), "1.0 KB");
-/// assert_eq!(format_bytes(1536 * 1024 * 1024), "1.5 GB");
+/// # use devdash_widgets::common::format_bytes;
+/// assert_eq!(format_bytes(1024), "1.0 KiB");
+/// assert_eq!(format_bytes(1536 * 1024 * 1024), "1.5 GiB");
 /// ```
 pub fn format_bytes(bytes: u64) -> String {
     format_bytes_unit(bytes, Unit::Auto)
@@ -61,34 +184,25 @@ pub fn format_bytes(bytes: u64) -> String {
 ///
 /// # Example
 /// ```rust
-/// assert_eq!(format_bytes_unit(1024, Unit::KB), "1.0 KB");
-/// assert_eq!(format_bytes_unit(1024, Unit::MB), "0.0 MB");
+/// # use devdash_widgets::common::{Unit, format_bytes_unit};
+/// assert_eq!(format_bytes_unit(1024, Unit::KB), "1.0 KiB");
+/// assert_eq!(format_bytes_unit(1024, Unit::MB), "0.0 MiB");
 /// ```
 pub fn format_bytes_unit(bytes: u64, unit: Unit) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    const TB: u64 = GB * 1024;
-
-    match unit {
-        Unit::Auto => {
-            if bytes >= TB {
-                format!("{:.1} TB", bytes as f64 / TB as f64)
-            } else if bytes >= GB {
-                format!("{:.1} GB", bytes as f64 / GB as f64)
-            } else if bytes >= MB {
-                format!("{:.1} MB", bytes as f64 / MB as f64)
-            } else if bytes >= KB {
-                format!("{:.1} KB", bytes as f64 / KB as f64)
-            } else {
-                format!("{} B", bytes)
-            }
-        }
-        Unit::Bytes => format!("{} B", bytes),
-        Unit::KB => format!("{:.1} KB", bytes as f64 / KB as f64),
-        Unit::MB => format!("{:.1} MB", bytes as f64 / MB as f64),
-        Unit::GB => format!("{:.1} GB", bytes as f64 / GB as f64),
-        Unit::TB => format!("{:.1} TB", bytes as f64 / TB as f64),
+    let system = unit_system();
+    let base = system.base();
+    let power = unit
+        .power()
+        .unwrap_or_else(|| auto_power(bytes as f64, base));
+
+    if power == 0 {
+        format!("{} {}", bytes, system.suffix(0))
+    } else {
+        format!(
+            "{:.1} {}",
+            bytes as f64 / base.powi(power as i32),
+            system.suffix(power)
+        )
     }
 }
 
@@ -98,29 +212,26 @@ pub fn format_bytes_unit(bytes: u64, unit: Unit) -> String {
 /// * `bytes_per_sec` - Rate in bytes per second
 ///
 /// # Returns
-/// Formatted rate string (e.g., "15.2 MB/s", "1.5 GB/s")
+/// Formatted rate string (e.g., "15.2 MiB/s", "1.5 GiB/s")
 ///
 /// # Example
 /// ```rust
-/// assert_eq!(format_rate(1024.0), "1.0 KB/s");
-/// assert_eq!(format_rate(15.2 * 1024.0 * 1024.0), "15.2 MB/s");
+/// # use devdash_widgets::common::format_rate;
+/// assert_eq!(format_rate(1024.0), "1.0 KiB/s");
+/// assert_eq!(format_rate(15.2 * 1024.0 * 1024.0), "15.2 MiB/s");
 /// ```
 pub fn format_rate(bytes_per_sec: f64) -> String {
-    const KB: f64 = 1024.0;
-    const MB: f64 = KB * 1024.0;
-    const GB: f64 = MB * 1024.0;
-    const TB: f64 = GB * 1024.0;
-
-    if bytes_per_sec >= TB {
-        format!("{:.1} TB/s", bytes_per_sec / TB)
-    } else if bytes_per_sec >= GB {
-        format!("{:.1} GB/s", bytes_per_sec / GB)
-    } else if bytes_per_sec >= MB {
-        format!("{:.1} MB/s", bytes_per_sec / MB)
-    } else if bytes_per_sec >= KB {
-        format!("{:.1} KB/s", bytes_per_sec / KB)
-    } else {
+    let system = unit_system();
+    let power = auto_power(bytes_per_sec, system.base());
+
+    if power == 0 {
         format!("{:.0} B/s", bytes_per_sec)
+    } else {
+        format!(
+            "{:.1} {}/s",
+            bytes_per_sec / system.base().powi(power as i32),
+            system.suffix(power)
+        )
     }
 }
 
@@ -134,6 +245,7 @@ pub fn format_rate(bytes_per_sec: f64) -> String {
 ///
 /// # Example
 /// ```rust
+/// # use devdash_widgets::common::format_percentage;
 /// assert_eq!(format_percentage(45.2), "45.2%");
 /// assert_eq!(format_percentage(100.0), "100.0%");
 /// ```
@@ -151,6 +263,7 @@ pub fn format_percentage(value: f64) -> String {
 ///
 /// # Example
 /// ```rust
+/// # use devdash_widgets::common::format_number;
 /// assert_eq!(format_number(1234567), "1,234,567");
 /// assert_eq!(format_number(123), "123");
 /// ```
@@ -169,6 +282,281 @@ pub fn format_number(value: u64) -> String {
     result
 }
 
+/// Format a duration as its two largest units
+///
+/// # Arguments
+/// * `duration` - Duration to format
+///
+/// # Returns
+/// Compact duration string (e.g., "45s", "2h 13m", "3d 4h")
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::format_duration;
+/// # use std::time::Duration;
+/// assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+/// assert_eq!(format_duration(Duration::from_secs(2 * 3600 + 13 * 60)), "2h 13m");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes, seconds) =
+        (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Format how long ago `time` was, in its largest whole unit
+///
+/// # Arguments
+/// * `time` - Point in the past; future times count as now
+///
+/// # Returns
+/// Relative time string (e.g., "just now", "5 minutes ago", "3 days ago")
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::format_relative_time;
+/// # use std::time::{Duration, SystemTime};
+/// let then = SystemTime::now() - Duration::from_secs(3 * 86_400);
+/// assert_eq!(format_relative_time(then), "3 days ago");
+/// ```
+pub fn format_relative_time(time: SystemTime) -> String {
+    format_age(SystemTime::now().duration_since(time).unwrap_or_default())
+}
+
+fn format_age(age: Duration) -> String {
+    const UNITS: [(u64, &str); 6] = [
+        (365 * 86_400, "year"),
+        (30 * 86_400, "month"),
+        (7 * 86_400, "week"),
+        (86_400, "day"),
+        (3600, "hour"),
+        (60, "minute"),
+    ];
+
+    let secs = age.as_secs();
+    match UNITS.iter().find(|(unit, _)| secs >= *unit) {
+        Some((unit, name)) => {
+            let count = secs / unit;
+            let plural = if count == 1 { "" } else { "s" };
+            format!("{} {}{} ago", count, name, plural)
+        }
+        None => "just now".to_string(),
+    }
+}
+
+/// Configured `time_format`; `None` uses the locale default
+static TIME_FORMAT: RwLock<Option<String>> = RwLock::new(None);
+
+/// Set the strftime-style format used by [`format_timestamp`]
+///
+/// `None` restores the locale default. Invalid formats are rejected and
+/// leave the current one in place.
+pub fn set_time_format(format: Option<&str>) -> Result<(), String> {
+    if let Some(format) = format {
+        validate_time_format(format)?;
+    }
+    *TIME_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = format.map(str::to_string);
+    Ok(())
+}
+
+/// The format [`format_timestamp`] currently uses
+pub fn time_format() -> String {
+    TIME_FORMAT
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| default_time_format(locale().as_deref()).to_string())
+}
+
+/// Check that `format` only uses known strftime specifiers
+pub fn validate_time_format(format: &str) -> Result<(), String> {
+    chrono::format::StrftimeItems::new(format)
+        .parse()
+        .map(|_| ())
+        .map_err(|_| format!("invalid time_format '{}'", format))
+}
+
+/// Default time format for a POSIX locale name such as `en_US.UTF-8`
+///
+/// Locales that conventionally use a 12-hour clock get one; everything
+/// else, including no locale, gets an ISO 8601 date and 24-hour time.
+fn default_time_format(locale: Option<&str>) -> &'static str {
+    const TWELVE_HOUR: [&str; 6] = ["en_US", "en_CA", "en_AU", "en_NZ", "en_PH", "en_IN"];
+    match locale {
+        Some(locale) if TWELVE_HOUR.iter().any(|l| locale.starts_with(l)) => "%Y-%m-%d %I:%M:%S %p",
+        _ => "%Y-%m-%d %H:%M:%S",
+    }
+}
+
+/// The locale governing time display, from the environment
+fn locale() -> Option<String> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+}
+
+/// Format a point in time in local time using the configured `time_format`
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::{format_timestamp, set_time_format};
+/// # use std::time::SystemTime;
+/// set_time_format(Some("%Y")).unwrap();
+/// assert_eq!(format_timestamp(SystemTime::now()).len(), 4);
+/// ```
+pub fn format_timestamp(time: SystemTime) -> String {
+    format_time_with(time, &time_format())
+}
+
+/// Format a point in time in local time using a strftime `format`
+///
+/// Falls back to ISO 8601 if `format` is invalid; check it first with
+/// [`validate_time_format`].
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::format_time_with;
+/// # use std::time::SystemTime;
+/// assert_eq!(format_time_with(SystemTime::now(), "%H:%M").len(), 5);
+/// ```
+pub fn format_time_with(time: SystemTime, format: &str) -> String {
+    use std::fmt::Write;
+
+    let local = chrono::DateTime::<chrono::Local>::from(time);
+    let mut text = String::new();
+    match write!(text, "{}", local.format(format)) {
+        Ok(()) => text,
+        // Only reachable with an unvalidated format
+        Err(_) => local.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
+
+/// Parse a size such as `2GB` (powers of 1000) or `512MiB` (powers of 1024)
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::parse_size;
+/// assert_eq!(parse_size("2GB"), Ok(2e9));
+/// ```
+pub fn parse_size(s: &str) -> Result<f64, String> {
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, suffix) = s.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size '{}'", s))?;
+    let (base, power) = match suffix.to_ascii_lowercase().as_str() {
+        "" | "b" => (1.0, 0),
+        "kb" => (1000.0, 1),
+        "mb" => (1000.0, 2),
+        "gb" => (1000.0, 3),
+        "tb" => (1000.0, 4),
+        "kib" => (1024.0, 1),
+        "mib" => (1024.0, 2),
+        "gib" => (1024.0, 3),
+        "tib" => (1024.0, 4),
+        _ => return Err(format!("unknown size unit in '{}'", s)),
+    };
+    Ok(number * f64::powi(base, power))
+}
+
+/// Parse an age such as `36h`, `2d` or `1w`, for thresholds too long for
+/// poll intervals
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::parse_age;
+/// # use std::time::Duration;
+/// assert_eq!(parse_age("2d"), Ok(Duration::from_secs(2 * 86_400)));
+/// ```
+pub fn parse_age(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().map_err(|_| format!("invalid age '{}'", s))?;
+    let unit_secs = match unit.trim() {
+        "" | "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        "d" => 86_400.0,
+        "w" => 7.0 * 86_400.0,
+        _ => return Err(format!("unknown unit in age '{}' (use s, m, h, d or w)", s)),
+    };
+    Duration::try_from_secs_f64(number * unit_secs).map_err(|_| format!("invalid age '{}'", s))
+}
+
+/// Cut `text` to at most `width` terminal cells, ending in `…` if it was
+/// cut
+///
+/// Widths count cells, not chars: CJK and most emoji take two, combining
+/// marks none, and a character is never split from its marks.
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::truncate_to_width;
+/// assert_eq!(truncate_to_width("postgres: checkpointer", 10), "postgres:…");
+/// assert_eq!(truncate_to_width("数据库备份", 7), "数据库…");
+/// ```
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut kept = take_width(text.graphemes(true), width.saturating_sub(1));
+    if width > 0 {
+        kept.push('…');
+    }
+    kept
+}
+
+/// Cut the middle out of `text` to fit it in `width` terminal cells,
+/// keeping both ends around a `…`
+///
+/// For paths and names whose end tells them apart, such as
+/// `/mnt/backup/…/2024-05` or `Local Area Connection* 12`.
+///
+/// # Example
+/// ```rust
+/// # use devdash_widgets::common::middle_ellipsis;
+/// assert_eq!(middle_ellipsis("/var/lib/docker/overlay2", 15), "/var/li…verlay2");
+/// ```
+pub fn middle_ellipsis(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let room = width - 1;
+    // The start gets the odd cell
+    let head = take_width(text.graphemes(true), room.div_ceil(2));
+    let tail = take_width(text.graphemes(true).rev(), room - head.width());
+    let tail: String = tail.graphemes(true).rev().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// The leading graphemes of `graphemes` that fit in `width` cells
+fn take_width<'a>(graphemes: impl Iterator<Item = &'a str>, width: usize) -> String {
+    let mut used = 0;
+    graphemes
+        .take_while(|g| {
+            used += g.width();
+            used <= width
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,26 +565,52 @@ mod tests {
     fn test_format_bytes() {
         assert_eq!(format_bytes(0), "0 B");
         assert_eq!(format_bytes(1023), "1023 B");
-        assert_eq!(format_bytes(1024), "1.0 KB");
-        assert_eq!(format_bytes(1536), "1.5 KB");
-        assert_eq!(format_bytes(1024 * 1024), "1.0 MB");
-        assert_eq!(format_bytes(1024 * 1024 * 1024), "1.0 GB");
-        assert_eq!(format_bytes(1024_u64.pow(4)), "1.0 TB");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(1024 * 1024), "1.0 MiB");
+        assert_eq!(format_bytes(1024 * 1024 * 1024), "1.0 GiB");
+        assert_eq!(format_bytes(1024_u64.pow(4)), "1.0 TiB");
+        assert_eq!(format_bytes(1024_u64.pow(5)), "1024.0 TiB");
+    }
+
+    #[test]
+    fn test_format_bytes_decimal() {
+        with_unit_system(Some(UnitSystem::Decimal), || {
+            assert_eq!(format_bytes(999), "999 B");
+            assert_eq!(format_bytes(1000), "1.0 KB");
+            assert_eq!(format_bytes(1024), "1.0 KB");
+            assert_eq!(format_bytes(2_500_000_000), "2.5 GB");
+        });
+        assert_eq!(format_bytes(1000), "1000 B");
     }
 
     #[test]
     fn test_format_bytes_unit() {
-        assert_eq!(format_bytes_unit(1024, Unit::KB), "1.0 KB");
-        assert_eq!(format_bytes_unit(1024, Unit::MB), "0.0 MB");
+        assert_eq!(format_bytes_unit(1024, Unit::KB), "1.0 KiB");
+        assert_eq!(format_bytes_unit(1024, Unit::MB), "0.0 MiB");
         assert_eq!(format_bytes_unit(1024, Unit::Bytes), "1024 B");
+        let mb = with_unit_system(Some(UnitSystem::Decimal), || {
+            format_bytes_unit(3_000_000, Unit::MB)
+        });
+        assert_eq!(mb, "3.0 MB");
     }
 
     #[test]
     fn test_format_rate() {
         assert_eq!(format_rate(0.0), "0 B/s");
-        assert_eq!(format_rate(1024.0), "1.0 KB/s");
-        assert_eq!(format_rate(15.2 * 1024.0 * 1024.0), "15.2 MB/s");
-        assert_eq!(format_rate(1.5 * 1024.0 * 1024.0 * 1024.0), "1.5 GB/s");
+        assert_eq!(format_rate(1024.0), "1.0 KiB/s");
+        assert_eq!(format_rate(15.2 * 1024.0 * 1024.0), "15.2 MiB/s");
+        assert_eq!(format_rate(1.5 * 1024.0 * 1024.0 * 1024.0), "1.5 GiB/s");
+        let rate = with_unit_system(Some(UnitSystem::Decimal), || format_rate(1_500_000.0));
+        assert_eq!(rate, "1.5 MB/s");
+    }
+
+    #[test]
+    fn test_parse_unit_system() {
+        assert_eq!("decimal".parse(), Ok(UnitSystem::Decimal));
+        assert_eq!("SI".parse(), Ok(UnitSystem::Decimal));
+        assert_eq!("binary".parse(), Ok(UnitSystem::Binary));
+        assert!("metric".parse::<UnitSystem>().is_err());
     }
 
     #[test]
@@ -223,4 +637,92 @@ mod tests {
         assert_eq!(Unit::GB.next(), Unit::TB);
         assert_eq!(Unit::TB.next(), Unit::Auto);
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(Duration::from_secs(59)), "59s");
+        assert_eq!(format_duration(Duration::from_secs(192)), "3m 12s");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1h 0m");
+        assert_eq!(
+            format_duration(Duration::from_secs(3 * 86_400 + 4 * 3600 + 59)),
+            "3d 4h"
+        );
+    }
+
+    #[test]
+    fn test_format_relative_time() {
+        assert_eq!(format_age(Duration::from_secs(30)), "just now");
+        assert_eq!(format_age(Duration::from_secs(60)), "1 minute ago");
+        assert_eq!(format_age(Duration::from_secs(5 * 3600)), "5 hours ago");
+        assert_eq!(format_age(Duration::from_secs(13 * 86_400)), "1 week ago");
+        assert_eq!(format_age(Duration::from_secs(800 * 86_400)), "2 years ago");
+
+        let future = SystemTime::now() + Duration::from_secs(60);
+        assert_eq!(format_relative_time(future), "just now");
+    }
+
+    #[test]
+    fn test_time_format() {
+        assert!(validate_time_format("%H:%M").is_ok());
+        assert!(validate_time_format("%Q").is_err());
+        assert!(set_time_format(Some("%Q")).is_err());
+
+        assert_eq!(default_time_format(None), "%Y-%m-%d %H:%M:%S");
+        assert_eq!(
+            default_time_format(Some("de_DE.UTF-8")),
+            "%Y-%m-%d %H:%M:%S"
+        );
+        assert_eq!(
+            default_time_format(Some("en_US.UTF-8")),
+            "%Y-%m-%d %I:%M:%S %p"
+        );
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(parse_size("2GB"), Ok(2e9));
+        assert_eq!(parse_size("512MiB"), Ok(512.0 * 1024.0 * 1024.0));
+        assert_eq!(parse_size("100"), Ok(100.0));
+        assert!(parse_size("2XB").is_err());
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("36h"), Ok(Duration::from_secs(36 * 3600)));
+        assert_eq!(parse_age("1.5d"), Ok(Duration::from_secs(36 * 3600)));
+        assert_eq!(parse_age("1w"), Ok(Duration::from_secs(7 * 86_400)));
+        assert_eq!(parse_age("90"), Ok(Duration::from_secs(90)));
+        assert!(parse_age("2y").is_err());
+        assert!(parse_age("soon").is_err());
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("eth0", 8), "eth0");
+        assert_eq!(truncate_to_width("kworker/0:1H-events", 8), "kworker…");
+        // Wide characters take two cells; one that doesn't fit is dropped
+        assert_eq!(truncate_to_width("修复内存泄漏", 6), "修复…");
+        assert_eq!(truncate_to_width("修复内存泄漏", 7), "修复内…");
+        assert_eq!(truncate_to_width("🚀 deploy v2", 6), "🚀 de…");
+        // Accents stay on their letter
+        assert_eq!(truncate_to_width("cafe\u{301} au lait", 5), "cafe\u{301}…");
+        assert_eq!(truncate_to_width("abc", 1), "…");
+        assert_eq!(truncate_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_middle_ellipsis() {
+        assert_eq!(middle_ellipsis("/home", 10), "/home");
+        assert_eq!(
+            middle_ellipsis("Local Area Connection* 12", 16),
+            "Local Ar…ion* 12"
+        );
+        assert_eq!(middle_ellipsis("/mnt/数据/备份", 9), "/mnt…备份");
+        assert_eq!(middle_ellipsis("/srv/data", 1), "…");
+        assert_eq!(middle_ellipsis("/srv/data", 0), "");
+        for width in 0..12 {
+            assert!(middle_ellipsis("/mnt/数据/备份/2024", width).width() <= width);
+        }
+    }
 }
@@ -1,5 +1,17 @@
+pub mod access;
+pub mod charts;
 pub mod colors;
 pub mod formatting;
+pub mod pattern;
+pub mod table;
+pub mod tail;
+pub mod text;
 
+pub use access::*;
+pub use charts::*;
 pub use colors::*;
 pub use formatting::*;
+pub use pattern::*;
+pub use table::*;
+pub use tail::*;
+pub use text::*;
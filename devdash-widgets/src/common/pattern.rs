@@ -0,0 +1,58 @@
+// devdash-widgets/src/common/pattern.rs
+//! Glob patterns for paths and names in widget settings.
+
+fn is_separator(c: char) -> bool {
+    c == '/' || c == '\\'
+}
+
+/// Whether `text` matches the glob `pattern`
+///
+/// `*` matches within one path component, `**` across components, and `?`
+/// any single character other than a separator.
+///
+/// # Examples
+/// ```
+/// use devdash_widgets::common::glob_match;
+///
+/// assert!(glob_match("/mnt/*", "/mnt/usb"));
+/// assert!(!glob_match("/mnt/*", "/mnt/usb/part1"));
+/// assert!(glob_match("/run/**", "/run/user/1000"));
+/// ```
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches(&pattern, &text)
+}
+
+fn matches(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| matches(rest, &text[i..])),
+        ['*', rest @ ..] => {
+            let end = text
+                .iter()
+                .position(|&c| is_separator(c))
+                .unwrap_or(text.len());
+            (0..=end).any(|i| matches(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => {
+            text.first().is_some_and(|&c| !is_separator(c)) && matches(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && matches(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("/", "/"));
+        assert!(!glob_match("/", "/home"));
+        assert!(glob_match("/snap/**", "/snap/core/123"));
+        assert!(glob_match("/media/*/usb?", "/media/dev/usb1"));
+        assert!(!glob_match("/media/*/usb?", "/media/dev/usb12"));
+        assert!(glob_match("*:\\", "D:\\"));
+    }
+}
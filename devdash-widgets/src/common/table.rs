@@ -0,0 +1,88 @@
+// devdash-widgets/src/common/table.rs
+//! Keeping a table's selection and scroll position in the widget's saved
+//! state, so they survive switching dashboards and restarts, and sizing
+//! cell text to its column.
+use devdash_core::WidgetState;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    widgets::TableState,
+};
+
+/// Store the selected row and the first row shown
+pub fn save_table(table: &TableState, state: &mut WidgetState) {
+    if let Some(selected) = table.selected() {
+        state.insert("selected".to_string(), (selected as i64).into());
+    }
+    state.insert("offset".to_string(), (table.offset() as i64).into());
+}
+
+/// Select the row and scroll to where [`save_table`] left the table
+///
+/// The rows may not be loaded yet; widgets clamp the selection when they
+/// arrive, and rendering pulls the offset back to the selection.
+pub fn restore_table(table: &mut TableState, state: &WidgetState) {
+    let row = |key| {
+        state
+            .get(key)
+            .and_then(|v| v.as_integer())
+            .and_then(|n| usize::try_from(n).ok())
+    };
+    if let Some(selected) = row("selected") {
+        table.select(Some(selected));
+    }
+    if let Some(offset) = row("offset") {
+        *table.offset_mut() = offset;
+    }
+}
+
+/// How wide a `Table` with `widths`, the default column spacing and a
+/// highlight symbol of `marker` cells makes column `index` in `width` cells
+///
+/// For cutting cell text to fit before the table is built, which otherwise
+/// clips it without a sign.
+pub fn column_width(widths: &[Constraint], index: usize, width: u16, marker: u16) -> u16 {
+    Layout::horizontal(widths)
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(Rect::new(0, 0, width.saturating_sub(marker), 1))
+        .get(index)
+        .map_or(0, |column| column.width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut state = WidgetState::new();
+        save_table(
+            &TableState::default().with_selected(Some(12)).with_offset(4),
+            &mut state,
+        );
+
+        let mut table = TableState::default();
+        restore_table(&mut table, &state);
+        assert_eq!((table.selected(), table.offset()), (Some(12), 4));
+
+        // Nothing selected leaves the selection alone
+        let mut state = WidgetState::new();
+        save_table(&TableState::default(), &mut state);
+        let mut table = TableState::default().with_selected(Some(0));
+        restore_table(&mut table, &state);
+        assert_eq!(table.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_column_width() {
+        let widths = [
+            Constraint::Length(7),
+            Constraint::Min(12),
+            Constraint::Length(6),
+        ];
+        // 60 cells less the marker, the fixed columns and two spaces
+        assert_eq!(column_width(&widths, 1, 60, 3), 42);
+        assert_eq!(column_width(&widths, 0, 60, 3), 7);
+        assert_eq!(column_width(&widths, 3, 60, 3), 0);
+    }
+}
@@ -0,0 +1,102 @@
+// devdash-widgets/src/common/tail.rs
+//! Following a log file as it grows, like `tail -f`.
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// Reads the lines appended to a file since the last read
+///
+/// The first read starts at the end of the file, so only new lines are
+/// returned. A file shorter than what was already read is taken to have
+/// been rotated or truncated and is read again from the start.
+#[derive(Debug)]
+pub struct LogTail {
+    path: PathBuf,
+    /// Bytes read so far; `None` before the first read
+    offset: Option<u64>,
+    /// Start of a line whose end hasn't been written yet
+    partial: String,
+}
+
+impl LogTail {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            offset: None,
+            partial: String::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Complete lines appended since the last read
+    pub fn read_lines(&mut self) -> io::Result<Vec<String>> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        let offset = match self.offset {
+            None => {
+                self.offset = Some(len);
+                return Ok(Vec::new());
+            }
+            Some(offset) if offset > len => {
+                self.partial.clear();
+                0
+            }
+            Some(offset) => offset,
+        };
+
+        file.seek(SeekFrom::Start(offset))?;
+        let mut bytes = Vec::new();
+        file.take(len - offset).read_to_end(&mut bytes)?;
+        self.offset = Some(offset + bytes.len() as u64);
+
+        self.partial.push_str(&String::from_utf8_lossy(&bytes));
+        let Some(end) = self.partial.rfind('\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.partial.split_off(end + 1);
+        let lines = std::mem::replace(&mut self.partial, rest)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, io::Write};
+
+    #[test]
+    fn test_read_lines() {
+        let path = std::env::temp_dir().join(format!("devdash-tail-{}", std::process::id()));
+        fs::write(&path, "old line\n").unwrap();
+        let append = |text: &str| {
+            fs::OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .write_all(text.as_bytes())
+                .unwrap()
+        };
+
+        let mut tail = LogTail::new(&path);
+        assert!(tail.read_lines().unwrap().is_empty());
+        append("first\nsec");
+        assert_eq!(tail.read_lines().unwrap(), ["first"]);
+        append("ond\n");
+        assert_eq!(tail.read_lines().unwrap(), ["second"]);
+
+        // Rotated
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), ["new"]);
+
+        fs::remove_file(&path).unwrap();
+        assert!(tail.read_lines().is_err());
+    }
+}
@@ -0,0 +1,58 @@
+// devdash-widgets/src/common/text.rs
+//! Writing a line of text straight into a buffer area.
+//!
+//! Widgets that lay out their own rows instead of using a `Paragraph` or
+//! `Table` write them with [`draw_text`], which counts terminal cells
+//! rather than chars: CJK and most emoji take two cells, combining marks
+//! none.
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+/// Write `line` on the first row of `area`, cut off at its right edge
+///
+/// A wide character that would straddle the edge is left out rather than
+/// split, and combining marks stay with the character they follow.
+pub fn draw_text(buf: &mut Buffer, area: Rect, line: &str, style: Style) {
+    let area = area.intersection(buf.area);
+    if area.is_empty() {
+        return;
+    }
+    buf.set_stringn(area.x, area.y, line, area.width as usize, style);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn test_draw_text() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 8, 3));
+        let style = Style::default().fg(Color::Cyan);
+        draw_text(
+            &mut buf,
+            Rect::new(1, 0, 6, 1),
+            "eth0 up and running",
+            style,
+        );
+        assert_eq!(row(&buf, 0), " eth0 u ");
+        assert_eq!(buf[(1, 0)].fg, Color::Cyan);
+        assert_eq!(buf[(7, 0)].fg, Color::Reset);
+
+        // 数据 takes four cells; the second wide char doesn't fit in three
+        draw_text(&mut buf, Rect::new(0, 1, 3, 1), "数据盘", style);
+        assert_eq!(buf[(0, 1)].symbol(), "数");
+        assert_eq!(buf[(2, 1)].symbol(), " ");
+
+        // The accent combines with the e before it
+        draw_text(&mut buf, Rect::new(0, 2, 8, 1), "cafe\u{301} ok", style);
+        assert_eq!(buf[(3, 2)].symbol(), "e\u{301}");
+        assert_eq!(buf[(5, 2)].symbol(), "o");
+
+        // Outside the buffer
+        draw_text(&mut buf, Rect::new(0, 5, 8, 1), "nowhere", style);
+    }
+}
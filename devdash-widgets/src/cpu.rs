@@ -0,0 +1,416 @@
+// devdash-widgets/src/cpu.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Action, DataStatus, EventBus, EventResult, Widget,
+    event::{Event, Subscription},
+    widget::DEFAULT_UPDATE_INTERVAL,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget as RatatuiWidget,
+    style::Style,
+    widgets::{Block, Borders, Sparkline},
+};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+use crate::common::{Heatmap, fit_to_width, focus_color, series_colors};
+use crate::platform::cgroup::{self, Cgroup};
+
+/// Number of samples averaged per core in the heatmap
+const CORE_WINDOW: usize = 5;
+
+/// CPU usage published to the event bus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CpuMetrics {
+    /// Overall usage percentage (0.0 - 100.0), of the container's quota
+    /// when `container` is set
+    pub usage: f32,
+    /// Usage percentage of each core
+    pub cores: Vec<f32>,
+    /// Whether `usage` is relative to a container's CPU quota
+    #[serde(default)]
+    pub container: bool,
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("toggle_view", &["t"], "Toggle sparkline/per-core heatmap"),
+    Action::new("history", &["h"], "Cycle history length"),
+    Action::new("percentage", &["p"], "Toggle percentage in title"),
+    Action::new("reset", &["r"], "Reset history"),
+    Action::new("faster", &["+", "="], "Poll faster"),
+    Action::new("slower", &["-"], "Poll slower"),
+];
+
+/// View mode for CpuWidget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewMode {
+    /// Global usage history as a sparkline
+    Sparkline,
+    /// One cell per core, colored by recent utilization
+    Heatmap,
+}
+
+/// CPU monitoring widget with a usage sparkline and a per-core heatmap
+///
+/// # Keyboard Shortcuts
+/// - `t` - Toggle between sparkline and per-core heatmap views
+/// - `h` - Toggle history length (30 → 60 → 120 → 300)
+/// - `p` - Toggle percentage display
+/// - `r` - Reset history
+/// - `+`/`-` - Poll faster/slower
+///
+/// # Event Publishing
+/// - Publishes `system.cpu` events on each poll with overall and per-core
+///   usage as [`CpuMetrics`]
+///
+/// Inside a container with a CPU quota, the usage figure and sparkline are
+/// relative to the quota rather than to all host cores.
+///
+/// When the event bus is not live, the widget displays `system.cpu` events
+/// published by others instead of polling the system.
+pub struct CpuWidget {
+    system: System,
+    container: Option<Cgroup>,
+    /// Last (wall time, cgroup CPU time) sample for quota-relative usage
+    container_sample: Option<(Instant, Duration)>,
+    usage: f32,
+    history: Vec<u64>,
+    core_history: Vec<Vec<f32>>, // Last CORE_WINDOW samples per core
+    view_mode: ViewMode,
+    poll_interval: Duration,
+    time_since_poll: Duration,
+    max_history: usize,
+    show_percentage: bool,
+
+    // Event bus
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+}
+
+impl CpuWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        let mut system = System::new_all();
+        system.refresh_cpu_all();
+
+        Self {
+            system,
+            container: None,
+            container_sample: None,
+            usage: 0.0,
+            history: Vec::with_capacity(60),
+            core_history: Vec::new(),
+            view_mode: ViewMode::Sparkline,
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+            max_history: 60,
+            show_percentage: true,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+        }
+    }
+
+    fn poll_cpu(&mut self) {
+        // Refresh CPU info and get global usage
+        self.system.refresh_cpu_all();
+        let container_usage = self.container_usage();
+        let metrics = CpuMetrics {
+            usage: container_usage.unwrap_or_else(|| self.system.global_cpu_usage()),
+            cores: self
+                .system
+                .cpus()
+                .iter()
+                .map(|cpu| cpu.cpu_usage())
+                .collect(),
+            container: container_usage.is_some(),
+        };
+        self.record(&metrics);
+        self.event_bus.publish(Event::new("system.cpu", metrics));
+    }
+
+    /// Add a sample to the usage history and the per-core window
+    fn record(&mut self, metrics: &CpuMetrics) {
+        self.usage = metrics.usage;
+        self.history.push(self.usage as u64);
+        if self.history.len() > self.max_history {
+            self.history.remove(0);
+        }
+
+        self.core_history.resize_with(metrics.cores.len(), Vec::new);
+        for (samples, usage) in self.core_history.iter_mut().zip(&metrics.cores) {
+            samples.push(*usage);
+            if samples.len() > CORE_WINDOW {
+                samples.remove(0);
+            }
+        }
+    }
+
+    /// Usage of the container's CPU quota since the previous sample
+    fn container_usage(&mut self) -> Option<f32> {
+        let container = self.container.as_ref()?;
+        let limit = container.cpu_limit()?;
+        let now = (Instant::now(), container.cpu_usage()?);
+        let (then_wall, then_cpu) = self.container_sample.replace(now)?;
+
+        let wall = now.0.duration_since(then_wall).as_secs_f64() * limit;
+        (wall > 0.0).then(|| {
+            let used = now.1.saturating_sub(then_cpu).as_secs_f64();
+            (used / wall * 100.0).min(100.0) as f32
+        })
+    }
+
+    /// Average utilization of each core over the recent window
+    fn core_averages(&self) -> Vec<f32> {
+        self.core_history
+            .iter()
+            .map(|samples| {
+                if samples.is_empty() {
+                    0.0
+                } else {
+                    samples.iter().sum::<f32>() / samples.len() as f32
+                }
+            })
+            .collect()
+    }
+
+    fn render_sparkline(&self, area: Rect, buf: &mut Buffer, block: Block) {
+        // Generate data points to fill the available width
+        // Account for borders (2 chars) and title space
+        let available_width = area.width.saturating_sub(4).max(1) as usize;
+        let display_data = fit_to_width(&self.history, available_width);
+
+        Sparkline::default()
+            .block(block)
+            .data(&display_data)
+            .style(Style::default().fg(series_colors().0))
+            .render(area, buf);
+    }
+
+    fn render_heatmap(&self, area: Rect, buf: &mut Buffer, block: Block) {
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let averages = self.core_averages();
+        let heatmap = Heatmap::new(&averages);
+
+        // Fall back to single-column cells when 2-wide cells would not fit
+        let heatmap = match heatmap.grid_size(inner) {
+            (_, rows) if rows > inner.height => heatmap.cell_width(1),
+            _ => heatmap,
+        };
+        heatmap.render(inner, buf);
+    }
+}
+
+impl Widget for CpuWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected metrics instead of polling
+            let (sub, rx) = self.event_bus.subscribe("system.cpu");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        // Show usage against the container's quota, if there is one
+        self.container =
+            Cgroup::current().filter(|c| cgroup::in_container() && c.cpu_limit().is_some());
+
+        self.poll_cpu(); // Initial poll
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let received: Vec<_> = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<CpuMetrics>())
+                .collect();
+            if !received.is_empty() {
+                self.last_injected = Instant::now();
+            }
+            for metrics in received {
+                self.record(&metrics);
+            }
+            return;
+        }
+
+        self.time_since_poll += delta;
+
+        if self.time_since_poll >= self.poll_interval {
+            self.poll_cpu();
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        self.injected.as_ref().map(|_| DataStatus {
+            refreshing: false,
+            last_data: self.last_injected,
+            interval: self.poll_interval,
+            retry_at: None,
+        })
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        if let devdash_core::Event::Action(action) = event {
+            match action.as_str() {
+                "toggle_view" => {
+                    self.view_mode = match self.view_mode {
+                        ViewMode::Sparkline => ViewMode::Heatmap,
+                        ViewMode::Heatmap => ViewMode::Sparkline,
+                    };
+                    return EventResult::Consumed;
+                }
+                "history" => {
+                    // Toggle history length: 30, 60, 120, 300
+                    self.max_history = match self.max_history {
+                        30 => 60,
+                        60 => 120,
+                        120 => 300,
+                        _ => 30,
+                    };
+                    // Trim history if needed
+                    if self.history.len() > self.max_history {
+                        self.history.drain(0..self.history.len() - self.max_history);
+                    }
+                    return EventResult::Consumed;
+                }
+                "percentage" => {
+                    // Toggle percentage display
+                    self.show_percentage = !self.show_percentage;
+                    return EventResult::Consumed;
+                }
+                "reset" => {
+                    // Reset/clear history
+                    self.history.clear();
+                    self.core_history.iter_mut().for_each(Vec::clear);
+                    return EventResult::Consumed;
+                }
+                "faster" => {
+                    // Increase poll frequency (faster updates)
+                    self.poll_interval = self
+                        .poll_interval
+                        .saturating_sub(Duration::from_millis(100));
+                    return EventResult::Consumed;
+                }
+                "slower" => {
+                    // Decrease poll frequency (slower updates)
+                    self.poll_interval += Duration::from_millis(100);
+                    return EventResult::Consumed;
+                }
+                _ => {}
+            }
+        }
+
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let border_color = focus_color(focused);
+
+        let detail = match self.view_mode {
+            ViewMode::Sparkline => format!("H:{}", self.max_history),
+            ViewMode::Heatmap => format!("{} cores", self.core_history.len()),
+        };
+        let badge = if self.container.is_some() {
+            " (container)"
+        } else {
+            ""
+        };
+        let title = if self.show_percentage {
+            format!(" CPU{} {:.1}% [{}] ", badge, self.usage, detail)
+        } else {
+            format!(" CPU{} [{}] ", badge, detail)
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(border_color));
+
+        match self.view_mode {
+            ViewMode::Sparkline => self.render_sparkline(area, buf, block),
+            ViewMode::Heatmap => self.render_heatmap(area, buf, block),
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        true // Always poll for updates
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn summary(&self) -> Option<String> {
+        let scope = if self.container.is_some() {
+            " of container limit"
+        } else {
+            ""
+        };
+        Some(format!("CPU {:.1}% used{}", self.usage, scope))
+    }
+
+    fn update_interval(&self) -> Duration {
+        // `+` can shrink the interval to zero; don't spin
+        self.poll_interval.max(DEFAULT_UPDATE_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(cores: &[f32]) -> CpuMetrics {
+        CpuMetrics {
+            usage: 0.0,
+            cores: cores.to_vec(),
+            container: false,
+        }
+    }
+
+    #[test]
+    fn test_toggle_view() {
+        let mut widget = CpuWidget::new(EventBus::new(), Duration::from_secs(1));
+        assert_eq!(widget.view_mode, ViewMode::Sparkline);
+
+        let toggle = || devdash_core::Event::Action("toggle_view".into());
+        assert_eq!(widget.on_event(toggle()), EventResult::Consumed);
+        assert_eq!(widget.view_mode, ViewMode::Heatmap);
+        widget.on_event(toggle());
+        assert_eq!(widget.view_mode, ViewMode::Sparkline);
+    }
+
+    #[test]
+    fn test_core_averages_use_recent_window() {
+        let mut widget = CpuWidget::new(EventBus::new(), Duration::from_secs(1));
+        assert!(widget.core_averages().is_empty());
+
+        // An old spike falls out of the window after CORE_WINDOW samples
+        widget.record(&metrics(&[100.0, 0.0]));
+        for _ in 0..CORE_WINDOW {
+            widget.record(&metrics(&[10.0, 50.0]));
+        }
+        assert_eq!(widget.core_averages(), vec![10.0, 50.0]);
+
+        // Cores that appear later average only their own samples
+        widget.record(&metrics(&[10.0, 50.0, 80.0]));
+        assert_eq!(widget.core_averages(), vec![10.0, 50.0, 80.0]);
+
+        widget.on_event(devdash_core::Event::Action("reset".into()));
+        assert_eq!(widget.core_averages(), vec![0.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,300 @@
+// devdash-widgets/src/cron.rs
+//! Crontab schedules and the times they fire.
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use std::str::FromStr;
+
+/// How far ahead or back to look for a matching time; a schedule such as
+/// "Feb 29 on a Monday" may not fire for years
+const SEARCH_DAYS: i64 = 8 * 366;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// The five time fields of a crontab line, or one of the `@daily` style
+/// shorthands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    /// Bits 1 to 31
+    days: u32,
+    /// Bits 1 to 12
+    months: u16,
+    /// Bits 0 (Sunday) to 6
+    weekdays: u8,
+    /// With both day fields restricted, either one matching is enough
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let expanded = match text.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(format!("unsupported schedule '{}'", other));
+            }
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("expected 5 fields in '{}'", text));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, &WEEKDAYS)?;
+        // 7 is Sunday too
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])? as u32,
+            days: parse_field(day, 1, 31, &[])? as u32,
+            months: parse_field(month, 1, 12, &MONTHS)? as u16,
+            weekdays: (weekdays & 0x7f) as u8,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+}
+
+/// Bit set of the values a field such as `1-5`, `*/15` or `mon,wed` allows
+///
+/// `names` spell out values from `min` up.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let lower = text.to_ascii_lowercase();
+        let named = names.iter().position(|name| *name == lower);
+        let value = match named {
+            Some(i) => i as u32 + min,
+            None => text
+                .parse()
+                .map_err(|_| format!("invalid value '{}'", text))?,
+        };
+        if (min..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(format!("{} is outside {}-{}", value, min, max))
+        }
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step in '{}'", part))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/10` runs from 5 to the end
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+impl CronSchedule {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    fn matches(&self, time: NaiveDateTime) -> bool {
+        self.months & (1 << time.month()) != 0
+            && self.matches_day(time.date())
+            && self.hours & (1 << time.hour()) != 0
+            && self.minutes & (1 << time.minute()) != 0
+    }
+
+    /// The first time after `after` the schedule fires
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        self.search(start, Duration::minutes(1))
+    }
+
+    /// The last time before `before` the schedule fired
+    pub fn previous_before(&self, before: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = before.naive_local().with_second(0)?.with_nanosecond(0)? - Duration::minutes(1);
+        self.search(start, Duration::minutes(-1))
+    }
+
+    /// Step a minute at a time from `start`, skipping whole days that
+    /// can't match
+    fn search(&self, start: NaiveDateTime, step: Duration) -> Option<DateTime<Local>> {
+        let limit = Duration::days(SEARCH_DAYS);
+        let mut time = start;
+        while (time - start).abs() < limit {
+            let date = time.date();
+            if self.months & (1 << date.month()) == 0 || !self.matches_day(date) {
+                time = if step > Duration::zero() {
+                    date.succ_opt()?.and_hms_opt(0, 0, 0)?
+                } else {
+                    date.pred_opt()?.and_hms_opt(23, 59, 0)?
+                };
+                continue;
+            }
+            // Local times skipped by a DST change never fire
+            if self.matches(time)
+                && let Some(local) = Local.from_local_datetime(&time).earliest()
+            {
+                return Some(local);
+            }
+            time += step;
+        }
+        None
+    }
+}
+
+/// One job from a crontab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronJob {
+    pub schedule: CronSchedule,
+    pub command: String,
+}
+
+/// The jobs in the text of a crontab, skipping comments, variable
+/// assignments, `@reboot` jobs and lines that don't parse
+pub fn parse_crontab(text: &str) -> Vec<CronJob> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            // `NAME=value` lines set variables
+            if line.split_whitespace().next()?.contains('=') {
+                return None;
+            }
+            let fields = if line.starts_with('@') { 1 } else { 5 };
+            let mut schedule = Vec::new();
+            let mut rest = line;
+            for _ in 0..fields {
+                let (field, tail) = rest.split_once(char::is_whitespace)?;
+                schedule.push(field);
+                rest = tail.trim_start();
+            }
+            Some(CronJob {
+                schedule: schedule.join(" ").parse().ok()?,
+                command: rest.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(text: &str) -> DateTime<Local> {
+        let time = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&time).earliest().unwrap()
+    }
+
+    fn next(schedule: &str, after: &str) -> String {
+        let schedule: CronSchedule = schedule.parse().unwrap();
+        schedule
+            .next_after(local(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M %a")
+            .to_string()
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(
+            next("30 2 * * *", "2024-05-01 02:30"),
+            "2024-05-02 02:30 Thu"
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2024-05-01 10:07"),
+            "2024-05-01 10:15 Wed"
+        );
+        assert_eq!(
+            next("0 9 * * mon-fri", "2024-05-03 09:00"),
+            "2024-05-06 09:00 Mon"
+        );
+        assert_eq!(next("@monthly", "2024-05-01 00:00"), "2024-06-01 00:00 Sat");
+        assert_eq!(
+            next("0 0 29 feb *", "2024-03-01 00:00"),
+            "2028-02-29 00:00 Tue"
+        );
+        // Either day field may match when both are set
+        assert_eq!(
+            next("0 12 13 * 5", "2024-05-01 00:00"),
+            "2024-05-03 12:00 Fri"
+        );
+        assert_eq!(
+            next("0 0 * * 7", "2024-05-01 00:00"),
+            "2024-05-05 00:00 Sun"
+        );
+    }
+
+    #[test]
+    fn test_previous_before() {
+        let schedule: CronSchedule = "0 3 * * *".parse().unwrap();
+        let previous = schedule.previous_before(local("2024-05-01 09:00")).unwrap();
+        assert_eq!(previous, local("2024-05-01 03:00"));
+        let previous = schedule.previous_before(local("2024-05-01 03:00")).unwrap();
+        assert_eq!(previous, local("2024-04-30 03:00"));
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        for text in [
+            "",
+            "* * * *",
+            "61 * * * *",
+            "* * * * funday",
+            "*/0 * * * *",
+            "@reboot",
+        ] {
+            assert!(text.parse::<CronSchedule>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_crontab() {
+        let jobs = parse_crontab(
+            "# m h dom mon dow command\n\
+             MAILTO=me@example.com\n\
+             \n\
+             30 2 * * * /usr/local/bin/backup --full  >/dev/null 2>&1\n\
+             @reboot /usr/bin/start-agent\n\
+             @daily  cleanup-tmp\n\
+             not a job\n",
+        );
+        let commands: Vec<&str> = jobs.iter().map(|j| j.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            [
+                "/usr/local/bin/backup --full  >/dev/null 2>&1",
+                "cleanup-tmp"
+            ]
+        );
+    }
+}
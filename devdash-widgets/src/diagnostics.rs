@@ -0,0 +1,696 @@
+// devdash-widgets/src/diagnostics.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Action, Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WidgetState,
+    WorkerPool, autostart,
+    event::{Event, Subscription},
+    opener, parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    common::{focus_color, format_relative_time, restore_table, save_table},
+    platform::shell,
+};
+
+/// The project is checked for changes at most this often, however short the
+/// widget's interval; each check walks the source tree
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Directories never looked at for changed sources
+const SKIPPED_DIRS: [&str; 2] = ["target", "node_modules"];
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+/// One compiler error or warning
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+    /// Lint or error code, e.g. `E0425` or `unused_variables`
+    #[serde(default)]
+    pub code: Option<String>,
+    /// Where the primary span starts, as the compiler reported it
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    #[serde(default)]
+    pub line: Option<u32>,
+    #[serde(default)]
+    pub column: Option<u32>,
+}
+
+impl Diagnostic {
+    /// `src/main.rs:12:5`, or as much of it as is known
+    pub fn location(&self) -> String {
+        let Some(file) = &self.file else {
+            return String::new();
+        };
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", file.display(), line, column),
+            (Some(line), None) => format!("{}:{}", file.display(), line),
+            _ => file.display().to_string(),
+        }
+    }
+}
+
+/// The outcome of one check of the project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticReport {
+    /// Errors first, then warnings, each in the order reported
+    pub diagnostics: Vec<Diagnostic>,
+    /// Why the check couldn't run
+    #[serde(default)]
+    pub error: Option<String>,
+    pub checked_at: Option<SystemTime>,
+}
+
+impl DiagnosticReport {
+    pub fn count(&self, level: DiagnosticLevel) -> usize {
+        self.diagnostics.iter().filter(|d| d.level == level).count()
+    }
+}
+
+/// Parse compiler diagnostics printed as JSON, one object per line
+///
+/// Understands `cargo check --message-format=json` (and `cargo clippy`),
+/// whose `compiler-message` lines wrap rustc's diagnostics, and rustc's own
+/// `--error-format=json`. Other lines are skipped, as are the closing
+/// "aborting due to" and "N warnings emitted" summaries and diagnostics
+/// reported twice, e.g. for a library and its tests.
+pub fn parse_messages(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for line in output.lines() {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let message = if value["reason"] == "compiler-message" {
+            &value["message"]
+        } else if value["$message_type"] == "diagnostic" {
+            &value
+        } else {
+            continue;
+        };
+        if let Some(diagnostic) = parse_diagnostic(message)
+            && !diagnostics.contains(&diagnostic)
+        {
+            diagnostics.push(diagnostic);
+        }
+    }
+    diagnostics.sort_by_key(|d| d.level);
+    diagnostics
+}
+
+fn parse_diagnostic(message: &Value) -> Option<Diagnostic> {
+    let level = match message["level"].as_str()? {
+        "error" | "error: internal compiler error" => DiagnosticLevel::Error,
+        "warning" => DiagnosticLevel::Warning,
+        _ => return None,
+    };
+    let text = message["message"].as_str()?;
+    let spans = message["spans"].as_array().map_or(&[][..], Vec::as_slice);
+    if spans.is_empty() && (text.starts_with("aborting due to") || text.ends_with(" emitted")) {
+        return None;
+    }
+    let primary = spans.iter().find(|span| span["is_primary"] == true);
+    let number = |field: &str| {
+        primary
+            .and_then(|span| span[field].as_u64())
+            .map(|n| n as u32)
+    };
+    Some(Diagnostic {
+        level,
+        message: text.to_string(),
+        code: message["code"]["code"].as_str().map(str::to_string),
+        file: primary
+            .and_then(|span| span["file_name"].as_str())
+            .map(PathBuf::from),
+        line: number("line_start"),
+        column: number("column_start"),
+    })
+}
+
+/// Newest modification time of the files under `dir`, leaving out build
+/// output and hidden directories
+fn newest_source(dir: &Path) -> SystemTime {
+    let mut newest = SystemTime::UNIX_EPOCH;
+    let Ok(entries) = fs::read_dir(dir) else {
+        return newest;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if file_type.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref()) {
+                newest = newest.max(newest_source(&entry.path()));
+            }
+        } else if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+            newest = newest.max(modified);
+        }
+    }
+    newest
+}
+
+/// Diagnostics widget settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticsOptions {
+    /// Project to check; the current directory when unset
+    pub path: Option<PathBuf>,
+    /// Shell command printing JSON diagnostics, run in `path`; `cargo check
+    /// --all-targets --message-format=json` when unset
+    pub command: Option<String>,
+}
+
+/// What each check needs, kept between runs
+struct Checker {
+    root: PathBuf,
+    command: Option<String>,
+    /// Newest source time when last checked; `None` before the first check
+    checked: Option<SystemTime>,
+    /// Set to check even if nothing changed
+    force: Arc<AtomicBool>,
+}
+
+/// Check the project if its sources changed since the last check (runs on a
+/// worker thread)
+fn check(checker: &mut Checker) -> Option<DiagnosticReport> {
+    let newest = newest_source(&checker.root);
+    let forced = checker.force.swap(false, Ordering::Relaxed);
+    if !forced && checker.checked == Some(newest) {
+        return None;
+    }
+    checker.checked = Some(newest);
+
+    let mut command = match &checker.command {
+        // An untrusted project's command only runs when asked for
+        Some(_) if !forced && !autostart() => {
+            return Some(DiagnosticReport {
+                error: Some("Not run until the project is trusted; press r to run it".to_string()),
+                checked_at: Some(SystemTime::now()),
+                ..DiagnosticReport::default()
+            });
+        }
+        Some(command) => shell(command),
+        None => {
+            let mut cargo = Command::new("cargo");
+            cargo.args(["check", "--all-targets", "--message-format=json"]);
+            cargo
+        }
+    };
+    let report = match command.current_dir(&checker.root).output() {
+        Ok(output) => {
+            let diagnostics = parse_messages(&String::from_utf8_lossy(&output.stdout));
+            // A failed check that reported nothing couldn't run at all
+            let error = (!output.status.success() && diagnostics.is_empty()).then(|| {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                stderr
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| line.starts_with("error"))
+                    .or_else(|| stderr.lines().map(str::trim).find(|line| !line.is_empty()))
+                    .map_or(format!("check failed ({})", output.status), str::to_string)
+            });
+            DiagnosticReport {
+                diagnostics,
+                error,
+                checked_at: Some(SystemTime::now()),
+            }
+        }
+        Err(e) => DiagnosticReport {
+            error: Some(format!("Can't run the check: {}", e)),
+            checked_at: Some(SystemTime::now()),
+            ..DiagnosticReport::default()
+        },
+    };
+    Some(report)
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Select next diagnostic"),
+    Action::new("up", &["k", "up"], "Select previous diagnostic"),
+    Action::new("open", &["enter"], "Open selected diagnostic in the editor"),
+    Action::new("refresh", &["r"], "Check again now"),
+];
+
+/// Compiler diagnostics widget
+///
+/// Checks the project with `cargo check` (or a configured command printing
+/// JSON diagnostics) and shows its error and warning counts with the
+/// diagnostics themselves, errors first. The project is checked again
+/// whenever a file in it changes; build output and hidden directories are
+/// left out.
+///
+/// # Keyboard Shortcuts
+/// - `Up`/`Down` or `k`/`j` - Select a diagnostic
+/// - `Enter` - Open the selected diagnostic's file at its line
+/// - `r` - Check again now
+///
+/// # Events
+/// - `system.diagnostics` - The [`DiagnosticReport`], after every check
+///
+/// When the event bus is not live, `system.diagnostics` events are shown
+/// instead of checking the project.
+pub struct DiagnosticsWidget {
+    collector: Collector<Checker, Option<DiagnosticReport>>,
+    root: PathBuf,
+    force: Arc<AtomicBool>,
+    report: Option<DiagnosticReport>,
+    table_state: TableState,
+    /// Why the selected diagnostic couldn't be opened
+    open_error: Option<String>,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl DiagnosticsWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, DiagnosticsOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings(settings)?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: DiagnosticsOptions,
+    ) -> Self {
+        let root = options.path.unwrap_or_else(|| PathBuf::from("."));
+        let force = Arc::new(AtomicBool::new(false));
+        let checker = Checker {
+            root: root.clone(),
+            command: options.command,
+            checked: None,
+            force: force.clone(),
+        };
+        Self {
+            collector: Collector::new(checker, check),
+            root,
+            force,
+            report: None,
+            table_state: TableState::default(),
+            open_error: None,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval: poll_interval.max(CHECK_INTERVAL),
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_report(&mut self, report: DiagnosticReport) {
+        let count = report.diagnostics.len();
+        self.table_state.select(match self.table_state.selected() {
+            _ if count == 0 => None,
+            Some(selected) => Some(selected.min(count - 1)),
+            None => Some(0),
+        });
+        self.report = Some(report);
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.report.as_ref().map_or(0, |r| r.diagnostics.len());
+        if count == 0 {
+            return;
+        }
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(Some(if down {
+            (selected + 1).min(count - 1)
+        } else {
+            selected.saturating_sub(1)
+        }));
+    }
+
+    /// Open the selected diagnostic's file at its line
+    ///
+    /// cargo reports paths relative to the workspace root, which may be
+    /// above the checked directory.
+    fn open_selected(&mut self) {
+        let Some(diagnostic) = self
+            .report
+            .as_ref()
+            .zip(self.table_state.selected())
+            .and_then(|(report, i)| report.diagnostics.get(i))
+        else {
+            return;
+        };
+        let Some(file) = &diagnostic.file else {
+            return;
+        };
+        let path = self
+            .root
+            .ancestors()
+            .map(|dir| dir.join(file))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| self.root.join(file));
+        self.open_error = opener::edit_file(&path, diagnostic.line)
+            .err()
+            .map(|e| e.to_string());
+    }
+
+    fn render_diagnostics(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let Some(report) = &self.report else {
+            return;
+        };
+        let location_width = report
+            .diagnostics
+            .iter()
+            .map(|d| d.location().chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(8, 40) as u16;
+        let rows = report.diagnostics.iter().map(|diagnostic| {
+            let (label, color) = match diagnostic.level {
+                DiagnosticLevel::Error => ("error", theme().critical),
+                DiagnosticLevel::Warning => ("warning", theme().warning),
+            };
+            Row::new(vec![
+                Cell::from(label).style(Style::default().fg(color)),
+                Cell::from(diagnostic.location()),
+                Cell::from(diagnostic.message.clone()),
+            ])
+        });
+        let header = Row::new(
+            ["Level", "Location", "Message"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(location_width),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header);
+        if focused {
+            table = table.row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        ratatui::widgets::StatefulWidget::render(table, area, buf, &mut self.table_state);
+    }
+}
+
+impl Widget for DiagnosticsWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected reports instead of checking the project
+            let (sub, rx) = self.event_bus.subscribe("system.diagnostics");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        // A check can take a while, so don't wait for the first one
+        self.collector.request(WorkerPool::global());
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<DiagnosticReport>())
+                .last();
+            if let Some(report) = latest {
+                self.apply_report(DiagnosticReport::clone(&report));
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(Some(report)) = self.collector.try_take() {
+            self.event_bus
+                .publish(Event::new("system.diagnostics", report.clone()));
+            self.apply_report(report);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            // Skipped while the previous check is still running
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        let devdash_core::Event::Action(action) = event else {
+            return EventResult::Ignored;
+        };
+        match action.as_str() {
+            "down" => self.move_selection(true),
+            "up" => self.move_selection(false),
+            "open" => self.open_selected(),
+            "refresh" => {
+                self.force.store(true, Ordering::Relaxed);
+                self.time_since_poll = self.poll_interval;
+            }
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let title = match &self.report {
+            None => " Diagnostics ".to_string(),
+            Some(report) if report.diagnostics.is_empty() && report.error.is_none() => {
+                " Diagnostics [clean] ".to_string()
+            }
+            Some(report) => format!(
+                " Diagnostics [{} errors, {} warnings] ",
+                report.count(DiagnosticLevel::Error),
+                report.count(DiagnosticLevel::Warning)
+            ),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let message = match (&self.open_error, &self.report) {
+            (Some(error), _) => Some((error.clone(), theme().warning)),
+            (None, None) => Some(("Checking...".to_string(), Color::DarkGray)),
+            (None, Some(report)) if report.diagnostics.is_empty() => Some(match &report.error {
+                Some(error) => (error.clone(), theme().warning),
+                None => ("No errors or warnings".to_string(), theme().good),
+            }),
+            _ => None,
+        };
+        match message {
+            // An open error takes the first line, above the diagnostics
+            Some((text, color)) if self.open_error.is_some() && inner.height > 1 => {
+                Paragraph::new(text)
+                    .style(Style::default().fg(color))
+                    .render(Rect { height: 1, ..inner }, buf);
+                let rest = Rect {
+                    y: inner.y + 1,
+                    height: inner.height - 1,
+                    ..inner
+                };
+                self.render_diagnostics(rest, buf, focused);
+            }
+            Some((text, color)) => Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf),
+            None => self.render_diagnostics(inner, buf, focused),
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        let Some(report) = &self.report else {
+            return Some("Diagnostics: not checked yet".to_string());
+        };
+        if let Some(error) = report
+            .error
+            .as_ref()
+            .filter(|_| report.diagnostics.is_empty())
+        {
+            return Some(format!("Diagnostics: check failed: {}", error));
+        }
+        let mut summary = format!(
+            "Diagnostics: {} errors, {} warnings",
+            report.count(DiagnosticLevel::Error),
+            report.count(DiagnosticLevel::Warning)
+        );
+        if let Some(first) = report.diagnostics.first() {
+            summary.push_str(&format!("; first: {} {}", first.location(), first.message));
+        }
+        if let Some(at) = report.checked_at {
+            summary.push_str(&format!("; checked {}", format_relative_time(at)));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_OUTPUT: &str = r#"{"reason":"compiler-artifact","package_id":"demo 0.1.0","target":{"name":"demo"}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"$message_type":"diagnostic","message":"unused variable: `x`","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","line_start":3,"line_end":3,"column_start":9,"column_end":10,"is_primary":true}],"children":[]}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"$message_type":"diagnostic","message":"cannot find value `y` in this scope","code":{"code":"E0425","explanation":"..."},"level":"error","spans":[{"file_name":"src/other.rs","line_start":1,"line_end":1,"column_start":1,"column_end":2,"is_primary":false},{"file_name":"src/main.rs","line_start":12,"line_end":12,"column_start":5,"column_end":6,"is_primary":true}],"children":[]}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"$message_type":"diagnostic","message":"unused variable: `x`","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","line_start":3,"line_end":3,"column_start":9,"column_end":10,"is_primary":true}],"children":[]}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[]}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"$message_type":"diagnostic","message":"1 warning emitted","code":null,"level":"warning","spans":[],"children":[]}}
+{"reason":"build-finished","success":false}
+"#;
+
+    #[test]
+    fn test_parse_messages() {
+        let diagnostics = parse_messages(CARGO_OUTPUT);
+        assert_eq!(
+            diagnostics,
+            [
+                Diagnostic {
+                    level: DiagnosticLevel::Error,
+                    message: "cannot find value `y` in this scope".to_string(),
+                    code: Some("E0425".to_string()),
+                    file: Some(PathBuf::from("src/main.rs")),
+                    line: Some(12),
+                    column: Some(5),
+                },
+                Diagnostic {
+                    level: DiagnosticLevel::Warning,
+                    message: "unused variable: `x`".to_string(),
+                    code: Some("unused_variables".to_string()),
+                    file: Some(PathBuf::from("src/lib.rs")),
+                    line: Some(3),
+                    column: Some(9),
+                },
+            ]
+        );
+        assert_eq!(diagnostics[0].location(), "src/main.rs:12:5");
+
+        // rustc's own JSON, and a linker error with no span
+        let rustc = r#"{"$message_type":"diagnostic","message":"linking with `cc` failed","code":null,"level":"error","spans":[],"children":[]}"#;
+        let diagnostics = parse_messages(rustc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location(), "");
+        assert!(parse_messages("   Compiling demo v0.1.0\nnot json").is_empty());
+    }
+
+    #[test]
+    fn test_checks_only_after_changes() {
+        let dir = std::env::temp_dir().join(format!("devdash-diagnostics-{}", std::process::id()));
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("lib.rs"), "").unwrap();
+        let force = Arc::new(AtomicBool::new(false));
+        let mut checker = Checker {
+            root: dir.clone(),
+            command: Some(format!(
+                "echo '{}'",
+                r#"{"$message_type":"diagnostic","message":"oops","level":"error","spans":[]}"#
+            )),
+            checked: None,
+            force: force.clone(),
+        };
+
+        let report = check(&mut checker).unwrap();
+        assert_eq!(report.count(DiagnosticLevel::Error), 1);
+        assert_eq!(report.error, None);
+        assert_eq!(check(&mut checker), None);
+
+        // Build output doesn't count as a change
+        fs::write(dir.join("target/out"), "").unwrap();
+        assert_eq!(check(&mut checker), None);
+        force.store(true, Ordering::Relaxed);
+        assert!(check(&mut checker).is_some());
+
+        checker.command = Some("echo 'error: could not find `Cargo.toml`' >&2; exit 101".into());
+        checker.force.store(true, Ordering::Relaxed);
+        let report = check(&mut checker).unwrap();
+        assert_eq!(
+            report.error.as_deref(),
+            Some("error: could not find `Cargo.toml`")
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,7 +1,10 @@
 // devdash-widgets/src/disk.rs
+use crossbeam::channel::Receiver;
 use devdash_core::{
-    EventBus, EventResult, Widget,
+    Action, DataStatus, EventBus, EventResult, Selection, Widget, WidgetSettings, WidgetState,
     event::{Event, Subscription},
+    parse_settings,
+    widget::DEFAULT_UPDATE_INTERVAL,
 };
 use ratatui::{
     buffer::Buffer,
@@ -10,10 +13,18 @@ use ratatui::{
     style::{Color, Style},
     widgets::{Block, Borders},
 };
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use sysinfo::{Disks, System};
+use unicode_width::UnicodeWidthStr;
 
-use crate::common::{focus_color, format_bytes, format_rate, usage_color};
+use crate::common::{
+    ActivityBar, draw_text, focus_color, format_bytes, format_rate, glob_match, middle_ellipsis,
+    series_colors, usage_color,
+};
+use crate::platform::DiskIoCounters;
+use crate::projection::{DiskProjection, DiskTrends};
 
 /// View mode for the DiskWidget
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,6 +35,96 @@ pub enum ViewMode {
     Usage,
 }
 
+/// Widest the mount point in the I/O view's title gets, leaving room for
+/// the rates
+const MAX_TITLE_MOUNT_WIDTH: usize = 24;
+
+/// File systems reached over the network, which can hang when unreachable
+const NETWORK_FILE_SYSTEMS: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smbfs",
+    "smb3",
+    "autofs",
+    "fuse.sshfs",
+    "sshfs",
+    "afpfs",
+    "webdav",
+];
+
+/// Mount points hidden when `exclude` isn't set
+fn default_excludes() -> Vec<String> {
+    let patterns: &[&str] = if cfg!(windows) {
+        &[]
+    } else if cfg!(target_os = "macos") {
+        // APFS system, VM and recovery volumes
+        &["/System/Volumes/**", "/private/var/vm", "/Volumes/Recovery"]
+    } else {
+        &[
+            "/proc",
+            "/proc/**",
+            "/sys",
+            "/sys/**",
+            "/dev",
+            "/dev/**",
+            "/run",
+            "/run/**",
+            "/var/run/**",
+            "/snap/**",
+            "/var/lib/docker/**",
+        ]
+    };
+    patterns.iter().map(|p| p.to_string()).collect()
+}
+
+/// Which mount points the disk widget lists
+///
+/// A mount is listed if it matches one of `include` (or `include` is empty)
+/// and none of `exclude`. Network file systems are only listed when
+/// `network` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MountFilter {
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Replaces the per-OS default list when set
+    #[serde(default = "default_excludes")]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub network: bool,
+}
+
+impl Default for MountFilter {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: default_excludes(),
+            network: false,
+        }
+    }
+}
+
+impl MountFilter {
+    /// Whether a mount with this file system type should be listed
+    pub fn allows(&self, mount_point: &str, file_system: &str) -> bool {
+        let matches_any = |patterns: &[String]| patterns.iter().any(|p| glob_match(p, mount_point));
+        (self.network || !NETWORK_FILE_SYSTEMS.contains(&file_system))
+            && (self.include.is_empty() || matches_any(&self.include))
+            && !matches_any(&self.exclude)
+    }
+}
+
+/// Disk counters and mount points read in one poll
+#[derive(Debug, Clone, Default)]
+pub struct DiskSample {
+    /// Bytes read so far, across all disks or the linked one
+    pub read_bytes: u64,
+    /// Bytes written so far, across all disks or the linked one
+    pub write_bytes: u64,
+    /// Mount points the [`MountFilter`] allows
+    pub disks: Vec<DiskInfo>,
+}
+
 /// Information about a disk mount point
 #[derive(Debug, Clone)]
 pub struct DiskInfo {
@@ -54,7 +155,7 @@ impl DiskInfo {
 }
 
 /// Disk I/O metrics published to the event bus
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskIOMetrics {
     /// Current read rate in bytes per second
     pub read_rate: u64,
@@ -67,7 +168,7 @@ pub struct DiskIOMetrics {
 }
 
 /// Disk usage metrics published to the event bus
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskUsageMetrics {
     /// Mount point path
     pub mount_point: String,
@@ -81,6 +182,16 @@ pub struct DiskUsageMetrics {
     pub percentage: f64,
 }
 
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("toggle_view", &["t"], "Toggle I/O and usage views"),
+    Action::new("next_disk", &["d"], "Next disk"),
+    Action::new("down", &["j", "down"], "Select next disk"),
+    Action::new("up", &["k", "up"], "Select previous disk"),
+    Action::new("history", &["h"], "Cycle history length"),
+    Action::new("reset", &["r"], "Reset I/O history"),
+];
+
 /// Disk monitoring widget with I/O statistics and usage display
 ///
 /// Displays system disk I/O rates with sparklines and disk usage per mount point.
@@ -97,9 +208,28 @@ pub struct DiskUsageMetrics {
 /// - Publishes `system.disk.io` events on each poll with current I/O metrics
 /// - Publishes `system.disk.usage` events when disk usage data updates
 /// - Publishes `system.disk.full` events when any disk exceeds 90% usage
+/// - Publishes `system.disk.projection` events for disks filling up, with
+///   the time left at the current growth rate
+///
+/// # Settings
+/// - `include`/`exclude` - Glob patterns selecting the mount points listed;
+///   see [`MountFilter`]
+/// - `network` - List network file systems too
+///
+/// # Linking
+/// The selected disk is shared as a `disk` selection. A disk selected in a
+/// linked widget is selected here too and limits the I/O stats to it.
+///
+/// Used space is sampled into [`DiskTrends`], kept in the session state, to
+/// project when each disk will be full.
+///
+/// When the event bus is not live, the widget displays `system.disk.io`,
+/// `system.disk.usage` and `system.disk.projection` events published by
+/// others instead of polling.
 pub struct DiskWidget {
     system: System,
     disks: Disks,
+    io_counters: DiskIoCounters,
 
     // Disk I/O state
     read_bytes: u64,
@@ -112,6 +242,12 @@ pub struct DiskWidget {
     // Disk usage state
     disk_info: Vec<DiskInfo>,
     selected_disk_idx: usize,
+    /// Mount point the I/O stats are limited to, set by a linked selection
+    io_scope: Option<String>,
+    mount_filter: MountFilter,
+    trends: DiskTrends,
+    /// Disks filling up, by mount point
+    projections: HashMap<String, DiskProjection>,
 
     // View mode
     view_mode: ViewMode,
@@ -126,6 +262,9 @@ pub struct DiskWidget {
     // Event bus
     event_bus: EventBus,
     _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
 }
 
 impl DiskWidget {
@@ -137,6 +276,9 @@ impl DiskWidget {
     ///
     /// # Example
     /// ```rust
+    /// # use devdash_core::EventBus;
+    /// # use devdash_widgets::DiskWidget;
+    /// # use std::time::Duration;
     /// let event_bus = EventBus::new();
     /// let disk_widget = DiskWidget::new(
     ///     event_bus,
@@ -153,6 +295,7 @@ impl DiskWidget {
         Self {
             system,
             disks,
+            io_counters: DiskIoCounters::new(),
             read_bytes: 0,
             write_bytes: 0,
             prev_read_bytes: 0,
@@ -161,50 +304,118 @@ impl DiskWidget {
             write_history: Vec::with_capacity(120),
             disk_info: Vec::new(),
             selected_disk_idx: 0,
+            io_scope: None,
+            mount_filter: MountFilter::default(),
+            trends: DiskTrends::default(),
+            projections: HashMap::new(),
             view_mode: ViewMode::IOStats,
             history_size: 30,
             poll_interval,
             time_since_poll: Duration::ZERO,
             event_bus,
             _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
         }
     }
 
-    /// Poll system for current disk I/O information
-    fn poll_disk_io(&mut self) {
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    ///
+    /// The `include`, `exclude` and `network` settings form the
+    /// [`MountFilter`].
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let filter = parse_settings(settings)?;
+        Ok(Self::new(event_bus, poll_interval).with_mount_filter(filter))
+    }
+
+    /// List only the mount points `filter` allows
+    pub fn with_mount_filter(mut self, filter: MountFilter) -> Self {
+        self.mount_filter = filter;
+        self
+    }
+
+    /// Poll the system for the disk counters and mount points
+    fn sample(&mut self) -> DiskSample {
         self.system.refresh_all();
         self.disks.refresh(true);
 
-        // Calculate total read/write bytes across all disks
-        let mut total_read = 0u64;
-        let mut total_write = 0u64;
+        // Total read/write bytes across all disks, or the linked one
+        let (read_bytes, write_bytes) = match &self.io_scope {
+            Some(mount_point) => self
+                .disks
+                .iter()
+                .find(|disk| disk.mount_point().to_string_lossy() == *mount_point)
+                .map_or((0, 0), |disk| {
+                    let usage = disk.usage();
+                    (usage.total_read_bytes, usage.total_written_bytes)
+                }),
+            None => self.io_counters.totals(&self.disks),
+        };
 
-        for disk in self.disks.iter() {
-            let usage = disk.usage();
-            total_read += usage.read_bytes;
-            total_write += usage.written_bytes;
+        let disks = self
+            .disks
+            .iter()
+            .filter_map(|disk| {
+                let mount_point = disk.mount_point().to_string_lossy().to_string();
+                let file_system = disk.file_system().to_string_lossy();
+                self.mount_filter
+                    .allows(&mount_point, &file_system)
+                    .then(|| DiskInfo {
+                        name: disk.name().to_string_lossy().to_string(),
+                        mount_point,
+                        total_space: disk.total_space(),
+                        available_space: disk.available_space(),
+                    })
+            })
+            .collect();
+
+        DiskSample {
+            read_bytes,
+            write_bytes,
+            disks,
         }
-
-        self.read_bytes = total_read;
-        self.write_bytes = total_write;
     }
 
-    /// Update disk usage information
-    fn update_disk_info(&mut self) {
-        self.disk_info.clear();
+    /// Process `sample` as if it had just been polled, `delta` after the
+    /// previous one: update the rates and usage and publish them
+    ///
+    /// Polling goes through this too, so feeding canned samples exercises
+    /// the same rate calculation and events.
+    pub fn feed(&mut self, sample: DiskSample, delta: Duration) {
+        self.read_bytes = sample.read_bytes;
+        self.write_bytes = sample.write_bytes;
+        self.update_disk_info(sample.disks);
+        self.update_projections();
+        self.calculate_rates(delta);
+        self.publish_events();
+    }
 
-        for disk in self.disks.iter() {
-            // Filter out virtual filesystems
-            let mount_point = disk.mount_point().to_string_lossy().to_string();
-            if !self.is_virtual_filesystem(&mount_point) {
-                self.disk_info.push(DiskInfo {
-                    name: disk.name().to_string_lossy().to_string(),
-                    mount_point,
-                    total_space: disk.total_space(),
-                    available_space: disk.available_space(),
-                });
-            }
+    /// Follow a disk selected in a linked widget
+    fn apply_link(&mut self, mount_point: &str) {
+        if let Some(idx) = self
+            .disk_info
+            .iter()
+            .position(|d| d.mount_point == mount_point)
+        {
+            self.selected_disk_idx = idx;
+        }
+        if self.io_scope.as_deref() != Some(mount_point) {
+            // Rates from the old counters don't carry over
+            self.io_scope = Some(mount_point.to_string());
+            self.read_history.clear();
+            self.write_history.clear();
+            self.prev_read_bytes = 0;
+            self.prev_write_bytes = 0;
         }
+    }
+
+    /// Replace the listed mount points
+    fn update_disk_info(&mut self, disks: Vec<DiskInfo>) {
+        self.disk_info = disks;
 
         // Sort by mount point for consistent ordering
         self.disk_info
@@ -216,15 +427,23 @@ impl DiskWidget {
         }
     }
 
-    /// Check if a mount point is a virtual filesystem
-    fn is_virtual_filesystem(&self, mount_point: &str) -> bool {
-        matches!(
-            mount_point,
-            "/proc" | "/sys" | "/dev" | "/run" | "/tmp" | "/var/run" | "/var/tmp"
-        ) || mount_point.starts_with("/proc/")
-            || mount_point.starts_with("/sys/")
-            || mount_point.starts_with("/dev/")
-            || mount_point.starts_with("/run/")
+    /// Sample used space and project when each disk will be full
+    fn update_projections(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.projections.clear();
+        for disk in &self.disk_info {
+            self.trends
+                .record(&disk.mount_point, disk.used_space(), now);
+            if let Some(projection) = self
+                .trends
+                .projection(&disk.mount_point, disk.available_space)
+            {
+                self.projections
+                    .insert(disk.mount_point.clone(), projection);
+            }
+        }
     }
 
     /// Calculate I/O rates and update history
@@ -295,6 +514,52 @@ impl DiskWidget {
                 self.event_bus
                     .publish(Event::new("system.disk.full", usage_metrics));
             }
+
+            if let Some(projection) = self.projections.get(&disk.mount_point) {
+                self.event_bus
+                    .publish(Event::new("system.disk.projection", projection.clone()));
+            }
+        }
+    }
+
+    /// Record I/O metrics received from the event bus
+    fn apply_io_metrics(&mut self, metrics: &DiskIOMetrics) {
+        self.read_bytes = metrics.total_read;
+        self.write_bytes = metrics.total_write;
+        self.read_history.push(metrics.read_rate);
+        self.write_history.push(metrics.write_rate);
+
+        if self.read_history.len() > self.history_size {
+            self.read_history.remove(0);
+        }
+        if self.write_history.len() > self.history_size {
+            self.write_history.remove(0);
+        }
+    }
+
+    /// Add or update a mount point from usage metrics received from the event bus
+    fn apply_usage_metrics(&mut self, metrics: &DiskUsageMetrics) {
+        if !self.mount_filter.allows(&metrics.mount_point, "") {
+            return;
+        }
+        let info = DiskInfo {
+            name: metrics.mount_point.clone(),
+            mount_point: metrics.mount_point.clone(),
+            total_space: metrics.total,
+            available_space: metrics.available,
+        };
+
+        match self
+            .disk_info
+            .iter_mut()
+            .find(|d| d.mount_point == metrics.mount_point)
+        {
+            Some(existing) => *existing = info,
+            None => {
+                self.disk_info.push(info);
+                self.disk_info
+                    .sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+            }
         }
     }
 
@@ -311,8 +576,19 @@ impl DiskWidget {
 
 impl Widget for DiskWidget {
     fn on_mount(&mut self) {
-        self.poll_disk_io();
-        self.update_disk_info();
+        if !self.event_bus.is_live() {
+            // Display injected metrics instead of polling
+            let (sub, rx) = self.event_bus.subscribe("system.disk.*");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        let sample = self.sample();
+        (self.read_bytes, self.write_bytes) = (sample.read_bytes, sample.write_bytes);
+        self.update_disk_info(sample.disks);
+        self.update_projections();
 
         // Subscribe to disk refresh events (for future use)
         let (sub, _rx) = self.event_bus.subscribe("system.disk.refresh");
@@ -320,23 +596,52 @@ impl Widget for DiskWidget {
     }
 
     fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = self.injected.clone() {
+            for event in rx.try_iter() {
+                if let Some(metrics) = event.payload.downcast::<DiskIOMetrics>() {
+                    self.apply_io_metrics(&metrics);
+                } else if let Some(metrics) = event.payload.downcast::<DiskUsageMetrics>() {
+                    self.apply_usage_metrics(&metrics);
+                } else if let Some(projection) = event.payload.downcast::<DiskProjection>() {
+                    self.projections
+                        .insert(projection.mount_point.clone(), (*projection).clone());
+                } else {
+                    continue;
+                }
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
         self.time_since_poll += delta;
 
         if self.time_since_poll >= self.poll_interval {
-            self.poll_disk_io();
-            self.update_disk_info();
-            self.calculate_rates(delta);
-            self.publish_events();
+            let sample = self.sample();
+            self.feed(sample, delta);
             self.time_since_poll = Duration::ZERO;
         }
     }
 
+    fn data_status(&self) -> Option<DataStatus> {
+        self.injected.as_ref().map(|_| DataStatus {
+            refreshing: false,
+            last_data: self.last_injected,
+            interval: self.poll_interval,
+            retry_at: None,
+        })
+    }
+
     fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
-        use crossterm::event::KeyCode;
+        if let devdash_core::Event::Linked(selection) = &event
+            && selection.kind == "disk"
+        {
+            self.apply_link(&selection.value);
+            return EventResult::Consumed;
+        }
 
-        if let devdash_core::Event::Key(key) = event {
-            match key.code {
-                KeyCode::Char('t') => {
+        if let devdash_core::Event::Action(action) = event {
+            match action.as_str() {
+                "toggle_view" => {
                     // Toggle between I/O Stats and Usage views
                     self.view_mode = match self.view_mode {
                         ViewMode::IOStats => ViewMode::Usage,
@@ -344,7 +649,7 @@ impl Widget for DiskWidget {
                     };
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('d') => {
+                "next_disk" => {
                     // Cycle through disks in Usage view
                     if !self.disk_info.is_empty() {
                         self.selected_disk_idx =
@@ -352,13 +657,13 @@ impl Widget for DiskWidget {
                     }
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('r') => {
+                "reset" => {
                     // Reset I/O history
                     self.read_history.clear();
                     self.write_history.clear();
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('h') => {
+                "history" => {
                     // Toggle history length: 30, 60, 120
                     self.history_size = match self.history_size {
                         30 => 60,
@@ -376,7 +681,7 @@ impl Widget for DiskWidget {
                     }
                     return EventResult::Consumed;
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
+                "down" => {
                     // Navigate down in disk list
                     if !self.disk_info.is_empty() {
                         self.selected_disk_idx =
@@ -384,7 +689,7 @@ impl Widget for DiskWidget {
                     }
                     return EventResult::Consumed;
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
+                "up" => {
                     // Navigate up in disk list
                     if !self.disk_info.is_empty() {
                         self.selected_disk_idx = if self.selected_disk_idx > 0 {
@@ -418,6 +723,59 @@ impl Widget for DiskWidget {
     fn needs_update(&self) -> bool {
         true // Always poll for updates
     }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn selection(&self) -> Option<Selection> {
+        self.disk_info
+            .get(self.selected_disk_idx)
+            .map(|disk| Selection::new("disk", &disk.mount_point))
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mut summary = format!(
+            "Disk read {}, write {}",
+            format_rate(self.get_read_rate() as f64),
+            format_rate(self.get_write_rate() as f64)
+        );
+        for disk in &self.disk_info {
+            let used = disk.total_space.saturating_sub(disk.available_space);
+            let percent = if disk.total_space > 0 {
+                used as f64 / disk.total_space as f64 * 100.0
+            } else {
+                0.0
+            };
+            summary.push_str(&format!("; {} {:.0}% full", disk.mount_point, percent));
+            if let Some(projection) = self.projections.get(&disk.mount_point) {
+                summary.push_str(&format!(", full in {}", projection.describe()));
+            }
+        }
+        Some(summary)
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        // Another machine's disks; keep the local history
+        if self.injected.is_some() {
+            return None;
+        }
+        let mut state = WidgetState::new();
+        self.trends.save(&mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        self.trends = DiskTrends::restore(state);
+    }
+
+    fn update_interval(&self) -> Duration {
+        // Injected events are drained at the default rate
+        match self.injected {
+            Some(_) => DEFAULT_UPDATE_INTERVAL,
+            None => self.poll_interval,
+        }
+    }
 }
 
 impl DiskWidget {
@@ -426,8 +784,12 @@ impl DiskWidget {
         let read_rate = self.get_read_rate();
         let write_rate = self.get_write_rate();
 
+        let scope = self.io_scope.as_ref().map_or(String::new(), |mount_point| {
+            format!(" {}", middle_ellipsis(mount_point, MAX_TITLE_MOUNT_WIDTH))
+        });
         let title = format!(
-            " Disk I/O [R: {} | W: {}] ",
+            " Disk I/O{} [R: {} | W: {}] ",
+            scope,
             format_rate(read_rate as f64),
             format_rate(write_rate as f64)
         );
@@ -461,12 +823,13 @@ impl DiskWidget {
 
         // Calculate activity levels (0-100%)
         let max_rate = 100 * 1024 * 1024; // 100 MB/s as max for visualization
-        let read_activity = ((read_rate as f64 / max_rate as f64) * 100.0).min(100.0) as u16;
-        let write_activity = ((write_rate as f64 / max_rate as f64) * 100.0).min(100.0) as u16;
+        let read_activity = (read_rate as f64 / max_rate as f64) * 100.0;
+        let write_activity = (write_rate as f64 / max_rate as f64) * 100.0;
 
         // Render activity bars
-        self.render_activity_bar(chunks[0], buf, "Read", read_activity, Color::Cyan);
-        self.render_activity_bar(chunks[1], buf, "Write", write_activity, Color::Magenta);
+        let (read, write) = series_colors();
+        ActivityBar::new("Read", read_activity, read).render(chunks[0], buf);
+        ActivityBar::new("Write", write_activity, write).render(chunks[1], buf);
 
         // Render current rates
         let rates_text = format!(
@@ -486,89 +849,14 @@ impl DiskWidget {
         let total_read_style = RatatuiStyle::default().fg(Color::Cyan);
         let total_write_style = RatatuiStyle::default().fg(Color::Magenta);
 
-        // Write rates line
-        for (i, ch) in rates_text.chars().enumerate() {
-            if let Some(pos) = chunks[3].x.checked_add(i as u16)
-                && pos < chunks[3].x + chunks[3].width
-            {
-                buf[(pos, chunks[3].y)].set_char(ch).set_style(rates_style);
-            }
-        }
-
-        // Write totals
-        for (i, ch) in total_read_text.chars().enumerate() {
-            if let Some(pos) = chunks[4].x.checked_add(i as u16)
-                && pos < chunks[4].x + chunks[4].width
-            {
-                buf[(pos, chunks[4].y)]
-                    .set_char(ch)
-                    .set_style(total_read_style);
-            }
-        }
-
-        for (i, ch) in total_write_text.chars().enumerate() {
-            if let Some(pos) = chunks[5].x.checked_add(i as u16)
-                && pos < chunks[5].x + chunks[5].width
-            {
-                buf[(pos, chunks[5].y)]
-                    .set_char(ch)
-                    .set_style(total_write_style);
-            }
-        }
+        draw_text(buf, chunks[3], &rates_text, rates_style);
+        draw_text(buf, chunks[4], &total_read_text, total_read_style);
+        draw_text(buf, chunks[5], &total_write_text, total_write_style);
 
         // Render the main block
         RatatuiWidget::render(block, area, buf);
     }
 
-    /// Render an activity bar showing current I/O activity level
-    fn render_activity_bar(
-        &self,
-        area: Rect,
-        buf: &mut Buffer,
-        label: &str,
-        activity: u16,
-        color: Color,
-    ) {
-        let bar_width = area.width.saturating_sub(label.len() as u16 + 3).max(1);
-        let filled_width = ((activity as f64 / 100.0) * bar_width as f64) as u16;
-
-        // Create activity bar with different characters for different activity levels
-        let mut bar = String::new();
-        for i in 0..bar_width {
-            if i < filled_width {
-                // Use different characters based on activity level
-                let char_index = (i as f64 / bar_width as f64 * 4.0) as usize;
-                let chars = ['░', '▒', '▓', '█'];
-                bar.push(chars[char_index.min(3)]);
-            } else {
-                bar.push(' ');
-            }
-        }
-
-        // Write label and bar to buffer
-        use ratatui::style::Style as RatatuiStyle;
-        let style = RatatuiStyle::default().fg(color);
-
-        // Write label
-        for (i, ch) in label.chars().enumerate() {
-            if let Some(pos) = area.x.checked_add(i as u16)
-                && pos < area.x + area.width
-            {
-                buf[(pos, area.y)].set_char(ch).set_style(style);
-            }
-        }
-
-        // Write bar
-        let bar_start_x = area.x + label.len() as u16 + 1;
-        for (i, ch) in bar.chars().enumerate() {
-            if let Some(pos) = bar_start_x.checked_add(i as u16)
-                && pos < area.x + area.width
-            {
-                buf[(pos, area.y)].set_char(ch).set_style(style);
-            }
-        }
-    }
-
     /// Render disk usage view
     fn render_usage_view(&mut self, area: Rect, buf: &mut Buffer, border_color: Color) {
         let title = format!(" Disk Usage [{} disks] ", self.disk_info.len());
@@ -629,19 +917,26 @@ impl DiskWidget {
         let usage_percent = disk.usage_percent();
         let usage_color = usage_color(usage_percent);
 
-        // Disk name and mount point
+        // Disk name and mount point, shortened in the middle to fit
+        let room =
+            (area.width as usize).saturating_sub(selection_indicator.len() + disk.name.width() + 3);
         let disk_line = format!(
             "{}{} ({})",
-            selection_indicator, disk.name, disk.mount_point
+            selection_indicator,
+            disk.name,
+            middle_ellipsis(&disk.mount_point, room)
         );
 
         // Usage info
-        let usage_line = format!(
+        let mut usage_line = format!(
             "   Used: {} / {} ({:.1}%)",
             format_bytes(disk.used_space()),
             format_bytes(disk.total_space),
             usage_percent
         );
+        if let Some(projection) = self.projections.get(&disk.mount_point) {
+            usage_line.push_str(&format!(", full in {}", projection.describe()));
+        }
 
         // Usage bar
         let bar_width = area.width.saturating_sub(2);
@@ -656,7 +951,7 @@ impl DiskWidget {
             }
         }
 
-        // Write to buffer
+        // Three rows: name, usage, bar
         use ratatui::style::Style as RatatuiStyle;
 
         let disk_style = if selected {
@@ -669,31 +964,37 @@ impl DiskWidget {
 
         let usage_style = RatatuiStyle::default().fg(usage_color);
 
-        // Write disk line
-        for (i, ch) in disk_line.chars().enumerate() {
-            if let Some(pos) = area.x.checked_add(i as u16)
-                && pos < area.x + area.width
-            {
-                buf[(pos, area.y)].set_char(ch).set_style(disk_style);
-            }
-        }
+        let rows = Layout::vertical([Constraint::Length(1); 3]).split(area);
+        draw_text(buf, rows[0], &disk_line, disk_style);
+        draw_text(buf, rows[1], &usage_line, usage_style);
+        draw_text(buf, rows[2], &bar, usage_style);
+    }
+}
 
-        // Write usage line
-        for (i, ch) in usage_line.chars().enumerate() {
-            if let Some(pos) = area.x.checked_add(i as u16)
-                && pos < area.x + area.width
-            {
-                buf[(pos, area.y + 1)].set_char(ch).set_style(usage_style);
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_default_filter() {
+        let filter = MountFilter::default();
+        assert!(filter.allows("/", "ext4"));
+        assert!(filter.allows("/tmp", "tmpfs"));
+        assert!(!filter.allows("/run/user/1000", "tmpfs"));
+        assert!(!filter.allows("/mnt/share", "nfs4"));
+    }
 
-        // Write bar
-        for (i, ch) in bar.chars().enumerate() {
-            if let Some(pos) = area.x.checked_add(i as u16)
-                && pos < area.x + area.width
-            {
-                buf[(pos, area.y + 2)].set_char(ch).set_style(usage_style);
-            }
-        }
+    #[test]
+    fn test_include_and_exclude() {
+        let filter = MountFilter {
+            include: vec!["/mnt/**".to_string(), "/".to_string()],
+            exclude: vec!["/mnt/backup".to_string()],
+            network: true,
+        };
+        assert!(filter.allows("/", "ext4"));
+        assert!(filter.allows("/mnt/share", "cifs"));
+        assert!(!filter.allows("/mnt/backup", "ext4"));
+        assert!(!filter.allows("/home", "ext4"));
     }
 }
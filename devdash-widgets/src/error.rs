@@ -82,5 +82,9 @@ impl Widget for ErrorWidget {
         false
     }
 
+    fn summary(&self) -> Option<String> {
+        Some(format!("{}: {}", self.title, self.message))
+    }
+
     fn on_unmount(&mut self) {}
 }
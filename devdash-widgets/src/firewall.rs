@@ -0,0 +1,614 @@
+// devdash-widgets/src/firewall.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WorkerPool,
+    event::{Event, Subscription},
+    parse_duration, parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::common::{AccessDenied, LOG_ACCESS_HINT, LogTail, focus_color};
+
+/// Attempts from one source within this long are checked against `burst`
+pub const BURST_WINDOW: Duration = Duration::from_secs(60);
+
+/// Ports listed per source
+const PORTS_PER_SOURCE: usize = 4;
+
+/// A connection attempt the firewall dropped or rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedAttempt {
+    pub source: String,
+    /// Destination port; `None` for protocols without ports, such as ICMP
+    pub port: Option<u16>,
+    pub protocol: String,
+}
+
+/// Parse one firewall log line, if it records a blocked attempt
+///
+/// Understands kernel netfilter lines, as logged by ufw (`[UFW BLOCK]`) and
+/// by nftables or iptables `log` rules whose prefix contains "block", "drop"
+/// or "reject", and Windows Firewall's `pfirewall.log`.
+pub fn parse_line(line: &str) -> Option<BlockedAttempt> {
+    parse_netfilter(line).or_else(|| parse_pfirewall(line))
+}
+
+/// `... [UFW BLOCK] IN=eth0 OUT= ... SRC=203.0.113.9 DST=10.0.0.2 ... PROTO=TCP SPT=51234 DPT=22 ...`
+fn parse_netfilter(line: &str) -> Option<BlockedAttempt> {
+    // The prefix comes before the packet fields; ufw also logs `[UFW ALLOW]`
+    // and `[UFW AUDIT]` lines
+    let prefix = line[..line.find(" IN=")?].to_ascii_lowercase();
+    if !["block", "drop", "reject"]
+        .iter()
+        .any(|word| prefix.contains(word))
+    {
+        return None;
+    }
+    let field = |name: &str| {
+        line.split_ascii_whitespace()
+            .find_map(|word| word.strip_prefix(name))
+    };
+    Some(BlockedAttempt {
+        source: field("SRC=")?.to_string(),
+        port: field("DPT=").and_then(|port| port.parse().ok()),
+        protocol: field("PROTO=").unwrap_or("?").to_string(),
+    })
+}
+
+/// `2024-05-01 10:00:00 DROP TCP 203.0.113.9 10.0.0.2 51234 3389 52 S ...`
+fn parse_pfirewall(line: &str) -> Option<BlockedAttempt> {
+    let fields: Vec<&str> = line.split_ascii_whitespace().collect();
+    match fields.as_slice() {
+        [
+            _date,
+            _time,
+            "DROP",
+            protocol,
+            source,
+            _dest,
+            _source_port,
+            port,
+            ..,
+        ] => Some(BlockedAttempt {
+            source: source.to_string(),
+            port: port.parse().ok(),
+            protocol: protocol.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// Blocked attempts from one source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceActivity {
+    pub source: String,
+    /// Attempts within the window
+    pub attempts: usize,
+    /// Attempts within the last [`BURST_WINDOW`]
+    pub recent: usize,
+    /// Whether `recent` reached the burst threshold
+    pub burst: bool,
+    /// Ports tried, most attempts first
+    pub ports: Vec<u16>,
+}
+
+/// Blocked attempts on one port
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortActivity {
+    pub port: Option<u16>,
+    pub protocol: String,
+    pub attempts: usize,
+}
+
+/// Blocked attempts over the rolling window, busiest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallSummary {
+    pub window: Duration,
+    pub total: usize,
+    pub sources: Vec<SourceActivity>,
+    pub ports: Vec<PortActivity>,
+}
+
+impl FirewallSummary {
+    /// Summarize `(when, attempt)` pairs, oldest first, as of `now`
+    pub fn new(
+        attempts: &VecDeque<(Instant, BlockedAttempt)>,
+        window: Duration,
+        burst: usize,
+        now: Instant,
+    ) -> Self {
+        let mut sources: HashMap<&str, (usize, usize, HashMap<u16, usize>)> = HashMap::new();
+        let mut ports: HashMap<(Option<u16>, &str), usize> = HashMap::new();
+        for (at, attempt) in attempts {
+            let source = sources.entry(&attempt.source).or_default();
+            source.0 += 1;
+            if now.saturating_duration_since(*at) < BURST_WINDOW {
+                source.1 += 1;
+            }
+            if let Some(port) = attempt.port {
+                *source.2.entry(port).or_default() += 1;
+            }
+            *ports.entry((attempt.port, &attempt.protocol)).or_default() += 1;
+        }
+
+        let mut sources: Vec<SourceActivity> = sources
+            .into_iter()
+            .map(|(source, (attempts, recent, ports))| {
+                let mut ports: Vec<(u16, usize)> = ports.into_iter().collect();
+                ports.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+                SourceActivity {
+                    source: source.to_string(),
+                    attempts,
+                    recent,
+                    burst: recent >= burst,
+                    ports: ports.into_iter().map(|(port, _)| port).collect(),
+                }
+            })
+            .collect();
+        sources.sort_by(|a, b| b.attempts.cmp(&a.attempts).then(a.source.cmp(&b.source)));
+
+        let mut ports: Vec<PortActivity> = ports
+            .into_iter()
+            .map(|((port, protocol), attempts)| PortActivity {
+                port,
+                protocol: protocol.to_string(),
+                attempts,
+            })
+            .collect();
+        ports.sort_by(|a, b| b.attempts.cmp(&a.attempts).then(a.port.cmp(&b.port)));
+
+        Self {
+            window,
+            total: attempts.len(),
+            sources,
+            ports,
+        }
+    }
+}
+
+/// Firewall widget settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct FirewallOptions {
+    /// Log file to follow; the platform's usual firewall log when unset
+    pub log: Option<PathBuf>,
+    /// How far back the summary reaches, e.g. `"10m"`
+    #[serde(deserialize_with = "duration")]
+    pub window: Duration,
+    /// Attempts from one source within a minute that make a burst
+    pub burst: usize,
+}
+
+impl Default for FirewallOptions {
+    fn default() -> Self {
+        Self {
+            log: None,
+            window: Duration::from_secs(10 * 60),
+            burst: 20,
+        }
+    }
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    parse_duration(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+/// Where the firewall logs blocked packets by default
+///
+/// Windows Firewall logs to `pfirewall.log` once logging of dropped packets
+/// is turned on. On Linux, ufw writes `/var/log/ufw.log`; other netfilter
+/// logs end up in the kernel log.
+pub fn default_log() -> PathBuf {
+    if cfg!(windows) {
+        let root = std::env::var_os("SystemRoot").unwrap_or_else(|| r"C:\Windows".into());
+        return PathBuf::from(root).join(r"System32\LogFiles\Firewall\pfirewall.log");
+    }
+    ["/var/log/ufw.log", "/var/log/kern.log", "/var/log/messages"]
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("/var/log/ufw.log"))
+}
+
+/// New blocked attempts in the log (runs on a worker thread)
+fn read_attempts(tail: &mut LogTail) -> Result<Vec<BlockedAttempt>, String> {
+    let lines = tail.read_lines().map_err(|e| {
+        match AccessDenied::from_io(&e, tail.path(), LOG_ACCESS_HINT) {
+            Some(denied) => denied.to_string(),
+            None => format!("Can't read {}: {}", tail.path().display(), e),
+        }
+    })?;
+    Ok(lines.iter().filter_map(|line| parse_line(line)).collect())
+}
+
+/// Firewall widget
+///
+/// Follows the firewall log and summarizes the connection attempts it
+/// blocked over a rolling window, by source and by port. Sources sending a
+/// burst of attempts are shown in the critical color. Only attempts logged
+/// after the widget starts are counted.
+///
+/// # Events
+/// - `system.firewall` - The [`FirewallSummary`], every poll
+/// - `system.firewall.burst` - The [`SourceActivity`] of each source
+///   bursting, every poll while it does
+///
+/// When the event bus is not live, `system.firewall` events are shown
+/// instead of reading the log.
+pub struct FirewallWidget {
+    collector: Collector<LogTail, Result<Vec<BlockedAttempt>, String>>,
+    options: FirewallOptions,
+    /// Attempts within the window, oldest first
+    attempts: VecDeque<(Instant, BlockedAttempt)>,
+    summary: FirewallSummary,
+    /// Why the log can't be read
+    error: Option<String>,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl FirewallWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, FirewallOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings(settings)?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: FirewallOptions,
+    ) -> Self {
+        let log = options.log.clone().unwrap_or_else(default_log);
+        Self {
+            // An unreadable log is retried less and less often
+            collector: Collector::new(LogTail::new(log), read_attempts)
+                .with_backoff(Result::is_err),
+            summary: FirewallSummary {
+                window: options.window,
+                ..FirewallSummary::default()
+            },
+            options,
+            attempts: VecDeque::new(),
+            error: None,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_attempts(&mut self, attempts: Result<Vec<BlockedAttempt>, String>) {
+        let now = Instant::now();
+        match attempts {
+            Ok(attempts) => {
+                self.error = None;
+                self.attempts
+                    .extend(attempts.into_iter().map(|attempt| (now, attempt)));
+            }
+            Err(e) => self.error = Some(e),
+        }
+        while self
+            .attempts
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) >= self.options.window)
+        {
+            self.attempts.pop_front();
+        }
+
+        self.summary =
+            FirewallSummary::new(&self.attempts, self.options.window, self.options.burst, now);
+        for source in self.summary.sources.iter().filter(|s| s.burst) {
+            self.event_bus
+                .publish(Event::new("system.firewall.burst", source.clone()));
+        }
+        self.event_bus
+            .publish(Event::new("system.firewall", self.summary.clone()));
+    }
+
+    fn render_tables(&self, area: Rect, buf: &mut Buffer) {
+        let header = |titles: [&'static str; 3]| {
+            Row::new(
+                titles
+                    .into_iter()
+                    .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+            )
+        };
+        let critical = Style::default()
+            .fg(theme().critical)
+            .add_modifier(Modifier::BOLD);
+
+        let sources = self.summary.sources.iter().map(|source| {
+            let ports: Vec<String> = source
+                .ports
+                .iter()
+                .take(PORTS_PER_SOURCE)
+                .map(u16::to_string)
+                .collect();
+            let row = Row::new(vec![
+                source.source.clone(),
+                source.attempts.to_string(),
+                ports.join(","),
+            ]);
+            if source.burst {
+                row.style(critical)
+            } else {
+                row
+            }
+        });
+        let ports = self.summary.ports.iter().map(|port| {
+            Row::new(vec![
+                port.port.map_or("-".to_string(), |p| p.to_string()),
+                port.protocol.clone(),
+                port.attempts.to_string(),
+            ])
+        });
+
+        let [sources_area, ports_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(area);
+        Table::new(
+            sources,
+            [
+                Constraint::Min(15),
+                Constraint::Length(6),
+                Constraint::Min(8),
+            ],
+        )
+        .header(header(["Source", "Hits", "Ports"]))
+        .render(sources_area, buf);
+        Table::new(
+            ports,
+            [
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(6),
+            ],
+        )
+        .header(header(["Port", "Proto", "Hits"]))
+        .render(ports_area, buf);
+    }
+}
+
+/// `10m`, `90s` or `1h`
+fn window_label(window: Duration) -> String {
+    let secs = window.as_secs();
+    if secs >= 3600 && secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+impl Widget for FirewallWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected summaries instead of reading the log
+            let (sub, rx) = self.event_bus.subscribe("system.firewall");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        // The first read only finds the end of the log
+        self.collector.request(WorkerPool::global());
+        if let Some(attempts) = self.collector.wait() {
+            self.apply_attempts(attempts);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<FirewallSummary>())
+                .last();
+            if let Some(summary) = latest {
+                self.summary = FirewallSummary::clone(&summary);
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(attempts) = self.collector.try_take() {
+            self.apply_attempts(attempts);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " Firewall [{} blocked, last {}] ",
+                self.summary.total,
+                window_label(self.summary.window)
+            ))
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        match &self.error {
+            Some(error) if self.summary.total == 0 => Paragraph::new(error.as_str())
+                .style(Style::default().fg(theme().warning))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf),
+            _ => self.render_tables(inner, buf),
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mut summary = format!(
+            "Firewall: {} blocked in the last {}",
+            self.summary.total,
+            window_label(self.summary.window)
+        );
+        if let Some(top) = self.summary.sources.first() {
+            summary.push_str(&format!("; most from {} ({})", top.source, top.attempts));
+        }
+        let bursts = self.summary.sources.iter().filter(|s| s.burst).count();
+        if bursts > 0 {
+            let plural = if bursts == 1 { "" } else { "s" };
+            summary.push_str(&format!("; {} source{} bursting", bursts, plural));
+        }
+        if let Some(error) = &self.error {
+            summary.push_str(&format!("; {}", error));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(source: &str, port: Option<u16>) -> BlockedAttempt {
+        BlockedAttempt {
+            source: source.to_string(),
+            port,
+            protocol: "TCP".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let ufw = "Oct 17 10:00:00 host kernel: [1234.5] [UFW BLOCK] IN=eth0 OUT= \
+                   MAC=00:11 SRC=203.0.113.9 DST=10.0.0.2 LEN=60 PROTO=TCP SPT=51234 DPT=22 SYN";
+        assert_eq!(parse_line(ufw), Some(attempt("203.0.113.9", Some(22))));
+
+        let nft = "2024-05-01T10:00:00+00:00 host kernel: nft drop: IN=eth0 OUT= \
+                   SRC=198.51.100.4 DST=10.0.0.2 PROTO=ICMP TYPE=8 CODE=0";
+        assert_eq!(
+            parse_line(nft),
+            Some(BlockedAttempt {
+                source: "198.51.100.4".to_string(),
+                port: None,
+                protocol: "ICMP".to_string(),
+            })
+        );
+
+        let windows = "2024-05-01 10:00:00 DROP TCP 203.0.113.9 10.0.0.2 51234 3389 52 S - - - - - - - RECEIVE";
+        assert_eq!(
+            parse_line(windows),
+            Some(attempt("203.0.113.9", Some(3389)))
+        );
+
+        let allowed = ufw.replace("UFW BLOCK", "UFW ALLOW");
+        assert_eq!(parse_line(&allowed), None);
+        assert_eq!(
+            parse_line("2024-05-01 10:00:00 ALLOW TCP 10.0.0.5 10.0.0.2 1 443 52"),
+            None
+        );
+        assert_eq!(parse_line("#Fields: date time action protocol"), None);
+    }
+
+    #[test]
+    fn test_summary() {
+        let now = Instant::now();
+        let earlier = now - Duration::from_secs(5 * 60);
+        let attempts: VecDeque<(Instant, BlockedAttempt)> = [
+            (earlier, attempt("10.0.0.9", Some(80))),
+            (now, attempt("203.0.113.9", Some(22))),
+            (now, attempt("203.0.113.9", Some(22))),
+            (now, attempt("203.0.113.9", Some(23))),
+        ]
+        .into_iter()
+        .collect();
+
+        let summary = FirewallSummary::new(&attempts, Duration::from_secs(600), 3, now);
+        assert_eq!(summary.total, 4);
+        assert_eq!(
+            summary.sources[0],
+            SourceActivity {
+                source: "203.0.113.9".to_string(),
+                attempts: 3,
+                recent: 3,
+                burst: true,
+                ports: vec![22, 23],
+            }
+        );
+        assert_eq!(summary.sources[1].recent, 0);
+        assert!(!summary.sources[1].burst);
+        assert_eq!(summary.ports[0].port, Some(22));
+        assert_eq!(summary.ports[0].attempts, 2);
+    }
+
+    #[test]
+    fn test_options() {
+        let mut settings = WidgetSettings::new();
+        settings.insert("log".to_string(), "/var/log/fw.log".into());
+        settings.insert("window".to_string(), "5m".into());
+        let options: FirewallOptions = parse_settings(&settings).unwrap();
+        assert_eq!(options.log, Some(PathBuf::from("/var/log/fw.log")));
+        assert_eq!(options.window, Duration::from_secs(300));
+        assert_eq!(options.burst, 20);
+
+        settings.insert("window".to_string(), "soon".into());
+        assert!(parse_settings::<FirewallOptions>(&settings).is_err());
+    }
+}
@@ -1,25 +1,32 @@
 // devdash-widgets/src/git.rs
 use devdash_core::{
-    EventBus, EventResult, Widget,
+    Action, Collector, DataStatus, EventBus, EventResult, GitBranchChange, Widget, WidgetSettings,
+    WorkerPool,
     event::{Event, Subscription},
+    opener, parse_settings,
 };
 use git2::{BranchType, Repository, StatusOptions};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     prelude::Widget as RatatuiWidget,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph, Wrap},
 };
-use std::path::PathBuf;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use unicode_width::UnicodeWidthStr;
 
-use crate::common::focus_color;
+use crate::common::{focus_color, format_relative_time, middle_ellipsis, truncate_to_width};
 
 /// Git repository status information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitStatus {
+    /// Root of the working tree
+    pub workdir: PathBuf,
     pub branch: String,
     pub remote_branch: Option<String>,
     pub ahead: usize,
@@ -27,17 +34,90 @@ pub struct GitStatus {
     pub staged: usize,
     pub unstaged: usize,
     pub untracked: usize,
+    /// Web page of the `origin` remote on its forge, if it has one
+    pub forge_url: Option<String>,
+    pub files: Vec<FileChange>,
     pub last_commits: Vec<CommitInfo>,
 }
 
+/// A file changed in the index or working tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChange {
+    /// Relative to the working tree root
+    pub path: PathBuf,
+    /// `A`, `M`, `D`, `R`, `U` (conflicted) or `?` (untracked)
+    pub state: char,
+    /// First changed line, preferring unstaged changes
+    pub line: Option<u32>,
+}
+
 /// Git commit information for display
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitInfo {
+    pub id: String,      // Full hash
     pub hash: String,    // Short hash (7 chars)
     pub message: String, // First line only
     pub author: String,
+    pub time: SystemTime,
+}
+
+/// How `Enter` opens a commit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitOpener {
+    /// The commit's page on the `origin` forge, or the editor without one
+    #[default]
+    Forge,
+    /// The `difftool` command
+    Difftool,
+    /// The commit's patch, in the editor
+    Editor,
+}
+
+fn default_difftool() -> String {
+    "git difftool --dir-diff --no-prompt {}^!".to_string()
+}
+
+/// Git widget settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GitOptions {
+    #[serde(default)]
+    pub open_commit: CommitOpener,
+    /// Command comparing a commit with its parent; `{}` is the commit hash
+    #[serde(default = "default_difftool")]
+    pub difftool: String,
+}
+
+impl Default for GitOptions {
+    fn default() -> Self {
+        Self {
+            open_commit: CommitOpener::default(),
+            difftool: default_difftool(),
+        }
+    }
 }
 
+/// What the lower part of the widget lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GitView {
+    Commits,
+    Changes,
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("open_folder", &["g"], "Open repository in file manager"),
+    Action::new(
+        "switch_view",
+        &["v"],
+        "Switch between commits and changed files",
+    ),
+    Action::new("down", &["j", "down"], "Select next commit or file"),
+    Action::new("up", &["k", "up"], "Select previous commit or file"),
+    Action::new("open", &["enter"], "Open selected commit or file"),
+    Action::new("refresh", &["r"], "Refresh now"),
+];
+
 /// Git repository monitoring widget with status and commit history
 ///
 /// Displays current git repository status including branch information,
@@ -45,18 +125,34 @@ pub struct CommitInfo {
 /// not in a git repository.
 ///
 /// # Keyboard Shortcuts
-/// - `g` - Open current directory in file manager
+/// - `g` - Open current directory in the file manager set in `[openers]`
+/// - `v` - Switch between recent commits and changed files
+/// - `Up`/`Down` or `k`/`j` - Select a commit or file
+/// - `Enter` - Open the selected commit (see [`CommitOpener`]) or file in
+///   the editor, at its first change
 /// - `r` - Force refresh git status
 ///
 /// # Event Publishing
-/// - Publishes `system.git.status` events with current git status
+/// - `system.git.status` - The [`GitStatus`], every poll
+/// - `system.git.branch` - A [`GitBranchChange`] when the checked-out branch
+///   differs from the previous poll's
+///
+/// Status is read on the shared worker pool, since it can take a while on
+/// large repositories.
 pub struct GitWidget {
     repo_path: PathBuf,        // Current directory
     status: Option<GitStatus>, // None if not in repo
+    collector: Collector<PathBuf, Option<GitStatus>>,
     poll_interval: Duration,
     time_since_poll: Duration,
     event_bus: EventBus,
     _subscription: Option<Subscription>,
+    options: GitOptions,
+    view: GitView,
+    /// Selected row in the current view
+    selected: usize,
+    /// Why the last open failed, shown until the next one succeeds
+    open_error: Option<String>,
 }
 
 impl GitWidget {
@@ -66,61 +162,220 @@ impl GitWidget {
     /// * `event_bus` - Event bus for publishing git status
     /// * `poll_interval` - How often to refresh git status
     pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        let repo_path = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
         Self {
-            repo_path: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            collector: Collector::new(repo_path.clone(), |path: &mut PathBuf| {
+                Repository::open(path)
+                    .ok()
+                    .map(|repo| GitStatus::from_repo(&repo))
+            }),
+            repo_path,
             status: None,
             poll_interval,
             time_since_poll: Duration::ZERO,
             event_bus,
             _subscription: None,
+            options: GitOptions::default(),
+            view: GitView::Commits,
+            selected: 0,
+            open_error: None,
         }
     }
 
-    /// Poll git repository for current status
-    fn poll_git_status(&mut self) {
-        match Repository::open(&self.repo_path) {
-            Ok(repo) => {
-                self.status = Some(GitStatus::from_repo(&repo));
-
-                // Publish git status event
-                if let Some(ref status) = self.status {
-                    self.event_bus.publish(Event::new(
-                        "system.git.status",
-                        format!(
-                            "branch={}, staged={}, unstaged={}, untracked={}, ahead={}, behind={}",
-                            status.branch,
-                            status.staged,
-                            status.unstaged,
-                            status.untracked,
-                            status.ahead,
-                            status.behind
-                        ),
-                    ));
-                }
-            }
-            Err(_) => {
-                self.status = None;
+    /// Create a git widget configured by its `[widgets.git]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings(settings)?;
+        Ok(Self::new(event_bus, poll_interval).with_options(options))
+    }
+
+    pub fn with_options(mut self, options: GitOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Rows in the current view
+    fn row_count(&self) -> usize {
+        self.status.as_ref().map_or(0, |status| match self.view {
+            GitView::Commits => status.last_commits.len(),
+            GitView::Changes => status.files.len(),
+        })
+    }
+
+    /// Display a freshly collected status (None if not in a repository)
+    fn apply_status(&mut self, status: Option<GitStatus>) {
+        if let Some(status) = &status {
+            if let Some(change) = branch_change(self.status.as_ref(), status) {
+                self.event_bus
+                    .publish(Event::new("system.git.branch", change));
             }
+            self.event_bus
+                .publish(Event::new("system.git.status", status.clone()));
         }
+
+        self.status = status;
+        self.selected = self.selected.min(self.row_count().saturating_sub(1));
     }
 
     /// Open current directory in file manager
-    fn open_file_manager(&self) {
-        let path = self.repo_path.to_string_lossy().to_string();
-
-        #[cfg(target_os = "windows")]
-        {
-            std::process::Command::new("cmd")
-                .args(["/c", "start", &path])
-                .spawn()
-                .ok();
+    fn open_file_manager(&mut self) {
+        self.open_error = opener::open_dir(&self.repo_path)
+            .err()
+            .map(|e| e.to_string());
+    }
+
+    /// Open the selected commit or file
+    fn open_selected(&mut self) {
+        let Some(status) = &self.status else {
+            return;
+        };
+        let result = match self.view {
+            GitView::Commits => match status.last_commits.get(self.selected) {
+                Some(commit) => self.open_commit(status, commit),
+                None => return,
+            },
+            GitView::Changes => match status.files.get(self.selected) {
+                Some(file) if file.state == 'D' => {
+                    Err(format!("{} was deleted", file.path.display()))
+                }
+                Some(file) => opener::edit_file(&status.workdir.join(&file.path), file.line)
+                    .map_err(|e| e.to_string()),
+                None => return,
+            },
+        };
+        self.open_error = result.err();
+    }
+
+    fn open_commit(&self, status: &GitStatus, commit: &CommitInfo) -> Result<(), String> {
+        match (self.options.open_commit, &status.forge_url) {
+            (CommitOpener::Forge, Some(url)) => {
+                opener::open_url(&format!("{}/commit/{}", url, commit.id))
+            }
+            (CommitOpener::Difftool, _) => opener::run(&self.options.difftool, &commit.id),
+            _ => {
+                let patch = write_patch(&status.workdir, &commit.id)
+                    .map_err(|e| format!("Failed to write patch: {}", e))?;
+                opener::edit_file(&patch, None)
+            }
         }
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// The branch change from `previous` to `current`, if the same working tree
+/// now has another branch checked out
+fn branch_change(previous: Option<&GitStatus>, current: &GitStatus) -> Option<GitBranchChange> {
+    let previous = previous.filter(|p| p.workdir == current.workdir)?;
+    (previous.branch != current.branch).then(|| GitBranchChange {
+        from: previous.branch.clone(),
+        to: current.branch.clone(),
+        repo_path: current.workdir.display().to_string(),
+    })
+}
+
+/// Web page of a repository from its remote URL, for remotes reached over
+/// HTTP(S) or SSH
+fn forge_url(remote: &str) -> Option<String> {
+    let remote = remote.trim_end_matches('/').trim_end_matches(".git");
+    let (host, path) = if let Some(rest) = remote
+        .strip_prefix("https://")
+        .or_else(|| remote.strip_prefix("http://"))
+        .or_else(|| remote.strip_prefix("ssh://"))
+    {
+        rest.split_once('/')?
+    } else if !remote.contains("://") {
+        // scp-like syntax, e.g. git@github.com:owner/repo
+        remote.split_once(':')?
+    } else {
+        return None;
+    };
+    // Drop any user and port
+    let host = host.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    if host.is_empty() || path.is_empty() || path.starts_with('/') {
+        return None;
+    }
+    Some(format!("https://{}/{}", host, path))
+}
 
-        #[cfg(not(target_os = "windows"))]
-        {
-            std::process::Command::new("open").arg(&path).spawn().ok();
+/// Write a commit and its diff against its first parent to a temporary
+/// file, returning its path
+fn write_patch(workdir: &Path, id: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let repo = Repository::open(workdir)?;
+    let commit = repo.find_commit(git2::Oid::from_str(id)?)?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+    let mut patch = format!("commit {}\nAuthor: {}\n\n", commit.id(), commit.author());
+    for line in commit.message().unwrap_or("").lines() {
+        patch.push_str(&format!("    {}\n", line));
+    }
+    patch.push('\n');
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
         }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+
+    let path = std::env::temp_dir().join(format!("devdash-{}.patch", &id[..7]));
+    std::fs::write(&path, patch)?;
+    Ok(path)
+}
+
+/// Letter shown for a file's status
+fn change_state(status: git2::Status) -> char {
+    if status.is_conflicted() {
+        'U'
+    } else if status.is_wt_new() {
+        '?'
+    } else if status.is_index_new() {
+        'A'
+    } else if status.is_index_deleted() || status.is_wt_deleted() {
+        'D'
+    } else if status.is_index_renamed() || status.is_wt_renamed() {
+        'R'
+    } else {
+        'M'
+    }
+}
+
+/// First changed line of each changed file, preferring unstaged changes
+fn first_changed_lines(repo: &Repository) -> HashMap<PathBuf, u32> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    // Without context, hunks start at the changed lines themselves
+    let mut options = git2::DiffOptions::new();
+    options.context_lines(0);
+    let diffs = [
+        repo.diff_index_to_workdir(None, Some(&mut options)).ok(),
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut options))
+            .ok(),
+    ];
+
+    let mut lines = HashMap::new();
+    for diff in diffs.into_iter().flatten() {
+        let _ = diff.foreach(
+            &mut |_, _| true,
+            None,
+            Some(&mut |delta, hunk| {
+                if let Some(path) = delta.new_file().path() {
+                    lines
+                        .entry(path.to_path_buf())
+                        .or_insert(hunk.new_start().max(1));
+                }
+                true
+            }),
+            None,
+        );
     }
+    lines
 }
 
 impl GitStatus {
@@ -153,9 +408,20 @@ impl GitStatus {
             })
             .unwrap_or((None, 0, 0));
 
+        let forge_url = repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().and_then(forge_url));
+
         // Get file status counts
+        let first_lines = first_changed_lines(repo);
+        let mut files = Vec::new();
         let (staged, unstaged, untracked) = repo
-            .statuses(Some(StatusOptions::default().include_untracked(true)))
+            .statuses(Some(
+                StatusOptions::default()
+                    .include_untracked(true)
+                    .recurse_untracked_dirs(true),
+            ))
             .map(|statuses| {
                 let mut staged = 0;
                 let mut unstaged = 0;
@@ -163,6 +429,14 @@ impl GitStatus {
 
                 for entry in statuses.iter() {
                     let status = entry.status();
+                    if let Some(path) = entry.path() {
+                        let path = PathBuf::from(path);
+                        files.push(FileChange {
+                            line: first_lines.get(&path).copied(),
+                            path,
+                            state: change_state(status),
+                        });
+                    }
                     if status.is_index_new()
                         || status.is_index_modified()
                         || status.is_index_deleted()
@@ -204,11 +478,15 @@ impl GitStatus {
                             .unwrap_or("")
                             .to_string();
                         let author = commit.author().name().unwrap_or("").to_string();
+                        let time =
+                            UNIX_EPOCH + Duration::from_secs(commit.time().seconds().max(0) as u64);
 
                         commits.push(CommitInfo {
+                            id: commit.id().to_string(),
                             hash,
                             message,
                             author,
+                            time,
                         });
                     }
                 }
@@ -217,6 +495,7 @@ impl GitStatus {
             .unwrap_or_default();
 
         Self {
+            workdir: repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf(),
             branch,
             remote_branch,
             ahead,
@@ -224,6 +503,8 @@ impl GitStatus {
             staged,
             unstaged,
             untracked,
+            forge_url,
+            files,
             last_commits,
         }
     }
@@ -231,7 +512,11 @@ impl GitStatus {
 
 impl Widget for GitWidget {
     fn on_mount(&mut self) {
-        self.poll_git_status(); // Initial poll
+        // Initial poll
+        self.collector.request(WorkerPool::global());
+        if let Some(status) = self.collector.wait() {
+            self.apply_status(status);
+        }
 
         // Subscribe to git refresh events
         let (sub, _rx) = self.event_bus.subscribe("system.git.refresh");
@@ -239,28 +524,56 @@ impl Widget for GitWidget {
     }
 
     fn on_update(&mut self, delta: Duration) {
+        if let Some(status) = self.collector.try_take() {
+            self.apply_status(status);
+        }
+
         self.time_since_poll += delta;
 
         if self.time_since_poll >= self.poll_interval {
-            self.poll_git_status();
+            self.collector.request(WorkerPool::global());
             self.time_since_poll = Duration::ZERO;
         }
     }
 
-    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
-        use crossterm::event::KeyCode;
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(self.collector.status(self.poll_interval))
+    }
 
-        if let devdash_core::Event::Key(key) = event {
-            match key.code {
-                KeyCode::Char('g') => {
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        if let devdash_core::Event::Action(action) = event {
+            match action.as_str() {
+                "open_folder" => {
                     self.open_file_manager();
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('r') => {
+                "refresh" => {
                     // Force refresh
                     self.time_since_poll = self.poll_interval;
                     return EventResult::Consumed;
                 }
+                "switch_view" => {
+                    self.view = match self.view {
+                        GitView::Commits => GitView::Changes,
+                        GitView::Changes => GitView::Commits,
+                    };
+                    self.selected = 0;
+                    return EventResult::Consumed;
+                }
+                "down" => {
+                    if self.selected + 1 < self.row_count() {
+                        self.selected += 1;
+                    }
+                    return EventResult::Consumed;
+                }
+                "up" => {
+                    self.selected = self.selected.saturating_sub(1);
+                    return EventResult::Consumed;
+                }
+                "open" => {
+                    self.open_selected();
+                    return EventResult::Consumed;
+                }
                 _ => {}
             }
         }
@@ -351,19 +664,79 @@ impl Widget for GitWidget {
                 },
             ]));
 
+            if let Some(error) = &self.open_error {
+                lines.push(Line::from(Span::styled(
+                    error.as_str(),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+
+            let selected_style = Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD);
+
             // Commits section
-            if !status.last_commits.is_empty() && inner_area.height > 4 {
+            if self.view == GitView::Commits
+                && !status.last_commits.is_empty()
+                && inner_area.height > 4
+            {
                 lines.push(Line::from(Span::styled(
                     "Recent commits:",
                     Style::default().fg(Color::Yellow),
                 )));
 
-                for commit in &status.last_commits {
-                    lines.push(Line::from(vec![
+                for (idx, commit) in status.last_commits.iter().enumerate() {
+                    // Cut long messages short so each commit keeps to one row
+                    // and its age stays in view
+                    let age = format!(" ({})", format_relative_time(commit.time));
+                    let room = (inner_area.width as usize)
+                        .saturating_sub(commit.hash.width() + 1 + age.width());
+                    let line = Line::from(vec![
                         Span::styled(&commit.hash, Style::default().fg(Color::Cyan)),
                         Span::from(" "),
-                        Span::from(&commit.message),
-                    ]));
+                        Span::from(truncate_to_width(&commit.message, room)),
+                        Span::styled(age, Style::default().fg(Color::DarkGray)),
+                    ]);
+                    lines.push(if idx == self.selected {
+                        line.style(selected_style)
+                    } else {
+                        line
+                    });
+                }
+            }
+
+            // Changed files section
+            if self.view == GitView::Changes && inner_area.height > 4 {
+                lines.push(Line::from(Span::styled(
+                    "Changes:",
+                    Style::default().fg(Color::Yellow),
+                )));
+                if status.files.is_empty() {
+                    lines.push(Line::from("  none"));
+                }
+
+                // Scroll to keep the selection in view
+                let rows = (inner_area.height as usize)
+                    .saturating_sub(lines.len())
+                    .max(1);
+                let start = (self.selected + 1).saturating_sub(rows);
+                for (idx, file) in status.files.iter().enumerate().skip(start).take(rows) {
+                    let color = match file.state {
+                        'A' => Color::Green,
+                        '?' => Color::Gray,
+                        _ => Color::Red,
+                    };
+                    let path = file.path.to_string_lossy();
+                    let room = (inner_area.width as usize).saturating_sub(2);
+                    let line = Line::from(vec![
+                        Span::styled(format!("{} ", file.state), Style::default().fg(color)),
+                        Span::from(middle_ellipsis(&path, room)),
+                    ]);
+                    lines.push(if idx == self.selected {
+                        line.style(selected_style)
+                    } else {
+                        line
+                    });
                 }
             }
 
@@ -392,4 +765,119 @@ impl Widget for GitWidget {
     fn needs_update(&self) -> bool {
         true // Always poll for updates
     }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn summary(&self) -> Option<String> {
+        let Some(status) = &self.status else {
+            return Some("Git: no repository".to_string());
+        };
+        let mut parts = vec![format!("Git branch {}", status.branch)];
+        for (count, label) in [
+            (status.ahead, "ahead"),
+            (status.behind, "behind"),
+            (status.staged, "staged"),
+            (status.unstaged, "modified"),
+            (status.untracked, "untracked"),
+        ] {
+            if count > 0 {
+                parts.push(format!("{} {}", count, label));
+            }
+        }
+        if status.staged + status.unstaged + status.untracked == 0 {
+            parts.push("clean".to_string());
+        }
+        Some(parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forge_url() {
+        for remote in [
+            "https://github.com/Cod-e-Codes/devdash.git",
+            "https://user@github.com/Cod-e-Codes/devdash/",
+            "git@github.com:Cod-e-Codes/devdash.git",
+            "ssh://git@github.com:22/Cod-e-Codes/devdash",
+        ] {
+            assert_eq!(
+                forge_url(remote).as_deref(),
+                Some("https://github.com/Cod-e-Codes/devdash"),
+                "{}",
+                remote
+            );
+        }
+        assert_eq!(forge_url("/srv/git/devdash.git"), None);
+        assert_eq!(forge_url("file:///srv/git/devdash"), None);
+    }
+
+    #[test]
+    fn test_branch_change() {
+        let status = |workdir: &str, branch: &str| GitStatus {
+            workdir: PathBuf::from(workdir),
+            branch: branch.to_string(),
+            remote_branch: None,
+            ahead: 0,
+            behind: 0,
+            staged: 0,
+            unstaged: 0,
+            untracked: 0,
+            forge_url: None,
+            files: Vec::new(),
+            last_commits: Vec::new(),
+        };
+        let main = status("/src/devdash", "main");
+        let change = branch_change(Some(&main), &status("/src/devdash", "fix-ci")).unwrap();
+        assert_eq!(
+            (change.from.as_str(), change.to.as_str()),
+            ("main", "fix-ci")
+        );
+        assert_eq!(change.repo_path, "/src/devdash");
+
+        assert!(branch_change(Some(&main), &main).is_none());
+        assert!(branch_change(None, &main).is_none());
+        // Another repository isn't a branch change
+        assert!(branch_change(Some(&main), &status("/src/other", "dev")).is_none());
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let dir = std::env::temp_dir().join(format!("devdash-git-{}", std::process::id()));
+        let repo = Repository::init(&dir).unwrap();
+        std::fs::write(dir.join("tracked.txt"), "one\ntwo\nthree\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("tracked.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+
+        std::fs::write(dir.join("tracked.txt"), "one\ntwo\nTHREE\n").unwrap();
+        std::fs::write(dir.join("new.txt"), "new\n").unwrap();
+        let status = GitStatus::from_repo(&repo);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            status.files,
+            [
+                FileChange {
+                    path: PathBuf::from("new.txt"),
+                    state: '?',
+                    line: None,
+                },
+                FileChange {
+                    path: PathBuf::from("tracked.txt"),
+                    state: 'M',
+                    line: Some(3),
+                },
+            ]
+        );
+        assert_eq!(status.last_commits[0].message, "Initial");
+        assert_eq!(status.forge_url, None);
+    }
 }
@@ -0,0 +1,659 @@
+// devdash-widgets/src/health.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WorkerPool,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::common::{
+    AccessDenied, LOG_ACCESS_HINT, LogTail, focus_color, format_relative_time, journal_restricted,
+};
+
+/// Problems kept for display, newest first
+const HISTORY_LEN: usize = 50;
+
+/// What kind of trouble a kernel message reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthKind {
+    /// A disk failed a read or write
+    IoError,
+    /// smartd saw a disk's S.M.A.R.T. attributes deteriorate
+    Smart,
+    /// A filesystem found corruption or failed to update its metadata
+    FilesystemError,
+    /// A filesystem was remounted read-only or shut down after an error
+    ReadOnly,
+    /// The OOM killer killed a process
+    OomKill,
+}
+
+impl HealthKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            HealthKind::IoError => "I/O error",
+            HealthKind::Smart => "S.M.A.R.T.",
+            HealthKind::FilesystemError => "FS error",
+            HealthKind::ReadOnly => "read-only",
+            HealthKind::OomKill => "OOM kill",
+        }
+    }
+}
+
+/// A problem reported in the kernel log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthIssue {
+    pub kind: HealthKind,
+    /// Device such as `sda1`, or the killed process for OOM kills
+    pub subject: Option<String>,
+    /// The log message, without the syslog prefix
+    pub message: String,
+    /// When the message was read
+    pub time: SystemTime,
+}
+
+impl HealthIssue {
+    /// One sentence describing the problem, e.g. for an alert
+    pub fn describe(&self) -> String {
+        match (self.kind, &self.subject) {
+            (HealthKind::IoError, Some(device)) => format!("Disk I/O error on {}", device),
+            (HealthKind::IoError, None) => "Disk I/O error".to_string(),
+            (HealthKind::Smart, Some(device)) => format!("S.M.A.R.T. warning for {}", device),
+            (HealthKind::Smart, None) => "S.M.A.R.T. warning".to_string(),
+            (HealthKind::FilesystemError, Some(device)) => {
+                format!("Filesystem error on {}", device)
+            }
+            (HealthKind::FilesystemError, None) => "Filesystem error".to_string(),
+            (HealthKind::ReadOnly, Some(device)) => {
+                format!("Filesystem on {} went read-only", device)
+            }
+            (HealthKind::ReadOnly, None) => "A filesystem went read-only".to_string(),
+            (HealthKind::OomKill, Some(process)) => format!("Out of memory: killed {}", process),
+            (HealthKind::OomKill, None) => "Out of memory: killed a process".to_string(),
+        }
+    }
+}
+
+/// Phrases marking each kind of problem, matched against the lowercased
+/// message; earlier kinds win
+const PATTERNS: [(HealthKind, &[&str]); 5] = [
+    (HealthKind::OomKill, &["killed process"]),
+    (
+        HealthKind::ReadOnly,
+        &[
+            "remounting filesystem read-only",
+            "forced readonly",
+            "shutting down filesystem",
+        ],
+    ),
+    (
+        HealthKind::FilesystemError,
+        &[
+            "-fs error",
+            "btrfs error",
+            "btrfs critical",
+            "metadata corruption",
+            "corruption detected",
+            "metadata i/o error",
+        ],
+    ),
+    (
+        HealthKind::Smart,
+        &[
+            "currently unreadable (pending) sectors",
+            "offline uncorrectable sectors",
+            "failed smart self-check",
+            "error count increased",
+        ],
+    ),
+    (
+        HealthKind::IoError,
+        &[
+            "i/o error",
+            "medium error",
+            "unrecovered read error",
+            "failed command: read",
+            "failed command: write",
+        ],
+    ),
+];
+
+/// Parse one kernel or smartd log line, if it reports a problem
+///
+/// Takes lines as syslog writes them (`Oct 17 10:00:00 host kernel: ...`)
+/// or bare messages as the journal prints them.
+pub fn parse_line(line: &str, time: SystemTime) -> Option<HealthIssue> {
+    let message = strip_prefix(line);
+    let lower = message.to_ascii_lowercase();
+    let kind = PATTERNS
+        .iter()
+        .find(|(_, phrases)| phrases.iter().any(|phrase| lower.contains(phrase)))
+        .map(|(kind, _)| *kind)?;
+    let subject = match kind {
+        HealthKind::OomKill => killed_process(message),
+        _ => device(message),
+    };
+    Some(HealthIssue {
+        kind,
+        subject,
+        message: message.to_string(),
+        time,
+    })
+}
+
+/// The message without the syslog prefix and kernel timestamp
+fn strip_prefix(line: &str) -> &str {
+    let message = ["kernel: ", "]: "]
+        .iter()
+        .find_map(|tag| line.split_once(tag).map(|(_, rest)| rest))
+        .unwrap_or(line)
+        .trim_start();
+    // `[12345.678901] message`
+    match message
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    {
+        Some((stamp, rest)) if stamp.trim().parse::<f64>().is_ok() => rest.trim_start(),
+        _ => message,
+    }
+}
+
+/// `Out of memory: Killed process 4321 (firefox) total-vm:...`
+fn killed_process(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once("process ")?;
+    let (_, rest) = rest.split_once('(')?;
+    let (name, _) = rest.split_once(')')?;
+    Some(name.to_string())
+}
+
+/// The device a disk or filesystem message is about
+///
+/// Understands `dev sda,` (block layer), `Device: /dev/sda` (smartd),
+/// `EXT4-fs error (device sda1):` and `XFS (sda1):`, and `ata1.00:`.
+fn device(message: &str) -> Option<String> {
+    let word = |rest: &str| {
+        rest.split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+            .next()
+            .filter(|word| !word.is_empty())
+            .map(|word| word.trim_start_matches("/dev/").to_string())
+    };
+    if let Some((_, rest)) = message.split_once("Device: ") {
+        return word(rest);
+    }
+    if let Some((_, rest)) = message.split_once("dev ") {
+        return word(rest);
+    }
+    if let Some((_, rest)) = message.split_once('(') {
+        let (inside, _) = rest.split_once(')')?;
+        return word(inside.trim_start_matches("device "));
+    }
+    message.starts_with("ata").then(|| word(message)).flatten()
+}
+
+/// Health widget settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct HealthOptions {
+    /// Log file to follow instead of the journal, e.g. `/var/log/kern.log`
+    pub log: Option<PathBuf>,
+}
+
+/// Where kernel messages are read from
+#[derive(Debug)]
+enum KernelLog {
+    File(LogTail),
+    /// The systemd journal, read after `cursor`; `None` before the first read
+    Journal {
+        cursor: Option<String>,
+    },
+}
+
+impl KernelLog {
+    /// The journal where journald runs, otherwise the first syslog file
+    /// that exists
+    fn detect() -> Self {
+        if Path::new("/run/systemd/journal").exists() {
+            return KernelLog::Journal { cursor: None };
+        }
+        let path = ["/var/log/kern.log", "/var/log/messages", "/var/log/syslog"]
+            .into_iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+            .unwrap_or_else(|| PathBuf::from("/var/log/kern.log"));
+        KernelLog::File(LogTail::new(path))
+    }
+}
+
+/// New problems in the kernel log (runs on a worker thread)
+///
+/// Like following a file, the first read only finds the end of the journal.
+fn read_issues(log: &mut KernelLog) -> Result<Vec<HealthIssue>, String> {
+    let lines = match log {
+        KernelLog::File(tail) => tail.read_lines().map_err(|e| {
+            match AccessDenied::from_io(&e, tail.path(), LOG_ACCESS_HINT) {
+                Some(denied) => denied.to_string(),
+                None => format!("Can't read {}: {}", tail.path().display(), e),
+            }
+        })?,
+        KernelLog::Journal { cursor } => {
+            let first = cursor.is_none();
+            let lines = read_journal(cursor)?;
+            if first { Vec::new() } else { lines }
+        }
+    };
+    let now = SystemTime::now();
+    Ok(lines
+        .iter()
+        .filter_map(|line| parse_line(line, now))
+        .collect())
+}
+
+/// Kernel and smartd messages after `cursor`, moving it past them
+fn read_journal(cursor: &mut Option<String>) -> Result<Vec<String>, String> {
+    // journalctl would only show the user's own messages, without saying so
+    if journal_restricted() {
+        return Err(AccessDenied::new(
+            "the system journal",
+            "add your user to the systemd-journal group, or run devdash with sudo",
+        )
+        .to_string());
+    }
+    let mut command = Command::new("journalctl");
+    command.args([
+        "--no-pager",
+        "--quiet",
+        "--output=cat",
+        "--show-cursor",
+        "_TRANSPORT=kernel",
+        "+",
+        "SYSLOG_IDENTIFIER=smartd",
+    ]);
+    match cursor {
+        Some(cursor) => command.arg(format!("--after-cursor={}", cursor)),
+        None => command.arg("--lines=1"),
+    };
+    let output = command
+        .output()
+        .map_err(|e| format!("Can't run journalctl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "journalctl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_journal(
+        &String::from_utf8_lossy(&output.stdout),
+        cursor,
+    ))
+}
+
+/// Split `journalctl --show-cursor` output into messages and the cursor
+/// after them; `cursor` is left alone when there were no new entries
+fn parse_journal(output: &str, cursor: &mut Option<String>) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in output.lines() {
+        match line.strip_prefix("-- cursor: ") {
+            Some(next) => *cursor = Some(next.to_string()),
+            None => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// System health widget
+///
+/// Scans the kernel log for disk I/O errors, S.M.A.R.T. warnings from
+/// smartd, filesystem errors, filesystems remounted read-only, and OOM
+/// kills, and lists the latest. Reads the systemd journal where journald
+/// runs and the syslog kernel log otherwise; either usually needs root or
+/// membership of the `adm` or `systemd-journal` group. Only messages
+/// logged after the widget starts are shown.
+///
+/// # Events
+/// - `system.health` - Every [`HealthIssue`] kept, newest first, every poll
+/// - `system.health.issue` - Each new [`HealthIssue`], once
+///
+/// When the event bus is not live, `system.health` events are shown
+/// instead of reading the log.
+pub struct HealthWidget {
+    collector: Collector<KernelLog, Result<Vec<HealthIssue>, String>>,
+    /// Newest first
+    issues: Vec<HealthIssue>,
+    /// Why the log can't be read
+    error: Option<String>,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl HealthWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, HealthOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings(settings)?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: HealthOptions,
+    ) -> Self {
+        let log = match options.log {
+            Some(path) => KernelLog::File(LogTail::new(path)),
+            None => KernelLog::detect(),
+        };
+        Self {
+            // An unreadable log is retried less and less often
+            collector: Collector::new(log, read_issues).with_backoff(Result::is_err),
+            issues: Vec::new(),
+            error: None,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_issues(&mut self, issues: Result<Vec<HealthIssue>, String>) {
+        match issues {
+            Ok(issues) => {
+                self.error = None;
+                for issue in issues {
+                    self.event_bus
+                        .publish(Event::new("system.health.issue", issue.clone()));
+                    self.issues.insert(0, issue);
+                }
+                self.issues.truncate(HISTORY_LEN);
+            }
+            Err(e) => self.error = Some(e),
+        }
+        self.event_bus
+            .publish(Event::new("system.health", self.issues.clone()));
+    }
+}
+
+impl Widget for HealthWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected issues instead of reading the log
+            let (sub, rx) = self.event_bus.subscribe("system.health");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        // The first read only finds the end of the log
+        self.collector.request(WorkerPool::global());
+        if let Some(issues) = self.collector.wait() {
+            self.apply_issues(issues);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<Vec<HealthIssue>>())
+                .last();
+            if let Some(issues) = latest {
+                self.issues = Vec::clone(&issues);
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(issues) = self.collector.try_take() {
+            self.apply_issues(issues);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let title = match self.issues.len() {
+            0 => " System Health [ok] ".to_string(),
+            1 => " System Health [1 problem] ".to_string(),
+            n => format!(" System Health [{} problems] ", n),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.issues.is_empty() {
+            let (text, color) = match &self.error {
+                Some(error) => (error.as_str(), theme().warning),
+                None => ("No disk, filesystem or memory errors", theme().good),
+            };
+            Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        }
+
+        let critical = Style::default().fg(theme().critical);
+        let rows = self.issues.iter().map(|issue| {
+            Row::new(vec![
+                Cell::from(format_relative_time(issue.time)),
+                Cell::from(issue.kind.label()).style(critical),
+                Cell::from(issue.subject.clone().unwrap_or_else(|| "-".to_string())),
+                Cell::from(issue.message.clone()),
+            ])
+        });
+        let header = Row::new(
+            ["When", "Problem", "Device", "Message"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .render(inner, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(match (self.issues.first(), &self.error) {
+            (None, Some(error)) => format!("System health: {}", error),
+            (None, None) => "System health: no problems".to_string(),
+            (Some(latest), _) => format!(
+                "System health: {} problems; latest {}, {}",
+                self.issues.len(),
+                latest.describe(),
+                format_relative_time(latest.time)
+            ),
+        })
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Option<(HealthKind, Option<String>, String)> {
+        parse_line(line, SystemTime::UNIX_EPOCH)
+            .map(|issue| (issue.kind, issue.subject, issue.message))
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let io = "Oct 17 10:00:00 host kernel: [ 1234.567890] I/O error, dev sda, sector 123456 \
+                  op 0x0:(READ) flags 0x0 phys_seg 1 prio class 0";
+        assert_eq!(
+            parse(io),
+            Some((
+                HealthKind::IoError,
+                Some("sda".to_string()),
+                "I/O error, dev sda, sector 123456 op 0x0:(READ) flags 0x0 phys_seg 1 prio class 0"
+                    .to_string()
+            ))
+        );
+
+        let cases = [
+            (
+                "Buffer I/O error on dev sdb1, logical block 0, async page read",
+                HealthKind::IoError,
+                Some("sdb1"),
+            ),
+            (
+                "ata1.00: failed command: READ FPDMA QUEUED",
+                HealthKind::IoError,
+                Some("ata1.00"),
+            ),
+            (
+                "EXT4-fs error (device sda1): ext4_lookup:1855: inode #2: comm ls: deleted inode referenced",
+                HealthKind::FilesystemError,
+                Some("sda1"),
+            ),
+            (
+                "XFS (nvme0n1p2): Metadata corruption detected at xfs_buf_ioend+0x5c",
+                HealthKind::FilesystemError,
+                Some("nvme0n1p2"),
+            ),
+            (
+                "EXT4-fs (sda1): Remounting filesystem read-only",
+                HealthKind::ReadOnly,
+                Some("sda1"),
+            ),
+            (
+                "BTRFS info (device dm-0): forced readonly",
+                HealthKind::ReadOnly,
+                Some("dm-0"),
+            ),
+            (
+                "Oct 17 10:00:00 host smartd[812]: Device: /dev/sda [SAT], 8 Currently unreadable (pending) sectors",
+                HealthKind::Smart,
+                Some("sda"),
+            ),
+            (
+                "Out of memory: Killed process 4321 (firefox) total-vm:8123456kB, anon-rss:4000000kB",
+                HealthKind::OomKill,
+                Some("firefox"),
+            ),
+            (
+                "Memory cgroup out of memory: Killed process 99 (node) total-vm:1000kB",
+                HealthKind::OomKill,
+                Some("node"),
+            ),
+        ];
+        for (line, kind, subject) in cases {
+            let (parsed_kind, parsed_subject, _) = parse(line).unwrap();
+            assert_eq!(
+                (parsed_kind, parsed_subject.as_deref()),
+                (kind, subject),
+                "{}",
+                line
+            );
+        }
+
+        assert_eq!(
+            parse("EXT4-fs (sda1): mounted filesystem with ordered data mode"),
+            None
+        );
+        assert_eq!(
+            parse("Oct 17 10:00:00 host kernel: [ 1.0] usb 1-1: new high-speed USB device"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let issue = parse_line(
+            "EXT4-fs (sda1): Remounting filesystem read-only",
+            SystemTime::UNIX_EPOCH,
+        )
+        .unwrap();
+        assert_eq!(issue.describe(), "Filesystem on sda1 went read-only");
+        let issue = HealthIssue {
+            subject: None,
+            ..issue
+        };
+        assert_eq!(issue.describe(), "A filesystem went read-only");
+    }
+
+    #[test]
+    fn test_parse_journal() {
+        let mut cursor = None;
+        let lines = parse_journal(
+            "I/O error, dev sda, sector 1\n-- cursor: s=abc;i=1\n",
+            &mut cursor,
+        );
+        assert_eq!(lines, ["I/O error, dev sda, sector 1"]);
+        assert_eq!(cursor.as_deref(), Some("s=abc;i=1"));
+
+        assert!(parse_journal("", &mut cursor).is_empty());
+        assert_eq!(cursor.as_deref(), Some("s=abc;i=1"));
+    }
+}
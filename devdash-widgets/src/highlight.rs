@@ -0,0 +1,237 @@
+// devdash-widgets/src/highlight.rs
+//! Threshold rules that color process rows, e.g. `cpu > 80 or memory > 2GB`.
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::str::FromStr;
+
+use crate::ProcessInfo;
+use crate::common::parse_size;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    /// CPU usage in percent
+    Cpu,
+    /// Resident memory in bytes
+    Memory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    field: Field,
+    op: Op,
+    value: f64,
+}
+
+impl Comparison {
+    fn matches(&self, process: &ProcessInfo) -> bool {
+        let actual = match self.field {
+            Field::Cpu => f64::from(process.cpu_percent),
+            Field::Memory => process.memory_bytes as f64,
+        };
+        match self.op {
+            Op::Gt => actual > self.value,
+            Op::Ge => actual >= self.value,
+            Op::Lt => actual < self.value,
+            Op::Le => actual <= self.value,
+            Op::Eq => actual == self.value,
+        }
+    }
+}
+
+/// Condition over a process's CPU and memory usage
+///
+/// Comparisons such as `cpu > 80` or `memory >= 512MiB`, joined with `and`
+/// and `or`; `and` binds tighter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// Alternatives, each a list of comparisons that must all hold
+    any: Vec<Vec<Comparison>>,
+}
+
+impl Condition {
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        self.any
+            .iter()
+            .any(|all| all.iter().all(|cmp| cmp.matches(process)))
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s);
+        let any = tokens
+            .split(|t| t.eq_ignore_ascii_case("or"))
+            .map(|group| {
+                group
+                    .split(|t| t.eq_ignore_ascii_case("and"))
+                    .map(parse_comparison)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{} in '{}'", e, s))?;
+        Ok(Self { any })
+    }
+}
+
+/// Split into words and runs of comparison operators
+fn tokenize(s: &str) -> Vec<String> {
+    let is_op = |c: char| matches!(c, '<' | '>' | '=');
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        while let Some(&c) = chars.peek()
+            && !c.is_whitespace()
+            && is_op(c) == is_op(token.chars().next().unwrap_or(c))
+        {
+            token.push(c);
+            chars.next();
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn parse_comparison(tokens: &[String]) -> Result<Comparison, String> {
+    let [field, op, value] = tokens else {
+        return Err(format!(
+            "expected '<field> <op> <value>', got '{}'",
+            tokens.join(" ")
+        ));
+    };
+    let field = match field.to_ascii_lowercase().as_str() {
+        "cpu" => Field::Cpu,
+        "memory" | "mem" => Field::Memory,
+        other => {
+            return Err(format!(
+                "unknown field '{}' (expected cpu or memory)",
+                other
+            ));
+        }
+    };
+    let op = match op.as_str() {
+        ">" => Op::Gt,
+        ">=" => Op::Ge,
+        "<" => Op::Lt,
+        "<=" => Op::Le,
+        "==" | "=" => Op::Eq,
+        other => return Err(format!("unknown operator '{}'", other)),
+    };
+    let value = match field {
+        Field::Cpu => value
+            .trim_end_matches('%')
+            .parse()
+            .map_err(|_| format!("invalid CPU percentage '{}'", value))?,
+        Field::Memory => parse_size(value)?,
+    };
+    Ok(Comparison { field, op, value })
+}
+
+/// Rows matching `when` are drawn in `color`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightRule {
+    pub when: Condition,
+    pub color: Color,
+}
+
+impl HighlightRule {
+    pub fn new(when: &str, color: &str) -> Result<Self, String> {
+        Ok(Self {
+            when: when.parse()?,
+            color: color
+                .parse()
+                .map_err(|_| format!("unknown color '{}'", color))?,
+        })
+    }
+}
+
+/// A highlight rule as written in the config
+#[derive(Debug, Clone, Deserialize)]
+pub struct HighlightConfig {
+    pub when: String,
+    pub color: String,
+}
+
+impl TryFrom<HighlightConfig> for HighlightRule {
+    type Error = String;
+
+    fn try_from(config: HighlightConfig) -> Result<Self, Self::Error> {
+        Self::new(&config.when, &config.color)
+    }
+}
+
+/// Color of the first rule matching `process`
+pub fn highlight_color(rules: &[HighlightRule], process: &ProcessInfo) -> Option<Color> {
+    rules
+        .iter()
+        .find(|rule| rule.when.matches(process))
+        .map(|rule| rule.color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(cpu_percent: f32, memory_bytes: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid: 1,
+            name: "test".to_string(),
+            cpu_percent,
+            memory_bytes,
+            start_time: 0,
+            cwd: None,
+            zombie: false,
+            open_files: None,
+            open_files_limit: None,
+        }
+    }
+
+    #[test]
+    fn test_or_and_precedence() {
+        let condition: Condition = "cpu > 80 or memory>2GB and cpu >= 1%".parse().unwrap();
+        assert!(condition.matches(&process(90.0, 0)));
+        assert!(condition.matches(&process(1.0, 3_000_000_000)));
+        assert!(!condition.matches(&process(0.5, 3_000_000_000)));
+        assert!(!condition.matches(&process(50.0, 1_000_000_000)));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert_eq!(
+            "load > 2".parse::<Condition>(),
+            Err("unknown field 'load' (expected cpu or memory) in 'load > 2'".to_string())
+        );
+        assert!("cpu > ".parse::<Condition>().is_err());
+        assert!("cpu > 80 or".parse::<Condition>().is_err());
+        assert!(HighlightRule::new("cpu > 80", "reddish").is_err());
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = [
+            HighlightRule::new("cpu > 80", "red").unwrap(),
+            HighlightRule::new("cpu > 50", "yellow").unwrap(),
+        ];
+        assert_eq!(highlight_color(&rules, &process(90.0, 0)), Some(Color::Red));
+        assert_eq!(
+            highlight_color(&rules, &process(60.0, 0)),
+            Some(Color::Yellow)
+        );
+        assert_eq!(highlight_color(&rules, &process(10.0, 0)), None);
+    }
+}
@@ -0,0 +1,743 @@
+// devdash-widgets/src/latency.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Action, Collector, DataStatus, EventBus, EventResult, FetchError, HttpClient, Widget,
+    WidgetSettings, WidgetState, WorkerPool,
+    event::{Event, Subscription},
+    parse_duration, parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::common::{
+    charts::{Histogram, bar_line},
+    focus_color, format_duration, restore_table, save_table,
+};
+
+/// Upper bounds of the histogram buckets in milliseconds; slower responses
+/// go in one more bucket
+const BUCKET_BOUNDS: [u64; 8] = [10, 25, 50, 100, 250, 500, 1000, 2500];
+
+/// Responses at least this slow color the percentiles
+const SLOW: Duration = Duration::from_millis(1000);
+
+/// One request's outcome
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probe {
+    /// How long the response took; `None` if none arrived
+    pub latency: Option<Duration>,
+    pub status: Option<u16>,
+    /// Why the request failed: no response, or an error status
+    pub error: Option<String>,
+}
+
+/// Latency percentiles and distribution of one endpoint over the window,
+/// published on `system.latency`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointLatency {
+    pub name: String,
+    pub url: String,
+    /// Requests made within the window
+    pub requests: usize,
+    /// ...and how many of them failed
+    pub errors: usize,
+    pub p50: Option<Duration>,
+    pub p95: Option<Duration>,
+    pub p99: Option<Duration>,
+    pub max: Option<Duration>,
+    /// Responses per bucket of [`bucket_labels`]
+    pub buckets: Vec<u64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl EndpointLatency {
+    /// Summarize the probes of an endpoint, oldest first
+    pub fn new<'a>(name: &str, url: &str, probes: impl IntoIterator<Item = &'a Probe>) -> Self {
+        let mut requests = 0;
+        let mut errors = 0;
+        let mut last_error = None;
+        let mut latencies = Vec::new();
+        let mut buckets = vec![0; BUCKET_BOUNDS.len() + 1];
+        for probe in probes {
+            requests += 1;
+            if probe.error.is_some() {
+                errors += 1;
+                last_error = probe.error.clone();
+            }
+            if let Some(latency) = probe.latency {
+                latencies.push(latency);
+                buckets[bucket(latency)] += 1;
+            }
+        }
+        latencies.sort();
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            requests,
+            errors,
+            p50: percentile(&latencies, 50),
+            p95: percentile(&latencies, 95),
+            p99: percentile(&latencies, 99),
+            max: latencies.last().copied(),
+            buckets,
+            last_error,
+        }
+    }
+}
+
+/// The smallest latency at least `p` percent of `sorted` don't exceed
+fn percentile(sorted: &[Duration], p: usize) -> Option<Duration> {
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// Index of the histogram bucket `latency` falls in
+fn bucket(latency: Duration) -> usize {
+    let millis = latency.as_millis();
+    BUCKET_BOUNDS
+        .iter()
+        .position(|&bound| millis <= bound as u128)
+        .unwrap_or(BUCKET_BOUNDS.len())
+}
+
+/// Labels of the histogram buckets: each one's upper bound, then `>2.5s`
+pub fn bucket_labels() -> Vec<String> {
+    let bound = |millis: u64| format_latency(Duration::from_millis(millis));
+    BUCKET_BOUNDS
+        .iter()
+        .map(|&millis| bound(millis))
+        .chain([format!(
+            ">{}",
+            bound(BUCKET_BOUNDS[BUCKET_BOUNDS.len() - 1])
+        )])
+        .collect()
+}
+
+/// `45ms`, or `1.25s` from a second up
+pub fn format_latency(latency: Duration) -> String {
+    if latency < Duration::from_secs(1) {
+        format!("{}ms", latency.as_millis())
+    } else {
+        let text = format!("{:.2}", latency.as_secs_f64());
+        format!("{}s", text.trim_end_matches('0').trim_end_matches('.'))
+    }
+}
+
+/// An endpoint to time
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Endpoint {
+    pub name: String,
+    pub url: String,
+    /// HTTP method, `GET` by default
+    #[serde(default = "get")]
+    pub method: String,
+}
+
+fn get() -> String {
+    "GET".to_string()
+}
+
+/// Latency widget settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LatencyOptions {
+    pub endpoints: Vec<Endpoint>,
+    /// How far back the percentiles reach, e.g. `"5m"`
+    #[serde(deserialize_with = "duration")]
+    pub window: Duration,
+    /// How long a request may take before it counts as failed
+    #[serde(deserialize_with = "duration")]
+    pub timeout: Duration,
+}
+
+impl Default for LatencyOptions {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            window: Duration::from_secs(5 * 60),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    parse_duration(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+/// Check that every endpoint has an HTTP URL and a name of its own
+pub(crate) fn check_endpoints(endpoints: &[Endpoint]) -> Result<(), String> {
+    for (i, endpoint) in endpoints.iter().enumerate() {
+        if !(endpoint.url.starts_with("http://") || endpoint.url.starts_with("https://")) {
+            return Err(format!(
+                "endpoint '{}' needs an http:// or https:// url",
+                endpoint.name
+            ));
+        }
+        if endpoints[..i].iter().any(|e| e.name == endpoint.name) {
+            return Err(format!("endpoint '{}' is configured twice", endpoint.name));
+        }
+    }
+    Ok(())
+}
+
+impl LatencyOptions {
+    fn validate(self) -> Result<Self, String> {
+        check_endpoints(&self.endpoints)?;
+        if self.timeout.is_zero() || self.window.is_zero() {
+            return Err("window and timeout must be longer than zero".to_string());
+        }
+        Ok(self)
+    }
+}
+
+/// Send `endpoint` a request through `client` and time the response
+///
+/// `None` if the endpoint's host is rate limited, so no request was made.
+pub(crate) fn probe(client: &HttpClient, endpoint: &Endpoint, timeout: Duration) -> Option<Probe> {
+    match client.send(&endpoint.method, &endpoint.url, timeout) {
+        Ok(response) => Some(Probe {
+            latency: response.elapsed,
+            status: Some(response.status),
+            error: (response.status >= 400).then(|| format!("HTTP {}", response.status)),
+        }),
+        Err(FetchError::RateLimited { .. }) => None,
+        Err(e) => Some(Probe {
+            latency: None,
+            status: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Request every endpoint at once through the shared [`HttpClient`] and
+/// time the responses (runs on a worker thread); see [`probe`]
+pub(crate) fn probe_all(endpoints: &[Endpoint], timeout: Duration) -> Vec<Option<Probe>> {
+    let client = HttpClient::global();
+    std::thread::scope(|scope| {
+        let requests: Vec<_> = endpoints
+            .iter()
+            .map(|endpoint| scope.spawn(move || probe(client, endpoint, timeout)))
+            .collect();
+        requests
+            .into_iter()
+            .map(|request| request.join().unwrap_or(None))
+            .collect()
+    })
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Select next endpoint"),
+    Action::new("up", &["k", "up"], "Select previous endpoint"),
+];
+
+/// HTTP latency widget
+///
+/// Requests each configured endpoint every poll and shows the 50th, 95th
+/// and 99th percentile and slowest response time over a sliding window,
+/// with the distribution of response times of each; averages hide the
+/// spikes. The selected endpoint's distribution is drawn as a histogram
+/// below. Requests go through the shared [`HttpClient`], uncached; a round
+/// skips endpoints whose host is rate limited.
+///
+/// # Keyboard Shortcuts
+/// - `Up`/`Down` or `k`/`j` - Select an endpoint
+///
+/// # Events
+/// - `system.latency` - Every endpoint's [`EndpointLatency`], after each
+///   round of requests
+///
+/// When the event bus is not live, `system.latency` events are shown
+/// instead of making requests.
+pub struct LatencyWidget {
+    collector: Collector<Vec<Endpoint>, Vec<Option<Probe>>>,
+    endpoints: Vec<Endpoint>,
+    /// Each endpoint's probes within the window, oldest first
+    history: Vec<VecDeque<(Instant, Probe)>>,
+    stats: Vec<EndpointLatency>,
+    window: Duration,
+    labels: Vec<String>,
+    table_state: TableState,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl LatencyWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, LatencyOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<LatencyOptions>(settings)?.validate()?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: LatencyOptions,
+    ) -> Self {
+        let timeout = options.timeout;
+        let stats: Vec<EndpointLatency> = options
+            .endpoints
+            .iter()
+            .map(|e| EndpointLatency::new(&e.name, &e.url, []))
+            .collect();
+        Self {
+            collector: Collector::new(
+                options.endpoints.clone(),
+                move |endpoints: &mut Vec<Endpoint>| probe_all(endpoints, timeout),
+            ),
+            history: vec![VecDeque::new(); options.endpoints.len()],
+            endpoints: options.endpoints,
+            table_state: TableState::default().with_selected((!stats.is_empty()).then_some(0)),
+            stats,
+            window: options.window,
+            labels: bucket_labels(),
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_stats(&mut self, stats: Vec<EndpointLatency>) {
+        let count = stats.len();
+        self.table_state.select(match self.table_state.selected() {
+            _ if count == 0 => None,
+            Some(selected) => Some(selected.min(count - 1)),
+            None => Some(0),
+        });
+        self.stats = stats;
+    }
+
+    /// Add a round of probes, drop those that left the window, and publish
+    /// the new percentiles
+    fn record(&mut self, probes: Vec<Option<Probe>>, now: Instant) {
+        for (history, probe) in self.history.iter_mut().zip(probes) {
+            if let Some(probe) = probe {
+                history.push_back((now, probe));
+            }
+        }
+        for history in &mut self.history {
+            while history
+                .front()
+                .is_some_and(|(at, _)| now.duration_since(*at) > self.window)
+            {
+                history.pop_front();
+            }
+        }
+        let stats: Vec<EndpointLatency> = self
+            .endpoints
+            .iter()
+            .zip(&self.history)
+            .map(|(endpoint, history)| {
+                EndpointLatency::new(
+                    &endpoint.name,
+                    &endpoint.url,
+                    history.iter().map(|(_, probe)| probe),
+                )
+            })
+            .collect();
+        self.event_bus
+            .publish(Event::new("system.latency", stats.clone()));
+        self.apply_stats(stats);
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.stats.len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(Some(if down {
+            (selected + 1).min(count - 1)
+        } else {
+            selected.saturating_sub(1)
+        }));
+    }
+
+    fn render_table(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let name_width = self
+            .stats
+            .iter()
+            .map(|s| s.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(4, 20) as u16;
+        let latency = |latency: Option<Duration>| {
+            let style = match latency {
+                Some(latency) if latency >= SLOW => Style::default().fg(theme().warning),
+                _ => Style::default(),
+            };
+            Cell::from(latency.map_or("-".to_string(), format_latency)).style(style)
+        };
+        let rows = self.stats.iter().map(|stats| {
+            let errors = Cell::from(stats.errors.to_string()).style(if stats.errors > 0 {
+                Style::default().fg(theme().critical)
+            } else {
+                Style::default()
+            });
+            Row::new(vec![
+                Cell::from(stats.name.clone()),
+                latency(stats.p50),
+                latency(stats.p95),
+                latency(stats.p99),
+                latency(stats.max),
+                Cell::from(stats.requests.to_string()),
+                errors,
+                Cell::from(bar_line(&stats.buckets)).style(Style::default().fg(Color::Cyan)),
+            ])
+        });
+        let header = Row::new(
+            [
+                "Name", "p50", "p95", "p99", "Max", "Reqs", "Errors", "Spread",
+            ]
+            .into_iter()
+            .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(name_width),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(5),
+                Constraint::Length(6),
+                Constraint::Length(BUCKET_BOUNDS.len() as u16 + 1),
+            ],
+        )
+        .header(header);
+        if focused {
+            table = table.row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        ratatui::widgets::StatefulWidget::render(table, area, buf, &mut self.table_state);
+    }
+}
+
+impl Widget for LatencyWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected percentiles instead of making requests
+            let (sub, rx) = self.event_bus.subscribe("system.latency");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+        if !self.endpoints.is_empty() {
+            self.collector.request(WorkerPool::global());
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<Vec<EndpointLatency>>())
+                .last();
+            if let Some(stats) = latest {
+                self.apply_stats(Vec::clone(&stats));
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(probes) = self.collector.try_take() {
+            self.record(probes, Instant::now());
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval && !self.endpoints.is_empty() {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        let devdash_core::Event::Action(action) = event else {
+            return EventResult::Ignored;
+        };
+        match action.as_str() {
+            "down" => self.move_selection(true),
+            "up" => self.move_selection(false),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let title = match self.injected {
+            Some(_) => " HTTP Latency ".to_string(),
+            None => format!(" HTTP Latency [{}] ", format_duration(self.window)),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.stats.is_empty() {
+            Paragraph::new("No endpoints configured")
+                .style(Style::default().fg(Color::DarkGray))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        }
+
+        // The table takes what it needs, up to half the space, then the
+        // selected endpoint's histogram
+        let table_height = (self.stats.len() as u16 + 1).min((inner.height / 2).max(2));
+        let [table_area, chart_area] =
+            Layout::vertical([Constraint::Length(table_height), Constraint::Min(0)]).areas(inner);
+        self.render_table(table_area, buf, focused);
+
+        let Some(stats) = self.table_state.selected().and_then(|i| self.stats.get(i)) else {
+            return;
+        };
+        let mut title = format!(" {} ", stats.name);
+        if let Some(error) = &stats.last_error {
+            title = format!(" {}: last error {} ", stats.name, error);
+        }
+        let chart_block = Block::default()
+            .borders(Borders::TOP)
+            .title(title)
+            .border_style(Style::default().fg(Color::DarkGray));
+        let chart_inner = chart_block.inner(chart_area);
+        chart_block.render(chart_area, buf);
+        Histogram::new(&stats.buckets, &self.labels).render(chart_inner, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        if self.stats.is_empty() {
+            return Some("HTTP latency: no endpoints configured".to_string());
+        }
+        let endpoints: Vec<String> = self
+            .stats
+            .iter()
+            .map(|stats| {
+                let mut text = match (stats.p50, stats.p95, stats.p99) {
+                    (Some(p50), Some(p95), Some(p99)) => format!(
+                        "{} p50 {}, p95 {}, p99 {}",
+                        stats.name,
+                        format_latency(p50),
+                        format_latency(p95),
+                        format_latency(p99)
+                    ),
+                    _ => format!("{} no responses", stats.name),
+                };
+                if stats.errors > 0 {
+                    text.push_str(&format!(" ({} of {} failed)", stats.errors, stats.requests));
+                }
+                text
+            })
+            .collect();
+        Some(format!("HTTP latency: {}", endpoints.join("; ")))
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devdash_core::fetch::{RawResponse, TransportError};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn response(millis: u64) -> Probe {
+        Probe {
+            latency: Some(ms(millis)),
+            status: Some(200),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let mut probes: Vec<Probe> = (1..=100).map(response).collect();
+        probes.push(Probe {
+            latency: None,
+            status: None,
+            error: Some("(28) Operation timed out".to_string()),
+        });
+        let stats = EndpointLatency::new("api", "http://localhost", &probes);
+        assert_eq!(stats.requests, 101);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.p50, Some(ms(50)));
+        assert_eq!(stats.p95, Some(ms(95)));
+        assert_eq!(stats.p99, Some(ms(99)));
+        assert_eq!(stats.max, Some(ms(100)));
+        assert_eq!(stats.buckets, [10, 15, 25, 50, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            stats.last_error.as_deref(),
+            Some("(28) Operation timed out")
+        );
+
+        // One slow response in twenty shows in p95, not p50
+        let mut spiky: Vec<Probe> = (0..19).map(|_| response(20)).collect();
+        spiky.push(response(3000));
+        let stats = EndpointLatency::new("api", "http://localhost", &spiky);
+        assert_eq!(stats.p50, Some(ms(20)));
+        assert_eq!(stats.p99, Some(ms(3000)));
+        assert_eq!(stats.buckets[8], 1);
+
+        let empty = EndpointLatency::new("api", "http://localhost", []);
+        assert_eq!((empty.p50, empty.max), (None, None));
+    }
+
+    #[test]
+    fn test_probe() {
+        let client = HttpClient::with_transport(Box::new(|request| match request.url.as_str() {
+            "http://localhost/down" => Ok(RawResponse {
+                status: 503,
+                elapsed: Some(Duration::from_millis(2)),
+                ..Default::default()
+            }),
+            "http://localhost/limited" => Ok(RawResponse {
+                status: 429,
+                ..Default::default()
+            }),
+            "http://localhost/" => Ok(RawResponse {
+                status: 200,
+                elapsed: Some(Duration::from_micros(45123)),
+                ..Default::default()
+            }),
+            _ => Err(TransportError::Unreachable(
+                "Failed to connect to localhost port 1".to_string(),
+            )),
+        }));
+        let endpoint = |url: &str| Endpoint {
+            name: "api".to_string(),
+            url: url.to_string(),
+            method: "GET".to_string(),
+        };
+        let timeout = Duration::from_secs(5);
+
+        assert_eq!(
+            probe(&client, &endpoint("http://localhost/"), timeout),
+            Some(Probe {
+                latency: Some(Duration::from_micros(45123)),
+                status: Some(200),
+                error: None,
+            })
+        );
+        let failed = probe(&client, &endpoint("http://localhost/down"), timeout).unwrap();
+        assert_eq!(failed.error.as_deref(), Some("HTTP 503"));
+        assert!(failed.latency.is_some());
+
+        // Held back rather than failed
+        assert_eq!(
+            probe(&client, &endpoint("http://localhost/limited"), timeout),
+            None
+        );
+
+        let refused = probe(&client, &endpoint("http://localhost:1/"), timeout).unwrap();
+        assert_eq!(refused.latency, None);
+        assert_eq!(refused.error.as_deref(), Some("localhost:1 is unreachable"));
+    }
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(
+            bucket_labels(),
+            [
+                "10ms", "25ms", "50ms", "100ms", "250ms", "500ms", "1s", "2.5s", ">2.5s"
+            ]
+        );
+        assert_eq!(format_latency(Duration::from_millis(1250)), "1.25s");
+    }
+
+    #[test]
+    fn test_options() {
+        let parse = |text: &str| {
+            let settings: WidgetSettings = toml::from_str(text).unwrap();
+            parse_settings::<LatencyOptions>(&settings)?.validate()
+        };
+        let options = parse(
+            r#"
+            window = "1m"
+            endpoints = [{ name = "api", url = "https://api.example.com/health", method = "HEAD" }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(options.window, Duration::from_secs(60));
+        assert_eq!(options.timeout, Duration::from_secs(5));
+        assert_eq!(options.endpoints[0].method, "HEAD");
+
+        assert!(parse(r#"endpoints = [{ name = "a", url = "localhost:80" }]"#).is_err());
+        assert!(parse(r#"window = "0s""#).is_err());
+        assert!(parse(r#"endpoints = [{ name = "a", url = "http://a", verb = "GET" }]"#).is_err());
+    }
+}
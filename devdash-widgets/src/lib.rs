@@ -1,15 +1,107 @@
+pub mod backup;
+pub mod cgroups;
+pub mod clock;
 pub mod common;
+pub mod cpu;
+pub mod cron;
+pub mod diagnostics;
 pub mod disk;
 pub mod error;
+pub mod firewall;
 pub mod git;
+pub mod health;
+pub mod highlight;
+pub mod latency;
 pub mod memory;
+pub mod monitor;
 pub mod network;
+pub mod platform;
+pub mod ports;
 pub mod process;
+pub mod projection;
+pub mod quota;
+pub mod schedule;
+pub mod sensors;
+pub mod sessions;
+pub mod supervisor;
+pub mod tasks;
+pub mod tunnels;
+pub mod uptime;
 
+pub use backup::{BackupOptions, BackupStatus, BackupTarget, BackupWidget, Freshness};
+pub use cgroups::{CgroupUsage, CgroupWidget};
+pub use clock::{ClockOptions, ClockWidget, ZoneConfig};
 pub use common::*;
-pub use disk::{DiskIOMetrics, DiskInfo, DiskUsageMetrics, DiskWidget, ViewMode};
+pub use cpu::{CpuMetrics, CpuWidget};
+pub use diagnostics::{
+    Diagnostic, DiagnosticLevel, DiagnosticReport, DiagnosticsOptions, DiagnosticsWidget,
+};
+pub use disk::{
+    DiskIOMetrics, DiskInfo, DiskSample, DiskUsageMetrics, DiskWidget, MountFilter, ViewMode,
+};
 pub use error::ErrorWidget;
-pub use git::{CommitInfo, GitStatus, GitWidget};
-pub use memory::{MemoryMetrics, MemoryWidget};
-pub use network::NetworkWidget;
-pub use process::{ProcessInfo, ProcessWidget, SortBy};
+pub use firewall::{
+    BlockedAttempt, FirewallOptions, FirewallSummary, FirewallWidget, PortActivity, SourceActivity,
+};
+pub use git::{CommitInfo, CommitOpener, FileChange, GitOptions, GitStatus, GitWidget};
+pub use health::{HealthIssue, HealthKind, HealthOptions, HealthWidget};
+pub use highlight::HighlightRule;
+pub use latency::{Endpoint, EndpointLatency, LatencyOptions, LatencyWidget};
+pub use memory::{MemoryMetrics, MemoryWidget, PagingRates};
+pub use monitor::{EndpointHealth, HttpMonitorWidget, MonitorOptions};
+pub use network::{InterfaceOptions, LinkChange, NetworkIOMetrics, NetworkWidget};
+pub use platform::sockets::Protocol;
+pub use ports::{ListeningPort, PortList, PortOptions, PortsWidget};
+pub use process::{
+    PROCESS_SELECT_TOPIC, ProcessGroup, ProcessHistory, ProcessInfo, ProcessWidget, SortBy,
+    group_processes,
+};
+pub use projection::{DiskProjection, DiskTrends};
+pub use quota::{Quota, QuotaUsage, TransferLedger};
+pub use schedule::{
+    JobResult, JobSchedule, JobSource, ScheduleOptions, ScheduleWidget, ScheduledJob,
+};
+pub use sensors::{SensorOptions, SensorsWidget, Temperature};
+pub use sessions::{Session, SessionActivity, SessionWidget};
+pub use supervisor::{
+    DevCommand, DevCrash, DevProcess, DevState, SupervisorOptions, SupervisorWidget,
+};
+pub use tasks::{Task, TaskList, TaskOptions, TaskRun, TaskSource, TaskWidget};
+pub use tunnels::{TunnelConfig, TunnelOptions, TunnelState, TunnelStatus, TunnelWidget};
+pub use uptime::{UptimeInfo, UptimeWidget};
+
+/// Give the built-in widgets' event payloads a JSON form, for the event
+/// log, the HTTP API and alert rules
+pub fn register_payloads(registry: &devdash_core::PayloadRegistry) {
+    registry.register::<GitStatus>("GitStatus");
+    registry.register::<CpuMetrics>("CpuMetrics");
+    registry.register::<MemoryMetrics>("MemoryMetrics");
+    registry.register::<DiskIOMetrics>("DiskIOMetrics");
+    registry.register::<DiskUsageMetrics>("DiskUsageMetrics");
+    registry.register::<DiskProjection>("DiskProjection");
+    registry.register::<ProcessInfo>("ProcessInfo");
+    registry.register::<QuotaUsage>("QuotaUsage");
+    registry.register::<NetworkIOMetrics>("NetworkIOMetrics");
+    registry.register::<LinkChange>("LinkChange");
+    registry.register::<Session>("Session");
+    registry.register::<SessionActivity>("SessionActivity");
+    registry.register::<PortList>("PortList");
+    registry.register::<FirewallSummary>("FirewallSummary");
+    registry.register::<SourceActivity>("SourceActivity");
+    registry.register::<JobSchedule>("JobSchedule");
+    registry.register::<ScheduledJob>("ScheduledJob");
+    registry.register::<BackupStatus>("BackupStatus");
+    registry.register::<Temperature>("Temperature");
+    registry.register::<Vec<HealthIssue>>("HealthIssues");
+    registry.register::<HealthIssue>("HealthIssue");
+    registry.register::<DiagnosticReport>("DiagnosticReport");
+    registry.register::<Vec<DevProcess>>("DevProcesses");
+    registry.register::<DevCrash>("DevCrash");
+    registry.register::<Vec<EndpointLatency>>("EndpointLatencies");
+    registry.register::<Vec<EndpointHealth>>("EndpointHealths");
+    registry.register::<EndpointHealth>("EndpointHealth");
+    registry.register::<TaskList>("TaskList");
+    registry.register::<TaskRun>("TaskRun");
+    registry.register::<Vec<TunnelStatus>>("TunnelStatuses");
+    registry.register::<std::time::SystemTime>("SystemTime");
+}
@@ -1,25 +1,60 @@
 // devdash-widgets/src/memory.rs
+use crossbeam::channel::Receiver;
 use devdash_core::{
-    EventBus, EventResult, Widget,
+    Action, DataStatus, EventBus, EventResult, Widget,
     event::{Event, Subscription},
+    theme,
+    widget::DEFAULT_UPDATE_INTERVAL,
 };
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
     prelude::Widget as RatatuiWidget,
     style::Style,
-    widgets::{Block, Borders, Gauge},
+    widgets::{Block, Borders},
 };
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use sysinfo::System;
 
-use crate::common::{Unit, focus_color, format_bytes_unit, usage_color};
+use crate::common::{
+    DualSparkline, LabelledGauge, Unit, focus_color, format_bytes_unit, series_colors,
+};
+use crate::platform::{
+    self,
+    cgroup::{self, Cgroup},
+};
+
+/// Paging rate samples kept for the sparkline
+const PAGING_HISTORY_LEN: usize = 120;
+
+/// Pages swapped per second, in and out combined, that count as heavy
+/// paging
+const THRASHING_PAGES_PER_SEC: f32 = 256.0;
+
+/// Consecutive polls of heavy paging before `system.memory.thrashing` is
+/// published
+const THRASHING_POLLS: u32 = 5;
+
+/// Swap activity, in pages per second
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PagingRates {
+    pub pages_in: f32,
+    pub pages_out: f32,
+}
+
+impl PagingRates {
+    /// Whether this much paging counts as heavy
+    pub fn is_heavy(&self) -> bool {
+        self.pages_in + self.pages_out >= THRASHING_PAGES_PER_SEC
+    }
+}
 
 /// Memory usage information published to the event bus
 ///
 /// Contains current memory and swap usage statistics that can be consumed
 /// by other widgets or external components for system monitoring.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryMetrics {
     /// Currently used memory in bytes
     pub used: u64,
@@ -33,23 +68,47 @@ pub struct MemoryMetrics {
     pub usage_percent: f32,
     /// Swap usage percentage (0.0 - 100.0)
     pub swap_percent: f32,
+    /// Pages swapped in and out since the previous poll, where the
+    /// platform counts them
+    #[serde(default)]
+    pub paging: Option<PagingRates>,
 }
 
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("unit", &["u"], "Cycle display unit"),
+    Action::new("swap", &["s"], "Toggle swap"),
+    Action::new("refresh", &["r"], "Refresh now"),
+];
+
 /// Memory monitoring widget with visual bars and interactive controls
 ///
 /// Displays system memory and swap usage with color-coded bars and percentage indicators.
 /// Supports interactive controls for unit switching and swap visibility toggling.
 ///
 /// # Keyboard Shortcuts
-/// - `u` - Cycle through display units (Auto → Bytes → KB → MB → GB)
+/// - `u` - Cycle through display units (Auto → Bytes → KB → MB → GB), in the
+///   current [`UnitSystem`](crate::common::UnitSystem)
 /// - `s` - Toggle swap visibility on/off
 /// - `r` - Force immediate memory refresh
 ///
 /// # Event Publishing
 /// - Publishes `system.memory` events on each poll with current memory metrics
 /// - Publishes `system.memory.pressure` events when memory usage exceeds 80%
+/// - Publishes `system.memory.thrashing` events on each poll once heavy
+///   paging has lasted five polls
+///
+/// On Linux, page-in and page-out rates from `/proc/vmstat` are plotted
+/// below the gauges when there is room.
+///
+/// Inside a container with a memory limit, usage is shown against the
+/// cgroup limit instead of host memory, and the title says so.
+///
+/// When the event bus is not live, the widget displays `system.memory`
+/// events published by others instead of polling the system.
 pub struct MemoryWidget {
     system: System,
+    container: Option<Cgroup>,
 
     // Memory state
     used_memory: u64,
@@ -57,6 +116,15 @@ pub struct MemoryWidget {
     swap_used: u64,
     swap_total: u64,
 
+    // Paging
+    /// Swap page counters at the previous poll
+    last_paging: Option<(Instant, (u64, u64))>,
+    paging: Option<PagingRates>,
+    pages_in_history: Vec<u64>,
+    pages_out_history: Vec<u64>,
+    /// Consecutive polls with heavy paging
+    heavy_paging_polls: u32,
+
     // UI state
     show_swap: bool,
     display_unit: Unit,
@@ -68,6 +136,9 @@ pub struct MemoryWidget {
     // Event bus
     event_bus: EventBus,
     _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
 }
 
 impl MemoryWidget {
@@ -79,6 +150,9 @@ impl MemoryWidget {
     ///
     /// # Example
     /// ```rust
+    /// # use devdash_core::EventBus;
+    /// # use devdash_widgets::MemoryWidget;
+    /// # use std::time::Duration;
     /// let event_bus = EventBus::new();
     /// let memory_widget = MemoryWidget::new(
     ///     event_bus,
@@ -91,16 +165,24 @@ impl MemoryWidget {
 
         Self {
             system,
+            container: None,
             used_memory: 0,
             total_memory: 0,
             swap_used: 0,
             swap_total: 0,
+            last_paging: None,
+            paging: None,
+            pages_in_history: Vec::new(),
+            pages_out_history: Vec::new(),
+            heavy_paging_polls: 0,
             show_swap: true,
             display_unit: Unit::Auto,
             poll_interval,
             time_since_poll: Duration::ZERO,
             event_bus,
             _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
         }
     }
 
@@ -113,6 +195,15 @@ impl MemoryWidget {
         self.swap_used = self.system.used_swap();
         self.swap_total = self.system.total_swap();
 
+        if let Some(container) = &self.container
+            && let Some(limit) = container.memory_limit()
+        {
+            self.total_memory = limit;
+            self.used_memory = container.memory_usage().unwrap_or(self.used_memory);
+        }
+        let paging = self.poll_paging();
+        self.record_paging(paging);
+
         // Publish memory metrics event
         let metrics = MemoryMetrics {
             used: self.used_memory,
@@ -129,11 +220,17 @@ impl MemoryWidget {
             } else {
                 0.0
             },
+            paging,
         };
 
         self.event_bus
             .publish(Event::new("system.memory", metrics.clone()));
 
+        if self.is_thrashing() {
+            self.event_bus
+                .publish(Event::new("system.memory.thrashing", metrics.clone()));
+        }
+
         // Publish pressure event if memory usage is high
         if metrics.usage_percent >= 80.0 {
             self.event_bus
@@ -141,6 +238,60 @@ impl MemoryWidget {
         }
     }
 
+    /// Paging rates since the previous poll; `None` on the first poll and
+    /// where swap activity isn't counted
+    fn poll_paging(&mut self) -> Option<PagingRates> {
+        let counts = platform::swap_page_counts()?;
+        let now = Instant::now();
+        let previous = self.last_paging.replace((now, counts));
+        let (at, (pages_in, pages_out)) = previous?;
+        let seconds = now.duration_since(at).as_secs_f32();
+        if seconds <= 0.0 {
+            return None;
+        }
+        Some(PagingRates {
+            pages_in: counts.0.saturating_sub(pages_in) as f32 / seconds,
+            pages_out: counts.1.saturating_sub(pages_out) as f32 / seconds,
+        })
+    }
+
+    /// Add a sample to the paging sparklines and track how long heavy
+    /// paging has lasted
+    fn record_paging(&mut self, paging: Option<PagingRates>) {
+        self.paging = paging;
+        let Some(rates) = paging else {
+            return;
+        };
+        for (history, rate) in [
+            (&mut self.pages_in_history, rates.pages_in),
+            (&mut self.pages_out_history, rates.pages_out),
+        ] {
+            history.push(rate.round() as u64);
+            if history.len() > PAGING_HISTORY_LEN {
+                history.remove(0);
+            }
+        }
+        self.heavy_paging_polls = if rates.is_heavy() {
+            self.heavy_paging_polls + 1
+        } else {
+            0
+        };
+    }
+
+    /// Whether heavy paging has gone on long enough to call it thrashing
+    fn is_thrashing(&self) -> bool {
+        self.heavy_paging_polls >= THRASHING_POLLS
+    }
+
+    /// Display metrics received from the event bus
+    fn apply_metrics(&mut self, metrics: &MemoryMetrics) {
+        self.used_memory = metrics.used;
+        self.total_memory = metrics.total;
+        self.swap_used = metrics.swap_used;
+        self.swap_total = metrics.swap_total;
+        self.record_paging(metrics.paging);
+    }
+
     /// Get memory usage percentage
     fn get_usage_percent(&self) -> f32 {
         if self.total_memory > 0 {
@@ -167,6 +318,19 @@ impl MemoryWidget {
 
 impl Widget for MemoryWidget {
     fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected metrics instead of polling
+            let (sub, rx) = self.event_bus.subscribe("system.memory");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        // Show usage against the container's limit, if there is one
+        self.container =
+            Cgroup::current().filter(|c| cgroup::in_container() && c.memory_limit().is_some());
+
         self.poll_memory(); // Initial poll
 
         // Subscribe to memory refresh events (for future use)
@@ -175,6 +339,15 @@ impl Widget for MemoryWidget {
     }
 
     fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx.try_iter().filter_map(|e| e.payload.downcast()).last();
+            if let Some(metrics) = latest {
+                self.apply_metrics(&metrics);
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
         self.time_since_poll += delta;
 
         if self.time_since_poll >= self.poll_interval {
@@ -183,24 +356,34 @@ impl Widget for MemoryWidget {
         }
     }
 
-    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
-        use crossterm::event::KeyCode;
+    fn data_status(&self) -> Option<DataStatus> {
+        self.injected.as_ref().map(|_| DataStatus {
+            refreshing: false,
+            last_data: self.last_injected,
+            interval: self.poll_interval,
+            retry_at: None,
+        })
+    }
 
-        if let devdash_core::Event::Key(key) = event {
-            match key.code {
-                KeyCode::Char('u') => {
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        if let devdash_core::Event::Action(action) = event {
+            match action.as_str() {
+                "unit" => {
                     // Cycle through display units
                     self.display_unit = self.display_unit.next();
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('s') => {
+                "swap" => {
                     // Toggle swap visibility
                     self.show_swap = !self.show_swap;
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('r') => {
+                "refresh" => {
                     // Force refresh
-                    self.time_since_poll = self.poll_interval;
+                    if self.injected.is_none() {
+                        self.poll_memory();
+                        self.time_since_poll = Duration::ZERO;
+                    }
                     return EventResult::Consumed;
                 }
                 _ => {}
@@ -223,7 +406,12 @@ impl Widget for MemoryWidget {
 
         // Create title with memory info
         let title = format!(
-            " Memory [{:.1}% - {}/{}] ",
+            " Memory{} [{:.1}% - {}/{}] ",
+            if self.container.is_some() {
+                " (container)"
+            } else {
+                ""
+            },
             usage_percent,
             format_bytes_unit(self.used_memory, self.display_unit),
             format_bytes_unit(self.total_memory, self.display_unit)
@@ -265,23 +453,42 @@ impl Widget for MemoryWidget {
         };
 
         // Render memory gauge
-        let memory_color = usage_color(usage_percent as f64);
-        let memory_gauge = Gauge::default()
-            .block(Block::default().title("RAM"))
-            .gauge_style(Style::default().fg(memory_color))
-            .ratio(usage_percent as f64 / 100.0);
-
-        RatatuiWidget::render(memory_gauge, chunks[0], buf);
+        RatatuiWidget::render(
+            LabelledGauge::new("RAM", usage_percent as f64),
+            chunks[0],
+            buf,
+        );
 
         // Render swap gauge if enabled and available
         if self.show_swap && self.has_swap() && chunks.len() > 1 {
-            let swap_color = usage_color(swap_percent as f64);
-            let swap_gauge = Gauge::default()
-                .block(Block::default().title("SWAP"))
-                .gauge_style(Style::default().fg(swap_color))
-                .ratio(swap_percent as f64 / 100.0);
+            RatatuiWidget::render(
+                LabelledGauge::new("SWAP", swap_percent as f64),
+                chunks[1],
+                buf,
+            );
+        }
 
-            RatatuiWidget::render(swap_gauge, chunks[1], buf);
+        // Plot paging in the remaining space, if it fits two sparklines
+        let rest = chunks[chunks.len() - 1];
+        if let Some(rates) = self.paging
+            && rest.height >= 4
+        {
+            let (in_color, out_color) = if self.is_thrashing() {
+                let critical = theme().critical;
+                (critical, critical)
+            } else {
+                series_colors()
+            };
+            let pages_in = format!("Paging in {:.0}/s", rates.pages_in);
+            let pages_out = format!("Paging out {:.0}/s", rates.pages_out);
+            RatatuiWidget::render(
+                DualSparkline::new(
+                    (&pages_in, &self.pages_in_history, in_color),
+                    (&pages_out, &self.pages_out_history, out_color),
+                ),
+                rest,
+                buf,
+            );
         }
 
         // Render the main block
@@ -291,4 +498,41 @@ impl Widget for MemoryWidget {
     fn needs_update(&self) -> bool {
         true // Always poll for updates
     }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mut summary = format!(
+            "Memory {:.1}% used, {} of {}",
+            self.get_usage_percent(),
+            format_bytes_unit(self.used_memory, self.display_unit),
+            format_bytes_unit(self.total_memory, self.display_unit)
+        );
+        if self.swap_total > 0 {
+            summary.push_str(&format!("; swap {:.1}% used", self.get_swap_percent()));
+        }
+        if let Some(rates) = self.paging {
+            summary.push_str(&format!(
+                "; paging {:.0} in, {:.0} out pages/s{}",
+                rates.pages_in,
+                rates.pages_out,
+                if self.is_thrashing() {
+                    ", thrashing"
+                } else {
+                    ""
+                }
+            ));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        // Injected events are drained at the default rate
+        match self.injected {
+            Some(_) => DEFAULT_UPDATE_INTERVAL,
+            None => self.poll_interval,
+        }
+    }
 }
@@ -0,0 +1,600 @@
+// devdash-widgets/src/monitor.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Action, Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WidgetState,
+    WorkerPool,
+    event::{Event, Subscription},
+    parse_duration, parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{focus_color, format_duration, parse_age, restore_table, save_table},
+    latency::{Endpoint, Probe, check_endpoints, format_latency, probe_all},
+};
+
+/// One endpoint's health, as shown and published on `system.http`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointHealth {
+    pub name: String,
+    pub url: String,
+    /// Whether the endpoint answers; `None` until it was first checked
+    pub up: Option<bool>,
+    /// Status code of the last response
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// How long the last response took
+    #[serde(default)]
+    pub latency: Option<Duration>,
+    /// Checks made within the window
+    pub checks: usize,
+    /// ...and how many of them failed
+    pub failures: usize,
+    /// Why the last check failed, if it did
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl EndpointHealth {
+    fn unchecked(endpoint: &Endpoint) -> Self {
+        Self {
+            name: endpoint.name.clone(),
+            url: endpoint.url.clone(),
+            up: None,
+            status: None,
+            latency: None,
+            checks: 0,
+            failures: 0,
+            error: None,
+        }
+    }
+
+    /// Share of the checks within the window that succeeded, in percent
+    pub fn uptime(&self) -> Option<f32> {
+        (self.checks > 0).then(|| (self.checks - self.failures) as f32 / self.checks as f32 * 100.0)
+    }
+
+    /// `api is down (HTTP 503)`, e.g. for an alert
+    pub fn describe(&self) -> String {
+        let state = match self.up {
+            Some(true) => "up",
+            Some(false) => "down",
+            None => "not checked yet",
+        };
+        match &self.error {
+            Some(error) => format!("{} is {} ({})", self.name, state, error),
+            None => format!("{} is {}", self.name, state),
+        }
+    }
+}
+
+/// HTTP monitor widget settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct MonitorOptions {
+    pub endpoints: Vec<Endpoint>,
+    /// How far back the uptime reaches, e.g. `"7d"`
+    #[serde(deserialize_with = "age")]
+    pub window: Duration,
+    /// How long a request may take before it counts as failed
+    #[serde(deserialize_with = "duration")]
+    pub timeout: Duration,
+    /// Failed checks in a row before an endpoint counts as down
+    pub failures: usize,
+}
+
+impl Default for MonitorOptions {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            window: Duration::from_secs(24 * 60 * 60),
+            timeout: Duration::from_secs(5),
+            failures: 2,
+        }
+    }
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    parse_duration(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+fn age<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    parse_age(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+impl MonitorOptions {
+    fn validate(self) -> Result<Self, String> {
+        check_endpoints(&self.endpoints)?;
+        if self.timeout.is_zero() || self.window.is_zero() {
+            return Err("window and timeout must be longer than zero".to_string());
+        }
+        if self.failures == 0 {
+            return Err("failures must be at least 1".to_string());
+        }
+        Ok(self)
+    }
+}
+
+/// The checks of one endpoint
+#[derive(Debug, Clone, Default)]
+struct CheckHistory {
+    /// When each check within the window was made and whether it
+    /// succeeded, oldest first
+    checks: VecDeque<(Instant, bool)>,
+    /// Failed checks since the last success
+    failing: usize,
+    last: Option<Probe>,
+}
+
+impl CheckHistory {
+    fn record(&mut self, probe: Probe, now: Instant, window: Duration) {
+        let ok = probe.error.is_none();
+        self.failing = if ok { 0 } else { self.failing + 1 };
+        self.checks.push_back((now, ok));
+        while self
+            .checks
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > window)
+        {
+            self.checks.pop_front();
+        }
+        self.last = Some(probe);
+    }
+
+    fn health(&self, endpoint: &Endpoint, failures: usize) -> EndpointHealth {
+        let Some(last) = &self.last else {
+            return EndpointHealth::unchecked(endpoint);
+        };
+        EndpointHealth {
+            up: Some(self.failing < failures),
+            status: last.status,
+            latency: last.latency,
+            checks: self.checks.len(),
+            failures: self.checks.iter().filter(|(_, ok)| !ok).count(),
+            error: last.error.clone(),
+            ..EndpointHealth::unchecked(endpoint)
+        }
+    }
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Select next endpoint"),
+    Action::new("up", &["k", "up"], "Select previous endpoint"),
+];
+
+/// HTTP monitor widget
+///
+/// Requests each configured endpoint every poll and shows whether it is
+/// up, the status code and response time of the last request, and the
+/// share of requests over a sliding window that succeeded. An endpoint is
+/// down once a number of requests in a row got no response or an error
+/// status. Requests go through the shared
+/// [`HttpClient`](devdash_core::HttpClient), uncached; a round skips
+/// endpoints whose host is rate limited, rather than count them as failed.
+///
+/// # Keyboard Shortcuts
+/// - `Up`/`Down` or `k`/`j` - Select an endpoint
+///
+/// # Events
+/// - `system.http` - Every endpoint's [`EndpointHealth`], after each round
+///   of requests
+/// - `system.http.down` - The [`EndpointHealth`] of each endpoint that is
+///   down, after each round of requests
+///
+/// When the event bus is not live, `system.http` events are shown instead
+/// of making requests.
+pub struct HttpMonitorWidget {
+    collector: Collector<Vec<Endpoint>, Vec<Option<Probe>>>,
+    endpoints: Vec<Endpoint>,
+    history: Vec<CheckHistory>,
+    health: Vec<EndpointHealth>,
+    window: Duration,
+    failures: usize,
+    table_state: TableState,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl HttpMonitorWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, MonitorOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<MonitorOptions>(settings)?.validate()?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: MonitorOptions,
+    ) -> Self {
+        let timeout = options.timeout;
+        let health: Vec<EndpointHealth> = options
+            .endpoints
+            .iter()
+            .map(EndpointHealth::unchecked)
+            .collect();
+        Self {
+            collector: Collector::new(
+                options.endpoints.clone(),
+                move |endpoints: &mut Vec<Endpoint>| probe_all(endpoints, timeout),
+            ),
+            history: vec![CheckHistory::default(); options.endpoints.len()],
+            endpoints: options.endpoints,
+            table_state: TableState::default().with_selected((!health.is_empty()).then_some(0)),
+            health,
+            window: options.window,
+            failures: options.failures,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_health(&mut self, health: Vec<EndpointHealth>) {
+        let count = health.len();
+        self.table_state.select(match self.table_state.selected() {
+            _ if count == 0 => None,
+            Some(selected) => Some(selected.min(count - 1)),
+            None => Some(0),
+        });
+        self.health = health;
+    }
+
+    /// Add a round of checks and publish the endpoints' health, and each
+    /// one that is down
+    fn record(&mut self, probes: Vec<Option<Probe>>, now: Instant) {
+        for (history, probe) in self.history.iter_mut().zip(probes) {
+            if let Some(probe) = probe {
+                history.record(probe, now, self.window);
+            }
+        }
+        let health: Vec<EndpointHealth> = self
+            .endpoints
+            .iter()
+            .zip(&self.history)
+            .map(|(endpoint, history)| history.health(endpoint, self.failures))
+            .collect();
+        self.event_bus
+            .publish(Event::new("system.http", health.clone()));
+        for endpoint in health.iter().filter(|h| h.up == Some(false)) {
+            self.event_bus
+                .publish(Event::new("system.http.down", endpoint.clone()));
+        }
+        self.apply_health(health);
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.health.len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(Some(if down {
+            (selected + 1).min(count - 1)
+        } else {
+            selected.saturating_sub(1)
+        }));
+    }
+}
+
+impl Widget for HttpMonitorWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected health instead of making requests
+            let (sub, rx) = self.event_bus.subscribe("system.http");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+        if !self.endpoints.is_empty() {
+            self.collector.request(WorkerPool::global());
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<Vec<EndpointHealth>>())
+                .last();
+            if let Some(health) = latest {
+                self.apply_health(Vec::clone(&health));
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(probes) = self.collector.try_take() {
+            self.record(probes, Instant::now());
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval && !self.endpoints.is_empty() {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        let devdash_core::Event::Action(action) = event else {
+            return EventResult::Ignored;
+        };
+        match action.as_str() {
+            "down" => self.move_selection(true),
+            "up" => self.move_selection(false),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let title = match self.injected {
+            Some(_) => " HTTP Monitor ".to_string(),
+            None => format!(" HTTP Monitor [{}] ", format_duration(self.window)),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.health.is_empty() {
+            Paragraph::new("No endpoints configured")
+                .style(Style::default().fg(Color::DarkGray))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        }
+
+        let name_width = self
+            .health
+            .iter()
+            .map(|h| h.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(4, 20) as u16;
+        let rows = self.health.iter().map(|health| {
+            let (state, color) = match health.up {
+                Some(true) => ("up", theme().good),
+                Some(false) => ("down", theme().critical),
+                None => ("-", Color::DarkGray),
+            };
+            let status = health.status.map_or("-".to_string(), |s| s.to_string());
+            let latency = health.latency.map_or("-".to_string(), format_latency);
+            let uptime = health
+                .uptime()
+                .map_or("-".to_string(), |percent| format!("{:.1}%", percent));
+            // A failed check of an endpoint still up is a warning
+            let error_color = match health.up {
+                Some(false) => theme().critical,
+                _ => theme().warning,
+            };
+            Row::new(vec![
+                Cell::from(health.name.clone()),
+                Cell::from(state).style(Style::default().fg(color)),
+                Cell::from(status),
+                Cell::from(latency),
+                Cell::from(uptime),
+                Cell::from(health.error.clone().unwrap_or_default())
+                    .style(Style::default().fg(error_color)),
+            ])
+        });
+        let header = Row::new(
+            ["Name", "State", "Code", "Time", "Uptime", "Error"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(name_width),
+                Constraint::Length(5),
+                Constraint::Length(4),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Min(0),
+            ],
+        )
+        .header(header);
+        if focused {
+            table = table.row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        ratatui::widgets::StatefulWidget::render(table, inner, buf, &mut self.table_state);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        if self.health.is_empty() {
+            return Some("HTTP monitor: no endpoints configured".to_string());
+        }
+        let up = self.health.iter().filter(|h| h.up == Some(true)).count();
+        let mut text = format!("HTTP monitor: {} of {} up", up, self.health.len());
+        let down: Vec<String> = self
+            .health
+            .iter()
+            .filter(|h| h.up == Some(false))
+            .map(EndpointHealth::describe)
+            .collect();
+        if !down.is_empty() {
+            text.push_str(&format!("; {}", down.join(", ")));
+        }
+        Some(text)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint() -> Endpoint {
+        Endpoint {
+            name: "api".to_string(),
+            url: "https://api.example.com/health".to_string(),
+            method: "GET".to_string(),
+        }
+    }
+
+    fn ok() -> Probe {
+        Probe {
+            latency: Some(Duration::from_millis(40)),
+            status: Some(200),
+            error: None,
+        }
+    }
+
+    fn failed() -> Probe {
+        Probe {
+            latency: Some(Duration::from_millis(3)),
+            status: Some(503),
+            error: Some("HTTP 503".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_history() {
+        let endpoint = endpoint();
+        let window = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut history = CheckHistory::default();
+        assert_eq!(history.health(&endpoint, 2).up, None);
+
+        history.record(ok(), start, window);
+        history.record(failed(), start + Duration::from_secs(10), window);
+        // One failure is not an outage yet
+        let health = history.health(&endpoint, 2);
+        assert_eq!(health.up, Some(true));
+        assert_eq!((health.checks, health.failures), (2, 1));
+        assert_eq!(health.status, Some(503));
+        assert_eq!(health.uptime(), Some(50.0));
+
+        history.record(failed(), start + Duration::from_secs(20), window);
+        let health = history.health(&endpoint, 2);
+        assert_eq!(health.up, Some(false));
+        assert_eq!(health.describe(), "api is down (HTTP 503)");
+
+        // The first check left the window
+        history.record(ok(), start + Duration::from_secs(65), window);
+        let health = history.health(&endpoint, 2);
+        assert_eq!(health.up, Some(true));
+        assert_eq!((health.checks, health.failures), (3, 2));
+        assert_eq!(health.error, None);
+    }
+
+    #[test]
+    fn test_rate_limited_checks_are_skipped() {
+        let options = MonitorOptions {
+            endpoints: vec![endpoint()],
+            failures: 1,
+            ..MonitorOptions::default()
+        };
+        let mut widget =
+            HttpMonitorWidget::with_options(EventBus::new(), Duration::from_secs(30), options);
+        let start = Instant::now();
+        widget.record(vec![Some(ok())], start);
+        // Held back by the host's rate limit: not a failure
+        widget.record(vec![None], start + Duration::from_secs(30));
+        assert_eq!(widget.health[0].up, Some(true));
+        assert_eq!((widget.health[0].checks, widget.health[0].failures), (1, 0));
+
+        widget.record(vec![Some(failed())], start + Duration::from_secs(60));
+        assert_eq!(widget.health[0].up, Some(false));
+    }
+
+    #[test]
+    fn test_options() {
+        let parse = |text: &str| {
+            let settings: WidgetSettings = toml::from_str(text).unwrap();
+            parse_settings::<MonitorOptions>(&settings)?.validate()
+        };
+        let options = parse(
+            r#"
+            window = "7d"
+            failures = 3
+            endpoints = [{ name = "api", url = "https://api.example.com/health" }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(options.window, Duration::from_secs(7 * 86_400));
+        assert_eq!(options.timeout, Duration::from_secs(5));
+        assert_eq!(options.failures, 3);
+
+        assert!(parse("failures = 0").is_err());
+        assert!(
+            parse(r#"endpoints = [{ name = "a", url = "http://a" }, { name = "a", url = "http://b" }]"#)
+                .is_err()
+        );
+    }
+}
@@ -1,16 +1,29 @@
 // devdash-widgets/src/network.rs
-use devdash_core::{EventBus, EventResult, Widget, event::Subscription};
+use devdash_core::{
+    Action, EventBus, EventResult, Widget, WidgetSettings, WidgetState,
+    event::{Event, Subscription},
+    parse_settings,
+};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
     prelude::Widget as RatatuiWidget,
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Sparkline},
+    widgets::{Block, Borders},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
 };
-use std::time::Duration;
 use sysinfo::Networks;
 
-use crate::common::{focus_color, format_bytes, format_rate};
+use crate::common::{
+    DualSparkline, LabelledGauge, draw_text, focus_color, format_bytes, format_rate, glob_match,
+    middle_ellipsis, series_colors,
+};
+use crate::platform;
+use crate::quota::{Quota, QuotaUsage, TransferLedger};
 
 /// View mode for NetworkWidget
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,8 +41,126 @@ pub struct InterfaceInfo {
     pub max_speed: Option<u64>, // Mbps, if known
 }
 
+/// Traffic of one interface, published to the event bus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkIOMetrics {
+    pub interface: String,
+    /// Receive rate in bytes per second
+    pub rx_rate: u64,
+    /// Transmit rate in bytes per second
+    pub tx_rate: u64,
+    /// Total bytes received since boot
+    pub total_rx: u64,
+    /// Total bytes transmitted since boot
+    pub total_tx: u64,
+}
+
+/// An interface's link going up or down
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkChange {
+    pub interface: String,
+    pub up: bool,
+}
+
+/// Links that changed state between two polls; an interface that
+/// disappeared went down, and one that appeared came up if its link is
+pub fn link_changes(
+    previous: &HashMap<String, bool>,
+    current: &HashMap<String, bool>,
+) -> Vec<LinkChange> {
+    let mut changes: Vec<LinkChange> = current
+        .iter()
+        .filter(|(name, up)| previous.get(*name).unwrap_or(&false) != *up)
+        .chain(
+            previous
+                .iter()
+                .filter(|(name, up)| **up && !current.contains_key(*name))
+                .map(|(name, _)| (name, &false)),
+        )
+        .map(|(name, up)| LinkChange {
+            interface: name.clone(),
+            up: *up,
+        })
+        .collect();
+    changes.sort_by(|a, b| a.interface.cmp(&b.interface));
+    changes
+}
+
+/// Interfaces hidden when `exclude` isn't set: loopback and container veths
+fn default_excludes() -> Vec<String> {
+    ["lo", "lo0", "Loopback*", "veth*"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// Which interfaces the network widget lists and starts on
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct InterfaceOptions {
+    /// Interface shown first, overriding the one remembered from the last run
+    #[serde(default)]
+    pub interface: Option<String>,
+    /// Glob patterns of interfaces to hide; replaces the default list
+    #[serde(default = "default_excludes")]
+    pub exclude: Vec<String>,
+}
+
+impl Default for InterfaceOptions {
+    fn default() -> Self {
+        Self {
+            interface: None,
+            exclude: default_excludes(),
+        }
+    }
+}
+
+/// Names of the interfaces not hidden by `exclude`, sorted
+fn list_interfaces(networks: &Networks, exclude: &[String]) -> Vec<String> {
+    let mut interfaces: Vec<String> = networks
+        .keys()
+        .filter(|name| !exclude.iter().any(|p| glob_match(p, name)))
+        .cloned()
+        .collect();
+    interfaces.sort();
+    interfaces
+}
+
+/// Widest an interface name gets in the interface list; Windows names such
+/// as `Local Area Connection* 12` are shortened in the middle so their
+/// numbers still tell them apart
+const MAX_INTERFACE_WIDTH: usize = 16;
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("toggle_view", &["t"], "Toggle I/O and interface views"),
+    Action::new("down", &["j", "down"], "Select next interface"),
+    Action::new("up", &["k", "up"], "Select previous interface"),
+    Action::new("reset", &["r"], "Reset totals"),
+];
+
+/// Network traffic widget
+///
+/// # Settings
+/// - `interface` - Interface to start on; otherwise the one selected in the
+///   last run is restored
+/// - `exclude` - Glob patterns of interfaces to hide, by default loopback
+///   and container `veth*` interfaces
+/// - `quota`, `quota_period`, `quota_warn`, `quota_reset_day`,
+///   `quota_direction`, `quota_interface` - Transfer cap; see [`Quota`]
+///
+/// Daily and monthly transfer per interface is kept in the session state.
+///
+/// # Events
+/// - `system.network.io` - The [`NetworkIOMetrics`] of each listed
+///   interface, every poll
+/// - `system.network.interface.up` / `system.network.interface.down` - A
+///   [`LinkChange`] when an interface's link comes up or goes down, or the
+///   interface appears or disappears
+/// - `system.network.quota` - The [`QuotaUsage`], every poll once a quota's
+///   warning threshold is reached
 pub struct NetworkWidget {
     networks: Networks,
+    options: InterfaceOptions,
 
     // Interface management
     interfaces: Vec<String>,
@@ -48,6 +179,16 @@ pub struct NetworkWidget {
     // View mode
     view_mode: ViewMode,
 
+    /// Whether each listed interface's link was up at the last poll; `None`
+    /// before the first
+    links: Option<HashMap<String, bool>>,
+    last_refresh: Instant,
+
+    // Transfer accounting
+    ledger: TransferLedger,
+    quota: Option<Quota>,
+    quota_usage: Option<QuotaUsage>,
+
     // Configuration
     max_history: usize,
     poll_interval: Duration,
@@ -61,11 +202,13 @@ pub struct NetworkWidget {
 impl NetworkWidget {
     pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
         let networks = Networks::new_with_refreshed_list();
-        let interfaces: Vec<String> = networks.keys().map(|s| s.to_string()).collect();
+        let options = InterfaceOptions::default();
+        let interfaces = list_interfaces(&networks, &options.exclude);
 
         Self {
             networks,
-            interfaces: interfaces.clone(),
+            options,
+            interfaces,
             current_idx: 0,
             rx_history: Vec::with_capacity(300),
             tx_history: Vec::with_capacity(300),
@@ -74,6 +217,11 @@ impl NetworkWidget {
             interface_info: Vec::new(),
             selected_interface_idx: 0,
             view_mode: ViewMode::IOStats,
+            links: None,
+            last_refresh: Instant::now(),
+            ledger: TransferLedger::default(),
+            quota: None,
+            quota_usage: None,
             max_history: 60,
             poll_interval,
             time_since_poll: Duration::ZERO,
@@ -82,8 +230,57 @@ impl NetworkWidget {
         }
     }
 
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings(settings)?;
+        let quota = Quota::from_settings(settings)?;
+        Ok(Self::new(event_bus, poll_interval)
+            .with_options(options)?
+            .with_quota(quota))
+    }
+
+    /// Track transfer against `quota`
+    pub fn with_quota(mut self, quota: Option<Quota>) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Hide and pin interfaces as `options` say; fails if the pinned
+    /// interface isn't listed
+    pub fn with_options(mut self, options: InterfaceOptions) -> Result<Self, String> {
+        self.interfaces = list_interfaces(&self.networks, &options.exclude);
+        self.current_idx = 0;
+        if let Some(name) = &options.interface
+            && !self.select_interface(name)
+        {
+            return Err(format!("network interface '{}' not found", name));
+        }
+        self.options = options;
+        Ok(self)
+    }
+
+    /// Show `name` in the I/O view; returns false if it isn't listed
+    fn select_interface(&mut self, name: &str) -> bool {
+        let Some(idx) = self.interfaces.iter().position(|i| i == name) else {
+            return false;
+        };
+        if idx != self.current_idx {
+            self.current_idx = idx;
+            self.reset_current_totals();
+        }
+        true
+    }
+
     fn poll_network(&mut self) {
         self.networks.refresh(true);
+        let elapsed = self.last_refresh.elapsed();
+        self.last_refresh = Instant::now();
+        self.account_transfer();
+        self.publish_traffic(elapsed);
 
         if self.interfaces.is_empty() {
             return;
@@ -123,6 +320,78 @@ impl NetworkWidget {
         self.update_interface_info();
     }
 
+    /// Publish the traffic of each interface in the `elapsed` since the last
+    /// refresh, and the links that went up or down
+    fn publish_traffic(&mut self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let mut links = HashMap::new();
+        for name in list_interfaces(&self.networks, &self.options.exclude) {
+            let Some(data) = self.networks.get(&name) else {
+                continue;
+            };
+            let rate = |bytes: u64| {
+                if secs > 0.0 {
+                    (bytes as f64 / secs) as u64
+                } else {
+                    0
+                }
+            };
+            self.event_bus.publish(Event::new(
+                "system.network.io",
+                NetworkIOMetrics {
+                    interface: name.clone(),
+                    rx_rate: rate(data.received()),
+                    tx_rate: rate(data.transmitted()),
+                    total_rx: data.total_received(),
+                    total_tx: data.total_transmitted(),
+                },
+            ));
+            // Listed interfaces exist, so count as up where the link state
+            // is unknown
+            links.insert(name.clone(), platform::link_up(&name).unwrap_or(true));
+        }
+
+        if let Some(previous) = &self.links {
+            for change in link_changes(previous, &links) {
+                let topic = if change.up {
+                    "system.network.interface.up"
+                } else {
+                    "system.network.interface.down"
+                };
+                self.event_bus.publish(Event::new(topic, change));
+            }
+        }
+        self.links = Some(links);
+    }
+
+    /// Add the traffic since the last poll to the ledger and check the quota
+    fn account_transfer(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        let reset_day = self.quota.as_ref().map_or(1, |q| q.reset_day);
+        for name in &self.interfaces {
+            if let Some(data) = self.networks.get(name) {
+                self.ledger.record(
+                    name,
+                    data.total_received(),
+                    data.total_transmitted(),
+                    today,
+                    reset_day,
+                );
+            }
+        }
+
+        self.quota_usage = self
+            .quota
+            .as_ref()
+            .map(|quota| quota.usage(quota.used(&self.ledger, &self.interfaces)));
+        if let Some(usage) = &self.quota_usage
+            && usage.is_warning()
+        {
+            self.event_bus
+                .publish(Event::new("system.network.quota", usage.clone()));
+        }
+    }
+
     fn update_interface_info(&mut self) {
         let mut new_info = Vec::new();
 
@@ -157,7 +426,7 @@ impl NetworkWidget {
                     name: name.clone(),
                     total_rx,
                     total_tx,
-                    max_speed: None,
+                    max_speed: platform::link_speed_mbps(name),
                 });
             }
         }
@@ -236,15 +505,13 @@ impl Widget for NetworkWidget {
     }
 
     fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
-        use crossterm::event::KeyCode;
-
-        if let devdash_core::Event::Key(key) = event {
-            match key.code {
-                KeyCode::Char('t') => {
+        if let devdash_core::Event::Action(action) = event {
+            match action.as_str() {
+                "toggle_view" => {
                     self.toggle_view();
                     return EventResult::Consumed;
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
+                "up" => {
                     if self.view_mode == ViewMode::InterfaceUsage {
                         self.selected_interface_idx = if self.selected_interface_idx == 0 {
                             self.interface_info.len().saturating_sub(1)
@@ -256,7 +523,7 @@ impl Widget for NetworkWidget {
                     }
                     return EventResult::Consumed;
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
+                "down" => {
                     if self.view_mode == ViewMode::InterfaceUsage {
                         self.selected_interface_idx =
                             (self.selected_interface_idx + 1) % self.interface_info.len();
@@ -265,7 +532,7 @@ impl Widget for NetworkWidget {
                     }
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('r') => {
+                "reset" => {
                     self.reset_current_totals();
                     return EventResult::Consumed;
                 }
@@ -291,6 +558,48 @@ impl Widget for NetworkWidget {
     fn needs_update(&self) -> bool {
         true
     }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mut summary = format!(
+            "Network {} down {}, up {}",
+            self.get_current_interface(),
+            format_rate(self.get_current_rx_rate() as f64),
+            format_rate(self.get_current_tx_rate() as f64)
+        );
+        if let Some(usage) = &self.quota_usage {
+            summary.push_str(&format!("; quota {}", usage.describe()));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        if let Some(name) = self.interfaces.get(self.current_idx) {
+            state.insert("interface".to_string(), name.clone().into());
+        }
+        self.ledger.save(&mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        // The next poll adds what was transferred since the last run
+        self.ledger = TransferLedger::restore(state);
+
+        // A pinned interface wins over the remembered one
+        if self.options.interface.is_none()
+            && let Some(name) = state.get("interface").and_then(|v| v.as_str())
+        {
+            self.select_interface(name);
+        }
+    }
 }
 
 impl NetworkWidget {
@@ -319,48 +628,37 @@ impl NetworkWidget {
 
         let chunks = Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
-            .constraints([
-                Constraint::Length(2),
-                Constraint::Length(2),
-                Constraint::Min(0),
-            ])
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
             .split(inner);
 
-        let available = chunks[0].width.saturating_sub(2).max(1) as usize;
-        let prepare = |hist: &[u64]| -> Vec<u64> {
-            if hist.is_empty() {
-                vec![0; available]
-            } else if hist.len() >= available {
-                hist.iter().rev().take(available).cloned().collect()
-            } else {
-                let mut v = Vec::with_capacity(available);
-                let scale = hist.len() as f32 / available as f32;
-                for i in 0..available {
-                    let idx = (i as f32 * scale) as usize;
-                    v.push(if idx < hist.len() {
-                        hist[idx]
-                    } else {
-                        *hist.last().unwrap_or(&0)
-                    });
-                }
-                v
-            }
-        };
+        let (down, up) = series_colors();
+        DualSparkline::new(
+            ("Down Download", &self.rx_history, down),
+            ("Up Upload", &self.tx_history, up),
+        )
+        .render(chunks[0], buf);
 
-        let rx_data = prepare(&self.rx_history);
-        let tx_data = prepare(&self.tx_history);
-
-        Sparkline::default()
-            .block(Block::default().title("Down Download"))
-            .data(&rx_data)
-            .style(Style::default().fg(Color::Green))
-            .render(chunks[0], buf);
-
-        Sparkline::default()
-            .block(Block::default().title("Up Upload"))
-            .data(&tx_data)
-            .style(Style::default().fg(Color::Blue))
-            .render(chunks[1], buf);
+        if let Some(usage) = &self.quota_usage
+            && chunks[1].height >= 2
+        {
+            let label = format!("Quota {}", usage.describe());
+            let color = if usage.is_exceeded() {
+                Color::Red
+            } else if usage.is_warning() {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            LabelledGauge::new(&label, usage.percentage)
+                .color(color)
+                .render(
+                    Rect {
+                        height: 2,
+                        ..chunks[1]
+                    },
+                    buf,
+                );
+        }
 
         block.render(area, buf);
     }
@@ -390,13 +688,23 @@ impl NetworkWidget {
                 let y = inner.y + (i as u16);
                 let selected = idx == self.selected_interface_idx;
                 let prefix = if selected { ">> " } else { "   " };
-                let line = format!(
+                let mut line = format!(
                     "{}{}  RX: {}  TX: {}",
                     prefix,
-                    info.name,
+                    middle_ellipsis(&info.name, MAX_INTERFACE_WIDTH),
                     format_bytes(info.total_rx),
                     format_bytes(info.total_tx)
                 );
+                if let Some(speed) = info.max_speed {
+                    line.push_str(&format!("  {} Mb/s", speed));
+                }
+                if let Some(transfer) = self.ledger.interface(&info.name) {
+                    line.push_str(&format!(
+                        "  Today: {}  Month: {}",
+                        format_bytes(transfer.today.total()),
+                        format_bytes(transfer.month.total())
+                    ));
+                }
 
                 let style = if selected {
                     Style::default()
@@ -406,16 +714,56 @@ impl NetworkWidget {
                     Style::default()
                 };
 
-                for (x, ch) in line.chars().enumerate() {
-                    if let Some(pos_x) = inner.x.checked_add(x as u16)
-                        && pos_x < inner.x + inner.width
-                    {
-                        buf[(pos_x, y)].set_char(ch).set_style(style);
-                    }
-                }
+                let row = Rect::new(inner.x, y, inner.width, 1);
+                draw_text(buf, row, &line, style);
             }
         }
 
         block.render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(states: &[(&str, bool)]) -> HashMap<String, bool> {
+        states
+            .iter()
+            .map(|(name, up)| (name.to_string(), *up))
+            .collect()
+    }
+
+    #[test]
+    fn test_link_changes() {
+        let previous = links(&[("eth0", true), ("wlan0", false), ("usb0", true)]);
+        let current = links(&[("eth0", false), ("wlan0", true), ("wg0", true)]);
+        let change = |interface: &str, up| LinkChange {
+            interface: interface.to_string(),
+            up,
+        };
+        assert_eq!(
+            link_changes(&previous, &current),
+            vec![
+                change("eth0", false),
+                change("usb0", false),
+                change("wg0", true),
+                change("wlan0", true),
+            ]
+        );
+        assert!(link_changes(&current, &current).is_empty());
+        // A new interface without a link isn't a change
+        assert!(link_changes(&HashMap::new(), &links(&[("eth1", false)])).is_empty());
+    }
+
+    #[test]
+    fn test_missing_interface_is_an_error() {
+        let mut settings = WidgetSettings::new();
+        settings.insert("interface".into(), "devdash-missing0".into());
+        let error =
+            NetworkWidget::from_settings(EventBus::new(), Duration::from_secs(1), &settings)
+                .err()
+                .unwrap();
+        assert_eq!(error, "network interface 'devdash-missing0' not found");
+    }
+}
@@ -0,0 +1,258 @@
+// devdash-widgets/src/platform/affinity.rs
+//! Which cores a process may run on, and how much it recently used each.
+//!
+//! Affinity works on Linux and Windows (on Windows, within the process's
+//! processor group of up to 64 cores). Per-core usage is Linux only: each
+//! thread's CPU time is attributed to the core it last ran on, so it is an
+//! estimate that is best for processes whose threads stay put.
+use std::{io, time::Instant};
+
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+
+#[cfg(windows)]
+use super::windows;
+
+/// Cores `pid` may run on, indexed by core number; the length is the number
+/// of cores
+pub fn allowed_cores(pid: u32) -> io::Result<Vec<bool>> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::allowed_cores(pid)
+    }
+
+    #[cfg(windows)]
+    {
+        let (process, system) = windows::affinity_mask(pid)?;
+        let cores = (usize::BITS - system.leading_zeros()) as usize;
+        Ok((0..cores).map(|core| process & (1 << core) != 0).collect())
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = pid;
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Let `pid` run only on the cores set in `allowed`
+pub fn set_allowed_cores(pid: u32, allowed: &[bool]) -> io::Result<()> {
+    if !allowed.contains(&true) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a process needs at least one core",
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_allowed_cores(pid, allowed)
+    }
+
+    #[cfg(windows)]
+    {
+        let mask = allowed
+            .iter()
+            .take(usize::BITS as usize)
+            .enumerate()
+            .filter(|(_, allowed)| **allowed)
+            .fold(0usize, |mask, (core, _)| mask | 1 << core);
+        windows::set_affinity_mask(pid, mask)
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = pid;
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Recent per-core CPU usage of one process
+pub struct CoreUsage {
+    pid: u32,
+    /// CPU ticks of each thread at the last sample
+    #[cfg(target_os = "linux")]
+    threads: HashMap<u32, u64>,
+    last_sample: Option<Instant>,
+}
+
+impl CoreUsage {
+    pub fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            #[cfg(target_os = "linux")]
+            threads: HashMap::new(),
+            last_sample: None,
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Percent of each of `cores` used since the previous sample
+    ///
+    /// `None` on the first sample, once the process has exited, and where
+    /// per-core usage is unsupported.
+    pub fn sample(&mut self, cores: usize) -> Option<Vec<f32>> {
+        let now = Instant::now();
+        let elapsed = self
+            .last_sample
+            .replace(now)
+            .map(|last| now.duration_since(last).as_secs_f32());
+
+        #[cfg(target_os = "linux")]
+        {
+            let threads = linux::thread_times(self.pid).ok()?;
+            let previous = std::mem::take(&mut self.threads);
+            let mut ticks = vec![0u64; cores];
+            for &(tid, total, core) in &threads {
+                self.threads.insert(tid, total);
+                // New threads count from the next sample
+                if let Some(before) = previous.get(&tid)
+                    && let Some(slot) = ticks.get_mut(core)
+                {
+                    *slot += total.saturating_sub(*before);
+                }
+            }
+
+            let seconds = elapsed.filter(|&s| s > 0.0)?;
+            let per_second = linux::ticks_per_second() as f32;
+            Some(
+                ticks
+                    .into_iter()
+                    .map(|t| (t as f32 / per_second / seconds * 100.0).min(100.0))
+                    .collect(),
+            )
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (cores, elapsed);
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{fs, io, mem};
+
+    pub fn allowed_cores(pid: u32) -> io::Result<Vec<bool>> {
+        let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+        let result = unsafe {
+            libc::sched_getaffinity(
+                pid as libc::pid_t,
+                mem::size_of::<libc::cpu_set_t>(),
+                &mut set,
+            )
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let configured = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_CONF) };
+        let cores = usize::try_from(configured)
+            .unwrap_or(1)
+            .clamp(1, libc::CPU_SETSIZE as usize);
+        Ok((0..cores)
+            .map(|core| unsafe { libc::CPU_ISSET(core, &set) })
+            .collect())
+    }
+
+    pub fn set_allowed_cores(pid: u32, allowed: &[bool]) -> io::Result<()> {
+        let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+        for (core, _) in allowed
+            .iter()
+            .enumerate()
+            .take(libc::CPU_SETSIZE as usize)
+            .filter(|(_, allowed)| **allowed)
+        {
+            unsafe { libc::CPU_SET(core, &mut set) };
+        }
+        let result = unsafe {
+            libc::sched_setaffinity(pid as libc::pid_t, mem::size_of::<libc::cpu_set_t>(), &set)
+        };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    pub fn ticks_per_second() -> u64 {
+        match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+            ticks if ticks > 0 => ticks as u64,
+            _ => 100,
+        }
+    }
+
+    /// `(tid, user + system ticks, last core)` for each thread of `pid`
+    pub fn thread_times(pid: u32) -> io::Result<Vec<(u32, u64, usize)>> {
+        let mut threads = Vec::new();
+        for entry in fs::read_dir(format!("/proc/{pid}/task"))? {
+            let entry = entry?;
+            let Some(tid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+            // Threads can exit between listing and reading
+            if let Ok(stat) = fs::read_to_string(entry.path().join("stat"))
+                && let Some((ticks, core)) = parse_thread_stat(&stat)
+            {
+                threads.push((tid, ticks, core));
+            }
+        }
+        Ok(threads)
+    }
+
+    /// CPU ticks and last core from a `/proc/<pid>/task/<tid>/stat` line
+    pub fn parse_thread_stat(stat: &str) -> Option<(u64, usize)> {
+        // The command name is parenthesized and may contain spaces
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        // Fields after the name start at `state`, field 3 in proc(5)
+        let field = |n: usize| fields.get(n - 3);
+        let utime: u64 = field(14)?.parse().ok()?;
+        let stime: u64 = field(15)?.parse().ok()?;
+        let processor = field(39)?.parse().ok()?;
+        Some((utime + stime, processor))
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_thread_stat() {
+        let stat = "4242 (tokio rt (1)) S 1 4242 4242 0 -1 4194368 120 0 0 0 \
+                    37 5 0 0 20 0 8 0 1000 100000 500 18446744073709551615 \
+                    1 1 0 0 0 0 0 4096 0 0 0 0 17 3 0 0 0 0 0";
+        assert_eq!(linux::parse_thread_stat(stat), Some((42, 3)));
+        assert_eq!(linux::parse_thread_stat("4242 (short) S 1"), None);
+    }
+
+    #[test]
+    fn test_pin_child_to_one_core() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let allowed = allowed_cores(pid).unwrap();
+        let first = allowed.iter().position(|&a| a).unwrap();
+        let mut pinned = vec![false; allowed.len()];
+        pinned[first] = true;
+
+        set_allowed_cores(pid, &pinned).unwrap();
+        assert_eq!(allowed_cores(pid).unwrap(), pinned);
+        assert!(set_allowed_cores(pid, &vec![false; allowed.len()]).is_err());
+
+        let mut usage = CoreUsage::new(pid);
+        assert_eq!(usage.sample(allowed.len()), None);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let sample = usage.sample(allowed.len()).unwrap();
+        assert_eq!(sample.len(), allowed.len());
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
@@ -0,0 +1,347 @@
+// devdash-widgets/src/platform/cgroup.rs
+//! Linux control group limits and usage.
+//!
+//! Supports both the unified (v2) hierarchy and the per-controller (v1)
+//! hierarchies. Everything is read from the cgroup filesystem, so on other
+//! platforms lookups simply find nothing.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Where the cgroup filesystem is mounted
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// v1 reports "unlimited" as a huge page-aligned number
+const V1_UNLIMITED: u64 = 1 << 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgroupVersion {
+    V1,
+    V2,
+}
+
+/// A single control group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cgroup {
+    /// Path within the hierarchy, e.g. `/system.slice/docker-abc.scope`
+    pub path: String,
+    pub version: CgroupVersion,
+    memory_dir: PathBuf,
+    cpu_dir: PathBuf,
+    cpuacct_dir: PathBuf,
+}
+
+impl Cgroup {
+    /// The cgroup this process runs in
+    pub fn current() -> Option<Self> {
+        let contents = fs::read_to_string("/proc/self/cgroup").ok()?;
+        Self::from_proc(&contents, Path::new(CGROUP_ROOT))
+    }
+
+    /// Parse the contents of `/proc/<pid>/cgroup`
+    ///
+    /// A v2 entry (`0::/path`) wins over v1 controller entries.
+    pub fn from_proc(contents: &str, root: &Path) -> Option<Self> {
+        let mut memory = None;
+        let mut cpu = None;
+        let mut cpuacct = None;
+
+        for line in contents.lines() {
+            let mut fields = line.splitn(3, ':');
+            let (Some(id), Some(controllers), Some(path)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+
+            if id == "0" && controllers.is_empty() && root.join("cgroup.controllers").exists() {
+                return Some(Self::v2(path, root));
+            }
+
+            // Co-mounted controllers share a directory named after all of them
+            let dir = root.join(controllers).join(path.trim_start_matches('/'));
+            for controller in controllers.split(',') {
+                match controller {
+                    "memory" => memory = Some((path, dir.clone())),
+                    "cpu" => cpu = Some(dir.clone()),
+                    "cpuacct" => cpuacct = Some(dir.clone()),
+                    _ => {}
+                }
+            }
+        }
+
+        let (path, memory_dir) = memory?;
+        let cpu_dir = cpu.unwrap_or_else(|| memory_dir.clone());
+        Some(Self {
+            path: path.to_string(),
+            version: CgroupVersion::V1,
+            cpuacct_dir: cpuacct.unwrap_or_else(|| cpu_dir.clone()),
+            memory_dir,
+            cpu_dir,
+        })
+    }
+
+    /// A cgroup in the per-controller hierarchies mounted under `root`
+    ///
+    /// Controllers are looked up both co-mounted (`cpu,cpuacct`) and alone.
+    pub fn v1(path: &str, root: &Path) -> Self {
+        let relative = path.trim_start_matches('/');
+        let controller_dir = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| root.join(name))
+                .find(|dir| dir.exists())
+                .unwrap_or_else(|| root.join(names[0]))
+                .join(relative)
+        };
+        Self {
+            path: path.to_string(),
+            version: CgroupVersion::V1,
+            memory_dir: controller_dir(&["memory"]),
+            cpu_dir: controller_dir(&["cpu,cpuacct", "cpu"]),
+            cpuacct_dir: controller_dir(&["cpu,cpuacct", "cpuacct"]),
+        }
+    }
+
+    /// A cgroup in the unified hierarchy mounted at `root`
+    pub fn v2(path: &str, root: &Path) -> Self {
+        let dir = root.join(path.trim_start_matches('/'));
+        Self {
+            path: path.to_string(),
+            version: CgroupVersion::V2,
+            memory_dir: dir.clone(),
+            cpu_dir: dir.clone(),
+            cpuacct_dir: dir,
+        }
+    }
+
+    /// Memory limit in bytes, `None` if unlimited
+    pub fn memory_limit(&self) -> Option<u64> {
+        match self.version {
+            CgroupVersion::V2 => read_u64(&self.memory_dir.join("memory.max")),
+            CgroupVersion::V1 => read_u64(&self.memory_dir.join("memory.limit_in_bytes"))
+                .filter(|&limit| limit < V1_UNLIMITED),
+        }
+    }
+
+    /// Memory in use in bytes, excluding reclaimable page cache
+    ///
+    /// Matches what `docker stats` reports.
+    pub fn memory_usage(&self) -> Option<u64> {
+        let (usage, inactive) = match self.version {
+            CgroupVersion::V2 => ("memory.current", "inactive_file"),
+            CgroupVersion::V1 => ("memory.usage_in_bytes", "total_inactive_file"),
+        };
+        let usage = read_u64(&self.memory_dir.join(usage))?;
+        let inactive = read_stat(&self.memory_dir.join("memory.stat"), inactive).unwrap_or(0);
+        Some(usage.saturating_sub(inactive))
+    }
+
+    /// CPU limit in cores, `None` if unlimited
+    pub fn cpu_limit(&self) -> Option<f64> {
+        let (quota, period) = match self.version {
+            CgroupVersion::V2 => {
+                let max = fs::read_to_string(self.cpu_dir.join("cpu.max")).ok()?;
+                let mut fields = max.split_whitespace();
+                (
+                    fields.next()?.parse::<f64>().ok()?,
+                    fields.next()?.parse::<f64>().ok()?,
+                )
+            }
+            CgroupVersion::V1 => {
+                let quota = fs::read_to_string(self.cpu_dir.join("cpu.cfs_quota_us")).ok()?;
+                let period = read_u64(&self.cpu_dir.join("cpu.cfs_period_us"))?;
+                // -1 means no quota
+                (quota.trim().parse::<f64>().ok()?, period as f64)
+            }
+        };
+        (quota > 0.0 && period > 0.0).then(|| quota / period)
+    }
+
+    /// Total CPU time consumed by the group
+    pub fn cpu_usage(&self) -> Option<Duration> {
+        match self.version {
+            CgroupVersion::V2 => read_stat(&self.cpuacct_dir.join("cpu.stat"), "usage_usec")
+                .map(Duration::from_micros),
+            CgroupVersion::V1 => {
+                read_u64(&self.cpuacct_dir.join("cpuacct.usage")).map(Duration::from_nanos)
+            }
+        }
+    }
+
+    /// Whether a memory or CPU limit applies
+    pub fn is_limited(&self) -> bool {
+        self.memory_limit().is_some() || self.cpu_limit().is_some()
+    }
+}
+
+/// Every cgroup under `root` with memory accounting, up to `max_depth`
+/// levels deep
+///
+/// The root group itself is skipped since it covers the whole host.
+pub fn list(root: &Path, max_depth: usize) -> Vec<Cgroup> {
+    let (version, base) = if root.join("cgroup.controllers").exists() {
+        (CgroupVersion::V2, root.to_path_buf())
+    } else {
+        (CgroupVersion::V1, root.join("memory"))
+    };
+    let usage_file = match version {
+        CgroupVersion::V2 => "memory.current",
+        CgroupVersion::V1 => "memory.usage_in_bytes",
+    };
+
+    let mut groups = Vec::new();
+    let mut pending = vec![(base.clone(), 0)];
+    while let Some((dir, depth)) = pending.pop() {
+        if depth > 0 && dir.join(usage_file).exists() {
+            let path = format!("/{}", dir.strip_prefix(&base).unwrap_or(&dir).display());
+            groups.push(match version {
+                CgroupVersion::V2 => Cgroup::v2(&path, root),
+                CgroupVersion::V1 => Cgroup::v1(&path, root),
+            });
+        }
+        if depth < max_depth
+            && let Ok(entries) = fs::read_dir(&dir)
+        {
+            pending.extend(
+                entries
+                    .flatten()
+                    .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                    .map(|entry| (entry.path(), depth + 1)),
+            );
+        }
+    }
+    groups.sort_by(|a, b| a.path.cmp(&b.path));
+    groups
+}
+
+/// Whether this process appears to run inside a container
+pub fn in_container() -> bool {
+    Path::new("/.dockerenv").exists()
+        || Path::new("/run/.containerenv").exists()
+        || std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+}
+
+/// Read a file holding a single number; `max` and parse errors give `None`
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Read a `key value` line from a stat file such as `memory.stat`
+fn read_stat(path: &Path, key: &str) -> Option<u64> {
+    fs::read_to_string(path).ok()?.lines().find_map(|line| {
+        line.strip_prefix(key)?
+            .strip_prefix(' ')?
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("devdash-cgroup-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (path, contents) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_v2_limits_and_usage() {
+        let root = fixture(
+            "v2",
+            &[
+                ("cgroup.controllers", "cpu memory"),
+                ("app/memory.max", "536870912\n"),
+                ("app/memory.current", "300000000\n"),
+                ("app/memory.stat", "anon 1\ninactive_file 100000000\n"),
+                ("app/cpu.max", "150000 100000\n"),
+                ("app/cpu.stat", "usage_usec 2500000\nuser_usec 2000000\n"),
+            ],
+        );
+
+        let cgroup = Cgroup::from_proc("0::/app\n", &root).unwrap();
+        assert_eq!(cgroup.version, CgroupVersion::V2);
+        assert_eq!(cgroup.memory_limit(), Some(536_870_912));
+        assert_eq!(cgroup.memory_usage(), Some(200_000_000));
+        assert_eq!(cgroup.cpu_limit(), Some(1.5));
+        assert_eq!(cgroup.cpu_usage(), Some(Duration::from_millis(2500)));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_v2_unlimited() {
+        let root = fixture(
+            "v2-max",
+            &[
+                ("cgroup.controllers", "cpu memory"),
+                ("memory.max", "max\n"),
+                ("cpu.max", "max 100000\n"),
+            ],
+        );
+
+        let cgroup = Cgroup::from_proc("0::/\n", &root).unwrap();
+        assert!(!cgroup.is_limited());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_list() {
+        let root = fixture(
+            "list",
+            &[
+                ("cgroup.controllers", "cpu memory"),
+                ("memory.current", "1\n"),
+                ("system.slice/memory.current", "2\n"),
+                ("system.slice/docker-abc.scope/memory.current", "3\n"),
+                (
+                    "system.slice/docker-abc.scope/inner/deep/memory.current",
+                    "4\n",
+                ),
+                ("no-memory/cpu.max", "max 100000\n"),
+            ],
+        );
+
+        let paths: Vec<_> = list(&root, 2).into_iter().map(|c| c.path).collect();
+        assert_eq!(paths, ["/system.slice", "/system.slice/docker-abc.scope"]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_v1_controllers() {
+        let root = fixture(
+            "v1",
+            &[
+                ("memory/docker/abc/memory.limit_in_bytes", "1073741824\n"),
+                ("memory/docker/abc/memory.usage_in_bytes", "600\n"),
+                (
+                    "memory/docker/abc/memory.stat",
+                    "cache 9\ntotal_inactive_file 100\n",
+                ),
+                ("cpu,cpuacct/docker/abc/cpu.cfs_quota_us", "-1\n"),
+                ("cpu,cpuacct/docker/abc/cpu.cfs_period_us", "100000\n"),
+                ("cpu,cpuacct/docker/abc/cpuacct.usage", "3000000000\n"),
+            ],
+        );
+
+        let proc = "12:memory:/docker/abc\n4:cpu,cpuacct:/docker/abc\n1:name=systemd:/docker/abc\n";
+        let cgroup = Cgroup::from_proc(proc, &root).unwrap();
+        assert_eq!(cgroup.version, CgroupVersion::V1);
+        assert_eq!(cgroup.path, "/docker/abc");
+        assert_eq!(cgroup.memory_limit(), Some(1 << 30));
+        assert_eq!(cgroup.memory_usage(), Some(500));
+        assert_eq!(cgroup.cpu_limit(), None);
+        assert_eq!(cgroup.cpu_usage(), Some(Duration::from_secs(3)));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
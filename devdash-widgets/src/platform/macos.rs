@@ -0,0 +1,207 @@
+// devdash-widgets/src/platform/macos.rs
+//! Temperature and fan readings from the System Management Controller.
+//!
+//! sysinfo finds few components on Apple Silicon, so the SMC is queried
+//! directly through IOKit's `AppleSMC` service.
+use super::{SensorKind, SensorReading};
+use std::{ffi::c_void, mem, ptr};
+
+type KernReturn = i32;
+type MachPort = u32;
+type IoObject = MachPort;
+
+const KERN_SUCCESS: KernReturn = 0;
+/// `kSMCHandleYPCEvent`, the user client method taking an `SmcKeyData`
+const SMC_HANDLE_EVENT: u32 = 2;
+const SMC_READ_BYTES: u8 = 5;
+const SMC_READ_KEY_INFO: u8 = 9;
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    fn IOServiceMatching(name: *const i8) -> *mut c_void;
+    fn IOServiceGetMatchingService(main_port: MachPort, matching: *mut c_void) -> IoObject;
+    fn IOServiceOpen(
+        service: IoObject,
+        task: MachPort,
+        kind: u32,
+        conn: *mut IoObject,
+    ) -> KernReturn;
+    fn IOServiceClose(conn: IoObject) -> KernReturn;
+    fn IOObjectRelease(object: IoObject) -> KernReturn;
+    fn IOConnectCallStructMethod(
+        conn: IoObject,
+        selector: u32,
+        input: *const c_void,
+        input_size: usize,
+        output: *mut c_void,
+        output_size: *mut usize,
+    ) -> KernReturn;
+}
+
+unsafe extern "C" {
+    static mach_task_self_: MachPort;
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcVersion {
+    major: u8,
+    minor: u8,
+    build: u8,
+    reserved: u8,
+    release: u16,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcPLimitData {
+    version: u16,
+    length: u16,
+    cpu_p_limit: u32,
+    gpu_p_limit: u32,
+    mem_p_limit: u32,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcKeyInfo {
+    data_size: u32,
+    data_type: u32,
+    data_attributes: u8,
+}
+
+/// `SMCKeyData_t` from the AppleSMC user client
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcKeyData {
+    key: u32,
+    vers: SmcVersion,
+    p_limit_data: SmcPLimitData,
+    key_info: SmcKeyInfo,
+    result: u8,
+    status: u8,
+    data8: u8,
+    data32: u32,
+    bytes: [u8; 32],
+}
+
+/// Candidate keys per reading; the hottest one present is reported
+///
+/// Intel and Apple Silicon machines expose different keys, and the set
+/// varies by model.
+const CPU_KEYS: &[&str] = &[
+    "TC0D", "TC0E", "TC0F", "TC0P", "Tp01", "Tp05", "Tp09", "Tp0D", "Tp0H", "Tp0L", "Tp0P", "Tp0T",
+    "Tp0X", "Tp0b",
+];
+const GPU_KEYS: &[&str] = &["TG0D", "TG0P", "Tg05", "Tg0D", "Tg0L", "Tg0T"];
+
+fn four_cc(s: &str) -> u32 {
+    s.bytes().fold(0, |acc, b| (acc << 8) | b as u32)
+}
+
+/// Open connection to the SMC
+pub struct Smc {
+    conn: IoObject,
+}
+
+impl Smc {
+    /// Connect to `AppleSMC`, or `None` if the service isn't available
+    pub fn open() -> Option<Self> {
+        unsafe {
+            let service = IOServiceGetMatchingService(0, IOServiceMatching(c"AppleSMC".as_ptr()));
+            if service == 0 {
+                return None;
+            }
+            let mut conn = 0;
+            let status = IOServiceOpen(service, mach_task_self_, 0, &mut conn);
+            IOObjectRelease(service);
+            (status == KERN_SUCCESS).then_some(Self { conn })
+        }
+    }
+
+    /// CPU and GPU die temperatures and fan speeds
+    pub fn readings(&self) -> Vec<SensorReading> {
+        let mut readings = Vec::new();
+
+        for (label, keys) in [("CPU die", CPU_KEYS), ("GPU die", GPU_KEYS)] {
+            let hottest = keys
+                .iter()
+                .filter_map(|key| self.read_value(key))
+                // Unpopulated keys read as 0 or garbage
+                .filter(|t| (1.0..150.0).contains(t))
+                .reduce(f32::max);
+            if let Some(value) = hottest {
+                readings.push(SensorReading {
+                    label: label.to_string(),
+                    kind: SensorKind::Temperature,
+                    value,
+                    critical: None,
+                });
+            }
+        }
+
+        let fans = self.read_value("FNum").unwrap_or(0.0) as usize;
+        for i in 0..fans {
+            if let Some(value) = self.read_value(&format!("F{i}Ac")) {
+                readings.push(SensorReading {
+                    label: format!("Fan {}", i + 1),
+                    kind: SensorKind::Fan,
+                    value,
+                    critical: None,
+                });
+            }
+        }
+
+        readings
+    }
+
+    /// Read a key and decode it according to its SMC data type
+    fn read_value(&self, key: &str) -> Option<f32> {
+        let mut input = SmcKeyData {
+            key: four_cc(key),
+            data8: SMC_READ_KEY_INFO,
+            ..Default::default()
+        };
+        let info = self.call(&input)?.key_info;
+        if info.data_size == 0 || info.data_size > 32 {
+            return None;
+        }
+
+        input.key_info = info;
+        input.data8 = SMC_READ_BYTES;
+        let output = self.call(&input)?;
+        let bytes = &output.bytes[..info.data_size as usize];
+
+        match &info.data_type.to_be_bytes() {
+            b"sp78" => Some(i16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 256.0),
+            b"fpe2" => Some(u16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 4.0),
+            b"flt " => Some(f32::from_le_bytes(bytes.try_into().ok()?)),
+            b"ui8 " => Some(bytes[0] as f32),
+            _ => None,
+        }
+    }
+
+    fn call(&self, input: &SmcKeyData) -> Option<SmcKeyData> {
+        let mut output = SmcKeyData::default();
+        let mut output_size = mem::size_of::<SmcKeyData>();
+        let status = unsafe {
+            IOConnectCallStructMethod(
+                self.conn,
+                SMC_HANDLE_EVENT,
+                ptr::from_ref(input).cast(),
+                mem::size_of::<SmcKeyData>(),
+                ptr::from_mut(&mut output).cast(),
+                &mut output_size,
+            )
+        };
+        (status == KERN_SUCCESS && output.result == 0).then_some(output)
+    }
+}
+
+impl Drop for Smc {
+    fn drop(&mut self) {
+        unsafe {
+            IOServiceClose(self.conn);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+// devdash-widgets/src/platform/mod.rs
+//! Platform-specific metric sources.
+//!
+//! sysinfo covers most metrics everywhere, but some are weak or missing on
+//! particular platforms. The types here use native APIs where sysinfo falls
+//! short and fall back to sysinfo (or `None`) elsewhere.
+pub mod affinity;
+pub mod cgroup;
+#[cfg(target_os = "macos")]
+mod macos;
+pub mod priority;
+pub mod sockets;
+pub mod systemd;
+pub mod utmp;
+#[cfg(windows)]
+mod windows;
+
+use serde::{Deserialize, Serialize};
+use std::process::{Child, Command};
+use sysinfo::{Components, Disks};
+
+/// `command` run through `sh -c`, or `cmd /C` on Windows
+pub fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// Start `command` in a process group of its own, so [`terminate_group`]
+/// stops what it starts too (Unix only)
+pub fn own_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+/// Ask `child`, started with [`own_process_group`], and everything it
+/// started to stop; on Windows only `child` itself is killed
+pub fn terminate_group(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: kill() only sends a signal; the group was created at spawn
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGTERM);
+    }
+    #[cfg(not(unix))]
+    let _ = child.kill();
+}
+
+/// Cumulative bytes read and written across all disks
+///
+/// On Windows the totals come from the `PhysicalDisk` performance counters,
+/// since sysinfo only reports I/O for volumes it can map to a process.
+pub struct DiskIoCounters {
+    #[cfg(windows)]
+    pdh: Option<windows::PdhDiskCounters>,
+}
+
+impl DiskIoCounters {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(windows)]
+            pdh: windows::PdhDiskCounters::open(),
+        }
+    }
+
+    /// Total `(read, written)` bytes; `disks` should be freshly refreshed
+    pub fn totals(&mut self, disks: &Disks) -> (u64, u64) {
+        #[cfg(windows)]
+        if let Some(pdh) = &mut self.pdh {
+            return pdh.totals();
+        }
+
+        disks.iter().fold((0, 0), |(read, written), disk| {
+            let usage = disk.usage();
+            (
+                read + usage.total_read_bytes,
+                written + usage.total_written_bytes,
+            )
+        })
+    }
+}
+
+impl Default for DiskIoCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a sensor measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensorKind {
+    /// Degrees Celsius
+    Temperature,
+    /// Revolutions per minute
+    Fan,
+}
+
+/// A single temperature or fan reading
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorReading {
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f32,
+    /// Temperature the hardware considers critical, if it reports one
+    #[serde(default)]
+    pub critical: Option<f32>,
+}
+
+/// Hardware temperature and fan sensors
+///
+/// On macOS the readings come from the SMC, which covers CPU/GPU die
+/// temperatures and fans on Apple Silicon where sysinfo finds little.
+/// Elsewhere, sysinfo's components supply temperatures only.
+pub struct Sensors {
+    #[cfg(target_os = "macos")]
+    smc: Option<macos::Smc>,
+    components: Components,
+}
+
+impl Sensors {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(target_os = "macos")]
+            smc: macos::Smc::open(),
+            components: Components::new_with_refreshed_list(),
+        }
+    }
+
+    /// Take a fresh set of readings
+    pub fn read(&mut self) -> Vec<SensorReading> {
+        #[cfg(target_os = "macos")]
+        if let Some(smc) = &self.smc {
+            let readings = smc.readings();
+            if !readings.is_empty() {
+                return readings;
+            }
+        }
+
+        self.components.refresh(true);
+        self.components
+            .iter()
+            .filter_map(|component| {
+                Some(SensorReading {
+                    label: component.label().to_string(),
+                    kind: SensorKind::Temperature,
+                    value: component.temperature()?,
+                    // Reported as 0 or NaN where the hardware doesn't say
+                    critical: component.critical().filter(|t| t.is_finite() && *t > 0.0),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for Sensors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Negotiated link speed of a network interface in Mbps, if known
+pub fn link_speed_mbps(interface: &str) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        // Reads -1 for interfaces that are down or have no fixed speed
+        let speed = std::fs::read_to_string(format!("/sys/class/net/{interface}/speed")).ok()?;
+        speed
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .filter(|&s| s > 0)
+            .map(|s| s as u64)
+    }
+
+    #[cfg(windows)]
+    {
+        windows::link_speed_mbps(interface)
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = interface;
+        None
+    }
+}
+
+/// Whether a network interface's link is up, where the platform says
+pub fn link_up(interface: &str) -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        let state =
+            std::fs::read_to_string(format!("/sys/class/net/{interface}/operstate")).ok()?;
+        // Loopback and tunnels report `unknown` while working fine
+        match state.trim() {
+            "up" => Some(true),
+            "down" | "lowerlayerdown" | "notpresent" | "dormant" => Some(false),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = interface;
+        None
+    }
+}
+
+/// Pages swapped in and out since boot, if the platform counts them
+pub fn swap_page_counts() -> Option<(u64, u64)> {
+    #[cfg(target_os = "linux")]
+    {
+        parse_vmstat(&std::fs::read_to_string("/proc/vmstat").ok()?)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// `pswpin` and `pswpout` from the contents of `/proc/vmstat`
+#[cfg(any(target_os = "linux", test))]
+fn parse_vmstat(text: &str) -> Option<(u64, u64)> {
+    let counter = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some((counter("pswpin")?, counter("pswpout")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vmstat() {
+        let text = "nr_free_pages 12345\npswpin 42\npswpout 1337\npgpgin 9\n";
+        assert_eq!(parse_vmstat(text), Some((42, 1337)));
+        assert_eq!(parse_vmstat("pswpin 1\n"), None);
+    }
+
+    #[test]
+    fn test_unknown_interface_has_no_speed() {
+        assert_eq!(link_speed_mbps("devdash-missing0"), None);
+        assert_eq!(link_up("devdash-missing0"), None);
+    }
+
+    #[test]
+    fn test_temperatures_are_plausible() {
+        // Containers and VMs often have no sensors at all
+        for reading in Sensors::new().read() {
+            assert!(reading.value.is_finite(), "{reading:?}");
+        }
+    }
+}
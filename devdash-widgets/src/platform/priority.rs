@@ -0,0 +1,204 @@
+// devdash-widgets/src/platform/priority.rs
+//! Reading and changing process scheduling priority.
+//!
+//! Priorities are nice values, from -20 (highest) to 19 (lowest). Windows
+//! has priority classes instead, which map to and from representative nice
+//! values; stepping moves one class at a time there.
+use std::io;
+
+pub const NICE_MIN: i32 = -20;
+pub const NICE_MAX: i32 = 19;
+
+#[cfg(windows)]
+use super::windows;
+
+/// Current nice value of `pid`
+pub fn nice(pid: u32) -> io::Result<i32> {
+    #[cfg(unix)]
+    {
+        unix::nice(pid)
+    }
+
+    #[cfg(windows)]
+    {
+        windows::priority_class(pid).map(class_nice)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pid;
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Set the nice value of `pid`, clamped to the valid range
+pub fn set_nice(pid: u32, nice: i32) -> io::Result<()> {
+    let nice = nice.clamp(NICE_MIN, NICE_MAX);
+
+    #[cfg(unix)]
+    {
+        unix::set_nice(pid, nice)
+    }
+
+    #[cfg(windows)]
+    {
+        windows::set_priority_class(pid, nice_class(nice))
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (pid, nice);
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Move `pid` one step up (`raise`) or down in priority, returning its new
+/// nice value
+pub fn step(pid: u32, raise: bool) -> io::Result<i32> {
+    let current = nice(pid)?;
+    let next = next_nice(current, raise);
+    if next != current {
+        set_nice(pid, next)?;
+    }
+    Ok(next)
+}
+
+/// The nice value one step from `current`
+fn next_nice(current: i32, raise: bool) -> i32 {
+    if cfg!(windows) {
+        let idx = CLASS_NICE
+            .iter()
+            .position(|&nice| nice <= current)
+            .unwrap_or(CLASS_NICE.len() - 1);
+        let idx = if raise {
+            (idx + 1).min(CLASS_NICE.len() - 1)
+        } else {
+            idx.saturating_sub(1)
+        };
+        CLASS_NICE[idx]
+    } else if raise {
+        (current - 1).max(NICE_MIN)
+    } else {
+        (current + 1).min(NICE_MAX)
+    }
+}
+
+/// How a nice value is shown: the priority class on Windows
+pub fn describe(nice: i32) -> String {
+    if cfg!(windows) {
+        let name = match nice {
+            n if n >= 19 => "idle",
+            n if n > 0 => "below normal",
+            0 => "normal",
+            n if n > -10 => "above normal",
+            _ => "high",
+        };
+        format!("{} priority", name)
+    } else {
+        format!("nice {}", nice)
+    }
+}
+
+/// Nice values standing for the idle, below normal, normal, above normal
+/// and high priority classes, lowest priority first
+///
+/// Stepping never reaches the realtime class, which can starve the system.
+const CLASS_NICE: [i32; 5] = [19, 10, 0, -5, -10];
+
+#[cfg(windows)]
+fn class_nice(class: windows::PriorityClass) -> i32 {
+    use windows::PriorityClass::*;
+    match class {
+        Idle => 19,
+        BelowNormal => 10,
+        Normal => 0,
+        AboveNormal => -5,
+        High => -10,
+        Realtime => NICE_MIN,
+    }
+}
+
+#[cfg(windows)]
+fn nice_class(nice: i32) -> windows::PriorityClass {
+    use windows::PriorityClass::*;
+    match nice {
+        n if n >= 19 => Idle,
+        n if n > 0 => BelowNormal,
+        0 => Normal,
+        n if n > -10 => AboveNormal,
+        _ => High,
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io;
+
+    fn clear_errno() {
+        // SAFETY: the errno location is valid for the calling thread
+        unsafe {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            {
+                *libc::__errno_location() = 0;
+            }
+            #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+            {
+                *libc::__error() = 0;
+            }
+        }
+    }
+
+    pub fn nice(pid: u32) -> io::Result<i32> {
+        // -1 is a valid priority, so errors only show through errno
+        clear_errno();
+        let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            Some(code) if nice == -1 && code != 0 => Err(error),
+            _ => Ok(nice),
+        }
+    }
+
+    pub fn set_nice(pid: u32, nice: i32) -> io::Result<()> {
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_nice_stays_in_range() {
+        if cfg!(windows) {
+            assert_eq!(next_nice(0, true), -5);
+            assert_eq!(next_nice(-10, true), -10);
+            assert_eq!(next_nice(19, false), 19);
+            assert_eq!(next_nice(4, false), 10);
+        } else {
+            assert_eq!(next_nice(0, true), -1);
+            assert_eq!(next_nice(NICE_MIN, true), NICE_MIN);
+            assert_eq!(next_nice(NICE_MAX, false), NICE_MAX);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lowering_own_priority() {
+        // Lowering priority is always allowed, so do it in a throwaway child
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let before = nice(child.id()).unwrap();
+        let after = step(child.id(), false).unwrap();
+        assert_eq!(nice(child.id()).unwrap(), after);
+        assert_eq!(after, (before + 1).min(NICE_MAX));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+// devdash-widgets/src/platform/sockets.rs
+//! Listening TCP and UDP sockets, from the `/proc/net` tables.
+//!
+//! The tables name each socket's inode but not its process; the owner is
+//! found by looking for `socket:[inode]` among the open files in
+//! `/proc/<pid>/fd`, which only works for processes the user may inspect.
+//! Other platforms have no such tables, so no sockets are found there.
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+/// `st` of a listening TCP socket
+const TCP_LISTEN: &str = "0A";
+
+/// `st` of an unconnected UDP socket, which receives from anyone
+const UDP_UNCONNECTED: &str = "07";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        })
+    }
+}
+
+/// A socket waiting for connections or datagrams
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListeningSocket {
+    pub protocol: Protocol,
+    pub address: IpAddr,
+    pub port: u16,
+    pub inode: u64,
+}
+
+/// The listening sockets in `table`, the contents of one of
+/// `/proc/net/{tcp,tcp6,udp,udp6}`
+pub fn parse(table: &str, protocol: Protocol) -> Vec<ListeningSocket> {
+    let listening = match protocol {
+        Protocol::Tcp => TCP_LISTEN,
+        Protocol::Udp => UDP_UNCONNECTED,
+    };
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[3] != listening {
+                return None;
+            }
+            let (address, port) = fields[1].split_once(':')?;
+            Some(ListeningSocket {
+                protocol,
+                address: parse_address(address)?,
+                port: u16::from_str_radix(port, 16).ok()?,
+                inode: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// An address as the kernel prints it: 32-bit words in hex, each in host
+/// byte order
+fn parse_address(hex: &str) -> Option<IpAddr> {
+    let words = (0..hex.len())
+        .step_by(8)
+        .map(|at| u32::from_str_radix(hex.get(at..at + 8)?, 16).ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+    match bytes.len() {
+        4 => Some(IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?))),
+        16 => Some(IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)).to_canonical()),
+        _ => None,
+    }
+}
+
+/// Every listening socket on the machine, IPv4 and IPv6
+pub fn listening() -> Vec<ListeningSocket> {
+    [
+        ("/proc/net/tcp", Protocol::Tcp),
+        ("/proc/net/tcp6", Protocol::Tcp),
+        ("/proc/net/udp", Protocol::Udp),
+        ("/proc/net/udp6", Protocol::Udp),
+    ]
+    .into_iter()
+    .filter_map(|(path, protocol)| {
+        let table = std::fs::read_to_string(path).ok()?;
+        Some(parse(&table, protocol))
+    })
+    .flatten()
+    .collect()
+}
+
+/// The PID and name of the process holding each socket inode, for the
+/// processes whose open files can be read
+pub fn socket_owners() -> HashMap<u64, (u32, String)> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return owners;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let name = std::fs::read_to_string(entry.path().join("comm"))
+            .map(|comm| comm.trim_end().to_string())
+            .unwrap_or_default();
+        for fd in fds.flatten() {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            if let Some(inode) = target
+                .to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse().ok())
+            {
+                owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+            }
+        }
+    }
+    owners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The tables print addresses in host byte order
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_parse() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+                   0: 0100007F:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 48213 1 0000000000000000 100 0 0 10 0\n\
+                   1: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 19532 1 0000000000000000 100 0 0 10 0\n\
+                   2: 0100007F:1538 0100007F:D2F0 01 00000000:00000000 00:00000000 00000000  1000        0 51720 1 0000000000000000 20 4 30 10 -1\n";
+        let sockets = parse(tcp, Protocol::Tcp);
+        assert_eq!(
+            sockets,
+            [
+                ListeningSocket {
+                    protocol: Protocol::Tcp,
+                    address: IpAddr::from([127, 0, 0, 1]),
+                    port: 5432,
+                    inode: 48213,
+                },
+                ListeningSocket {
+                    protocol: Protocol::Tcp,
+                    address: IpAddr::from([0, 0, 0, 0]),
+                    port: 22,
+                    inode: 19532,
+                },
+            ]
+        );
+
+        // Connected UDP sockets only talk to their peer
+        let udp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops\n\
+                   3: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 20871 2 0000000000000000 0\n\
+                   4: 0F02000A:9C40 08080808:0035 01 00000000:00000000 00:00000000 00000000  1000        0 61733 2 0000000000000000 0\n";
+        let sockets = parse(udp, Protocol::Udp);
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].port, 5353);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_parse_address() {
+        assert_eq!(
+            parse_address("00000000000000000000000001000000"),
+            Some(IpAddr::from(Ipv6Addr::LOCALHOST))
+        );
+        // IPv4-mapped addresses read as IPv4
+        assert_eq!(
+            parse_address("0000000000000000FFFF00000100007F"),
+            Some(IpAddr::from([127, 0, 0, 1]))
+        );
+        assert_eq!(parse_address("0100007"), None);
+    }
+}
@@ -0,0 +1,78 @@
+// devdash-widgets/src/platform/systemd.rs
+//! Unit properties from `systemctl show`.
+use std::{
+    collections::HashMap,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Properties of one unit, by name
+pub type UnitProperties = HashMap<String, String>;
+
+/// `properties` of the units matching `patterns` (e.g. `*.timer`), from the
+/// system manager or the user's own
+///
+/// Timestamps are requested as `@<unix seconds>`. Empty where systemd isn't
+/// running or `systemctl` fails.
+pub fn show(user: bool, patterns: &[&str], properties: &[&str]) -> Vec<UnitProperties> {
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+    let mut command = Command::new("systemctl");
+    if user {
+        command.arg("--user");
+    }
+    command
+        .args(["show", "--timestamp=unix", "--no-pager"])
+        .arg(format!("--property={}", properties.join(",")))
+        .args(patterns);
+    match command.output() {
+        Ok(output) if output.status.success() => {
+            parse_show(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// `systemctl show` output: `Name=value` lines, one block per unit
+pub fn parse_show(text: &str) -> Vec<UnitProperties> {
+    text.split("\n\n")
+        .map(|block| {
+            block
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<UnitProperties>()
+        })
+        .filter(|unit| !unit.is_empty())
+        .collect()
+}
+
+/// A `@<unix seconds>` timestamp; `None` when unset (empty, `n/a` or `0`)
+pub fn parse_timestamp(value: &str) -> Option<SystemTime> {
+    let secs: u64 = value.strip_prefix('@')?.parse().ok()?;
+    (secs > 0).then(|| UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_show() {
+        let units = parse_show(
+            "Id=backup.timer\nUnit=backup.service\nNextElapseUSecRealtime=@1714532400\n\
+             LastTriggerUSec=n/a\n\nId=logrotate.timer\nUnit=logrotate.service\n\
+             NextElapseUSecRealtime=\nLastTriggerUSec=@1714446000\n",
+        );
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0]["Unit"], "backup.service");
+        assert_eq!(
+            parse_timestamp(&units[0]["NextElapseUSecRealtime"]),
+            Some(UNIX_EPOCH + Duration::from_secs(1_714_532_400))
+        );
+        assert_eq!(parse_timestamp(&units[0]["LastTriggerUSec"]), None);
+        assert_eq!(parse_timestamp(&units[1]["NextElapseUSecRealtime"]), None);
+        assert_eq!(parse_timestamp("@0"), None);
+    }
+}
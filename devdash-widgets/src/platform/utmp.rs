@@ -0,0 +1,166 @@
+// devdash-widgets/src/platform/utmp.rs
+//! Login records from utmp (open sessions) and wtmp (login history).
+//!
+//! Both files are arrays of fixed-size `struct utmp` records in the glibc
+//! layout used on Linux. Other platforms keep their records elsewhere or in
+//! other formats, so no records are found there.
+use std::{
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom},
+    ops::Range,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Sessions open now
+pub const UTMP_PATH: &str = "/var/run/utmp";
+
+/// Every login and logout since the file was rotated
+pub const WTMP_PATH: &str = "/var/log/wtmp";
+
+/// Size of one `struct utmp`
+const RECORD_SIZE: usize = 384;
+
+/// `ut_type` of a login; logouts, boots and runlevel changes use others
+const USER_PROCESS: i16 = 7;
+
+/// Records read from the end of wtmp per login wanted, since logouts and
+/// boots are interleaved with logins
+const RECORDS_PER_LOGIN: usize = 4;
+
+/// One login, from a `USER_PROCESS` record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginRecord {
+    pub pid: u32,
+    pub user: String,
+    /// Terminal relative to `/dev`, e.g. `pts/0`
+    pub line: String,
+    /// Remote host or X display; empty for console logins
+    pub host: String,
+    pub time: SystemTime,
+}
+
+/// The logins in `bytes`, in file order
+pub fn parse(bytes: &[u8]) -> Vec<LoginRecord> {
+    bytes
+        .chunks_exact(RECORD_SIZE)
+        .filter_map(parse_record)
+        .collect()
+}
+
+fn parse_record(record: &[u8]) -> Option<LoginRecord> {
+    let int = |at: usize| i32::from_ne_bytes(record[at..at + 4].try_into().unwrap());
+    let text = |range: Range<usize>| {
+        let field = &record[range];
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8_lossy(&field[..end]).into_owned()
+    };
+
+    if i16::from_ne_bytes([record[0], record[1]]) != USER_PROCESS {
+        return None;
+    }
+    let user = text(44..76);
+    if user.is_empty() {
+        return None;
+    }
+    Some(LoginRecord {
+        pid: int(4) as u32,
+        line: text(8..40),
+        user,
+        host: text(76..332),
+        time: UNIX_EPOCH + Duration::from_secs(int(340) as u32 as u64),
+    })
+}
+
+/// Sessions open now, from a utmp file
+pub fn current(path: &Path) -> Vec<LoginRecord> {
+    fs::read(path)
+        .map(|bytes| parse(&bytes))
+        .unwrap_or_default()
+}
+
+/// The last `count` logins in a wtmp file, newest first
+///
+/// Only the end of the file is read; it grows with every login.
+pub fn recent(path: &Path, count: usize) -> Vec<LoginRecord> {
+    let read_tail = || -> std::io::Result<Vec<u8>> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let records = len / RECORD_SIZE as u64;
+        let tail = records.min((count * RECORDS_PER_LOGIN) as u64);
+        file.seek(SeekFrom::Start((records - tail) * RECORD_SIZE as u64))?;
+        let mut bytes = Vec::new();
+        file.take(tail * RECORD_SIZE as u64)
+            .read_to_end(&mut bytes)?;
+        Ok(bytes)
+    };
+
+    let mut logins = read_tail().map(|bytes| parse(&bytes)).unwrap_or_default();
+    logins.reverse();
+    logins.truncate(count);
+    logins
+}
+
+/// How long since the terminal last saw input, from its access time
+pub fn idle_time(line: &str) -> Option<Duration> {
+    let accessed = fs::metadata(Path::new("/dev").join(line))
+        .ok()?
+        .accessed()
+        .ok()?;
+    Some(
+        SystemTime::now()
+            .duration_since(accessed)
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `struct utmp` as glibc lays it out
+    fn record(kind: i16, pid: i32, line: &str, user: &str, host: &str, time: i32) -> Vec<u8> {
+        let mut record = vec![0u8; RECORD_SIZE];
+        let mut put = |at: usize, bytes: &[u8]| record[at..at + bytes.len()].copy_from_slice(bytes);
+        put(0, &kind.to_ne_bytes());
+        put(4, &pid.to_ne_bytes());
+        put(8, line.as_bytes());
+        put(44, user.as_bytes());
+        put(76, host.as_bytes());
+        put(340, &time.to_ne_bytes());
+        record
+    }
+
+    #[test]
+    fn test_parse_skips_other_records() {
+        let bytes = [
+            record(2, 0, "~", "reboot", "6.1.0", 1_000),
+            record(USER_PROCESS, 812, "tty1", "alice", "", 1_100),
+            record(8, 812, "tty1", "", "", 1_200),
+            record(USER_PROCESS, 990, "pts/0", "bob", "10.0.0.7", 1_300),
+        ]
+        .concat();
+
+        let logins = parse(&bytes);
+        assert_eq!(logins.len(), 2);
+        assert_eq!(logins[0].user, "alice");
+        assert_eq!(logins[0].line, "tty1");
+        assert_eq!(logins[1].pid, 990);
+        assert_eq!(logins[1].host, "10.0.0.7");
+        assert_eq!(logins[1].time, UNIX_EPOCH + Duration::from_secs(1_300));
+    }
+
+    #[test]
+    fn test_recent_reads_newest_first() {
+        let path = std::env::temp_dir().join(format!("devdash-wtmp-{}", std::process::id()));
+        let bytes: Vec<u8> = (0..10)
+            .flat_map(|i| record(USER_PROCESS, i, "pts/1", &format!("user{}", i), "", i))
+            .collect();
+        fs::write(&path, bytes).unwrap();
+
+        let users: Vec<String> = recent(&path, 3).into_iter().map(|l| l.user).collect();
+        assert_eq!(users, ["user9", "user8", "user7"]);
+        assert!(recent(Path::new("/nonexistent/wtmp"), 3).is_empty());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+// devdash-widgets/src/platform/windows.rs
+//! Windows metric sources using PDH and the IP helper API, and process
+//! priority classes and affinity.
+use std::{io, ptr, time::Instant};
+use windows_sys::Win32::{
+    Foundation::{CloseHandle, ERROR_SUCCESS, HANDLE},
+    NetworkManagement::IpHelper::{FreeMibTable, GetIfTable2, MIB_IF_TABLE2},
+    System::{
+        Performance::{
+            PDH_FMT_COUNTERVALUE, PDH_FMT_DOUBLE, PDH_HCOUNTER, PDH_HQUERY, PdhAddEnglishCounterW,
+            PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterValue, PdhOpenQueryW,
+        },
+        Threading::{
+            ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, GetPriorityClass,
+            GetProcessAffinityMask, HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS,
+            NORMAL_PRIORITY_CLASS, OpenProcess, PROCESS_ACCESS_RIGHTS,
+            PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_INFORMATION, REALTIME_PRIORITY_CLASS,
+            SetPriorityClass, SetProcessAffinityMask,
+        },
+    },
+};
+
+const READ_COUNTER: &str = r"\PhysicalDisk(_Total)\Disk Read Bytes/sec";
+const WRITE_COUNTER: &str = r"\PhysicalDisk(_Total)\Disk Write Bytes/sec";
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Disk throughput counters integrated into running totals
+pub struct PdhDiskCounters {
+    query: PDH_HQUERY,
+    read: PDH_HCOUNTER,
+    write: PDH_HCOUNTER,
+    last_sample: Option<Instant>,
+    read_total: u64,
+    write_total: u64,
+}
+
+// SAFETY: PDH query handles may be used from any thread; the widget only
+// touches them through `&mut self`.
+unsafe impl Send for PdhDiskCounters {}
+unsafe impl Sync for PdhDiskCounters {}
+
+impl PdhDiskCounters {
+    /// Open the counters, or `None` if PDH is unavailable
+    pub fn open() -> Option<Self> {
+        let mut query: PDH_HQUERY = ptr::null_mut();
+        let mut read: PDH_HCOUNTER = ptr::null_mut();
+        let mut write: PDH_HCOUNTER = ptr::null_mut();
+
+        unsafe {
+            if PdhOpenQueryW(ptr::null(), 0, &mut query) != ERROR_SUCCESS {
+                return None;
+            }
+            let added = PdhAddEnglishCounterW(query, wide(READ_COUNTER).as_ptr(), 0, &mut read)
+                == ERROR_SUCCESS
+                && PdhAddEnglishCounterW(query, wide(WRITE_COUNTER).as_ptr(), 0, &mut write)
+                    == ERROR_SUCCESS;
+            if !added {
+                PdhCloseQuery(query);
+                return None;
+            }
+            // Rate counters need a first sample to diff against
+            PdhCollectQueryData(query);
+        }
+
+        Some(Self {
+            query,
+            read,
+            write,
+            last_sample: Some(Instant::now()),
+            read_total: 0,
+            write_total: 0,
+        })
+    }
+
+    /// Bytes read and written since the counters were opened
+    pub fn totals(&mut self) -> (u64, u64) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_sample
+            .replace(now)
+            .map_or(0.0, |last| now.duration_since(last).as_secs_f64());
+
+        if unsafe { PdhCollectQueryData(self.query) } == ERROR_SUCCESS {
+            self.read_total += (Self::rate(self.read) * elapsed) as u64;
+            self.write_total += (Self::rate(self.write) * elapsed) as u64;
+        }
+        (self.read_total, self.write_total)
+    }
+
+    /// Current value of a bytes/sec counter, 0 if it has no valid sample
+    fn rate(counter: PDH_HCOUNTER) -> f64 {
+        let mut value: PDH_FMT_COUNTERVALUE = unsafe { std::mem::zeroed() };
+        let status = unsafe {
+            PdhGetFormattedCounterValue(counter, PDH_FMT_DOUBLE, ptr::null_mut(), &mut value)
+        };
+        if status == ERROR_SUCCESS {
+            unsafe { value.Anonymous.doubleValue }.max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Drop for PdhDiskCounters {
+    fn drop(&mut self) {
+        unsafe {
+            PdhCloseQuery(self.query);
+        }
+    }
+}
+
+/// Receive link speed of the interface whose alias is `interface`
+///
+/// sysinfo names Windows interfaces by their alias (e.g. "Ethernet").
+pub fn link_speed_mbps(interface: &str) -> Option<u64> {
+    let mut table: *mut MIB_IF_TABLE2 = ptr::null_mut();
+    if unsafe { GetIfTable2(&mut table) } != ERROR_SUCCESS || table.is_null() {
+        return None;
+    }
+
+    let speed = unsafe {
+        let rows =
+            std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize);
+        rows.iter()
+            .find(|row| {
+                let len = row
+                    .Alias
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(row.Alias.len());
+                String::from_utf16_lossy(&row.Alias[..len]) == interface
+            })
+            .map(|row| row.ReceiveLinkSpeed)
+    };
+    unsafe { FreeMibTable(table.cast()) };
+
+    // Bits per second; u64::MAX means unknown
+    speed
+        .filter(|&bps| bps > 0 && bps != u64::MAX)
+        .map(|bps| bps / 1_000_000)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityClass {
+    Idle,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+    Realtime,
+}
+
+impl PriorityClass {
+    fn flag(self) -> u32 {
+        match self {
+            Self::Idle => IDLE_PRIORITY_CLASS,
+            Self::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+            Self::Normal => NORMAL_PRIORITY_CLASS,
+            Self::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+            Self::High => HIGH_PRIORITY_CLASS,
+            Self::Realtime => REALTIME_PRIORITY_CLASS,
+        }
+    }
+}
+
+/// A process handle closed on drop
+struct ProcessHandle(HANDLE);
+
+impl ProcessHandle {
+    fn open(pid: u32, access: PROCESS_ACCESS_RIGHTS) -> io::Result<Self> {
+        let handle = unsafe { OpenProcess(access, 0, pid) };
+        if handle.is_null() {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Self(handle))
+        }
+    }
+}
+
+impl Drop for ProcessHandle {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.0);
+        }
+    }
+}
+
+pub fn priority_class(pid: u32) -> io::Result<PriorityClass> {
+    let process = ProcessHandle::open(pid, PROCESS_QUERY_LIMITED_INFORMATION)?;
+    let flag = unsafe { GetPriorityClass(process.0) };
+    if flag == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    use PriorityClass::*;
+    Ok([Idle, BelowNormal, Normal, AboveNormal, High, Realtime]
+        .into_iter()
+        .find(|class| class.flag() == flag)
+        .unwrap_or(Normal))
+}
+
+pub fn set_priority_class(pid: u32, class: PriorityClass) -> io::Result<()> {
+    let process = ProcessHandle::open(pid, PROCESS_SET_INFORMATION)?;
+    if unsafe { SetPriorityClass(process.0, class.flag()) } == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// `(process, system)` affinity masks of `pid`
+pub fn affinity_mask(pid: u32) -> io::Result<(usize, usize)> {
+    let process = ProcessHandle::open(pid, PROCESS_QUERY_LIMITED_INFORMATION)?;
+    let (mut process_mask, mut system_mask) = (0usize, 0usize);
+    if unsafe { GetProcessAffinityMask(process.0, &mut process_mask, &mut system_mask) } == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok((process_mask, system_mask))
+    }
+}
+
+pub fn set_affinity_mask(pid: u32, mask: usize) -> io::Result<()> {
+    let process = ProcessHandle::open(pid, PROCESS_SET_INFORMATION)?;
+    if unsafe { SetProcessAffinityMask(process.0, mask) } == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,547 @@
+// devdash-widgets/src/ports.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    ACTION_TOPIC, Action, ActionRequest, Collector, DataStatus, EventBus, EventResult, Selection,
+    Widget, WidgetSettings, WidgetState, WorkerPool,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use crate::common::{AccessDenied, focus_color, glob_match, is_root, restore_table, save_table};
+use crate::platform::sockets::{self, Protocol};
+use crate::process::PROCESS_SELECT_TOPIC;
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Select next port"),
+    Action::new("up", &["k", "up"], "Select previous port"),
+    Action::new("protocol", &["p"], "Show TCP, UDP or both"),
+    Action::new("loopback", &["l"], "Show or hide loopback-only ports"),
+    Action::new(
+        "show_process",
+        &["enter"],
+        "Show the owning process in the process widget",
+    ),
+];
+
+/// A listening socket and the process holding it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListeningPort {
+    pub protocol: Protocol,
+    pub address: IpAddr,
+    pub port: u16,
+    /// Owning process, where it could be found
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[serde(default)]
+    pub process: Option<String>,
+}
+
+impl ListeningPort {
+    /// Whether only this machine can reach the port
+    pub fn is_loopback(&self) -> bool {
+        self.address.is_loopback()
+    }
+
+    /// `address:port`, with IPv6 addresses in brackets
+    pub fn endpoint(&self) -> String {
+        match self.address {
+            IpAddr::V4(address) => format!("{}:{}", address, self.port),
+            IpAddr::V6(address) => format!("[{}]:{}", address, self.port),
+        }
+    }
+}
+
+/// Listening ports at one point in time
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PortList {
+    /// By port, then protocol and address
+    pub ports: Vec<ListeningPort>,
+}
+
+impl PortList {
+    /// Read the socket tables and find each socket's owner (runs on a
+    /// worker thread)
+    pub fn read() -> Self {
+        let owners = sockets::socket_owners();
+        let mut ports: Vec<ListeningPort> = sockets::listening()
+            .into_iter()
+            .map(|socket| {
+                let owner = owners.get(&socket.inode);
+                ListeningPort {
+                    protocol: socket.protocol,
+                    address: socket.address,
+                    port: socket.port,
+                    pid: owner.map(|(pid, _)| *pid),
+                    process: owner.map(|(_, name)| name.clone()),
+                }
+            })
+            .collect();
+        ports.sort_by_key(|p| (p.port, p.protocol, p.address));
+        ports.dedup();
+        Self { ports }
+    }
+}
+
+/// Ports widget settings
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PortOptions {
+    /// Only list this protocol; both when unset
+    pub protocol: Option<Protocol>,
+    /// List ports bound to a loopback address
+    pub loopback: bool,
+    /// Glob patterns of process names whose ports are hidden
+    pub exclude: Vec<String>,
+}
+
+impl Default for PortOptions {
+    fn default() -> Self {
+        Self {
+            protocol: None,
+            loopback: true,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl PortOptions {
+    /// Whether `port` is listed under these filters
+    pub fn shows(&self, port: &ListeningPort) -> bool {
+        self.protocol
+            .is_none_or(|protocol| port.protocol == protocol)
+            && (self.loopback || !port.is_loopback())
+            && !port
+                .process
+                .as_ref()
+                .is_some_and(|name| self.exclude.iter().any(|pattern| glob_match(pattern, name)))
+    }
+}
+
+/// Listening ports widget
+///
+/// Lists the TCP and UDP sockets accepting connections or datagrams, with
+/// the address they are bound to and the process holding them. Sockets are
+/// read from `/proc/net` on Linux; other platforms list none. The owners
+/// of other users' sockets are only known when running as root.
+///
+/// # Keyboard Shortcuts
+/// - `Up`/`Down` or `k`/`j` - Select a port
+/// - `p` - Cycle between both protocols, TCP only and UDP only
+/// - `l` - Show or hide ports bound to a loopback address
+/// - `Enter` - Select the owning process in the process widget and focus it
+///
+/// # Settings
+/// - `protocol` - `"tcp"` or `"udp"` to list only that protocol
+/// - `loopback` - Whether to list loopback-only ports (default true)
+/// - `exclude` - Glob patterns of process names whose ports are hidden
+///
+/// # Events
+/// - `system.ports` - Every listening port as a [`PortList`], every poll
+///
+/// `Enter` publishes the owner's PID on
+/// [`PROCESS_SELECT_TOPIC`](crate::process::PROCESS_SELECT_TOPIC) and asks
+/// the dashboard to focus the `process` widget.
+///
+/// When the event bus is not live, `system.ports` events are shown instead
+/// of reading the socket tables.
+pub struct PortsWidget {
+    collector: Collector<(), PortList>,
+    list: PortList,
+    /// Rows shown under the current filters
+    ports: Vec<ListeningPort>,
+    table_state: TableState,
+    options: PortOptions,
+    /// Why some owners are unknown, if they are
+    hidden: Option<AccessDenied>,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl PortsWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, PortOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<PortOptions>(settings)?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: PortOptions,
+    ) -> Self {
+        Self {
+            collector: Collector::new((), |_: &mut ()| PortList::read()),
+            list: PortList::default(),
+            ports: Vec::new(),
+            table_state: TableState::default(),
+            options,
+            hidden: None,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_list(&mut self, list: PortList) {
+        self.list = list;
+        self.filter();
+    }
+
+    fn apply_snapshot(&mut self, list: PortList) {
+        self.hidden = (!is_root() && list.ports.iter().any(|p| p.pid.is_none())).then(|| {
+            AccessDenied::new(
+                "the owners of other users' sockets",
+                "run devdash with sudo",
+            )
+        });
+        self.event_bus
+            .publish(Event::new("system.ports", list.clone()));
+        self.apply_list(list);
+    }
+
+    /// Rebuild the rows from the latest list, keeping the selected port
+    /// selected
+    fn filter(&mut self) {
+        let selected = self.selected().cloned();
+        self.ports = self
+            .list
+            .ports
+            .iter()
+            .filter(|port| self.options.shows(port))
+            .cloned()
+            .collect();
+        let row = selected
+            .and_then(|selected| self.ports.iter().position(|p| *p == selected))
+            .or(self.table_state.selected())
+            .map(|row| row.min(self.ports.len().saturating_sub(1)));
+        self.table_state.select(if self.ports.is_empty() {
+            None
+        } else {
+            row.or(Some(0))
+        });
+    }
+
+    fn selected(&self) -> Option<&ListeningPort> {
+        self.ports.get(self.table_state.selected()?)
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.ports.len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(Some(if down {
+            (selected + 1).min(count - 1)
+        } else {
+            selected.saturating_sub(1)
+        }));
+    }
+
+    /// Hand the selected port's process to the process widget
+    fn show_process(&mut self) {
+        let Some(pid) = self.selected().and_then(|port| port.pid) else {
+            return;
+        };
+        self.event_bus.publish(Event::new(
+            PROCESS_SELECT_TOPIC,
+            Selection::new("process", pid.to_string()),
+        ));
+        self.event_bus.publish(Event::new(
+            ACTION_TOPIC,
+            ActionRequest::dashboard("focus_widget").with_argument("process"),
+        ));
+    }
+}
+
+impl Widget for PortsWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected ports instead of reading the tables
+            let (sub, rx) = self.event_bus.subscribe("system.ports");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        self.collector.request(WorkerPool::global());
+        if let Some(list) = self.collector.wait() {
+            self.apply_snapshot(list);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx.try_iter().filter_map(|e| e.payload.downcast()).last();
+            if let Some(list) = latest {
+                self.apply_list(PortList::clone(&list));
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(list) = self.collector.try_take() {
+            self.apply_snapshot(list);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        let devdash_core::Event::Action(action) = event else {
+            return EventResult::Ignored;
+        };
+        match action.as_str() {
+            "down" => self.move_selection(true),
+            "up" => self.move_selection(false),
+            "protocol" => {
+                self.options.protocol = match self.options.protocol {
+                    None => Some(Protocol::Tcp),
+                    Some(Protocol::Tcp) => Some(Protocol::Udp),
+                    Some(Protocol::Udp) => None,
+                };
+                self.filter();
+            }
+            "loopback" => {
+                self.options.loopback = !self.options.loopback;
+                self.filter();
+            }
+            "show_process" => self.show_process(),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let mut filters = Vec::new();
+        if let Some(protocol) = self.options.protocol {
+            filters.push(protocol.to_string());
+        }
+        if !self.options.loopback {
+            filters.push("no loopback".to_string());
+        }
+        let title = match filters.is_empty() {
+            true => format!(" Ports [{}] ", self.ports.len()),
+            false => format!(" Ports [{}, {}] ", self.ports.len(), filters.join(", ")),
+        };
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        if let Some(hidden) = &self.hidden {
+            block = block.title_bottom(
+                Line::from(format!(" {} ", hidden)).style(Style::default().fg(theme().warning)),
+            );
+        }
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.ports.is_empty() {
+            Paragraph::new("No listening ports")
+                .style(Style::default().fg(Color::DarkGray))
+                .render(inner, buf);
+            return;
+        }
+
+        let rows = self.ports.iter().map(|port| {
+            let address = Cell::from(port.endpoint());
+            Row::new(vec![
+                Cell::from(port.protocol.to_string()),
+                if port.is_loopback() {
+                    address
+                } else {
+                    // Reachable from other machines
+                    address.style(Style::default().fg(theme().warning))
+                },
+                Cell::from(port.pid.map_or("-".to_string(), |pid| pid.to_string())),
+                Cell::from(port.process.clone().unwrap_or_else(|| "-".to_string())),
+            ])
+        });
+        let header = Row::new(
+            ["Proto", "Address", "PID", "Process"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Min(16),
+                Constraint::Length(7),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header);
+        if focused {
+            table = table.row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        ratatui::widgets::StatefulWidget::render(table, inner, buf, &mut self.table_state);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        let exposed: Vec<String> = self
+            .ports
+            .iter()
+            .filter(|p| !p.is_loopback())
+            .map(|p| format!("{}/{}", p.port, p.protocol))
+            .collect();
+        let mut summary = format!("Ports: {} listening", self.ports.len());
+        if !exposed.is_empty() {
+            summary.push_str(&format!(
+                "; reachable from the network: {}",
+                exposed.join(", ")
+            ));
+        }
+        if let Some(hidden) = &self.hidden {
+            summary.push_str(&format!("; {}", hidden));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(protocol: Protocol, address: [u8; 4], port: u16, process: &str) -> ListeningPort {
+        ListeningPort {
+            protocol,
+            address: IpAddr::from(address),
+            port,
+            pid: Some(100 + port as u32),
+            process: Some(process.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_filters() {
+        let postgres = port(Protocol::Tcp, [127, 0, 0, 1], 5432, "postgres");
+        let ssh = port(Protocol::Tcp, [0, 0, 0, 0], 22, "sshd");
+        let mdns = port(Protocol::Udp, [0, 0, 0, 0], 5353, "avahi-daemon");
+
+        let options = PortOptions::default();
+        assert!([&postgres, &ssh, &mdns].iter().all(|p| options.shows(p)));
+
+        let options = PortOptions {
+            protocol: Some(Protocol::Tcp),
+            loopback: false,
+            exclude: Vec::new(),
+        };
+        assert!(!options.shows(&postgres));
+        assert!(options.shows(&ssh));
+        assert!(!options.shows(&mdns));
+
+        let options = PortOptions {
+            exclude: vec!["avahi*".to_string()],
+            ..PortOptions::default()
+        };
+        assert!(!options.shows(&mdns));
+        // Ports of unknown processes can't be excluded by name
+        let unknown = ListeningPort {
+            pid: None,
+            process: None,
+            ..mdns.clone()
+        };
+        assert!(options.shows(&unknown));
+    }
+
+    #[test]
+    fn test_options() {
+        let settings: WidgetSettings =
+            toml::from_str("protocol = \"udp\"\nloopback = false\ninterval = 5").unwrap();
+        let options = parse_settings::<PortOptions>(&settings).unwrap();
+        assert_eq!(options.protocol, Some(Protocol::Udp));
+        assert!(!options.loopback);
+
+        let settings: WidgetSettings = toml::from_str("protocol = \"sctp\"").unwrap();
+        assert!(parse_settings::<PortOptions>(&settings).is_err());
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let mut port = port(Protocol::Tcp, [10, 0, 0, 2], 8080, "node");
+        assert_eq!(port.endpoint(), "10.0.0.2:8080");
+        port.address = "::1".parse().unwrap();
+        assert_eq!(port.endpoint(), "[::1]:8080");
+        assert!(port.is_loopback());
+    }
+}
@@ -1,34 +1,506 @@
 // devdash-widgets/src/process.rs
+use crossbeam::channel::Receiver;
 use devdash_core::{
-    EventBus, EventResult, Widget,
+    Action, Collector, DataStatus, EventBus, EventResult, Selection, Widget, WidgetSettings,
+    WidgetState, WorkerPool,
     event::{Event, Subscription},
+    parse_settings, theme,
 };
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Layout, Rect},
+    prelude::Widget as RatatuiWidget,
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Row, Table, TableState},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
 };
-use std::time::Duration;
-use sysinfo::System;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use sysinfo::{
+    Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System, UpdateKind,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::common::{
+    AccessDenied, DualSparkline, column_width, focus_color, format_bytes, format_duration,
+    hidden_processes, restore_table, save_table, series_colors, truncate_to_width, usage_color,
+};
+use crate::highlight::{HighlightConfig, HighlightRule, highlight_color};
+use crate::platform::{
+    affinity::{self, CoreUsage},
+    priority,
+};
+
+/// Samples kept for the selected process
+const HISTORY_LEN: usize = 120;
 
-use crate::common::{focus_color, format_bytes};
+/// Height of the detail pane including its separator line
+const DETAIL_HEIGHT: u16 = 6;
 
-#[derive(Clone)]
+/// Width of one core in the detail pane's core view
+const CORE_CELL_WIDTH: u16 = 10;
+
+/// Topic other widgets publish a [`Selection`] of kind `process` on, with
+/// a PID as its value, to have the process widget select that process
+pub const PROCESS_SELECT_TOPIC: &str = "process.select";
+
+/// Share of its open file limit a process may use before
+/// `process.fd.pressure` is published, unless `fd_warn` is set
+const FD_WARN_PERCENT: f32 = 80.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub cpu_percent: f32,
     pub memory_bytes: u64,
+    /// Start time in seconds since the Unix epoch; 0 if unknown
+    #[serde(default)]
+    pub start_time: u64,
+    /// Working directory, if readable
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// Exited but not yet reaped by its parent
+    #[serde(default)]
+    pub zombie: bool,
+    /// Open file descriptors and the soft limit on them, if readable
+    #[serde(default)]
+    pub open_files: Option<usize>,
+    #[serde(default)]
+    pub open_files_limit: Option<usize>,
+}
+
+impl ProcessInfo {
+    /// How long the process has been running, if its start time is known
+    pub fn uptime(&self) -> Option<Duration> {
+        if self.start_time == 0 {
+            return None;
+        }
+        let started = UNIX_EPOCH + Duration::from_secs(self.start_time);
+        Some(
+            SystemTime::now()
+                .duration_since(started)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Open file descriptors as a percentage of the limit, if both are
+    /// known
+    pub fn fd_percent(&self) -> Option<f32> {
+        fd_percent(self.open_files, self.open_files_limit)
+    }
+}
+
+fn fd_percent(open_files: Option<usize>, limit: Option<usize>) -> Option<f32> {
+    match (open_files, limit) {
+        (Some(open), Some(limit)) if limit > 0 => Some(open as f32 / limit as f32 * 100.0),
+        _ => None,
+    }
+}
+
+/// What is kept of every process: its sort keys, and what its
+/// [`ProcessInfo`] is built from once it is in view
+///
+/// Names and working directories are shared with the previous poll's entry
+/// for the same process, so a refresh only allocates for processes that
+/// started or changed.
+#[derive(Debug, Clone)]
+struct ProcessEntry {
+    pid: u32,
+    name: Arc<str>,
+    cpu_percent: f32,
+    memory_bytes: u64,
+    start_time: u64,
+    cwd: Option<Arc<Path>>,
+    zombie: bool,
+    open_files: Option<usize>,
+    open_files_limit: Option<usize>,
+}
+
+impl ProcessEntry {
+    fn from_info(process: &ProcessInfo) -> Self {
+        Self {
+            pid: process.pid,
+            name: Arc::from(process.name.as_str()),
+            cpu_percent: process.cpu_percent,
+            memory_bytes: process.memory_bytes,
+            start_time: process.start_time,
+            cwd: process.cwd.as_deref().map(Arc::from),
+            zombie: process.zombie,
+            open_files: process.open_files,
+            open_files_limit: process.open_files_limit,
+        }
+    }
+
+    fn info(&self) -> ProcessInfo {
+        ProcessInfo {
+            pid: self.pid,
+            name: self.name.to_string(),
+            cpu_percent: self.cpu_percent,
+            memory_bytes: self.memory_bytes,
+            start_time: self.start_time,
+            cwd: self.cwd.as_deref().map(Path::to_path_buf),
+            zombie: self.zombie,
+            open_files: self.open_files,
+            open_files_limit: self.open_files_limit,
+        }
+    }
+
+    /// Whether the process works under `scope`, if set
+    fn in_scope(&self, scope: Option<&Path>) -> bool {
+        scope.is_none_or(|mount_point| {
+            self.cwd
+                .as_deref()
+                .is_some_and(|cwd| cwd.starts_with(mount_point))
+        })
+    }
+}
+
+/// The process table, with the entries of its last read to reuse
+struct ProcessSource {
+    system: System,
+    previous: HashMap<u32, ProcessEntry>,
+}
+
+/// All running instances of one program, combined
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessGroup {
+    pub name: String,
+    pub count: usize,
+    /// Sum of the instances' CPU usage
+    pub cpu_percent: f32,
+    /// Sum of the instances' resident memory
+    pub memory_bytes: u64,
+}
+
+/// Combine `processes` by name, in order of first appearance
+pub fn group_processes<'a>(
+    processes: impl IntoIterator<Item = &'a ProcessInfo>,
+) -> Vec<ProcessGroup> {
+    combine(
+        processes
+            .into_iter()
+            .map(|p| (p.name.as_str(), p.cpu_percent, p.memory_bytes)),
+    )
 }
 
+/// Combine `(name, cpu_percent, memory_bytes)` samples by name, in order of
+/// first appearance
+fn combine<'a>(processes: impl IntoIterator<Item = (&'a str, f32, u64)>) -> Vec<ProcessGroup> {
+    let mut groups: Vec<ProcessGroup> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (name, cpu_percent, memory_bytes) in processes {
+        match index.get(name) {
+            Some(&i) => {
+                let group = &mut groups[i];
+                group.count += 1;
+                group.cpu_percent += cpu_percent;
+                group.memory_bytes += memory_bytes;
+            }
+            None => {
+                index.insert(name, groups.len());
+                groups.push(ProcessGroup {
+                    name: name.to_string(),
+                    count: 1,
+                    cpu_percent,
+                    memory_bytes,
+                });
+            }
+        }
+    }
+    groups
+}
+
+/// CPU/memory samples accumulated for a single process
+#[derive(Debug, Clone, Default)]
+pub struct ProcessHistory {
+    pub pid: u32,
+    /// CPU usage samples in whole percent, oldest first
+    pub cpu: VecDeque<u64>,
+    /// Resident memory samples in bytes, oldest first
+    pub memory: VecDeque<u64>,
+}
+
+impl ProcessHistory {
+    fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            ..Default::default()
+        }
+    }
+
+    /// Add a sample, dropping the oldest past [`HISTORY_LEN`]
+    fn push(&mut self, cpu_percent: f32, memory_bytes: u64) {
+        if self.cpu.len() == HISTORY_LEN {
+            self.cpu.pop_front();
+            self.memory.pop_front();
+        }
+        self.cpu.push_back(cpu_percent.round() as u64);
+        self.memory.push_back(memory_bytes);
+    }
+
+    /// The CPU and memory samples as slices, oldest first
+    fn series(&mut self) -> (&[u64], &[u64]) {
+        (self.cpu.make_contiguous(), self.memory.make_contiguous())
+    }
+}
+
+/// The selected process's cores, shown in the detail pane
+struct CoreView {
+    usage: CoreUsage,
+    /// Cores the process may run on, or why they can't be read
+    allowed: Result<Vec<bool>, String>,
+    /// Per-core usage percent since the previous refresh (Linux only)
+    recent: Option<Vec<f32>>,
+    cursor: usize,
+    /// Why the last pin or unpin failed
+    error: Option<String>,
+}
+
+impl CoreView {
+    fn new(pid: u32) -> Self {
+        let mut view = Self {
+            usage: CoreUsage::new(pid),
+            allowed: Ok(Vec::new()),
+            recent: None,
+            cursor: 0,
+            error: None,
+        };
+        view.refresh();
+        view
+    }
+
+    fn refresh(&mut self) {
+        self.allowed = affinity::allowed_cores(self.usage.pid()).map_err(|e| e.to_string());
+        let cores = self.allowed.as_ref().map_or(0, Vec::len);
+        self.recent = self.usage.sample(cores);
+        self.cursor = self.cursor.min(cores.saturating_sub(1));
+    }
+
+    fn move_cursor(&mut self, forward: bool) {
+        let cores = self.allowed.as_ref().map_or(0, Vec::len);
+        self.cursor = if forward {
+            (self.cursor + 1).min(cores.saturating_sub(1))
+        } else {
+            self.cursor.saturating_sub(1)
+        };
+    }
+
+    /// Apply a new set of allowed cores, built from the current one
+    fn set_allowed(&mut self, change: impl FnOnce(&mut Vec<bool>)) {
+        let Ok(allowed) = &self.allowed else {
+            return;
+        };
+        let mut next = allowed.clone();
+        change(&mut next);
+        match affinity::set_allowed_cores(self.usage.pid(), &next) {
+            Ok(()) => {
+                self.allowed = Ok(next);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Can't change cores: {}", e)),
+        }
+    }
+
+    /// Pin or unpin the core under the cursor
+    fn toggle(&mut self) {
+        let cursor = self.cursor;
+        self.set_allowed(|allowed| {
+            if let Some(core) = allowed.get_mut(cursor) {
+                *core = !*core;
+            }
+        });
+    }
+
+    fn allow_all(&mut self) {
+        self.set_allowed(|allowed| allowed.fill(true));
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let allowed = match &self.allowed {
+            Ok(allowed) => allowed,
+            Err(e) => {
+                Paragraph::new(format!("Cores unavailable: {}", e))
+                    .style(Style::default().fg(Color::Red))
+                    .render(area, buf);
+                return;
+            }
+        };
+
+        // Keep the cursor's row in view, leaving the last line for help
+        let per_row = (area.width / CORE_CELL_WIDTH).max(1) as usize;
+        let rows = (area.height as usize).saturating_sub(1).max(1);
+        let first_row = (self.cursor / per_row + 1).saturating_sub(rows);
+
+        let mut lines: Vec<Line> = allowed
+            .chunks(per_row)
+            .enumerate()
+            .skip(first_row)
+            .take(rows)
+            .map(|(row, cores)| {
+                Line::from(
+                    cores
+                        .iter()
+                        .enumerate()
+                        .map(|(col, &allowed)| {
+                            let core = row * per_row + col;
+                            let usage = self.recent.as_ref().and_then(|u| u.get(core));
+                            let text = match usage {
+                                Some(usage) => {
+                                    format!("{}{:>3} {:>3.0}%", mark(allowed), core, usage)
+                                }
+                                None => format!("{}{:>3}", mark(allowed), core),
+                            };
+                            let mut style = match usage {
+                                _ if !allowed => Style::default().fg(Color::DarkGray),
+                                Some(&usage) => Style::default().fg(usage_color(usage.into())),
+                                None => Style::default(),
+                            };
+                            if core == self.cursor {
+                                style = style.add_modifier(Modifier::REVERSED);
+                            }
+                            Span::styled(
+                                format!("{:<width$}", text, width = CORE_CELL_WIDTH as usize),
+                                style,
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        lines.push(match &self.error {
+            Some(error) => Line::from(error.as_str()).style(Style::default().fg(Color::Red)),
+            None => Line::from("←/→ select  Space pin/unpin  u all cores")
+                .style(Style::default().fg(Color::DarkGray)),
+        });
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+/// Marks a core the process may (●) or may not (○) run on
+fn mark(allowed: bool) -> &'static str {
+    if allowed { "●" } else { "○" }
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Move selection down"),
+    Action::new("up", &["k", "up"], "Move selection up"),
+    Action::new("sort_cpu", &["c"], "Sort by CPU"),
+    Action::new("sort_memory", &["m"], "Sort by memory"),
+    Action::new("sort_name", &["n"], "Sort by name"),
+    Action::new("toggle_detail", &["d"], "Toggle detail pane"),
+    Action::new("raise_priority", &["+", "="], "Raise priority").mutating(),
+    Action::new("lower_priority", &["-"], "Lower priority").mutating(),
+    Action::new("kill", &["K"], "Kill selected process").mutating(),
+    Action::new("cores", &["a"], "Show/hide cores"),
+    Action::new("core_prev", &["left"], "Select previous core"),
+    Action::new("core_next", &["right"], "Select next core"),
+    Action::new("core_toggle", &["space"], "Pin/unpin selected core").mutating(),
+    Action::new("core_all", &["u"], "Allow all cores").mutating(),
+    Action::new("clear_link", &["x"], "Clear linked disk filter"),
+    Action::new("group", &["g"], "Group by name"),
+];
+
 /// Process viewer widget with sorting and filtering
+///
+/// While a row stays selected, its CPU and memory samples are accumulated
+/// and drawn as sparklines in a detail pane below the table.
+///
+/// # Keyboard Shortcuts
+/// - `j`/`k` or `↓`/`↑` - Move the selection
+/// - `c`/`m`/`n` - Sort by CPU, memory or name
+/// - `d` - Toggle the detail pane
+/// - `+`/`-` - Raise or lower the selected process's priority (nice value,
+///   or priority class on Windows); raising usually needs root or
+///   administrator rights
+/// - `K` - Ask the selected process to exit (`SIGTERM`; terminated outright
+///   on Windows)
+/// - `a` - Show the cores the selected process may run on in the detail
+///   pane, with its recent usage of each on Linux; there, `←`/`→` pick a
+///   core, `Space` pins or unpins it and `u` allows every core
+/// - `x` - Show all processes again after a linked disk selection
+/// - `g` - Toggle the group view, one row per program name with the
+///   instance count and combined CPU and memory
+///
+/// # Settings
+/// - `highlight` - Rules coloring matching rows wherever they sort, e.g.
+///   `[{ when = "cpu > 80 or memory > 2GB", color = "red" }]`; the first
+///   matching rule wins
+/// - `fd_warn` - Percentage of its open file limit at which a process is
+///   marked and reported (default 80)
+///
+/// # Event Publishing
+/// - Publishes `system.process.top` with the top row on each poll
+/// - Publishes `process.zombie` for each zombie process, and
+///   `process.fd.pressure` for each process past `fd_warn`, on each poll;
+///   both carry the [`ProcessInfo`]
+///
+/// Zombies are listed as `<defunct>` in gray, processes short of file
+/// descriptors with their usage in magenta.
+///
+/// # Linking
+/// A `disk` selected in a linked widget limits the table to processes whose
+/// working directory is on that mount point.
+///
+/// A PID published on [`PROCESS_SELECT_TOPIC`], e.g. by the ports widget,
+/// selects that process and keeps it in the table while it stays selected,
+/// even outside the top rows.
+///
+/// The process table is read on the shared worker pool, so a slow refresh
+/// never delays a frame. When the event bus is not live, each
+/// `system.process.top` event adds or updates a row instead.
 pub struct ProcessWidget {
-    system: System,
-    processes: Vec<ProcessInfo>,
+    collector: Collector<ProcessSource, Vec<ProcessEntry>>,
+    snapshot: Vec<ProcessEntry>, // Every known process, unsorted
+    processes: Vec<ProcessInfo>, // Sorted rows shown in the table
     table_state: TableState,
+
+    /// Sorted rows of the group view, while it is open
+    groups: Option<Vec<ProcessGroup>>,
+    group_state: TableState,
+    /// Name of the selected group, followed across refreshes
+    selected_group: Option<String>,
+
     event_bus: EventBus,
     _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    /// Selections requested on [`PROCESS_SELECT_TOPIC`]
+    select_requests: Option<(Subscription, Receiver<Event>)>,
+
+    // Selection tracking
+    selected_pid: Option<u32>,
+    /// Process selected by request, kept in the table while selected
+    pinned: Option<u32>,
+    history: ProcessHistory,
+    show_detail: bool,
+
+    /// Mount point set by a linked disk selection
+    scope: Option<PathBuf>,
+
+    /// Outcome of the last priority change or kill, shown until the
+    /// selection moves
+    action_status: Option<Result<String, String>>,
+
+    /// Why other users' processes are missing from the list, if they are
+    hidden: Option<AccessDenied>,
+
+    /// Core view of the detail pane, replacing the sparklines while open
+    cores: Option<CoreView>,
+
+    highlights: Vec<HighlightRule>,
+    /// Open file usage, in percent of the limit, reported as pressure
+    fd_warn: f32,
 
     // Config
     poll_interval: Duration,
@@ -37,6 +509,16 @@ pub struct ProcessWidget {
     sort_by: SortBy,
 }
 
+/// Options read from the widget's settings
+#[derive(Debug, Default, Deserialize)]
+struct ProcessSettings {
+    #[serde(default)]
+    highlight: Vec<HighlightConfig>,
+    /// Rows shown in the table
+    max_rows: Option<usize>,
+    fd_warn: Option<f32>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SortBy {
     Cpu,
@@ -46,64 +528,549 @@ pub enum SortBy {
 
 impl ProcessWidget {
     pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
-        let mut sys = System::new_all();
-        sys.refresh_all();
-
         Self {
-            system: sys,
+            collector: Collector::new(
+                ProcessSource {
+                    system: System::new(),
+                    previous: HashMap::new(),
+                },
+                collect_processes,
+            ),
+            snapshot: Vec::new(),
             processes: Vec::new(),
             table_state: TableState::default(),
+            groups: None,
+            group_state: TableState::default(),
+            selected_group: None,
             event_bus,
             _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
             poll_interval,
             time_since_poll: Duration::ZERO,
             max_processes: 20,
             sort_by: SortBy::Cpu,
+            select_requests: None,
+            selected_pid: None,
+            pinned: None,
+            history: ProcessHistory::default(),
+            show_detail: true,
+            scope: None,
+            action_status: None,
+            hidden: None,
+            cores: None,
+            highlights: Vec::new(),
+            fd_warn: FD_WARN_PERCENT,
         }
     }
 
-    fn refresh_processes(&mut self) {
-        self.system
-            .refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let settings: ProcessSettings = parse_settings(settings)?;
+        let highlights = settings
+            .highlight
+            .into_iter()
+            .map(HighlightRule::try_from)
+            .collect::<Result<_, _>>()?;
+        let mut widget = Self::new(event_bus, poll_interval).with_highlights(highlights);
+        if let Some(rows) = settings.max_rows {
+            if rows == 0 {
+                return Err("max_rows must be at least 1".to_string());
+            }
+            widget = widget.with_max_rows(rows);
+        }
+        if let Some(percent) = settings.fd_warn {
+            if !(percent > 0.0 && percent <= 100.0) {
+                return Err(format!("fd_warn must be a percentage, got {}", percent));
+            }
+            widget.fd_warn = percent;
+        }
+        Ok(widget)
+    }
 
-        self.processes = self
-            .system
-            .processes()
-            .iter()
-            .map(|(pid, process)| ProcessInfo {
-                pid: pid.as_u32(),
-                name: process.name().to_string_lossy().to_string(),
-                cpu_percent: process.cpu_usage(),
-                memory_bytes: process.memory(),
-            })
-            .collect();
+    /// Show at most `rows` processes
+    pub fn with_max_rows(mut self, rows: usize) -> Self {
+        self.max_processes = rows;
+        self
+    }
 
-        // Sort
-        match self.sort_by {
-            SortBy::Cpu => self
-                .processes
-                .sort_by(|a, b| b.cpu_percent.partial_cmp(&a.cpu_percent).unwrap()),
-            SortBy::Memory => self
-                .processes
-                .sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes)),
-            SortBy::Name => self.processes.sort_by(|a, b| a.name.cmp(&b.name)),
+    /// Whether a process using `fd_percent` of its open files is at
+    /// `fd_warn` or more
+    fn fd_pressure(&self, fd_percent: Option<f32>) -> bool {
+        fd_percent.is_some_and(|percent| percent >= self.fd_warn)
+    }
+
+    /// Color rows matching any of `rules`
+    pub fn with_highlights(mut self, rules: Vec<HighlightRule>) -> Self {
+        self.highlights = rules;
+        self
+    }
+
+    /// `" on <mount>"` while a linked disk selection filters the table
+    fn scope_label(&self) -> String {
+        self.scope.as_ref().map_or(String::new(), |mount_point| {
+            format!(" on {}", mount_point.display())
+        })
+    }
+
+    /// Select the row at `idx` and start tracking its process
+    fn select_row(&mut self, idx: usize) {
+        self.table_state.select(Some(idx));
+        self.selected_pid = self.processes.get(idx).map(|p| p.pid);
+        if self.selected_pid != self.pinned {
+            self.pinned = None;
+        }
+        if let Some(pid) = self.selected_pid
+            && self.history.pid != pid
+        {
+            self.history = ProcessHistory::new(pid);
+            self.action_status = None;
         }
+    }
+
+    /// Move the selected process one step up or down in priority
+    fn renice_selected(&mut self, raise: bool) {
+        let Some(process) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.processes.get(idx))
+        else {
+            return;
+        };
 
-        // Truncate to max
-        self.processes.truncate(self.max_processes);
+        self.action_status = Some(match priority::step(process.pid, raise) {
+            Ok(nice) => Ok(format!(
+                "{} ({}): {}",
+                process.name,
+                process.pid,
+                priority::describe(nice)
+            )),
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => Err(format!(
+                "Can't {} priority of {} ({}): permission denied{}",
+                if raise { "raise" } else { "lower" },
+                process.name,
+                process.pid,
+                if raise {
+                    ", raising needs root or administrator rights"
+                } else {
+                    ""
+                }
+            )),
+            Err(e) => Err(format!(
+                "Can't change priority of {} ({}): {}",
+                process.name, process.pid, e
+            )),
+        });
+    }
+
+    /// Ask the selected process to exit
+    fn kill_selected(&mut self) {
+        let Some(process) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.processes.get(idx))
+        else {
+            return;
+        };
+
+        self.action_status = Some(match terminate(process.pid) {
+            Ok(()) => Ok(format!(
+                "Sent {} ({}) a kill signal",
+                process.name, process.pid
+            )),
+            Err(e) => Err(format!(
+                "Can't kill {} ({}): {}",
+                process.name, process.pid, e
+            )),
+        });
+    }
+
+    /// Keep the selection on the same PID across refreshes and sample it
+    fn sync_selection(&mut self) {
+        let row = self
+            .selected_pid
+            .and_then(|pid| self.processes.iter().position(|p| p.pid == pid));
+
+        match row {
+            Some(idx) => self.table_state.select(Some(idx)),
+            None => {
+                // The process left the list; fall back to the same row index
+                let idx = self
+                    .table_state
+                    .selected()
+                    .unwrap_or(0)
+                    .min(self.processes.len().saturating_sub(1));
+                self.table_state.select(Some(idx));
+                self.selected_pid = self.processes.get(idx).map(|p| p.pid);
+            }
+        }
+    }
+
+    /// Append a sample for the selected process, resetting on PID change
+    fn track_selected(&mut self) {
+        let Some(pid) = self.selected_pid else {
+            self.history = ProcessHistory::default();
+            return;
+        };
+
+        if let Some(cores) = &mut self.cores {
+            if cores.usage.pid() == pid {
+                cores.refresh();
+            } else {
+                *cores = CoreView::new(pid);
+            }
+        }
+
+        if self.history.pid != pid {
+            self.history = ProcessHistory::new(pid);
+        }
+
+        // Sample from the full snapshot so rows outside the top list still update
+        if let Some(process) = self.snapshot.iter().find(|p| p.pid == pid) {
+            self.history.push(process.cpu_percent, process.memory_bytes);
+        }
+    }
+
+    fn render_detail(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(selected) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.processes.get(idx))
+        else {
+            return;
+        };
+
+        let open_files = match (selected.open_files, selected.open_files_limit) {
+            (Some(open), Some(limit)) => format!("  FD {}/{}", open, limit),
+            _ => String::new(),
+        };
+        let header = format!(
+            " {} ({})  CPU {:.1}%  MEM {}{}  [{} samples]",
+            selected.name,
+            selected.pid,
+            selected.cpu_percent,
+            format_bytes(selected.memory_bytes),
+            open_files,
+            self.history.cpu.len()
+        );
+        let chunks = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+
+        Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(Line::from(header).style(Style::default().fg(Color::Yellow)))
+            .render(chunks[0], buf);
+
+        match &self.cores {
+            Some(cores) => cores.render(chunks[1], buf),
+            None => {
+                let (cpu, memory) = series_colors();
+                let (cpu_samples, memory_samples) = self.history.series();
+                DualSparkline::new(("CPU", cpu_samples, cpu), ("MEM", memory_samples, memory))
+                    .render(chunks[1], buf)
+            }
+        }
+    }
+
+    /// Display a freshly collected process list
+    fn apply_snapshot(&mut self, snapshot: Vec<ProcessEntry>) {
+        self.snapshot = snapshot;
+        self.sort_processes();
+        self.track_selected();
 
         // Publish top process update
         if let Some(top) = self.processes.first() {
             self.event_bus
                 .publish(Event::new("system.process.top", top.clone()));
         }
+
+        for process in &self.snapshot {
+            if process.zombie {
+                self.event_bus
+                    .publish(Event::new("process.zombie", process.info()));
+            }
+            if self.fd_pressure(fd_percent(process.open_files, process.open_files_limit)) {
+                self.event_bus
+                    .publish(Event::new("process.fd.pressure", process.info()));
+            }
+        }
+    }
+
+    /// Sort the snapshot into the visible rows and re-sync the selection
+    ///
+    /// Only the rows in view are sorted in full and get a [`ProcessInfo`],
+    /// so thousands of processes cost little more than a screenful.
+    fn sort_processes(&mut self) {
+        let scope = self.scope.as_deref();
+        let mut rows: Vec<&ProcessEntry> =
+            self.snapshot.iter().filter(|p| p.in_scope(scope)).collect();
+
+        let groups = self.groups.is_some().then(|| {
+            combine(
+                rows.iter()
+                    .map(|p| (&*p.name, p.cpu_percent, p.memory_bytes)),
+            )
+        });
+
+        // Ties go by PID so rows don't swap places between refreshes
+        let sort_by = self.sort_by;
+        let order = |a: &&ProcessEntry, b: &&ProcessEntry| {
+            match sort_by {
+                SortBy::Cpu => b.cpu_percent.total_cmp(&a.cpu_percent),
+                SortBy::Memory => b.memory_bytes.cmp(&a.memory_bytes),
+                SortBy::Name => a.name.cmp(&b.name),
+            }
+            .then(a.pid.cmp(&b.pid))
+        };
+        let visible = self.max_processes;
+        let mut pinned = None;
+        if rows.len() > visible {
+            if visible > 0 {
+                rows.select_nth_unstable_by(visible - 1, order);
+            }
+            // Keep a requested process in the last row
+            pinned = self
+                .pinned
+                .and_then(|pid| rows[visible..].iter().find(|p| p.pid == pid).copied());
+            rows.truncate(visible);
+        }
+        rows.sort_by(order);
+        if let Some(process) = pinned
+            && rows.pop().is_some()
+        {
+            rows.push(process);
+        }
+        self.processes = rows.into_iter().map(ProcessEntry::info).collect();
+
+        if let Some(groups) = groups {
+            self.sort_groups(groups);
+        }
+        self.sync_selection();
+    }
+
+    /// Select the process with `pid`, as requested by another widget
+    fn select_pid(&mut self, pid: u32) {
+        let Some(process) = self.snapshot.iter().find(|p| p.pid == pid) else {
+            return;
+        };
+        if !process.in_scope(self.scope.as_deref()) {
+            self.scope = None;
+        }
+        self.groups = None;
+        self.selected_pid = Some(pid);
+        self.pinned = Some(pid);
+        self.action_status = None;
+        self.sort_processes();
+        self.track_selected();
+    }
+
+    /// Show `groups` of the processes in scope, in the same order as the
+    /// process rows
+    fn sort_groups(&mut self, mut groups: Vec<ProcessGroup>) {
+        groups.sort_by(|a, b| {
+            match self.sort_by {
+                SortBy::Cpu => b.cpu_percent.total_cmp(&a.cpu_percent),
+                SortBy::Memory => b.memory_bytes.cmp(&a.memory_bytes),
+                SortBy::Name => Ordering::Equal,
+            }
+            .then_with(|| a.name.cmp(&b.name))
+        });
+        groups.truncate(self.max_processes);
+
+        let row = self
+            .selected_group
+            .as_ref()
+            .and_then(|name| groups.iter().position(|g| &g.name == name))
+            .unwrap_or_else(|| {
+                self.group_state
+                    .selected()
+                    .unwrap_or(0)
+                    .min(groups.len().saturating_sub(1))
+            });
+        self.group_state.select(Some(row));
+        self.selected_group = groups.get(row).map(|g| g.name.clone());
+        self.groups = Some(groups);
+    }
+
+    /// Open or close the group view
+    fn toggle_groups(&mut self) {
+        if self.groups.take().is_none() {
+            // Start on the selected process's group
+            self.selected_group = self
+                .table_state
+                .selected()
+                .and_then(|idx| self.processes.get(idx))
+                .map(|p| p.name.clone());
+            self.groups = Some(Vec::new());
+            self.sort_processes();
+        }
+    }
+
+    /// Move the selection by one row in whichever view is open
+    fn move_selection(&mut self, down: bool) {
+        let (selected, len) = match &self.groups {
+            Some(groups) => (self.group_state.selected(), groups.len()),
+            None => (self.table_state.selected(), self.processes.len()),
+        };
+        let i = selected.unwrap_or(0);
+        let next = if down {
+            (i + 1).min(len.saturating_sub(1))
+        } else {
+            i.saturating_sub(1)
+        };
+        match &self.groups {
+            Some(groups) => {
+                self.group_state.select(Some(next));
+                self.selected_group = groups.get(next).map(|g| g.name.clone());
+            }
+            None => self.select_row(next),
+        }
+    }
+
+    /// Add or update a process published on the event bus
+    fn apply_process(&mut self, process: &ProcessInfo) {
+        let entry = ProcessEntry::from_info(process);
+        match self.snapshot.iter_mut().find(|p| p.pid == process.pid) {
+            Some(existing) => *existing = entry,
+            None => self.snapshot.push(entry),
+        }
+    }
+
+    /// Re-sort now and, when live, collect a fresh list in the background
+    fn refresh(&mut self) {
+        self.sort_processes();
+        if self.injected.is_none() {
+            self.collector.request(WorkerPool::global());
+        }
+    }
+}
+
+/// Send `pid` SIGTERM, or terminate it where there are no signals
+fn terminate(pid: u32) -> std::io::Result<()> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    let process = system
+        .process(pid)
+        .ok_or_else(|| std::io::Error::other("no longer running"))?;
+    let sent = process
+        .kill_with(Signal::Term)
+        .unwrap_or_else(|| process.kill());
+    if sent {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
     }
 }
 
+/// The group view's table: instance count, name, combined CPU and memory
+fn render_groups(groups: &[ProcessGroup], state: &mut TableState, area: Rect, buf: &mut Buffer) {
+    let header = Row::new(
+        ["Count", "Name", "CPU%", "Memory"]
+            .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+    )
+    .height(1)
+    .bottom_margin(1);
+    let widths = [
+        Constraint::Length(6),
+        Constraint::Min(12),
+        Constraint::Length(6),
+        Constraint::Length(10),
+    ];
+    let name_width = column_width(&widths, 1, area.width, 3) as usize;
+    let rows = groups.iter().map(|group| {
+        Row::new([
+            Cell::from(group.count.to_string()),
+            Cell::from(truncate_to_width(&group.name, name_width)),
+            Cell::from(format!("{:.1}", group.cpu_percent)),
+            Cell::from(format_bytes(group.memory_bytes)),
+        ])
+    });
+    let table = Table::new(rows, widths)
+        .header(header)
+        .row_highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    ratatui::widgets::StatefulWidget::render(table, area, buf, state);
+}
+
+/// Read the process table (runs on a worker thread)
+fn collect_processes(source: &mut ProcessSource) -> Vec<ProcessEntry> {
+    let ProcessSource { system, previous } = source;
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_memory()
+            .with_cpu()
+            .with_cwd(UpdateKind::Always),
+    );
+    let entries: Vec<ProcessEntry> = system
+        .processes()
+        .iter()
+        .map(|(pid, process)| {
+            let pid = pid.as_u32();
+            let zombie = process.status() == ProcessStatus::Zombie;
+            let last = previous.get(&pid);
+            let name = match last {
+                Some(last) if *process.name() == *last.name => last.name.clone(),
+                _ => Arc::from(process.name().to_string_lossy()),
+            };
+            let cwd = match last {
+                Some(last) if last.cwd.as_deref() == process.cwd() => last.cwd.clone(),
+                _ => process.cwd().map(Arc::from),
+            };
+            ProcessEntry {
+                pid,
+                name,
+                cpu_percent: process.cpu_usage(),
+                memory_bytes: process.memory(),
+                start_time: process.start_time(),
+                cwd,
+                zombie,
+                // A zombie has released its descriptors
+                open_files: process.open_files().filter(|_| !zombie),
+                open_files_limit: process.open_files_limit(),
+            }
+        })
+        .collect();
+    previous.clear();
+    previous.extend(entries.iter().map(|entry| (entry.pid, entry.clone())));
+    entries
+}
+
 impl Widget for ProcessWidget {
     fn on_mount(&mut self) {
-        self.refresh_processes();
-        self.table_state.select(Some(0));
+        self.select_requests = Some(self.event_bus.subscribe(PROCESS_SELECT_TOPIC));
+
+        if !self.event_bus.is_live() {
+            // Display injected processes instead of polling
+            let (sub, rx) = self.event_bus.subscribe("system.process.top");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        self.hidden = hidden_processes();
+
+        // Collect a first list right away; CPU usage needs two samples, so
+        // the first one just primes the counters
+        self.collector.request(WorkerPool::global());
+        if let Some(snapshot) = self.collector.wait() {
+            self.apply_snapshot(snapshot);
+        }
+        self.collector.request(WorkerPool::global());
 
         // Subscribe to sort change events
         let (sub, _rx) = self.event_bus.subscribe("widget.process.sort");
@@ -114,46 +1081,170 @@ impl Widget for ProcessWidget {
     }
 
     fn on_update(&mut self, delta: Duration) {
+        let requested = self.select_requests.as_ref().and_then(|(_, rx)| {
+            rx.try_iter()
+                .filter_map(|e| e.payload.downcast::<Selection>())
+                .filter(|selection| selection.kind == "process")
+                .filter_map(|selection| selection.value.parse().ok())
+                .last()
+        });
+        if let Some(pid) = requested {
+            self.select_pid(pid);
+        }
+
+        if let Some(rx) = self.injected.clone() {
+            let mut changed = false;
+            for event in rx.try_iter() {
+                if let Some(process) = event.payload.downcast::<ProcessInfo>() {
+                    self.apply_process(&process);
+                    changed = true;
+                }
+            }
+            if changed {
+                self.last_injected = Instant::now();
+                self.sort_processes();
+                self.track_selected();
+            }
+            return;
+        }
+
+        if let Some(snapshot) = self.collector.try_take() {
+            self.apply_snapshot(snapshot);
+        }
+
         self.time_since_poll += delta;
 
         if self.time_since_poll >= self.poll_interval {
-            self.refresh_processes();
+            self.collector.request(WorkerPool::global());
             self.time_since_poll = Duration::ZERO;
         }
     }
 
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
     fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
-        use crossterm::event::KeyCode;
-
-        if let devdash_core::Event::Key(key) = event {
-            match key.code {
-                KeyCode::Down | KeyCode::Char('j') => {
-                    let i = self.table_state.selected().unwrap_or(0);
-                    if i < self.processes.len().saturating_sub(1) {
-                        self.table_state.select(Some(i + 1));
-                    }
+        if let devdash_core::Event::Linked(selection) = &event
+            && selection.kind == "disk"
+        {
+            self.scope = Some(PathBuf::from(&selection.value));
+            self.sort_processes();
+            return EventResult::Consumed;
+        }
+
+        // Priority and cores act on one process, not a group
+        if let devdash_core::Event::Action(action) = &event
+            && self.groups.is_some()
+            && matches!(
+                action.as_str(),
+                "raise_priority"
+                    | "lower_priority"
+                    | "kill"
+                    | "cores"
+                    | "core_prev"
+                    | "core_next"
+                    | "core_toggle"
+                    | "core_all"
+            )
+        {
+            return EventResult::Ignored;
+        }
+
+        if let devdash_core::Event::Action(action) = &event
+            && let Some(cores) = &mut self.cores
+        {
+            let handled = match action.as_str() {
+                "core_prev" => {
+                    cores.move_cursor(false);
+                    true
+                }
+                "core_next" => {
+                    cores.move_cursor(true);
+                    true
+                }
+                "core_toggle" => {
+                    cores.toggle();
+                    true
+                }
+                "core_all" => {
+                    cores.allow_all();
+                    true
+                }
+                _ => false,
+            };
+            if handled {
+                return EventResult::Consumed;
+            }
+        }
+
+        if let devdash_core::Event::Action(action) = event {
+            match action.as_str() {
+                "down" => {
+                    self.move_selection(true);
                     return EventResult::Consumed;
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    let i = self.table_state.selected().unwrap_or(0);
-                    if i > 0 {
-                        self.table_state.select(Some(i - 1));
-                    }
+                "up" => {
+                    self.move_selection(false);
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('c') => {
+                "group" => {
+                    self.toggle_groups();
+                    return EventResult::Consumed;
+                }
+                "sort_cpu" => {
                     self.sort_by = SortBy::Cpu;
-                    self.refresh_processes();
+                    self.refresh();
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('m') => {
+                "sort_memory" => {
                     self.sort_by = SortBy::Memory;
-                    self.refresh_processes();
+                    self.refresh();
                     return EventResult::Consumed;
                 }
-                KeyCode::Char('n') => {
+                "sort_name" => {
                     self.sort_by = SortBy::Name;
-                    self.refresh_processes();
+                    self.refresh();
+                    return EventResult::Consumed;
+                }
+                "toggle_detail" => {
+                    self.show_detail = !self.show_detail;
+                    return EventResult::Consumed;
+                }
+                "raise_priority" => {
+                    self.renice_selected(true);
+                    return EventResult::Consumed;
+                }
+                "lower_priority" => {
+                    self.renice_selected(false);
+                    return EventResult::Consumed;
+                }
+                "kill" => {
+                    self.kill_selected();
+                    return EventResult::Consumed;
+                }
+                "cores" => {
+                    self.cores = match (&self.cores, self.selected_pid) {
+                        (None, Some(pid)) => {
+                            self.show_detail = true;
+                            Some(CoreView::new(pid))
+                        }
+                        _ => None,
+                    };
+                    return EventResult::Consumed;
+                }
+
+                "clear_link" if self.scope.is_some() => {
+                    self.scope = None;
+                    self.sort_processes();
                     return EventResult::Consumed;
                 }
                 _ => {}
@@ -176,13 +1267,42 @@ impl Widget for ProcessWidget {
 
         let border_color = focus_color(focused);
 
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
-            .title(format!(" Processes [{}] ", sort_indicator))
+            .title(format!(
+                " Processes [{}]{}{} ",
+                sort_indicator,
+                if self.groups.is_some() {
+                    " grouped"
+                } else {
+                    ""
+                },
+                self.scope_label()
+            ))
             .title_alignment(ratatui::layout::Alignment::Left)
             .border_style(Style::default().fg(border_color));
+        if let Some(status) = &self.action_status {
+            let (text, color) = match status {
+                Ok(text) => (text, Color::Green),
+                Err(text) => (text, Color::Red),
+            };
+            block = block
+                .title_bottom(Line::from(format!(" {} ", text)).style(Style::default().fg(color)));
+        } else if let Some(hidden) = &self.hidden {
+            block = block.title_bottom(
+                Line::from(format!(" {} ", hidden)).style(Style::default().fg(theme().warning)),
+            );
+        }
 
-        let header_cells = ["PID", "Name", "CPU%", "Memory"]
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if let Some(groups) = &self.groups {
+            render_groups(groups, &mut self.group_state, inner, buf);
+            return;
+        }
+
+        let header_cells = ["PID", "Name", "CPU%", "Memory", "Uptime"]
             .iter()
             .map(|h| Cell::from(*h).style(Style::default().fg(Color::Yellow)));
         let header = Row::new(header_cells)
@@ -190,26 +1310,52 @@ impl Widget for ProcessWidget {
             .height(1)
             .bottom_margin(1);
 
+        let widths = [
+            Constraint::Length(7),
+            Constraint::Min(12),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(7),
+        ];
+        let name_width = column_width(&widths, 1, inner.width, 3) as usize;
+
         let rows = self.processes.iter().map(|proc| {
+            let fd_pressure = self.fd_pressure(proc.fd_percent());
+            // Long names are cut short, keeping the marks after them
+            let suffix = if proc.zombie {
+                " <defunct>".to_string()
+            } else if fd_pressure {
+                format!(" [fd {:.0}%]", proc.fd_percent().unwrap_or_default())
+            } else {
+                String::new()
+            };
+            let name = format!(
+                "{}{}",
+                truncate_to_width(&proc.name, name_width.saturating_sub(suffix.width())),
+                suffix
+            );
             let cells = vec![
                 Cell::from(proc.pid.to_string()),
-                Cell::from(proc.name.clone()),
+                Cell::from(name),
                 Cell::from(format!("{:.1}", proc.cpu_percent)),
                 Cell::from(format_bytes(proc.memory_bytes)),
+                Cell::from(proc.uptime().map_or("-".to_string(), format_duration)),
             ];
-            Row::new(cells).height(1)
+            let mark = if proc.zombie {
+                Some(Color::DarkGray)
+            } else if fd_pressure {
+                Some(Color::Magenta)
+            } else {
+                None
+            };
+            let style = highlight_color(&self.highlights, proc)
+                .or(mark)
+                .map_or(Style::default(), |color| Style::default().fg(color));
+            Row::new(cells).height(1).style(style)
         });
 
-        let widths = [
-            Constraint::Length(8),
-            Constraint::Min(20),
-            Constraint::Length(8),
-            Constraint::Length(12),
-        ];
-
         let table = Table::new(rows, widths)
             .header(header)
-            .block(block)
             .row_highlight_style(
                 Style::default()
                     .bg(Color::DarkGray)
@@ -217,10 +1363,339 @@ impl Widget for ProcessWidget {
             )
             .highlight_symbol(">> ");
 
-        ratatui::widgets::StatefulWidget::render(table, area, buf, &mut self.table_state);
+        // Only show the detail pane when the table keeps a useful height
+        let (table_area, detail_area) = if self.show_detail && inner.height >= DETAIL_HEIGHT * 2 {
+            let chunks = Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(DETAIL_HEIGHT)])
+                .split(inner);
+            (chunks[0], Some(chunks[1]))
+        } else {
+            (inner, None)
+        };
+
+        ratatui::widgets::StatefulWidget::render(table, table_area, buf, &mut self.table_state);
+
+        if let Some(detail_area) = detail_area {
+            self.render_detail(detail_area, buf);
+        }
     }
 
     fn needs_update(&self) -> bool {
         true
     }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        if let Some(pid) = self.selected_pid {
+            state.insert("pid".to_string(), i64::from(pid).into());
+        }
+        let sort = match self.sort_by {
+            SortBy::Cpu => "cpu",
+            SortBy::Memory => "memory",
+            SortBy::Name => "name",
+        };
+        state.insert("sort".to_string(), sort.into());
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        // The selection follows the PID if it is still running
+        restore_table(&mut self.table_state, state);
+        self.selected_pid = state
+            .get("pid")
+            .and_then(|v| v.as_integer())
+            .and_then(|pid| u32::try_from(pid).ok());
+        self.sort_by = match state.get("sort").and_then(|v| v.as_str()) {
+            Some("memory") => SortBy::Memory,
+            Some("name") => SortBy::Name,
+            _ => SortBy::Cpu,
+        };
+    }
+
+    fn summary(&self) -> Option<String> {
+        let sort = match self.sort_by {
+            SortBy::Cpu => "CPU",
+            SortBy::Memory => "memory",
+            SortBy::Name => "name",
+        };
+        let top: Vec<String> = match &self.groups {
+            Some(groups) => groups
+                .iter()
+                .take(3)
+                .map(|g| {
+                    format!(
+                        "{} ×{} {:.1}% CPU {}",
+                        g.name,
+                        g.count,
+                        g.cpu_percent,
+                        format_bytes(g.memory_bytes)
+                    )
+                })
+                .collect(),
+            None => self
+                .processes
+                .iter()
+                .take(3)
+                .map(|p| {
+                    format!(
+                        "{} {:.1}% CPU {}",
+                        p.name,
+                        p.cpu_percent,
+                        format_bytes(p.memory_bytes)
+                    )
+                })
+                .collect(),
+        };
+        let hidden = match &self.hidden {
+            Some(hidden) => format!("; {}", hidden),
+            None => String::new(),
+        };
+        Some(format!(
+            "{}{} by {}: {}{}",
+            if self.groups.is_some() {
+                "Process groups"
+            } else {
+                "Processes"
+            },
+            self.scope_label(),
+            sort,
+            if top.is_empty() {
+                "none".to_string()
+            } else {
+                top.join(", ")
+            },
+            hidden
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, name: &str, cpu_percent: f32) -> ProcessEntry {
+        ProcessEntry::from_info(&ProcessInfo {
+            pid,
+            name: name.to_string(),
+            cpu_percent,
+            memory_bytes: u64::from(pid) * 1024,
+            start_time: 0,
+            cwd: None,
+            zombie: false,
+            open_files: None,
+            open_files_limit: None,
+        })
+    }
+
+    fn widget(snapshot: Vec<ProcessEntry>, rows: usize) -> ProcessWidget {
+        let mut widget = ProcessWidget::new(EventBus::new(), Duration::from_secs(1));
+        widget.snapshot = snapshot;
+        widget.max_processes = rows;
+        widget
+    }
+
+    fn pids(widget: &ProcessWidget) -> Vec<u32> {
+        widget.processes.iter().map(|p| p.pid).collect()
+    }
+
+    /// 50 processes with CPU usage in a scrambled order, some equal
+    fn many() -> Vec<ProcessEntry> {
+        (1..=50)
+            .map(|pid| entry(pid, "worker", ((pid * 37) % 23) as f32))
+            .collect()
+    }
+
+    #[test]
+    fn test_top_rows_match_full_sort() {
+        for sort_by in [SortBy::Cpu, SortBy::Memory, SortBy::Name] {
+            let snapshot = many();
+            let mut widget = widget(snapshot.clone(), 10);
+            widget.sort_by = sort_by;
+            widget.sort_processes();
+
+            let mut all = snapshot;
+            all.sort_by(|a, b| {
+                match sort_by {
+                    SortBy::Cpu => b.cpu_percent.total_cmp(&a.cpu_percent),
+                    SortBy::Memory => b.memory_bytes.cmp(&a.memory_bytes),
+                    SortBy::Name => a.name.cmp(&b.name),
+                }
+                .then(a.pid.cmp(&b.pid))
+            });
+            let expected: Vec<u32> = all.iter().take(10).map(|p| p.pid).collect();
+            assert_eq!(pids(&widget), expected, "{:?}", sort_by);
+        }
+    }
+
+    #[test]
+    fn test_equal_cpu_goes_by_pid() {
+        let snapshot = vec![
+            entry(40, "b", 5.0),
+            entry(7, "c", 5.0),
+            entry(12, "a", 5.0),
+            entry(3, "d", 9.0),
+            entry(25, "e", 5.0),
+        ];
+        let mut widget = widget(snapshot.clone(), 3);
+        widget.sort_processes();
+        assert_eq!(pids(&widget), [3, 7, 12]);
+
+        // The same however the snapshot is ordered
+        let mut reversed = snapshot;
+        reversed.reverse();
+        widget.snapshot = reversed;
+        widget.sort_processes();
+        assert_eq!(pids(&widget), [3, 7, 12]);
+    }
+
+    #[test]
+    fn test_pinned_process_outside_top_rows() {
+        let mut widget = widget(many(), 5);
+        widget.sort_processes();
+        let top = pids(&widget);
+        // Among the least busy, far outside the top five
+        let quiet = many()
+            .into_iter()
+            .min_by(|a, b| a.cpu_percent.total_cmp(&b.cpu_percent))
+            .unwrap()
+            .pid;
+
+        widget.select_pid(quiet);
+        let rows = pids(&widget);
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[..4], top[..4]);
+        assert_eq!(rows[4], quiet);
+        assert_eq!(widget.table_state.selected(), Some(4));
+
+        // Selecting another row lets it go
+        widget.select_row(0);
+        widget.sort_processes();
+        assert_eq!(pids(&widget), top);
+    }
+
+    #[test]
+    fn test_no_rows() {
+        let mut widget = widget(many(), 0);
+        widget.pinned = Some(10);
+        widget.sort_processes();
+        assert!(widget.processes.is_empty());
+        assert_eq!(widget.selected_pid, None);
+    }
+
+    #[test]
+    fn test_groups_count_processes_out_of_view() {
+        let mut snapshot = many();
+        snapshot.push(entry(60, "postgres", 1.0));
+        let mut widget = widget(snapshot, 3);
+        widget.toggle_groups();
+
+        assert_eq!(widget.processes.len(), 3);
+        let groups = widget.groups.as_ref().unwrap();
+        let workers = groups.iter().find(|g| g.name == "worker").unwrap();
+        assert_eq!(workers.count, 50);
+        let cpu: f32 = many().iter().map(|p| p.cpu_percent).sum();
+        assert_eq!(workers.cpu_percent, cpu);
+        assert!(groups.iter().any(|g| g.name == "postgres"));
+    }
+
+    #[test]
+    fn test_names_are_reused_between_polls() {
+        let mut source = ProcessSource {
+            system: System::new(),
+            previous: HashMap::new(),
+        };
+        let pid = std::process::id();
+        let name = |entries: &[ProcessEntry]| {
+            entries
+                .iter()
+                .find(|p| p.pid == pid)
+                .map(|p| p.name.clone())
+                .unwrap()
+        };
+        let first = name(&collect_processes(&mut source));
+        let second = name(&collect_processes(&mut source));
+        assert_eq!(first, second);
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = ProcessHistory::new(1);
+        for i in 0..HISTORY_LEN + 5 {
+            history.push(i as f32, i as u64 * 1024);
+        }
+        assert_eq!(history.cpu.len(), HISTORY_LEN);
+        assert_eq!(history.memory.len(), HISTORY_LEN);
+        let (cpu, memory) = history.series();
+        assert_eq!((cpu[0], memory[0]), (5, 5 * 1024));
+        assert_eq!(cpu[HISTORY_LEN - 1], (HISTORY_LEN + 4) as u64);
+    }
+
+    #[test]
+    fn test_history_follows_selected_process() {
+        let mut widget = widget(
+            vec![
+                entry(1, "a", 30.0),
+                entry(2, "b", 20.0),
+                entry(3, "c", 10.0),
+            ],
+            10,
+        );
+        widget.sort_processes();
+        widget.select_row(1);
+        widget.track_selected();
+        widget.track_selected();
+        assert_eq!(widget.history.pid, 2);
+        assert_eq!(widget.history.cpu, [20, 20]);
+
+        // Another process selected starts over
+        widget.select_row(2);
+        assert_eq!((widget.history.pid, widget.history.cpu.len()), (3, 0));
+        widget.track_selected();
+        assert_eq!(widget.history.cpu, [10]);
+
+        // So does the selected process exiting, with the row below it
+        // selected in its place
+        widget.select_row(1);
+        widget.track_selected();
+        widget.snapshot.retain(|p| p.pid != 2);
+        widget.sort_processes();
+        widget.track_selected();
+        assert_eq!(widget.selected_pid, Some(3));
+        assert_eq!(widget.history.pid, 3);
+        assert_eq!(widget.history.cpu, [10]);
+    }
+
+    #[test]
+    fn test_selection_follows_pid_across_sorts() {
+        let mut widget = widget(
+            vec![
+                entry(1, "a", 30.0),
+                entry(2, "b", 20.0),
+                entry(3, "c", 10.0),
+            ],
+            10,
+        );
+        widget.sort_processes();
+        widget.select_row(2);
+        assert_eq!(widget.selected_pid, Some(3));
+
+        // Now the busiest
+        widget.snapshot[2].cpu_percent = 90.0;
+        widget.sort_processes();
+        assert_eq!(pids(&widget), [3, 1, 2]);
+        assert_eq!(widget.table_state.selected(), Some(0));
+        assert_eq!(widget.selected_pid, Some(3));
+
+        widget.sort_by = SortBy::Name;
+        widget.sort_processes();
+        assert_eq!(widget.table_state.selected(), Some(2));
+        assert_eq!(widget.selected_pid, Some(3));
+    }
 }
@@ -0,0 +1,202 @@
+// devdash-widgets/src/projection.rs
+//! Disk space trends and when each mount point will fill up.
+//!
+//! [`DiskTrends`] keeps a sample of each mount point's used space every
+//! [`SAMPLE_INTERVAL`] over the last [`TREND_WINDOW`], saved with the disk
+//! widget's session state so the history carries over between runs. A
+//! least-squares line through the samples gives the growth rate and, while
+//! it is positive, a [`DiskProjection`].
+use devdash_core::WidgetState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::common::format_bytes;
+
+/// Time between two samples of one mount point
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How far back the trend looks
+pub const TREND_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Samples must span this long before anything is projected
+const MIN_SPAN: Duration = Duration::from_secs(30 * 60);
+
+/// Projections further out than this are not worth showing
+const MAX_HORIZON: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// When a mount point will run out of space at its current growth rate,
+/// published on `system.disk.projection`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskProjection {
+    pub mount_point: String,
+    /// Growth of used space in bytes per day
+    pub bytes_per_day: f64,
+    /// Seconds until the available space is used up
+    pub seconds_until_full: u64,
+}
+
+impl DiskProjection {
+    pub fn time_until_full(&self) -> Duration {
+        Duration::from_secs(self.seconds_until_full)
+    }
+
+    /// Rough time left, e.g. `"~9 days"` or `"~5 hours"`
+    pub fn describe(&self) -> String {
+        let hours = self.seconds_until_full / 3600;
+        match hours {
+            0 => "<1 hour".to_string(),
+            1 => "~1 hour".to_string(),
+            2..48 => format!("~{} hours", hours),
+            _ => format!("~{} days", hours / 24),
+        }
+    }
+
+    /// E.g. `"/home full in ~9 days (+2.1 GiB/day)"`
+    pub fn message(&self) -> String {
+        format!(
+            "{} full in {} (+{}/day)",
+            self.mount_point,
+            self.describe(),
+            format_bytes(self.bytes_per_day as u64)
+        )
+    }
+}
+
+/// Used space over time per mount point, as `(unix seconds, used bytes)`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskTrends {
+    mounts: BTreeMap<String, Vec<(u64, u64)>>,
+}
+
+impl DiskTrends {
+    /// Read trends saved with [`save`](Self::save); anything unreadable
+    /// starts empty
+    pub fn restore(state: &WidgetState) -> Self {
+        state
+            .get("trends")
+            .and_then(|value| value.as_table())
+            .and_then(|table| table.clone().try_into().ok())
+            .unwrap_or_default()
+    }
+
+    /// Store the trends in `state` under `trends`
+    pub fn save(&self, state: &mut WidgetState) {
+        if let Ok(table) = WidgetState::try_from(self) {
+            state.insert("trends".to_string(), table.into());
+        }
+    }
+
+    /// Note that `mount_point` had `used` bytes in use at `now` (unix
+    /// seconds)
+    ///
+    /// Kept only if the last sample is at least [`SAMPLE_INTERVAL`] old;
+    /// samples older than [`TREND_WINDOW`] are dropped.
+    pub fn record(&mut self, mount_point: &str, used: u64, now: u64) {
+        let samples = self.mounts.entry(mount_point.to_string()).or_default();
+        if samples
+            .last()
+            .is_some_and(|&(at, _)| now.saturating_sub(at) < SAMPLE_INTERVAL.as_secs())
+        {
+            return;
+        }
+        samples.push((now, used));
+        let oldest = now.saturating_sub(TREND_WINDOW.as_secs());
+        samples.retain(|&(at, _)| at >= oldest);
+    }
+
+    /// Growth of used space on `mount_point` in bytes per second, from a
+    /// least-squares fit through its samples
+    pub fn growth_rate(&self, mount_point: &str) -> Option<f64> {
+        let samples = self.mounts.get(mount_point)?;
+        let (&(first, _), &(last, _)) = (samples.first()?, samples.last()?);
+        if last.saturating_sub(first) < MIN_SPAN.as_secs() {
+            return None;
+        }
+
+        // Relative to the first sample to keep the sums small
+        let points: Vec<(f64, f64)> = samples
+            .iter()
+            .map(|&(at, used)| ((at - first) as f64, used as f64))
+            .collect();
+        let n = points.len() as f64;
+        let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let mean_u = points.iter().map(|(_, u)| u).sum::<f64>() / n;
+        let (covariance, variance) = points.iter().fold((0.0, 0.0), |(c, v), (t, u)| {
+            (c + (t - mean_t) * (u - mean_u), v + (t - mean_t).powi(2))
+        });
+        (variance > 0.0).then(|| covariance / variance)
+    }
+
+    /// When `mount_point`, with `available` bytes left, fills up at its
+    /// current growth rate; `None` if it isn't growing or won't fill within
+    /// a year
+    pub fn projection(&self, mount_point: &str, available: u64) -> Option<DiskProjection> {
+        let rate = self.growth_rate(mount_point).filter(|&rate| rate > 0.0)?;
+        let seconds = available as f64 / rate;
+        (seconds <= MAX_HORIZON.as_secs_f64()).then(|| DiskProjection {
+            mount_point: mount_point.to_string(),
+            bytes_per_day: rate * 86_400.0,
+            seconds_until_full: seconds as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+    const HOUR: u64 = 3600;
+
+    #[test]
+    fn test_record_spaces_samples() {
+        let mut trends = DiskTrends::default();
+        trends.record("/", 10, 1_000);
+        trends.record("/", 20, 1_060);
+        trends.record("/", 30, 1_000 + SAMPLE_INTERVAL.as_secs());
+        assert_eq!(trends.mounts["/"], [(1_000, 10), (1_600, 30)]);
+
+        // Old samples fall out of the window
+        let later = 1_601 + TREND_WINDOW.as_secs();
+        trends.record("/", 40, later);
+        assert_eq!(trends.mounts["/"], [(later, 40)]);
+    }
+
+    #[test]
+    fn test_projection() {
+        let mut trends = DiskTrends::default();
+        // 1 GiB more every hour for a day
+        for hour in 0..=24 {
+            trends.record("/home", 100 * GIB + hour * GIB, hour * HOUR);
+        }
+        let rate = trends.growth_rate("/home").unwrap();
+        assert!((rate * HOUR as f64 - GIB as f64).abs() < 1.0);
+
+        let projection = trends.projection("/home", 216 * GIB).unwrap();
+        assert_eq!(projection.seconds_until_full, 216 * HOUR);
+        assert_eq!(projection.describe(), "~9 days");
+        assert_eq!(
+            projection.message(),
+            "/home full in ~9 days (+24.0 GiB/day)"
+        );
+
+        // Too little history, or shrinking
+        trends.record("/tmp", GIB, 0);
+        trends.record("/tmp", 2 * GIB, SAMPLE_INTERVAL.as_secs());
+        assert_eq!(trends.projection("/tmp", GIB), None);
+        for hour in 0..=4 {
+            trends.record("/var", 50 * GIB - hour * GIB, hour * HOUR);
+        }
+        assert_eq!(trends.projection("/var", GIB), None);
+    }
+
+    #[test]
+    fn test_trends_round_trip() {
+        let mut trends = DiskTrends::default();
+        trends.record("/", 5 * GIB, 1_000);
+        let mut state = WidgetState::new();
+        trends.save(&mut state);
+        assert_eq!(DiskTrends::restore(&state), trends);
+    }
+}
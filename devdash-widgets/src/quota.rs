@@ -0,0 +1,422 @@
+// devdash-widgets/src/quota.rs
+//! Transfer accounting against a data cap, for metered connections and
+//! egress budgets.
+//!
+//! A [`TransferLedger`] adds up what each interface sent and received today
+//! and in the current billing month from the OS byte counters. It is saved
+//! with the network widget's session state, and traffic between two runs is
+//! picked up from the counters as long as the machine didn't reboot.
+use chrono::{Datelike, Months, NaiveDate};
+use devdash_core::{WidgetSettings, WidgetState, parse_settings};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::common::{format_bytes, glob_match, parse_size};
+
+/// Span a quota applies to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaPeriod {
+    Daily,
+    #[default]
+    Monthly,
+}
+
+/// Traffic counted against a quota
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaDirection {
+    #[default]
+    Both,
+    Download,
+    Upload,
+}
+
+/// Bytes received and sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    pub rx: u64,
+    pub tx: u64,
+}
+
+impl Transfer {
+    pub fn total(&self) -> u64 {
+        self.rx + self.tx
+    }
+
+    fn add(&mut self, rx: u64, tx: u64) {
+        self.rx += rx;
+        self.tx += tx;
+    }
+
+    fn counted(&self, direction: QuotaDirection) -> u64 {
+        match direction {
+            QuotaDirection::Both => self.total(),
+            QuotaDirection::Download => self.rx,
+            QuotaDirection::Upload => self.tx,
+        }
+    }
+}
+
+/// Traffic of one interface in the current day and billing month
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceTransfer {
+    pub today: Transfer,
+    pub month: Transfer,
+    /// OS counters at the last sample, as `(received, transmitted)`
+    counters: Option<(u64, u64)>,
+}
+
+/// Day, billing month, and per-interface totals
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferLedger {
+    day: Option<NaiveDate>,
+    /// First day of the billing month
+    month: Option<NaiveDate>,
+    interfaces: BTreeMap<String, InterfaceTransfer>,
+}
+
+impl TransferLedger {
+    /// Read a ledger saved with [`save`](Self::save); anything unreadable
+    /// starts a new one
+    pub fn restore(state: &WidgetState) -> Self {
+        state
+            .get("transfer")
+            .and_then(|value| value.as_table())
+            .and_then(|table| table.clone().try_into().ok())
+            .unwrap_or_default()
+    }
+
+    /// Store the ledger in `state` under `transfer`
+    pub fn save(&self, state: &mut WidgetState) {
+        if let Ok(table) = WidgetState::try_from(self) {
+            state.insert("transfer".to_string(), table.into());
+        }
+    }
+
+    /// Count the traffic since the last sample of `interface`, given its OS
+    /// counters on `today`
+    ///
+    /// The first sample of an interface only sets the baseline. Counters
+    /// lower than the last sample were reset by a reboot, and count from
+    /// zero.
+    pub fn record(
+        &mut self,
+        interface: &str,
+        received: u64,
+        transmitted: u64,
+        today: NaiveDate,
+        reset_day: u32,
+    ) {
+        self.roll_over(today, reset_day);
+        let entry = self.interfaces.entry(interface.to_string()).or_default();
+        if let Some((last_rx, last_tx)) = entry.counters {
+            let delta = |now: u64, last: u64| if now >= last { now - last } else { now };
+            let (rx, tx) = (delta(received, last_rx), delta(transmitted, last_tx));
+            entry.today.add(rx, tx);
+            entry.month.add(rx, tx);
+        }
+        entry.counters = Some((received, transmitted));
+    }
+
+    /// Start new totals when the day or the billing month changed
+    fn roll_over(&mut self, today: NaiveDate, reset_day: u32) {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.interfaces
+                .values_mut()
+                .for_each(|i| i.today = Transfer::default());
+        }
+        let month = month_start(today, reset_day);
+        if self.month != Some(month) {
+            self.month = Some(month);
+            self.interfaces
+                .values_mut()
+                .for_each(|i| i.month = Transfer::default());
+        }
+    }
+
+    pub fn interface(&self, name: &str) -> Option<&InterfaceTransfer> {
+        self.interfaces.get(name)
+    }
+
+    /// Start of the billing month, once anything was recorded
+    pub fn month_start(&self) -> Option<NaiveDate> {
+        self.month
+    }
+}
+
+/// First day of the billing month containing `today`, for months that
+/// start on `reset_day`
+pub fn month_start(today: NaiveDate, reset_day: u32) -> NaiveDate {
+    let this_month = today.with_day(reset_day).unwrap_or(today);
+    if today.day() >= reset_day {
+        this_month
+    } else {
+        this_month - Months::new(1)
+    }
+}
+
+/// Quota settings as written in the config
+#[derive(Debug, Deserialize)]
+struct QuotaSettings {
+    #[serde(default)]
+    quota: Option<String>,
+    #[serde(default)]
+    quota_period: QuotaPeriod,
+    #[serde(default = "default_warn")]
+    quota_warn: f64,
+    #[serde(default = "default_reset_day")]
+    quota_reset_day: u32,
+    #[serde(default)]
+    quota_direction: QuotaDirection,
+    /// Glob pattern of the interfaces counted; all listed ones if unset
+    #[serde(default)]
+    quota_interface: Option<String>,
+}
+
+fn default_warn() -> f64 {
+    80.0
+}
+
+fn default_reset_day() -> u32 {
+    1
+}
+
+/// A transfer cap and when to warn about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quota {
+    /// Bytes allowed per period
+    pub limit: u64,
+    pub period: QuotaPeriod,
+    /// Percentage of the limit at which to warn
+    pub warn_percent: f64,
+    /// Day of the month a monthly quota starts over (1-28)
+    pub reset_day: u32,
+    pub direction: QuotaDirection,
+    /// Glob pattern of the interfaces counted; all listed ones if unset
+    pub interface: Option<String>,
+}
+
+impl Quota {
+    /// Read the `quota*` keys of the network widget's settings; `None`
+    /// without a `quota`
+    pub fn from_settings(settings: &WidgetSettings) -> Result<Option<Self>, String> {
+        let settings: QuotaSettings = parse_settings(settings)?;
+        let Some(limit) = settings.quota else {
+            return Ok(None);
+        };
+        let limit = parse_size(&limit)? as u64;
+        if limit == 0 {
+            return Err("quota must be above zero".to_string());
+        }
+        if !(0.0..=100.0).contains(&settings.quota_warn) {
+            return Err(format!(
+                "quota_warn must be a percentage, got {}",
+                settings.quota_warn
+            ));
+        }
+        if !(1..=28).contains(&settings.quota_reset_day) {
+            return Err(format!(
+                "quota_reset_day must be between 1 and 28, got {}",
+                settings.quota_reset_day
+            ));
+        }
+        Ok(Some(Self {
+            limit,
+            period: settings.quota_period,
+            warn_percent: settings.quota_warn,
+            reset_day: settings.quota_reset_day,
+            direction: settings.quota_direction,
+            interface: settings.quota_interface,
+        }))
+    }
+
+    /// Whether traffic on `interface` counts against the quota
+    pub fn counts(&self, interface: &str) -> bool {
+        self.interface
+            .as_deref()
+            .is_none_or(|pattern| glob_match(pattern, interface))
+    }
+
+    /// Bytes used so far this period on the `interfaces` the quota counts
+    pub fn used<'a>(
+        &self,
+        ledger: &TransferLedger,
+        interfaces: impl IntoIterator<Item = &'a String>,
+    ) -> u64 {
+        interfaces
+            .into_iter()
+            .filter(|name| self.counts(name))
+            .filter_map(|name| ledger.interface(name))
+            .map(|transfer| match self.period {
+                QuotaPeriod::Daily => transfer.today.counted(self.direction),
+                QuotaPeriod::Monthly => transfer.month.counted(self.direction),
+            })
+            .sum()
+    }
+
+    /// Where `used` bytes stand against the limit
+    pub fn usage(&self, used: u64) -> QuotaUsage {
+        let percentage = used as f64 * 100.0 / self.limit as f64;
+        QuotaUsage {
+            used,
+            limit: self.limit,
+            percentage,
+            period: self.period,
+            warn_percent: self.warn_percent,
+        }
+    }
+}
+
+/// Quota consumption, published on `system.network.quota` once it passes
+/// the warning threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub used: u64,
+    pub limit: u64,
+    /// Share of the limit used (may exceed 100.0)
+    pub percentage: f64,
+    pub period: QuotaPeriod,
+    pub warn_percent: f64,
+}
+
+impl QuotaUsage {
+    pub fn is_warning(&self) -> bool {
+        self.percentage >= self.warn_percent
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.used >= self.limit
+    }
+
+    /// E.g. `"312.0 GiB of 500.0 GiB this month (62%)"`
+    pub fn describe(&self) -> String {
+        format!(
+            "{} of {} {} ({:.0}%)",
+            format_bytes(self.used),
+            format_bytes(self.limit),
+            match self.period {
+                QuotaPeriod::Daily => "today",
+                QuotaPeriod::Monthly => "this month",
+            },
+            self.percentage
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_month_start() {
+        assert_eq!(month_start(date(2026, 10, 17), 1), date(2026, 10, 1));
+        assert_eq!(month_start(date(2026, 10, 17), 20), date(2026, 9, 20));
+        assert_eq!(month_start(date(2026, 1, 5), 15), date(2025, 12, 15));
+        assert_eq!(month_start(date(2026, 10, 20), 20), date(2026, 10, 20));
+    }
+
+    #[test]
+    fn test_ledger_counts_deltas_and_rolls_over() {
+        let mut ledger = TransferLedger::default();
+        ledger.record("eth0", 1000, 500, date(2026, 10, 30), 1);
+        assert_eq!(ledger.interface("eth0").unwrap().month, Transfer::default());
+
+        ledger.record("eth0", 1600, 700, date(2026, 10, 30), 1);
+        ledger.record("eth0", 2000, 800, date(2026, 10, 31), 1);
+        let eth0 = ledger.interface("eth0").unwrap();
+        assert_eq!(eth0.today, Transfer { rx: 400, tx: 100 });
+        assert_eq!(eth0.month, Transfer { rx: 1000, tx: 300 });
+
+        // New month, and a reboot reset the counters
+        ledger.record("eth0", 50, 10, date(2026, 11, 1), 1);
+        let eth0 = ledger.interface("eth0").unwrap();
+        assert_eq!(eth0.today, Transfer { rx: 50, tx: 10 });
+        assert_eq!(eth0.month, Transfer { rx: 50, tx: 10 });
+        assert_eq!(ledger.month_start(), Some(date(2026, 11, 1)));
+    }
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let mut ledger = TransferLedger::default();
+        ledger.record("eth0", 1000, 500, date(2026, 10, 17), 1);
+        ledger.record("eth0", 3000, 900, date(2026, 10, 17), 1);
+
+        let mut state = WidgetState::new();
+        ledger.save(&mut state);
+        assert_eq!(TransferLedger::restore(&state), ledger);
+        assert_eq!(
+            TransferLedger::restore(&WidgetState::new()),
+            TransferLedger::default()
+        );
+    }
+
+    fn settings(pairs: &[(&str, &str)]) -> WidgetSettings {
+        let mut settings = WidgetSettings::new();
+        for (key, value) in pairs {
+            settings.insert(key.to_string(), value.to_string().into());
+        }
+        settings
+    }
+
+    #[test]
+    fn test_quota_settings() {
+        assert_eq!(
+            Quota::from_settings(&settings(&[("interface", "eth0")])),
+            Ok(None)
+        );
+
+        let quota = Quota::from_settings(&settings(&[
+            ("quota", "500GB"),
+            ("quota_period", "daily"),
+            ("quota_direction", "upload"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(quota.limit, 500_000_000_000);
+        assert_eq!(quota.period, QuotaPeriod::Daily);
+        assert_eq!(quota.direction, QuotaDirection::Upload);
+        assert_eq!(quota.warn_percent, 80.0);
+
+        let mut late_reset = settings(&[("quota", "1GB")]);
+        late_reset.insert("quota_reset_day".to_string(), 31.into());
+        assert!(
+            Quota::from_settings(&late_reset)
+                .unwrap_err()
+                .contains("1 and 28")
+        );
+    }
+
+    #[test]
+    fn test_quota_usage() {
+        let mut ledger = TransferLedger::default();
+        for (name, rx, tx) in [("eth0", 600, 200), ("wlan0", 100, 100)] {
+            ledger.record(name, 0, 0, date(2026, 10, 17), 1);
+            ledger.record(name, rx, tx, date(2026, 10, 17), 1);
+        }
+        let interfaces = ["eth0".to_string(), "wlan0".to_string()];
+        let mut quota = Quota {
+            limit: 1000,
+            period: QuotaPeriod::Monthly,
+            warn_percent: 80.0,
+            reset_day: 1,
+            direction: QuotaDirection::Both,
+            interface: None,
+        };
+        let usage = quota.usage(quota.used(&ledger, &interfaces));
+        assert_eq!(usage.used, 1000);
+        assert!(usage.is_warning() && usage.is_exceeded());
+        assert_eq!(usage.describe(), "1000 B of 1000 B this month (100%)");
+
+        quota.interface = Some("eth*".to_string());
+        quota.direction = QuotaDirection::Download;
+        let usage = quota.usage(quota.used(&ledger, &interfaces));
+        assert_eq!(usage.used, 600);
+        assert!(!usage.is_warning());
+    }
+}
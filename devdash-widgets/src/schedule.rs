@@ -0,0 +1,581 @@
+// devdash-widgets/src/schedule.rs
+use chrono::Local;
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WorkerPool,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    process::Command,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::common::{focus_color, format_duration, format_relative_time};
+use crate::cron::parse_crontab;
+use crate::platform::systemd::{self, UnitProperties, parse_timestamp};
+
+/// Where a job is scheduled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobSource {
+    /// The user's crontab
+    Crontab,
+    /// A timer of the system manager
+    Timer,
+    /// A timer of the user's own manager
+    UserTimer,
+}
+
+impl JobSource {
+    fn label(self) -> &'static str {
+        match self {
+            JobSource::Crontab => "cron",
+            JobSource::Timer => "timer",
+            JobSource::UserTimer => "user",
+        }
+    }
+}
+
+/// How the last run of a job went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobResult {
+    Success,
+    /// Still going
+    Running,
+    /// Why it failed, e.g. `exit 1` or `timeout`
+    Failed(String),
+}
+
+/// A job that runs on a schedule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    /// Timer unit, or the command of a crontab line
+    pub name: String,
+    pub source: JobSource,
+    pub next_run: Option<SystemTime>,
+    /// When it last ran; for crontab jobs, when it was last due
+    pub last_run: Option<SystemTime>,
+    /// `None` where unknown, as for crontab jobs, or before the first run
+    pub result: Option<JobResult>,
+}
+
+impl ScheduledJob {
+    pub fn failed(&self) -> bool {
+        matches!(self.result, Some(JobResult::Failed(_)))
+    }
+}
+
+/// Jobs due to run, soonest first; jobs with no next run come last
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobSchedule {
+    pub jobs: Vec<ScheduledJob>,
+}
+
+impl JobSchedule {
+    pub fn new(mut jobs: Vec<ScheduledJob>) -> Self {
+        jobs.sort_by(|a, b| {
+            (a.next_run.is_none(), a.next_run, &a.name).cmp(&(
+                b.next_run.is_none(),
+                b.next_run,
+                &b.name,
+            ))
+        });
+        Self { jobs }
+    }
+
+    /// Failed jobs whose failure isn't in `previous`: a different last run,
+    /// or a job that wasn't failing there
+    pub fn new_failures<'a>(
+        &'a self,
+        previous: &'a JobSchedule,
+    ) -> impl Iterator<Item = &'a ScheduledJob> {
+        self.jobs.iter().filter(move |job| {
+            job.failed()
+                && !previous.jobs.iter().any(|old| {
+                    old.failed()
+                        && old.name == job.name
+                        && old.source == job.source
+                        && old.last_run == job.last_run
+                })
+        })
+    }
+}
+
+/// Schedule widget settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ScheduleOptions {
+    /// Crontab file to read instead of the output of `crontab -l`
+    pub crontab: Option<PathBuf>,
+    /// Whether to list systemd timers, both system and user ones
+    pub timers: bool,
+}
+
+impl Default for ScheduleOptions {
+    fn default() -> Self {
+        Self {
+            crontab: None,
+            timers: true,
+        }
+    }
+}
+
+/// Properties read from each timer
+const TIMER_PROPERTIES: [&str; 4] = ["Id", "Unit", "NextElapseUSecRealtime", "LastTriggerUSec"];
+
+/// Properties read from the service each timer starts
+const SERVICE_PROPERTIES: [&str; 4] = ["Id", "ActiveState", "Result", "ExecMainStatus"];
+
+/// Jobs for `timers` and the `services` they start
+///
+/// A service still activating is running; otherwise its `Result` is how the
+/// last run went. Timers that never fired have no result.
+pub fn timer_jobs(
+    timers: &[UnitProperties],
+    services: &[UnitProperties],
+    source: JobSource,
+) -> Vec<ScheduledJob> {
+    let services: HashMap<&str, &UnitProperties> = services
+        .iter()
+        .filter_map(|service| Some((service.get("Id")?.as_str(), service)))
+        .collect();
+    let property = |unit: &UnitProperties, name: &str| -> String {
+        unit.get(name).cloned().unwrap_or_default()
+    };
+
+    timers
+        .iter()
+        .filter_map(|timer| {
+            let name = timer.get("Id")?.clone();
+            let last_run = parse_timestamp(&property(timer, "LastTriggerUSec"));
+            let service = timer
+                .get("Unit")
+                .and_then(|unit| services.get(unit.as_str()));
+            let result = match service {
+                Some(service) if property(service, "ActiveState") == "activating" => {
+                    Some(JobResult::Running)
+                }
+                _ if last_run.is_none() => None,
+                Some(service) => match property(service, "Result").as_str() {
+                    "" => None,
+                    "success" => Some(JobResult::Success),
+                    "exit-code" => Some(JobResult::Failed(format!(
+                        "exit {}",
+                        property(service, "ExecMainStatus")
+                    ))),
+                    other => Some(JobResult::Failed(other.to_string())),
+                },
+                None => None,
+            };
+            Some(ScheduledJob {
+                name,
+                source,
+                next_run: parse_timestamp(&property(timer, "NextElapseUSecRealtime")),
+                last_run,
+                result,
+            })
+        })
+        .collect()
+}
+
+/// Timers of the system manager, or of the user's own
+fn read_timers(user: bool) -> Vec<ScheduledJob> {
+    let timers = systemd::show(user, &["*.timer"], &TIMER_PROPERTIES);
+    let units: Vec<&str> = timers
+        .iter()
+        .filter_map(|timer| timer.get("Unit"))
+        .map(String::as_str)
+        .collect();
+    let services = systemd::show(user, &units, &SERVICE_PROPERTIES);
+    let source = if user {
+        JobSource::UserTimer
+    } else {
+        JobSource::Timer
+    };
+    timer_jobs(&timers, &services, source)
+}
+
+/// Jobs in the crontab, with when each is next and was last due
+fn read_crontab(crontab: Option<&PathBuf>) -> Vec<ScheduledJob> {
+    let text = match crontab {
+        Some(path) => fs::read_to_string(path).unwrap_or_default(),
+        // Fails when the user has no crontab or there is no cron
+        None => match Command::new("crontab").arg("-l").output() {
+            Ok(output) if output.status.success() => {
+                String::from_utf8_lossy(&output.stdout).into_owned()
+            }
+            _ => String::new(),
+        },
+    };
+
+    let now = Local::now();
+    parse_crontab(&text)
+        .into_iter()
+        .map(|job| ScheduledJob {
+            next_run: job.schedule.next_after(now).map(SystemTime::from),
+            last_run: job.schedule.previous_before(now).map(SystemTime::from),
+            name: job.command,
+            source: JobSource::Crontab,
+            result: None,
+        })
+        .collect()
+}
+
+/// Read the crontab and timers (runs on a worker thread)
+fn read_schedule(options: &mut ScheduleOptions) -> JobSchedule {
+    let mut jobs = read_crontab(options.crontab.as_ref());
+    if options.timers {
+        jobs.extend(read_timers(false));
+        jobs.extend(read_timers(true));
+    }
+    JobSchedule::new(jobs)
+}
+
+/// Scheduled jobs widget
+///
+/// Lists the jobs in the user's crontab and the systemd timers, system and
+/// user, soonest first, with a countdown to each one's next run and how its
+/// last run went. cron doesn't record results, so crontab jobs show when
+/// they were last due instead.
+///
+/// # Events
+/// - `system.schedule` - The [`JobSchedule`], every poll
+/// - `system.job.failed` - A [`ScheduledJob`] whose last run failed, once
+///   per failed run (including failures found by the first poll)
+///
+/// When the event bus is not live, `system.schedule` events are shown
+/// instead of reading the crontab and timers.
+pub struct ScheduleWidget {
+    collector: Collector<ScheduleOptions, JobSchedule>,
+    schedule: JobSchedule,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl ScheduleWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, ScheduleOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings(settings)?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: ScheduleOptions,
+    ) -> Self {
+        Self {
+            collector: Collector::new(options, read_schedule),
+            schedule: JobSchedule::default(),
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_schedule(&mut self, schedule: JobSchedule) {
+        for job in schedule.new_failures(&self.schedule) {
+            self.event_bus
+                .publish(Event::new("system.job.failed", job.clone()));
+        }
+        self.event_bus
+            .publish(Event::new("system.schedule", schedule.clone()));
+        self.schedule = schedule;
+    }
+}
+
+fn result_cell(result: Option<&JobResult>) -> Cell<'static> {
+    match result {
+        Some(JobResult::Success) => Cell::from("ok").style(Style::default().fg(theme().good)),
+        Some(JobResult::Running) => Cell::from("running"),
+        Some(JobResult::Failed(reason)) => {
+            Cell::from(reason.clone()).style(Style::default().fg(theme().critical))
+        }
+        None => Cell::from("-"),
+    }
+}
+
+impl Widget for ScheduleWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected schedules instead of reading the jobs
+            let (sub, rx) = self.event_bus.subscribe("system.schedule");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        self.collector.request(WorkerPool::global());
+        if let Some(schedule) = self.collector.wait() {
+            self.apply_schedule(schedule);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<JobSchedule>())
+                .last();
+            if let Some(schedule) = latest {
+                self.schedule = JobSchedule::clone(&schedule);
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(schedule) = self.collector.try_take() {
+            self.apply_schedule(schedule);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let failed = self.schedule.jobs.iter().filter(|j| j.failed()).count();
+        let title = match failed {
+            0 => format!(" Schedule [{} jobs] ", self.schedule.jobs.len()),
+            _ => format!(
+                " Schedule [{} jobs, {} failed] ",
+                self.schedule.jobs.len(),
+                failed
+            ),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+
+        let now = SystemTime::now();
+        let rows = self.schedule.jobs.iter().map(|job| {
+            let next = match job.next_run {
+                Some(next) => match next.duration_since(now) {
+                    Ok(left) => format!("in {}", format_duration(left)),
+                    Err(_) => "due".to_string(),
+                },
+                None => "-".to_string(),
+            };
+            Row::new(vec![
+                Cell::from(job.name.clone()),
+                Cell::from(job.source.label()),
+                Cell::from(next),
+                Cell::from(job.last_run.map_or("-".to_string(), format_relative_time)),
+                result_cell(job.result.as_ref()),
+            ])
+        });
+        let header = Row::new(
+            ["Job", "Type", "Next", "Last", "Result"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        Table::new(
+            rows,
+            [
+                Constraint::Min(16),
+                Constraint::Length(5),
+                Constraint::Length(10),
+                Constraint::Length(15),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .render(area, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mut summary = format!("Schedule: {} jobs", self.schedule.jobs.len());
+        if let Some((job, next)) = self
+            .schedule
+            .jobs
+            .iter()
+            .find_map(|job| Some((job, job.next_run?)))
+        {
+            let left = next
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO);
+            summary.push_str(&format!("; next {} in {}", job.name, format_duration(left)));
+        }
+        let failed: Vec<&str> = self
+            .schedule
+            .jobs
+            .iter()
+            .filter(|j| j.failed())
+            .map(|j| j.name.as_str())
+            .collect();
+        if !failed.is_empty() {
+            summary.push_str(&format!("; failed: {}", failed.join(", ")));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn unit(properties: &[(&str, &str)]) -> UnitProperties {
+        properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn job(name: &str, last_run: u64, result: Option<JobResult>) -> ScheduledJob {
+        ScheduledJob {
+            name: name.to_string(),
+            source: JobSource::Timer,
+            next_run: None,
+            last_run: Some(UNIX_EPOCH + Duration::from_secs(last_run)),
+            result,
+        }
+    }
+
+    #[test]
+    fn test_timer_jobs() {
+        let timers = [
+            unit(&[
+                ("Id", "backup.timer"),
+                ("Unit", "backup.service"),
+                ("NextElapseUSecRealtime", "@1714532400"),
+                ("LastTriggerUSec", "@1714446000"),
+            ]),
+            unit(&[
+                ("Id", "fstrim.timer"),
+                ("Unit", "fstrim.service"),
+                ("NextElapseUSecRealtime", "@1714600000"),
+                ("LastTriggerUSec", "n/a"),
+            ]),
+            unit(&[
+                ("Id", "reindex.timer"),
+                ("Unit", "reindex.service"),
+                ("LastTriggerUSec", "@1714446000"),
+            ]),
+        ];
+        let services = [
+            unit(&[
+                ("Id", "backup.service"),
+                ("ActiveState", "inactive"),
+                ("Result", "exit-code"),
+                ("ExecMainStatus", "2"),
+            ]),
+            unit(&[("Id", "fstrim.service"), ("Result", "success")]),
+            unit(&[("Id", "reindex.service"), ("ActiveState", "activating")]),
+        ];
+
+        let jobs = timer_jobs(&timers, &services, JobSource::Timer);
+        assert_eq!(
+            jobs[0],
+            ScheduledJob {
+                name: "backup.timer".to_string(),
+                source: JobSource::Timer,
+                next_run: Some(UNIX_EPOCH + Duration::from_secs(1_714_532_400)),
+                last_run: Some(UNIX_EPOCH + Duration::from_secs(1_714_446_000)),
+                result: Some(JobResult::Failed("exit 2".to_string())),
+            }
+        );
+        // Never fired
+        assert_eq!(jobs[1].result, None);
+        assert_eq!(jobs[2].result, Some(JobResult::Running));
+        assert_eq!(jobs[2].next_run, None);
+    }
+
+    #[test]
+    fn test_new_failures() {
+        let failed = || Some(JobResult::Failed("exit 1".to_string()));
+        let before = JobSchedule::new(vec![
+            job("backup.timer", 100, failed()),
+            job("logrotate.timer", 100, Some(JobResult::Success)),
+        ]);
+        let after = JobSchedule::new(vec![
+            // Same failed run as before
+            job("backup.timer", 100, failed()),
+            job("logrotate.timer", 200, failed()),
+        ]);
+        let new: Vec<&str> = after
+            .new_failures(&before)
+            .map(|j| j.name.as_str())
+            .collect();
+        assert_eq!(new, ["logrotate.timer"]);
+
+        let rerun = JobSchedule::new(vec![job("backup.timer", 200, failed())]);
+        assert_eq!(rerun.new_failures(&after).count(), 1);
+        assert_eq!(before.new_failures(&JobSchedule::default()).count(), 1);
+    }
+
+    #[test]
+    fn test_options() {
+        let mut settings = WidgetSettings::new();
+        settings.insert("crontab".to_string(), "/etc/cron.d/backup".into());
+        settings.insert("timers".to_string(), false.into());
+        let options: ScheduleOptions = parse_settings(&settings).unwrap();
+        assert_eq!(options.crontab, Some(PathBuf::from("/etc/cron.d/backup")));
+        assert!(!options.timers);
+        assert!(ScheduleOptions::default().timers);
+    }
+}
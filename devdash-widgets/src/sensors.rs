@@ -0,0 +1,443 @@
+// devdash-widgets/src/sensors.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WorkerPool,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::common::{focus_color, glob_match};
+use crate::platform::{SensorKind, SensorReading, Sensors};
+
+/// Critical temperature of sensors that don't report one, in °C
+pub const DEFAULT_CRITICAL: f32 = 90.0;
+
+/// How far below its critical temperature a sensor is shown as warm, in °C
+pub const WARM_MARGIN: f32 = 10.0;
+
+/// One temperature sensor's latest reading, in °C
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Temperature {
+    pub label: String,
+    pub celsius: f32,
+    /// Highest temperature read since the widget started
+    pub max: f32,
+    /// Temperature at which the sensor counts as critical: the widget's
+    /// `critical` setting, or what the hardware reports
+    pub critical: f32,
+}
+
+impl Temperature {
+    pub fn is_critical(&self) -> bool {
+        self.celsius >= self.critical
+    }
+
+    /// Within [`WARM_MARGIN`] of the critical temperature
+    pub fn is_warm(&self) -> bool {
+        self.celsius >= self.critical - WARM_MARGIN
+    }
+
+    fn color(&self) -> Color {
+        if self.is_critical() {
+            theme().critical
+        } else if self.is_warm() {
+            theme().warning
+        } else {
+            theme().good
+        }
+    }
+}
+
+/// Sensors widget settings
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SensorOptions {
+    /// Critical temperature in °C for every sensor, instead of the one the
+    /// hardware reports
+    pub critical: Option<f32>,
+    /// Glob patterns of sensor labels to hide
+    pub exclude: Vec<String>,
+}
+
+/// Read every sensor not hidden by `exclude` (runs on a worker thread)
+fn read_sensors((sensors, exclude): &mut (Sensors, Vec<String>)) -> Vec<SensorReading> {
+    sensors
+        .read()
+        .into_iter()
+        .filter(|r| !exclude.iter().any(|p| glob_match(p, &r.label)))
+        .collect()
+}
+
+fn format_celsius(celsius: f32) -> String {
+    format!("{:.0}°C", celsius)
+}
+
+/// Temperature sensors widget
+///
+/// Lists the machine's temperature sensors (CPU packages and cores, GPUs,
+/// NVMe drives, ...) with their current and highest temperatures, followed
+/// by its fans where the platform reports them. Sensors within
+/// [`WARM_MARGIN`] of their critical temperature are shown in the warning
+/// color, and at or above it in the critical color. The critical
+/// temperature is the one the hardware reports, [`DEFAULT_CRITICAL`]
+/// without one, or the `critical` setting for every sensor.
+///
+/// # Events
+/// - `system.temperature` - The [`Temperature`] of each sensor, every poll
+/// - `system.temperature.critical` - The [`Temperature`] of each sensor at
+///   or above its critical temperature, every poll while it is
+///
+/// When the event bus is not live, `system.temperature` events are shown
+/// instead of reading the sensors.
+pub struct SensorsWidget {
+    collector: Collector<(Sensors, Vec<String>), Vec<SensorReading>>,
+    temperatures: Vec<Temperature>,
+    /// Fan speeds in RPM
+    fans: Vec<SensorReading>,
+    /// Highest temperature read from each sensor
+    maxima: HashMap<String, f32>,
+    critical: Option<f32>,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl SensorsWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, SensorOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<SensorOptions>(settings)?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: SensorOptions,
+    ) -> Self {
+        Self {
+            collector: Collector::new((Sensors::new(), options.exclude), read_sensors),
+            temperatures: Vec::new(),
+            fans: Vec::new(),
+            maxima: HashMap::new(),
+            critical: options.critical,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_readings(&mut self, readings: Vec<SensorReading>) {
+        let (temperatures, fans): (Vec<_>, Vec<_>) = readings
+            .into_iter()
+            .partition(|r| r.kind == SensorKind::Temperature);
+        self.fans = fans;
+        self.temperatures = temperatures
+            .into_iter()
+            .map(|reading| {
+                let max = self
+                    .maxima
+                    .entry(reading.label.clone())
+                    .and_modify(|max| *max = max.max(reading.value))
+                    .or_insert(reading.value);
+                Temperature {
+                    celsius: reading.value,
+                    max: *max,
+                    critical: self
+                        .critical
+                        .or(reading.critical)
+                        .unwrap_or(DEFAULT_CRITICAL),
+                    label: reading.label,
+                }
+            })
+            .collect();
+
+        for temperature in &self.temperatures {
+            self.event_bus
+                .publish(Event::new("system.temperature", temperature.clone()));
+            if temperature.is_critical() {
+                self.event_bus.publish(Event::new(
+                    "system.temperature.critical",
+                    temperature.clone(),
+                ));
+            }
+        }
+    }
+
+    fn hottest(&self) -> Option<&Temperature> {
+        self.temperatures
+            .iter()
+            .max_by(|a, b| a.celsius.total_cmp(&b.celsius))
+    }
+}
+
+impl Widget for SensorsWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected readings instead of reading the sensors
+            let (sub, rx) = self.event_bus.subscribe("system.temperature");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        self.collector.request(WorkerPool::global());
+        if let Some(readings) = self.collector.wait() {
+            self.apply_readings(readings);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let mut received = false;
+            for temperature in rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<Temperature>())
+            {
+                match self
+                    .temperatures
+                    .iter_mut()
+                    .find(|t| t.label == temperature.label)
+                {
+                    Some(existing) => *existing = Temperature::clone(&temperature),
+                    None => self.temperatures.push(Temperature::clone(&temperature)),
+                }
+                received = true;
+            }
+            if received {
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(readings) = self.collector.try_take() {
+            self.apply_readings(readings);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            // Skipped while the previous read is still in flight
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let title = match self.hottest() {
+            Some(hottest) => format!(" Sensors [hottest {}] ", format_celsius(hottest.celsius)),
+            None => " Sensors ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+
+        if self.temperatures.is_empty() && self.fans.is_empty() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            Paragraph::new("No temperature sensors found")
+                .style(Style::default().fg(theme().warning))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        }
+
+        let temperatures = self.temperatures.iter().map(|temperature| {
+            Row::new(vec![
+                Cell::from(temperature.label.clone()),
+                Cell::from(format_celsius(temperature.celsius))
+                    .style(Style::default().fg(temperature.color())),
+                Cell::from(format_celsius(temperature.max)),
+                Cell::from(format_celsius(temperature.critical)),
+            ])
+        });
+        let fans = self.fans.iter().map(|fan| {
+            Row::new(vec![
+                Cell::from(fan.label.clone()),
+                Cell::from(format!("{:.0} rpm", fan.value)),
+            ])
+        });
+        let header = Row::new(
+            ["Sensor", "Now", "Max", "Critical"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        Table::new(
+            temperatures.chain(fans),
+            [
+                Constraint::Min(16),
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .render(area, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        let Some(hottest) = self.hottest() else {
+            return Some("Sensors: none found".to_string());
+        };
+        let mut summary = format!(
+            "Sensors: {} read; hottest {} at {}",
+            self.temperatures.len(),
+            hottest.label,
+            format_celsius(hottest.celsius)
+        );
+        let critical: Vec<&str> = self
+            .temperatures
+            .iter()
+            .filter(|t| t.is_critical())
+            .map(|t| t.label.as_str())
+            .collect();
+        if !critical.is_empty() {
+            summary.push_str(&format!("; critical: {}", critical.join(", ")));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devdash_core::BusEvent;
+
+    fn reading(label: &str, value: f32, critical: Option<f32>) -> SensorReading {
+        SensorReading {
+            label: label.to_string(),
+            kind: SensorKind::Temperature,
+            value,
+            critical,
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        let temperature = |celsius| Temperature {
+            label: "coretemp Package id 0".to_string(),
+            celsius,
+            max: celsius,
+            critical: 90.0,
+        };
+        assert!(!temperature(70.0).is_warm());
+        assert!(temperature(80.0).is_warm());
+        assert!(!temperature(89.5).is_critical());
+        assert!(temperature(90.0).is_critical());
+        assert_eq!(temperature(95.0).color(), theme().critical);
+    }
+
+    #[test]
+    fn test_apply_readings() {
+        let bus = EventBus::new();
+        let (_sub, rx) = bus.subscribe("system.temperature.critical");
+        let mut widget = SensorsWidget::new(bus, Duration::from_secs(1));
+
+        widget.apply_readings(vec![
+            reading("nvme Composite", 60.0, Some(84.8)),
+            reading("acpitz", 95.0, None),
+        ]);
+        widget.apply_readings(vec![
+            reading("nvme Composite", 55.0, Some(84.8)),
+            reading("acpitz", 50.0, None),
+            SensorReading {
+                label: "Fan 1".to_string(),
+                kind: SensorKind::Fan,
+                value: 1200.0,
+                critical: None,
+            },
+        ]);
+
+        let nvme = &widget.temperatures[0];
+        assert_eq!((nvme.celsius, nvme.max, nvme.critical), (55.0, 60.0, 84.8));
+        assert_eq!(widget.temperatures[1].critical, DEFAULT_CRITICAL);
+        assert_eq!(widget.fans.len(), 1);
+
+        // Only the first poll's acpitz reading was critical
+        let critical: Vec<BusEvent> = rx.try_iter().collect();
+        assert_eq!(critical.len(), 1);
+        assert_eq!(
+            critical[0]
+                .payload
+                .downcast::<Temperature>()
+                .unwrap()
+                .celsius,
+            95.0
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let settings: WidgetSettings = toml::from_str(
+            r#"
+            critical = 85.0
+            exclude = ["acpitz*"]
+            "#,
+        )
+        .unwrap();
+        let options = parse_settings::<SensorOptions>(&settings).unwrap();
+        assert_eq!(options.critical, Some(85.0));
+        assert_eq!(options.exclude, ["acpitz*"]);
+
+        let settings: WidgetSettings = toml::from_str(r#"critical = "hot""#).unwrap();
+        assert!(parse_settings::<SensorOptions>(&settings).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+// devdash-widgets/src/sessions.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Collector, DataStatus, EventBus, EventResult, Widget, WorkerPool,
+    event::{Event, Subscription},
+    theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::common::{focus_color, format_duration, format_relative_time};
+use crate::platform::utmp::{self, LoginRecord, UTMP_PATH, WTMP_PATH};
+
+/// Past logins listed below the open sessions
+const RECENT_LOGINS: usize = 10;
+
+/// A login session, open now or from the login history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub user: String,
+    /// Terminal, e.g. `pts/0`
+    pub tty: String,
+    /// Remote host or X display the login came from; empty for local ones
+    pub origin: String,
+    pub login_time: SystemTime,
+    /// Time since the terminal last saw input; `None` for past logins and
+    /// where unknown
+    #[serde(default)]
+    pub idle: Option<Duration>,
+}
+
+impl Session {
+    fn from_record(record: LoginRecord, idle: Option<Duration>) -> Self {
+        Self {
+            user: record.user,
+            tty: record.line,
+            origin: record.host,
+            login_time: record.time,
+            idle,
+        }
+    }
+
+    /// Whether the login came over the network rather than from a local
+    /// console or X display
+    pub fn is_remote(&self) -> bool {
+        !self.origin.is_empty() && !self.origin.starts_with(':')
+    }
+
+    fn origin_cell(&self) -> Cell<'static> {
+        if self.origin.is_empty() {
+            Cell::from("local")
+        } else if self.is_remote() {
+            Cell::from(self.origin.clone()).style(Style::default().fg(theme().warning))
+        } else {
+            Cell::from(self.origin.clone())
+        }
+    }
+}
+
+/// Open sessions and recent logins at one point in time
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionActivity {
+    /// Newest first
+    pub active: Vec<Session>,
+    /// Newest first, including sessions still open
+    pub recent: Vec<Session>,
+}
+
+impl SessionActivity {
+    /// Read utmp and wtmp (runs on a worker thread)
+    pub fn read(utmp_path: &Path, wtmp_path: &Path) -> Self {
+        let mut active: Vec<Session> = utmp::current(utmp_path)
+            .into_iter()
+            .map(|record| {
+                let idle = utmp::idle_time(&record.line);
+                Session::from_record(record, idle)
+            })
+            .collect();
+        active.sort_by_key(|s| std::cmp::Reverse(s.login_time));
+        let recent = utmp::recent(wtmp_path, RECENT_LOGINS)
+            .into_iter()
+            .map(|record| Session::from_record(record, None))
+            .collect();
+        Self { active, recent }
+    }
+
+    /// Remote sessions open here that weren't open in `previous`
+    pub fn new_remote_logins<'a>(
+        &'a self,
+        previous: &SessionActivity,
+    ) -> impl Iterator<Item = &'a Session> {
+        let known: HashSet<_> = previous
+            .active
+            .iter()
+            .map(|s| (&s.user, &s.tty, s.login_time))
+            .collect();
+        self.active
+            .iter()
+            .filter(move |s| s.is_remote() && !known.contains(&(&s.user, &s.tty, s.login_time)))
+    }
+
+    /// Number of distinct users logged in
+    pub fn user_count(&self) -> usize {
+        self.active
+            .iter()
+            .map(|s| &s.user)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+/// Login sessions widget
+///
+/// Lists who is logged in (terminal, origin, idle time) and the latest
+/// logins from the history, with remote origins highlighted. Sessions come
+/// from utmp and wtmp, so the widget is empty on platforms without them.
+///
+/// # Events
+/// - `system.sessions` - The [`SessionActivity`], every poll
+/// - `system.login.remote` - A remote [`Session`] opened since the previous
+///   poll; sessions already open when the widget starts don't count
+///
+/// When the event bus is not live, `system.sessions` events are shown
+/// instead of reading the files.
+pub struct SessionWidget {
+    collector: Collector<(PathBuf, PathBuf), SessionActivity>,
+    activity: SessionActivity,
+    /// Whether `activity` came from a poll, so new logins can be told apart
+    polled: bool,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl SessionWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_files(event_bus, poll_interval, UTMP_PATH, WTMP_PATH)
+    }
+
+    /// Read sessions from utmp and wtmp files somewhere other than
+    /// `/var/run/utmp` and `/var/log/wtmp`
+    pub fn with_files(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        utmp: impl Into<PathBuf>,
+        wtmp: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            collector: Collector::new((utmp.into(), wtmp.into()), |(utmp, wtmp)| {
+                SessionActivity::read(utmp, wtmp)
+            }),
+            activity: SessionActivity::default(),
+            polled: false,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_snapshot(&mut self, activity: SessionActivity) {
+        if self.polled {
+            for session in activity.new_remote_logins(&self.activity) {
+                self.event_bus
+                    .publish(Event::new("system.login.remote", session.clone()));
+            }
+        }
+        self.event_bus
+            .publish(Event::new("system.sessions", activity.clone()));
+        self.activity = activity;
+        self.polled = true;
+    }
+}
+
+impl Widget for SessionWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected sessions instead of reading the files
+            let (sub, rx) = self.event_bus.subscribe("system.sessions");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        self.collector.request(WorkerPool::global());
+        if let Some(activity) = self.collector.wait() {
+            self.apply_snapshot(activity);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx.try_iter().filter_map(|e| e.payload.downcast()).last();
+            if let Some(activity) = latest {
+                self.activity = SessionActivity::clone(&activity);
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(activity) = self.collector.try_take() {
+            self.apply_snapshot(activity);
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " Sessions [{} users, {} open] ",
+                self.activity.user_count(),
+                self.activity.active.len()
+            ))
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let header = |titles: [&'static str; 5]| {
+            Row::new(
+                titles
+                    .into_iter()
+                    .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+            )
+        };
+        let widths = [
+            Constraint::Min(10),
+            Constraint::Length(8),
+            Constraint::Min(12),
+            Constraint::Length(15),
+            Constraint::Length(8),
+        ];
+
+        let active = self.activity.active.iter().map(|session| {
+            Row::new(vec![
+                Cell::from(session.user.clone()),
+                Cell::from(session.tty.clone()),
+                session.origin_cell(),
+                Cell::from(format_relative_time(session.login_time)),
+                Cell::from(session.idle.map_or("-".to_string(), format_duration)),
+            ])
+        });
+        let recent = self.activity.recent.iter().map(|session| {
+            Row::new(vec![
+                Cell::from(session.user.clone()),
+                Cell::from(session.tty.clone()),
+                session.origin_cell(),
+                Cell::from(format_relative_time(session.login_time)),
+            ])
+        });
+
+        // Open sessions first; recent logins get what's left
+        let [active_area, recent_area] = Layout::vertical([
+            Constraint::Length(self.activity.active.len() as u16 + 2),
+            Constraint::Min(0),
+        ])
+        .areas(inner);
+        Table::new(active, widths)
+            .header(header(["User", "TTY", "From", "Login", "Idle"]))
+            .render(active_area, buf);
+        if !self.activity.recent.is_empty() {
+            Table::new(recent, widths)
+                .header(header(["Recent", "TTY", "From", "Login", ""]))
+                .render(recent_area, buf);
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        let remote = self
+            .activity
+            .active
+            .iter()
+            .filter(|s| s.is_remote())
+            .count();
+        let mut summary = format!(
+            "Sessions: {} users, {} open",
+            self.activity.user_count(),
+            self.activity.active.len()
+        );
+        if remote > 0 {
+            summary.push_str(&format!(", {} remote", remote));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(user: &str, tty: &str, origin: &str, login_secs: u64) -> Session {
+        Session {
+            user: user.to_string(),
+            tty: tty.to_string(),
+            origin: origin.to_string(),
+            login_time: SystemTime::UNIX_EPOCH + Duration::from_secs(login_secs),
+            idle: None,
+        }
+    }
+
+    #[test]
+    fn test_new_remote_logins() {
+        let before = SessionActivity {
+            active: vec![
+                session("alice", "tty1", "", 100),
+                session("bob", "pts/0", "10.0.0.7", 200),
+            ],
+            recent: Vec::new(),
+        };
+        let after = SessionActivity {
+            active: vec![
+                session("alice", "pts/2", ":0", 300),
+                session("bob", "pts/0", "10.0.0.7", 200),
+                // Another login from a host already connected
+                session("bob", "pts/1", "10.0.0.7", 400),
+                session("eve", "pts/3", "203.0.113.9", 500),
+            ],
+            recent: Vec::new(),
+        };
+
+        let new: Vec<&str> = after
+            .new_remote_logins(&before)
+            .map(|s| s.tty.as_str())
+            .collect();
+        assert_eq!(new, ["pts/1", "pts/3"]);
+        assert_eq!(after.user_count(), 3);
+    }
+}
@@ -0,0 +1,792 @@
+// devdash-widgets/src/supervisor.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Action, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WidgetState, autostart,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    process::Child,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    common::{focus_color, format_duration, restore_table, save_table},
+    platform::{shell, terminate_group},
+    tasks::{clean_line, spawn},
+};
+
+/// Lines of output kept per command
+pub const LOG_LINES: usize = 200;
+
+/// Lines of output sent along with a crash
+const CRASH_LINES: usize = 10;
+
+/// How often output is picked up, however long the widget's interval
+const OUTPUT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Wait before restarting a crashed command the first time; doubled after
+/// each further crash up to [`RESTART_MAX`]
+const RESTART_MIN: Duration = Duration::from_secs(1);
+const RESTART_MAX: Duration = Duration::from_secs(60);
+
+/// A command that stayed up this long is counted as healthy again, so its
+/// next crash restarts it quickly
+const HEALTHY_AFTER: Duration = Duration::from_secs(30);
+
+/// What a supervised command is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DevState {
+    Running,
+    /// Stopped with the stop key, or never started
+    Stopped,
+    /// Exited successfully on its own
+    Exited,
+    /// Exited with an error or was killed; restarted after a delay unless
+    /// `restart` is off
+    Crashed,
+}
+
+impl DevState {
+    pub fn label(self) -> &'static str {
+        match self {
+            DevState::Running => "running",
+            DevState::Stopped => "stopped",
+            DevState::Exited => "exited",
+            DevState::Crashed => "crashed",
+        }
+    }
+}
+
+/// One supervised command as shown, and as published on `system.dev`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevProcess {
+    pub name: String,
+    pub command: String,
+    pub state: DevState,
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// When it was last started
+    #[serde(default)]
+    pub started_at: Option<SystemTime>,
+    /// Exit code of the last run; `None` if it was killed by a signal or is
+    /// still running
+    #[serde(default)]
+    pub code: Option<i32>,
+    /// Times it was restarted, by hand or after crashing
+    #[serde(default)]
+    pub restarts: u32,
+    /// Its most recent output, stdout and stderr interleaved
+    #[serde(default)]
+    pub log: Vec<String>,
+}
+
+/// A supervised command crashing, published on `system.dev.crashed`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevCrash {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub code: Option<i32>,
+    /// The last lines it printed
+    #[serde(default)]
+    pub log: Vec<String>,
+}
+
+impl DevCrash {
+    /// "web crashed (exit code 1)"
+    pub fn describe(&self) -> String {
+        match self.code {
+            Some(code) => format!("{} crashed (exit code {})", self.name, code),
+            None => format!("{} was killed", self.name),
+        }
+    }
+}
+
+/// A configured command
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DevCommand {
+    pub name: String,
+    /// Shell command, e.g. `npm run dev`
+    pub command: String,
+    /// Directory to run it in; the current directory when unset
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Whether to restart it after it crashes
+    #[serde(default = "yes")]
+    pub restart: bool,
+    /// Whether to start it with the widget
+    #[serde(default = "yes")]
+    pub enabled: bool,
+}
+
+fn yes() -> bool {
+    true
+}
+
+/// Supervisor widget settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SupervisorOptions {
+    pub commands: Vec<DevCommand>,
+}
+
+impl SupervisorOptions {
+    fn validate(self) -> Result<Self, String> {
+        for (i, command) in self.commands.iter().enumerate() {
+            if command.name.trim().is_empty() || command.command.trim().is_empty() {
+                return Err("dev commands need a name and a command".to_string());
+            }
+            if self.commands[..i].iter().any(|c| c.name == command.name) {
+                return Err(format!(
+                    "dev command '{}' is configured twice",
+                    command.name
+                ));
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// A command's process, output and restart bookkeeping
+struct Supervised {
+    config: DevCommand,
+    child: Option<Child>,
+    output: Option<Receiver<String>>,
+    log: VecDeque<String>,
+    state: DevState,
+    started: Option<(Instant, SystemTime)>,
+    code: Option<i32>,
+    restarts: u32,
+    /// Crashes since it was last healthy
+    crashes: u32,
+    /// When to restart it after a crash
+    restart_at: Option<Instant>,
+}
+
+impl Supervised {
+    fn new(config: DevCommand) -> Self {
+        Self {
+            config,
+            child: None,
+            output: None,
+            log: VecDeque::new(),
+            state: DevState::Stopped,
+            started: None,
+            code: None,
+            restarts: 0,
+            crashes: 0,
+            restart_at: None,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.log.len() == LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(clean_line(line));
+    }
+
+    fn start(&mut self, now: Instant) {
+        let root = self
+            .config
+            .path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."));
+        self.restart_at = None;
+        self.code = None;
+        match spawn(shell(&self.config.command), &root) {
+            Ok((child, output)) => {
+                self.child = Some(child);
+                self.output = Some(output);
+                self.state = DevState::Running;
+                self.started = Some((now, SystemTime::now()));
+            }
+            Err(e) => {
+                self.push_line(&format!("Can't start `{}`: {}", self.config.command, e));
+                self.state = DevState::Crashed;
+                self.crashed(now);
+            }
+        }
+    }
+
+    /// Schedule a restart, backing off while it keeps crashing
+    fn crashed(&mut self, now: Instant) {
+        self.crashes += 1;
+        if self.config.restart {
+            let delay = RESTART_MIN
+                .saturating_mul(1 << (self.crashes - 1).min(16))
+                .min(RESTART_MAX);
+            self.restart_at = Some(now + delay);
+        }
+    }
+
+    /// Stop the command; it shows as stopped once it has exited
+    fn stop(&mut self) {
+        self.restart_at = None;
+        self.state = DevState::Stopped;
+        if let Some(child) = &mut self.child {
+            terminate_group(child);
+        }
+    }
+
+    /// Pick up output and exits, and restart the command when due
+    ///
+    /// Returns the crash if it just crashed.
+    fn update(&mut self, now: Instant) -> Option<DevCrash> {
+        let lines: Vec<String> = self
+            .output
+            .as_ref()
+            .map(|rx| rx.try_iter().collect())
+            .unwrap_or_default();
+        for line in &lines {
+            self.push_line(line);
+        }
+
+        let mut crash = None;
+        if let Some(child) = &mut self.child
+            && let Ok(Some(status)) = child.try_wait()
+        {
+            self.child = None;
+            self.code = status.code();
+            if let Some((started, _)) = self.started
+                && now.duration_since(started) >= HEALTHY_AFTER
+            {
+                self.crashes = 0;
+            }
+            // Stopping it on purpose isn't a crash
+            if self.state == DevState::Running {
+                if status.success() {
+                    self.state = DevState::Exited;
+                } else {
+                    self.state = DevState::Crashed;
+                    self.crashed(now);
+                    let tail = self.log.len().saturating_sub(CRASH_LINES);
+                    crash = Some(DevCrash {
+                        name: self.config.name.clone(),
+                        command: self.config.command.clone(),
+                        code: self.code,
+                        log: self.log.iter().skip(tail).cloned().collect(),
+                    });
+                }
+            }
+        }
+
+        if self.child.is_none() && self.restart_at.is_some_and(|at| now >= at) {
+            self.restarts += 1;
+            self.start(now);
+        }
+        crash
+    }
+
+    fn status(&self) -> DevProcess {
+        DevProcess {
+            name: self.config.name.clone(),
+            command: self.config.command.clone(),
+            state: self.state,
+            pid: self.child.as_ref().map(Child::id),
+            started_at: self.started.map(|(_, at)| at),
+            code: self.code,
+            restarts: self.restarts,
+            log: self.log.iter().cloned().collect(),
+        }
+    }
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Select next command"),
+    Action::new("up", &["k", "up"], "Select previous command"),
+    Action::new("restart", &["r"], "Restart selected command").mutating(),
+    Action::new("stop", &["s"], "Stop selected command, or start it again").mutating(),
+];
+
+/// Dev server supervisor widget
+///
+/// Runs the configured long-running commands (dev servers, `cargo watch`
+/// and the like), shows whether each is running and how often it was
+/// restarted, and the recent output of the selected one. A command that
+/// crashes is restarted after a delay that grows while it keeps crashing,
+/// up to a minute.
+///
+/// # Keyboard Shortcuts
+/// - `Up`/`Down` or `k`/`j` - Select a command
+/// - `r` - Restart the selected command
+/// - `s` - Stop the selected command, or start it again
+///
+/// # Events
+/// - `system.dev` - Every command's [`DevProcess`], whenever one changes
+/// - `system.dev.crashed` - A [`DevCrash`], whenever a command crashes
+///
+/// When the event bus is not live, `system.dev` events are shown instead of
+/// running the commands.
+pub struct SupervisorWidget {
+    commands: Vec<Supervised>,
+    statuses: Vec<DevProcess>,
+    table_state: TableState,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+}
+
+impl SupervisorWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, SupervisorOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<SupervisorOptions>(settings)?.validate()?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: SupervisorOptions,
+    ) -> Self {
+        let commands: Vec<Supervised> = options.commands.into_iter().map(Supervised::new).collect();
+        Self {
+            statuses: commands.iter().map(Supervised::status).collect(),
+            table_state: TableState::default().with_selected((!commands.is_empty()).then_some(0)),
+            commands,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval: poll_interval.min(OUTPUT_INTERVAL),
+        }
+    }
+
+    fn apply_statuses(&mut self, statuses: Vec<DevProcess>) {
+        let count = statuses.len();
+        self.table_state.select(match self.table_state.selected() {
+            _ if count == 0 => None,
+            Some(selected) => Some(selected.min(count - 1)),
+            None => Some(0),
+        });
+        self.statuses = statuses;
+    }
+
+    /// Recompute the statuses, publishing them if they changed
+    fn refresh_statuses(&mut self) {
+        let statuses: Vec<DevProcess> = self.commands.iter().map(Supervised::status).collect();
+        if statuses != self.statuses {
+            self.event_bus
+                .publish(Event::new("system.dev", statuses.clone()));
+            self.apply_statuses(statuses);
+        }
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.statuses.len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(Some(if down {
+            (selected + 1).min(count - 1)
+        } else {
+            selected.saturating_sub(1)
+        }));
+    }
+
+    fn selected_command(&mut self) -> Option<&mut Supervised> {
+        let selected = self.table_state.selected()?;
+        self.commands.get_mut(selected)
+    }
+
+    /// Stop the selected command and start it again right away
+    fn restart_selected(&mut self) {
+        let Some(command) = self.selected_command() else {
+            return;
+        };
+        if let Some(mut child) = command.child.take() {
+            terminate_group(&mut child);
+            // Reap it in the background
+            std::thread::spawn(move || child.wait());
+        }
+        command.restarts += 1;
+        command.crashes = 0;
+        command.start(Instant::now());
+        self.refresh_statuses();
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(command) = self.selected_command() else {
+            return;
+        };
+        if command.child.is_some() && command.state == DevState::Running {
+            command.stop();
+        } else if command.child.is_none() {
+            command.crashes = 0;
+            command.start(Instant::now());
+        }
+        self.refresh_statuses();
+    }
+
+    fn render_commands(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let name_width = self
+            .statuses
+            .iter()
+            .map(|s| s.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(4, 20) as u16;
+        let rows = self.statuses.iter().map(|status| {
+            let color = match status.state {
+                DevState::Running => theme().good,
+                DevState::Crashed => theme().critical,
+                DevState::Exited => theme().warning,
+                DevState::Stopped => Color::DarkGray,
+            };
+            let state = match (status.state, status.code, status.started_at) {
+                (DevState::Running, _, Some(at)) => {
+                    let up = SystemTime::now().duration_since(at).unwrap_or_default();
+                    format!("up {}", format_duration(up))
+                }
+                (DevState::Crashed | DevState::Exited, Some(code), _) => {
+                    format!("{} ({})", status.state.label(), code)
+                }
+                (state, _, _) => state.label().to_string(),
+            };
+            Row::new(vec![
+                Cell::from(status.name.clone()),
+                Cell::from(state).style(Style::default().fg(color)),
+                Cell::from(status.pid.map_or(String::new(), |pid| pid.to_string())),
+                Cell::from(status.restarts.to_string()),
+                Cell::from(status.command.clone()).style(Style::default().fg(Color::Gray)),
+            ])
+        });
+        let header = Row::new(
+            ["Name", "State", "PID", "Restarts", "Command"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(name_width),
+                Constraint::Length(14),
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header);
+        if focused {
+            table = table.row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        ratatui::widgets::StatefulWidget::render(table, area, buf, &mut self.table_state);
+    }
+}
+
+impl Widget for SupervisorWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected statuses instead of running the commands
+            let (sub, rx) = self.event_bus.subscribe("system.dev");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        // An untrusted project's commands wait to be started by hand
+        let now = Instant::now();
+        for command in self
+            .commands
+            .iter_mut()
+            .filter(|c| c.config.enabled && autostart())
+        {
+            command.start(now);
+        }
+        self.refresh_statuses();
+    }
+
+    fn on_update(&mut self, _delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<Vec<DevProcess>>())
+                .last();
+            if let Some(statuses) = latest {
+                self.apply_statuses(Vec::clone(&statuses));
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        let now = Instant::now();
+        for command in &mut self.commands {
+            if let Some(crash) = command.update(now) {
+                self.event_bus
+                    .publish(Event::new("system.dev.crashed", crash));
+            }
+        }
+        self.refresh_statuses();
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        // Output arrives whenever the commands print, so it never goes stale
+        self.injected.as_ref().map(|_| DataStatus {
+            refreshing: false,
+            last_data: self.last_injected,
+            interval: self.poll_interval,
+            retry_at: None,
+        })
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        let devdash_core::Event::Action(action) = event else {
+            return EventResult::Ignored;
+        };
+        match action.as_str() {
+            "down" => self.move_selection(true),
+            "up" => self.move_selection(false),
+            "restart" if self.injected.is_none() => self.restart_selected(),
+            "stop" if self.injected.is_none() => self.toggle_selected(),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let running = self
+            .statuses
+            .iter()
+            .filter(|s| s.state == DevState::Running)
+            .count();
+        let title = match self.statuses.len() {
+            0 => " Dev Servers ".to_string(),
+            total => format!(" Dev Servers [{}/{} running] ", running, total),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.statuses.is_empty() {
+            Paragraph::new("No commands configured")
+                .style(Style::default().fg(Color::DarkGray))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        }
+
+        // The table takes what it needs, up to half the space, then the
+        // selected command's output
+        let table_height = (self.statuses.len() as u16 + 1).min((inner.height / 2).max(2));
+        let [table_area, log_area] =
+            Layout::vertical([Constraint::Length(table_height), Constraint::Min(0)]).areas(inner);
+        self.render_commands(table_area, buf, focused);
+
+        let Some(status) = self
+            .table_state
+            .selected()
+            .and_then(|i| self.statuses.get(i))
+        else {
+            return;
+        };
+        let log_block = Block::default()
+            .borders(Borders::TOP)
+            .title(format!(" {} output ", status.name))
+            .border_style(Style::default().fg(Color::DarkGray));
+        let log_inner = log_block.inner(log_area);
+        log_block.render(log_area, buf);
+        let skip = status.log.len().saturating_sub(log_inner.height as usize);
+        let lines: Vec<Line> = status.log[skip..]
+            .iter()
+            .map(|line| Line::raw(line.as_str()))
+            .collect();
+        Paragraph::new(lines).render(log_inner, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        if self.statuses.is_empty() {
+            return Some("Dev servers: none configured".to_string());
+        }
+        let running = self
+            .statuses
+            .iter()
+            .filter(|s| s.state == DevState::Running)
+            .count();
+        let mut summary = format!(
+            "Dev servers: {} of {} running",
+            running,
+            self.statuses.len()
+        );
+        for status in self
+            .statuses
+            .iter()
+            .filter(|s| s.state == DevState::Crashed)
+        {
+            summary.push_str(&format!("; {} crashed", status.name));
+            if status.restarts > 0 {
+                summary.push_str(&format!(" ({} restarts)", status.restarts));
+            }
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    fn on_unmount(&mut self) {
+        for command in &mut self.commands {
+            command.stop();
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn command(script: &str, restart: bool) -> DevCommand {
+        DevCommand {
+            name: "web".to_string(),
+            command: script.to_string(),
+            path: None,
+            restart,
+            enabled: true,
+        }
+    }
+
+    fn parse(text: &str) -> Result<SupervisorOptions, String> {
+        let settings: WidgetSettings = toml::from_str(text).unwrap();
+        parse_settings::<SupervisorOptions>(&settings)?.validate()
+    }
+
+    #[test]
+    fn test_options() {
+        let options = parse(
+            r#"
+            commands = [
+              { name = "web", command = "npm run dev", path = "frontend" },
+              { name = "docs", command = "mdbook serve", restart = false, enabled = false },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(options.commands[0].path, Some(PathBuf::from("frontend")));
+        assert!(options.commands[0].restart && options.commands[0].enabled);
+        assert!(!options.commands[1].restart && !options.commands[1].enabled);
+
+        assert!(parse(r#"commands = [{ name = "web", command = "" }]"#).is_err());
+        assert!(
+            parse(r#"commands = [{ name = "a", command = "x" }, { name = "a", command = "y" }]"#)
+                .is_err()
+        );
+        assert!(parse(r#"commands = [{ name = "a", command = "x", cwd = "." }]"#).is_err());
+    }
+
+    /// Update until the command is no longer running
+    fn wait_for_exit(supervised: &mut Supervised, now: Instant) -> Option<DevCrash> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            let crash = supervised.update(now);
+            if supervised.child.is_none() {
+                return crash;
+            }
+        }
+        panic!("command still running");
+    }
+
+    #[test]
+    fn test_restarts_crashed_command() {
+        let mut supervised = Supervised::new(command("echo starting; exit 2", true));
+        let start = Instant::now();
+        supervised.start(start);
+        assert_eq!(supervised.status().state, DevState::Running);
+
+        let crash = wait_for_exit(&mut supervised, start).unwrap();
+        assert_eq!(crash.code, Some(2));
+        assert_eq!(crash.describe(), "web crashed (exit code 2)");
+        assert_eq!(supervised.status().state, DevState::Crashed);
+        assert_eq!(supervised.restart_at, Some(start + RESTART_MIN));
+
+        // Restarted once the delay passed
+        assert!(supervised.update(start + RESTART_MIN).is_none());
+        assert_eq!(supervised.status().state, DevState::Running);
+        assert_eq!(supervised.restarts, 1);
+        wait_for_exit(&mut supervised, start + RESTART_MIN).unwrap();
+        assert_eq!(
+            supervised.restart_at,
+            Some(start + RESTART_MIN + RESTART_MIN * 2)
+        );
+        // Output from both runs, all of it
+        let mut log: Vec<_> = supervised.log.iter().cloned().collect();
+        log.dedup();
+        assert_eq!(log, ["starting"]);
+        assert_eq!(supervised.log.len(), 2);
+    }
+
+    #[test]
+    fn test_stopping_is_not_a_crash() {
+        let mut supervised = Supervised::new(command("exec sleep 30", true));
+        let start = Instant::now();
+        supervised.start(start);
+        supervised.stop();
+        assert!(wait_for_exit(&mut supervised, start).is_none());
+        assert_eq!(supervised.status().state, DevState::Stopped);
+        assert_eq!(supervised.restart_at, None);
+
+        let mut clean = Supervised::new(command("true", false));
+        clean.start(start);
+        assert!(wait_for_exit(&mut clean, start).is_none());
+        assert_eq!(clean.status().state, DevState::Exited);
+    }
+}
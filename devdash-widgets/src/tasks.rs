@@ -0,0 +1,1047 @@
+// devdash-widgets/src/tasks.rs
+use crossbeam::channel::{Receiver, TryRecvError};
+use devdash_core::{
+    Action, Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WidgetState,
+    WorkerPool,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    common::{focus_color, format_duration, restore_table, save_table},
+    platform::{own_process_group, terminate_group},
+};
+
+/// Most lines of a run's output kept for scrolling back
+pub const OUTPUT_LINES: usize = 1000;
+
+/// Lines of output sent along with a finished run
+const TAIL_LINES: usize = 20;
+
+/// How long to keep reading output after a task exits, in case something it
+/// started still holds the pipes open
+const CLOSE_GRACE: Duration = Duration::from_millis(500);
+
+/// How often a running task's output is picked up
+const RUNNING_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Lines scrolled by Page Up and Page Down
+const SCROLL_STEP: usize = 10;
+
+/// The task files looked for, in the order their tasks are listed
+const MAKEFILES: [&str; 3] = ["GNUmakefile", "makefile", "Makefile"];
+const JUSTFILES: [&str; 3] = ["justfile", "Justfile", ".justfile"];
+const PACKAGE_JSON: &str = "package.json";
+
+/// Which file a task comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskSource {
+    Make,
+    Just,
+    Npm,
+}
+
+impl TaskSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskSource::Make => "make",
+            TaskSource::Just => "just",
+            TaskSource::Npm => "npm",
+        }
+    }
+}
+
+/// A Makefile target, justfile recipe or package.json script
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Task {
+    pub source: TaskSource,
+    pub name: String,
+    /// The `## comment` after a target, the `# comment` above a recipe, or
+    /// a script's command
+    #[serde(default)]
+    pub description: Option<String>,
+    /// How the task is run, e.g. `make build` or `npm run test`
+    pub command: String,
+    /// The program running the task and its arguments, ending with the
+    /// task's name
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl Task {
+    /// A task run as `runner` followed by its name, e.g. `npm run build`
+    fn new(source: TaskSource, runner: &str, name: &str, description: Option<&str>) -> Self {
+        let mut args: Vec<String> = runner.split_whitespace().map(String::from).collect();
+        args.push(name.to_string());
+        Self {
+            source,
+            name: name.to_string(),
+            description: description.filter(|d| !d.is_empty()).map(str::to_string),
+            command: args.join(" "),
+            args,
+        }
+    }
+
+    /// The process running the task; its name is passed as an argument, so
+    /// no shell reads it
+    pub fn process(&self) -> Command {
+        let (program, args) = self
+            .args
+            .split_first()
+            .map_or(("", &[][..]), |(program, args)| (program.as_str(), args));
+        // Package managers are batch files on Windows, which are only found
+        // by their full name
+        let mut process = if cfg!(windows) && matches!(program, "npm" | "yarn" | "pnpm") {
+            Command::new(format!("{}.cmd", program))
+        } else {
+            Command::new(program)
+        };
+        process.args(args);
+        process
+    }
+}
+
+/// One finished run of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub command: String,
+    pub success: bool,
+    /// `None` when the task was killed by a signal
+    #[serde(default)]
+    pub code: Option<i32>,
+    pub duration: Duration,
+    /// The last lines of its output
+    #[serde(default)]
+    pub output: Vec<String>,
+}
+
+impl TaskRun {
+    /// "`make build` failed with exit code 2 after 1.1s"
+    pub fn describe(&self) -> String {
+        let outcome = match self.code {
+            _ if self.success => "succeeded".to_string(),
+            Some(code) => format!("failed with exit code {}", code),
+            None => "was stopped".to_string(),
+        };
+        format!(
+            "`{}` {} after {}",
+            self.command,
+            outcome,
+            format_elapsed(self.duration)
+        )
+    }
+}
+
+/// Tenths of a second for short runs, [`format_duration`] for longer ones
+fn format_elapsed(duration: Duration) -> String {
+    if duration < Duration::from_secs(60) {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format_duration(duration)
+    }
+}
+
+/// Targets in a Makefile that can be run by name
+///
+/// Leaves out variable assignments, special targets like `.PHONY`, pattern
+/// rules and targets built from variables. A `## comment` after a target
+/// becomes its description.
+pub fn parse_makefile(text: &str) -> Vec<Task> {
+    let mut tasks: Vec<Task> = Vec::new();
+    for line in text.lines() {
+        if line.is_empty() || line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let (rule, comment) = match line.split_once("##") {
+            Some((rule, comment)) => (rule, Some(comment.trim())),
+            None => (line, None),
+        };
+        let Some((targets, rest)) = rule.split_once(':') else {
+            continue;
+        };
+        // `:=`, `::=`, `=` and target-specific variables
+        if targets.contains('=') || rest.starts_with('=') || rest.contains('=') {
+            continue;
+        }
+        for name in targets.split_whitespace() {
+            // A leading `-` would be read as an option
+            if name.contains(['%', '$'])
+                || name.starts_with('-')
+                || tasks.iter().any(|t| t.name == name)
+            {
+                continue;
+            }
+            tasks.push(Task::new(TaskSource::Make, "make", name, comment));
+        }
+    }
+    tasks
+}
+
+/// Recipes in a justfile
+///
+/// Leaves out settings, aliases, imports, variable assignments and private
+/// recipes (named with a leading `_` or marked `[private]`). The comment
+/// right above a recipe becomes its description.
+pub fn parse_justfile(text: &str) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    let mut comment: Option<&str> = None;
+    let mut private = false;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            comment = None;
+            private = false;
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            if !text.starts_with('!') {
+                comment = Some(text.trim());
+            }
+            continue;
+        }
+        if line.starts_with('[') {
+            private |= line.contains("private");
+            continue;
+        }
+        let header = line.trim_start_matches('@');
+        let keyword = header.split_whitespace().next().unwrap_or_default();
+        let skipped = matches!(keyword, "set" | "alias" | "export" | "import" | "mod")
+            || header.contains(":=");
+        let name = header
+            .split_once(':')
+            .and_then(|(signature, _)| signature.split_whitespace().next())
+            .filter(|name| {
+                name.chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+            });
+        if let Some(name) = name
+            && !skipped
+            && !private
+            && !name.starts_with(['_', '-'])
+        {
+            tasks.push(Task::new(TaskSource::Just, "just", name, comment));
+        }
+        comment = None;
+        private = false;
+    }
+    tasks
+}
+
+/// Scripts in a package.json, run with `runner` (`npm run`, `yarn` or
+/// `pnpm run`)
+pub fn parse_scripts(text: &str, runner: &str) -> Result<Vec<Task>, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("package.json: {}", e))?;
+    let Some(scripts) = value["scripts"].as_object() else {
+        return Ok(Vec::new());
+    };
+    Ok(scripts
+        .iter()
+        // A leading `-` would be read as an option
+        .filter(|(name, _)| !name.starts_with('-'))
+        .map(|(name, script)| Task::new(TaskSource::Npm, runner, name, script.as_str()))
+        .collect())
+}
+
+/// The package manager a project uses, going by its lockfile
+fn npm_runner(root: &Path) -> &'static str {
+    if root.join("yarn.lock").exists() {
+        "yarn"
+    } else if root.join("pnpm-lock.yaml").exists() {
+        "pnpm run"
+    } else {
+        "npm run"
+    }
+}
+
+/// The tasks found in a project, or why some couldn't be read
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskList {
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Read every task file in `root`
+pub fn find_tasks(root: &Path) -> TaskList {
+    let mut list = TaskList::default();
+    let first = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| root.join(name))
+            .find(|p| p.exists())
+    };
+    if let Some(text) = first(&MAKEFILES).and_then(|path| fs::read_to_string(path).ok()) {
+        list.tasks.extend(parse_makefile(&text));
+    }
+    if let Some(text) = first(&JUSTFILES).and_then(|path| fs::read_to_string(path).ok()) {
+        list.tasks.extend(parse_justfile(&text));
+    }
+    if let Ok(text) = fs::read_to_string(root.join(PACKAGE_JSON)) {
+        match parse_scripts(&text, npm_runner(root)) {
+            Ok(tasks) => list.tasks.extend(tasks),
+            Err(e) => list.error = Some(e),
+        }
+    }
+    list
+}
+
+/// What each scan needs, kept between runs
+struct Finder {
+    root: PathBuf,
+    /// Modification times of the task files when last read; `None` before
+    /// the first scan
+    modified: Option<Vec<Option<SystemTime>>>,
+}
+
+/// Read the task files again if any of them changed (runs on a worker
+/// thread)
+fn scan(finder: &mut Finder) -> Option<TaskList> {
+    let modified: Vec<_> = MAKEFILES
+        .iter()
+        .chain(&JUSTFILES)
+        .chain([&PACKAGE_JSON])
+        .map(|name| {
+            fs::metadata(finder.root.join(name))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+        .collect();
+    if finder.modified.as_ref() == Some(&modified) {
+        return None;
+    }
+    finder.modified = Some(modified);
+    Some(find_tasks(&finder.root))
+}
+
+/// A task that is running, and its output so far
+struct Running {
+    command: String,
+    child: Child,
+    lines: Receiver<String>,
+    started: Instant,
+    /// When the task exited, while its output is still being read
+    exited: Option<(ExitStatus, Instant)>,
+}
+
+/// Start `process` in `root`, reading its stdout and stderr line by line on
+/// their own threads
+pub(crate) fn spawn(
+    mut process: Command,
+    root: &Path,
+) -> std::io::Result<(Child, Receiver<String>)> {
+    process
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    own_process_group(&mut process);
+    let mut child = process.spawn()?;
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+    let streams: [Option<Box<dyn Read + Send>>; 2] = [
+        child.stdout.take().map(|s| Box::new(s) as _),
+        child.stderr.take().map(|s| Box::new(s) as _),
+    ];
+    for stream in streams.into_iter().flatten() {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    Ok((child, rx))
+}
+
+/// An output line as it would last appear in a terminal
+pub(crate) fn clean_line(line: &str) -> String {
+    let line = line.trim_end_matches('\r');
+    let line = line.rsplit('\r').next().unwrap_or(line);
+    line.replace('\t', "    ")
+}
+
+/// Task widget settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct TaskOptions {
+    /// Project whose tasks to list; the current directory when unset
+    pub path: Option<PathBuf>,
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Select next task"),
+    Action::new("up", &["k", "up"], "Select previous task"),
+    Action::new("run", &["enter"], "Run selected task").mutating(),
+    Action::new("stop", &["s"], "Stop the running task").mutating(),
+    Action::new("scroll-up", &["pageup"], "Scroll output up"),
+    Action::new("scroll-down", &["pagedown"], "Scroll output down"),
+];
+
+/// Task runner widget
+///
+/// Lists the targets of a project's Makefile, the recipes of its justfile
+/// and the scripts of its package.json, and runs the selected one in the
+/// background. Its output streams into a pane below the list, followed by
+/// its exit status and how long it took. The files are read again whenever
+/// they change.
+///
+/// # Keyboard Shortcuts
+/// - `Up`/`Down` or `k`/`j` - Select a task
+/// - `Enter` - Run the selected task, unless one is running
+/// - `s` - Stop the running task
+/// - `PageUp`/`PageDown` - Scroll the output
+///
+/// # Events
+/// - `system.tasks` - The [`TaskList`], whenever the task files change
+/// - `system.task.finished` - A [`TaskRun`], whenever a task exits
+///
+/// When the event bus is not live, these events are shown instead of reading
+/// and running tasks.
+pub struct TaskWidget {
+    collector: Collector<Finder, Option<TaskList>>,
+    root: PathBuf,
+    list: Option<TaskList>,
+    table_state: TableState,
+
+    running: Option<Running>,
+    /// The running or last run task's output
+    output: VecDeque<String>,
+    /// Lines scrolled back from the end of the output
+    scroll: usize,
+    last_run: Option<TaskRun>,
+    /// Why the selected task couldn't be started
+    run_error: Option<String>,
+
+    event_bus: EventBus,
+    _subscriptions: Vec<Subscription>,
+    injected: Option<(Receiver<Event>, Receiver<Event>)>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl TaskWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, TaskOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings(settings)?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: TaskOptions,
+    ) -> Self {
+        let root = options.path.unwrap_or_else(|| PathBuf::from("."));
+        let finder = Finder {
+            root: root.clone(),
+            modified: None,
+        };
+        Self {
+            collector: Collector::new(finder, scan),
+            root,
+            list: None,
+            table_state: TableState::default(),
+            running: None,
+            output: VecDeque::new(),
+            scroll: 0,
+            last_run: None,
+            run_error: None,
+            event_bus,
+            _subscriptions: Vec::new(),
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn tasks(&self) -> &[Task] {
+        self.list.as_ref().map_or(&[], |list| &list.tasks)
+    }
+
+    fn apply_list(&mut self, list: TaskList) {
+        let count = list.tasks.len();
+        self.table_state.select(match self.table_state.selected() {
+            _ if count == 0 => None,
+            Some(selected) => Some(selected.min(count - 1)),
+            None => Some(0),
+        });
+        self.list = Some(list);
+    }
+
+    fn apply_run(&mut self, run: TaskRun) {
+        self.output = run.output.iter().cloned().collect();
+        self.scroll = 0;
+        self.last_run = Some(run);
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.tasks().len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(Some(if down {
+            (selected + 1).min(count - 1)
+        } else {
+            selected.saturating_sub(1)
+        }));
+    }
+
+    fn scroll_output(&mut self, up: bool) {
+        self.scroll = if up {
+            (self.scroll + SCROLL_STEP).min(self.output.len().saturating_sub(1))
+        } else {
+            self.scroll.saturating_sub(SCROLL_STEP)
+        };
+    }
+
+    /// Start the selected task, unless one is already running
+    fn run_selected(&mut self) {
+        if self.running.is_some() || self.injected.is_some() {
+            return;
+        }
+        let Some(task) = self
+            .table_state
+            .selected()
+            .and_then(|i| self.tasks().get(i))
+        else {
+            return;
+        };
+        let command = task.command.clone();
+        match spawn(task.process(), &self.root) {
+            Ok((child, lines)) => {
+                self.output.clear();
+                self.scroll = 0;
+                self.run_error = None;
+                self.running = Some(Running {
+                    command,
+                    child,
+                    lines,
+                    started: Instant::now(),
+                    exited: None,
+                });
+            }
+            Err(e) => self.run_error = Some(format!("Can't run `{}`: {}", command, e)),
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.output.len() == OUTPUT_LINES {
+            self.output.pop_front();
+        }
+        self.output.push_back(clean_line(line));
+        // Keep the lines being read in view
+        if self.scroll > 0 {
+            self.scroll = (self.scroll + 1).min(self.output.len().saturating_sub(1));
+        }
+    }
+
+    /// Read the running task's new output, and finish the run once it exited
+    /// and its output was read
+    fn poll_running(&mut self) {
+        let Some(running) = &mut self.running else {
+            return;
+        };
+        let mut lines = Vec::new();
+        let closed = loop {
+            match running.lines.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        if running.exited.is_none() {
+            match running.child.try_wait() {
+                Ok(Some(status)) => running.exited = Some((status, Instant::now())),
+                Ok(None) => {}
+                Err(e) => lines.push(format!("Can't wait for the task: {}", e)),
+            }
+        }
+        let finished = running
+            .exited
+            .filter(|(_, at)| closed || at.elapsed() >= CLOSE_GRACE);
+        for line in &lines {
+            self.push_line(line);
+        }
+
+        let Some((status, exited_at)) = finished else {
+            return;
+        };
+        let Some(running) = self.running.take() else {
+            return;
+        };
+        let tail = self.output.len().saturating_sub(TAIL_LINES);
+        let run = TaskRun {
+            command: running.command,
+            success: status.success(),
+            code: status.code(),
+            duration: exited_at.duration_since(running.started),
+            output: self.output.iter().skip(tail).cloned().collect(),
+        };
+        self.event_bus
+            .publish(Event::new("system.task.finished", run.clone()));
+        self.last_run = Some(run);
+    }
+
+    /// "Running `make build` for 3s", or how the last run ended
+    fn status(&self) -> Option<(String, Color)> {
+        if let Some(running) = &self.running {
+            return Some((
+                format!(
+                    "Running `{}` for {}",
+                    running.command,
+                    format_duration(running.started.elapsed())
+                ),
+                theme().warning,
+            ));
+        }
+        let run = self.last_run.as_ref()?;
+        let color = match run.code {
+            _ if run.success => theme().good,
+            Some(_) => theme().critical,
+            None => theme().warning,
+        };
+        Some((run.describe(), color))
+    }
+
+    fn render_tasks(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let name_width = self
+            .tasks()
+            .iter()
+            .map(|t| t.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(4, 30) as u16;
+        let rows: Vec<Row> = self
+            .tasks()
+            .iter()
+            .map(|task| {
+                Row::new(vec![
+                    Cell::from(task.source.label()).style(Style::default().fg(Color::DarkGray)),
+                    Cell::from(task.name.clone()),
+                    Cell::from(task.description.clone().unwrap_or_default())
+                        .style(Style::default().fg(Color::Gray)),
+                ])
+            })
+            .collect();
+        let header = Row::new(
+            ["From", "Task", "Description"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Length(name_width),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header);
+        if focused {
+            table = table.row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        ratatui::widgets::StatefulWidget::render(table, area, buf, &mut self.table_state);
+    }
+
+    fn render_output(&self, area: Rect, buf: &mut Buffer) {
+        let height = area.height as usize;
+        let end = self.output.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(height);
+        let lines: Vec<Line> = self
+            .output
+            .range(start..end)
+            .map(|line| Line::raw(line.as_str()))
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+impl Widget for TaskWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected tasks and runs instead of reading the project
+            let (tasks_sub, tasks_rx) = self.event_bus.subscribe("system.tasks");
+            let (runs_sub, runs_rx) = self.event_bus.subscribe("system.task.finished");
+            self._subscriptions = vec![tasks_sub, runs_sub];
+            self.injected = Some((tasks_rx, runs_rx));
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        // Reading a few small files is quick; wait so they show right away
+        self.collector.request(WorkerPool::global());
+        if let Some(Some(list)) = self.collector.wait() {
+            self.event_bus
+                .publish(Event::new("system.tasks", list.clone()));
+            self.apply_list(list);
+        }
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some((tasks_rx, runs_rx)) = &self.injected {
+            let list = tasks_rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<TaskList>())
+                .last();
+            let run = runs_rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<TaskRun>())
+                .last();
+            if list.is_some() || run.is_some() {
+                self.last_injected = Instant::now();
+            }
+            if let Some(list) = list {
+                self.apply_list(TaskList::clone(&list));
+            }
+            if let Some(run) = run {
+                self.apply_run(TaskRun::clone(&run));
+            }
+            return;
+        }
+
+        if let Some(Some(list)) = self.collector.try_take() {
+            self.event_bus
+                .publish(Event::new("system.tasks", list.clone()));
+            self.apply_list(list);
+        }
+        self.poll_running();
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        let devdash_core::Event::Action(action) = event else {
+            return EventResult::Ignored;
+        };
+        match action.as_str() {
+            "down" => self.move_selection(true),
+            "up" => self.move_selection(false),
+            "run" => self.run_selected(),
+            "stop" => {
+                if let Some(running) = &mut self.running {
+                    terminate_group(&mut running.child);
+                }
+            }
+            "scroll-up" => self.scroll_output(true),
+            "scroll-down" => self.scroll_output(false),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let title = match &self.list {
+            Some(list) if !list.tasks.is_empty() => format!(" Tasks [{}] ", list.tasks.len()),
+            _ => " Tasks ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let message = match &self.list {
+            None => Some(("Reading tasks...".to_string(), Color::DarkGray)),
+            Some(list) if list.tasks.is_empty() => Some(match &list.error {
+                Some(error) => (error.clone(), theme().warning),
+                None => (
+                    format!(
+                        "No Makefile, justfile or package.json in {}",
+                        self.root.display()
+                    ),
+                    Color::DarkGray,
+                ),
+            }),
+            _ => None,
+        };
+        if let Some((text, color)) = message {
+            Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        }
+
+        let status = match &self.run_error {
+            Some(error) => Some((error.clone(), theme().critical)),
+            None => self.status(),
+        };
+        let Some((status, color)) = status else {
+            self.render_tasks(inner, buf, focused);
+            return;
+        };
+        // The list takes what it needs, up to half the space, then the
+        // status line and the output
+        let list_height = (self.tasks().len() as u16 + 1).min((inner.height / 2).max(2));
+        let [list_area, status_area, output_area] = Layout::vertical([
+            Constraint::Length(list_height),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .areas(inner);
+        self.render_tasks(list_area, buf, focused);
+        Paragraph::new(status)
+            .style(Style::default().fg(color))
+            .render(status_area, buf);
+        self.render_output(output_area, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        let Some(list) = &self.list else {
+            return Some("Tasks: not read yet".to_string());
+        };
+        let mut summary = format!("Tasks: {} tasks", list.tasks.len());
+        if let Some((status, _)) = self.status() {
+            summary.push_str(&format!("; {}", status));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        // Output is picked up more often than the files are read
+        if self.running.is_some() {
+            self.poll_interval.min(RUNNING_INTERVAL)
+        } else {
+            self.poll_interval
+        }
+    }
+
+    fn on_unmount(&mut self) {
+        if let Some(running) = &mut self.running {
+            terminate_group(&mut running.child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_makefile() {
+        let makefile = "\
+CARGO := cargo
+PREFIX ?= /usr/local
+.PHONY: build test
+
+build: src/main.rs ## Build the release binary
+\t$(CARGO) build --release
+
+test lint: build
+\t$(CARGO) test
+
+%.o: %.c
+\tcc -c $<
+
+$(OUTPUT): build
+debug: CFLAGS += -g
+install:: build ## Install into PREFIX
+";
+        let tasks = parse_makefile(makefile);
+        let names: Vec<_> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["build", "test", "lint", "install"]);
+        assert_eq!(
+            tasks[0].description.as_deref(),
+            Some("Build the release binary")
+        );
+        assert_eq!(tasks[0].command, "make build");
+        assert_eq!(tasks[0].args, ["make", "build"]);
+        assert_eq!(tasks[1].description, None);
+    }
+
+    #[test]
+    fn test_task_names_are_not_read_by_a_shell() {
+        let json = r#"{"scripts": {"a;touch pwned": "x", "--help": "y"}}"#;
+        let tasks = parse_scripts(json, "pnpm run").unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].args, ["pnpm", "run", "a;touch pwned"]);
+        let process = tasks[0].process();
+        let args: Vec<_> = process.get_args().collect();
+        assert_eq!(args, ["run", "a;touch pwned"]);
+
+        let tasks = parse_makefile("-flags:\n\t@echo\nx$(NAME):\nok|yes:\n");
+        let names: Vec<_> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["ok|yes"]);
+    }
+
+    #[test]
+    fn test_parse_justfile() {
+        let justfile = "\
+set shell := [\"bash\", \"-c\"]
+alias b := build
+version := \"1.0\"
+
+# Build everything
+build target=\"debug\":
+    cargo build
+
+# Not a recipe comment
+
+[private]
+setup:
+    ./setup.sh
+
+_helper:
+    echo hi
+
+@test *args: build
+    cargo test {{args}}
+";
+        let tasks = parse_justfile(justfile);
+        let names: Vec<_> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["build", "test"]);
+        assert_eq!(tasks[0].description.as_deref(), Some("Build everything"));
+        assert_eq!(tasks[1].description, None);
+        assert_eq!(tasks[1].command, "just test");
+    }
+
+    #[test]
+    fn test_parse_scripts() {
+        let json = r#"{"name": "app", "scripts": {"dev": "vite", "build": "vite build"}}"#;
+        let tasks = parse_scripts(json, "yarn").unwrap();
+        assert_eq!(tasks.len(), 2);
+        let build = tasks.iter().find(|t| t.name == "build").unwrap();
+        assert_eq!(build.command, "yarn build");
+        assert_eq!(build.description.as_deref(), Some("vite build"));
+        assert!(
+            parse_scripts(r#"{"name": "app"}"#, "npm run")
+                .unwrap()
+                .is_empty()
+        );
+        assert!(parse_scripts("{", "npm run").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runs_task() {
+        let dir = std::env::temp_dir().join(format!("devdash-tasks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let bus = EventBus::new();
+        bus.set_live(true);
+        let (_sub, finished) = bus.subscribe("system.task.finished");
+        let options = TaskOptions {
+            path: Some(dir.clone()),
+        };
+        let mut widget = TaskWidget::with_options(bus, Duration::from_secs(1), options);
+        widget.on_mount();
+        assert_eq!(widget.tasks().len(), 0);
+        widget.apply_list(TaskList {
+            tasks: vec![Task {
+                source: TaskSource::Make,
+                name: "fail".to_string(),
+                description: None,
+                command: "echo one; echo two >&2; exit 3".to_string(),
+                args: ["sh", "-c", "echo one; echo two >&2; exit 3"]
+                    .map(String::from)
+                    .to_vec(),
+            }],
+            error: None,
+        });
+
+        widget.on_event(devdash_core::Event::Action("run".into()));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while widget.running.is_some() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            widget.on_update(Duration::from_millis(20));
+        }
+        let run = widget.last_run.clone().unwrap();
+        assert_eq!(run.code, Some(3));
+        assert!(!run.success);
+        let mut output = run.output.clone();
+        output.sort();
+        assert_eq!(output, ["one", "two"]);
+        assert!(
+            run.describe()
+                .starts_with("`echo one; echo two >&2; exit 3` failed with exit code 3 after ")
+        );
+        let event = finished.try_recv().unwrap();
+        assert_eq!(event.payload.downcast::<TaskRun>().as_deref(), Some(&run));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_clean_line() {
+        assert_eq!(clean_line("10%\r50%\r100%\r"), "100%");
+        assert_eq!(clean_line("\tindented"), "    indented");
+    }
+}
@@ -0,0 +1,791 @@
+// devdash-widgets/src/tunnels.rs
+use crossbeam::channel::Receiver;
+use devdash_core::{
+    Action, Collector, DataStatus, EventBus, EventResult, Widget, WidgetSettings, WidgetState,
+    WorkerPool, autostart,
+    event::{Event, Subscription},
+    parse_settings, theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{BufRead, BufReader},
+    net::{SocketAddr, TcpStream},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{focus_color, restore_table, save_table},
+    platform::{own_process_group, shell, terminate_group},
+};
+
+/// How long a port probe waits for the tunnel to accept
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Wait before restarting a tunnel the first time it dies; doubled after
+/// each further failure up to [`RETRY_MAX`]
+const RETRY_MIN: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Whether a tunnel is carrying traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelState {
+    /// Turned off with the toggle key
+    Off,
+    /// Running but not accepting connections yet
+    Starting,
+    Up,
+    /// Exited or failed to start; restarted after a delay
+    Down,
+}
+
+impl TunnelState {
+    pub fn label(self) -> &'static str {
+        match self {
+            TunnelState::Off => "off",
+            TunnelState::Starting => "starting",
+            TunnelState::Up => "up",
+            TunnelState::Down => "down",
+        }
+    }
+}
+
+/// One tunnel as shown, and as published on `system.tunnels`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TunnelStatus {
+    pub name: String,
+    /// Port on this machine the tunnel listens on
+    pub local: u16,
+    /// Where it leads, e.g. `db.internal:5432 via bastion`
+    pub target: String,
+    pub state: TunnelState,
+    /// Times it was restarted after dying
+    #[serde(default)]
+    pub restarts: u32,
+    /// Last line of error output, or why it couldn't start
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A configured tunnel: an SSH `-L` forward, a `kubectl port-forward`, or
+/// any command that forwards `local`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TunnelConfig {
+    pub name: String,
+    pub local: u16,
+    /// SSH destination, e.g. `bastion` or `me@host`
+    #[serde(default)]
+    pub ssh: Option<String>,
+    /// kubectl resource, e.g. `svc/grafana` or `pod/api-0`
+    #[serde(default)]
+    pub kubectl: Option<String>,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Shell command keeping the forward open
+    #[serde(default)]
+    pub command: Option<String>,
+    /// `host:port` behind an SSH forward, or the port of a kubectl one;
+    /// `localhost` and the local port when unset
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Whether to open the tunnel when the widget starts
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl TunnelConfig {
+    /// The forwarded address or port, as `host:port` for SSH
+    fn remote(&self) -> String {
+        match &self.remote {
+            Some(remote) if self.ssh.is_some() && !remote.contains(':') => {
+                format!("localhost:{}", remote)
+            }
+            Some(remote) => remote.clone(),
+            None if self.ssh.is_some() => format!("localhost:{}", self.local),
+            None => self.local.to_string(),
+        }
+    }
+
+    /// Where the tunnel leads, for display
+    pub fn target(&self) -> String {
+        match (&self.ssh, &self.kubectl, &self.command) {
+            (Some(destination), _, _) => format!("{} via {}", self.remote(), destination),
+            (_, Some(resource), _) => {
+                let namespace = self.namespace.as_deref().unwrap_or("default");
+                format!("{}/{}:{}", namespace, resource, self.remote())
+            }
+            (_, _, Some(command)) => command.clone(),
+            _ => String::new(),
+        }
+    }
+
+    /// The command keeping the tunnel open
+    pub fn command(&self) -> Command {
+        if let Some(destination) = &self.ssh {
+            let mut ssh = Command::new("ssh");
+            ssh.args(["-N", "-o", "ExitOnForwardFailure=yes"])
+                .args(["-o", "ServerAliveInterval=15", "-o", "BatchMode=yes"])
+                .arg("-L")
+                .arg(format!("{}:{}", self.local, self.remote()))
+                .arg(destination);
+            return ssh;
+        }
+        if let Some(resource) = &self.kubectl {
+            let mut kubectl = Command::new("kubectl");
+            kubectl.arg("port-forward");
+            if let Some(context) = &self.context {
+                kubectl.args(["--context", context]);
+            }
+            if let Some(namespace) = &self.namespace {
+                kubectl.args(["--namespace", namespace]);
+            }
+            kubectl
+                .arg(resource)
+                .arg(format!("{}:{}", self.local, self.remote()));
+            return kubectl;
+        }
+        shell(self.command.as_deref().unwrap_or_default())
+    }
+}
+
+/// Tunnel widget settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct TunnelOptions {
+    pub tunnels: Vec<TunnelConfig>,
+}
+
+impl TunnelOptions {
+    /// Check each tunnel has exactly one of `ssh`, `kubectl` and `command`
+    fn validate(self) -> Result<Self, String> {
+        for tunnel in &self.tunnels {
+            let kinds = [&tunnel.ssh, &tunnel.kubectl, &tunnel.command]
+                .iter()
+                .filter(|kind| kind.is_some())
+                .count();
+            if kinds != 1 {
+                return Err(format!(
+                    "tunnel '{}' needs exactly one of ssh, kubectl and command",
+                    tunnel.name
+                ));
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Whether something accepts connections on each local port (runs on a
+/// worker thread)
+fn probe(ports: &[u16]) -> Vec<bool> {
+    ports
+        .iter()
+        .map(|&port| {
+            let address = SocketAddr::from(([127, 0, 0, 1], port));
+            TcpStream::connect_timeout(&address, PROBE_TIMEOUT).is_ok()
+        })
+        .collect()
+}
+
+/// A tunnel's process and restart bookkeeping
+struct Tunnel {
+    config: TunnelConfig,
+    /// Whether it should be open; cleared by the toggle key
+    wanted: bool,
+    child: Option<Child>,
+    /// Error output of the running process
+    stderr: Option<Receiver<String>>,
+    error: Option<String>,
+    /// Whether the local port accepted a connection at the last probe
+    listening: bool,
+    /// Failures since the tunnel was last seen up
+    failures: u32,
+    /// When to start it again after it died
+    retry_at: Option<Instant>,
+    /// Whether it was ever started, so later starts count as restarts
+    started: bool,
+    restarts: u32,
+}
+
+impl Tunnel {
+    fn new(config: TunnelConfig) -> Self {
+        Self {
+            // An untrusted project's tunnels wait to be opened by hand
+            wanted: config.enabled && autostart(),
+            config,
+            child: None,
+            stderr: None,
+            error: None,
+            listening: false,
+            failures: 0,
+            retry_at: None,
+            started: false,
+            restarts: 0,
+        }
+    }
+
+    fn start(&mut self, now: Instant) {
+        let mut command = self.config.command();
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        own_process_group(&mut command);
+        match command.spawn() {
+            Ok(mut child) => {
+                if let Some(stderr) = child.stderr.take() {
+                    let (tx, rx) = crossbeam::channel::unbounded();
+                    std::thread::spawn(move || {
+                        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                            if tx.send(line).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    self.stderr = Some(rx);
+                }
+                self.child = Some(child);
+                self.retry_at = None;
+                if self.started {
+                    self.restarts += 1;
+                }
+                self.started = true;
+            }
+            Err(e) => {
+                self.error = Some(format!("Can't start: {}", e));
+                self.failed(now);
+            }
+        }
+    }
+
+    /// Schedule a restart, backing off while the tunnel keeps failing
+    fn failed(&mut self, now: Instant) {
+        self.failures += 1;
+        let delay = RETRY_MIN
+            .saturating_mul(1 << (self.failures - 1).min(16))
+            .min(RETRY_MAX);
+        self.retry_at = Some(now + delay);
+    }
+
+    fn stop(&mut self) {
+        if let Some(child) = &mut self.child {
+            terminate_group(child);
+        }
+    }
+
+    /// Pick up error output and exits, and restart the tunnel when due
+    fn update(&mut self, now: Instant) {
+        if let Some(line) = self
+            .stderr
+            .as_ref()
+            .and_then(|rx| rx.try_iter().filter(|l| !l.trim().is_empty()).last())
+        {
+            self.error = Some(line.trim().to_string());
+        }
+        if let Some(child) = &mut self.child
+            && let Ok(Some(status)) = child.try_wait()
+        {
+            self.child = None;
+            self.listening = false;
+            if self.wanted {
+                if self.error.is_none() {
+                    self.error = Some(format!("exited ({})", status));
+                }
+                self.failed(now);
+            }
+        }
+        if self.wanted && self.child.is_none() && self.retry_at.is_none_or(|at| now >= at) {
+            self.start(now);
+        }
+    }
+
+    fn status(&self) -> TunnelStatus {
+        let state = match (&self.child, self.listening) {
+            _ if !self.wanted => TunnelState::Off,
+            (Some(_), true) => TunnelState::Up,
+            (Some(_), false) => TunnelState::Starting,
+            (None, _) => TunnelState::Down,
+        };
+        TunnelStatus {
+            name: self.config.name.clone(),
+            local: self.config.local,
+            target: self.config.target(),
+            state,
+            restarts: self.restarts,
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// What the widget does with keys, and their default keys
+const ACTIONS: &[Action] = &[
+    Action::new("down", &["j", "down"], "Select next tunnel"),
+    Action::new("up", &["k", "up"], "Select previous tunnel"),
+    Action::new(
+        "toggle",
+        &["enter", "space"],
+        "Open or close selected tunnel",
+    )
+    .mutating(),
+    Action::new("restart", &["r"], "Restart selected tunnel").mutating(),
+];
+
+/// Port-forward manager widget
+///
+/// Keeps the configured SSH port-forwards and `kubectl port-forward`
+/// tunnels open, showing whether each accepts connections on its local
+/// port. A tunnel that dies is restarted after a delay that grows while it
+/// keeps failing, up to a minute.
+///
+/// # Keyboard Shortcuts
+/// - `Up`/`Down` or `k`/`j` - Select a tunnel
+/// - `Enter` or `Space` - Close the selected tunnel, or open it again
+/// - `r` - Restart the selected tunnel
+///
+/// # Events
+/// - `system.tunnels` - Every tunnel's [`TunnelStatus`], whenever one changes
+///
+/// When the event bus is not live, `system.tunnels` events are shown instead
+/// of opening tunnels.
+pub struct TunnelWidget {
+    collector: Collector<Vec<u16>, Vec<bool>>,
+    tunnels: Vec<Tunnel>,
+    statuses: Vec<TunnelStatus>,
+    table_state: TableState,
+
+    event_bus: EventBus,
+    _subscription: Option<Subscription>,
+    injected: Option<Receiver<Event>>,
+    /// When an injected event last arrived
+    last_injected: Instant,
+
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl TunnelWidget {
+    pub fn new(event_bus: EventBus, poll_interval: Duration) -> Self {
+        Self::with_options(event_bus, poll_interval, TunnelOptions::default())
+    }
+
+    /// Build from the widget's `[[dashboard.widgets]]` settings
+    pub fn from_settings(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        settings: &WidgetSettings,
+    ) -> Result<Self, String> {
+        let options = parse_settings::<TunnelOptions>(settings)?.validate()?;
+        Ok(Self::with_options(event_bus, poll_interval, options))
+    }
+
+    pub fn with_options(
+        event_bus: EventBus,
+        poll_interval: Duration,
+        options: TunnelOptions,
+    ) -> Self {
+        let ports = options.tunnels.iter().map(|t| t.local).collect();
+        let tunnels: Vec<Tunnel> = options.tunnels.into_iter().map(Tunnel::new).collect();
+        Self {
+            collector: Collector::new(ports, |ports: &mut Vec<u16>| probe(ports)),
+            statuses: tunnels.iter().map(Tunnel::status).collect(),
+            table_state: TableState::default().with_selected((!tunnels.is_empty()).then_some(0)),
+            tunnels,
+            event_bus,
+            _subscription: None,
+            injected: None,
+            last_injected: Instant::now(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    fn apply_statuses(&mut self, statuses: Vec<TunnelStatus>) {
+        let count = statuses.len();
+        self.table_state.select(match self.table_state.selected() {
+            _ if count == 0 => None,
+            Some(selected) => Some(selected.min(count - 1)),
+            None => Some(0),
+        });
+        self.statuses = statuses;
+    }
+
+    /// Recompute the statuses, publishing them if they changed
+    fn refresh_statuses(&mut self) {
+        let statuses: Vec<TunnelStatus> = self.tunnels.iter().map(Tunnel::status).collect();
+        if statuses != self.statuses {
+            self.event_bus
+                .publish(Event::new("system.tunnels", statuses.clone()));
+            self.apply_statuses(statuses);
+        }
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.statuses.len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(Some(if down {
+            (selected + 1).min(count - 1)
+        } else {
+            selected.saturating_sub(1)
+        }));
+    }
+
+    fn selected_tunnel(&mut self) -> Option<&mut Tunnel> {
+        let selected = self.table_state.selected()?;
+        self.tunnels.get_mut(selected)
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(tunnel) = self.selected_tunnel() else {
+            return;
+        };
+        tunnel.wanted = !tunnel.wanted;
+        tunnel.failures = 0;
+        tunnel.retry_at = None;
+        tunnel.error = None;
+        if tunnel.wanted {
+            tunnel.start(Instant::now());
+        } else {
+            tunnel.stop();
+        }
+        self.refresh_statuses();
+    }
+
+    /// Stop the selected tunnel and start it again right away
+    fn restart_selected(&mut self) {
+        let Some(tunnel) = self.selected_tunnel() else {
+            return;
+        };
+        if let Some(mut child) = tunnel.child.take() {
+            terminate_group(&mut child);
+            // Reap it in the background; the port frees once it has gone
+            std::thread::spawn(move || child.wait());
+        }
+        tunnel.wanted = true;
+        tunnel.listening = false;
+        tunnel.failures = 0;
+        tunnel.error = None;
+        tunnel.start(Instant::now());
+        self.refresh_statuses();
+    }
+}
+
+impl Widget for TunnelWidget {
+    fn on_mount(&mut self) {
+        if !self.event_bus.is_live() {
+            // Display injected statuses instead of opening tunnels
+            let (sub, rx) = self.event_bus.subscribe("system.tunnels");
+            self._subscription = Some(sub);
+            self.injected = Some(rx);
+            self.last_injected = Instant::now();
+            return;
+        }
+
+        let now = Instant::now();
+        for tunnel in self.tunnels.iter_mut().filter(|t| t.wanted) {
+            tunnel.start(now);
+        }
+        self.collector.request(WorkerPool::global());
+        self.refresh_statuses();
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        if let Some(rx) = &self.injected {
+            let latest = rx
+                .try_iter()
+                .filter_map(|e| e.payload.downcast::<Vec<TunnelStatus>>())
+                .last();
+            if let Some(statuses) = latest {
+                self.apply_statuses(Vec::clone(&statuses));
+                self.last_injected = Instant::now();
+            }
+            return;
+        }
+
+        if let Some(listening) = self.collector.try_take() {
+            for (tunnel, listening) in self.tunnels.iter_mut().zip(listening) {
+                // A probe that raced an exit doesn't count
+                tunnel.listening = listening && tunnel.child.is_some();
+                if tunnel.listening {
+                    tunnel.failures = 0;
+                }
+            }
+        }
+        let now = Instant::now();
+        for tunnel in &mut self.tunnels {
+            tunnel.update(now);
+        }
+        self.refresh_statuses();
+
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.collector.request(WorkerPool::global());
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn data_status(&self) -> Option<DataStatus> {
+        Some(match self.injected {
+            Some(_) => DataStatus {
+                refreshing: false,
+                last_data: self.last_injected,
+                interval: self.poll_interval,
+                retry_at: None,
+            },
+            None => self.collector.status(self.poll_interval),
+        })
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        ACTIONS.to_vec()
+    }
+
+    fn on_event(&mut self, event: devdash_core::Event) -> EventResult {
+        let devdash_core::Event::Action(action) = event else {
+            return EventResult::Ignored;
+        };
+        match action.as_str() {
+            "down" => self.move_selection(true),
+            "up" => self.move_selection(false),
+            "toggle" if self.injected.is_none() => self.toggle_selected(),
+            "restart" if self.injected.is_none() => self.restart_selected(),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let up = self
+            .statuses
+            .iter()
+            .filter(|s| s.state == TunnelState::Up)
+            .count();
+        let title = match self.statuses.len() {
+            0 => " Tunnels ".to_string(),
+            total => format!(" Tunnels [{}/{} up] ", up, total),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(focus_color(focused)));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.statuses.is_empty() {
+            Paragraph::new("No tunnels configured")
+                .style(Style::default().fg(Color::DarkGray))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        }
+
+        let name_width = self
+            .statuses
+            .iter()
+            .map(|s| s.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(4, 20) as u16;
+        let rows = self.statuses.iter().map(|status| {
+            let color = match status.state {
+                TunnelState::Up => theme().good,
+                TunnelState::Starting => theme().warning,
+                TunnelState::Down => theme().critical,
+                TunnelState::Off => Color::DarkGray,
+            };
+            let restarts = match status.restarts {
+                0 => String::new(),
+                n => n.to_string(),
+            };
+            let note = match status.state {
+                TunnelState::Up | TunnelState::Off => String::new(),
+                _ => status.error.clone().unwrap_or_default(),
+            };
+            Row::new(vec![
+                Cell::from(status.name.clone()),
+                Cell::from(status.local.to_string()),
+                Cell::from(status.target.clone()),
+                Cell::from(status.state.label()).style(Style::default().fg(color)),
+                Cell::from(restarts),
+                Cell::from(note).style(Style::default().fg(Color::DarkGray)),
+            ])
+        });
+        let header = Row::new(
+            ["Name", "Port", "Target", "State", "Restarts", "Error"]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow))),
+        );
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(name_width),
+                Constraint::Length(5),
+                Constraint::Min(16),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header);
+        if focused {
+            table = table.row_highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        ratatui::widgets::StatefulWidget::render(table, inner, buf, &mut self.table_state);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<WidgetState> {
+        let mut state = WidgetState::new();
+        save_table(&self.table_state, &mut state);
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &WidgetState) {
+        restore_table(&mut self.table_state, state);
+    }
+
+    fn summary(&self) -> Option<String> {
+        if self.statuses.is_empty() {
+            return Some("Tunnels: none configured".to_string());
+        }
+        let up = self
+            .statuses
+            .iter()
+            .filter(|s| s.state == TunnelState::Up)
+            .count();
+        let mut summary = format!("Tunnels: {} of {} up", up, self.statuses.len());
+        for status in self
+            .statuses
+            .iter()
+            .filter(|s| s.state == TunnelState::Down)
+        {
+            summary.push_str(&format!("; {} down", status.name));
+            if let Some(error) = &status.error {
+                summary.push_str(&format!(": {}", error));
+            }
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    fn on_unmount(&mut self) {
+        for tunnel in &mut self.tunnels {
+            tunnel.wanted = false;
+            tunnel.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<TunnelOptions, String> {
+        let settings: WidgetSettings = toml::from_str(text).unwrap();
+        parse_settings::<TunnelOptions>(&settings)?.validate()
+    }
+
+    #[test]
+    fn test_tunnel_commands() {
+        let options = parse(
+            r#"
+            tunnels = [
+              { name = "db", ssh = "bastion", local = 5432, remote = "db.internal:5432" },
+              { name = "web", ssh = "me@web", local = 8080, remote = "80" },
+              { name = "grafana", kubectl = "svc/grafana", local = 3000, remote = "80", namespace = "monitoring", context = "prod" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let args = |tunnel: &TunnelConfig| {
+            let command = tunnel.command();
+            let mut args = vec![command.get_program().to_string_lossy().into_owned()];
+            args.extend(command.get_args().map(|a| a.to_string_lossy().into_owned()));
+            args.join(" ")
+        };
+        let [db, web, grafana] = &options.tunnels[..] else {
+            panic!("{:?}", options.tunnels);
+        };
+        assert!(args(db).ends_with("-L 5432:db.internal:5432 bastion"));
+        assert!(args(web).ends_with("-L 8080:localhost:80 me@web"));
+        assert_eq!(web.target(), "localhost:80 via me@web");
+        assert_eq!(
+            args(grafana),
+            "kubectl port-forward --context prod --namespace monitoring svc/grafana 3000:80"
+        );
+        assert_eq!(grafana.target(), "monitoring/svc/grafana:80");
+
+        assert!(parse(r#"tunnels = [{ name = "x", local = 1 }]"#).is_err());
+        assert!(
+            parse(r#"tunnels = [{ name = "x", local = 1, ssh = "a", command = "b" }]"#).is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restarts_dead_tunnel() {
+        let config = TunnelConfig {
+            name: "flaky".to_string(),
+            local: 1,
+            ssh: None,
+            kubectl: None,
+            namespace: None,
+            context: None,
+            command: Some("echo 'connection refused' >&2; exit 1".to_string()),
+            remote: None,
+            enabled: true,
+        };
+        let mut tunnel = Tunnel::new(config);
+        let start = Instant::now();
+        tunnel.update(start);
+        assert!(tunnel.child.is_some());
+        assert_eq!(tunnel.status().state, TunnelState::Starting);
+
+        let deadline = start + Duration::from_secs(10);
+        while tunnel.child.is_some() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            tunnel.update(start);
+        }
+        let status = tunnel.status();
+        assert_eq!(status.state, TunnelState::Down);
+        assert_eq!(tunnel.retry_at, Some(start + RETRY_MIN));
+
+        // Restarted once the delay passed, backing off further next time
+        tunnel.update(start + RETRY_MIN);
+        assert!(tunnel.child.is_some());
+        assert_eq!(tunnel.restarts, 1);
+        tunnel.failed(start);
+        assert_eq!(tunnel.retry_at, Some(start + RETRY_MIN * 2));
+        tunnel.stop();
+    }
+}
@@ -0,0 +1,154 @@
+// devdash-widgets/src/uptime.rs
+use devdash_core::{EventResult, Widget};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget as RatatuiWidget,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::System;
+
+use crate::common::{focus_color, format_duration, format_relative_time, format_timestamp};
+
+/// System uptime at one point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct UptimeInfo {
+    pub uptime: Duration,
+    pub boot_time: SystemTime,
+    /// 1, 5 and 15 minute load averages; `None` where the OS has none
+    pub load_average: Option<[f64; 3]>,
+}
+
+impl UptimeInfo {
+    pub fn current() -> Self {
+        let load = System::load_average();
+        Self {
+            uptime: Duration::from_secs(System::uptime()),
+            boot_time: UNIX_EPOCH + Duration::from_secs(System::boot_time()),
+            // Windows reports zeros
+            load_average: (!cfg!(windows)).then_some([load.one, load.five, load.fifteen]),
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::Yellow));
+        let mut lines = vec![
+            Line::from(vec![
+                label("Up:     "),
+                Span::from(format_duration(self.uptime)),
+            ]),
+            Line::from(vec![
+                label("Booted: "),
+                Span::from(format!(
+                    "{} ({})",
+                    format_timestamp(self.boot_time),
+                    format_relative_time(self.boot_time)
+                )),
+            ]),
+        ];
+        if let Some([one, five, fifteen]) = self.load_average {
+            lines.push(Line::from(vec![
+                label("Load:   "),
+                Span::from(format!("{:.2} {:.2} {:.2}", one, five, fifteen)),
+            ]));
+        }
+        lines
+    }
+}
+
+/// System uptime widget
+///
+/// Shows how long the machine has been up, when it booted, and the load
+/// averages where the platform has them.
+pub struct UptimeWidget {
+    info: UptimeInfo,
+    poll_interval: Duration,
+    time_since_poll: Duration,
+}
+
+impl UptimeWidget {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            info: UptimeInfo::current(),
+            poll_interval,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+}
+
+impl Widget for UptimeWidget {
+    fn on_mount(&mut self) {
+        self.info = UptimeInfo::current();
+    }
+
+    fn on_update(&mut self, delta: Duration) {
+        self.time_since_poll += delta;
+        if self.time_since_poll >= self.poll_interval {
+            self.info = UptimeInfo::current();
+            self.time_since_poll = Duration::ZERO;
+        }
+    }
+
+    fn on_event(&mut self, _event: devdash_core::Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_focused(area, buf, true);
+    }
+
+    fn render_focused(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Uptime ")
+            .border_style(Style::default().fg(focus_color(focused)));
+
+        Paragraph::new(self.info.lines())
+            .block(block)
+            .render(area, buf);
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mut summary = format!("Up {}", format_duration(self.info.uptime));
+        if let Some([one, five, fifteen]) = self.info.load_average {
+            summary.push_str(&format!(", load {:.2} {:.2} {:.2}", one, five, fifteen));
+        }
+        Some(summary)
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::set_time_format;
+
+    #[test]
+    fn test_lines() {
+        set_time_format(Some("boot")).unwrap();
+        let info = UptimeInfo {
+            uptime: Duration::from_secs(2 * 86_400 + 5 * 3600),
+            boot_time: SystemTime::now() - Duration::from_secs(2 * 86_400 + 5 * 3600),
+            load_average: Some([0.5, 1.0, 1.25]),
+        };
+        let text: Vec<String> = info.lines().iter().map(|l| l.to_string()).collect();
+        assert_eq!(
+            text,
+            [
+                "Up:     2d 5h",
+                "Booted: boot (2 days ago)",
+                "Load:   0.50 1.00 1.25"
+            ]
+        );
+    }
+}
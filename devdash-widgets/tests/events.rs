@@ -0,0 +1,175 @@
+// devdash-widgets/tests/events.rs
+//! Golden event sequences: canned samples are fed through a widget's
+//! collection path and the events it publishes are compared line by line.
+//!
+//! Each event is written as its topic followed by the payload fields that
+//! matter, so a change in a rate calculation shows up as a readable diff.
+use crossbeam::channel::Receiver;
+use devdash_core::{BusEvent, Event, EventBus, Selection, Widget, event::Subscription};
+use devdash_widgets::{DiskIOMetrics, DiskInfo, DiskSample, DiskUsageMetrics, DiskWidget};
+use std::time::Duration;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+const POLL: Duration = Duration::from_secs(2);
+
+/// The events published since the last call, one line each
+fn drain(events: &Receiver<BusEvent>) -> Vec<String> {
+    events
+        .try_iter()
+        .map(|event| {
+            let fields = if let Some(io) = event.payload.downcast::<DiskIOMetrics>() {
+                format!(
+                    "read_rate={} write_rate={} total_read={} total_write={}",
+                    io.read_rate, io.write_rate, io.total_read, io.total_write
+                )
+            } else if let Some(usage) = event.payload.downcast::<DiskUsageMetrics>() {
+                format!(
+                    "{} used={} available={} percentage={:.1}",
+                    usage.mount_point, usage.used, usage.available, usage.percentage
+                )
+            } else {
+                "?".to_string()
+            };
+            format!("{} {}", event.topic, fields)
+        })
+        .collect()
+}
+
+fn disk(mount_point: &str, total_space: u64, available_space: u64) -> DiskInfo {
+    DiskInfo {
+        name: mount_point.to_string(),
+        mount_point: mount_point.to_string(),
+        total_space,
+        available_space,
+    }
+}
+
+fn sample(read_bytes: u64, write_bytes: u64) -> DiskSample {
+    DiskSample {
+        read_bytes,
+        write_bytes,
+        disks: Vec::new(),
+    }
+}
+
+/// A disk widget that is never polled, and the events it publishes while
+/// the subscription is kept
+fn disk_widget() -> (DiskWidget, Subscription, Receiver<BusEvent>) {
+    let bus = EventBus::new();
+    let (subscription, events) = bus.subscribe("system.disk.*");
+    (DiskWidget::new(bus, POLL), subscription, events)
+}
+
+#[test]
+fn test_disk_rates() {
+    let (mut widget, _subscription, events) = disk_widget();
+
+    // The first sample only seeds the counters
+    widget.feed(sample(1_000, 500), POLL);
+    widget.feed(sample(5_096, 2_548), POLL);
+    widget.feed(sample(5_096, 2_548), POLL);
+    widget.feed(sample(8_096, 3_548), Duration::from_millis(500));
+
+    assert_eq!(
+        drain(&events),
+        [
+            "system.disk.io read_rate=0 write_rate=0 total_read=1000 total_write=500",
+            "system.disk.io read_rate=2048 write_rate=1024 total_read=5096 total_write=2548",
+            "system.disk.io read_rate=0 write_rate=0 total_read=5096 total_write=2548",
+            "system.disk.io read_rate=6000 write_rate=2000 total_read=8096 total_write=3548",
+        ]
+    );
+}
+
+#[test]
+fn test_disk_counter_reset() {
+    let (mut widget, _subscription, events) = disk_widget();
+
+    widget.feed(sample(1_000_000, 1_000_000), POLL);
+    // Both counters went back to zero, e.g. after a device was remounted;
+    // the rate is what was counted since
+    widget.feed(sample(4_000, 1_000_002), POLL);
+    widget.feed(sample(8_000, 10), POLL);
+    widget.feed(sample(10_000, 20), POLL);
+
+    assert_eq!(
+        drain(&events),
+        [
+            "system.disk.io read_rate=0 write_rate=0 total_read=1000000 total_write=1000000",
+            "system.disk.io read_rate=2000 write_rate=1 total_read=4000 total_write=1000002",
+            "system.disk.io read_rate=2000 write_rate=5 total_read=8000 total_write=10",
+            "system.disk.io read_rate=1000 write_rate=5 total_read=10000 total_write=20",
+        ]
+    );
+}
+
+#[test]
+fn test_disk_zero_delta_repeats_rate() {
+    let (mut widget, _subscription, events) = disk_widget();
+
+    widget.feed(sample(0, 100), POLL);
+    widget.feed(sample(2_000, 300), POLL);
+    // No time passed, so there is no new rate; the counters still move on
+    widget.feed(sample(3_000, 400), Duration::ZERO);
+    widget.feed(sample(5_000, 400), POLL);
+
+    assert_eq!(
+        drain(&events),
+        [
+            "system.disk.io read_rate=0 write_rate=0 total_read=0 total_write=100",
+            "system.disk.io read_rate=1000 write_rate=100 total_read=2000 total_write=300",
+            "system.disk.io read_rate=1000 write_rate=100 total_read=3000 total_write=400",
+            "system.disk.io read_rate=1000 write_rate=0 total_read=5000 total_write=400",
+        ]
+    );
+}
+
+#[test]
+fn test_disk_link_reseeds_counters() {
+    let (mut widget, _subscription, events) = disk_widget();
+
+    widget.feed(sample(10_000, 10_000), POLL);
+    widget.feed(sample(12_000, 10_000), POLL);
+    // Counters of the linked disk aren't comparable with the totals
+    widget.on_event(Event::Linked(Selection::new("disk", "/home")));
+    widget.feed(sample(3_000, 1_000), POLL);
+    widget.feed(sample(3_400, 1_000), POLL);
+
+    assert_eq!(
+        drain(&events),
+        [
+            "system.disk.io read_rate=0 write_rate=0 total_read=10000 total_write=10000",
+            "system.disk.io read_rate=1000 write_rate=0 total_read=12000 total_write=10000",
+            "system.disk.io read_rate=0 write_rate=0 total_read=3000 total_write=1000",
+            "system.disk.io read_rate=200 write_rate=0 total_read=3400 total_write=1000",
+        ]
+    );
+}
+
+#[test]
+fn test_disk_usage_and_full() {
+    let (mut widget, _subscription, events) = disk_widget();
+
+    widget.feed(
+        DiskSample {
+            read_bytes: 0,
+            write_bytes: 0,
+            disks: vec![
+                disk("/home", 100 * GIB, 50 * GIB),
+                disk("/", 10 * GIB, GIB / 2),
+            ],
+        },
+        POLL,
+    );
+
+    // Mount points are sorted; a disk over 90% also raises an alert
+    assert_eq!(
+        drain(&events),
+        [
+            "system.disk.io read_rate=0 write_rate=0 total_read=0 total_write=0",
+            "system.disk.usage / used=10200547328 available=536870912 percentage=95.0",
+            "system.disk.full / used=10200547328 available=536870912 percentage=95.0",
+            "system.disk.usage /home used=53687091200 available=53687091200 percentage=50.0",
+        ]
+    );
+}
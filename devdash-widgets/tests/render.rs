@@ -0,0 +1,1104 @@
+// devdash-widgets/tests/render.rs
+//! Render snapshots of the built-in widgets fed with injected data.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to regenerate `tests/snapshots/`.
+use crossterm::event::KeyCode;
+use devdash_core::{ActionRequest, BusEvent, Event, EventBus, Selection, Widget};
+use devdash_test::{WidgetHarness, assert_snapshot, offline_bus};
+use devdash_widgets::{
+    BackupStatus, BackupWidget, ClockWidget, CpuMetrics, CpuWidget, DevProcess, DevState,
+    Diagnostic, DiagnosticLevel, DiagnosticReport, DiagnosticsWidget, DiskIOMetrics,
+    DiskProjection, DiskUsageMetrics, DiskWidget, EndpointHealth, EndpointLatency, ErrorWidget,
+    FirewallSummary, FirewallWidget, Freshness, HealthIssue, HealthWidget, HighlightRule,
+    HttpMonitorWidget, JobResult, JobSchedule, JobSource, LatencyWidget, ListeningPort,
+    MemoryMetrics, MemoryWidget, PROCESS_SELECT_TOPIC, PagingRates, PortActivity, PortList,
+    PortsWidget, ProcessInfo, ProcessWidget, Protocol, ScheduleWidget, ScheduledJob, SensorsWidget,
+    Session, SessionActivity, SessionWidget, SourceActivity, SupervisorWidget, Task, TaskList,
+    TaskRun, TaskSource, TaskWidget, Temperature, TunnelState, TunnelStatus, TunnelWidget,
+    common::{UnitSystem, with_unit_system},
+    health,
+};
+use ratatui::style::Color;
+use std::time::{Duration, SystemTime};
+
+const GIB: u64 = 1024 * 1024 * 1024;
+const TICK: Duration = Duration::from_millis(100);
+
+fn memory_metrics(used: u64, total: u64, swap_used: u64, swap_total: u64) -> MemoryMetrics {
+    MemoryMetrics {
+        used,
+        total,
+        swap_used,
+        swap_total,
+        usage_percent: used as f32 / total as f32 * 100.0,
+        swap_percent: swap_used as f32 / swap_total as f32 * 100.0,
+        paging: None,
+    }
+}
+
+fn publish_memory(bus: &EventBus, used: u64, total: u64, swap_used: u64, swap_total: u64) {
+    bus.publish(BusEvent::new(
+        "system.memory",
+        memory_metrics(used, total, swap_used, swap_total),
+    ));
+}
+
+fn publish_process(
+    bus: &EventBus,
+    pid: u32,
+    name: &str,
+    cpu_percent: f32,
+    memory_bytes: u64,
+    cwd: &str,
+) {
+    bus.publish(BusEvent::new(
+        "system.process.top",
+        ProcessInfo {
+            pid,
+            name: name.to_string(),
+            cpu_percent,
+            memory_bytes,
+            start_time: 0,
+            cwd: Some(cwd.into()),
+            zombie: false,
+            open_files: None,
+            open_files_limit: None,
+        },
+    ));
+}
+
+fn publish_disks(bus: &EventBus) {
+    for (mount_point, used) in [("/", 80 * GIB), ("/home", 450 * GIB)] {
+        let total = 500 * GIB;
+        bus.publish(BusEvent::new(
+            "system.disk.usage",
+            DiskUsageMetrics {
+                mount_point: mount_point.to_string(),
+                total,
+                used,
+                available: total - used,
+                percentage: used as f64 / total as f64 * 100.0,
+            },
+        ));
+    }
+}
+
+fn publish_processes(bus: &EventBus) {
+    publish_process(bus, 100, "cargo", 85.0, 512 * 1024 * 1024, "/home/dev/app");
+    publish_process(bus, 200, "rust-analyzer", 20.0, 2 * GIB, "/home/dev/app");
+    publish_process(bus, 300, "zsh", 0.5, 8 * 1024 * 1024, "/");
+}
+
+#[test]
+fn error_widget() {
+    let mut harness = WidgetHarness::new(ErrorWidget::plugin_error("clock"), 40, 4);
+    harness.mount();
+    assert_snapshot!(harness, "error_plugin");
+}
+
+#[test]
+fn cpu_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(CpuWidget::new(bus.clone(), TICK), 40, 6);
+    harness.mount();
+
+    bus.publish(BusEvent::new(
+        "system.cpu",
+        CpuMetrics {
+            usage: 42.5,
+            cores: vec![10.0, 95.0, 40.0, 25.0],
+            container: false,
+        },
+    ));
+    harness.advance(TICK);
+    assert_eq!(harness.widget().summary().unwrap(), "CPU 42.5% used");
+
+    harness.key(KeyCode::Char('t'));
+    assert_snapshot!(harness, "cpu_heatmap");
+}
+
+#[test]
+fn memory_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(MemoryWidget::new(bus.clone(), TICK), 40, 6);
+    harness.mount();
+
+    publish_memory(&bus, 6 * GIB, 16 * GIB, GIB, 4 * GIB);
+    harness.advance(TICK);
+    assert_snapshot!(harness, "memory");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Memory 37.5% used, 6.0 GiB of 16.0 GiB; swap 25.0% used"
+    );
+
+    // Hiding swap leaves only the memory gauge
+    harness.key(KeyCode::Char('s'));
+    assert_snapshot!(harness, "memory_no_swap");
+}
+
+#[test]
+fn memory_widget_reports_injected_data_age() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(MemoryWidget::new(bus.clone(), TICK), 40, 6);
+    harness.mount();
+    let mounted = harness.widget().data_status().unwrap();
+    assert!(!mounted.refreshing);
+
+    std::thread::sleep(Duration::from_millis(5));
+    harness.advance(TICK);
+    assert_eq!(harness.widget().data_status().unwrap(), mounted);
+
+    publish_memory(&bus, 6 * GIB, 16 * GIB, GIB, 4 * GIB);
+    harness.advance(TICK);
+    assert!(harness.widget().data_status().unwrap().last_data > mounted.last_data);
+}
+
+#[test]
+fn memory_widget_decimal_units() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(MemoryWidget::new(bus.clone(), TICK), 40, 6);
+    harness.mount();
+
+    publish_memory(&bus, 6_000_000_000, 16_000_000_000, GIB, 4 * GIB);
+    harness.advance(TICK);
+    with_unit_system(Some(UnitSystem::Decimal), || {
+        assert_snapshot!(harness, "memory_decimal");
+    });
+}
+
+#[test]
+fn memory_widget_paging() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(MemoryWidget::new(bus.clone(), TICK), 40, 12);
+    harness.mount();
+
+    for pages in [0.0, 10.0, 400.0, 600.0, 800.0, 900.0, 700.0] {
+        let metrics = MemoryMetrics {
+            paging: Some(PagingRates {
+                pages_in: pages,
+                pages_out: pages / 2.0,
+            }),
+            ..memory_metrics(15 * GIB, 16 * GIB, 3 * GIB, 4 * GIB)
+        };
+        bus.publish(BusEvent::new("system.memory", metrics));
+        harness.advance(TICK);
+    }
+    assert_snapshot!(harness, "memory_paging");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Memory 93.8% used, 15.0 GiB of 16.0 GiB; swap 75.0% used; \
+         paging 700 in, 350 out pages/s, thrashing"
+    );
+}
+
+#[test]
+fn disk_widget_views() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(DiskWidget::new(bus.clone(), TICK), 48, 10);
+    harness.mount();
+
+    bus.publish(BusEvent::new(
+        "system.disk.io",
+        DiskIOMetrics {
+            read_rate: 50 * 1024 * 1024,
+            write_rate: 10 * 1024 * 1024,
+            total_read: 20 * GIB,
+            total_write: 5 * GIB,
+        },
+    ));
+    publish_disks(&bus);
+    harness.advance(TICK);
+    assert_snapshot!(harness, "disk_io");
+
+    harness.key(KeyCode::Char('t'));
+    assert_snapshot!(harness, "disk_usage");
+}
+
+#[test]
+fn disk_widget_shows_projection() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(DiskWidget::new(bus.clone(), TICK), 60, 10);
+    harness.mount();
+
+    publish_disks(&bus);
+    bus.publish(BusEvent::new(
+        "system.disk.projection",
+        DiskProjection {
+            mount_point: "/home".to_string(),
+            bytes_per_day: 5.0 * GIB as f64,
+            seconds_until_full: 10 * 86_400,
+        },
+    ));
+    harness.advance(TICK);
+    harness.key(KeyCode::Char('t'));
+    assert_snapshot!(harness, "disk_usage_projection");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Disk read 0 B/s, write 0 B/s; / 16% full; /home 90% full, full in ~10 days"
+    );
+}
+
+#[test]
+fn process_widget_selection() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ProcessWidget::new(bus.clone(), TICK), 56, 16);
+    harness.mount();
+
+    publish_processes(&bus);
+    harness.advance(TICK);
+    assert_snapshot!(harness, "process_by_cpu");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Processes by CPU: cargo 85.0% CPU 512.0 MiB, rust-analyzer 20.0% CPU 2.0 GiB, \
+         zsh 0.5% CPU 8.0 MiB"
+    );
+
+    // Sorting by memory keeps the selected process selected
+    harness.key(KeyCode::Char('m'));
+    assert_snapshot!(harness, "process_by_memory");
+}
+
+#[test]
+fn process_widget_select_request() {
+    let bus = offline_bus();
+    let widget = ProcessWidget::new(bus.clone(), TICK).with_max_rows(2);
+    let mut harness = WidgetHarness::new(widget, 56, 16);
+    harness.mount();
+    publish_processes(&bus);
+    harness.advance(TICK);
+
+    // A requested process is kept in view even outside the top rows
+    bus.publish(BusEvent::new(
+        PROCESS_SELECT_TOPIC,
+        Selection::new("process", "300"),
+    ));
+    harness.advance(TICK);
+    assert_snapshot!(harness, "process_select_request");
+
+    // Until another row is selected
+    harness.key(KeyCode::Char('k'));
+    publish_processes(&bus);
+    harness.advance(TICK);
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Processes by CPU: cargo 85.0% CPU 512.0 MiB, rust-analyzer 20.0% CPU 2.0 GiB"
+    );
+}
+
+#[test]
+fn process_widget_long_names() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ProcessWidget::new(bus.clone(), TICK), 56, 8);
+    harness.mount();
+    publish_process(
+        &bus,
+        100,
+        "postgres: checkpointer writing",
+        12.0,
+        GIB,
+        "/var/lib",
+    );
+    publish_process(&bus, 200, "数据库备份任务调度程序", 3.0, GIB, "/srv");
+    harness.advance(TICK);
+
+    // Names are cut short at the column's edge by cells, not chars
+    assert_snapshot!(harness, "process_long_names");
+}
+
+#[test]
+fn process_widget_linked_disk() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ProcessWidget::new(bus.clone(), TICK), 56, 16);
+    harness.mount();
+    publish_processes(&bus);
+    harness.advance(TICK);
+
+    harness.send(Event::Linked(Selection::new("disk", "/home")));
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Processes on /home by CPU: cargo 85.0% CPU 512.0 MiB, \
+         rust-analyzer 20.0% CPU 2.0 GiB"
+    );
+
+    // The filter can be cleared from the process widget
+    harness.key(KeyCode::Char('x'));
+    assert!(
+        harness
+            .widget()
+            .summary()
+            .unwrap()
+            .starts_with("Processes by CPU:")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn process_widget_lowers_priority() {
+    // Anyone may lower a priority, so use a throwaway child
+    let mut child = std::process::Command::new("sleep")
+        .arg("5")
+        .spawn()
+        .unwrap();
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ProcessWidget::new(bus.clone(), TICK), 72, 16);
+    harness.mount();
+    publish_process(&bus, child.id(), "sleep", 0.0, 1024, "/");
+    harness.advance(TICK);
+
+    harness.key(KeyCode::Char('-'));
+    let text = harness.render_text();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(
+        text.contains(&format!("sleep ({}): nice ", child.id())),
+        "{}",
+        text
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn process_widget_pins_cores() {
+    let mut child = std::process::Command::new("sleep")
+        .arg("5")
+        .spawn()
+        .unwrap();
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ProcessWidget::new(bus.clone(), TICK), 72, 16);
+    harness.mount();
+    publish_process(&bus, child.id(), "sleep", 0.0, 1024, "/");
+    harness.advance(TICK);
+
+    let allowed_cores = devdash_widgets::platform::affinity::allowed_cores;
+    let before = allowed_cores(child.id()).unwrap();
+    harness.key(KeyCode::Char('a'));
+    let mark = |allowed: bool| if allowed { "●  0" } else { "○  0" };
+    assert!(harness.render_text().contains(mark(before[0])));
+
+    // Toggling core 0 works unless it is the only core left
+    harness.key(KeyCode::Char(' '));
+    let text = harness.render_text();
+    let after = allowed_cores(child.id()).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    if before.iter().filter(|&&a| a).count() > 1 || !before[0] {
+        assert_eq!(after[0], !before[0]);
+        assert!(text.contains(mark(after[0])), "{}", text);
+    } else {
+        assert!(text.contains("at least one core"), "{}", text);
+    }
+}
+
+#[test]
+fn disk_widget_follows_linked_disk() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(DiskWidget::new(bus.clone(), TICK), 48, 10);
+    harness.mount();
+    publish_disks(&bus);
+    harness.advance(TICK);
+    assert_eq!(
+        harness.widget().selection(),
+        Some(Selection::new("disk", "/"))
+    );
+
+    harness.send(Event::Linked(Selection::new("disk", "/home")));
+    assert_eq!(
+        harness.widget().selection(),
+        Some(Selection::new("disk", "/home"))
+    );
+    assert!(harness.render_text().contains("Disk I/O /home"));
+}
+
+#[test]
+fn process_widget_highlights() {
+    let bus = offline_bus();
+    let widget = ProcessWidget::new(bus.clone(), TICK)
+        .with_highlights(vec![HighlightRule::new("memory > 1GiB", "red").unwrap()]);
+    let mut harness = WidgetHarness::new(widget, 56, 16);
+    harness.mount();
+    publish_processes(&bus);
+    harness.advance(TICK);
+
+    // rust-analyzer is second by CPU but over the memory threshold
+    let buffer = harness.render();
+    let row_color = |y: u16| buffer[(12, y)].fg;
+    assert_eq!(row_color(3), Color::Reset);
+    assert_eq!(row_color(4), Color::Red);
+    assert_eq!(row_color(5), Color::Reset);
+}
+
+#[test]
+fn process_widget_max_rows() {
+    let bus = offline_bus();
+    let widget = ProcessWidget::new(bus.clone(), TICK).with_max_rows(2);
+    let mut harness = WidgetHarness::new(widget, 56, 16);
+    harness.mount();
+    publish_processes(&bus);
+    harness.advance(TICK);
+
+    let text = harness.render_text();
+    assert!(text.contains("cargo"));
+    assert!(text.contains("rust-analyzer"));
+    assert!(!text.contains("zsh"));
+}
+
+#[test]
+fn process_widget_groups_by_name() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ProcessWidget::new(bus.clone(), TICK), 56, 16);
+    harness.mount();
+    publish_processes(&bus);
+    publish_process(&bus, 101, "cargo", 10.0, 512 * 1024 * 1024, "/home/dev/app");
+    harness.advance(TICK);
+
+    harness.key(KeyCode::Char('g'));
+    assert_snapshot!(harness, "process_grouped");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Process groups by CPU: cargo ×2 95.0% CPU 1.0 GiB, rust-analyzer ×1 20.0% CPU \
+         2.0 GiB, zsh ×1 0.5% CPU 8.0 MiB"
+    );
+
+    // Sorting applies to the groups
+    harness.key(KeyCode::Char('m'));
+    assert!(
+        harness
+            .widget()
+            .summary()
+            .unwrap()
+            .starts_with("Process groups by memory: rust-analyzer")
+    );
+
+    harness.key(KeyCode::Char('g'));
+    assert!(harness.render_text().contains("PID"));
+}
+
+#[test]
+fn process_widget_marks_zombies_and_fd_pressure() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ProcessWidget::new(bus.clone(), TICK), 56, 16);
+    harness.mount();
+    publish_processes(&bus);
+    for (pid, name, zombie, open_files) in
+        [(400, "make", true, None), (500, "node", false, Some(950))]
+    {
+        bus.publish(BusEvent::new(
+            "system.process.top",
+            ProcessInfo {
+                pid,
+                name: name.to_string(),
+                cpu_percent: 0.0,
+                memory_bytes: 0,
+                start_time: 0,
+                cwd: None,
+                zombie,
+                open_files,
+                open_files_limit: Some(1024),
+            },
+        ));
+    }
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "process_marks");
+    let text = harness.render_text();
+    assert!(text.contains("make <defunct>"));
+    assert!(text.contains("node [fd 93%]"));
+}
+
+#[test]
+fn session_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(SessionWidget::new(bus.clone(), TICK), 60, 12);
+    harness.mount();
+
+    let hours_ago = |hours: u64| SystemTime::now() - Duration::from_secs(hours * 3600 + 60);
+    let session = |user: &str, tty: &str, origin: &str, hours, idle: Option<u64>| Session {
+        user: user.to_string(),
+        tty: tty.to_string(),
+        origin: origin.to_string(),
+        login_time: hours_ago(hours),
+        idle: idle.map(Duration::from_secs),
+    };
+    bus.publish(BusEvent::new(
+        "system.sessions",
+        SessionActivity {
+            active: vec![
+                session("deploy", "pts/3", "203.0.113.9", 1, Some(42)),
+                session("dev", "pts/0", ":0", 5, Some(20 * 60)),
+                session("dev", "tty1", "", 30, None),
+            ],
+            recent: vec![
+                session("deploy", "pts/3", "203.0.113.9", 1, None),
+                session("dev", "pts/1", "10.0.0.7", 3, None),
+            ],
+        },
+    ));
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "sessions");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Sessions: 2 users, 3 open, 1 remote"
+    );
+}
+
+#[test]
+fn firewall_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(FirewallWidget::new(bus.clone(), TICK), 64, 8);
+    harness.mount();
+
+    let source = |source: &str, attempts, recent, burst, ports: &[u16]| SourceActivity {
+        source: source.to_string(),
+        attempts,
+        recent,
+        burst,
+        ports: ports.to_vec(),
+    };
+    let port = |port, protocol: &str, attempts| PortActivity {
+        port,
+        protocol: protocol.to_string(),
+        attempts,
+    };
+    bus.publish(BusEvent::new(
+        "system.firewall",
+        FirewallSummary {
+            window: Duration::from_secs(600),
+            total: 57,
+            sources: vec![
+                source("198.51.100.4", 48, 31, true, &[22, 2222, 23]),
+                source("203.0.113.9", 7, 0, false, &[3389]),
+                source("192.0.2.77", 2, 1, false, &[]),
+            ],
+            ports: vec![
+                port(Some(22), "TCP", 40),
+                port(Some(3389), "TCP", 7),
+                port(Some(2222), "TCP", 5),
+                port(None, "ICMP", 2),
+            ],
+        },
+    ));
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "firewall");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Firewall: 57 blocked in the last 10m; most from 198.51.100.4 (48); 1 source bursting"
+    );
+}
+
+#[test]
+fn schedule_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(ScheduleWidget::new(bus.clone(), TICK), 64, 7);
+    harness.mount();
+
+    // A minute of slack so the countdowns and ages don't tick over
+    let in_hours = |hours: u64| Some(SystemTime::now() + Duration::from_secs(hours * 3600 + 60));
+    let hours_ago = |hours: u64| Some(SystemTime::now() - Duration::from_secs(hours * 3600 + 60));
+    let job = |name: &str, source, next_run, last_run, result| ScheduledJob {
+        name: name.to_string(),
+        source,
+        next_run,
+        last_run,
+        result,
+    };
+    bus.publish(BusEvent::new(
+        "system.schedule",
+        JobSchedule::new(vec![
+            job(
+                "backup.timer",
+                JobSource::Timer,
+                in_hours(14),
+                hours_ago(9),
+                Some(JobResult::Failed("exit 2".to_string())),
+            ),
+            job(
+                "/usr/local/bin/sync-notes",
+                JobSource::Crontab,
+                in_hours(1),
+                hours_ago(0),
+                None,
+            ),
+            job(
+                "logrotate.timer",
+                JobSource::Timer,
+                in_hours(3),
+                hours_ago(20),
+                Some(JobResult::Success),
+            ),
+            job(
+                "mail-sync.timer",
+                JobSource::UserTimer,
+                None,
+                hours_ago(2),
+                Some(JobResult::Running),
+            ),
+        ]),
+    ));
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "schedule");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Schedule: 4 jobs; next /usr/local/bin/sync-notes in 1h 0m; failed: backup.timer"
+    );
+}
+
+#[test]
+fn backup_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(BackupWidget::new(bus.clone(), TICK), 60, 7);
+    harness.mount();
+
+    let hours_ago = |hours: u64| Some(SystemTime::now() - Duration::from_secs(hours * 3600 + 60));
+    let status = |name: &str, last_success, freshness, error: Option<&str>| BackupStatus {
+        name: name.to_string(),
+        last_success,
+        freshness,
+        error: error.map(str::to_string),
+    };
+    for backup in [
+        status("home", hours_ago(7), Freshness::Fresh, None),
+        status("photos", hours_ago(30), Freshness::Overdue, None),
+        status("offsite", hours_ago(24 * 5), Freshness::Stale, None),
+        status(
+            "mail",
+            None,
+            Freshness::Unknown,
+            Some("repository is locked"),
+        ),
+    ] {
+        bus.publish(BusEvent::new("system.backup", backup));
+    }
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "backup");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Backups: 4 targets; stale: offsite; overdue: photos; unknown: mail"
+    );
+}
+
+#[test]
+fn sensors_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(SensorsWidget::new(bus.clone(), TICK), 50, 6);
+    harness.mount();
+
+    let reading = |label: &str, celsius, max, critical| Temperature {
+        label: label.to_string(),
+        celsius,
+        max,
+        critical,
+    };
+    for sensor in [
+        reading("coretemp Package id 0", 71.0, 88.0, 100.0),
+        reading("nvme Composite", 76.0, 79.0, 84.8),
+        reading("amdgpu edge", 94.0, 95.0, 90.0),
+    ] {
+        bus.publish(BusEvent::new("system.temperature", sensor));
+    }
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "sensors");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Sensors: 3 read; hottest amdgpu edge at 94°C; critical: amdgpu edge"
+    );
+}
+
+#[test]
+fn ports_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(PortsWidget::new(bus.clone(), TICK), 50, 7);
+    harness.mount();
+
+    let port = |protocol, address: &str, port, owner: Option<(u32, &str)>| ListeningPort {
+        protocol,
+        address: address.parse().unwrap(),
+        port,
+        pid: owner.map(|(pid, _)| pid),
+        process: owner.map(|(_, name)| name.to_string()),
+    };
+    let ports = vec![
+        port(Protocol::Tcp, "0.0.0.0", 22, None),
+        port(Protocol::Tcp, "127.0.0.1", 5432, Some((812, "postgres"))),
+        port(Protocol::Udp, "0.0.0.0", 5353, Some((640, "avahi-daemon"))),
+        port(Protocol::Tcp, "::", 8080, Some((4242, "node"))),
+    ];
+    bus.publish(BusEvent::new("system.ports", PortList { ports }));
+    harness.advance(TICK);
+    assert_snapshot!(harness, "ports");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Ports: 4 listening; reachable from the network: 22/tcp, 5353/udp, 8080/tcp"
+    );
+
+    // Filtered down to TCP ports reachable from the network
+    harness.key(KeyCode::Char('p'));
+    harness.key(KeyCode::Char('l'));
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Ports: 2 listening; reachable from the network: 22/tcp, 8080/tcp"
+    );
+
+    // Enter hands the owner to the process widget
+    let (_select, selections) = bus.subscribe(PROCESS_SELECT_TOPIC);
+    let (_action, actions) = bus.subscribe("action");
+    harness.key(KeyCode::Down);
+    harness.key(KeyCode::Enter);
+    assert_eq!(
+        *selections
+            .try_recv()
+            .unwrap()
+            .payload
+            .downcast::<Selection>()
+            .unwrap(),
+        Selection::new("process", "4242")
+    );
+    assert_eq!(
+        *actions
+            .try_recv()
+            .unwrap()
+            .payload
+            .downcast::<ActionRequest>()
+            .unwrap(),
+        ActionRequest::dashboard("focus_widget").with_argument("process")
+    );
+}
+
+#[test]
+fn health_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(HealthWidget::new(bus.clone(), TICK), 90, 6);
+    harness.mount();
+
+    let hours_ago = |hours: u64| SystemTime::now() - Duration::from_secs(hours * 3600 + 60);
+    let issues: Vec<HealthIssue> = [
+        ("EXT4-fs (sda1): Remounting filesystem read-only", 1),
+        ("I/O error, dev sda, sector 123456 op 0x0:(READ)", 2),
+        (
+            "Out of memory: Killed process 4321 (firefox) total-vm:8123456kB",
+            5,
+        ),
+    ]
+    .into_iter()
+    .map(|(line, hours)| health::parse_line(line, hours_ago(hours)).unwrap())
+    .collect();
+    bus.publish(BusEvent::new("system.health", issues));
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "health");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "System health: 3 problems; latest Filesystem on sda1 went read-only, 1 hour ago"
+    );
+}
+
+#[test]
+fn diagnostics_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(DiagnosticsWidget::new(bus.clone(), TICK), 80, 6);
+    harness.mount();
+
+    let diagnostic = |level, file: &str, line, message: &str| Diagnostic {
+        level,
+        message: message.to_string(),
+        code: None,
+        file: Some(file.into()),
+        line: Some(line),
+        column: Some(5),
+    };
+    let report = DiagnosticReport {
+        diagnostics: vec![
+            diagnostic(
+                DiagnosticLevel::Error,
+                "src/main.rs",
+                12,
+                "cannot find value `y` in this scope",
+            ),
+            diagnostic(
+                DiagnosticLevel::Warning,
+                "src/lib.rs",
+                3,
+                "unused variable: `x`",
+            ),
+        ],
+        error: None,
+        checked_at: None,
+    };
+    bus.publish(BusEvent::new("system.diagnostics", report));
+    harness.advance(TICK);
+    harness.key(KeyCode::Char('j'));
+
+    assert_snapshot!(harness, "diagnostics");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Diagnostics: 1 errors, 1 warnings; first: src/main.rs:12:5 cannot find value `y` in this scope"
+    );
+}
+
+#[test]
+fn tasks_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(TaskWidget::new(bus.clone(), TICK), 80, 10);
+    harness.mount();
+
+    let task = |source, name: &str, description: Option<&str>, command: &str| Task {
+        source,
+        name: name.to_string(),
+        description: description.map(str::to_string),
+        command: command.to_string(),
+        args: command.split(' ').map(str::to_string).collect(),
+    };
+    let list = TaskList {
+        tasks: vec![
+            task(
+                TaskSource::Make,
+                "build",
+                Some("Build the release binary"),
+                "make build",
+            ),
+            task(TaskSource::Just, "test", None, "just test"),
+            task(TaskSource::Npm, "lint", Some("eslint ."), "npm run lint"),
+        ],
+        error: None,
+    };
+    let run = TaskRun {
+        command: "make build".to_string(),
+        success: false,
+        code: Some(2),
+        duration: Duration::from_millis(1100),
+        output: vec![
+            "cc -c main.c".to_string(),
+            "main.c:3:1: error: expected ';'".to_string(),
+            "make: *** [build] Error 1".to_string(),
+        ],
+    };
+    bus.publish(BusEvent::new("system.tasks", list));
+    bus.publish(BusEvent::new("system.task.finished", run));
+    harness.advance(TICK);
+    harness.key(KeyCode::Char('j'));
+
+    assert_snapshot!(harness, "tasks");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Tasks: 3 tasks; `make build` failed with exit code 2 after 1.1s"
+    );
+}
+
+#[test]
+fn tunnels_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(TunnelWidget::new(bus.clone(), TICK), 80, 6);
+    harness.mount();
+
+    let status =
+        |name: &str, local, target: &str, state, restarts, error: Option<&str>| TunnelStatus {
+            name: name.to_string(),
+            local,
+            target: target.to_string(),
+            state,
+            restarts,
+            error: error.map(str::to_string),
+        };
+    let statuses = vec![
+        status(
+            "db",
+            5432,
+            "db.internal:5432 via bastion",
+            TunnelState::Up,
+            1,
+            None,
+        ),
+        status(
+            "grafana",
+            3000,
+            "monitoring/svc/grafana:80",
+            TunnelState::Down,
+            3,
+            Some("error: services \"grafana\" not found"),
+        ),
+        status(
+            "staging",
+            8080,
+            "ssh -N -L 8080:localhost:80 staging",
+            TunnelState::Off,
+            0,
+            None,
+        ),
+    ];
+    bus.publish(BusEvent::new("system.tunnels", statuses));
+    harness.advance(TICK);
+    harness.key(KeyCode::Char('j'));
+
+    assert_snapshot!(harness, "tunnels");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Tunnels: 1 of 3 up; grafana down: error: services \"grafana\" not found"
+    );
+}
+
+#[test]
+fn supervisor_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(SupervisorWidget::new(bus.clone(), TICK), 80, 12);
+    harness.mount();
+
+    let lines = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect();
+    let processes = vec![
+        DevProcess {
+            name: "web".to_string(),
+            command: "npm run dev".to_string(),
+            state: DevState::Running,
+            pid: Some(4242),
+            started_at: None,
+            code: None,
+            restarts: 0,
+            log: lines(&[
+                "> vite",
+                "  VITE v5.2.0  ready in 412 ms",
+                "  Local: http://localhost:5173/",
+            ]),
+        },
+        DevProcess {
+            name: "api".to_string(),
+            command: "cargo watch -x run".to_string(),
+            state: DevState::Crashed,
+            pid: None,
+            started_at: None,
+            code: Some(101),
+            restarts: 2,
+            log: lines(&[
+                "   Compiling api v0.1.0",
+                "    Finished dev profile in 3.2s",
+                "thread 'main' panicked at src/main.rs:12:5:",
+                "Address already in use (os error 98)",
+            ]),
+        },
+    ];
+    bus.publish(BusEvent::new("system.dev", processes));
+    harness.advance(TICK);
+    harness.key(KeyCode::Char('j'));
+
+    assert_snapshot!(harness, "supervisor");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "Dev servers: 1 of 2 running; api crashed (2 restarts)"
+    );
+}
+
+#[test]
+fn latency_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(LatencyWidget::new(bus.clone(), TICK), 80, 14);
+    harness.mount();
+
+    let ms = |millis| Some(Duration::from_millis(millis));
+    let stats = vec![
+        EndpointLatency {
+            name: "api".to_string(),
+            url: "https://api.example.com/health".to_string(),
+            requests: 120,
+            errors: 0,
+            p50: ms(42),
+            p95: ms(180),
+            p99: ms(1450),
+            max: ms(2900),
+            buckets: vec![0, 12, 61, 30, 12, 2, 1, 1, 1],
+            last_error: None,
+        },
+        EndpointLatency {
+            name: "web".to_string(),
+            url: "http://localhost:3000/".to_string(),
+            requests: 120,
+            errors: 3,
+            p50: ms(8),
+            p95: ms(22),
+            p99: ms(31),
+            max: ms(35),
+            buckets: vec![70, 40, 7, 0, 0, 0, 0, 0, 0],
+            last_error: Some("(7) Failed to connect to localhost port 3000".to_string()),
+        },
+    ];
+    bus.publish(BusEvent::new("system.latency", stats));
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "latency");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "HTTP latency: api p50 42ms, p95 180ms, p99 1.45s; web p50 8ms, p95 22ms, p99 31ms (3 of 120 failed)"
+    );
+}
+
+#[test]
+fn http_monitor_widget() {
+    let bus = offline_bus();
+    let mut harness = WidgetHarness::new(HttpMonitorWidget::new(bus.clone(), TICK), 64, 7);
+    harness.mount();
+
+    let endpoint = |name: &str, up, status, error: Option<&str>, failures| EndpointHealth {
+        name: name.to_string(),
+        url: format!("https://{}.example.com/", name),
+        up,
+        status,
+        // No response, no response time
+        latency: status.map(|_| Duration::from_millis(48)),
+        checks: if up.is_some() { 200 } else { 0 },
+        failures,
+        error: error.map(str::to_string),
+    };
+    let health = vec![
+        endpoint("api", Some(true), Some(200), None, 0),
+        endpoint("docs", Some(true), Some(502), Some("HTTP 502"), 1),
+        endpoint(
+            "shop",
+            Some(false),
+            None,
+            Some("(28) Operation timed out"),
+            9,
+        ),
+        endpoint("blog", None, None, None, 0),
+    ];
+    bus.publish(BusEvent::new("system.http", health));
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "http_monitor");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "HTTP monitor: 2 of 4 up; shop is down ((28) Operation timed out)"
+    );
+}
+
+#[test]
+fn clock_widget() {
+    let bus = offline_bus();
+    let settings = toml::from_str(
+        r#"
+        zone = "UTC"
+        hour12 = false
+        week = true
+        calendar = true
+        zones = [
+          { zone = "America/New_York" },
+          { zone = "Asia/Kolkata", label = "Bangalore" },
+        ]
+        "#,
+    )
+    .unwrap();
+    let widget = ClockWidget::from_settings(bus.clone(), TICK, &settings).unwrap();
+    let mut harness = WidgetHarness::new(widget, 40, 21);
+    harness.mount();
+
+    // Saturday 17 October 2026, 22:05:09 UTC
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_792_274_709);
+    bus.publish(BusEvent::new("system.time", time));
+    harness.advance(TICK);
+
+    assert_snapshot!(harness, "clock");
+    assert_eq!(
+        harness.widget().summary().unwrap(),
+        "22:05, Saturday 17 October 2026; New York 18:05, Bangalore 03:35"
+    );
+}